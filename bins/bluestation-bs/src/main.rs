@@ -1,8 +1,10 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
+use std::time::Duration;
 use tetra_core::tetra_entities::TetraEntity;
 use tetra_entities::net_control::channel::build_all_control_links;
 use tetra_entities::net_control::{
@@ -10,8 +12,9 @@ use tetra_entities::net_control::{
 };
 
 use tetra_config::bluestation::{PhyBackend, SharedConfig, StackConfig, parsing};
-use tetra_core::{TdmaTime, debug};
+use tetra_core::{NetworkAddress, TdmaTime, debug};
 use tetra_entities::MessageRouter;
+use tetra_entities::net_admin::{AdminServer, make_admin_link};
 use tetra_entities::net_brew::entity::BrewEntity;
 use tetra_entities::net_brew::new_websocket_transport;
 use tetra_entities::net_telemetry::worker::TelemetryWorker;
@@ -25,7 +28,10 @@ use tetra_entities::{
     lmac::lmac_bs::LmacBs,
     mle::mle_bs::MleBs,
     mm::mm_bs::MmBs,
-    phy::{components::soapy_dev::RxTxDevSoapySdr, phy_bs::PhyBs},
+    phy::{
+        components::{soapy_dev::RxTxDevSoapySdr, soapy_enum},
+        phy_bs::PhyBs,
+    },
     sndcp::sndcp_bs::Sndcp,
     umac::umac_bs::UmacBs,
 };
@@ -74,6 +80,20 @@ fn start_telemetry_worker(cfg: SharedConfig, telemetry_source: TelemetrySource)
     })
 }
 
+/// Bind the admin server and hand its link to the router. Exits if the port cannot be bound.
+fn start_admin_server(router: &mut MessageRouter, port: u16, config_path: &str) -> thread::JoinHandle<()> {
+    let (client, link) = make_admin_link();
+    let mut server = AdminServer::bind(port, client, Some(PathBuf::from(config_path))).unwrap_or_else(|e| {
+        eprintln!("Failed to bind admin port {}: {}", port, e);
+        std::process::exit(1);
+    });
+    server.set_router_stats(router.stats());
+    router.set_admin_link(link);
+    eprintln!(" -> Admin interface on 127.0.0.1:{}", port);
+
+    thread::spawn(move || server.run())
+}
+
 fn start_control_worker(cfg: SharedConfig, command_dispatchers: HashMap<TetraEntity, CommandDispatcher>) -> thread::JoinHandle<()> {
     let config = cfg.config();
     let ccfg = config.control.as_ref().unwrap();
@@ -108,8 +128,12 @@ fn start_control_worker(cfg: SharedConfig, command_dispatchers: HashMap<TetraEnt
 }
 
 /// Start base station stack
-fn build_bs_stack(cfg: &mut SharedConfig) -> (MessageRouter, Option<TelemetrySource>, HashMap<TetraEntity, CommandDispatcher>) {
+fn build_bs_stack(
+    cfg: &mut SharedConfig,
+    stats_interval: Option<Duration>,
+) -> (MessageRouter, Option<TelemetrySource>, HashMap<TetraEntity, CommandDispatcher>) {
     let mut router = MessageRouter::new(cfg.clone());
+    router.set_stats_interval(stats_interval);
 
     // Add suitable Phy component based on PhyIo type
     match cfg.config().phy_io.backend {
@@ -140,8 +164,9 @@ fn build_bs_stack(cfg: &mut SharedConfig) -> (MessageRouter, Option<TelemetrySou
 
     // Add remaining components
     let lmac = LmacBs::new(cfg.clone());
-    let umac = UmacBs::new(cfg.clone());
-    let llc = Llc::new(cfg.clone());
+    let umac = UmacBs::new(cfg.clone(), tsink.clone());
+    let mut llc = Llc::new(cfg.clone());
+    llc.set_stats_interval(stats_interval);
     let mle = MleBs::new(cfg.clone());
     let mm = MmBs::new(cfg.clone(), tsink.clone(), c_e.remove(&TetraEntity::Mm));
     let sndcp = Sndcp::new(cfg.clone());
@@ -179,13 +204,46 @@ fn build_bs_stack(cfg: &mut SharedConfig) -> (MessageRouter, Option<TelemetrySou
     author,
     version,
     about = "TETRA BlueStation base station stack",
-    long_about = "Runs the TETRA BlueStation base station stack using the provided TOML configuration files"
+    long_about = "Runs the TETRA BlueStation base station stack using the provided TOML configuration files",
+    subcommand_negates_reqs = true
 )]
 
 struct Args {
     /// Config file (required)
-    #[arg(help = "TOML config with network/cell parameters")]
-    config: String,
+    #[arg(required = true, help = "TOML config with network/cell parameters")]
+    config: Option<String>,
+
+    /// Log layer statistics every N seconds
+    #[arg(long, value_name = "SECONDS")]
+    stats_interval: Option<u64>,
+
+    /// Serve the admin interface on 127.0.0.1 at the given TCP port
+    #[arg(long, value_name = "PORT")]
+    admin_port: Option<u16>,
+
+    /// Network management peer for the TNMM interface, overrides tnmm_address from the config
+    #[arg(long, value_name = "URI", help = "TNMM peer address, e.g. tcp://192.168.1.1:5555")]
+    tnmm_address: Option<NetworkAddress>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List the available SoapySDR devices as JSON
+    ListSoapy,
+}
+
+/// Print the detected SoapySDR devices as a JSON array to stdout
+fn list_soapy_devices() {
+    match soapy_enum::devices_json(soapy_enum::enumerate_devices) {
+        Ok(json) => println!("{}", json),
+        Err(e) => {
+            eprintln!("Failed to enumerate SoapySDR devices: {}", e);
+            std::process::exit(1);
+        }
+    }
 }
 
 fn main() {
@@ -198,13 +256,24 @@ fn main() {
 
     // Parse command-line arguments
     let args = Args::parse();
+    if let Some(Command::ListSoapy) = args.command {
+        list_soapy_devices();
+        return;
+    }
 
     // Build immutable, cheaply clonable SharedConfig and build the base station stack
-    let stack_cfg = load_config_from_toml(&args.config);
+    let config_path = args.config.expect("config is required without a subcommand");
+    let mut stack_cfg = load_config_from_toml(&config_path);
+    if let Some(addr) = args.tnmm_address {
+        stack_cfg.tnmm_address = Some(addr);
+    }
+    if let Some(ref addr) = stack_cfg.tnmm_address {
+        eprintln!(" -> TNMM peer at {}", addr);
+    }
     let mut cfg = SharedConfig::from_parts(stack_cfg, None);
 
     let _log_guards = debug::setup_logging_default(cfg.config().debug_log.clone());
-    let (mut router, tsource, cdispatchers) = build_bs_stack(&mut cfg);
+    let (mut router, tsource, cdispatchers) = build_bs_stack(&mut cfg, args.stats_interval.map(Duration::from_secs));
 
     // Start Telemetry and Control threads, if enabled
     if let Some(telemetry_source) = tsource {
@@ -213,6 +282,15 @@ fn main() {
     if cfg.config().control.is_some() {
         start_control_worker(cfg.clone(), cdispatchers);
     };
+    if let Some(port) = args.admin_port {
+        start_admin_server(&mut router, port, &config_path);
+    }
+    if let Some(path) = cfg.config().recording.clone() {
+        router.enable_recording(path.clone()).unwrap_or_else(|e| {
+            eprintln!("Failed to open recording file {}: {}", path.display(), e);
+            std::process::exit(1);
+        });
+    }
 
     // Set up Ctrl+C handler for graceful shutdown
     let is_running = Arc::new(AtomicBool::new(true));
@@ -222,8 +300,7 @@ fn main() {
     })
     .expect("failed to set Ctrl+C handler");
 
-    // Start the stack
+    // Start the stack. On Ctrl+C it releases active calls, drains the queues and drops the entities,
+    // upper layers first, so networked entities have disconnected when it returns.
     router.run_stack(None, Some(is_running));
-
-    // router drops here → entities are dropped, networked entities disconnect.
 }
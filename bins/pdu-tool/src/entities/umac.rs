@@ -227,7 +227,7 @@ impl UmacParser {
                 // Apply PDU association
                 Self::apply_pdu_association(pdu, mac_data.length_ind, mac_data.fill_bits);
             }
-            Err(e) => println!("[!] Failed to parse MAC-DATA: {:?}", e),
+            Err(e) => eprintln!("[!] Failed to parse MAC-DATA: {}", e),
         }
     }
 
@@ -250,7 +250,7 @@ impl UmacParser {
                 // Apply PDU association
                 Self::apply_pdu_association(pdu, mac_access.length_ind, mac_access.fill_bits);
             }
-            Err(e) => println!("[!] Failed to parse MAC-ACCESS: {:?}", e),
+            Err(e) => eprintln!("[!] Failed to parse MAC-ACCESS: {}", e),
         }
     }
 
@@ -268,7 +268,7 @@ impl UmacParser {
                 }
                 println!("BitBuffer: {}", pdu.dump_bin());
             }
-            Err(e) => println!("[!] Failed to parse MAC-FRAG: {:?}", e),
+            Err(e) => eprintln!("[!] Failed to parse MAC-FRAG: {}", e),
         }
     }
 
@@ -291,7 +291,7 @@ impl UmacParser {
                 // Apply PDU association
                 Self::apply_pdu_association(pdu, mac_end.length_ind, mac_end.fill_bits);
             }
-            Err(e) => println!("[!] Failed to parse MAC-END: {:?}", e),
+            Err(e) => eprintln!("[!] Failed to parse MAC-END: {}", e),
         }
     }
 
@@ -314,7 +314,7 @@ impl UmacParser {
                 // Apply PDU association
                 Self::apply_pdu_association(pdu, mac_end.length_ind, mac_end.fill_bits);
             }
-            Err(e) => println!("[!] Failed to parse MAC-END-HU: {:?}", e),
+            Err(e) => eprintln!("[!] Failed to parse MAC-END-HU: {}", e),
         }
     }
 
@@ -327,7 +327,7 @@ impl UmacParser {
                 println!("TM-SDU: {} bits remaining", remaining);
                 println!("BitBuffer: {}", pdu.dump_bin());
             }
-            Err(e) => println!("[!] Failed to parse MAC-U-BLCK: {:?}", e),
+            Err(e) => eprintln!("[!] Failed to parse MAC-U-BLCK: {}", e),
         }
     }
 
@@ -338,7 +338,7 @@ impl UmacParser {
                 println!("{:#?}", mac_u_signal);
                 println!("BitBuffer: {}", pdu.dump_bin());
             }
-            Err(e) => println!("[!] Failed to parse MAC-U-SIGNAL: {:?}", e),
+            Err(e) => eprintln!("[!] Failed to parse MAC-U-SIGNAL: {}", e),
         }
     }
 
@@ -353,7 +353,7 @@ impl UmacParser {
                 println!("{:#?}", mac_res);
 
                 // Print SDU preview
-                let info = Self::interpret_length_ind(mac_res.length_ind, pdu.get_len_remaining());
+                let info = Self::interpret_length_ind(mac_res.length_ind.get(), pdu.get_len_remaining());
                 if !info.is_null_pdu && !info.is_frag_start && !info.second_half_stolen && info.pdu_len_bits > 0 {
                     Self::print_sdu(pdu, info.pdu_len_bits.min(pdu.get_len_remaining()), "TM-SDU");
                 } else if info.is_frag_start {
@@ -362,9 +362,9 @@ impl UmacParser {
                 }
 
                 // Apply PDU association
-                Self::apply_pdu_association(pdu, Some(mac_res.length_ind), mac_res.fill_bits);
+                Self::apply_pdu_association(pdu, Some(mac_res.length_ind.get()), mac_res.fill_bits);
             }
-            Err(e) => println!("[!] Failed to parse MAC-RESOURCE: {:?}", e),
+            Err(e) => eprintln!("[!] Failed to parse MAC-RESOURCE: {}", e),
         }
     }
 
@@ -377,7 +377,7 @@ impl UmacParser {
                 println!("TM-SDU fragment: {} bits remaining", remaining);
                 println!("BitBuffer: {}", pdu.dump_bin());
             }
-            Err(e) => println!("[!] Failed to parse MAC-FRAG (DL): {:?}", e),
+            Err(e) => eprintln!("[!] Failed to parse MAC-FRAG (DL): {}", e),
         }
     }
 
@@ -398,7 +398,7 @@ impl UmacParser {
                 // Apply PDU association
                 Self::apply_pdu_association(pdu, Some(mac_end.length_ind), mac_end.fill_bits);
             }
-            Err(e) => println!("[!] Failed to parse MAC-END (DL): {:?}", e),
+            Err(e) => eprintln!("[!] Failed to parse MAC-END (DL): {}", e),
         }
     }
 
@@ -411,7 +411,7 @@ impl UmacParser {
                 println!("TM-SDU: {} bits remaining", remaining);
                 println!("BitBuffer: {}", pdu.dump_bin());
             }
-            Err(e) => println!("[!] Failed to parse MAC-D-BLCK: {:?}", e),
+            Err(e) => eprintln!("[!] Failed to parse MAC-D-BLCK: {}", e),
         }
     }
 
@@ -448,7 +448,7 @@ impl UmacParser {
                 println!("{:#?}", mac_sync);
                 println!("BitBuffer: {}", pdu.dump_bin());
             }
-            Err(e) => println!("[!] Failed to parse MAC-SYNC: {:?}", e),
+            Err(e) => eprintln!("[!] Failed to parse MAC-SYNC: {}", e),
         }
     }
 
@@ -463,7 +463,7 @@ impl UmacParser {
                 }
                 println!("BitBuffer: {}", pdu.dump_bin());
             }
-            Err(e) => println!("[!] Failed to parse MAC-SYSINFO: {:?}", e),
+            Err(e) => eprintln!("[!] Failed to parse MAC-SYSINFO: {}", e),
         }
     }
 
@@ -474,7 +474,7 @@ impl UmacParser {
                 println!("{:#?}", access_def);
                 println!("BitBuffer: {}", pdu.dump_bin());
             }
-            Err(e) => println!("[!] Failed to parse ACCESS-DEFINE: {:?}", e),
+            Err(e) => eprintln!("[!] Failed to parse ACCESS-DEFINE: {}", e),
         }
     }
 
@@ -27,9 +27,17 @@ struct Args {
     destination: String,
 
     /// Raw bitstring to decode
-    #[arg(help = "Raw bitstring (binary representation) to parse as PDU")]
+    #[arg(help = "Raw bitstring (binary or hex representation, see --format) to parse as PDU")]
     bitstring: String,
 
+    #[arg(
+        short = 'f',
+        long = "format",
+        default_value = "bin",
+        help = "Input format of the bitstring: [ bin | hex ]. Hex may carry a 0x prefix"
+    )]
+    format: String,
+
     #[arg(
         short = 'c',
         long = "channel",
@@ -73,9 +81,23 @@ fn main() {
         }
     };
 
+    let pdu = match args.format.to_lowercase().as_str() {
+        "bin" => BitBuffer::from_bitstr(args.bitstring.as_str()),
+        "hex" => match BitBuffer::from_hex_str(args.bitstring.as_str()) {
+            Ok(pdu) => pdu,
+            Err(e) => {
+                eprintln!("Error: Invalid hex string '{}': {}", args.bitstring, e);
+                std::process::exit(1);
+            }
+        },
+        _ => {
+            eprintln!("Error: Unsupported format '{}'. Use: bin, hex", args.format);
+            std::process::exit(1);
+        }
+    };
+
     match (args.sap.to_lowercase().as_str(), args.destination.to_lowercase().as_str()) {
         ("tmv", "umac") => {
-            let pdu = BitBuffer::from_bitstr(args.bitstring.as_str());
             if is_downlink {
                 UmacParser::parse_dl(pdu, logical_channel);
             } else {
@@ -1,8 +1,10 @@
 use serde::Deserialize;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 use tetra_core::freqs::FreqInfo;
+use tetra_core::{NetworkAddress, TimeslotAllocator};
 
-use crate::bluestation::{CfgCellInfo, CfgControl, CfgNetInfo, CfgPhyIo, PhyBackend, StackState};
+use crate::bluestation::{CfgCellInfo, CfgCmce, CfgControl, CfgLlc, CfgNetInfo, CfgPhyIo, PhyBackend, StackState, SubscriberPolicy};
 
 use super::sec_brew::CfgBrew;
 use super::sec_telemetry::CfgTelemetry;
@@ -54,13 +56,27 @@ pub enum StackMode {
 
 #[derive(Debug, Clone)]
 pub struct StackConfig {
+    /// Schema version the config file was migrated to, always CURRENT_SCHEMA_VERSION once loaded
+    pub schema_version: u32,
     pub stack_mode: StackMode,
     pub debug_log: Option<String>,
+    /// Record every routed SapMsg to this file as newline-delimited JSON, for offline replay
+    pub recording: Option<PathBuf>,
+    /// Persist the traffic timeslot allocation to this file, so it survives a stack restart
+    pub state_file: Option<PathBuf>,
+    /// Network management peer for the TNMM interface, e.g. `tcp://192.168.1.1:5555`
+    pub tnmm_address: Option<NetworkAddress>,
 
     pub phy_io: CfgPhyIo,
     pub net: CfgNetInfo,
     pub cell: CfgCellInfo,
 
+    /// LLC timers and retry counts
+    pub llc: CfgLlc,
+
+    /// Call control behaviour
+    pub cmce: CfgCmce,
+
     /// Brew protocol (TetraPack/BrandMeister) configuration
     pub brew: Option<CfgBrew>,
 
@@ -123,6 +139,12 @@ impl StackConfig {
             return Err("ms_txpwr_max_cell must be 0-7 (3 bits)");
         }
 
+        if let SubscriberPolicy::Whitelist { issis } = &self.cell.subscriber_policy
+            && issis.is_empty()
+        {
+            return Err("cell.allowed_issis must list at least one ISSI when subscriber_policy is whitelist");
+        }
+
         // Validate timezone if configured
         if let Some(ref tz) = self.cell.timezone {
             if tz.parse::<chrono_tz::Tz>().is_err() {
@@ -151,9 +173,14 @@ impl SharedConfig {
             Err(e) => panic!("Invalid stack configuration: {}", e),
         }
 
+        let state = state.unwrap_or_else(|| StackState {
+            timeslot_alloc: cfg.state_file.as_deref().map(restore_timeslot_alloc).unwrap_or_default(),
+            ..Default::default()
+        });
+
         Self {
             cfg: Arc::new(cfg),
-            state: Arc::new(RwLock::new(state.unwrap_or_default())),
+            state: Arc::new(RwLock::new(state)),
         }
     }
 
@@ -162,6 +189,16 @@ impl SharedConfig {
         Arc::clone(&self.cfg)
     }
 
+    /// Returns a SharedConfig with a new immutable config that shares the mutable state with this one.
+    /// Used to hand a reloaded config to a running stack.
+    pub fn with_config(&self, cfg: StackConfig) -> Result<Self, String> {
+        cfg.validate().map_err(|e| e.to_string())?;
+        Ok(Self {
+            cfg: Arc::new(cfg),
+            state: Arc::clone(&self.state),
+        })
+    }
+
     /// Read guard for mutable state.
     pub fn state_read(&self) -> std::sync::RwLockReadGuard<'_, StackState> {
         self.state.read().expect("StackState RwLock blocked")
@@ -171,4 +208,40 @@ impl SharedConfig {
     pub fn state_write(&self) -> std::sync::RwLockWriteGuard<'_, StackState> {
         self.state.write().expect("StackState RwLock blocked")
     }
+
+    /// Modify the timeslot allocation. If it changed and a state file is configured, the new allocation is written to it.
+    pub fn update_timeslot_alloc<R>(&self, f: impl FnOnce(&mut TimeslotAllocator) -> R) -> R {
+        let mut state = self.state_write();
+        let before = state.timeslot_alloc.clone();
+        let ret = f(&mut state.timeslot_alloc);
+        if state.timeslot_alloc != before
+            && let Some(path) = &self.cfg.state_file
+            && let Err(e) = std::fs::write(path, state.timeslot_alloc.to_json())
+        {
+            tracing::warn!("Failed to persist timeslot allocation to {}: {}", path.display(), e);
+        }
+        ret
+    }
+}
+
+/// Load the timeslot allocation persisted before a restart. A missing or corrupted state file yields an empty allocation.
+fn restore_timeslot_alloc(path: &Path) -> TimeslotAllocator {
+    let json = match std::fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return TimeslotAllocator::default(),
+        Err(e) => {
+            tracing::warn!("Failed to read state file {}: {}", path.display(), e);
+            return TimeslotAllocator::default();
+        }
+    };
+    match TimeslotAllocator::from_json(&json) {
+        Ok(alloc) => {
+            tracing::info!("Restored timeslot allocation from {}: {:?}", path.display(), alloc);
+            alloc
+        }
+        Err(e) => {
+            tracing::warn!("Ignoring corrupted state file {}: {}", path.display(), e);
+            TimeslotAllocator::default()
+        }
+    }
 }
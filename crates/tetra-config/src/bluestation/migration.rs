@@ -0,0 +1,157 @@
+use toml::Value;
+use toml::value::Table;
+
+/// Schema version of the config files understood by this build.
+/// Files without a `schema_version` field are treated as version 0.
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MigrationError {
+    /// The config root is not a TOML table
+    NotATable,
+    /// `schema_version` is present but not a non-negative integer
+    InvalidVersion,
+    /// The config was written for a newer schema than this build supports
+    UnsupportedVersion(u32),
+    /// Both the old and the new name of a renamed section are present
+    Conflict { old: &'static str, new: &'static str },
+}
+
+impl std::fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrationError::NotATable => write!(f, "config root is not a table"),
+            MigrationError::InvalidVersion => write!(f, "schema_version must be a non-negative integer"),
+            MigrationError::UnsupportedVersion(version) => write!(
+                f,
+                "schema_version {} is newer than the supported version {}",
+                version, CURRENT_SCHEMA_VERSION
+            ),
+            MigrationError::Conflict { old, new } => write!(f, "both [{}] and its replacement [{}] are present", old, new),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+/// Read the schema version of a parsed config, 0 if the field is absent
+pub fn schema_version(config: &Value) -> Result<u32, MigrationError> {
+    let table = config.as_table().ok_or(MigrationError::NotATable)?;
+    match table.get("schema_version") {
+        None => Ok(0),
+        Some(Value::Integer(version)) => u32::try_from(*version).map_err(|_| MigrationError::InvalidVersion),
+        Some(_) => Err(MigrationError::InvalidVersion),
+    }
+}
+
+/// Run a single migration step, returning the config in schema version `from_version + 1`
+pub fn migrate(config: Value, from_version: u32) -> Result<Value, MigrationError> {
+    let Value::Table(table) = config else {
+        return Err(MigrationError::NotATable);
+    };
+    let mut table = match from_version {
+        0 => migrate_v0_to_v1(table)?,
+        _ => return Err(MigrationError::UnsupportedVersion(from_version)),
+    };
+    table.insert("schema_version".to_string(), Value::Integer(from_version as i64 + 1));
+    Ok(Value::Table(table))
+}
+
+/// Bring a config up to CURRENT_SCHEMA_VERSION by applying all migration steps in order
+pub fn migrate_to_current(mut config: Value) -> Result<Value, MigrationError> {
+    let original = schema_version(&config)?;
+    if original > CURRENT_SCHEMA_VERSION {
+        return Err(MigrationError::UnsupportedVersion(original));
+    }
+    if original < CURRENT_SCHEMA_VERSION {
+        tracing::warn!(
+            "Migrating config from schema_version {} to {}, consider updating the config file",
+            original,
+            CURRENT_SCHEMA_VERSION
+        );
+    }
+    for version in original..CURRENT_SCHEMA_VERSION {
+        config = migrate(config, version)?;
+    }
+    Ok(config)
+}
+
+/// v0 -> v1: the PHY section was renamed from [phy] to [phy_io]
+fn migrate_v0_to_v1(mut table: Table) -> Result<Table, MigrationError> {
+    rename_section(&mut table, "phy", "phy_io")?;
+    Ok(table)
+}
+
+fn rename_section(table: &mut Table, old: &'static str, new: &'static str) -> Result<(), MigrationError> {
+    if let Some(section) = table.remove(old) {
+        if table.contains_key(new) {
+            return Err(MigrationError::Conflict { old, new });
+        }
+        table.insert(new.to_string(), section);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(toml_str: &str) -> Value {
+        toml::from_str(toml_str).unwrap()
+    }
+
+    #[test]
+    fn test_migrate_v0_to_v1() {
+        let config = parse("stack_mode = \"Bs\"\n[phy]\nbackend = \"SoapySdr\"\n");
+        assert_eq!(schema_version(&config), Ok(0));
+
+        let migrated = migrate(config, 0).unwrap();
+        assert_eq!(schema_version(&migrated), Ok(1));
+        let table = migrated.as_table().unwrap();
+        assert!(!table.contains_key("phy"));
+        assert_eq!(table["phy_io"]["backend"].as_str(), Some("SoapySdr"));
+        assert_eq!(table["stack_mode"].as_str(), Some("Bs"));
+
+        // A v0 file that already uses the new name only gets its version bumped
+        let migrated = migrate(parse("[phy_io]\nbackend = \"None\"\n"), 0).unwrap();
+        assert_eq!(schema_version(&migrated), Ok(1));
+        assert_eq!(migrated["phy_io"]["backend"].as_str(), Some("None"));
+    }
+
+    #[test]
+    fn test_migrate_v0_to_v1_conflict() {
+        let config = parse("[phy]\nbackend = \"SoapySdr\"\n[phy_io]\nbackend = \"None\"\n");
+        assert_eq!(migrate(config, 0), Err(MigrationError::Conflict { old: "phy", new: "phy_io" }));
+    }
+
+    #[test]
+    fn test_migrate_to_current() {
+        let migrated = migrate_to_current(parse("[phy]\nbackend = \"SoapySdr\"\n")).unwrap();
+        assert_eq!(schema_version(&migrated), Ok(CURRENT_SCHEMA_VERSION));
+        assert!(migrated.get("phy_io").is_some());
+
+        // Current configs pass through untouched
+        let current = parse(&format!(
+            "schema_version = {}\n[phy]\nbackend = \"SoapySdr\"\n",
+            CURRENT_SCHEMA_VERSION
+        ));
+        assert_eq!(migrate_to_current(current.clone()), Ok(current));
+    }
+
+    #[test]
+    fn test_invalid_versions() {
+        let newer = CURRENT_SCHEMA_VERSION + 1;
+        assert_eq!(
+            migrate_to_current(parse(&format!("schema_version = {}\n", newer))),
+            Err(MigrationError::UnsupportedVersion(newer))
+        );
+        assert_eq!(
+            migrate_to_current(parse("schema_version = -1\n")),
+            Err(MigrationError::InvalidVersion)
+        );
+        assert_eq!(
+            migrate_to_current(parse("schema_version = \"1\"\n")),
+            Err(MigrationError::InvalidVersion)
+        );
+    }
+}
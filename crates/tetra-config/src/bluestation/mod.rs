@@ -1,6 +1,9 @@
 pub mod parsing;
 pub use parsing::*;
 
+pub mod migration;
+pub use migration::*;
+
 pub mod config;
 pub use config::*;
 
@@ -25,5 +28,11 @@ pub use sec_telemetry::*;
 pub mod sec_control;
 pub use sec_control::*;
 
+pub mod sec_llc;
+pub use sec_llc::*;
+
+pub mod sec_cmce;
+pub use sec_cmce::*;
+
 pub mod state;
 pub use state::*;
@@ -1,21 +1,26 @@
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufReader, Read};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use serde::Deserialize;
 use toml::Value;
 
-use crate::bluestation::{CellInfoDto, CfgControlDto, NetInfoDto, apply_control_patch, cell_dto_to_cfg, net_dto_to_cfg};
+use crate::bluestation::{
+    CellInfoDto, CfgCmce, CfgCmceDto, CfgControlDto, CfgLlc, CfgLlcDto, NetInfoDto, apply_cmce_patch, apply_control_patch, apply_llc_patch,
+    cell_dto_to_cfg, net_dto_to_cfg,
+};
 
 use super::config::{StackConfig, StackMode};
+use super::migration::migrate_to_current;
 use super::sec_brew::{CfgBrewDto, apply_brew_patch};
 use super::sec_telemetry::{CfgTelemetryDto, apply_telemetry_patch};
 use super::{PhyIoDto, phy_dto_to_cfg};
 
 /// Build `StackConfig` from a TOML configuration file
 pub fn from_toml_str(toml_str: &str) -> Result<StackConfig, Box<dyn std::error::Error>> {
-    let root: TomlConfigRoot = toml::from_str(toml_str)?;
+    let value: Value = toml::from_str(toml_str)?;
+    let root: TomlConfigRoot = migrate_to_current(value)?.try_into()?;
 
     // Various sanity checks
     let expected_config_version = "0.6";
@@ -50,6 +55,20 @@ pub fn from_toml_str(toml_str: &str) -> Result<StackConfig, Box<dyn std::error::
         return Err(format!("Unrecognized fields in cell_info: {:?}", sorted_keys(&root.cell_info.extra)).into());
     }
 
+    // Optional llc section
+    if let Some(ref llc) = root.llc
+        && !llc.extra.is_empty()
+    {
+        return Err(format!("Unrecognized fields in llc config: {:?}", sorted_keys(&llc.extra)).into());
+    }
+
+    // Optional cmce section
+    if let Some(ref cmce) = root.cmce
+        && !cmce.extra.is_empty()
+    {
+        return Err(format!("Unrecognized fields in cmce config: {:?}", sorted_keys(&cmce.extra)).into());
+    }
+
     // Optional brew section
     if let Some(ref brew) = root.brew {
         if !brew.extra.is_empty() {
@@ -66,18 +85,35 @@ pub fn from_toml_str(toml_str: &str) -> Result<StackConfig, Box<dyn std::error::
 
     // Build config from required and optional values
     let mut cfg = StackConfig {
+        schema_version: root.schema_version,
         stack_mode: root.stack_mode,
         debug_log: root.debug_log,
+        recording: root.recording,
+        state_file: root.state_file,
+        tnmm_address: root
+            .tnmm_address
+            .map(|addr| addr.parse().map_err(|e| format!("Invalid tnmm_address '{}': {}", addr, e)))
+            .transpose()?,
         phy_io: phy_dto_to_cfg(root.phy_io),
         net: net_dto_to_cfg(root.net_info),
-        cell: cell_dto_to_cfg(root.cell_info),
+        cell: cell_dto_to_cfg(root.cell_info)?,
+        llc: CfgLlc::default(),
+        cmce: CfgCmce::default(),
         brew: None,
         telemetry: None,
         control: None,
     };
 
+    if let Some(llc) = root.llc {
+        cfg.llc = apply_llc_patch(llc)?;
+    }
+
+    if let Some(cmce) = root.cmce {
+        cfg.cmce = apply_cmce_patch(cmce)?;
+    }
+
     if let Some(brew) = root.brew {
-        cfg.brew = Some(apply_brew_patch(brew));
+        cfg.brew = Some(apply_brew_patch(brew)?);
     }
 
     if let Some(telemetry) = root.telemetry {
@@ -118,13 +154,19 @@ fn sorted_keys(map: &HashMap<String, Value>) -> Vec<&str> {
 #[derive(Deserialize)]
 struct TomlConfigRoot {
     config_version: String,
+    schema_version: u32,
     stack_mode: StackMode,
     debug_log: Option<String>,
+    recording: Option<PathBuf>,
+    state_file: Option<PathBuf>,
+    tnmm_address: Option<String>,
 
     phy_io: PhyIoDto,
     net_info: NetInfoDto,
     cell_info: CellInfoDto,
 
+    llc: Option<CfgLlcDto>,
+    cmce: Option<CfgCmceDto>,
     brew: Option<CfgBrewDto>,
     telemetry: Option<CfgTelemetryDto>,
     command: Option<CfgControlDto>,
@@ -132,3 +174,51 @@ struct TomlConfigRoot {
     #[serde(flatten)]
     extra: HashMap<String, Value>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bluestation::CURRENT_SCHEMA_VERSION;
+    use tetra_core::NetworkAddress;
+
+    const EXAMPLE_CONFIG: &str = include_str!("../../../../example_config/config.toml");
+
+    #[test]
+    fn test_example_config() {
+        let cfg = from_toml_str(EXAMPLE_CONFIG).unwrap();
+        assert_eq!(cfg.schema_version, CURRENT_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_unversioned_config_migrated() {
+        let legacy = EXAMPLE_CONFIG.replace("schema_version = 1\n", "").replace("[phy_io", "[phy");
+        assert!(!legacy.contains("schema_version = "));
+        let cfg = from_toml_str(&legacy).unwrap();
+        assert_eq!(cfg.schema_version, CURRENT_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_tnmm_address() {
+        let cfg = from_toml_str(EXAMPLE_CONFIG).unwrap();
+        assert_eq!(cfg.tnmm_address, None);
+
+        let with_addr = EXAMPLE_CONFIG.replace(
+            "stack_mode = \"Bs\"\n",
+            "stack_mode = \"Bs\"\ntnmm_address = \"tcp://192.168.1.1:5555\"\n",
+        );
+        let cfg = from_toml_str(&with_addr).unwrap();
+        assert_eq!(
+            cfg.tnmm_address,
+            Some(NetworkAddress::Tcp {
+                host: "192.168.1.1".to_string(),
+                port: 5555,
+            })
+        );
+
+        let invalid = EXAMPLE_CONFIG.replace(
+            "stack_mode = \"Bs\"\n",
+            "stack_mode = \"Bs\"\ntnmm_address = \"192.168.1.1:5555\"\n",
+        );
+        assert!(from_toml_str(&invalid).is_err());
+    }
+}
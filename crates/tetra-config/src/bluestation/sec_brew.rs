@@ -1,6 +1,7 @@
-use std::{collections::HashMap, time::Duration};
+use std::{collections::HashMap, path::PathBuf, time::Duration};
 
 use serde::Deserialize;
+use tetra_core::{SsiType, TetraAddress};
 use toml::Value;
 
 use crate::bluestation::SecretField;
@@ -20,13 +21,42 @@ pub struct CfgBrew {
     pub password: Option<SecretField>,
     /// Reconnection delay
     pub reconnect_delay: Duration,
+    /// Playout buffer depth in frames for inbound Brew voice, the adaptive baseline
+    pub jitter_buffer_frames: u8,
     /// Extra initial jitter playout delay in frames (added on top of adaptive baseline)
     pub jitter_initial_latency_frames: u8,
+    /// If present, local UL calls are recorded to files in this directory
+    pub recording_dir: Option<PathBuf>,
 
     /// Set to true when SDS between local and Brew clients is enabled
     pub feature_sds_enabled: bool,
     /// If present, restrict Brew call to these remote SSIs
     pub whitelisted_ssis: Option<Vec<u32>>,
+    /// GSSIs that are monitored only: local calls are forwarded to Brew, calls from Brew are not transmitted
+    pub monitoring_only: Vec<u32>,
+    /// Human-readable names for ISSIs, used in call logs
+    pub alias_map: HashMap<u32, String>,
+    /// Brew priority value for each TETRA call priority 0-15
+    pub priority_map: [u8; 16],
+}
+
+/// Default TETRA to Brew priority map, spreading priority 0-15 linearly over 0-255
+pub const DEFAULT_BREW_PRIORITY_MAP: [u8; 16] = [0, 17, 34, 51, 68, 85, 102, 119, 136, 153, 170, 187, 204, 221, 238, 255];
+
+impl CfgBrew {
+    /// Maps a TETRA call priority (0-15) to the Brew priority value
+    pub fn brew_priority(&self, tetra_priority: u8) -> u8 {
+        self.priority_map[tetra_priority.min(15) as usize]
+    }
+
+    /// Maps a Brew priority value back to a TETRA call priority: the highest TETRA priority
+    /// whose Brew value does not exceed the given value, or 0 if there is none
+    pub fn tetra_priority(&self, brew_priority: u8) -> u8 {
+        (0..16u8)
+            .rev()
+            .find(|&p| self.priority_map[p as usize] <= brew_priority)
+            .unwrap_or(0)
+    }
 }
 
 #[derive(Default, Deserialize)]
@@ -45,12 +75,26 @@ pub struct CfgBrewDto {
     /// Reconnection delay in seconds
     #[serde(default = "default_brew_reconnect_delay")]
     pub reconnect_delay_secs: u64,
+    /// Playout buffer depth in frames for inbound Brew voice, the adaptive baseline
+    #[serde(default = "default_brew_jitter_buffer_frames")]
+    pub jitter_buffer_frames: u8,
     /// Extra initial jitter playout delay in frames (added on top of adaptive baseline)
     #[serde(default)]
     pub jitter_initial_latency_frames: u8,
+    /// If present, local UL calls are recorded to files in this directory
+    #[serde(default)]
+    pub recording_dir: Option<PathBuf>,
 
     /// If present, restrict Brew call to these remote SSIs
     pub whitelisted_ssis: Option<Vec<u32>>,
+    /// GSSIs that are monitored only: local calls are forwarded to Brew, calls from Brew are not transmitted
+    #[serde(default)]
+    pub monitoring_only: Vec<u32>,
+    /// Human-readable names for ISSIs, used in call logs. TOML keys are the ISSIs.
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+    /// Brew priority value for each TETRA call priority 0-15, 16 entries
+    pub priority_map: Option<Vec<u8>>,
 
     /// Set to true when SDS between local and Brew clients is enabled
     #[serde(default = "default_brew_feature_sds_enabled")]
@@ -68,21 +112,79 @@ fn default_brew_reconnect_delay() -> u64 {
     15
 }
 
+fn default_brew_jitter_buffer_frames() -> u8 {
+    4
+}
+
 fn default_brew_feature_sds_enabled() -> bool {
     true
 }
 
 /// Convert a CfgBrewDto (from TOML) into a CfgBrew (used in the stack config)
-pub fn apply_brew_patch(src: CfgBrewDto) -> CfgBrew {
-    CfgBrew {
+pub fn apply_brew_patch(src: CfgBrewDto) -> Result<CfgBrew, String> {
+    let mut alias_map = HashMap::new();
+    for (issi, alias) in src.aliases {
+        let Ok(issi) = issi.parse::<u32>() else {
+            return Err(format!("brew: alias key {} is not an ISSI", issi));
+        };
+        TetraAddress::try_new(issi, SsiType::Issi).map_err(|e| format!("brew: alias key: {}", e))?;
+        alias_map.insert(issi, alias);
+    }
+
+    let priority_map = match src.priority_map {
+        Some(map) => map
+            .try_into()
+            .map_err(|map: Vec<u8>| format!("brew: priority_map needs 16 entries, got {}", map.len()))?,
+        None => DEFAULT_BREW_PRIORITY_MAP,
+    };
+
+    Ok(CfgBrew {
         host: src.host,
         port: src.port,
         tls: src.tls,
         username: Some(src.username.to_string()),
         password: Some(SecretField::from(src.password)),
         reconnect_delay: Duration::from_secs(src.reconnect_delay_secs),
+        jitter_buffer_frames: src.jitter_buffer_frames,
         jitter_initial_latency_frames: src.jitter_initial_latency_frames,
+        recording_dir: src.recording_dir,
         feature_sds_enabled: src.feature_sds_enabled,
         whitelisted_ssis: src.whitelisted_ssis,
+        monitoring_only: src.monitoring_only,
+        alias_map,
+        priority_map,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_cfg(priority_map: Option<Vec<u8>>) -> Result<CfgBrew, String> {
+        apply_brew_patch(CfgBrewDto {
+            priority_map,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_default_priority_map() {
+        let cfg = get_cfg(None).unwrap();
+        assert_eq!(cfg.brew_priority(0), 0);
+        assert_eq!(cfg.brew_priority(15), 255);
+        for p in 0..16 {
+            assert_eq!(cfg.tetra_priority(cfg.brew_priority(p)), p);
+        }
+        assert_eq!(cfg.tetra_priority(20), 1);
+    }
+
+    #[test]
+    fn test_priority_map_length_checked() {
+        assert!(get_cfg(Some(vec![0; 15])).is_err());
+        let cfg = get_cfg(Some(vec![5; 16])).unwrap();
+        assert_eq!(cfg.brew_priority(3), 5);
+        // Several TETRA priorities map to the same value, the highest one wins
+        assert_eq!(cfg.tetra_priority(5), 15);
+        assert_eq!(cfg.tetra_priority(4), 0);
     }
 }
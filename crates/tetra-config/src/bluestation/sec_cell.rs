@@ -2,6 +2,7 @@ use serde::Deserialize;
 use std::collections::HashMap;
 
 use tetra_core::ranges::{SortedDisjointSsiRanges, SsiRange};
+use tetra_core::{SsiType, TetraAddress};
 use toml::Value;
 
 #[derive(Debug, Clone)]
@@ -28,8 +29,11 @@ pub struct CfgCellInfo {
 
     // 14 bits, from 18.4.2.2 D-MLE-SYSINFO
     pub location_area: u16,
-    // 16 bits, from 18.4.2.2 D-MLE-SYSINFO
+    /// 16 bits, from 18.4.2.2 D-MLE-SYSINFO. Bitmap of the subscriber classes allowed in this cell
     pub subscriber_class: u16,
+    /// Subscriber class bitmaps assigned to individual ISSIs, sent in D-LOCATION UPDATE ACCEPT.
+    /// ISSIs without an entry belong to all classes.
+    pub ms_subscriber_classes: HashMap<u32, u16>,
 
     // 1-bit service flags
     pub registration: bool,
@@ -59,6 +63,76 @@ pub struct CfgCellInfo {
     /// IANA timezone name (e.g. "Europe/Amsterdam"). When set, enables D-NWRK-BROADCAST
     /// time broadcasting so MSs can synchronize their clocks.
     pub timezone: Option<String>,
+
+    /// Number of times a D-RELEASE ending a group call is stolen from its traffic channel,
+    /// in consecutive frames, so that MSs with a missed block still leave the call
+    pub d_release_repeat: u8,
+
+    /// Which ISSIs may register in this cell
+    pub subscriber_policy: SubscriberPolicy,
+
+    /// CA neighbour cells announced in D-NWRK-BROADCAST, at most 7
+    pub neighbor_cells: Vec<NeighborCell>,
+
+    /// Energy economy parameters passed to the MAC in TL-CONFIGURE, None when energy economy is disabled
+    pub energy_economy: Option<EnergyEconomyConfig>,
+}
+
+/// Energy economy (napping) parameters, see clause 23.7.6
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnergyEconomyConfig {
+    /// Energy economy group EG1-EG7, Table 16.29
+    pub group: u8,
+    /// TDMA frame (1-18) at which the first awake period starts
+    pub startpoint: u8,
+    /// Energy economy group for dual watch, EG1-EG7
+    pub dual_watch_group: Option<u8>,
+}
+
+/// A neighbour cell, as announced to MSs for cell reselection
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NeighborCell {
+    /// 12 bits, main carrier number of the neighbour cell, in the same band as this cell
+    pub carrier: u16,
+    /// 10 bits, only needed if the neighbour belongs to another network
+    pub mcc: Option<u16>,
+    /// 14 bits, only needed if the neighbour belongs to another network
+    pub mnc: Option<u16>,
+    /// 14 bits
+    pub location_area: Option<u16>,
+}
+
+/// Registration policy applied by MM to incoming location updates
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum SubscriberPolicy {
+    /// Any ISSI may register
+    #[default]
+    AllowAll,
+    /// Only the listed ISSIs may register
+    Whitelist { issis: Vec<u32> },
+    /// All ISSIs except the listed ones may register
+    Blacklist { issis: Vec<u32> },
+}
+
+impl SubscriberPolicy {
+    pub fn permits(&self, issi: u32) -> bool {
+        match self {
+            SubscriberPolicy::AllowAll => true,
+            SubscriberPolicy::Whitelist { issis } => issis.contains(&issi),
+            SubscriberPolicy::Blacklist { issis } => !issis.contains(&issi),
+        }
+    }
+}
+
+/// Policy selector as written in the config file, the ISSI lists are separate fields
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriberPolicyDto {
+    AllowAll,
+    Whitelist,
+    Blacklist,
 }
 
 #[derive(Default, Deserialize)]
@@ -74,7 +148,9 @@ pub struct CellInfoDto {
 
     pub neighbor_cell_broadcast: Option<u8>,
     pub late_entry_supported: Option<bool>,
+    #[serde(alias = "allowed_subscriber_classes")]
     pub subscriber_class: Option<u16>,
+    pub ms_subscriber_classes: Option<Vec<(u32, u16)>>,
     pub registration: Option<bool>,
     pub deregistration: Option<bool>,
     pub priority_cell: Option<bool>,
@@ -100,12 +176,57 @@ pub struct CellInfoDto {
 
     pub timezone: Option<String>,
 
+    pub d_release_repeat: Option<u8>,
+
+    pub subscriber_policy: Option<SubscriberPolicyDto>,
+    pub allowed_issis: Option<Vec<u32>>,
+    pub blocked_issis: Option<Vec<u32>>,
+
+    pub neighbor_cells: Option<Vec<NeighborCell>>,
+
+    pub energy_economy_enabled: Option<bool>,
+    pub energy_economy: Option<EnergyEconomyConfig>,
+
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
 }
 
-pub fn cell_dto_to_cfg(ci: CellInfoDto) -> CfgCellInfo {
-    CfgCellInfo {
+pub fn cell_dto_to_cfg(ci: CellInfoDto) -> Result<CfgCellInfo, String> {
+    let subscriber_policy = match ci.subscriber_policy {
+        None | Some(SubscriberPolicyDto::AllowAll) => SubscriberPolicy::AllowAll,
+        Some(SubscriberPolicyDto::Whitelist) => SubscriberPolicy::Whitelist {
+            issis: validate_issis("allowed_issis", ci.allowed_issis.unwrap_or_default())?,
+        },
+        Some(SubscriberPolicyDto::Blacklist) => SubscriberPolicy::Blacklist {
+            issis: validate_issis("blocked_issis", ci.blocked_issis.unwrap_or_default())?,
+        },
+    };
+
+    let mut ms_subscriber_classes = HashMap::new();
+    for (issi, class) in ci.ms_subscriber_classes.unwrap_or_default() {
+        TetraAddress::try_new(issi, SsiType::Issi).map_err(|e| format!("cell_info.ms_subscriber_classes: {}", e))?;
+        if class == 0 {
+            return Err(format!("cell_info.ms_subscriber_classes: ISSI {} has no subscriber class", issi));
+        }
+        if ms_subscriber_classes.insert(issi, class).is_some() {
+            return Err(format!("cell_info.ms_subscriber_classes: ISSI {} listed more than once", issi));
+        }
+    }
+
+    let neighbor_cells = ci.neighbor_cells.unwrap_or_default();
+    validate_neighbor_cells(&neighbor_cells)?;
+
+    let energy_economy = if ci.energy_economy_enabled.unwrap_or(false) {
+        let Some(ee) = ci.energy_economy else {
+            return Err("cell_info.energy_economy: section required when energy_economy_enabled is set".to_string());
+        };
+        validate_energy_economy(&ee)?;
+        Some(ee)
+    } else {
+        None
+    };
+
+    Ok(CfgCellInfo {
         main_carrier: ci.main_carrier,
         freq_band: ci.freq_band,
         freq_offset_hz: ci.freq_offset,
@@ -116,6 +237,7 @@ pub fn cell_dto_to_cfg(ci: CellInfoDto) -> CfgCellInfo {
         neighbor_cell_broadcast: ci.neighbor_cell_broadcast.unwrap_or(0),
         late_entry_supported: ci.late_entry_supported.unwrap_or(false),
         subscriber_class: ci.subscriber_class.unwrap_or(65535), // All subscriber classes allowed
+        ms_subscriber_classes,
         registration: ci.registration.unwrap_or(true),
         deregistration: ci.deregistration.unwrap_or(true),
         priority_cell: ci.priority_cell.unwrap_or(false),
@@ -139,7 +261,74 @@ pub fn cell_dto_to_cfg(ci: CellInfoDto) -> CfgCellInfo {
             .map(SortedDisjointSsiRanges::from_vec_tuple)
             .unwrap_or(default_tetrapack_local_ranges()),
         timezone: ci.timezone,
+        d_release_repeat: ci.d_release_repeat.unwrap_or(3).max(1),
+        subscriber_policy,
+        neighbor_cells,
+        energy_economy,
+    })
+}
+
+impl CfgCellInfo {
+    /// Subscriber class bitmap assigned to an ISSI, if it has been assigned one
+    pub fn ms_subscriber_class(&self, issi: u32) -> Option<u16> {
+        self.ms_subscriber_classes.get(&issi).copied()
     }
+
+    /// Whether an ISSI belongs to at least one of the subscriber classes allowed in this cell
+    pub fn subscriber_class_permits(&self, issi: u32) -> bool {
+        self.ms_subscriber_class(issi)
+            .is_none_or(|class| class & self.subscriber_class != 0)
+    }
+}
+
+fn validate_issis(field: &str, issis: Vec<u32>) -> Result<Vec<u32>, String> {
+    for &issi in &issis {
+        TetraAddress::try_new(issi, SsiType::Issi).map_err(|e| format!("cell_info.{}: {}", field, e))?;
+    }
+    Ok(issis)
+}
+
+fn validate_neighbor_cells(cells: &[NeighborCell]) -> Result<(), String> {
+    if cells.len() > 7 {
+        return Err(format!(
+            "cell_info.neighbor_cells: {} cells given, at most 7 supported",
+            cells.len()
+        ));
+    }
+    for cell in cells {
+        let fields = [
+            ("carrier", Some(cell.carrier), 12),
+            ("mcc", cell.mcc, 10),
+            ("mnc", cell.mnc, 14),
+            ("location_area", cell.location_area, 14),
+        ];
+        for (name, value, bits) in fields {
+            if let Some(value) = value
+                && value >= 1 << bits
+            {
+                return Err(format!(
+                    "cell_info.neighbor_cells: {} {} does not fit in {} bits",
+                    name, value, bits
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_energy_economy(ee: &EnergyEconomyConfig) -> Result<(), String> {
+    if !(1..=7).contains(&ee.group) {
+        return Err(format!("cell_info.energy_economy: group {} not in 1-7", ee.group));
+    }
+    if !(1..=18).contains(&ee.startpoint) {
+        return Err(format!("cell_info.energy_economy: startpoint {} not in 1-18", ee.startpoint));
+    }
+    if let Some(group) = ee.dual_watch_group
+        && !(1..=7).contains(&group)
+    {
+        return Err(format!("cell_info.energy_economy: dual_watch_group {} not in 1-7", group));
+    }
+    Ok(())
 }
 
 /// Default local SSI ranges are defined as 0-90 (inclusive), which fits the TetraPack configuration.
@@ -148,3 +337,233 @@ pub fn cell_dto_to_cfg(ci: CellInfoDto) -> CfgCellInfo {
 fn default_tetrapack_local_ranges() -> SortedDisjointSsiRanges {
     SortedDisjointSsiRanges::from_vec_ssirange(vec![SsiRange::new(0, 90)])
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_allow_all() {
+        let policy = SubscriberPolicy::default();
+        assert_eq!(policy, SubscriberPolicy::AllowAll);
+        assert!(policy.permits(0));
+        assert!(policy.permits(2041234));
+        assert!(policy.permits(0xFFFFFF));
+    }
+
+    #[test]
+    fn test_policy_whitelist() {
+        let policy = SubscriberPolicy::Whitelist {
+            issis: vec![2041234, 2041235],
+        };
+        assert!(policy.permits(2041234));
+        assert!(policy.permits(2041235));
+        assert!(!policy.permits(2041233));
+        assert!(!policy.permits(2041236));
+        assert!(!policy.permits(0));
+
+        let empty = SubscriberPolicy::Whitelist { issis: vec![] };
+        assert!(!empty.permits(2041234));
+    }
+
+    #[test]
+    fn test_policy_blacklist() {
+        let policy = SubscriberPolicy::Blacklist { issis: vec![2041234] };
+        assert!(!policy.permits(2041234));
+        assert!(policy.permits(2041233));
+        assert!(policy.permits(2041235));
+
+        let empty = SubscriberPolicy::Blacklist { issis: vec![] };
+        assert!(empty.permits(2041234));
+    }
+
+    #[test]
+    fn test_policy_from_toml() {
+        let toml = r#"
+            main_carrier = 1521
+            freq_band = 4
+            freq_offset = 0
+            duplex_spacing = 4
+            reverse_operation = false
+            location_area = 2
+            subscriber_policy = "whitelist"
+            allowed_issis = [2041234, 2041235]
+        "#;
+        let dto: CellInfoDto = toml::from_str(toml).unwrap();
+        assert!(dto.extra.is_empty());
+        assert_eq!(
+            cell_dto_to_cfg(dto).unwrap().subscriber_policy,
+            SubscriberPolicy::Whitelist {
+                issis: vec![2041234, 2041235]
+            }
+        );
+    }
+
+    #[test]
+    fn test_policy_rejects_reserved_issi() {
+        let toml = r#"
+            main_carrier = 1521
+            freq_band = 4
+            freq_offset = 0
+            duplex_spacing = 4
+            reverse_operation = false
+            location_area = 2
+            subscriber_policy = "blacklist"
+            blocked_issis = [2041234, 0]
+        "#;
+        let dto: CellInfoDto = toml::from_str(toml).unwrap();
+        assert_eq!(cell_dto_to_cfg(dto).unwrap_err(), "cell_info.blocked_issis: SSI 0 is reserved");
+    }
+
+    #[test]
+    fn test_subscriber_classes_from_toml() {
+        let toml = r#"
+            main_carrier = 1521
+            freq_band = 4
+            freq_offset = 0
+            duplex_spacing = 4
+            reverse_operation = false
+            location_area = 2
+            allowed_subscriber_classes = 0x0003
+            ms_subscriber_classes = [[2041234, 0x0001], [2041235, 0x0004]]
+        "#;
+        let dto: CellInfoDto = toml::from_str(toml).unwrap();
+        assert!(dto.extra.is_empty());
+        let cell = cell_dto_to_cfg(dto).unwrap();
+        assert_eq!(cell.subscriber_class, 0x0003);
+        assert_eq!(cell.ms_subscriber_class(2041234), Some(0x0001));
+        assert_eq!(cell.ms_subscriber_class(2041236), None);
+        assert!(cell.subscriber_class_permits(2041234));
+        assert!(!cell.subscriber_class_permits(2041235));
+        // MSs without an assigned class are not filtered
+        assert!(cell.subscriber_class_permits(2041236));
+    }
+
+    #[test]
+    fn test_subscriber_classes_rejects_invalid() {
+        let base = r#"
+            main_carrier = 1521
+            freq_band = 4
+            freq_offset = 0
+            duplex_spacing = 4
+            reverse_operation = false
+            location_area = 2
+        "#;
+        let parse = |classes: &str| {
+            let dto: CellInfoDto = toml::from_str(&format!("{}ms_subscriber_classes = {}\n", base, classes)).unwrap();
+            cell_dto_to_cfg(dto).unwrap_err()
+        };
+        assert_eq!(
+            parse("[[2041234, 0]]"),
+            "cell_info.ms_subscriber_classes: ISSI 2041234 has no subscriber class"
+        );
+        assert_eq!(
+            parse("[[2041234, 1], [2041234, 2]]"),
+            "cell_info.ms_subscriber_classes: ISSI 2041234 listed more than once"
+        );
+        assert_eq!(parse("[[0, 1]]"), "cell_info.ms_subscriber_classes: SSI 0 is reserved");
+    }
+
+    #[test]
+    fn test_neighbor_cells_from_toml() {
+        let toml = r#"
+            main_carrier = 1521
+            freq_band = 4
+            freq_offset = 0
+            duplex_spacing = 4
+            reverse_operation = false
+            location_area = 2
+
+            [[neighbor_cells]]
+            carrier = 1522
+
+            [[neighbor_cells]]
+            carrier = 1530
+            mcc = 204
+            mnc = 1338
+            location_area = 3
+        "#;
+        let dto: CellInfoDto = toml::from_str(toml).unwrap();
+        assert!(dto.extra.is_empty());
+        let cell = cell_dto_to_cfg(dto).unwrap();
+        assert_eq!(
+            cell.neighbor_cells,
+            vec![
+                NeighborCell {
+                    carrier: 1522,
+                    mcc: None,
+                    mnc: None,
+                    location_area: None,
+                },
+                NeighborCell {
+                    carrier: 1530,
+                    mcc: Some(204),
+                    mnc: Some(1338),
+                    location_area: Some(3),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_neighbor_cells_rejects_invalid() {
+        let neighbor = |carrier| NeighborCell {
+            carrier,
+            mcc: None,
+            mnc: None,
+            location_area: None,
+        };
+        assert!(validate_neighbor_cells(&vec![neighbor(1522); 7]).is_ok());
+        assert_eq!(
+            validate_neighbor_cells(&vec![neighbor(1522); 8]).unwrap_err(),
+            "cell_info.neighbor_cells: 8 cells given, at most 7 supported"
+        );
+        assert_eq!(
+            validate_neighbor_cells(&[neighbor(4096)]).unwrap_err(),
+            "cell_info.neighbor_cells: carrier 4096 does not fit in 12 bits"
+        );
+    }
+
+    #[test]
+    fn test_energy_economy_from_toml() {
+        let base = r#"
+            main_carrier = 1521
+            freq_band = 4
+            freq_offset = 0
+            duplex_spacing = 4
+            reverse_operation = false
+            location_area = 2
+        "#;
+        let parse = |extra: &str| {
+            let dto: CellInfoDto = toml::from_str(&format!("{}{}", base, extra)).unwrap();
+            assert!(dto.extra.is_empty());
+            cell_dto_to_cfg(dto)
+        };
+
+        assert_eq!(parse("").unwrap().energy_economy, None);
+        let section = "[energy_economy]\ngroup = 3\nstartpoint = 5\ndual_watch_group = 2\n";
+        assert_eq!(parse(section).unwrap().energy_economy, None);
+        assert_eq!(
+            parse(&format!("energy_economy_enabled = true\n{}", section))
+                .unwrap()
+                .energy_economy,
+            Some(EnergyEconomyConfig {
+                group: 3,
+                startpoint: 5,
+                dual_watch_group: Some(2),
+            })
+        );
+        assert_eq!(
+            parse("energy_economy_enabled = true\n").unwrap_err(),
+            "cell_info.energy_economy: section required when energy_economy_enabled is set"
+        );
+        assert_eq!(
+            parse("energy_economy_enabled = true\n[energy_economy]\ngroup = 0\nstartpoint = 5\n").unwrap_err(),
+            "cell_info.energy_economy: group 0 not in 1-7"
+        );
+        assert_eq!(
+            parse("energy_economy_enabled = true\n[energy_economy]\ngroup = 1\nstartpoint = 19\n").unwrap_err(),
+            "cell_info.energy_economy: startpoint 19 not in 1-18"
+        );
+    }
+}
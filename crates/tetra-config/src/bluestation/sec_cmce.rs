@@ -0,0 +1,29 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use toml::Value;
+
+/// CMCE call control configuration
+#[derive(Debug, Clone, Default)]
+pub struct CfgCmce {
+    /// Release a group call as soon as every MS that set it up or joined it has sent U-RELEASE.
+    /// If false, the first U-RELEASE for a group call releases it.
+    pub teardown_on_empty: bool,
+}
+
+#[derive(Deserialize)]
+pub struct CfgCmceDto {
+    pub teardown_on_empty: Option<bool>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// Convert a [`CfgCmceDto`] (from TOML) into a [`CfgCmce`].
+pub fn apply_cmce_patch(src: CfgCmceDto) -> Result<CfgCmce, String> {
+    let mut cfg = CfgCmce::default();
+    if let Some(teardown_on_empty) = src.teardown_on_empty {
+        cfg.teardown_on_empty = teardown_on_empty;
+    }
+    Ok(cfg)
+}
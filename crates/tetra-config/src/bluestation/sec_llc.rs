@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use toml::Value;
+
+/// Default T.251 sender retry timer: 4 signalling frames
+pub const DEFAULT_T251_MS: u32 = 227;
+/// Default N.252 maximum number of TL-SDU retransmissions on the acknowledged basic link
+pub const DEFAULT_N252: u8 = 3;
+
+/// LLC basic link timer and retry configuration (Annex A.1 / A.2)
+#[derive(Debug, Clone)]
+pub struct CfgLlc {
+    /// T.251 sender retry timer, in milliseconds. Retransmit BL-DATA if not acknowledged within this time.
+    pub t251_ms: u32,
+    /// N.252 maximum number of retransmissions before the TL-SDU is reported lost
+    pub n252: u8,
+}
+
+impl Default for CfgLlc {
+    fn default() -> Self {
+        Self {
+            t251_ms: DEFAULT_T251_MS,
+            n252: DEFAULT_N252,
+        }
+    }
+}
+
+impl CfgLlc {
+    /// T.251 converted to a number of timeslots, rounded to the nearest timeslot (85/6 ms each)
+    pub fn t251_timeslots(&self) -> u32 {
+        (self.t251_ms * 6 + 42) / 85
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CfgLlcDto {
+    pub t251_ms: Option<u32>,
+    pub n252: Option<u8>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// Convert a [`CfgLlcDto`] (from TOML) into a [`CfgLlc`].
+///
+/// Returns an error string if a value is outside the range allowed by Annex A.
+pub fn apply_llc_patch(src: CfgLlcDto) -> Result<CfgLlc, String> {
+    let mut cfg = CfgLlc::default();
+    if let Some(t251_ms) = src.t251_ms {
+        if t251_ms == 0 {
+            return Err("llc: t251_ms must be non-zero".to_string());
+        }
+        cfg.t251_ms = t251_ms;
+    }
+    if let Some(n252) = src.n252 {
+        if !(1..=5).contains(&n252) {
+            return Err("llc: n252 must be 1-5".to_string());
+        }
+        cfg.n252 = n252;
+    }
+    Ok(cfg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_t251_matches_four_frames() {
+        // 4 signalling frames of 4 timeslots
+        assert_eq!(CfgLlc::default().t251_timeslots(), 16);
+    }
+}
@@ -27,8 +27,14 @@ pub struct CfgPhyIo {
 
     /// For Soapysdr backend: SoapySDR configuration
     pub soapysdr: Option<CfgSoapySdr>,
+
+    /// Drift of the TDMA clock against wall-clock time, in ppm, above which a warning is logged
+    pub clock_drift_threshold_ppm: f64,
 }
 
+/// Default for `CfgPhyIo::clock_drift_threshold_ppm`
+pub const DEFAULT_CLOCK_DRIFT_THRESHOLD_PPM: f64 = 100.0;
+
 #[derive(Deserialize)]
 pub struct PhyIoDto {
     pub backend: PhyBackend,
@@ -40,6 +46,8 @@ pub struct PhyIoDto {
 
     pub soapysdr: Option<SoapySdrDto>,
 
+    pub clock_drift_threshold_ppm: Option<f64>,
+
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
 }
@@ -100,5 +108,6 @@ pub fn phy_dto_to_cfg(src: PhyIoDto) -> CfgPhyIo {
         ul_input_file: src.ul_input_file,
         dl_input_file: src.dl_input_file,
         soapysdr,
+        clock_drift_threshold_ppm: src.clock_drift_threshold_ppm.unwrap_or(DEFAULT_CLOCK_DRIFT_THRESHOLD_PPM),
     }
 }
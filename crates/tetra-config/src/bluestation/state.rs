@@ -1,11 +1,13 @@
 use std::collections::{HashMap, HashSet};
-use tetra_core::TimeslotAllocator;
+use tetra_core::{ChannelClassInfo, EndpointRegistry, LinkIdAllocator, TimeslotAllocator};
 
 #[derive(Debug, Clone)]
 pub struct Subscriber {
     pub issi: u32,
     // Set of attached GSSIs
     pub attached_groups: HashSet<u32>,
+    /// Channel classes last advised by the MS in U-CHANNEL CLASS ADVICE
+    pub channel_class: Option<ChannelClassInfo>,
 }
 
 /// Centralized subscriber registry tracking locally registered ISSIs and their group affiliations.
@@ -37,6 +39,7 @@ impl SubscriberRegistry {
             Subscriber {
                 issi,
                 attached_groups: HashSet::new(),
+                channel_class: None,
             },
         );
     }
@@ -46,6 +49,7 @@ impl SubscriberRegistry {
         self.subscribers.entry(issi).or_insert_with(|| Subscriber {
             issi,
             attached_groups: HashSet::new(),
+            channel_class: None,
         })
     }
 
@@ -82,6 +86,20 @@ impl SubscriberRegistry {
         }
     }
 
+    /// Store the channel classes advised by a registered ISSI. Returns false if the ISSI is not registered.
+    pub fn set_channel_class(&mut self, issi: u32, channel_class: ChannelClassInfo) -> bool {
+        let Some(subscriber) = self.subscribers.get_mut(&issi) else {
+            return false;
+        };
+        subscriber.channel_class = Some(channel_class);
+        true
+    }
+
+    /// Iterate over all registered subscribers, in no particular order
+    pub fn subscribers(&self) -> impl Iterator<Item = &Subscriber> {
+        self.subscribers.values()
+    }
+
     /// Check if any subscriber is affiliated with the given GSSI
     pub fn has_group_members(&self, gssi: u32) -> bool {
         self.all_attached_groups.contains(&gssi)
@@ -96,6 +114,10 @@ pub struct StackState {
     pub network_connected: bool,
     /// Centralized subscriber registry for local-first routing decisions.
     pub subscribers: SubscriberRegistry,
+    /// Link identifiers in use by advanced links
+    pub link_ids: LinkIdAllocator,
+    /// Endpoint identifiers in use by MAC timeslot allocations
+    pub endpoints: EndpointRegistry,
 }
 
 #[cfg(test)]
@@ -146,6 +168,25 @@ mod tests {
         assert!(!reg.has_group_members(100));
     }
 
+    #[test]
+    fn test_set_channel_class() {
+        let mut reg = SubscriberRegistry::new();
+        let info = ChannelClassInfo {
+            identifiers: vec![2],
+            data_priority: None,
+        };
+        assert!(!reg.set_channel_class(1001, info.clone()));
+        assert!(!reg.is_registered(1001));
+
+        reg.register(1001);
+        assert!(reg.set_channel_class(1001, info.clone()));
+        assert_eq!(reg.subscribers().next().unwrap().channel_class, Some(info));
+
+        // Registering again starts from a clean record
+        reg.register(1001);
+        assert_eq!(reg.subscribers().next().unwrap().channel_class, None);
+    }
+
     #[test]
     fn test_has_group_members_empty() {
         let reg = SubscriberRegistry::new();
@@ -173,6 +214,8 @@ impl Default for StackState {
             timeslot_alloc: TimeslotAllocator::default(),
             network_connected: false,
             subscribers: SubscriberRegistry::new(),
+            link_ids: LinkIdAllocator::default(),
+            endpoints: EndpointRegistry::default(),
         }
     }
 }
@@ -1,5 +1,9 @@
+use core::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
 #[allow(dead_code)]
-#[derive(Copy, Debug, Clone, PartialEq)]
+#[derive(Copy, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum SsiType {
     Unknown,
     /// Generic type when specific type unknown. Avoid using where possible.
@@ -33,21 +37,92 @@ impl core::fmt::Display for SsiType {
     }
 }
 
-#[derive(Copy, Debug, Clone)]
+impl FromStr for SsiType {
+    type Err = AddressError;
+
+    /// Parse the names used by Display, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "UNKNOWN" => Ok(SsiType::Unknown),
+            "SSI" => Ok(SsiType::Ssi),
+            "ISSI" => Ok(SsiType::Issi),
+            "GSSI" => Ok(SsiType::Gssi),
+            "USSI" => Ok(SsiType::Ussi),
+            "SMI" => Ok(SsiType::Smi),
+            "ESI" => Ok(SsiType::Esi),
+            "EVENTLABEL" => Ok(SsiType::EventLabel),
+            _ => Err(AddressError::Malformed),
+        }
+    }
+}
+
+/// Largest value of a 24-bit SSI field
+pub const SSI_MAX: u32 = 0xFFFFFF;
+
+/// Reasons an SSI is not valid for a given SsiType, see ETSI EN 300 392-1 clause 7
+#[derive(Copy, Debug, Clone, PartialEq, Eq)]
+pub enum AddressError {
+    /// The value is reserved and may not be assigned to a subscriber or group
+    Reserved(u32),
+    /// The value does not fit in the SSI field
+    OutOfRange { value: u32, max: u32 },
+    /// The string is not of the form TYPE:SSI, e.g. ISSI:1234
+    Malformed,
+}
+
+impl core::fmt::Display for AddressError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AddressError::Reserved(ssi) => write!(f, "SSI {} is reserved", ssi),
+            AddressError::OutOfRange { value, max } => write!(f, "SSI {} exceeds maximum {}", value, max),
+            AddressError::Malformed => write!(f, "address must be of the form TYPE:SSI, e.g. ISSI:1234"),
+        }
+    }
+}
+
+impl std::error::Error for AddressError {}
+
+#[derive(Copy, Debug, Clone, Serialize, Deserialize)]
 pub struct TetraAddress {
     pub ssi: u32,
     pub ssi_type: SsiType,
 }
 
 impl TetraAddress {
+    /// Unchecked constructor, for addresses that were already validated or decoded from a 24-bit field
     pub fn new(ssi: u32, ssi_type: SsiType) -> Self {
         Self { ssi, ssi_type }
     }
 
+    /// Checked constructor for addresses from untrusted sources.
+    /// ISSIs must be in 1..=16777215, GSSIs in 1..=16777214. Other types only get the 24-bit range check.
+    pub fn try_new(ssi: u32, ssi_type: SsiType) -> Result<Self, AddressError> {
+        if ssi > SSI_MAX {
+            return Err(AddressError::OutOfRange { value: ssi, max: SSI_MAX });
+        }
+        match ssi_type {
+            SsiType::Issi | SsiType::Gssi if ssi == 0 => return Err(AddressError::Reserved(ssi)),
+            // All-ones GSSI is the broadcast (ALL) group and cannot be assigned
+            SsiType::Gssi if ssi == SSI_MAX => return Err(AddressError::Reserved(ssi)),
+            _ => {}
+        }
+        Ok(Self { ssi, ssi_type })
+    }
+
     /// Convenience constructor to create ISSI type address
     pub fn issi(ssi: u32) -> Self {
         Self::new(ssi, SsiType::Issi)
     }
+
+    /// True for the all-ones GSSI, the broadcast (ALL) group
+    pub fn is_broadcast(&self) -> bool {
+        self.ssi_type == SsiType::Gssi && self.ssi == SSI_MAX
+    }
+
+    /// True if this is an ISSI that may be assigned to a subscriber, see `try_new`
+    pub fn is_valid_issi(&self) -> bool {
+        self.ssi_type == SsiType::Issi && Self::try_new(self.ssi, self.ssi_type).is_ok()
+    }
 }
 
 impl core::fmt::Display for TetraAddress {
@@ -55,3 +130,88 @@ impl core::fmt::Display for TetraAddress {
         write!(f, "{}:{}", self.ssi_type, self.ssi)
     }
 }
+
+impl FromStr for TetraAddress {
+    type Err = AddressError;
+
+    /// Parse the Display form, e.g. ISSI:1234 or GSSI:5678. The SSI is checked as in `try_new`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (ssi_type, ssi) = s.split_once(':').ok_or(AddressError::Malformed)?;
+        let ssi = ssi.trim().parse().map_err(|_| AddressError::Malformed)?;
+        Self::try_new(ssi, ssi_type.trim().parse()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_new_issi_bounds() {
+        assert_eq!(TetraAddress::try_new(0, SsiType::Issi).unwrap_err(), AddressError::Reserved(0));
+        assert_eq!(TetraAddress::try_new(1, SsiType::Issi).unwrap().ssi, 1);
+        assert_eq!(TetraAddress::try_new(16777215, SsiType::Issi).unwrap().ssi, 16777215);
+        assert_eq!(
+            TetraAddress::try_new(16777216, SsiType::Issi).unwrap_err(),
+            AddressError::OutOfRange {
+                value: 16777216,
+                max: 16777215
+            }
+        );
+    }
+
+    #[test]
+    fn test_try_new_gssi_bounds() {
+        assert_eq!(TetraAddress::try_new(0, SsiType::Gssi).unwrap_err(), AddressError::Reserved(0));
+        assert_eq!(TetraAddress::try_new(1, SsiType::Gssi).unwrap().ssi, 1);
+        assert_eq!(TetraAddress::try_new(16777214, SsiType::Gssi).unwrap().ssi, 16777214);
+        assert_eq!(
+            TetraAddress::try_new(16777215, SsiType::Gssi).unwrap_err(),
+            AddressError::Reserved(16777215)
+        );
+        assert_eq!(
+            TetraAddress::try_new(u32::MAX, SsiType::Gssi).unwrap_err(),
+            AddressError::OutOfRange {
+                value: u32::MAX,
+                max: 16777215
+            }
+        );
+    }
+
+    #[test]
+    fn test_try_new_other_types() {
+        // Only the field width is checked for types without reserved values
+        assert!(TetraAddress::try_new(0, SsiType::Ssi).is_ok());
+        assert!(TetraAddress::try_new(16777215, SsiType::Ussi).is_ok());
+        assert!(TetraAddress::try_new(16777216, SsiType::Ssi).is_err());
+        assert_eq!(AddressError::Reserved(0).to_string(), "SSI 0 is reserved");
+    }
+
+    #[test]
+    fn test_display_from_str_roundtrip() {
+        for addr in [TetraAddress::issi(1234), TetraAddress::new(5678, SsiType::Gssi)] {
+            let s = addr.to_string();
+            let parsed: TetraAddress = s.parse().unwrap();
+            assert_eq!((parsed.ssi, parsed.ssi_type), (addr.ssi, addr.ssi_type));
+        }
+        assert_eq!(TetraAddress::issi(1234).to_string(), "ISSI:1234");
+        assert_eq!("gssi: 91".parse::<TetraAddress>().unwrap().ssi_type, SsiType::Gssi);
+
+        assert_eq!("1234".parse::<TetraAddress>().unwrap_err(), AddressError::Malformed);
+        assert_eq!("XSSI:1234".parse::<TetraAddress>().unwrap_err(), AddressError::Malformed);
+        assert_eq!("ISSI:-1".parse::<TetraAddress>().unwrap_err(), AddressError::Malformed);
+        assert_eq!("ISSI:0".parse::<TetraAddress>().unwrap_err(), AddressError::Reserved(0));
+    }
+
+    #[test]
+    fn test_broadcast_and_valid_issi() {
+        assert!(TetraAddress::new(SSI_MAX, SsiType::Gssi).is_broadcast());
+        assert!(!TetraAddress::new(SSI_MAX, SsiType::Issi).is_broadcast());
+        assert!(!TetraAddress::new(1234, SsiType::Gssi).is_broadcast());
+
+        assert!(TetraAddress::issi(1234).is_valid_issi());
+        assert!(!TetraAddress::issi(0).is_valid_issi());
+        assert!(!TetraAddress::issi(SSI_MAX + 1).is_valid_issi());
+        assert!(!TetraAddress::new(1234, SsiType::Gssi).is_valid_issi());
+    }
+}
@@ -1,11 +1,13 @@
 use std::{
     cmp::{max, min},
-    fmt,
+    fmt, io,
 };
 
+use serde::{Deserialize, Serialize};
+
 use crate::pdu_parse_error::PduParseErr;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 
 pub struct BitBuffer {
     buffer: Vec<u8>,
@@ -89,6 +91,27 @@ impl BitBuffer {
         buf
     }
 
+    /// Construct a BitBuffer from a string of hexadecimal characters, MSB first.
+    /// Each character yields 4 bits; for an odd number of characters, the last byte is zero-padded
+    /// and the window ends halfway through it. An optional `0x` prefix is skipped, so values copied
+    /// from Wireshark can be passed as is. Returns an error on any other non-hex character.
+    pub fn from_hex_str(hexstr: &str) -> Result<Self, PduParseErr> {
+        let hexstr = hexstr.strip_prefix("0x").or_else(|| hexstr.strip_prefix("0X")).unwrap_or(hexstr);
+        let mut buf = BitBuffer::new(hexstr.len() * 4);
+        for c in hexstr.chars() {
+            let Some(nibble) = c.to_digit(16) else {
+                return Err(PduParseErr::InvalidValue {
+                    field: "hexstr",
+                    value: c as u64,
+                });
+            };
+            buf.write_bits(nibble as u64, 4);
+        }
+        // Reset pos back to start
+        buf.pos = buf.start;
+        Ok(buf)
+    }
+
     /// Construct a BitBuffer directly from a byte array of '0'/'1' bytes.
     pub fn from_bitarr(data: &[u8]) -> Self {
         let mut buf = BitBuffer::new(data.len());
@@ -134,6 +157,33 @@ impl BitBuffer {
         buf
     }
 
+    /// Split into two independent buffers at `pos` (relative to window start). The first covers `[pos, split)`,
+    /// from the current position up to the split point, the second covers `[split, end)`.
+    /// Both are byte-wise copies of the relevant part of the underlying storage, so heading/trailing data may be present.
+    pub fn split_at(&self, pos: usize) -> (BitBuffer, BitBuffer) {
+        let split = self.start + pos;
+        assert!(
+            split >= self.pos && split <= self.end,
+            "split out of window: got {}, allowed [{},{}]",
+            split,
+            self.pos,
+            self.end
+        );
+        (self.copy_range(self.pos, split), self.copy_range(split, self.end))
+    }
+
+    /// Copy the absolute bit range `[from, to)` into a new BitBuffer whose window covers exactly that range.
+    fn copy_range(&self, from: usize, to: usize) -> Self {
+        let start_byte = from / 8;
+        let end_byte = to.div_ceil(8);
+        let mut buf = BitBuffer::new((end_byte - start_byte) * 8);
+        buf.buffer.copy_from_slice(&self.buffer[start_byte..end_byte]);
+        buf.start = from % 8;
+        buf.pos = buf.start;
+        buf.end = buf.start + (to - from);
+        buf
+    }
+
     /// Takes slice as parameter for output. Reads slice.len() bits from bitbuf[pos], and writes to output slice. 1 bit per byte.
     pub fn to_bitarr(&mut self, buf: &mut [u8]) {
         // TODO bounds check here, optimize performance
@@ -177,6 +227,13 @@ impl BitBuffer {
         self.peek_bits_startoffset(start_offset, num_bits)
     }
 
+    /// Peek `num_bits` at `offset` bits past the current pos, without advancing.
+    /// Returns None on overflow or if `num_bits>64`.
+    pub fn peek_bits_at_offset(&self, offset: usize, num_bits: usize) -> Option<u64> {
+        let start_offset = (self.pos - self.start).checked_add(offset)?;
+        self.peek_bits_startoffset(start_offset, num_bits)
+    }
+
     /// Peek `num_bits` with offset from window start, without advancing.
     /// Returns None on overflow or if `num_bits>64`.
     pub fn peek_bits_startoffset(&self, offset: usize, num_bits: usize) -> Option<u64> {
@@ -198,8 +255,11 @@ impl BitBuffer {
 
     /// Similar to read_bits, but returns a ParseError::BufferEnded with the given error_string if not enough bits are available.
     pub fn read_field(&mut self, num_bits: usize, error_string: &'static str) -> Result<u64, PduParseErr> {
-        self.read_bits(num_bits)
-            .ok_or(PduParseErr::BufferEnded { field: Some(error_string) })
+        self.read_bits(num_bits).ok_or(PduParseErr::BufferEnded {
+            field: Some(error_string),
+            bit_offset: self.get_pos(),
+            bit_length: num_bits,
+        })
     }
 
     pub fn read_bit(&mut self) -> Option<u8> {
@@ -208,21 +268,46 @@ impl BitBuffer {
         Some(v as u8)
     }
 
+    /// Iterate over the bits from the current pos up to the window end, advancing pos with each bit yielded.
+    /// Bits not taken from the iterator are left unread.
+    pub fn bits(&mut self) -> BitIter<'_> {
+        BitIter { buf: self }
+    }
+
     /// Read `num_bits` at the current pos, advancing pos, and write them into the provided output slice as bytes
     pub fn read_bits_into_slice(&mut self, num_bits: usize, buf: &mut [u8]) -> Option<()> {
-        let num_bytes = (num_bits + 7) / 8;
-        assert!(buf.len() >= num_bytes, "output buffer too small for num_bits");
-
-        let mut bits_remaining = num_bits;
-        for i in 0..num_bytes {
-            let bits_in_byte = usize::min(bits_remaining, 8);
-            let read_byte = self.read_bits(bits_in_byte)? as u8;
-            buf[i] = read_byte << (8 - bits_in_byte);
-            bits_remaining -= bits_in_byte;
+        if self.get_len_remaining() < num_bits {
+            return None;
         }
+        self.read_to_slice(buf, num_bits);
         Some(())
     }
 
+    /// Read `num_bits` at the current pos into `data`, packed MSB first, advancing pos.
+    /// Unused bits of the last byte are zeroed. Byte-aligned reads are a single memcpy.
+    /// Panics if fewer than `num_bits` bits remain or `data` is too small.
+    pub fn read_to_slice(&mut self, data: &mut [u8], num_bits: usize) {
+        assert!(data.len() * 8 >= num_bits, "output slice too small for num_bits");
+        assert!(self.get_len_remaining() >= num_bits, "read would exceed buffer end");
+
+        let full_bytes = num_bits / 8;
+        let tail_bits = num_bits % 8;
+        if self.pos.is_multiple_of(8) {
+            let idx = self.pos / 8;
+            data[..full_bytes].copy_from_slice(&self.buffer[idx..idx + full_bytes]);
+            self.pos += full_bytes * 8;
+        } else {
+            for byte in &mut data[..full_bytes] {
+                *byte = self.read_bits_at_unchecked(self.pos, 8) as u8;
+                self.pos += 8;
+            }
+        }
+        if tail_bits > 0 {
+            data[full_bytes] = (self.read_bits_at_unchecked(self.pos, tail_bits) as u8) << (8 - tail_bits);
+            self.pos += tail_bits;
+        }
+    }
+
     fn _realloc_tail(&mut self, new_cap_bits: usize) {
         let new_cap_bytes = (new_cap_bits + 7) / 8;
         assert!(
@@ -307,6 +392,20 @@ impl BitBuffer {
         Some(())
     }
 
+    /// Xors a keystream, packed MSB first, into `len_bits` bits starting at `start_bit` (relative to window start).
+    /// Used to apply an air interface encryption mask in place. Leaves pos unchanged.
+    /// Returns None, without modifying the buffer, if the range exceeds the window or the keystream is too short.
+    pub fn xor_mask(&mut self, mask: &[u8], start_bit: usize, len_bits: usize) -> Option<()> {
+        if mask.len() * 8 < len_bits || start_bit.checked_add(len_bits)? > self.get_len() {
+            return None;
+        }
+        let pos = self.pos;
+        self.seek(start_bit);
+        let result = self.xor_bytearr(mask, len_bits);
+        self.pos = pos;
+        result
+    }
+
     /// Write a single bit to pos
     pub fn write_bit(&mut self, value: u8) {
         assert!(value == 0 || value == 1, "write_bit: value must be 0 or 1");
@@ -416,6 +515,36 @@ impl BitBuffer {
         self.pos += num_bits;
     }
 
+    /// Write the first `num_bits` bits of `data`, packed MSB first, at the current pos, advancing pos.
+    /// Byte-aligned writes are a single memcpy plus one masked write for a trailing partial byte.
+    /// If autoexpand is enabled, will advance end as well and/or realloc if buffer full
+    pub fn copy_from_slice(&mut self, data: &[u8], num_bits: usize) {
+        assert!(data.len() * 8 >= num_bits, "input slice too small for num_bits");
+        if self.pos + num_bits > self.end {
+            if self.flag_autoexpand {
+                self._move_end(self.pos + num_bits - self.end);
+            } else {
+                panic!("write would exceed buffer end");
+            }
+        }
+
+        let full_bytes = num_bits / 8;
+        let tail_bits = num_bits % 8;
+        if self.pos.is_multiple_of(8) {
+            let idx = self.pos / 8;
+            self.buffer[idx..idx + full_bytes].copy_from_slice(&data[..full_bytes]);
+            self.pos += full_bytes * 8;
+        } else {
+            for chunk in data[..full_bytes].chunks(8) {
+                let v = chunk.iter().fold(0u64, |acc, &byte| acc << 8 | byte as u64);
+                self.write_bits(v, chunk.len() * 8);
+            }
+        }
+        if tail_bits > 0 {
+            self.write_bits((data[full_bytes] >> (8 - tail_bits)) as u64, tail_bits);
+        }
+    }
+
     /// Read `num_bits` from a source bitbuffer, starting at `pos`.
     /// Write this data into the current bitbuffer at the current `pos`.
     pub fn copy_bits(&mut self, src_bitbuf: &mut BitBuffer, num_bits: usize) {
@@ -670,12 +799,60 @@ impl BitBuffer {
     }
 }
 
+/// Iterator over the bits of a [`BitBuffer`], see [`BitBuffer::bits`]
+pub struct BitIter<'a> {
+    buf: &'a mut BitBuffer,
+}
+
+impl Iterator for BitIter<'_> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<bool> {
+        self.buf.read_bit().map(|bit| bit != 0)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.buf.get_len_remaining();
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for BitIter<'_> {}
+
 impl fmt::Debug for BitBuffer {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "BitBuffer {{ <{} ^{} >{} {} }}", self.start, self.pos, self.end, self.dump_bin())
     }
 }
 
+impl io::Read for BitBuffer {
+    /// Read packed bytes (MSB first) from the current position, advancing it.
+    /// If fewer than 8 bits remain, the final byte holds the remaining bits, zero-padded at the LSB side.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let num_bits = min(self.get_len_remaining(), buf.len() * 8);
+        self.read_to_slice(buf, num_bits);
+        Ok((num_bits + 7) / 8)
+    }
+}
+
+impl io::Write for BitBuffer {
+    /// Write packed bytes (MSB first) at the current position, advancing it.
+    /// A non-autoexpanding buffer only accepts as many whole bytes as fit before its end.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let num_bytes = if self.flag_autoexpand {
+            buf.len()
+        } else {
+            min(buf.len(), self.get_len_remaining() / 8)
+        };
+        self.copy_from_slice(buf, num_bytes * 8);
+        Ok(num_bytes)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -690,6 +867,26 @@ mod tests {
         assert_eq!(bb.read_bits(8).unwrap(), 0xCD);
     }
 
+    #[test]
+    fn test_bits_iter() {
+        let mut bb = BitBuffer::from_bitstr("1101101110");
+        bb.seek(2);
+        bb.set_raw_end(bb.get_raw_start() + 8);
+        assert_eq!(bb.bits().len(), 6);
+
+        // Indices of the set bits among the next four
+        let set: Vec<u8> = bb.bits().take(4).enumerate().filter(|&(_, b)| b).map(|(i, _)| i as u8).collect();
+        assert_eq!(set, vec![1, 2]);
+        assert_eq!(bb.get_pos(), 6);
+
+        // Interleaved with field reads through by_ref, stopping at the window end
+        let mut bits = bb.bits();
+        assert_eq!(bits.by_ref().next(), Some(true));
+        assert_eq!(bits.collect::<Vec<_>>(), vec![true]);
+        assert_eq!(bb.get_len_remaining(), 0);
+        assert_eq!(bb.bits().next(), None);
+    }
+
     #[test]
     fn test_partial_boundary_read_write() {
         let mut bb = BitBuffer::new(16);
@@ -799,6 +996,81 @@ mod tests {
         assert_eq!(bb.dump_hex(), "BC");
     }
 
+    #[test]
+    fn test_from_hex_str() {
+        let bb = BitBuffer::from_hex_str("a5F").unwrap();
+        assert_eq!(bb.get_len(), 12);
+        assert_eq!(bb.get_pos(), 0);
+        assert_eq!(bb.to_bitstr(), "101001011111");
+        assert_eq!(bb.dump_hex(), "A5F");
+
+        assert_eq!(BitBuffer::from_hex_str("").unwrap().get_len(), 0);
+
+        // Optional 0x prefix, also with an odd number of digits
+        assert_eq!(BitBuffer::from_hex_str("0x1A2B3C").unwrap().dump_hex(), "1A2B3C");
+        let bb = BitBuffer::from_hex_str("0XA5F").unwrap();
+        assert_eq!(bb.get_len(), 12);
+        assert_eq!(bb.to_bitstr(), "101001011111");
+        assert_eq!(BitBuffer::from_hex_str("0x").unwrap().get_len(), 0);
+        assert!(BitBuffer::from_hex_str("x1A").is_err());
+
+        assert_eq!(
+            BitBuffer::from_hex_str("0g").unwrap_err(),
+            PduParseErr::InvalidValue {
+                field: "hexstr",
+                value: 'g' as u64
+            }
+        );
+    }
+
+    #[test]
+    fn test_hex_roundtrip_all_bytes() {
+        for byte in 0..=255u8 {
+            let hex = format!("{:02x}", byte);
+            let bb = BitBuffer::from_hex_str(&hex).unwrap();
+            assert_eq!(bb.into_bytes(), vec![byte]);
+            assert_eq!(BitBuffer::from_hex_str(&hex).unwrap().dump_hex(), hex.to_uppercase());
+        }
+    }
+
+    #[test]
+    fn test_io_read_bufreader() {
+        use std::io::Read;
+
+        // 20 bits: two full bytes and a partial final byte
+        let bb = BitBuffer::from_bitstr("10101011110011010110");
+        let mut reader = io::BufReader::new(bb);
+        let mut out = Vec::new();
+        reader.read_to_end(&mut out).unwrap();
+        assert_eq!(out, vec![0xAB, 0xCD, 0x60]);
+    }
+
+    #[test]
+    fn test_io_write_bufwriter() {
+        use std::io::Write;
+
+        let mut writer = io::BufWriter::new(BitBuffer::new_autoexpand(8));
+        writer.write_all(&[0xDE, 0xAD, 0xBE, 0xEF]).unwrap();
+        let bb = writer.into_inner().unwrap();
+        assert_eq!(bb.get_len(), 32);
+        assert_eq!(bb.dump_hex(), "DEADBEEF");
+
+        // Fixed-size buffer refuses to write beyond its end
+        let mut bb = BitBuffer::new(12);
+        assert_eq!(bb.write(&[0x12, 0x34]).unwrap(), 1);
+        assert!(bb.write_all(&[0x56]).is_err());
+    }
+
+    #[test]
+    fn test_io_copy_roundtrip() {
+        let mut src = BitBuffer::from_hex_str("0123456789ABCDEF").unwrap();
+        src.seek(4);
+        let mut dst = BitBuffer::new_autoexpand(8);
+        let copied = io::copy(&mut src, &mut dst).unwrap();
+        assert_eq!(copied, 8);
+        assert_eq!(dst.dump_hex(), "123456789ABCDEF0");
+    }
+
     #[test]
     fn test_dump_funcs() {
         let mut bb = BitBuffer::from_vec(vec![0xA0]); // 10100000
@@ -866,6 +1138,23 @@ mod tests {
         assert_eq!(bb.get_pos(), 0);
     }
 
+    #[test]
+    fn test_peek_bits_at_offset() {
+        let mut bb = BitBuffer::from_bitstr("1010101111001101");
+        bb.seek(3);
+        // offset 0 matches peek_bits
+        assert_eq!(bb.peek_bits_at_offset(0, 5), bb.peek_bits(5));
+        // lookahead relative to current pos: bits [7..13) = "111001"
+        assert_eq!(bb.peek_bits_at_offset(4, 6), Some(0b111001));
+        assert_eq!(bb.get_pos(), 3);
+
+        // peeking up to the end is allowed, beyond it is not
+        assert_eq!(bb.peek_bits_at_offset(12, 1), Some(1));
+        assert!(bb.peek_bits_at_offset(12, 2).is_none());
+        assert!(bb.peek_bits_at_offset(usize::MAX, 1).is_none());
+        assert_eq!(bb.get_pos(), 3);
+    }
+
     #[test]
     fn test_to_bitarr() {
         let mut bb = BitBuffer::from_bitstr("10110011");
@@ -885,6 +1174,84 @@ mod tests {
         assert_eq!(bb.get_pos(), 11);
     }
 
+    #[test]
+    fn test_read_to_slice() {
+        // Byte-aligned and unaligned start, with a partial trailing byte
+        let mut bb = BitBuffer::from_bitstr("1011001101100111011");
+        let mut out = [0xFFu8; 3];
+        bb.read_to_slice(&mut out, 11);
+        assert_eq!(out, [0b10110011, 0b01100000, 0xFF]);
+        bb.read_to_slice(&mut out, 8);
+        assert_eq!(out[0], 0b00111011);
+        assert_eq!(bb.get_len_remaining(), 0);
+    }
+
+    #[test]
+    fn test_copy_from_slice() {
+        let data = [0xDE, 0xAD, 0xBE, 0xEF, 0x01, 0x23, 0x45, 0x67, 0x89, 0xAB];
+
+        // Aligned: full bytes plus a masked partial byte, without touching the bits after it
+        let mut bb = BitBuffer::new(80);
+        bb.write_ones(80);
+        bb.seek(0);
+        bb.copy_from_slice(&data, 20);
+        assert_eq!(bb.get_pos(), 20);
+        bb.seek(0);
+        assert_eq!(bb.read_bits(24).unwrap(), 0xDEADBF);
+
+        // Unaligned, spanning more than 64 bits
+        let mut bb = BitBuffer::new_autoexpand(8);
+        bb.write_bits(0b101, 3);
+        bb.copy_from_slice(&data, 76);
+        assert_eq!(bb.get_len(), 79);
+        bb.seek(3);
+        let mut out = [0u8; 10];
+        bb.read_to_slice(&mut out, 76);
+        assert_eq!(out[..9], data[..9]);
+        assert_eq!(out[9], 0xA0);
+    }
+
+    /// Compares copy_from_slice against the equivalent write_bits loop for a 268-bit SCH/F block
+    #[test]
+    #[ignore = "timing benchmark, run with --release --ignored"]
+    fn bench_copy_from_slice() {
+        use std::hint::black_box;
+        use std::time::Instant;
+
+        const ITERATIONS: usize = 100_000;
+        const NUM_BITS: usize = 268;
+        let data: Vec<u8> = (0..NUM_BITS.div_ceil(8)).map(|i| (i as u8).wrapping_mul(37)).collect();
+        let mut bb = BitBuffer::new(NUM_BITS);
+
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            bb.seek(0);
+            for &byte in &data[..NUM_BITS / 8] {
+                bb.write_bits(byte as u64, 8);
+            }
+            bb.write_bits((data[NUM_BITS / 8] >> 4) as u64, 4);
+            black_box(&bb);
+        }
+        let loop_time = start.elapsed();
+        let reference = bb.to_bitstr();
+
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            bb.seek(0);
+            bb.copy_from_slice(black_box(&data), NUM_BITS);
+            black_box(&bb);
+        }
+        let copy_time = start.elapsed();
+
+        assert_eq!(bb.to_bitstr(), reference);
+        assert!(
+            copy_time * 4 <= loop_time,
+            "copy_from_slice {:?} not 4x faster than write_bits loop {:?}",
+            copy_time,
+            loop_time
+        );
+    }
+
     #[test]
     fn test_xor_bit() {
         let mut bb = BitBuffer::from_bitstr("10110000");
@@ -923,4 +1290,126 @@ mod tests {
             "001100000011000000110000001100000011000000110000001100000011000000001100"
         );
     }
+
+    #[test]
+    fn test_xor_mask() {
+        let mut bb = BitBuffer::from_bitstr("1111000011110000111100");
+        bb.seek(5);
+        // Unaligned start, partial final byte: the trailing bits of the mask are not applied
+        bb.xor_mask(&[0b10101010, 0b11111111], 3, 13).unwrap();
+        assert_eq!(bb.to_bitstr(), "1110010110101111111100");
+        assert_eq!(bb.get_pos(), 5);
+
+        // Applying the same mask again restores the plaintext
+        bb.xor_mask(&[0b10101010, 0b11111111], 3, 13).unwrap();
+        assert_eq!(bb.to_bitstr(), "1111000011110000111100");
+
+        // Out of window or short keystream leaves the buffer untouched
+        assert!(bb.xor_mask(&[0xFF, 0xFF], 10, 13).is_none());
+        assert!(bb.xor_mask(&[0xFF], 0, 9).is_none());
+        assert_eq!(bb.to_bitstr(), "1111000011110000111100");
+    }
+
+    #[test]
+    fn test_split_at() {
+        let mut bb = BitBuffer::from_bitstr("1100101011110000101");
+        bb.seek(3);
+        let (mut left, mut right) = bb.split_at(11);
+        assert_eq!(left.to_bitstr(), "01010111");
+        assert_eq!(right.to_bitstr(), "10000101");
+        assert_eq!(left.get_pos(), 0);
+        assert_eq!(right.get_pos(), 0);
+
+        // Views are independent of each other and of the original
+        left.write_bits(0, 8);
+        assert_eq!(right.read_bits(8), Some(0b10000101));
+        assert_eq!(bb.to_bitstr(), "1100101011110000101");
+
+        // Splitting at the current position or at the end yields an empty view
+        let (left, right) = bb.split_at(3);
+        assert_eq!(left.get_len(), 0);
+        assert_eq!(right.to_bitstr(), "0101011110000101");
+        let (left, right) = bb.split_at(bb.get_len());
+        assert_eq!(left.get_len(), 16);
+        assert_eq!(right.get_len(), 0);
+    }
+
+    mod proptests {
+        use proptest::prelude::*;
+
+        use super::*;
+
+        proptest! {
+            #![proptest_config(ProptestConfig::with_cases(10_000))]
+
+            #[test]
+            fn bitwise_write_read_roundtrip(payload in prop::collection::vec(any::<u8>(), 0..=512)) {
+                let src = BitBuffer::from_bytes(&payload);
+                let mut bb = BitBuffer::new(payload.len() * 8);
+                for i in 0..payload.len() * 8 {
+                    bb.write_bit(src.peek_bits_startoffset(i, 1).unwrap() as u8);
+                }
+                prop_assert_eq!(bb.get_len_remaining(), 0);
+
+                bb.seek(0);
+                for &byte in &payload {
+                    let mut v = 0u8;
+                    for _ in 0..8 {
+                        v = (v << 1) | bb.read_bit().unwrap();
+                    }
+                    prop_assert_eq!(v, byte);
+                }
+                prop_assert_eq!(bb.into_bytes(), payload);
+            }
+
+            #[test]
+            fn copy_bits_random_offsets(
+                payload in prop::collection::vec(any::<u8>(), 1..=64),
+                src_off in 0usize..64,
+                dst_off in 0usize..64,
+                len_frac in 0.0f64..=1.0,
+            ) {
+                let total = payload.len() * 8;
+                let src_off = src_off % total;
+                let num_bits = ((total - src_off) as f64 * len_frac) as usize;
+
+                let mut src = BitBuffer::from_bytes(&payload);
+                src.seek(src_off);
+                let mut dst = BitBuffer::new(dst_off + num_bits);
+                dst.seek(dst_off);
+                dst.copy_bits(&mut src, num_bits);
+
+                prop_assert_eq!(src.get_pos(), src_off + num_bits);
+                prop_assert_eq!(dst.get_len_remaining(), 0);
+                prop_assert_eq!(dst.peek_bits_startoffset(0, dst_off.min(64)), Some(0));
+                for i in 0..num_bits {
+                    prop_assert_eq!(
+                        dst.peek_bits_startoffset(dst_off + i, 1),
+                        src.peek_bits_startoffset(src_off + i, 1)
+                    );
+                }
+            }
+
+            #[test]
+            fn write_read_bits_all_widths(
+                num_bits in 1usize..=64,
+                value in any::<u64>(),
+                offset in 0usize..16,
+            ) {
+                let value = if num_bits == 64 { value } else { value & ((1 << num_bits) - 1) };
+                let mut bb = BitBuffer::new(offset + num_bits + 16);
+                bb.write_ones(offset);
+                bb.write_bits(value, num_bits);
+                bb.write_ones(16);
+
+                bb.seek(offset);
+                prop_assert_eq!(bb.read_bits(num_bits), Some(value));
+                prop_assert_eq!(bb.read_bits(16), Some(0xFFFF));
+                bb.seek(0);
+                if offset > 0 {
+                    prop_assert_eq!(bb.read_bits(offset), Some((1 << offset) - 1));
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,129 @@
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use crate::TdmaTime;
+use crate::tdma_time::MULTIFRAME_WRAP;
+
+/// Duration of one timeslot in seconds: 255 symbols at 18 ksymbols/s
+pub const TIMESLOT_DURATION_SECS: f64 = 255.0 / 18000.0;
+
+/// Number of samples kept for the drift estimate by [`ClockDriftMonitor::default`]
+pub const DEFAULT_DRIFT_WINDOW: usize = 60;
+
+/// Tracks the TDMA clock against wall-clock time to detect drift.
+/// Feed it a (wall time, TDMA time) pair at a regular interval, e.g. every multiframe.
+/// The drift is positive when the TDMA clock runs fast compared to the wall clock.
+#[derive(Debug, Clone)]
+pub struct ClockDriftMonitor {
+    /// First sample ever seen, None until the first update
+    reference: Option<(Instant, TdmaTime)>,
+    /// The most recent samples, oldest first
+    samples: VecDeque<(Instant, TdmaTime)>,
+    /// Maximum number of samples kept
+    window: usize,
+}
+
+impl Default for ClockDriftMonitor {
+    fn default() -> Self {
+        Self::new(DEFAULT_DRIFT_WINDOW)
+    }
+}
+
+impl ClockDriftMonitor {
+    /// Create a monitor estimating drift over the last `window` samples, at least 2
+    pub fn new(window: usize) -> Self {
+        let window = window.max(2);
+        Self {
+            reference: None,
+            samples: VecDeque::with_capacity(window),
+            window,
+        }
+    }
+
+    /// Record a sample, dropping the oldest one once the window is full
+    pub fn update(&mut self, wall: Instant, tdma: TdmaTime) {
+        if self.reference.is_none() {
+            self.reference = Some((wall, tdma));
+        }
+        if self.samples.len() == self.window {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((wall, tdma));
+    }
+
+    /// Drift in parts-per-million over the sample window, 0.0 until two samples are available
+    pub fn drift_ppm(&self) -> f64 {
+        match (self.samples.front(), self.samples.back()) {
+            (Some(oldest), Some(newest)) => Self::ppm_between(*oldest, *newest),
+            _ => 0.0,
+        }
+    }
+
+    /// Drift in parts-per-million since the first sample
+    pub fn total_drift_ppm(&self) -> f64 {
+        match (self.reference, self.samples.back()) {
+            (Some(reference), Some(newest)) => Self::ppm_between(reference, *newest),
+            _ => 0.0,
+        }
+    }
+
+    /// Returns true if the absolute drift over the sample window exceeds the threshold
+    pub fn is_drifted(&self, threshold_ppm: f64) -> bool {
+        self.drift_ppm().abs() > threshold_ppm
+    }
+
+    /// Timeslots elapsed from `from` to `to`, counting whole multiframes so that spans of any length
+    /// within a hyperframe number cycle are measured correctly
+    fn tdma_slots_between(from: TdmaTime, to: TdmaTime) -> i64 {
+        let multiframes = (to.absolute_multiframe() + MULTIFRAME_WRAP - from.absolute_multiframe()) % MULTIFRAME_WRAP;
+        multiframes as i64 * crate::multiframes!(1) as i64 + to.slot_in_multiframe() as i64 - from.slot_in_multiframe() as i64
+    }
+
+    fn ppm_between(from: (Instant, TdmaTime), to: (Instant, TdmaTime)) -> f64 {
+        let wall_secs = to.0.saturating_duration_since(from.0).as_secs_f64();
+        if wall_secs <= 0.0 {
+            return 0.0;
+        }
+        let tdma_secs = Self::tdma_slots_between(from.1, to.1) as f64 * TIMESLOT_DURATION_SECS;
+        (tdma_secs - wall_secs) / wall_secs * 1e6
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    /// Feed `count` multiframes to the monitor, with the wall clock running at the given ppm offset
+    fn feed(monitor: &mut ClockDriftMonitor, count: i32, wall_ppm: f64) {
+        let start = Instant::now();
+        let tdma_start = TdmaTime::default();
+        let multiframe_secs = crate::multiframes!(1) as f64 * TIMESLOT_DURATION_SECS;
+        for i in 0..count {
+            let wall = start + Duration::from_secs_f64(i as f64 * multiframe_secs * (1.0 + wall_ppm / 1e6));
+            monitor.update(wall, tdma_start.add_timeslots(crate::multiframes!(i)));
+        }
+    }
+
+    #[test]
+    fn test_no_drift() {
+        let mut monitor = ClockDriftMonitor::default();
+        assert_eq!(monitor.drift_ppm(), 0.0);
+        feed(&mut monitor, 100, 0.0);
+        assert!(monitor.drift_ppm().abs() < 1.0, "drift {}", monitor.drift_ppm());
+        assert!(!monitor.is_drifted(100.0));
+    }
+
+    #[test]
+    fn test_detects_200_ppm_drift() {
+        let mut monitor = ClockDriftMonitor::default();
+        // Wall clock runs slow, so the TDMA clock is ahead
+        feed(&mut monitor, 100, -200.0);
+        let drift = monitor.drift_ppm();
+        assert!((drift - 200.0).abs() < 1.0, "drift {}", drift);
+        assert!(monitor.is_drifted(100.0));
+        assert!(!monitor.is_drifted(250.0));
+        assert!((monitor.total_drift_ppm() - 200.0).abs() < 1.0);
+    }
+}
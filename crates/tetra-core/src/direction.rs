@@ -1,4 +1,6 @@
-#[derive(Debug, Clone, PartialEq, Copy)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Copy, Serialize, Deserialize)]
 pub enum Direction {
     None,
     /// Uplink
@@ -19,3 +21,25 @@ impl Direction {
         matches!(self, Direction::Dl | Direction::Both)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Direction; 4] = [Direction::None, Direction::Ul, Direction::Dl, Direction::Both];
+
+    #[test]
+    fn test_includes_all_variants() {
+        for dir in ALL {
+            // Exhaustive on purpose: a new variant must be classified here before this compiles
+            let (dl, ul) = match dir {
+                Direction::None => (false, false),
+                Direction::Ul => (false, true),
+                Direction::Dl => (true, false),
+                Direction::Both => (true, true),
+            };
+            assert_eq!(dir.includes_dl(), dl, "{:?}", dir);
+            assert_eq!(dir.includes_ul(), ul, "{:?}", dir);
+        }
+    }
+}
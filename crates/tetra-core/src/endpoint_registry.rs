@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use crate::tetra_entities::TetraEntity;
+use crate::{Direction, EndpointId, TdmaTime};
+
+/// The MAC resource behind an endpoint identifier
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndpointInfo {
+    /// Timeslot, 1-4
+    pub ts: u8,
+    pub direction: Direction,
+    /// Entity that requested the resource
+    pub owner: TetraEntity,
+    pub allocated_at: TdmaTime,
+}
+
+/// Makes the correspondence between endpoint identifiers and MAC timeslot allocations explicit.
+/// Entries are registered by the MAC when it sets up a resource and removed when it is released.
+#[derive(Debug, Clone, Default)]
+pub struct EndpointRegistry {
+    entries: HashMap<EndpointId, EndpointInfo>,
+}
+
+impl EndpointRegistry {
+    /// Register an endpoint, returning the previous entry for the id, if any
+    pub fn register(&mut self, id: EndpointId, info: EndpointInfo) -> Option<EndpointInfo> {
+        self.entries.insert(id, info)
+    }
+
+    pub fn deregister(&mut self, id: EndpointId) -> Option<EndpointInfo> {
+        self.entries.remove(&id)
+    }
+
+    pub fn resolve(&self, id: EndpointId) -> Option<&EndpointInfo> {
+        self.entries.get(&id)
+    }
+
+    /// All endpoints using the given timeslot, sorted by id
+    pub fn reverse_lookup(&self, ts: u8) -> Vec<EndpointId> {
+        let mut ids: Vec<EndpointId> = self.entries.iter().filter(|(_, info)| info.ts == ts).map(|(id, _)| *id).collect();
+        ids.sort_unstable();
+        ids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(ts: u8, direction: Direction) -> EndpointInfo {
+        EndpointInfo {
+            ts,
+            direction,
+            owner: TetraEntity::Cmce,
+            allocated_at: TdmaTime::default(),
+        }
+    }
+
+    #[test]
+    fn test_register_resolve() {
+        let mut reg = EndpointRegistry::default();
+        assert!(reg.resolve(2).is_none());
+        assert_eq!(reg.register(2, info(2, Direction::Dl)), None);
+        assert_eq!(reg.resolve(2), Some(&info(2, Direction::Dl)));
+
+        // Re-registering replaces the entry and returns the old one
+        assert_eq!(reg.register(2, info(2, Direction::Both)), Some(info(2, Direction::Dl)));
+        assert_eq!(reg.resolve(2).unwrap().direction, Direction::Both);
+    }
+
+    #[test]
+    fn test_deregister() {
+        let mut reg = EndpointRegistry::default();
+        reg.register(3, info(3, Direction::Ul));
+        assert_eq!(reg.deregister(3), Some(info(3, Direction::Ul)));
+        assert!(reg.resolve(3).is_none());
+        assert_eq!(reg.deregister(3), None);
+    }
+
+    #[test]
+    fn test_reverse_lookup() {
+        let mut reg = EndpointRegistry::default();
+        reg.register(7, info(2, Direction::Dl));
+        reg.register(5, info(2, Direction::Ul));
+        reg.register(3, info(3, Direction::Both));
+        assert_eq!(reg.reverse_lookup(2), vec![5, 7]);
+        assert_eq!(reg.reverse_lookup(3), vec![3]);
+        assert!(reg.reverse_lookup(4).is_empty());
+
+        reg.deregister(5);
+        assert_eq!(reg.reverse_lookup(2), vec![7]);
+    }
+}
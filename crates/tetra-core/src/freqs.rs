@@ -1,5 +1,7 @@
 use serde::Deserialize;
 
+use crate::ranges::TetraRange;
+
 /// ETSI TS 100 392-15 V1.5.1 (2011-02), clause 6: Duplex spacing
 const TETRA_DUPLEX_SPACING: [[Option<u32>; 16]; 8] = [
     [
@@ -118,6 +120,57 @@ const TETRA_DUPLEX_SPACING: [[Option<u32>; 16]; 8] = [
     ],
 ];
 
+/// Standardized paired band plans, as (lowest DL Hz, highest DL Hz, duplex spacing index).
+/// The duplex spacing follows from TETRA_DUPLEX_SPACING for the band of the DL frequency, UL is below DL.
+const TETRA_PAIRED_BAND_PLANS: [(u64, u64, u8); 5] = [
+    // 380-390 / 390-400 MHz, 10 MHz duplex
+    (390_000_000, 400_000_000, 0),
+    // 410-420 / 420-430 MHz, 10 MHz duplex
+    (420_000_000, 430_000_000, 0),
+    // 450-460 / 460-470 MHz, 10 MHz duplex
+    (460_000_000, 470_000_000, 0),
+    // 806-825 / 851-870 MHz, 45 MHz duplex
+    (851_000_000, 870_000_000, 1),
+    // 870-876 / 915-921 MHz, 45 MHz duplex
+    (915_000_000, 921_000_000, 1),
+];
+
+/// Lowest and highest frequency band with a band plan, in 100 MHz increments (100 MHz to 1 GHz)
+const MIN_FREQ_BAND: u8 = 1;
+const MAX_FREQ_BAND: u8 = 9;
+/// Carrier numbers within a band, spaced 25 kHz apart
+const CARRIER_RANGE: TetraRange<u16> = TetraRange { start: 0, end: 3999 };
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FreqError {
+    /// Band has no TETRA band plan
+    InvalidBand(u8),
+    /// Carrier number out of range 0-3999
+    InvalidCarrier(u16),
+    /// Frequency offset index out of range 0-3
+    InvalidFreqOffset(u8),
+    /// Duplex spacing index out of range 0-7
+    InvalidDuplexSpacing(u8),
+    /// Duplex spacing index is reserved for this band
+    NoDuplexSpacing { band: u8, duplex_spacing_id: u8 },
+}
+
+impl std::fmt::Display for FreqError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FreqError::InvalidBand(band) => write!(f, "invalid frequency band {}", band),
+            FreqError::InvalidCarrier(carrier) => write!(f, "invalid carrier number {}", carrier),
+            FreqError::InvalidFreqOffset(id) => write!(f, "invalid frequency offset index {}", id),
+            FreqError::InvalidDuplexSpacing(id) => write!(f, "invalid duplex spacing index {}", id),
+            FreqError::NoDuplexSpacing { band, duplex_spacing_id } => {
+                write!(f, "no duplex spacing for band {}, duplex index {}", band, duplex_spacing_id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FreqError {}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct FreqInfo {
     /// Frequency band in 100MHz increments
@@ -156,6 +209,60 @@ impl FreqInfo {
         }
     }
 
+    /// Downlink frequency in Hz of a carrier, as signalled in SYSINFO:
+    /// band * 100 MHz + carrier * 25 kHz + frequency offset.
+    /// ETSI EN 300 392-2 clause 21.4.4.1 and ETSI TS 100 392-15 clause 6.
+    pub fn dl_frequency_hz(band: u8, carrier: u16, freq_offset_id: u8) -> Result<f64, FreqError> {
+        Self::check_band(band)?;
+        Self::check_carrier(carrier)?;
+        let offset_hz = Self::freq_offset_id_to_hz(freq_offset_id).ok_or(FreqError::InvalidFreqOffset(freq_offset_id))?;
+        Ok(Self::carrier_base_hz(band, carrier) as f64 + offset_hz as f64)
+    }
+
+    /// Uplink frequency in Hz for a downlink frequency, using the standardized duplex spacing of the band.
+    /// With reverse operation the uplink is above the downlink.
+    pub fn ul_frequency_hz(band: u8, duplex_spacing_id: u8, reverse_operation: bool, dl_freq: f64) -> Result<f64, FreqError> {
+        Self::check_band(band)?;
+        if duplex_spacing_id as usize >= TETRA_DUPLEX_SPACING.len() {
+            return Err(FreqError::InvalidDuplexSpacing(duplex_spacing_id));
+        }
+        let spacing =
+            Self::get_default_duplex_spacing(band, duplex_spacing_id).ok_or(FreqError::NoDuplexSpacing { band, duplex_spacing_id })? as f64;
+        Ok(if reverse_operation { dl_freq + spacing } else { dl_freq - spacing })
+    }
+
+    /// Uplink frequency in Hz for a downlink frequency within one of the standardized paired band plans.
+    /// Returns None if the frequency is outside all of them, in which case the duplex spacing must be configured.
+    pub fn ul_from_dl_freq(dl_hz: u64) -> Option<u64> {
+        let (_, _, duplex_spacing_id) = TETRA_PAIRED_BAND_PLANS
+            .iter()
+            .find(|(start, end, _)| (*start..=*end).contains(&dl_hz))?;
+        let band = (dl_hz / 100_000_000) as u8;
+        let spacing = Self::get_default_duplex_spacing(band, *duplex_spacing_id)?;
+        Some(dl_hz - spacing as u64)
+    }
+
+    fn check_band(band: u8) -> Result<(), FreqError> {
+        if (MIN_FREQ_BAND..=MAX_FREQ_BAND).contains(&band) {
+            Ok(())
+        } else {
+            Err(FreqError::InvalidBand(band))
+        }
+    }
+
+    fn check_carrier(carrier: u16) -> Result<(), FreqError> {
+        if CARRIER_RANGE.contains_point(carrier) {
+            Ok(())
+        } else {
+            Err(FreqError::InvalidCarrier(carrier))
+        }
+    }
+
+    /// Frequency of the 25 kHz aligned carrier, before the frequency offset
+    fn carrier_base_hz(band: u8, carrier: u16) -> u32 {
+        100_000_000 * band as u32 + carrier as u32 * 25_000
+    }
+
     /// Construct FreqInfo from band, carrier, frequency offset, duplex spacing index and reverse operation flag.
     /// Optionally accepts a custom duplex spacing value in Hz, if a duplex spacing table is used by the radios.
     pub fn from_components(
@@ -166,18 +273,24 @@ impl FreqInfo {
         duplex_index: u8,
         custom_duplex_spacing: Option<u32>,
     ) -> Result<Self, String> {
-        assert!(band <= 8, "Invalid frequency band {}", band);
-        assert!(carrier < 4000, "Invalid carrier number {}", carrier);
-        assert!(
-            freq_offset_val == 0 || freq_offset_val == 6250 || freq_offset_val == -6250 || freq_offset_val == 12500,
-            "Invalid frequency offset {}",
-            freq_offset_val
-        );
+        Self::check_band(band).map_err(|e| e.to_string())?;
+        Self::check_carrier(carrier).map_err(|e| e.to_string())?;
+        if Self::freq_offset_hz_to_id(freq_offset_val).is_none() {
+            return Err(format!("invalid frequency offset {}", freq_offset_val));
+        }
+        if duplex_index as usize >= TETRA_DUPLEX_SPACING.len() {
+            return Err(FreqError::InvalidDuplexSpacing(duplex_index).to_string());
+        }
         let duplex_spacing_val = if let Some(cds) = custom_duplex_spacing {
             cds
         } else {
-            Self::get_default_duplex_spacing(band, duplex_index)
-                .ok_or_else(|| format!("Invalid duplex spacing for band {}, duplex index {}", band, duplex_index))?
+            Self::get_default_duplex_spacing(band, duplex_index).ok_or_else(|| {
+                FreqError::NoDuplexSpacing {
+                    band,
+                    duplex_spacing_id: duplex_index,
+                }
+                .to_string()
+            })?
         };
 
         Ok(Self {
@@ -200,11 +313,7 @@ impl FreqInfo {
 
     /// Get the downlink and uplink frequencies for this instance
     pub fn get_freqs(&self) -> (u32, u32) {
-        // Compute dlfreq
-        let mut dl_freq = 100000000 * self.band as i32;
-        dl_freq += self.carrier as i32 * 25000;
-        dl_freq += self.freq_offset_hz as i32;
-        let dl_freq = dl_freq as u32;
+        let dl_freq = (Self::carrier_base_hz(self.band, self.carrier) as i64 + self.freq_offset_hz as i64) as u32;
 
         // Derive ulfreq
         let ul_freq = if !self.reverse_operation {
@@ -243,4 +352,85 @@ mod tests {
         assert_eq!(dlfreq - duplex_spacing, ulfreq);
         assert!(!f1.reverse_operation);
     }
+
+    /// (band, carrier, freq offset id, duplex spacing id, reverse operation, DL Hz, UL Hz)
+    const BAND_PLAN_CASES: [(u8, u16, u8, u8, bool, f64, f64); 8] = [
+        // 380-400 MHz public safety, 10 MHz duplex
+        (3, 3601, 0, 0, false, 390_025_000.0, 380_025_000.0),
+        (3, 3602, 2, 0, false, 390_043_750.0, 380_043_750.0),
+        // 410-430 MHz, 10 MHz duplex
+        (4, 601, 1, 0, false, 415_031_250.0, 405_031_250.0),
+        (4, 1000, 0, 0, true, 425_000_000.0, 435_000_000.0),
+        // 450-470 MHz, 10 MHz duplex
+        (4, 2400, 3, 0, false, 460_012_500.0, 450_012_500.0),
+        // 806-825 / 851-870 MHz, 45 MHz duplex
+        (8, 2040, 3, 1, false, 851_012_500.0, 806_012_500.0),
+        // 870-876 / 915-921 MHz, 45 MHz duplex
+        (9, 600, 3, 1, false, 915_012_500.0, 870_012_500.0),
+        (9, 839, 0, 1, false, 920_975_000.0, 875_975_000.0),
+    ];
+
+    #[test]
+    fn test_band_plans() {
+        for (band, carrier, offset_id, duplex_id, reverse, dl_expected, ul_expected) in BAND_PLAN_CASES {
+            let dl = FreqInfo::dl_frequency_hz(band, carrier, offset_id).unwrap();
+            assert_eq!(dl, dl_expected, "DL for band {} carrier {}", band, carrier);
+            let ul = FreqInfo::ul_frequency_hz(band, duplex_id, reverse, dl).unwrap();
+            assert_eq!(ul, ul_expected, "UL for band {} carrier {}", band, carrier);
+
+            // FreqInfo as built from the cell config derives the same frequencies
+            let offset_hz = FreqInfo::freq_offset_id_to_hz(offset_id).unwrap();
+            let info = FreqInfo::from_components(band, carrier, offset_hz, reverse, duplex_id, None).unwrap();
+            assert_eq!(info.get_freqs(), (dl_expected as u32, ul_expected as u32));
+        }
+    }
+
+    #[test]
+    fn test_ul_from_dl_freq() {
+        for (dl, ul) in [
+            (390_000_000, 380_000_000),
+            (392_512_500, 382_512_500),
+            (400_000_000, 390_000_000),
+            (425_006_250, 415_006_250),
+            (460_012_500, 450_012_500),
+            (469_975_000, 459_975_000),
+            (851_012_500, 806_012_500),
+            (870_000_000, 825_000_000),
+            (915_012_500, 870_012_500),
+            (920_975_000, 875_975_000),
+        ] {
+            assert_eq!(FreqInfo::ul_from_dl_freq(dl), Some(ul), "UL for DL {}", dl);
+        }
+
+        // Agrees with the band plan cases that lie within a paired band, except for reverse operation
+        for (_, _, _, _, reverse, dl, ul) in BAND_PLAN_CASES {
+            if let Some(derived) = FreqInfo::ul_from_dl_freq(dl as u64)
+                && !reverse
+            {
+                assert_eq!(derived, ul as u64);
+            }
+        }
+
+        // UL halves of the plans and frequencies outside any plan
+        for dl in [380_025_000, 415_031_250, 450_000_000, 806_012_500, 875_975_000, 1_000_000_000, 0] {
+            assert_eq!(FreqInfo::ul_from_dl_freq(dl), None, "DL {}", dl);
+        }
+    }
+
+    #[test]
+    fn test_invalid_band_plan_params() {
+        assert_eq!(FreqInfo::dl_frequency_hz(0, 100, 0), Err(FreqError::InvalidBand(0)));
+        assert_eq!(FreqInfo::dl_frequency_hz(10, 100, 0), Err(FreqError::InvalidBand(10)));
+        assert_eq!(FreqInfo::dl_frequency_hz(4, 4000, 0), Err(FreqError::InvalidCarrier(4000)));
+        assert_eq!(FreqInfo::dl_frequency_hz(4, 100, 4), Err(FreqError::InvalidFreqOffset(4)));
+        assert_eq!(FreqInfo::ul_frequency_hz(4, 8, false, 4e8), Err(FreqError::InvalidDuplexSpacing(8)));
+        assert_eq!(
+            FreqInfo::ul_frequency_hz(5, 1, false, 5e8),
+            Err(FreqError::NoDuplexSpacing {
+                band: 5,
+                duplex_spacing_id: 1
+            })
+        );
+        assert!(FreqInfo::from_components(9, 600, 0, false, 7, None).is_err());
+    }
 }
@@ -9,9 +9,13 @@ pub const STACK_VERSION: &str = const_format::formatcp!("{}-{}", env!("CARGO_PKG
 
 pub mod address;
 pub mod bitbuffer;
+pub mod clock_drift;
 pub mod debug;
 pub mod direction;
+pub mod endpoint_registry;
 pub mod freqs;
+pub mod link_id_alloc;
+pub mod network_address;
 pub mod pdu_parse_error;
 pub mod phy_types;
 pub mod ranges;
@@ -25,8 +29,11 @@ pub mod typed_pdu_fields;
 
 // Re-export commonly used items
 pub use address::*;
-pub use bitbuffer::BitBuffer;
+pub use bitbuffer::{BitBuffer, BitIter};
 pub use direction::Direction;
+pub use endpoint_registry::{EndpointInfo, EndpointRegistry};
+pub use link_id_alloc::LinkIdAllocator;
+pub use network_address::{NetworkAddress, NetworkAddressError};
 pub use pdu_parse_error::PduParseErr;
 pub use phy_types::*;
 pub use sap_fields::*;
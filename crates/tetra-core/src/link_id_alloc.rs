@@ -0,0 +1,137 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{LinkId, TdmaTime};
+
+/// Lowest and highest link identifier handed out, 0 is left for links without an identifier
+pub const LINK_ID_MIN: LinkId = 1;
+pub const LINK_ID_MAX: LinkId = 0xFFFF;
+
+/// Central allocator for link identifiers, so entities creating links do not hand out colliding ids.
+/// Free ids are kept in a sorted free list and the lowest one is handed out first, so released ids are reused.
+/// Every active id carries the time it was last used, ids that are never released are recovered by `gc_stale`.
+#[derive(Debug, Clone)]
+pub struct LinkIdAllocator {
+    free: BTreeSet<LinkId>,
+    /// Active id -> time of last use
+    active: HashMap<LinkId, TdmaTime>,
+}
+
+impl Default for LinkIdAllocator {
+    fn default() -> Self {
+        Self {
+            free: (LINK_ID_MIN..=LINK_ID_MAX).collect(),
+            active: HashMap::new(),
+        }
+    }
+}
+
+impl LinkIdAllocator {
+    /// Take the lowest free id. Returns None if all ids are in use.
+    pub fn allocate(&mut self, now: TdmaTime) -> Option<LinkId> {
+        let id = self.free.pop_first()?;
+        self.active.insert(id, now);
+        Some(id)
+    }
+
+    /// Return an id to the free list. Returns false if the id was not allocated.
+    pub fn release(&mut self, id: LinkId) -> bool {
+        if self.active.remove(&id).is_none() {
+            return false;
+        }
+        self.free.insert(id);
+        true
+    }
+
+    pub fn is_active(&self, id: LinkId) -> bool {
+        self.active.contains_key(&id)
+    }
+
+    /// Mark an active id as used at the given time, postponing its garbage collection
+    pub fn touch(&mut self, id: LinkId, now: TdmaTime) {
+        if let Some(last_used) = self.active.get_mut(&id) {
+            *last_used = now;
+        }
+    }
+
+    pub fn num_active(&self) -> usize {
+        self.active.len()
+    }
+
+    /// Release all ids not used for more than `max_age_slots` timeslots. Returns the released ids, sorted.
+    pub fn gc_stale(&mut self, current_time: TdmaTime, max_age_slots: u64) -> Vec<LinkId> {
+        let mut stale: Vec<LinkId> = self
+            .active
+            .iter()
+            .filter(|(_, last_used)| last_used.age(current_time).max(0) as u64 > max_age_slots)
+            .map(|(id, _)| *id)
+            .collect();
+        stale.sort_unstable();
+        for id in &stale {
+            self.release(*id);
+        }
+        stale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocate_unique() {
+        let mut alloc = LinkIdAllocator::default();
+        let now = TdmaTime::default();
+        let a = alloc.allocate(now).unwrap();
+        let b = alloc.allocate(now).unwrap();
+        assert_eq!((a, b), (LINK_ID_MIN, LINK_ID_MIN + 1));
+        assert!(alloc.is_active(a) && alloc.is_active(b));
+        assert!(!alloc.is_active(0));
+        assert_eq!(alloc.num_active(), 2);
+    }
+
+    #[test]
+    fn test_release_and_reuse() {
+        let mut alloc = LinkIdAllocator::default();
+        let now = TdmaTime::default();
+        let a = alloc.allocate(now).unwrap();
+        let b = alloc.allocate(now).unwrap();
+        assert!(alloc.release(a));
+        assert!(!alloc.is_active(a));
+        assert!(!alloc.release(a), "double release must be rejected");
+
+        // The released id is handed out again before any fresh one
+        assert_eq!(alloc.allocate(now), Some(a));
+        assert_eq!(alloc.allocate(now), Some(b + 1));
+    }
+
+    #[test]
+    fn test_exhaustion() {
+        let mut alloc = LinkIdAllocator::default();
+        let now = TdmaTime::default();
+        for _ in LINK_ID_MIN..=LINK_ID_MAX {
+            assert!(alloc.allocate(now).is_some());
+        }
+        assert_eq!(alloc.allocate(now), None);
+        alloc.release(1234);
+        assert_eq!(alloc.allocate(now), Some(1234));
+    }
+
+    #[test]
+    fn test_gc_stale() {
+        let mut alloc = LinkIdAllocator::default();
+        let start = TdmaTime::default();
+        let stale = alloc.allocate(start).unwrap();
+        let used = alloc.allocate(start).unwrap();
+        let fresh = alloc.allocate(start.add_timeslots(900)).unwrap();
+        alloc.touch(used, start.add_timeslots(800));
+
+        let now = start.add_timeslots(1000);
+        assert_eq!(alloc.gc_stale(now, 500), vec![stale]);
+        assert!(!alloc.is_active(stale));
+        assert!(alloc.is_active(used) && alloc.is_active(fresh));
+
+        // Nothing left to collect within the age limit
+        assert!(alloc.gc_stale(now, 500).is_empty());
+        assert_eq!(alloc.allocate(now), Some(stale));
+    }
+}
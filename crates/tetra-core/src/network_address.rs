@@ -0,0 +1,148 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Network address abstraction
+///
+/// Textual form is URI-style: `tcp://host:port`, `udp://host:port` or `scheme://address`
+/// for any other scheme. IPv6 hosts are written in brackets, e.g. `tcp://[::1]:5555`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NetworkAddress {
+    /// TCP endpoint
+    Tcp { host: String, port: u16 },
+    /// UDP endpoint
+    Udp { host: String, port: u16 },
+    /// Custom addressing scheme
+    Custom { scheme: String, address: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkAddressError {
+    /// No `://` separator, or an empty scheme
+    MissingScheme,
+    /// Nothing after the `://` separator
+    EmptyAddress,
+    /// A tcp/udp address without a `:port` suffix
+    MissingPort,
+    /// The port is not a number from 0 to 65535
+    InvalidPort(String),
+}
+
+impl fmt::Display for NetworkAddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkAddressError::MissingScheme => write!(f, "missing scheme, expected scheme://address"),
+            NetworkAddressError::EmptyAddress => write!(f, "empty address"),
+            NetworkAddressError::MissingPort => write!(f, "missing port, expected host:port"),
+            NetworkAddressError::InvalidPort(port) => write!(f, "invalid port: {}", port),
+        }
+    }
+}
+
+impl std::error::Error for NetworkAddressError {}
+
+fn fmt_host_port(f: &mut fmt::Formatter<'_>, scheme: &str, host: &str, port: u16) -> fmt::Result {
+    if host.contains(':') {
+        write!(f, "{}://[{}]:{}", scheme, host, port)
+    } else {
+        write!(f, "{}://{}:{}", scheme, host, port)
+    }
+}
+
+fn parse_host_port(address: &str) -> Result<(String, u16), NetworkAddressError> {
+    let (host, port) = address.rsplit_once(':').ok_or(NetworkAddressError::MissingPort)?;
+    let host = host.strip_prefix('[').and_then(|h| h.strip_suffix(']')).unwrap_or(host);
+    if host.is_empty() {
+        return Err(NetworkAddressError::EmptyAddress);
+    }
+    let port = port.parse().map_err(|_| NetworkAddressError::InvalidPort(port.to_string()))?;
+    Ok((host.to_string(), port))
+}
+
+impl fmt::Display for NetworkAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkAddress::Tcp { host, port } => fmt_host_port(f, "tcp", host, *port),
+            NetworkAddress::Udp { host, port } => fmt_host_port(f, "udp", host, *port),
+            NetworkAddress::Custom { scheme, address } => write!(f, "{}://{}", scheme, address),
+        }
+    }
+}
+
+impl FromStr for NetworkAddress {
+    type Err = NetworkAddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (scheme, address) = s.split_once("://").ok_or(NetworkAddressError::MissingScheme)?;
+        if scheme.is_empty() {
+            return Err(NetworkAddressError::MissingScheme);
+        }
+        if address.is_empty() {
+            return Err(NetworkAddressError::EmptyAddress);
+        }
+        match scheme {
+            "tcp" => parse_host_port(address).map(|(host, port)| NetworkAddress::Tcp { host, port }),
+            "udp" => parse_host_port(address).map(|(host, port)| NetworkAddress::Udp { host, port }),
+            _ => Ok(NetworkAddress::Custom {
+                scheme: scheme.to_string(),
+                address: address.to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let tcp = NetworkAddress::Tcp {
+            host: "192.168.1.1".to_string(),
+            port: 5555,
+        };
+        let udp = NetworkAddress::Udp {
+            host: "::1".to_string(),
+            port: 9000,
+        };
+        let custom = NetworkAddress::Custom {
+            scheme: "mock".to_string(),
+            address: "peer-1".to_string(),
+        };
+        assert_eq!(tcp.to_string(), "tcp://192.168.1.1:5555");
+        assert_eq!(udp.to_string(), "udp://[::1]:9000");
+        assert_eq!(custom.to_string(), "mock://peer-1");
+
+        for addr in [tcp, udp, custom] {
+            assert_eq!(addr.to_string().parse::<NetworkAddress>(), Ok(addr));
+        }
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(
+            "tcp://localhost:80".parse(),
+            Ok(NetworkAddress::Tcp {
+                host: "localhost".to_string(),
+                port: 80,
+            })
+        );
+        assert_eq!(
+            "wss://example.org:443/path".parse(),
+            Ok(NetworkAddress::Custom {
+                scheme: "wss".to_string(),
+                address: "example.org:443/path".to_string(),
+            })
+        );
+        assert_eq!("localhost:80".parse::<NetworkAddress>(), Err(NetworkAddressError::MissingScheme));
+        assert_eq!("://localhost:80".parse::<NetworkAddress>(), Err(NetworkAddressError::MissingScheme));
+        assert_eq!("tcp://".parse::<NetworkAddress>(), Err(NetworkAddressError::EmptyAddress));
+        assert_eq!("tcp://:80".parse::<NetworkAddress>(), Err(NetworkAddressError::EmptyAddress));
+        assert_eq!("udp://localhost".parse::<NetworkAddress>(), Err(NetworkAddressError::MissingPort));
+        assert_eq!(
+            "tcp://localhost:70000".parse::<NetworkAddress>(),
+            Err(NetworkAddressError::InvalidPort("70000".to_string()))
+        );
+    }
+}
@@ -1,16 +1,73 @@
 #[derive(Debug, PartialEq, Eq)]
 pub enum PduParseErr {
-    InvalidPduType { expected: u64, found: u64 },
-    BufferEnded { field: Option<&'static str> },
+    InvalidPduType {
+        expected: u64,
+        found: u64,
+    },
+    /// Not enough bits left to read `bit_length` bits at `bit_offset`, relative to the window start of the buffer
+    BufferEnded {
+        field: Option<&'static str>,
+        bit_offset: usize,
+        bit_length: usize,
+    },
     InvalidTrailingMbitValue,
-    InvalidElemId { found: u64 },
-    FieldNotPresent { field: Option<&'static str> },
-    InvalidValue { field: &'static str, value: u64 },
-    InconsistentLength { expected: usize, found: usize },
-    Inconsistency { field: &'static str, reason: &'static str },
-    NotImplemented { field: Option<&'static str> },
+    InvalidElemId {
+        found: u64,
+    },
+    FieldNotPresent {
+        field: Option<&'static str>,
+    },
+    InvalidValue {
+        field: &'static str,
+        value: u64,
+    },
+    /// Value has no corresponding variant in the enum for this field
+    UnsupportedValue {
+        field: &'static str,
+        value: u64,
+    },
+    InconsistentLength {
+        expected: usize,
+        found: usize,
+    },
+    Inconsistency {
+        field: &'static str,
+        reason: &'static str,
+    },
+    NotImplemented {
+        field: Option<&'static str>,
+    },
 }
 
+impl core::fmt::Display for PduParseErr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PduParseErr::InvalidPduType { expected, found } => write!(f, "Invalid PDU type {}, expected {}", found, expected),
+            PduParseErr::BufferEnded {
+                field,
+                bit_offset,
+                bit_length,
+            } => write!(
+                f,
+                "Failed at field '{}' offset={} len={}: buffer ended",
+                field.unwrap_or("unknown"),
+                bit_offset,
+                bit_length
+            ),
+            PduParseErr::InvalidTrailingMbitValue => write!(f, "Invalid trailing m-bit value"),
+            PduParseErr::InvalidElemId { found } => write!(f, "Invalid element identifier {}", found),
+            PduParseErr::FieldNotPresent { field } => write!(f, "Field '{}' not present", field.unwrap_or("unknown")),
+            PduParseErr::InvalidValue { field, value } => write!(f, "Invalid value {} for field '{}'", value, field),
+            PduParseErr::UnsupportedValue { field, value } => write!(f, "Unsupported value {} for field '{}'", value, field),
+            PduParseErr::InconsistentLength { expected, found } => write!(f, "Inconsistent length {}, expected {}", found, expected),
+            PduParseErr::Inconsistency { field, reason } => write!(f, "Inconsistency in field '{}': {}", field, reason),
+            PduParseErr::NotImplemented { field } => write!(f, "Field '{}' not implemented", field.unwrap_or("unknown")),
+        }
+    }
+}
+
+impl std::error::Error for PduParseErr {}
+
 /// Checks whether a PDU type value matches the expected value. If not, returns PduParseErr::InvalidPduType
 #[macro_export]
 macro_rules! expect_pdu_type {
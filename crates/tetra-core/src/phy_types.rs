@@ -3,8 +3,10 @@
 //! These types originate from the PHY layer but are referenced by LMAC, UMAC,
 //! and SAP primitives, so they live in tetra-core to avoid circular dependencies.
 
+use serde::{Deserialize, Serialize};
+
 /// Identifies which block(s) within a timeslot
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum PhyBlockNum {
     /// Both half-slots combined (full slot)
     Both,
@@ -17,7 +19,7 @@ pub enum PhyBlockNum {
 }
 
 /// Physical block types
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum PhyBlockType {
     BBK,
     /// TODO FIXME Merge SB1 and SB2 into SDB
@@ -30,7 +32,7 @@ pub enum PhyBlockType {
 }
 
 /// Burst types (Clause 9.4.4.1)
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum BurstType {
     /// Control Uplink Burst
     CUB,
@@ -43,7 +45,7 @@ pub enum BurstType {
 }
 
 /// Training sequences
-#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[derive(Debug, Copy, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub enum TrainingSequence {
     /// 22 n bits
     NormalTrainSeq1 = 1,
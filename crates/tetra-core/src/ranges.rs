@@ -63,6 +63,138 @@ impl SortedDisjointSsiRanges {
     }
 }
 
+/// Values that can bound a TetraRange. `next` and `prev` return None at the ends of the type's domain,
+/// they are needed to detect adjacent ranges and to split a range around a removed part.
+pub trait RangeValue: Ord + Copy {
+    fn next(self) -> Option<Self>;
+    fn prev(self) -> Option<Self>;
+}
+
+macro_rules! impl_range_value {
+    ($($t:ty),*) => {
+        $(impl RangeValue for $t {
+            fn next(self) -> Option<Self> {
+                self.checked_add(1)
+            }
+            fn prev(self) -> Option<Self> {
+                self.checked_sub(1)
+            }
+        })*
+    };
+}
+impl_range_value!(u8, u16, u32, u64);
+
+/// An inclusive range of values, e.g. timeslots 2-4 or carriers 0-3999
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TetraRange<T> {
+    /// Inclusive start of the range
+    pub start: T,
+    /// Inclusive end of the range
+    pub end: T,
+}
+
+impl<T: RangeValue> TetraRange<T> {
+    pub fn new(start: T, end: T) -> Self {
+        assert!(start <= end, "Invalid range: start after end");
+        Self { start, end }
+    }
+
+    pub fn contains_point(&self, point: T) -> bool {
+        self.start <= point && point <= self.end
+    }
+
+    pub fn overlaps(&self, other: &TetraRange<T>) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// True if the ranges overlap or one ends right before the other starts
+    fn touches(&self, other: &TetraRange<T>) -> bool {
+        self.overlaps(other) || self.end.next() == Some(other.start) || other.end.next() == Some(self.start)
+    }
+
+    /// The range covering both, or None if there is a gap between them
+    pub fn union(&self, other: &TetraRange<T>) -> Option<TetraRange<T>> {
+        self.touches(other).then(|| TetraRange {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        })
+    }
+
+    /// The values in both ranges, or None if they do not overlap
+    pub fn intersection(&self, other: &TetraRange<T>) -> Option<TetraRange<T>> {
+        self.overlaps(other).then(|| TetraRange {
+            start: self.start.max(other.start),
+            end: self.end.min(other.end),
+        })
+    }
+}
+
+/// A set of values stored as sorted, disjoint ranges. Adjacent ranges are merged, so every value
+/// has exactly one representation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeSet<T> {
+    ranges: Vec<TetraRange<T>>,
+}
+
+impl<T> Default for RangeSet<T> {
+    fn default() -> Self {
+        Self { ranges: Vec::new() }
+    }
+}
+
+impl<T: RangeValue> RangeSet<T> {
+    pub fn as_slice(&self) -> &[TetraRange<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Add all values of the range, merging with any range it overlaps or touches
+    pub fn insert(&mut self, range: TetraRange<T>) {
+        // First range that ends at or after the one before range.start, i.e. the first that may touch it
+        let first = self.ranges.partition_point(|r| r.end.next().is_some_and(|next| next < range.start));
+        let mut merged = range;
+        let mut last = first;
+        while last < self.ranges.len() {
+            let Some(union) = merged.union(&self.ranges[last]) else {
+                break;
+            };
+            merged = union;
+            last += 1;
+        }
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    /// Remove all values of the range, splitting ranges that extend past it
+    pub fn remove(&mut self, range: TetraRange<T>) {
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = first + self.ranges[first..].iter().take_while(|r| r.start <= range.end).count();
+        if first == last {
+            return;
+        }
+
+        let mut remainder = Vec::with_capacity(2);
+        if let Some(before) = range.start.prev()
+            && self.ranges[first].start <= before
+        {
+            remainder.push(TetraRange::new(self.ranges[first].start, before));
+        }
+        if let Some(after) = range.end.next()
+            && after <= self.ranges[last - 1].end
+        {
+            remainder.push(TetraRange::new(after, self.ranges[last - 1].end));
+        }
+        self.ranges.splice(first..last, remainder);
+    }
+
+    pub fn contains(&self, point: T) -> bool {
+        let idx = self.ranges.partition_point(|r| r.end < point);
+        self.ranges.get(idx).is_some_and(|r| r.contains_point(point))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -111,4 +243,102 @@ mod tests {
         let ranges = vec![SsiRange { start: 200, end: 100 }];
         SortedDisjointSsiRanges::from_vec_ssirange(ranges);
     }
+
+    #[test]
+    fn test_tetra_range_ops() {
+        let a = TetraRange::new(2u8, 5);
+        let b = TetraRange::new(4u8, 8);
+        let c = TetraRange::new(6u8, 9);
+        let d = TetraRange::new(7u8, 9);
+        assert!(a.contains_point(2) && a.contains_point(5) && !a.contains_point(6));
+        assert!(a.overlaps(&b) && !a.overlaps(&c));
+        assert_eq!(a.union(&b), Some(TetraRange::new(2, 8)));
+        assert_eq!(a.union(&c), Some(TetraRange::new(2, 9)), "adjacent ranges are contiguous");
+        assert_eq!(a.union(&d), None);
+        assert_eq!(a.intersection(&b), Some(TetraRange::new(4, 5)));
+        assert_eq!(a.intersection(&c), None);
+        assert_eq!(
+            TetraRange::new(0u8, 255).union(&TetraRange::new(255, 255)),
+            Some(TetraRange::new(0, 255))
+        );
+    }
+
+    #[test]
+    fn test_range_set() {
+        let mut set = RangeSet::default();
+        set.insert(TetraRange::new(10u16, 20));
+        set.insert(TetraRange::new(30, 40));
+        set.insert(TetraRange::new(21, 25));
+        assert_eq!(set.as_slice(), &[TetraRange::new(10, 25), TetraRange::new(30, 40)]);
+        set.insert(TetraRange::new(26, 29));
+        assert_eq!(set.as_slice(), &[TetraRange::new(10, 40)]);
+
+        set.remove(TetraRange::new(15, 35));
+        assert_eq!(set.as_slice(), &[TetraRange::new(10, 14), TetraRange::new(36, 40)]);
+        assert!(set.contains(14) && !set.contains(15) && !set.contains(35) && set.contains(36));
+        set.remove(TetraRange::new(0, 100));
+        assert!(set.is_empty());
+    }
+
+    mod proptests {
+        use std::collections::BTreeSet;
+
+        use proptest::prelude::*;
+
+        use super::*;
+
+        fn range() -> impl Strategy<Value = TetraRange<u8>> {
+            (any::<u8>(), any::<u8>()).prop_map(|(a, b)| TetraRange::new(a.min(b), a.max(b)))
+        }
+
+        fn values(range: &TetraRange<u8>) -> BTreeSet<u8> {
+            (range.start..=range.end).collect()
+        }
+
+        proptest! {
+            #![proptest_config(ProptestConfig::with_cases(10_000))]
+
+            #[test]
+            fn range_ops_match_sets(a in range(), b in range(), point in any::<u8>()) {
+                let (va, vb) = (values(&a), values(&b));
+                prop_assert_eq!(a.contains_point(point), va.contains(&point));
+                prop_assert_eq!(a.overlaps(&b), !va.is_disjoint(&vb));
+
+                let intersection: BTreeSet<u8> = va.intersection(&vb).copied().collect();
+                prop_assert_eq!(a.intersection(&b).map(|r| values(&r)).unwrap_or_default(), intersection);
+
+                // The union is a range exactly when the combined values have no gap
+                let union: BTreeSet<u8> = va.union(&vb).copied().collect();
+                let (min, max) = (*union.first().unwrap(), *union.last().unwrap());
+                let contiguous = union.len() == (max - min) as usize + 1;
+                match a.union(&b) {
+                    Some(r) => prop_assert_eq!(values(&r), union),
+                    None => prop_assert!(!contiguous),
+                }
+            }
+
+            #[test]
+            fn range_set_matches_model(ops in prop::collection::vec((any::<bool>(), range()), 0..32)) {
+                let mut set = RangeSet::default();
+                let mut model = BTreeSet::new();
+                for (insert, range) in ops {
+                    if insert {
+                        set.insert(range);
+                        model.extend(values(&range));
+                    } else {
+                        set.remove(range);
+                        model.retain(|v| !range.contains_point(*v));
+                    }
+
+                    // Sorted, disjoint and never adjacent
+                    for pair in set.as_slice().windows(2) {
+                        prop_assert!(pair[0].end.next().unwrap() < pair[1].start);
+                    }
+                }
+                for v in 0..=255u8 {
+                    prop_assert_eq!(set.contains(v), model.contains(&v));
+                }
+            }
+        }
+    }
 }
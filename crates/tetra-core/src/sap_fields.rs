@@ -1,8 +1,82 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use std::ops::RangeInclusive;
+
+use serde::{Deserialize, Serialize};
+
+/// Carrier id of the main carrier. Single-carrier code uses this for all physical channels.
+pub const MAIN_CARRIER_ID: u8 = 0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PhysicalChannel {
-    Tp,
-    Cp,
+    /// Traffic physical channel on timeslot `ts` of carrier `carrier_id`
+    Tp {
+        ts: u8,
+        carrier_id: u8,
+    },
+    /// Control physical channel on carrier `carrier_id`
+    Cp {
+        carrier_id: u8,
+    },
     Unallocated,
+    /// Traffic timeslot `ts` of carrier `carrier_id` with a half-slot stolen for FACCH signalling (STCH+TCH).
+    /// `subslot` is the stolen half-slot, 1 or 2.
+    TrafficFacch {
+        ts: u8,
+        subslot: u8,
+        carrier_id: u8,
+    },
+}
+
+impl PhysicalChannel {
+    /// Traffic physical channel on the main carrier
+    pub fn tp(ts: u8) -> Self {
+        PhysicalChannel::Tp {
+            ts,
+            carrier_id: MAIN_CARRIER_ID,
+        }
+    }
+
+    /// Control physical channel on the main carrier
+    pub fn cp() -> Self {
+        PhysicalChannel::Cp {
+            carrier_id: MAIN_CARRIER_ID,
+        }
+    }
+
+    /// True for traffic timeslots, including those with a stolen half-slot
+    pub fn is_traffic(&self) -> bool {
+        matches!(self, PhysicalChannel::Tp { .. } | PhysicalChannel::TrafficFacch { .. })
+    }
+
+    /// Timeslot of a traffic channel, None for channels not tied to a single timeslot
+    pub fn ts(&self) -> Option<u8> {
+        match self {
+            PhysicalChannel::Tp { ts, .. } | PhysicalChannel::TrafficFacch { ts, .. } => Some(*ts),
+            PhysicalChannel::Cp { .. } | PhysicalChannel::Unallocated => None,
+        }
+    }
+
+    /// Carrier the channel belongs to, None if not known
+    pub fn carrier_id(&self) -> Option<u8> {
+        match self {
+            PhysicalChannel::Tp { carrier_id, .. }
+            | PhysicalChannel::Cp { carrier_id }
+            | PhysicalChannel::TrafficFacch { carrier_id, .. } => Some(*carrier_id),
+            PhysicalChannel::Unallocated => None,
+        }
+    }
+}
+
+impl core::fmt::Display for PhysicalChannel {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PhysicalChannel::Tp { ts, carrier_id } => write!(f, "Tp ts{} c{}", ts, carrier_id),
+            PhysicalChannel::Cp { carrier_id } => write!(f, "Cp c{}", carrier_id),
+            PhysicalChannel::Unallocated => write!(f, "Unallocated"),
+            PhysicalChannel::TrafficFacch { ts, subslot, carrier_id } => {
+                write!(f, "TrafficFacch ts{} sub{} c{}", ts, subslot, carrier_id)
+            }
+        }
+    }
 }
 
 /// The endpoint identifiers between the MLE and LLC, and between the LLC and MAC, refer to the MAC resource that is
@@ -17,7 +91,56 @@ pub type LinkId = u32;
 /// Handle assigned by MLE to primitives for MM/CMCE/SNDCP
 pub type MleHandle = u32;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// Hands out handles for MLE requests, so the reports for them can be told apart. Handle 0 is left for requests
+/// that expect no report and is never handed out. After the last handle of the range, allocation wraps to the first.
+#[derive(Debug, Clone)]
+pub struct MleHandleAllocator {
+    range: RangeInclusive<MleHandle>,
+    next: MleHandle,
+}
+
+impl Default for MleHandleAllocator {
+    fn default() -> Self {
+        Self::new(1..=MleHandle::MAX)
+    }
+}
+
+impl MleHandleAllocator {
+    /// Allocator for the given range of handles. Entities whose parts allocate handles independently give each
+    /// part its own range, so their handles do not collide.
+    pub fn new(range: RangeInclusive<MleHandle>) -> Self {
+        assert!(*range.start() > 0 && !range.is_empty(), "invalid MLE handle range {:?}", range);
+        Self {
+            next: *range.start(),
+            range,
+        }
+    }
+
+    pub fn allocate(&mut self) -> MleHandle {
+        let handle = self.next;
+        self.next = if handle == *self.range.end() {
+            *self.range.start()
+        } else {
+            handle + 1
+        };
+        handle
+    }
+
+    pub fn contains(&self, handle: MleHandle) -> bool {
+        self.range.contains(&handle)
+    }
+}
+
+/// Result of an acknowledged layer 2 transfer, as given by TL-REPORT and passed on to the MLE service user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransferResult {
+    /// The peer acknowledged the TL-SDU
+    Successful,
+    /// The TL-SDU was not acknowledged after the maximum number of retransmissions
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Layer2Service {
     /// TODO FIXME, remove this option once all Layer2Service uses have been checked to have the right type
     /// Behavior defaults to Acknowledged type
@@ -27,3 +150,56 @@ pub enum Layer2Service {
     /// Use unacknowledged BL-UDATA service
     Unacknowledged,
 }
+
+/// Packet data channel classes an MS advised as usable in U-CHANNEL CLASS ADVICE, clause 18.4.1.4.8
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelClassInfo {
+    /// 4-bit channel class identifiers, 1 to 4 of them
+    pub identifiers: Vec<u8>,
+    /// Data priority of the SN PDUs awaiting access to a packet data channel
+    pub data_priority: Option<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mle_handle_allocator_wraps_within_range() {
+        let mut handles = MleHandleAllocator::new(5..=7);
+        let allocated: Vec<_> = (0..5).map(|_| handles.allocate()).collect();
+        assert_eq!(allocated, [5, 6, 7, 5, 6]);
+        assert!(handles.contains(7));
+        assert!(!handles.contains(8));
+
+        let mut handles = MleHandleAllocator::new(MleHandle::MAX..=MleHandle::MAX);
+        assert_eq!((handles.allocate(), handles.allocate()), (MleHandle::MAX, MleHandle::MAX));
+    }
+
+    #[test]
+    fn test_physical_channel_accessors() {
+        let tp = PhysicalChannel::Tp { ts: 3, carrier_id: 2 };
+        assert_eq!((tp.ts(), tp.carrier_id()), (Some(3), Some(2)));
+        assert!(tp.is_traffic());
+
+        let cp = PhysicalChannel::Cp { carrier_id: 1 };
+        assert_eq!((cp.ts(), cp.carrier_id()), (None, Some(1)));
+        assert!(!cp.is_traffic());
+
+        let facch = PhysicalChannel::TrafficFacch {
+            ts: 2,
+            subslot: 1,
+            carrier_id: 3,
+        };
+        assert_eq!((facch.ts(), facch.carrier_id()), (Some(2), Some(3)));
+        assert!(facch.is_traffic());
+        assert_eq!(facch.to_string(), "TrafficFacch ts2 sub1 c3");
+        assert_eq!(PhysicalChannel::Unallocated.ts(), None);
+        assert_eq!(PhysicalChannel::Unallocated.carrier_id(), None);
+
+        // Single-carrier constructors default to the main carrier
+        assert_eq!(PhysicalChannel::tp(4), PhysicalChannel::Tp { ts: 4, carrier_id: 0 });
+        assert_eq!(PhysicalChannel::cp().carrier_id(), Some(MAIN_CARRIER_ID));
+        assert_eq!(tp.to_string(), "Tp ts3 c2");
+    }
+}
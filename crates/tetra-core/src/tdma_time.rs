@@ -1,3 +1,4 @@
+use core::cmp::Ordering;
 use core::fmt;
 
 use bitcode::{Decode, Encode};
@@ -27,7 +28,7 @@ macro_rules! frames {
     };
 }
 
-#[derive(Clone, Copy, PartialEq, Encode, Decode, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
 pub struct TdmaTime {
     /// Timeslot, from 1 to 4
     pub t: u8,
@@ -49,6 +50,9 @@ impl Default for TdmaTime {
 /// Value of i32 time where it wraps back to 0.
 pub const TIME_INT_WRAP: i32 = 4 * 18 * 60 * 65536;
 
+/// Number of multiframes after which the hyperframe number wraps back to 0.
+pub const MULTIFRAME_WRAP: u64 = 60 * 65536;
+
 /// Difference between two int times, handling wrap-around of hyperframe number.
 pub fn time_int_diff(a: i32, b: i32) -> i32 {
     let mut diff = a - b;
@@ -82,6 +86,33 @@ impl TdmaTime {
         TdmaTime { t, f, m, h }
     }
 
+    /// Flat multiframe counter, h * 60 + m - 1
+    pub fn multiframe_number(&self) -> u64 {
+        self.h as u64 * 60 + self.m as u64 - 1
+    }
+
+    /// Index of this multiframe counted from 0/01/01/1. TdmaTime carries no count of hyperframe number wraps,
+    /// so the index is unique within one hyperframe number cycle (about 46 days) and wraps at MULTIFRAME_WRAP.
+    pub fn absolute_multiframe(&self) -> u64 {
+        self.multiframe_number()
+    }
+
+    /// Inverse of absolute_multiframe, placing the time at the given frame and timeslot of multiframe n
+    pub fn from_absolute_multiframe(n: u64, t: u8, f: u8) -> Self {
+        let n = n % MULTIFRAME_WRAP;
+        TdmaTime {
+            t,
+            f,
+            m: (n % 60) as u8 + 1,
+            h: (n / 60) as u16,
+        }
+    }
+
+    /// Timeslot offset within the multiframe, 0 to 71
+    pub fn slot_in_multiframe(&self) -> u32 {
+        (self.f as u32 - 1) * 4 + (self.t as u32 - 1)
+    }
+
     /// Add a number of timeslots to a TdmaTime
     pub fn add_timeslots(self, num_slots: i32) -> TdmaTime {
         TdmaTime::from_int(self.to_int() + num_slots)
@@ -98,6 +129,21 @@ impl TdmaTime {
         now.diff(self)
     }
 
+    /// Returns true if this time lies before `other`, taking the hyperframe number wrap into account.
+    /// Use this (or `is_after`) for timeout checks such as `start.add_timeslots(timeout).is_before(now)`; the `Ord`
+    /// impl is only meant for sorting and map keys. Both times must lie within half a wrap cycle of each other.
+    #[inline(always)]
+    pub fn is_before(self, other: TdmaTime) -> bool {
+        self.diff(other) < 0
+    }
+
+    /// Returns true if this time lies after `other`, taking the hyperframe number wrap into account.
+    /// See `is_before`.
+    #[inline(always)]
+    pub fn is_after(self, other: TdmaTime) -> bool {
+        self.diff(other) > 0
+    }
+
     #[inline(always)]
     /// Round this time up to the next occurrence for the given timeslot
     /// If already the right timeslot, time remains unchanged
@@ -106,6 +152,24 @@ impl TdmaTime {
         self.add_timeslots(slots_to_add)
     }
 
+    #[inline(always)]
+    /// Round this time up to the next occurrence of the given frame on the same timeslot
+    /// If already the right frame, time remains unchanged
+    pub fn forward_to_frame(self, f: u8) -> TdmaTime {
+        let frames_to_add = ((f + 18 - self.f) % 18) as i32;
+        self.add_timeslots(frames_to_add * 4)
+    }
+
+    /// Returns true if this is frame 18, the control frame carrying no traffic
+    pub fn is_frame18(&self) -> bool {
+        self.f == 18
+    }
+
+    /// Returns true if this is the first multiframe of the hyperframe
+    pub fn is_multiframe1(&self) -> bool {
+        self.m == 1
+    }
+
     /// Returns true if this DL timeslot should contain a mandatory BSCH (SYNC) block
     pub fn is_mandatory_bsch(&self) -> bool {
         self.f == 18 && self.t == 4 - ((self.m + 1) % 4)
@@ -123,6 +187,23 @@ impl TdmaTime {
     }
 }
 
+/// Orders times by `to_int`, i.e. by hyperframe, multiframe, frame and timeslot. This is a total order, so it is
+/// safe for sorting and `BTreeMap` keys, but it does not know about the hyperframe number wrap: the last timeslot
+/// before the wrap compares greater than the first one after it. To ask whether one time comes before another,
+/// e.g. for timeout checks, use the wrap-aware `is_before` / `is_after`; use `diff` or `age` when the distance
+/// itself is needed.
+impl Ord for TdmaTime {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.to_int().cmp(&other.to_int())
+    }
+}
+
+impl PartialOrd for TdmaTime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl fmt::Display for TdmaTime {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{:5}/{:02}/{:02}/{}", self.h, self.m, self.f, self.t)
@@ -135,6 +216,29 @@ impl fmt::Debug for TdmaTime {
     }
 }
 
+/// Compact serde encoding of a TdmaTime as its timeslot number counted from 0/01/01/1, see `TdmaTime::to_int`.
+/// Use on a field with `#[serde(with = "tetra_core::tdma_time::tdma_time_compact")]`.
+pub mod tdma_time_compact {
+    use serde::{Deserialize, Deserializer, Serializer, de};
+
+    use super::{TIME_INT_WRAP, TdmaTime};
+
+    pub fn serialize<S: Serializer>(time: &TdmaTime, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(time.to_int() as u32)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TdmaTime, D::Error> {
+        let time = u32::deserialize(deserializer)?;
+        if time >= TIME_INT_WRAP as u32 {
+            return Err(de::Error::custom(format!(
+                "timeslot number {} exceeds hyperframe number cycle",
+                time
+            )));
+        }
+        Ok(TdmaTime::from_int(time as i32))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -168,6 +272,49 @@ mod tests {
         assert_eq!(time, initial_time);
     }
 
+    #[test]
+    fn test_ord_across_wrap() {
+        let t1 = TdmaTime { t: 1, f: 1, m: 1, h: 0 };
+        assert!(t1 < t1.add_timeslots(1));
+        assert!(t1 < TdmaTime { t: 1, f: 1, m: 2, h: 0 });
+        assert_eq!(t1.cmp(&t1), Ordering::Equal);
+
+        // Ord is plain and total: the last timeslot before the hyperframe number wraps compares greater than the
+        // first one after it, while diff still sees it as one timeslot earlier
+        let before_wrap = TdmaTime {
+            t: 4,
+            f: 18,
+            m: 60,
+            h: 0xFFFF,
+        };
+        assert!(before_wrap > t1);
+        assert_eq!(t1.diff(before_wrap), 1);
+
+        let mut times = vec![before_wrap, t1.add_timeslots(5), before_wrap.add_timeslots(-3), t1];
+        times.sort();
+        assert_eq!(times, vec![t1, t1.add_timeslots(5), before_wrap.add_timeslots(-3), before_wrap]);
+    }
+
+    #[test]
+    fn test_is_before_after_across_wrap() {
+        let t1 = TdmaTime { t: 1, f: 1, m: 1, h: 0 };
+        assert!(t1.is_before(t1.add_timeslots(1)));
+        assert!(t1.add_timeslots(1).is_after(t1));
+        assert!(!t1.is_before(t1));
+        assert!(!t1.is_after(t1));
+
+        // Unlike Ord, the helpers see the last timeslot before the wrap as earlier than the first one after it
+        let before_wrap = t1.add_timeslots(-1);
+        assert!(before_wrap > t1);
+        assert!(before_wrap.is_before(t1));
+        assert!(t1.is_after(before_wrap));
+
+        // Timeout check spanning the wrap: started 10 slots before it, 20 slot timeout
+        let start = t1.add_timeslots(-10);
+        assert!(!start.add_timeslots(20).is_before(t1.add_timeslots(10)));
+        assert!(start.add_timeslots(20).is_before(t1.add_timeslots(11)));
+    }
+
     #[test]
     fn test_from_int() {
         // Test both negative and positive numbers
@@ -186,4 +333,113 @@ mod tests {
             assert_eq!(TdmaTime::from_int(time_int).diff(TdmaTime::from_int(0)), time_int);
         }
     }
+
+    #[test]
+    fn test_absolute_multiframe_monotonic() {
+        // Step one multiframe at a time across multiframe and hyperframe boundaries
+        let mut time = TdmaTime { t: 3, f: 7, m: 58, h: 41 };
+        let mut prev = time.absolute_multiframe();
+        for _ in 0..200 {
+            time = time.add_timeslots(crate::multiframes!(1));
+            let cur = time.absolute_multiframe();
+            assert_eq!(cur, prev + 1, "at {}", time);
+            prev = cur;
+        }
+        assert_eq!(
+            TdmaTime { t: 4, f: 18, m: 60, h: 3 }.absolute_multiframe() + 1,
+            TdmaTime { t: 1, f: 1, m: 1, h: 4 }.absolute_multiframe()
+        );
+        assert_eq!(TdmaTime::default().absolute_multiframe(), 0);
+        assert_eq!(
+            TdmaTime {
+                t: 1,
+                f: 1,
+                m: 60,
+                h: 65535
+            }
+            .absolute_multiframe(),
+            MULTIFRAME_WRAP - 1
+        );
+    }
+
+    #[test]
+    fn test_absolute_multiframe_round_trip() {
+        for n in [0, 1, 59, 60, 61, 3599, 3600, 123_456, MULTIFRAME_WRAP - 1] {
+            let time = TdmaTime::from_absolute_multiframe(n, 2, 17);
+            assert!(time.is_valid());
+            assert_eq!((time.t, time.f), (2, 17));
+            assert_eq!(time.absolute_multiframe(), n);
+        }
+        let time = TdmaTime {
+            t: 4,
+            f: 9,
+            m: 33,
+            h: 1234,
+        };
+        assert_eq!(TdmaTime::from_absolute_multiframe(time.absolute_multiframe(), time.t, time.f), time);
+        // Past the hyperframe number cycle the index wraps
+        assert_eq!(TdmaTime::from_absolute_multiframe(MULTIFRAME_WRAP, 1, 1), TdmaTime::default());
+    }
+
+    #[test]
+    fn test_forward_to_frame() {
+        let time = TdmaTime { t: 3, f: 17, m: 60, h: 7 };
+        assert_eq!(time.forward_to_frame(17), time);
+        assert_eq!(time.forward_to_frame(18), TdmaTime { t: 3, f: 18, m: 60, h: 7 });
+        assert!(time.forward_to_frame(18).is_frame18());
+
+        // Wraps into the next multiframe, and here into the next hyperframe
+        let next = time.forward_to_frame(2);
+        assert_eq!(next, TdmaTime { t: 3, f: 2, m: 1, h: 8 });
+        assert!(next.is_multiframe1());
+        assert!(!next.is_frame18());
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Compact(#[serde(with = "tdma_time_compact")] TdmaTime);
+
+    #[test]
+    fn test_serde_formats() {
+        let time = TdmaTime { t: 3, f: 18, m: 60, h: 2 };
+        let json = serde_json::to_string(&time).unwrap();
+        assert_eq!(json, r#"{"t":3,"f":18,"m":60,"h":2}"#);
+        assert_eq!(serde_json::from_str::<TdmaTime>(&json).unwrap(), time);
+
+        assert_eq!(serde_json::to_string(&Compact(time)).unwrap(), time.to_int().to_string());
+
+        // Boundaries of every field and of the hyperframe number cycle
+        for time in [
+            TdmaTime::default(),
+            TdmaTime { t: 4, f: 1, m: 1, h: 0 },
+            TdmaTime { t: 1, f: 18, m: 1, h: 0 },
+            TdmaTime { t: 1, f: 1, m: 60, h: 0 },
+            TdmaTime {
+                t: 4,
+                f: 18,
+                m: 60,
+                h: 0xFFFF,
+            },
+        ] {
+            let json = serde_json::to_string(&Compact(time)).unwrap();
+            assert_eq!(serde_json::from_str::<Compact>(&json).unwrap(), Compact(time));
+        }
+        assert!(serde_json::from_str::<Compact>(&TIME_INT_WRAP.to_string()).is_err());
+    }
+
+    mod proptests {
+        use proptest::prelude::*;
+
+        use super::*;
+
+        proptest! {
+            #[test]
+            fn serde_compact_roundtrip(time in 0..TIME_INT_WRAP) {
+                let time = TdmaTime::from_int(time);
+                let json = serde_json::to_string(&Compact(time)).unwrap();
+                prop_assert_eq!(serde_json::from_str::<Compact>(&json).unwrap(), Compact(time));
+                let json = serde_json::to_string(&time).unwrap();
+                prop_assert_eq!(serde_json::from_str::<TdmaTime>(&json).unwrap(), time);
+            }
+        }
+    }
 }
@@ -1,8 +1,10 @@
+use serde::{Deserialize, Serialize};
+
 // Placeholder type
 pub type Todo = i32;
 
 // SAPs as defined in the standard
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Sap {
     TpSap,  // Phy/LMAC
     TpcSap, // Phy/LMAC mgmt
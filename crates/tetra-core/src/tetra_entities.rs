@@ -26,4 +26,8 @@ pub enum TetraEntity {
 
     /// Brew protocol bridge (TetraPack/BrandMeister integration)
     Brew,
+
+    /// Remote admin interface. Not a registered entity, the MessageRouter hands
+    /// messages addressed to it to the admin link.
+    Admin,
 }
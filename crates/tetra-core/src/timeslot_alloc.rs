@@ -1,4 +1,11 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+use crate::ranges::TetraRange;
+
+/// Timeslots that can carry traffic, TS1 is reserved for the MCCH
+pub const TRAFFIC_TIMESLOTS: TetraRange<u8> = TetraRange { start: 2, end: 4 };
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TimeslotOwner {
     Brew,
     Cmce,
@@ -21,7 +28,7 @@ pub enum TimeslotAllocErr {
     },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TimeslotAllocator {
     // Index 0 = TS2, 1 = TS3, 2 = TS4
     owners: [Option<TimeslotOwner>; 3],
@@ -37,8 +44,8 @@ impl Default for TimeslotAllocator {
 
 impl TimeslotAllocator {
     fn idx(ts: u8) -> Result<usize, TimeslotAllocErr> {
-        if (2..=4).contains(&ts) {
-            Ok((ts - 2) as usize)
+        if TRAFFIC_TIMESLOTS.contains_point(ts) {
+            Ok((ts - TRAFFIC_TIMESLOTS.start) as usize)
         } else {
             Err(TimeslotAllocErr::InvalidTimeslot(ts))
         }
@@ -88,4 +95,52 @@ impl TimeslotAllocator {
     pub fn is_free(&self, ts: u8) -> bool {
         self.owner(ts).is_none()
     }
+
+    /// Timeslots currently allocated to the given owner
+    pub fn owned_by(&self, owner: TimeslotOwner) -> Vec<u8> {
+        (TRAFFIC_TIMESLOTS.start..=TRAFFIC_TIMESLOTS.end)
+            .filter(|&ts| self.owner(ts) == Some(owner))
+            .collect()
+    }
+
+    /// Serialize the allocation state, for persisting it across stack restarts
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("TimeslotAllocator is always serializable")
+    }
+
+    /// Restore an allocation state previously produced by `to_json`
+    pub fn from_json(s: &str) -> Result<TimeslotAllocator, serde_json::Error> {
+        serde_json::from_str(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_roundtrip() {
+        let mut alloc = TimeslotAllocator::default();
+        alloc.reserve(TimeslotOwner::Brew, 3).unwrap();
+        alloc.reserve(TimeslotOwner::Cmce, 4).unwrap();
+
+        let restored = TimeslotAllocator::from_json(&alloc.to_json()).unwrap();
+        assert_eq!(restored, alloc);
+        assert!(restored.is_free(2));
+        assert_eq!(restored.owner(3), Some(TimeslotOwner::Brew));
+        assert_eq!(restored.owned_by(TimeslotOwner::Cmce), vec![4]);
+    }
+
+    #[test]
+    fn test_json_empty_roundtrip() {
+        let alloc = TimeslotAllocator::default();
+        assert_eq!(TimeslotAllocator::from_json(&alloc.to_json()).unwrap(), alloc);
+    }
+
+    #[test]
+    fn test_json_corrupted() {
+        assert!(TimeslotAllocator::from_json("").is_err());
+        assert!(TimeslotAllocator::from_json("{\"owners\":[null,\"Cmce\"]}").is_err());
+        assert!(TimeslotAllocator::from_json("{\"owners\":[null,\"Nobody\",null]}").is_err());
+    }
 }
@@ -1,3 +1,5 @@
+use crate::{bitbuffer::BitBuffer, pdu_parse_error::PduParseErr};
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct Type4FieldGeneric {
     pub field_id: u64,
@@ -15,6 +17,88 @@ pub struct Type3FieldGeneric {
     pub data: u64,
 }
 
+/// Defines a newtype for an unsigned PDU field of fixed bit width, stored in `$repr`.
+/// Values wider than the field panic in debug builds; release builds keep the value as given,
+/// so that the field is written truncated exactly as a plain integer would have been.
+macro_rules! field {
+    ($name:ident: $repr:ty, $bits:expr) => {
+        #[doc = concat!("Unsigned ", stringify!($bits), "-bit PDU field")]
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name($repr);
+
+        impl $name {
+            pub const BITS: usize = $bits;
+            pub const MAX: $repr = ((1u64 << $bits) - 1) as $repr;
+
+            pub const fn new(val: $repr) -> Self {
+                debug_assert!(val <= Self::MAX, concat!("value out of range for ", stringify!($name)));
+                Self(val)
+            }
+
+            pub const fn get(self) -> $repr {
+                self.0
+            }
+
+            /// Read the field from `buffer`
+            pub fn read(buffer: &mut BitBuffer, field_name: &'static str) -> Result<Self, PduParseErr> {
+                Ok(Self(buffer.read_field($bits, field_name)? as $repr))
+            }
+
+            /// Write the field to `buffer`
+            pub fn write(self, buffer: &mut BitBuffer) {
+                buffer.write_bits(self.0 as u64, $bits);
+            }
+        }
+
+        impl From<$repr> for $name {
+            fn from(val: $repr) -> Self {
+                Self::new(val)
+            }
+        }
+
+        impl From<$name> for $repr {
+            fn from(field: $name) -> Self {
+                field.0
+            }
+        }
+
+        impl PartialEq<$repr> for $name {
+            fn eq(&self, other: &$repr) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl PartialOrd<$repr> for $name {
+            fn partial_cmp(&self, other: &$repr) -> Option<std::cmp::Ordering> {
+                self.0.partial_cmp(other)
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+    };
+}
+
+field!(FieldU2: u8, 2);
+field!(FieldU4: u8, 4);
+field!(FieldU6: u8, 6);
+field!(FieldU8: u8, 8);
+field!(FieldU10: u16, 10);
+field!(FieldU16: u16, 16);
+
+/// Evaluates to `$val`, failing compilation if the constant `$val` does not fit in `$bits` bits.
+/// Intended for literals assigned to PDU fields, e.g. `FieldU6::new(field_range_check!(0b111111, 6))`.
+#[macro_export]
+macro_rules! field_range_check {
+    ($val:expr, $bits:expr) => {{
+        const _: () = assert!(($val as u64) >> $bits == 0, "value exceeds field width");
+        $val
+    }};
+}
+
 /// Helper functions for dealing with type2, type3 and type4 fields for MLE, CMCE, MM and SNDCP PDUs.
 pub mod delimiters {
     use crate::{bitbuffer::BitBuffer, pdu_parse_error::PduParseErr};
@@ -168,7 +252,7 @@ pub mod typed {
             }
             Some(1) => {
                 // Some field is present, read and compare id
-                let id_bits = buffer.peek_bits_posoffset(1, 4);
+                let id_bits = buffer.peek_bits_at_offset(1, 4);
                 match id_bits {
                     Some(id) if id == expected_id => {
                         // The expected is here; the field exists
@@ -182,12 +266,16 @@ pub mod typed {
                         // Read failed
                         Err(PduParseErr::BufferEnded {
                             field: Some("peek_type34_mbit_and_id id_bits"),
+                            bit_offset: buffer.get_pos() + 1,
+                            bit_length: 4,
                         })
                     }
                 }
             }
             None => Err(PduParseErr::BufferEnded {
                 field: Some("peek_type34_mbit_and_id mbit"),
+                bit_offset: buffer.get_pos(),
+                bit_length: 1,
             }),
             _ => panic!(), // Never happens
         }
@@ -220,6 +308,8 @@ pub mod typed {
             None => {
                 return Err(PduParseErr::BufferEnded {
                     field: Some("parse_type3_generic len_bits"),
+                    bit_offset: buffer.get_pos(),
+                    bit_length: 11,
                 });
             }
         };
@@ -229,6 +319,8 @@ pub mod typed {
             None => {
                 return Err(PduParseErr::BufferEnded {
                     field: Some("parse_type3_generic data"),
+                    bit_offset: buffer.get_pos(),
+                    bit_length: read_bits,
                 });
             }
         };
@@ -275,6 +367,8 @@ pub mod typed {
             None => {
                 return Err(PduParseErr::BufferEnded {
                     field: Some("parse_type3_struct len_bits"),
+                    bit_offset: buffer.get_pos(),
+                    bit_length: 11,
                 });
             }
         };
@@ -410,6 +504,8 @@ pub mod typed {
             None => {
                 return Err(PduParseErr::BufferEnded {
                     field: Some("parse_type4_header len_bits"),
+                    bit_offset: buffer.get_pos(),
+                    bit_length: 11,
                 });
             }
         };
@@ -419,6 +515,8 @@ pub mod typed {
             None => {
                 return Err(PduParseErr::BufferEnded {
                     field: Some("parse_type4_header num_elems"),
+                    bit_offset: buffer.get_pos(),
+                    bit_length: 6,
                 });
             }
         };
@@ -607,3 +705,46 @@ pub mod typed {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_conversions() {
+        let field = FieldU6::from(field_range_check!(0b111111, 6));
+        assert_eq!(field, 63);
+        assert_eq!(u8::from(field), FieldU6::MAX);
+        assert!(FieldU2::new(1) > 0);
+
+        let field: u16 = FieldU10::new(1023).into();
+        assert_eq!(field, 1023);
+        assert_eq!(FieldU16::MAX, u16::MAX);
+    }
+
+    #[test]
+    fn test_field_read_write() {
+        let mut buffer = BitBuffer::new_autoexpand(16);
+        FieldU4::new(0b1010).write(&mut buffer);
+        FieldU10::new(0b11_0000_0001).write(&mut buffer);
+        assert_eq!(buffer.get_pos(), 14);
+
+        buffer.seek(0);
+        assert_eq!(FieldU4::read(&mut buffer, "field_u4").unwrap(), 0b1010);
+        assert_eq!(FieldU10::read(&mut buffer, "field_u10").unwrap(), 0b11_0000_0001);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "value out of range for FieldU4")]
+    fn test_field_out_of_range() {
+        let _ = FieldU4::from(16);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "value out of range for FieldU10")]
+    fn test_field_out_of_range_u16() {
+        let _ = FieldU10::new(1024);
+    }
+}
@@ -88,6 +88,10 @@ impl TetraEntityTrait for CmceBs {
         self.config = config;
     }
 
+    fn shutdown(&mut self, queue: &mut MessageQueue) {
+        self.cc.shutdown(queue);
+    }
+
     fn tick_start(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
         // Propagate tick to subentities
         self.cc.tick_start(queue, ts);
@@ -118,6 +122,9 @@ impl TetraEntityTrait for CmceBs {
                 SapMsgInner::LcmcMleUnitdataInd(_) => {
                     self.rx_lcmc_mle_unitdata_ind(queue, message);
                 }
+                SapMsgInner::LcmcMleReportInd(prim) => {
+                    tracing::debug!("rx_prim: report for handle {}: {:?}", prim.handle, prim);
+                }
                 _ => {
                     panic!("Unexpected message on LcmcSap: {:?}", message.msg);
                 }
@@ -132,6 +139,15 @@ impl TetraEntityTrait for CmceBs {
                 SapMsgInner::CmceSdsData(_) => {
                     self.sds.rx_sds_from_brew(queue, message);
                 }
+                SapMsgInner::QueryActiveCalls(_) => {
+                    self.cc.rx_query_active_calls(queue, message.src);
+                }
+                SapMsgInner::QueryStackState(_) => {
+                    self.cc.rx_query_stack_state(queue, message.src);
+                }
+                SapMsgInner::ReleaseCallReq(req) => {
+                    self.cc.rx_release_call_req(queue, message.src, req.call_id);
+                }
                 _ => {
                     panic!("Unexpected control message: {:?}", message.msg);
                 }
@@ -1,7 +1,7 @@
 use std::collections::VecDeque;
 
 use tetra_core::{Direction, TdmaTime, TimeslotAllocator, TimeslotOwner, frames, multiframes};
-use tetra_pdus::cmce::structs::cmce_circuit::CmceCircuit;
+use tetra_pdus::cmce::structs::cmce_circuit::{CircuitBuildError, CmceCircuit, CmceCircuitBuilder};
 use tetra_saps::{
     control::enums::{circuit_mode_type::CircuitModeType, communication_type::CommunicationType},
     lcmc::CallId,
@@ -15,6 +15,18 @@ pub enum CircuitErr {
     NoCircuitFree,
     CircuitAlreadyInUse,
     CircuitNotActive,
+    /// Direction::None was passed where a circuit direction is required
+    InvalidDirection,
+    /// The requested kind of circuit is not supported by this stack
+    NotImplemented,
+    /// The circuit parameters were invalid
+    Build(CircuitBuildError),
+}
+
+impl From<CircuitBuildError> for CircuitErr {
+    fn from(e: CircuitBuildError) -> Self {
+        CircuitErr::Build(e)
+    }
 }
 
 pub enum CircuitMgrCmd {
@@ -55,40 +67,34 @@ impl CircuitMgr {
     /// Returns (dl_active, ul_active)
     pub fn is_active(&self, ts: u8) -> (bool, bool) {
         match &self.dl[ts as usize - 1] {
-            Some(dl) => {
-                if dl.direction == Direction::Both {
-                    (true, true)
-                } else {
-                    (true, self.ul_only[ts as usize - 1].is_some())
-                }
-            }
+            Some(dl) => (true, dl.direction.includes_ul() || self.ul_only[ts as usize - 1].is_some()),
             None => (false, self.ul_only[ts as usize - 1].is_some()),
         }
     }
 
     /// Checks if a circuit is active on the given timeslot and direction
-    /// Direction must be Dl or Ul
+    /// For Both, both directions must be active. None is never active.
     pub fn is_active_dir(&self, ts: u8, dir: Direction) -> bool {
         match dir {
             Direction::Dl => self.dl[ts as usize - 1].is_some(),
             Direction::Ul => {
                 let dl_is_both = if let Some(dl) = &self.dl[ts as usize - 1] {
-                    assert!(self.ul_only[ts as usize - 1].is_none());
-                    dl.direction == Direction::Both
+                    assert!(self.ul_only[ts as usize - 1].is_none() || !dl.direction.includes_ul());
+                    dl.direction.includes_ul()
                 } else {
                     false
                 };
                 self.ul_only[ts as usize - 1].is_some() || dl_is_both
             }
-
-            _ => panic!("can only use with specific ul/dl direction"),
+            Direction::Both => self.is_active_dir(ts, Direction::Dl) && self.is_active_dir(ts, Direction::Ul),
+            Direction::None => false,
         }
     }
 
     /// Gets the usage number of an active circuit, (Option<dl_usage>, Option<ul_usage>)
     pub fn get_usage(&self, ts: u8) -> (Option<u8>, Option<u8>) {
         let (dl_usage, dl_is_both) = if let Some(dl) = &self.dl[ts as usize - 1] {
-            (Some(dl.usage), dl.direction == Direction::Both)
+            (Some(dl.usage), dl.direction.includes_ul())
         } else {
             (None, false)
         };
@@ -123,6 +129,9 @@ impl CircuitMgr {
 
     /// Finds a free timeslot for the given direction (Ul, Dl or Both)
     fn get_free_ts(&self, dir: Direction) -> Result<u8, CircuitErr> {
+        if dir == Direction::None {
+            return Err(CircuitErr::InvalidDirection);
+        }
         // TODO FIXME we may do a bit smarter allocation here
         for ts in 2..=4 {
             let (dl_active, ul_active) = self.is_active(ts);
@@ -132,7 +141,7 @@ impl CircuitMgr {
                 (Direction::Ul, true, false) => {
                     // Check if dl circuit covers Dl+Ul
                     let dl = self.dl[ts as usize - 1].as_ref().unwrap();
-                    if dl.direction != Direction::Both {
+                    if !dl.direction.includes_ul() {
                         return Ok(ts);
                     }
                 }
@@ -150,19 +159,7 @@ impl CircuitMgr {
         let usage = self.get_next_usage_number();
 
         // Create circuit
-        let circuit = CmceCircuit {
-            ts_created: self.dltime,
-            direction: dir,
-            ts,
-            call_id,
-            usage,
-            circuit_mode: CircuitModeType::TchS, // TODO: only speech supported for now
-            // endpoint_id: 0, // TODO, we don't use endpoints as of yet
-            comm_type,
-            simplex_duplex: false,   // TODO, simplex only for now
-            speech_service: Some(0), // TODO, only TETRA encoded speech for now
-            etee_encrypted: false,   // TODO, no encryption for now
-        };
+        let circuit = self.speech_circuit(call_id, ts, usage, dir, comm_type)?;
 
         // Register circuit and return
         Ok(self.open_circuit(dir, circuit)?)
@@ -182,22 +179,57 @@ impl CircuitMgr {
         let call_id = self.get_next_call_id();
         let usage = self.get_next_usage_number();
 
-        // Create circuit
-        let circuit = CmceCircuit {
-            ts_created: self.dltime,
-            direction: dir,
-            ts,
-            call_id,
-            usage,
-            circuit_mode: CircuitModeType::TchS,
-            comm_type,
-            simplex_duplex: false,
-            speech_service: Some(0),
-            etee_encrypted: false,
+        // Create and register circuit, handing the timeslot back if it is invalid or turns out to be in use here
+        let circuit = match self.speech_circuit(call_id, ts, usage, dir, comm_type) {
+            Ok(circuit) => circuit,
+            Err(e) => {
+                let _ = timeslot_alloc.release(owner, ts);
+                return Err(e.into());
+            }
         };
+        match self.open_circuit(dir, circuit) {
+            Ok(circuit) => Ok(circuit),
+            Err(e) => {
+                let _ = timeslot_alloc.release(owner, ts);
+                Err(e)
+            }
+        }
+    }
 
-        // Register circuit and return
-        Ok(self.open_circuit(dir, circuit)?)
+    /// Build a circuit created now, for TETRA encoded speech
+    fn speech_circuit(
+        &self,
+        call_id: CallId,
+        ts: u8,
+        usage: u8,
+        dir: Direction,
+        comm_type: CommunicationType,
+    ) -> Result<CmceCircuit, CircuitBuildError> {
+        CmceCircuitBuilder::new(call_id, ts)
+            .ts_created(self.dltime)
+            .direction(dir)
+            .comm_type(comm_type)
+            .circuit_mode(CircuitModeType::TchS) // TODO: only speech supported for now
+            .usage(usage)
+            .speech_service(0) // TODO, only TETRA encoded speech for now, simplex and unencrypted
+            .build()
+    }
+
+    /// Allocate a point-to-point circuit. Both parties talk on the same timeslot, so the UL and DL are
+    /// always allocated together on one timeslot.
+    pub fn allocate_circuit_p2p(
+        &mut self,
+        timeslot_alloc: &mut TimeslotAllocator,
+        owner: TimeslotOwner,
+    ) -> Result<&CmceCircuit, CircuitErr> {
+        self.allocate_circuit_with_allocator(Direction::Both, CommunicationType::P2p, timeslot_alloc, owner)
+    }
+
+    /// Allocate a circuit for a call spanning multiple sites. This needs resources on an inter-site link,
+    /// which does not exist yet, so it always fails.
+    pub fn allocate_circuit_multisite(&mut self, comm_type: CommunicationType) -> Result<&CmceCircuit, CircuitErr> {
+        tracing::warn!("CircuitMgr: no inter-site link, can't allocate multisite {} circuit", comm_type);
+        Err(CircuitErr::NotImplemented)
     }
 
     /// Closes any active circuits for given timeslot and direction.
@@ -217,7 +249,7 @@ impl CircuitMgr {
                 let circuit = self.ul_only[ts as usize - 1].take();
                 circuit.ok_or(CircuitErr::CircuitNotActive)
             }
-            _ => panic!(),
+            Direction::None => Err(CircuitErr::InvalidDirection),
         }
     }
 
@@ -248,7 +280,7 @@ impl CircuitMgr {
                 self.ul_only[ts as usize - 1] = Some(circuit);
                 Ok(self.ul_only[ts as usize - 1].as_ref().unwrap())
             }
-            _ => panic!(),
+            Direction::None => Err(CircuitErr::InvalidDirection),
         }
     }
 
@@ -334,3 +366,108 @@ impl CircuitMgr {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OWNER: TimeslotOwner = TimeslotOwner::Cmce;
+
+    fn allocate(mgr: &mut CircuitMgr, alloc: &mut TimeslotAllocator, dir: Direction) -> Result<u8, CircuitErr> {
+        mgr.allocate_circuit_with_allocator(dir, CommunicationType::P2Mp, alloc, OWNER)
+            .map(|circuit| circuit.ts)
+    }
+
+    #[test]
+    fn test_allocate_and_close_dl_circuit() {
+        let mut mgr = CircuitMgr::new();
+        let mut alloc = TimeslotAllocator::default();
+
+        // The first traffic timeslot is taken, both here and in the allocator
+        assert_eq!(allocate(&mut mgr, &mut alloc, Direction::Dl), Ok(2));
+        assert!(mgr.is_active_dir(2, Direction::Dl));
+        assert!(!mgr.is_active_dir(2, Direction::Ul));
+        assert_eq!(alloc.owner(2), Some(OWNER));
+
+        // A UL-only circuit may share the timeslot with the DL-only circuit
+        let ul = mgr.allocate_circuit(Direction::Ul, CommunicationType::P2p).unwrap();
+        assert_eq!(ul.ts, 2);
+        assert_eq!(mgr.is_active(2), (true, true));
+
+        // Closing the DL circuit frees the DL for a new circuit, the UL circuit remains
+        let closed = mgr.close_circuit(Direction::Dl, 2).unwrap();
+        assert_eq!(closed.direction, Direction::Dl);
+        assert_eq!(mgr.is_active(2), (false, true));
+        assert_eq!(mgr.close_circuit(Direction::Dl, 2).unwrap_err(), CircuitErr::CircuitNotActive);
+        assert_eq!(mgr.allocate_circuit(Direction::Dl, CommunicationType::P2Mp).unwrap().ts, 2);
+    }
+
+    #[test]
+    fn test_allocate_on_occupied_timeslot() {
+        let mut mgr = CircuitMgr::new();
+        let mut alloc = TimeslotAllocator::default();
+        assert_eq!(allocate(&mut mgr, &mut alloc, Direction::Dl), Ok(2));
+
+        // The allocator hands out ts2 again, while the DL circuit on it is still open
+        alloc.release(OWNER, 2).unwrap();
+        assert_eq!(allocate(&mut mgr, &mut alloc, Direction::Dl), Err(CircuitErr::CircuitAlreadyInUse));
+        assert!(alloc.is_free(2), "timeslot must be released when the circuit can't be opened");
+        assert!(mgr.is_active_dir(2, Direction::Dl));
+    }
+
+    #[test]
+    fn test_allocate_until_exhausted() {
+        let mut mgr = CircuitMgr::new();
+        let mut alloc = TimeslotAllocator::default();
+
+        for ts in 2..=4 {
+            assert_eq!(allocate(&mut mgr, &mut alloc, Direction::Both), Ok(ts));
+        }
+        assert_eq!(allocate(&mut mgr, &mut alloc, Direction::Both), Err(CircuitErr::NoCircuitFree));
+        assert_eq!(alloc.owned_by(OWNER), vec![2, 3, 4]);
+
+        for ts in 2..=4 {
+            let circuit = mgr.close_circuit(Direction::Both, ts).unwrap();
+            assert_eq!(circuit.ts, ts);
+            alloc.release(OWNER, ts).unwrap();
+        }
+        for ts in 2..=4 {
+            assert_eq!(mgr.is_active(ts), (false, false));
+            assert!(alloc.is_free(ts));
+        }
+
+        // Call identifiers and usage numbers are unique per circuit
+        assert_eq!(allocate(&mut mgr, &mut alloc, Direction::Both), Ok(2));
+        let circuit = mgr.dl[1].as_ref().unwrap();
+        assert_eq!((circuit.call_id, circuit.usage), (7, 7));
+    }
+
+    #[test]
+    fn test_allocate_p2p_symmetric() {
+        let mut mgr = CircuitMgr::new();
+        let mut alloc = TimeslotAllocator::default();
+
+        // A DL-only circuit on ts2 leaves no room for a symmetric circuit there
+        assert_eq!(allocate(&mut mgr, &mut alloc, Direction::Dl), Ok(2));
+        let circuit = mgr.allocate_circuit_p2p(&mut alloc, OWNER).unwrap().clone();
+        assert_eq!(circuit.ts, 3);
+        assert_eq!(circuit.direction, Direction::Both);
+        assert_eq!(circuit.comm_type, CommunicationType::P2p);
+        assert!(mgr.is_active_dir(3, Direction::Dl));
+        assert!(mgr.is_active_dir(3, Direction::Ul));
+        assert_eq!(mgr.get_usage(3), (Some(circuit.usage), Some(circuit.usage)));
+        assert_eq!(alloc.owner(3), Some(OWNER));
+    }
+
+    #[test]
+    fn test_allocate_multisite_not_implemented() {
+        let mut mgr = CircuitMgr::new();
+        assert_eq!(
+            mgr.allocate_circuit_multisite(CommunicationType::P2Mp).unwrap_err(),
+            CircuitErr::NotImplemented
+        );
+        for ts in 1..=4 {
+            assert_eq!(mgr.is_active(ts), (false, false));
+        }
+    }
+}
@@ -1,8 +1,22 @@
-use std::collections::{HashMap, HashSet};
+//! Call control for the base station: group and individual call setup, floor control and release
+//!
+//! Group calls are traced in a `call{call_id, gssi}` span:
+//! ```text
+//! call{call_id, gssi}              opened in rx_u_setup / rx_network_call_start and kept in ActiveCall
+//! ├── rx_u_tx_ceased, rx_u_tx_demand, network speaker changes
+//! └── hangtime expiry, release_call
+//! ```
+//! The span closes once the call is released and removed from `active_calls`.
+//!
+//! Messages sent for a call carry its `call_id` in the SapMsg. MLE and LLC copy it into the primitives they
+//! build, and the MessageRouter delivers such messages inside a `call{call_id}` span, so UMAC, LLC and MLE
+//! events show up with the same call_id as the CMCE events.
+
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use tetra_config::bluestation::SharedConfig;
 use tetra_core::{BitBuffer, Direction, Sap, SsiType, TdmaTime, TetraAddress, tetra_entities::TetraEntity, unimplemented_log};
-use tetra_core::{Layer2Service, TimeslotOwner, TxReporter, TxState};
+use tetra_core::{Layer2Service, MleHandleAllocator, TimeslotOwner, TxReporter, TxState};
 use tetra_pdus::cmce::enums::disconnect_cause::DisconnectCause;
 use tetra_pdus::cmce::{
     enums::{
@@ -11,8 +25,9 @@ use tetra_pdus::cmce::{
     },
     fields::basic_service_information::BasicServiceInformation,
     pdus::{
-        d_call_proceeding::DCallProceeding, d_connect::DConnect, d_release::DRelease, d_setup::DSetup, d_tx_ceased::DTxCeased,
-        d_tx_granted::DTxGranted, u_disconnect::UDisconnect, u_release::URelease, u_setup::USetup, u_tx_ceased::UTxCeased,
+        d_alert::DAlert, d_call_proceeding::DCallProceeding, d_connect::DConnect, d_connect_acknowledge::DConnectAcknowledge,
+        d_disconnect::DDisconnect, d_release::DRelease, d_setup::DSetup, d_tx_ceased::DTxCeased, d_tx_granted::DTxGranted, u_alert::UAlert,
+        u_connect::UConnect, u_disconnect::UDisconnect, u_release::URelease, u_setup::USetup, u_tx_ceased::UTxCeased,
         u_tx_demand::UTxDemand,
     },
     structs::cmce_circuit::CmceCircuit,
@@ -20,8 +35,10 @@ use tetra_pdus::cmce::{
 use tetra_saps::{
     SapMsg, SapMsgInner,
     control::{
+        admin::{ActiveCallInfo, ActiveCallsReply, ReleaseCallConf, StackStateSnapshot},
         brew::{BrewSubscriberAction, MmSubscriberUpdate},
         call_control::{CallControl, Circuit},
+        emergency::CmceEmergencyCall,
         enums::{circuit_mode_type::CircuitModeType, communication_type::CommunicationType},
     },
     lcmc::{
@@ -31,12 +48,26 @@ use tetra_saps::{
     },
 };
 
+use super::CC_MLE_HANDLES;
 use crate::net_brew;
 use crate::{
     MessageQueue,
-    cmce::components::circuit_mgr::{CircuitMgr, CircuitMgrCmd},
+    cmce::components::circuit_mgr::{CircuitErr, CircuitMgr, CircuitMgrCmd},
 };
 
+/// T310: time we wait for the called MS of an individual call to answer with U-CONNECT, ~60 seconds.
+/// Matches the set-up phase time-out we announce to the calling MS in D-ALERT.
+pub const T310_TIMESLOTS: i32 = 60 * 18 * 4;
+
+/// T309: time we wait for the other party of an individual call to answer D-DISCONNECT with U-RELEASE, ~10 seconds.
+pub const T309_TIMESLOTS: i32 = 10 * 18 * 4;
+
+/// Call priority value for emergency pre-emptive priority, such calls may pre-empt other calls
+const EMERGENCY_CALL_PRIORITY: u8 = 15;
+
+/// How long timeslots restored from the state file stay reserved after a restart, ~10 seconds
+const RESTORED_TIMESLOT_HOLD_TIMESLOTS: i32 = 10 * 18 * 4;
+
 /// Clause 11 Call Control CMCE sub-entity
 pub struct CcBsSubentity {
     config: SharedConfig,
@@ -46,10 +77,22 @@ pub struct CcBsSubentity {
     circuits: CircuitMgr,
     /// Active group calls: call_id -> call info
     active_calls: HashMap<u16, ActiveCall>,
+    /// Individual calls between two local MSes: call_id -> call info
+    individual_calls: HashMap<u16, IndividualCall>,
     /// Registered subscriber groups (ISSI -> set of GSSIs)
     subscriber_groups: HashMap<u32, HashSet<u32>>,
     /// Listener counts per GSSI
     group_listeners: HashMap<u32, usize>,
+    /// Timeslots found allocated in the state file on startup, reserved until RESTORED_TIMESLOT_HOLD_TIMESLOTS expires
+    restored_timeslots: Vec<u8>,
+    /// Time of the first tick after startup, from which restored timeslots are held
+    restored_since: Option<TdmaTime>,
+    /// Released group calls whose UMAC circuit stays open until their D-RELEASE went out on FACCH
+    pending_releases: Vec<PendingRelease>,
+    /// Call control commands for UMAC that did not fit in the message queue, see send_umac_call_control
+    umac_backlog: VecDeque<SapMsg>,
+    /// Handles of our MLE-UNITDATA requests, see CC_MLE_HANDLES
+    handles: MleHandleAllocator,
 }
 
 /// Origin of a group call
@@ -73,6 +116,8 @@ struct ActiveCall {
     source_issi: u32, // Current speaker
     ts: u8,
     usage: u8,
+    /// Call priority, 0 - 15, as requested in U-SETUP or by the network
+    priority: u8,
     /// True if someone is currently transmitting
     tx_active: bool,
     /// When PTT was released (for hangtime). None if transmitting.
@@ -80,16 +125,56 @@ struct ActiveCall {
     /// Brew session UUID — set when a network speaker is active on this call,
     /// regardless of call origin. Cleared when the network speaker ends.
     brew_uuid: Option<uuid::Uuid>,
+    /// ISSIs that set up or joined the call (U-SETUP, U-CONNECT) and have not sent U-RELEASE since
+    active_subscribers: HashSet<u32>,
+    /// "call" span, entered while handling signalling for this call
+    span: tracing::Span,
+}
+
+/// A released group call whose circuit is still open in UMAC, so the D-RELEASE stolen from its traffic channel can be sent
+struct PendingRelease {
+    call_id: u16,
+    ts: u8,
+    /// Circuit to close in UMAC, None if CircuitMgr had already closed it
+    circuit: Option<CmceCircuit>,
+    /// Reporter of the last D-RELEASE transmission
+    reporter: TxReporter,
+}
+
+/// Tracks an individual (point-to-point) call between two local MSes
+#[derive(Clone)]
+struct IndividualCall {
+    caller_addr: TetraAddress,
+    called_addr: TetraAddress,
+    ts: u8,
+    usage: u8,
+    /// Call priority, 0 - 15, as requested in U-SETUP
+    priority: u8,
+    /// True once the called MS reported it is alerting its user (U-ALERT)
+    alerting: bool,
+    /// True once the called MS answered (U-CONNECT) and the traffic channel was assigned
+    connected: bool,
+    /// Start of T310, running from D-SETUP until the called MS answers. None once connected.
+    t310_start: Option<TdmaTime>,
+    /// Set while a disconnect is in progress: (ISSI that sent U-DISCONNECT, start of T309)
+    disconnecting: Option<(u32, TdmaTime)>,
 }
 
 impl CcBsSubentity {
     pub fn new(config: SharedConfig) -> Self {
+        let restored_timeslots = config.state_read().timeslot_alloc.owned_by(TimeslotOwner::Cmce);
         CcBsSubentity {
             config,
+            restored_timeslots,
+            restored_since: None,
+            pending_releases: Vec::new(),
+            umac_backlog: VecDeque::new(),
+            handles: MleHandleAllocator::new(CC_MLE_HANDLES),
             dltime: TdmaTime::default(),
             cached_setups: HashMap::new(),
             circuits: CircuitMgr::new(),
             active_calls: HashMap::new(),
+            individual_calls: HashMap::new(),
             subscriber_groups: HashMap::new(),
             group_listeners: HashMap::new(),
         }
@@ -126,11 +211,11 @@ impl CcBsSubentity {
         reporter: Option<TxReporter>,
     ) -> SapMsg {
         // Construct prim
-        SapMsg {
-            sap: Sap::LcmcSap,
-            src: TetraEntity::Cmce,
-            dest: TetraEntity::Mle,
-            msg: SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
+        SapMsg::new(
+            Sap::LcmcSap,
+            TetraEntity::Cmce,
+            TetraEntity::Mle,
+            SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
                 sdu,
                 handle: 0,
                 endpoint_id: 0,
@@ -142,9 +227,10 @@ impl CcBsSubentity {
                 stealing_repeats_flag: false,
                 chan_alloc,
                 main_address: address,
+                redundant_transmission: 1,
                 tx_reporter: reporter,
             }),
-        }
+        )
     }
 
     fn build_sapmsg_stealing(sdu: BitBuffer, address: TetraAddress, ts: u8) -> SapMsg {
@@ -159,11 +245,11 @@ impl CcBsSubentity {
             ul_dl_assigned: UlDlAssignment::Both,
         };
 
-        SapMsg {
-            sap: Sap::LcmcSap,
-            src: TetraEntity::Cmce,
-            dest: TetraEntity::Mle,
-            msg: SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
+        SapMsg::new(
+            Sap::LcmcSap,
+            TetraEntity::Cmce,
+            TetraEntity::Mle,
+            SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
                 sdu,
                 handle: 0,
                 endpoint_id: 0,
@@ -175,9 +261,15 @@ impl CcBsSubentity {
                 stealing_repeats_flag: false,
                 chan_alloc: Some(chan_alloc),
                 main_address: address,
+                redundant_transmission: 1,
                 tx_reporter: None,
             }),
-        }
+        )
+    }
+
+    /// Span covering the signalling of a single call. Stored in the call state so later events can be recorded in it.
+    fn call_span(call_id: u16, gssi: u32) -> tracing::Span {
+        tracing::info_span!("call", call_id, gssi)
     }
 
     fn build_d_release_from_d_setup(d_setup_pdu: &DSetup, disconnect_cause: DisconnectCause) -> BitBuffer {
@@ -232,12 +324,12 @@ impl CcBsSubentity {
             tracing::info!("CMCE: dropping call_id={} gssi={} (no listeners)", call_id, gssi);
             if let CallOrigin::Network { brew_uuid } = origin {
                 if net_brew::is_brew_gssi_routable(&self.config, gssi) {
-                    queue.push_back(SapMsg {
-                        sap: Sap::Control,
-                        src: TetraEntity::Cmce,
-                        dest: TetraEntity::Brew,
-                        msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid }),
-                    });
+                    let _ = queue.push_back(SapMsg::new(
+                        Sap::Control,
+                        TetraEntity::Cmce,
+                        TetraEntity::Brew,
+                        SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid }),
+                    ));
                 };
             };
             self.release_call(queue, call_id, DisconnectCause::SwmiRequestedDisconnection);
@@ -338,13 +430,13 @@ impl CcBsSubentity {
         sdu.seek(0);
         tracing::info!("-> {:?} sdu {}", pdu_response, sdu.dump_bin());
 
-        let msg = SapMsg {
-            sap: Sap::LcmcSap,
-            src: TetraEntity::Cmce,
-            dest: TetraEntity::Mle,
-            msg: SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
+        let msg = SapMsg::new(
+            Sap::LcmcSap,
+            TetraEntity::Cmce,
+            TetraEntity::Mle,
+            SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
                 sdu,
-                handle: prim.handle,
+                handle: self.handles.allocate(),
                 endpoint_id: prim.endpoint_id,
                 link_id: prim.link_id,
                 layer2service: Layer2Service::Acknowledged,
@@ -355,13 +447,58 @@ impl CcBsSubentity {
 
                 chan_alloc: None,
                 main_address: prim.received_tetra_address,
+                redundant_transmission: 1,
                 tx_reporter: None,
             }),
+        );
+        let _ = queue.push_back(msg);
+    }
+
+    /// Answer a U-SETUP that is rejected before a call identifier was assigned with a D-RELEASE, so the calling MS
+    /// does not wait for its setup timer to expire
+    fn send_d_release_setup_rejected(&mut self, queue: &mut MessageQueue, message: &SapMsg, disconnect_cause: DisconnectCause) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &message.msg else {
+            panic!()
         };
-        queue.push_back(msg);
+
+        let d_release = DRelease {
+            // No call identifier was assigned yet; zero is reserved and never handed out by the CircuitMgr
+            call_identifier: 0,
+            disconnect_cause,
+            notification_indicator: None,
+            facility: None,
+            proprietary: None,
+        };
+
+        let mut sdu = BitBuffer::new_autoexpand(32);
+        d_release.to_bitbuf(&mut sdu).expect("Failed to serialize DRelease");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", d_release, sdu.dump_bin());
+
+        let msg = SapMsg::new(
+            Sap::LcmcSap,
+            TetraEntity::Cmce,
+            TetraEntity::Mle,
+            SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
+                sdu,
+                handle: self.handles.allocate(),
+                endpoint_id: prim.endpoint_id,
+                link_id: prim.link_id,
+                layer2service: Layer2Service::Acknowledged,
+                pdu_prio: 0,
+                layer2_qos: 0,
+                stealing_permission: false,
+                stealing_repeats_flag: false,
+                chan_alloc: None,
+                main_address: prim.received_tetra_address,
+                redundant_transmission: 1,
+                tx_reporter: None,
+            }),
+        );
+        let _ = queue.push_back(msg);
     }
 
-    fn signal_umac_circuit_open(queue: &mut MessageQueue, call: &CmceCircuit) {
+    fn signal_umac_circuit_open(&mut self, queue: &mut MessageQueue, call: &CmceCircuit) {
         let circuit = Circuit {
             direction: call.direction,
             ts: call.ts,
@@ -370,23 +507,40 @@ impl CcBsSubentity {
             speech_service: call.speech_service,
             etee_encrypted: call.etee_encrypted,
         };
-        let cmd = SapMsg {
-            sap: Sap::Control,
-            src: TetraEntity::Cmce,
-            dest: TetraEntity::Umac,
-            msg: SapMsgInner::CmceCallControl(CallControl::Open(circuit)),
-        };
-        queue.push_back(cmd);
+        self.send_umac_call_control(queue, call.call_id, CallControl::Open(circuit));
     }
 
-    fn signal_umac_circuit_close(queue: &mut MessageQueue, circuit: CmceCircuit) {
-        let cmd = SapMsg {
-            sap: Sap::Control,
-            src: TetraEntity::Cmce,
-            dest: TetraEntity::Umac,
-            msg: SapMsgInner::CmceCallControl(CallControl::Close(circuit.direction, circuit.ts)),
-        };
-        queue.push_back(cmd);
+    fn signal_umac_circuit_close(&mut self, queue: &mut MessageQueue, circuit: CmceCircuit) {
+        self.send_umac_call_control(queue, circuit.call_id, CallControl::Close(circuit.direction, circuit.ts));
+    }
+
+    /// Send a call control command to UMAC. UMAC has to see every command, in order, to keep its circuits in line
+    /// with ours, so a command that does not fit in the queue is held back and resent on the next tick, together
+    /// with all commands after it.
+    fn send_umac_call_control(&mut self, queue: &mut MessageQueue, call_id: u16, cmd: CallControl) {
+        let msg = SapMsg::new(
+            Sap::Control,
+            TetraEntity::Cmce,
+            TetraEntity::Umac,
+            SapMsgInner::CmceCallControl(cmd),
+        )
+        .with_call_id(call_id);
+        self.umac_backlog.push_back(msg);
+        self.flush_umac_backlog(queue);
+    }
+
+    /// Resend call control commands to UMAC held back by send_umac_call_control
+    fn flush_umac_backlog(&mut self, queue: &mut MessageQueue) {
+        while let Some(msg) = self.umac_backlog.front() {
+            if queue.push_back(msg.clone()).is_err() {
+                tracing::debug!(
+                    "CcBsSubentity: {} call control commands for UMAC held back",
+                    self.umac_backlog.len()
+                );
+                return;
+            }
+            self.umac_backlog.pop_front();
+        }
     }
 
     fn rx_u_setup(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
@@ -418,8 +572,34 @@ impl CcBsSubentity {
             tracing::warn!("U-SETUP without called_party_ssi, ignoring");
             return;
         };
-        let dest_gssi = dest_gssi as u32;
-        let dest_addr = TetraAddress::new(dest_gssi, SsiType::Gssi);
+
+        // Reject reserved called party identities before any call state is created
+        let is_individual = pdu.basic_service_information.communication_type == CommunicationType::P2p;
+        let dest_type = if is_individual { SsiType::Issi } else { SsiType::Gssi };
+        let dest_addr = match TetraAddress::try_new(dest_gssi as u32, dest_type) {
+            Ok(addr) => addr,
+            Err(e) => {
+                tracing::warn!(
+                    "CMCE: rejecting U-SETUP from issi={}: invalid called party: {}",
+                    calling_party.ssi,
+                    e
+                );
+                self.send_d_release_setup_rejected(queue, &message, DisconnectCause::UnknownTetraIdentity);
+                return;
+            }
+        };
+
+        match pdu.basic_service_information.communication_type {
+            // Individual calls are offered to the called MS first, the traffic channel is only assigned once it answers
+            CommunicationType::P2p => {
+                self.rx_u_setup_individual(queue, &message, &pdu, dest_addr.ssi);
+                return;
+            }
+            // Acknowledged group calls and broadcast calls are set up as plain group calls
+            CommunicationType::P2Mp | CommunicationType::P2MpAcked | CommunicationType::Broadcast => {}
+        }
+
+        let dest_gssi = dest_addr.ssi;
 
         if !self.has_listener(dest_gssi) {
             tracing::info!(
@@ -430,23 +610,37 @@ impl CcBsSubentity {
             return;
         }
 
+        // A group member entering an ongoing call may not be synchronized to its traffic channel yet
+        if let Some((&call_id, call)) = self.active_calls.iter_mut().find(|(_, c)| c.dest_gssi == dest_gssi) {
+            call.active_subscribers.insert(calling_party.ssi);
+            tracing::info!(
+                "rx_u_setup: late entry of ISSI {} into call_id={} on ts={}",
+                calling_party.ssi,
+                call_id,
+                call.ts
+            );
+            let ts = call.ts;
+            self.send_umac_call_control(queue, call_id, CallControl::LateEntry { call_id, ts });
+        }
+
+        let emergency = pdu.call_priority >= EMERGENCY_CALL_PRIORITY;
+        if emergency {
+            tracing::warn!("CMCE: EMERGENCY call from issi={} to gssi={}", calling_party.ssi, dest_gssi);
+        }
+
         // Allocate circuit (DL+UL for group call)
-        let circuit = match {
-            let mut state = self.config.state_write();
-            self.circuits.allocate_circuit_with_allocator(
-                Direction::Both,
-                pdu.basic_service_information.communication_type,
-                &mut state.timeslot_alloc,
-                TimeslotOwner::Cmce,
-            )
-        } {
-            Ok(circuit) => circuit.clone(),
+        let circuit = match self.allocate_circuit(queue, pdu.basic_service_information.communication_type, pdu.call_priority) {
+            Ok(circuit) => circuit,
             Err(e) => {
                 tracing::error!("Failed to allocate circuit for U-SETUP: {:?}", e);
                 return;
             }
         };
 
+        // Everything from here on belongs to the call, see the module doc for the span hierarchy
+        let span = Self::call_span(circuit.call_id, dest_gssi);
+        let _enter = span.enter();
+
         tracing::info!(
             "rx_u_setup: call from ISSI {} to GSSI {} → ts={} call_id={} usage={}",
             calling_party.ssi,
@@ -457,7 +651,7 @@ impl CcBsSubentity {
         );
 
         // Signal UMAC to open DL+UL circuits
-        Self::signal_umac_circuit_open(queue, &circuit);
+        self.signal_umac_circuit_open(queue, &circuit);
 
         // Build channel allocation timeslot mask for this call
         let mut timeslots = [false; 4];
@@ -502,11 +696,11 @@ impl CcBsSubentity {
         connect_sdu.seek(0);
         tracing::info!("-> {:?} sdu {}", d_connect, connect_sdu.dump_bin());
 
-        let connect_msg = SapMsg {
-            sap: Sap::LcmcSap,
-            src: TetraEntity::Cmce,
-            dest: TetraEntity::Mle,
-            msg: SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
+        let connect_msg = SapMsg::new(
+            Sap::LcmcSap,
+            TetraEntity::Cmce,
+            TetraEntity::Mle,
+            SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
                 sdu: connect_sdu,
                 handle: ul_handle,
                 endpoint_id: ul_endpoint_id,
@@ -524,10 +718,12 @@ impl CcBsSubentity {
                     ul_dl_assigned: UlDlAssignment::Both,
                 }),
                 main_address: calling_party,
+                redundant_transmission: 1,
                 tx_reporter: None,
             }),
-        };
-        queue.push_back(connect_msg);
+        )
+        .with_call_id(circuit.call_id);
+        let _ = queue.push_back(connect_msg);
 
         // === 3) Send D-SETUP to group (broadcast on MCCH with channel allocation) ===
         // GrantedToOtherUser tells other group members that someone else has the floor.
@@ -557,8 +753,9 @@ impl CcBsSubentity {
         let (d_setup_ref, _, _) = self.cached_setups.get(&circuit.call_id).unwrap();
 
         let (setup_sdu, setup_chan_alloc) = Self::build_d_setup_prim(d_setup_ref, circuit.usage, circuit.ts, UlDlAssignment::Both);
-        let setup_msg = Self::build_sapmsg(setup_sdu, Some(setup_chan_alloc), dest_addr, Layer2Service::Unacknowledged, None);
-        queue.push_back(setup_msg);
+        let setup_msg = Self::build_sapmsg(setup_sdu, Some(setup_chan_alloc), dest_addr, Layer2Service::Unacknowledged, None)
+            .with_call_id(circuit.call_id);
+        let _ = queue.push_back(setup_msg);
 
         // Track the active local call — caller is granted the floor, so tx_active = true
         self.active_calls.insert(
@@ -571,28 +768,419 @@ impl CcBsSubentity {
                 source_issi: calling_party.ssi,
                 ts: circuit.ts,
                 usage: circuit.usage,
+                priority: pdu.call_priority,
                 tx_active: true,
                 hangtime_start: None,
                 brew_uuid: None,
+                active_subscribers: HashSet::from([calling_party.ssi]),
+                span: span.clone(),
             },
         );
 
         // Notify Brew entity about this local call if Brew is loaded and the SSI is cleared for Brew
         // It can then forward to TetraPack if the group is subscribed
         if net_brew::is_brew_gssi_routable(&self.config, dest_gssi) {
-            let msg = SapMsg {
-                sap: Sap::Control,
-                src: TetraEntity::Cmce,
-                dest: TetraEntity::Brew,
-                msg: SapMsgInner::CmceCallControl(CallControl::FloorGranted {
+            let msg = SapMsg::new(
+                Sap::Control,
+                TetraEntity::Cmce,
+                TetraEntity::Brew,
+                SapMsgInner::CmceCallControl(CallControl::FloorGranted {
                     call_id: circuit.call_id,
                     source_issi: calling_party.ssi,
                     dest_gssi,
                     ts: circuit.ts,
+                    priority: pdu.call_priority,
                 }),
+            )
+            .with_call_id(circuit.call_id);
+            let _ = queue.push_back(msg);
+
+            if emergency {
+                let _ = queue.push_back(SapMsg::new(
+                    Sap::Control,
+                    TetraEntity::Cmce,
+                    TetraEntity::Brew,
+                    SapMsgInner::CmceEmergencyCall(CmceEmergencyCall {
+                        issi: calling_party.ssi,
+                        gssi: dest_gssi,
+                    }),
+                ));
+            }
+        }
+    }
+
+    /// Set up an individual call between two local MSes (ETSI 14.5.1.1)
+    /// The calling MS gets D-CALL-PROCEEDING, the called MS gets D-SETUP and T310 is started.
+    /// D-ALERT and D-CONNECT follow once the called MS sends U-ALERT and U-CONNECT.
+    fn rx_u_setup_individual(&mut self, queue: &mut MessageQueue, message: &SapMsg, pdu: &USetup, called_issi: u32) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &message.msg else {
+            panic!()
+        };
+        let calling_party = prim.received_tetra_address;
+        let called_addr = TetraAddress::new(called_issi, SsiType::Issi);
+
+        if !self.config.state_read().subscribers.is_registered(called_issi) {
+            tracing::info!(
+                "CMCE: rejecting individual U-SETUP from issi={} to issi={} (not registered)",
+                calling_party.ssi,
+                called_issi
+            );
+            self.send_d_release_setup_rejected(queue, message, DisconnectCause::CalledPartyNotReachable);
+            return;
+        }
+
+        // Reserve the circuit now so the call identifier is known, UMAC is only told once the called MS answers
+        let circuit = match self.allocate_circuit(queue, CommunicationType::P2p, pdu.call_priority) {
+            Ok(circuit) => circuit,
+            Err(e) => {
+                tracing::error!("Failed to allocate circuit for individual U-SETUP: {:?}", e);
+                return;
+            }
+        };
+
+        tracing::info!(
+            "rx_u_setup: individual call from ISSI {} to ISSI {} → ts={} call_id={} usage={}",
+            calling_party.ssi,
+            called_issi,
+            circuit.ts,
+            circuit.call_id,
+            circuit.usage
+        );
+
+        self.send_d_call_proceeding(queue, message, pdu, circuit.call_id);
+
+        let d_setup = DSetup {
+            call_identifier: circuit.call_id,
+            call_time_out: CallTimeout::T5m,
+            hook_method_selection: pdu.hook_method_selection,
+            simplex_duplex_selection: pdu.simplex_duplex_selection,
+            basic_service_information: pdu.basic_service_information.clone(),
+            transmission_grant: TransmissionGrant::NotGranted,
+            transmission_request_permission: false,
+            call_priority: pdu.call_priority,
+            notification_indicator: None,
+            temporary_address: None,
+            calling_party_address_ssi: Some(calling_party.ssi),
+            calling_party_extension: None,
+            external_subscriber_number: None,
+            facility: None,
+            dm_ms_address: None,
+            proprietary: None,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(80);
+        d_setup.to_bitbuf(&mut sdu).expect("Failed to serialize DSetup");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", d_setup, sdu.dump_bin());
+        let _ = queue.push_back(Self::build_sapmsg(sdu, None, called_addr, Layer2Service::Unacknowledged, None));
+
+        self.individual_calls.insert(
+            circuit.call_id,
+            IndividualCall {
+                caller_addr: calling_party,
+                called_addr,
+                ts: circuit.ts,
+                usage: circuit.usage,
+                priority: pdu.call_priority,
+                alerting: false,
+                connected: false,
+                t310_start: Some(self.dltime),
+                disconnecting: None,
+            },
+        );
+    }
+
+    /// Handle U-ALERT: the called MS of an individual call is alerting its user
+    /// Response: send D-ALERT to the calling MS
+    fn rx_u_alert(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let sender = prim.received_tetra_address;
+
+        let pdu = match UAlert::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing U-ALERT: {:?}", e);
+                return;
+            }
+        };
+
+        let call_id = pdu.call_identifier;
+        let Some(call) = self.individual_calls.get_mut(&call_id) else {
+            tracing::warn!("U-ALERT for unknown call_id={}", call_id);
+            return;
+        };
+        if call.called_addr.ssi != sender.ssi || call.connected {
+            tracing::warn!("U-ALERT from ISSI {} unexpected for call_id={}", sender.ssi, call_id);
+            return;
+        }
+        if call.alerting {
+            tracing::debug!("U-ALERT: already alerting for call_id={}, ignoring duplicate", call_id);
+            return;
+        }
+
+        tracing::info!("U-ALERT: ISSI {} alerting on call_id={}", sender.ssi, call_id);
+        call.alerting = true;
+        let caller_addr = call.caller_addr;
+
+        let d_alert = DAlert {
+            call_identifier: call_id,
+            call_time_out_set_up_phase: CallTimeoutSetupPhase::T60s.into_raw() as u8,
+            reserved: true, // Hook on/Hook off signalling, see note 1 of Clause 14.7.1.1
+            simplex_duplex_selection: pdu.simplex_duplex_selection,
+            call_queued: false,
+            basic_service_information: None, // Only needed if different from requested
+            notification_indicator: None,
+            facility: None,
+            proprietary: None,
+        };
+
+        let mut sdu = BitBuffer::new_autoexpand(30);
+        d_alert.to_bitbuf(&mut sdu).expect("Failed to serialize DAlert");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", d_alert, sdu.dump_bin());
+        let _ = queue.push_back(Self::build_sapmsg(sdu, None, caller_addr, Layer2Service::Unacknowledged, None));
+    }
+
+    /// Handle U-CONNECT: the called MS of an individual call answered
+    /// Response: stop T310, open the circuit and send D-CONNECT to the calling MS and
+    /// D-CONNECT ACKNOWLEDGE to the called MS, both carrying the traffic channel allocation
+    fn rx_u_connect(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let sender = prim.received_tetra_address;
+
+        let pdu = match UConnect::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing U-CONNECT: {:?}", e);
+                return;
+            }
+        };
+
+        let call_id = pdu.call_identifier;
+
+        // A group member joining the call, nothing to answer
+        if let Some(call) = self.active_calls.get_mut(&call_id) {
+            tracing::info!("U-CONNECT: ISSI {} joined group call_id={}", sender.ssi, call_id);
+            call.active_subscribers.insert(sender.ssi);
+            return;
+        }
+
+        let Some(call) = self.individual_calls.get_mut(&call_id) else {
+            tracing::warn!("U-CONNECT for unknown call_id={}", call_id);
+            return;
+        };
+        if call.called_addr.ssi != sender.ssi || call.connected {
+            tracing::warn!("U-CONNECT from ISSI {} unexpected for call_id={}", sender.ssi, call_id);
+            return;
+        }
+
+        tracing::info!("U-CONNECT: ISSI {} answered call_id={}", sender.ssi, call_id);
+        call.connected = true;
+        call.t310_start = None;
+        let call = call.clone();
+
+        let Some(circuit) = self.circuits.dl[call.ts as usize - 1].clone() else {
+            tracing::error!("No circuit for individual call_id={} on ts={}", call_id, call.ts);
+            return;
+        };
+        self.signal_umac_circuit_open(queue, &circuit);
+
+        let mut timeslots = [false; 4];
+        timeslots[call.ts as usize - 1] = true;
+        let chan_alloc = CmceChanAllocReq {
+            usage: Some(call.usage),
+            alloc_type: ChanAllocType::Replace,
+            carrier: None,
+            timeslots,
+            ul_dl_assigned: UlDlAssignment::Both,
+        };
+
+        // The calling MS is the call owner and gets the floor first
+        let d_connect = DConnect {
+            call_identifier: call_id,
+            call_time_out: CallTimeout::T5m,
+            hook_method_selection: pdu.hook_method_selection,
+            simplex_duplex_selection: pdu.simplex_duplex_selection,
+            transmission_grant: TransmissionGrant::Granted,
+            transmission_request_permission: false,
+            call_ownership: true,
+            call_priority: None,
+            basic_service_information: None,
+            temporary_address: None,
+            notification_indicator: None,
+            facility: None,
+            proprietary: None,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(30);
+        d_connect.to_bitbuf(&mut sdu).expect("Failed to serialize DConnect");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", d_connect, sdu.dump_bin());
+        let _ = queue.push_back(Self::build_sapmsg(
+            sdu,
+            Some(chan_alloc.clone()),
+            call.caller_addr,
+            Layer2Service::Unacknowledged,
+            None,
+        ));
+
+        let d_connect_ack = DConnectAcknowledge {
+            call_identifier: call_id,
+            call_time_out: CallTimeout::T5m.into_raw() as u8,
+            transmission_grant: TransmissionGrant::GrantedToOtherUser.into_raw() as u8,
+            transmission_request_permission: false,
+            notification_indicator: None,
+            facility: None,
+            proprietary: None,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(30);
+        d_connect_ack.to_bitbuf(&mut sdu).expect("Failed to serialize DConnectAcknowledge");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", d_connect_ack, sdu.dump_bin());
+        let _ = queue.push_back(Self::build_sapmsg(
+            sdu,
+            Some(chan_alloc),
+            call.called_addr,
+            Layer2Service::Unacknowledged,
+            None,
+        ));
+    }
+
+    /// Release any individual calls where the called MS did not answer before T310 expired
+    fn check_t310_expiry(&mut self, queue: &mut MessageQueue) {
+        let expired: Vec<u16> = self
+            .individual_calls
+            .iter()
+            .filter_map(|(&call_id, call)| match call.t310_start {
+                Some(t310_start) if t310_start.add_timeslots(T310_TIMESLOTS).is_before(self.dltime) => Some(call_id),
+                _ => None,
+            })
+            .collect();
+
+        for call_id in expired {
+            tracing::info!("T310 expired for individual call_id={}, called party did not answer", call_id);
+            self.release_individual_call(queue, call_id, DisconnectCause::ExpiryOfTimer, None);
+        }
+    }
+
+    /// Force-release any individual calls where the other party did not answer D-DISCONNECT before T309 expired
+    fn check_t309_expiry(&mut self, queue: &mut MessageQueue) {
+        let expired: Vec<u16> = self
+            .individual_calls
+            .iter()
+            .filter_map(|(&call_id, call)| match call.disconnecting {
+                Some((_, t309_start)) if t309_start.add_timeslots(T309_TIMESLOTS).is_before(self.dltime) => Some(call_id),
+                _ => None,
+            })
+            .collect();
+
+        for call_id in expired {
+            tracing::info!("T309 expired for individual call_id={}, force-releasing both parties", call_id);
+            self.release_individual_call(queue, call_id, DisconnectCause::ExpiryOfTimer, None);
+        }
+    }
+
+    /// Start the disconnect of an individual call on request of `sender` (ETSI 14.5.1.3)
+    /// The other party gets D-DISCONNECT and T309 is started. If the other party sent U-DISCONNECT
+    /// itself in the meantime, both want to leave and the call is released right away.
+    fn disconnect_individual_call(&mut self, queue: &mut MessageQueue, call_id: u16, sender: u32, disconnect_cause: DisconnectCause) {
+        let Some(call) = self.individual_calls.get_mut(&call_id) else {
+            return;
+        };
+
+        if let Some((initiator, _)) = call.disconnecting {
+            if initiator == sender {
+                tracing::debug!("U-DISCONNECT: already disconnecting call_id={}, ignoring duplicate", call_id);
+            } else {
+                tracing::info!("U-DISCONNECT: both parties disconnecting call_id={}, releasing", call_id);
+                self.release_individual_call(queue, call_id, disconnect_cause, None);
+            }
+            return;
+        }
+
+        call.disconnecting = Some((sender, self.dltime));
+        call.t310_start = None;
+        let call = call.clone();
+        let other_addr = if call.caller_addr.ssi == sender {
+            call.called_addr
+        } else {
+            call.caller_addr
+        };
+
+        let pdu = DDisconnect {
+            call_identifier: call_id,
+            disconnect_cause,
+            notification_indicator: None,
+            facility: None,
+            proprietary: None,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut sdu).expect("Failed to serialize DDisconnect");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", pdu, sdu.dump_bin());
+
+        let prim = if call.connected {
+            Self::build_sapmsg_stealing(sdu, other_addr, call.ts)
+        } else {
+            Self::build_sapmsg(sdu, None, other_addr, Layer2Service::Unacknowledged, None)
+        };
+        let _ = queue.push_back(prim);
+    }
+
+    /// Release an individual call: send D-RELEASE to the parties, close the circuit and clean up state.
+    /// `released_ssi` is a party that already released the call itself and gets no D-RELEASE.
+    fn release_individual_call(
+        &mut self,
+        queue: &mut MessageQueue,
+        call_id: u16,
+        disconnect_cause: DisconnectCause,
+        released_ssi: Option<u32>,
+    ) {
+        let Some(call) = self.individual_calls.remove(&call_id) else {
+            tracing::error!("No individual call for call_id={}", call_id);
+            return;
+        };
+
+        for addr in [call.caller_addr, call.called_addr] {
+            if released_ssi == Some(addr.ssi) {
+                continue;
+            }
+            let pdu = DRelease {
+                call_identifier: call_id,
+                disconnect_cause,
+                notification_indicator: None,
+                facility: None,
+                proprietary: None,
+            };
+            let mut sdu = BitBuffer::new_autoexpand(32);
+            pdu.to_bitbuf(&mut sdu).expect("Failed to serialize DRelease");
+            sdu.seek(0);
+            tracing::info!("-> {:?} sdu {}", pdu, sdu.dump_bin());
+
+            let prim = if call.connected {
+                Self::build_sapmsg_stealing(sdu, addr, call.ts)
+            } else {
+                Self::build_sapmsg(sdu, None, addr, Layer2Service::Unacknowledged, None)
             };
-            queue.push_back(msg);
+            let _ = queue.push_back(prim);
         }
+
+        // UMAC only knows about the circuit once the called MS answered
+        if let Ok(circuit) = self.circuits.close_circuit(Direction::Both, call.ts)
+            && call.connected
+        {
+            self.signal_umac_circuit_close(queue, circuit);
+            self.send_umac_call_control(queue, call_id, CallControl::CallEnded { call_id, ts: call.ts });
+        }
+        self.release_timeslot(call.ts);
     }
 
     pub fn route_xx_deliver(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
@@ -617,11 +1205,9 @@ impl CcBsSubentity {
             CmcePduTypeUl::UTxDemand => self.rx_u_tx_demand(_queue, message),
             CmcePduTypeUl::URelease => self.rx_u_release(_queue, message),
             CmcePduTypeUl::UDisconnect => self.rx_u_disconnect(_queue, message),
-            CmcePduTypeUl::UAlert
-            | CmcePduTypeUl::UConnect
-            | CmcePduTypeUl::UInfo
-            | CmcePduTypeUl::UStatus
-            | CmcePduTypeUl::UCallRestore => {
+            CmcePduTypeUl::UAlert => self.rx_u_alert(_queue, message),
+            CmcePduTypeUl::UConnect => self.rx_u_connect(_queue, message),
+            CmcePduTypeUl::UInfo | CmcePduTypeUl::UStatus | CmcePduTypeUl::UCallRestore => {
                 unimplemented_log!("{}", pdu_type);
             }
             _ => {
@@ -633,13 +1219,29 @@ impl CcBsSubentity {
     pub fn tick_start(&mut self, queue: &mut MessageQueue, dltime: TdmaTime) {
         self.dltime = dltime;
 
+        // Resend call control commands UMAC did not get yet, before any new ones
+        self.flush_umac_backlog(queue);
+
+        // Close circuits of released calls whose D-RELEASE is out
+        self.check_pending_releases(queue);
+
         // Check hangtime expiry for active local calls
         self.check_hangtime_expiry(queue);
+        self.check_restored_timeslots();
+
+        // Check whether called parties of individual calls answered in time
+        self.check_t310_expiry(queue);
+        self.check_t309_expiry(queue);
 
         if let Some(tasks) = self.circuits.tick_start(dltime) {
             for task in tasks {
                 match task {
                     CircuitMgrCmd::SendDSetup(call_id, usage, ts) => {
+                        // Individual calls are not announced on the MCCH, the called MS got its own D-SETUP
+                        if self.individual_calls.contains_key(&call_id) {
+                            continue;
+                        }
+
                         // Skip late-entry D-SETUP during hangtime. The traffic channel is still
                         // allocated and sending D-SETUP with NotGranted can prevent floor requests.
                         if let Some(active) = self.active_calls.get(&call_id) {
@@ -686,19 +1288,29 @@ impl CcBsSubentity {
                         // Create a fresh txreporter for this re-send
                         let reporter = TxReporter::new();
 
-                        let prim = Self::build_sapmsg(sdu, Some(chan_alloc), dest_addr, Layer2Service::Unacknowledged, Some(reporter));
-                        queue.push_back(prim);
+                        let prim = Self::build_sapmsg(sdu, Some(chan_alloc), dest_addr, Layer2Service::Unacknowledged, Some(reporter))
+                            .with_call_id(call_id);
+                        let _ = queue.push_back(prim);
                     }
 
                     CircuitMgrCmd::SendClose(call_id, circuit) => {
                         tracing::warn!("need to send CLOSE for call id {}", call_id);
+                        if self.individual_calls.contains_key(&call_id) {
+                            // CircuitMgr already dropped the circuit, so release_individual_call won't close it again
+                            if self.individual_calls.get(&call_id).is_some_and(|call| call.connected) {
+                                self.signal_umac_circuit_close(queue, circuit);
+                            }
+                            self.release_individual_call(queue, call_id, DisconnectCause::ExpiryOfTimer, None);
+                            continue;
+                        }
+
                         let ts = circuit.ts;
                         // Get our cached D-SETUP, build D-RELEASE and send
                         if let Some((pdu, dest_addr, _)) = self.cached_setups.get(&call_id) {
                             let dest_addr = *dest_addr;
                             let sdu = Self::build_d_release_from_d_setup(pdu, DisconnectCause::ExpiryOfTimer);
-                            let prim = Self::build_sapmsg(sdu, None, dest_addr, Layer2Service::Unacknowledged, None);
-                            queue.push_back(prim);
+                            let prim = Self::build_sapmsg(sdu, None, dest_addr, Layer2Service::Unacknowledged, None).with_call_id(call_id);
+                            let _ = queue.push_back(prim);
                         } else {
                             tracing::error!("No cached D-SETUP for call id {}", call_id);
                         }
@@ -708,7 +1320,7 @@ impl CcBsSubentity {
                         self.active_calls.remove(&call_id);
 
                         // Signal UMAC to release the circuit
-                        Self::signal_umac_circuit_close(queue, circuit);
+                        self.signal_umac_circuit_close(queue, circuit);
                         self.release_timeslot(ts);
                     }
                 }
@@ -721,28 +1333,65 @@ impl CcBsSubentity {
         // Hangtime: 5 multiframes = ~5 seconds
         const HANGTIME_FRAMES: i32 = 5 * 18 * 4;
 
-        let expired: Vec<u16> = self
+        let expired: Vec<(u16, tracing::Span)> = self
             .active_calls
             .iter()
             .filter_map(|(&call_id, call)| {
                 if let Some(hangtime_start) = call.hangtime_start {
-                    if hangtime_start.age(self.dltime) > HANGTIME_FRAMES {
-                        return Some(call_id);
+                    if hangtime_start.add_timeslots(HANGTIME_FRAMES).is_before(self.dltime) {
+                        return Some((call_id, call.span.clone()));
                     }
                 }
                 None
             })
             .collect();
 
-        for call_id in expired {
-            tracing::info!("Hangtime expired for call_id={}, releasing", call_id);
-            self.release_call(queue, call_id, DisconnectCause::ExpiryOfTimer);
+        for (call_id, span) in expired {
+            span.in_scope(|| {
+                tracing::info!("Hangtime expired for call_id={}, releasing", call_id);
+                self.release_call(queue, call_id, DisconnectCause::ExpiryOfTimer);
+            });
+        }
+    }
+
+    /// Release timeslots that were in use before a restart, once MSs still on those calls have given up on them
+    fn check_restored_timeslots(&mut self) {
+        if self.restored_timeslots.is_empty() {
+            return;
+        }
+        let since = *self.restored_since.get_or_insert(self.dltime);
+        if since.add_timeslots(RESTORED_TIMESLOT_HOLD_TIMESLOTS).is_before(self.dltime) {
+            for ts in std::mem::take(&mut self.restored_timeslots) {
+                tracing::info!("Releasing timeslot ts={} restored from state file", ts);
+                self.release_timeslot(ts);
+            }
+        }
+    }
+
+    /// Close the UMAC circuits of released calls once their D-RELEASE was sent, or discarded
+    fn check_pending_releases(&mut self, queue: &mut MessageQueue) {
+        let (done, pending) = std::mem::take(&mut self.pending_releases)
+            .into_iter()
+            .partition(|release| release.reporter.is_in_final_state());
+        self.pending_releases = pending;
+        self.close_released_circuits(queue, done);
+    }
+
+    /// Close the UMAC circuits of released calls and have UMAC end their calls
+    fn close_released_circuits(&mut self, queue: &mut MessageQueue, releases: Vec<PendingRelease>) {
+        for release in releases {
+            let PendingRelease { call_id, ts, circuit, .. } = release;
+            if let Some(circuit) = circuit {
+                self.signal_umac_circuit_close(queue, circuit);
+            }
+
+            // Ensure UMAC clears hangtime even if the CMCE circuit was already closed before
+            self.send_umac_call_control(queue, call_id, CallControl::CallEnded { call_id, ts });
         }
     }
 
     fn release_timeslot(&mut self, ts: u8) {
-        let mut state = self.config.state_write();
-        if let Err(err) = state.timeslot_alloc.release(TimeslotOwner::Cmce, ts) {
+        if let Err(err) = self.config.update_timeslot_alloc(|alloc| alloc.release(TimeslotOwner::Cmce, ts)) {
             tracing::warn!("CcBsSubentity: failed to release timeslot ts={} err={:?}", ts, err);
         }
     }
@@ -754,52 +1403,59 @@ impl CcBsSubentity {
             return;
         };
         let dest_addr = *dest_addr;
+        let span = self.active_calls.get(&call_id).map(|call| call.span.clone());
+        let _enter = span.as_ref().map(|span| span.enter());
 
-        // Send D-RELEASE to group
+        // Send D-RELEASE to group on the traffic channel. Repeat it, as MSs that miss it stay in the call
+        // until their own timers expire.
         let sdu = Self::build_d_release_from_d_setup(pdu, disconnect_cause);
-        let prim = if let Some(ts) = self.active_calls.get(&call_id).map(|c| c.ts) {
-            Self::build_sapmsg_stealing(sdu, dest_addr, ts)
-        } else {
+        let repeats = self.config.config().cell.d_release_repeat;
+        let Some(call) = self.active_calls.get(&call_id) else {
             tracing::warn!(
                 "release_call: no active call state for call_id={}, sending D-RELEASE on MCCH",
                 call_id
             );
-            Self::build_sapmsg(sdu, None, dest_addr, Layer2Service::Unacknowledged, None)
-        };
-        queue.push_back(prim);
-
-        // Close the circuit in CircuitMgr and notify Brew
-        if let Some(call) = self.active_calls.get(&call_id) {
-            let ts = call.ts;
-            let dest_ssi = call.dest_gssi;
-            let is_local = matches!(call.origin, CallOrigin::Local { .. });
-
-            if let Ok(circuit) = self.circuits.close_circuit(Direction::Both, ts) {
-                Self::signal_umac_circuit_close(queue, circuit);
+            let mut prim = Self::build_sapmsg(sdu, None, dest_addr, Layer2Service::Unacknowledged, None).with_call_id(call_id);
+            if let SapMsgInner::LcmcMleUnitdataReq(req) = &mut prim.msg {
+                req.redundant_transmission = repeats;
             }
+            let _ = queue.push_back(prim);
+            self.cached_setups.remove(&call_id);
+            return;
+        };
+        let ts = call.ts;
+        let dest_ssi = call.dest_gssi;
+        let is_local = matches!(call.origin, CallOrigin::Local { .. });
 
-            // Ensure UMAC clears hangtime even if the CMCE circuit was already closed above.
-            queue.push_back(SapMsg {
-                sap: Sap::Control,
-                src: TetraEntity::Cmce,
-                dest: TetraEntity::Umac,
-                msg: SapMsgInner::CmceCallControl(CallControl::CallEnded { call_id, ts }),
-            });
+        let reporter = TxReporter::new_unacked();
+        let mut prim = Self::build_sapmsg_stealing(sdu, dest_addr, ts).with_call_id(call_id);
+        if let SapMsgInner::LcmcMleUnitdataReq(req) = &mut prim.msg {
+            req.redundant_transmission = repeats;
+            req.tx_reporter = Some(reporter.clone());
+        }
+        let _ = queue.push_back(prim);
 
-            self.release_timeslot(ts);
-
-            // Notify Brew only for local calls on SSIs that are cleared for Brew
-            if net_brew::is_brew_gssi_routable(&self.config, dest_ssi) {
-                if is_local {
-                    let notify = SapMsg {
-                        sap: Sap::Control,
-                        src: TetraEntity::Cmce,
-                        dest: TetraEntity::Brew,
-                        msg: SapMsgInner::CmceCallControl(CallControl::CallEnded { call_id, ts }),
-                    };
-                    queue.push_back(notify);
-                }
-            }
+        // Free the circuit in CircuitMgr. UMAC keeps it until the D-RELEASE is sent, as it drops stolen
+        // blocks queued on a timeslot that is no longer a traffic channel.
+        let circuit = self.circuits.close_circuit(Direction::Both, ts).ok();
+        self.pending_releases.push(PendingRelease {
+            call_id,
+            ts,
+            circuit,
+            reporter,
+        });
+        self.release_timeslot(ts);
+
+        // Notify Brew only for local calls on SSIs that are cleared for Brew
+        if is_local && net_brew::is_brew_gssi_routable(&self.config, dest_ssi) {
+            let notify = SapMsg::new(
+                Sap::Control,
+                TetraEntity::Cmce,
+                TetraEntity::Brew,
+                SapMsgInner::CmceCallControl(CallControl::CallEnded { call_id, ts }),
+            )
+            .with_call_id(call_id);
+            let _ = queue.push_back(notify);
         }
 
         // Clean up
@@ -807,6 +1463,173 @@ impl CcBsSubentity {
         self.active_calls.remove(&call_id);
     }
 
+    /// Allocate a DL+UL circuit for a new call. If no timeslot is free and the call is an emergency call,
+    /// the lowest-priority non-emergency call is pre-empted to make room.
+    fn allocate_circuit(
+        &mut self,
+        queue: &mut MessageQueue,
+        comm_type: CommunicationType,
+        priority: u8,
+    ) -> Result<CmceCircuit, CircuitErr> {
+        let allocated = self.try_allocate_circuit(comm_type);
+        if !matches!(allocated, Err(CircuitErr::NoCircuitFree)) || priority < EMERGENCY_CALL_PRIORITY {
+            return allocated;
+        }
+
+        // Pre-empt the lowest-priority call, the oldest call identifier goes first on a tie
+        let group_victim = self
+            .active_calls
+            .iter()
+            .filter(|(_, call)| call.priority < EMERGENCY_CALL_PRIORITY)
+            .map(|(call_id, call)| (call.priority, *call_id, false));
+        let individual_victim = self
+            .individual_calls
+            .iter()
+            .filter(|(_, call)| call.priority < EMERGENCY_CALL_PRIORITY)
+            .map(|(call_id, call)| (call.priority, *call_id, true));
+        let Some((victim_priority, victim_call_id, individual)) = group_victim.chain(individual_victim).min() else {
+            tracing::warn!("CMCE: no free circuit for emergency call and no call to pre-empt");
+            return allocated;
+        };
+
+        tracing::warn!(
+            "CMCE: pre-empting call_id={} priority={} for emergency call",
+            victim_call_id,
+            victim_priority
+        );
+        if individual {
+            self.release_individual_call(queue, victim_call_id, DisconnectCause::PreEmptiveUseOfResource, None);
+        } else {
+            self.release_group_call_forced(queue, victim_call_id, DisconnectCause::PreEmptiveUseOfResource);
+        }
+
+        self.try_allocate_circuit(comm_type)
+    }
+
+    /// Release a group call on behalf of the infrastructure. A network call is ended towards Brew as well,
+    /// as no NetworkCallEnd will arrive for it.
+    fn release_group_call_forced(&mut self, queue: &mut MessageQueue, call_id: u16, disconnect_cause: DisconnectCause) {
+        if let Some(call) = self.active_calls.get(&call_id)
+            && let CallOrigin::Network { brew_uuid } = call.origin
+            && net_brew::is_brew_gssi_routable(&self.config, call.dest_gssi)
+        {
+            let _ = queue.push_back(SapMsg::new(
+                Sap::Control,
+                TetraEntity::Cmce,
+                TetraEntity::Brew,
+                SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid }),
+            ));
+        }
+        self.release_call(queue, call_id, disconnect_cause);
+    }
+
+    /// Snapshot of all active group and individual calls, sorted by call identifier
+    fn active_calls_info(&self) -> Vec<ActiveCallInfo> {
+        let group_calls = self.active_calls.iter().map(|(call_id, call)| ActiveCallInfo {
+            call_id: *call_id,
+            group: true,
+            network: matches!(call.origin, CallOrigin::Network { .. }),
+            source_ssi: call.source_issi,
+            dest_ssi: call.dest_gssi,
+            ts: call.ts,
+            priority: call.priority,
+        });
+        let individual_calls = self.individual_calls.iter().map(|(call_id, call)| ActiveCallInfo {
+            call_id: *call_id,
+            group: false,
+            network: false,
+            source_ssi: call.caller_addr.ssi,
+            dest_ssi: call.called_addr.ssi,
+            ts: call.ts,
+            priority: call.priority,
+        });
+        let mut calls: Vec<ActiveCallInfo> = group_calls.chain(individual_calls).collect();
+        calls.sort_unstable_by_key(|call| call.call_id);
+        calls
+    }
+
+    /// Reply to the sender with a snapshot of all active group and individual calls
+    pub fn rx_query_active_calls(&mut self, queue: &mut MessageQueue, sender: TetraEntity) {
+        let _ = queue.push_back(SapMsg::new(
+            Sap::Control,
+            TetraEntity::Cmce,
+            sender,
+            SapMsgInner::ActiveCallsReply(ActiveCallsReply {
+                calls: self.active_calls_info(),
+            }),
+        ));
+    }
+
+    /// Reply to the sender with the call control part of the stack state
+    pub fn rx_query_stack_state(&mut self, queue: &mut MessageQueue, sender: TetraEntity) {
+        let _ = queue.push_back(SapMsg::new(
+            Sap::Control,
+            TetraEntity::Cmce,
+            sender,
+            SapMsgInner::StackStateSnapshot(StackStateSnapshot {
+                active_calls: self.active_calls_info(),
+                ..Default::default()
+            }),
+        ));
+    }
+
+    /// Release all calls before the stack shuts down, so MSs do not stay in calls that no longer exist
+    pub fn shutdown(&mut self, queue: &mut MessageQueue) {
+        let mut individual: Vec<u16> = self.individual_calls.keys().copied().collect();
+        individual.sort_unstable();
+        for call_id in individual {
+            tracing::info!("CMCE: shutdown, releasing individual call_id={}", call_id);
+            self.release_individual_call(queue, call_id, DisconnectCause::SwmiRequestedDisconnection, None);
+        }
+
+        let mut group: Vec<u16> = self.active_calls.keys().copied().collect();
+        group.sort_unstable();
+        for call_id in group {
+            tracing::info!("CMCE: shutdown, releasing group call_id={}", call_id);
+            self.release_group_call_forced(queue, call_id, DisconnectCause::SwmiRequestedDisconnection);
+        }
+
+        // The stack stops ticking, so the circuits cannot wait for their D-RELEASE to be sent
+        let releases = std::mem::take(&mut self.pending_releases);
+        self.close_released_circuits(queue, releases);
+    }
+
+    /// Release a call on request of the operator and confirm to the sender
+    pub fn rx_release_call_req(&mut self, queue: &mut MessageQueue, sender: TetraEntity, call_id: u16) {
+        let released = if self.individual_calls.contains_key(&call_id) {
+            self.release_individual_call(queue, call_id, DisconnectCause::SwmiRequestedDisconnection, None);
+            true
+        } else if self.active_calls.contains_key(&call_id) {
+            self.release_group_call_forced(queue, call_id, DisconnectCause::SwmiRequestedDisconnection);
+            true
+        } else {
+            false
+        };
+        tracing::info!("CMCE: release of call_id={} requested, released={}", call_id, released);
+
+        let _ = queue.push_back(SapMsg::new(
+            Sap::Control,
+            TetraEntity::Cmce,
+            sender,
+            SapMsgInner::ReleaseCallConf(ReleaseCallConf { call_id, released }),
+        ));
+    }
+
+    fn try_allocate_circuit(&mut self, comm_type: CommunicationType) -> Result<CmceCircuit, CircuitErr> {
+        let circuits = &mut self.circuits;
+        let allocated = self.config.update_timeslot_alloc(|alloc| match comm_type {
+            CommunicationType::P2p => circuits.allocate_circuit_p2p(alloc, TimeslotOwner::Cmce).cloned(),
+            CommunicationType::P2Mp | CommunicationType::P2MpAcked | CommunicationType::Broadcast => circuits
+                .allocate_circuit_with_allocator(Direction::Both, comm_type, alloc, TimeslotOwner::Cmce)
+                .cloned(),
+        });
+        if let Ok(circuit) = &allocated {
+            // The new circuit replaces a released one still open in UMAC, whose D-RELEASE then goes out on the new call's FACCH
+            self.pending_releases.retain(|release| release.ts != circuit.ts);
+        }
+        allocated
+    }
+
     fn feature_check_u_setup(pdu: &USetup) -> bool {
         let mut supported = true;
 
@@ -877,6 +1700,8 @@ impl CcBsSubentity {
             tracing::warn!("U-TX CEASED for unknown call_id={}", call_id);
             return;
         };
+        let span = call.span.clone();
+        let _enter = span.enter();
 
         // Check if already in hangtime - ignore duplicate U-TX CEASED to avoid resetting timer
         if !call.tx_active && call.hangtime_start.is_some() {
@@ -914,25 +1739,23 @@ impl CcBsSubentity {
         tracing::info!("-> {:?} sdu {}", d_tx_ceased, sdu.dump_bin());
 
         // Send via FACCH (stealing channel) so radios on the traffic channel hear the beep
-        let msg = Self::build_sapmsg_stealing(sdu, dest_addr, ts);
-        queue.push_back(msg);
+        let msg = Self::build_sapmsg_stealing(sdu, dest_addr, ts).with_call_id(call_id);
+        let _ = queue.push_back(msg);
 
         // Notify UMAC to enter hangtime signalling mode on this traffic timeslot.
-        queue.push_back(SapMsg {
-            sap: Sap::Control,
-            src: TetraEntity::Cmce,
-            dest: TetraEntity::Umac,
-            msg: SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, ts }),
-        });
+        self.send_umac_call_control(queue, call_id, CallControl::FloorReleased { call_id, ts });
 
         // Notify Brew to stop forwarding audio, if this SSI is cleared for Br
         if net_brew::is_brew_gssi_routable(&self.config, dest_ssi) {
-            queue.push_back(SapMsg {
-                sap: Sap::Control,
-                src: TetraEntity::Cmce,
-                dest: TetraEntity::Brew,
-                msg: SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, ts }),
-            });
+            let _ = queue.push_back(
+                SapMsg::new(
+                    Sap::Control,
+                    TetraEntity::Cmce,
+                    TetraEntity::Brew,
+                    SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, ts }),
+                )
+                .with_call_id(call_id),
+            );
         }
     }
 
@@ -961,6 +1784,8 @@ impl CcBsSubentity {
             tracing::warn!("U-TX DEMAND for unknown call_id={}", call_id);
             return;
         };
+        let span = call.span.clone();
+        let _enter = span.enter();
 
         tracing::info!("U-TX DEMAND: ISSI {} requests floor on call_id={}", requesting_party.ssi, call_id);
 
@@ -978,6 +1803,7 @@ impl CcBsSubentity {
 
         // Grant the floor to the requesting MS
         let ts = call.ts;
+        let priority = call.priority;
         call.tx_active = true;
         call.hangtime_start = None;
         call.source_issi = requesting_party.ssi;
@@ -1016,49 +1842,57 @@ impl CcBsSubentity {
         tracing::info!("-> {:?} sdu {}", d_tx_granted_individual, sdu.dump_bin());
 
         let requesting_addr = TetraAddress::new(requesting_party.ssi, SsiType::Issi);
-        let msg = Self::build_sapmsg_stealing(sdu, requesting_addr, ts);
-        queue.push_back(msg);
+        let msg = Self::build_sapmsg_stealing(sdu, requesting_addr, ts).with_call_id(call_id);
+        let _ = queue.push_back(msg);
 
         // ETSI 14.5.2.2.1 b): Send group D-TX GRANTED (GrantedToOtherUser) to GSSI
         self.send_d_tx_granted_facch(queue, call_id, requesting_party.ssi, dest_addr.ssi, ts);
 
         // Notify UMAC to resume traffic mode (exit hangtime) for this timeslot.
-        queue.push_back(SapMsg {
-            sap: Sap::Control,
-            src: TetraEntity::Cmce,
-            dest: TetraEntity::Umac,
-            msg: SapMsgInner::CmceCallControl(CallControl::FloorGranted {
+        self.send_umac_call_control(
+            queue,
+            call_id,
+            CallControl::FloorGranted {
                 call_id,
                 source_issi: requesting_party.ssi,
                 dest_gssi: dest_addr.ssi,
                 ts,
-            }),
-        });
+                priority,
+            },
+        );
 
         // Notify Brew of speaker change (local MS taking floor)
         if net_brew::is_brew_gssi_routable(&self.config, dest_addr.ssi) {
             let Some(call) = self.active_calls.get(&call_id) else {
                 return;
             };
-            queue.push_back(SapMsg {
-                sap: Sap::Control,
-                src: TetraEntity::Cmce,
-                dest: TetraEntity::Brew,
-                msg: SapMsgInner::CmceCallControl(CallControl::FloorGranted {
-                    call_id,
-                    source_issi: requesting_party.ssi,
-                    dest_gssi: dest_addr.ssi,
-                    ts: call.ts,
-                }),
-            });
+            let _ = queue.push_back(
+                SapMsg::new(
+                    Sap::Control,
+                    TetraEntity::Cmce,
+                    TetraEntity::Brew,
+                    SapMsgInner::CmceCallControl(CallControl::FloorGranted {
+                        call_id,
+                        source_issi: requesting_party.ssi,
+                        dest_gssi: dest_addr.ssi,
+                        ts: call.ts,
+                        priority,
+                    }),
+                )
+                .with_call_id(call_id),
+            );
         }
     }
 
     /// Handle U-RELEASE: radio explicitly releases the call
+    /// For individual calls this is the answer to D-DISCONNECT, or a rejection of D-SETUP by the called MS.
+    /// The other party gets D-RELEASE, the sender already considers the call released.
+    /// With `cmce.teardown_on_empty`, a group call is only released once all its active subscribers left.
     fn rx_u_release(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
             panic!()
         };
+        let sender = prim.received_tetra_address;
 
         let pdu = match URelease::from_bitbuf(&mut prim.sdu) {
             Ok(pdu) => {
@@ -1073,6 +1907,30 @@ impl CcBsSubentity {
 
         let call_id = pdu.call_identifier;
         tracing::info!("U-RELEASE: call_id={} cause={}", call_id, pdu.disconnect_cause);
+        if let Some(call) = self.individual_calls.get(&call_id) {
+            if call.caller_addr.ssi != sender.ssi && call.called_addr.ssi != sender.ssi {
+                tracing::warn!("U-RELEASE from ISSI {} not party to individual call_id={}", sender.ssi, call_id);
+                return;
+            }
+            self.release_individual_call(queue, call_id, pdu.disconnect_cause, Some(sender.ssi));
+            return;
+        }
+
+        if self.config.config().cmce.teardown_on_empty
+            && let Some(call) = self.active_calls.get_mut(&call_id)
+        {
+            call.active_subscribers.remove(&sender.ssi);
+            if !call.active_subscribers.is_empty() {
+                tracing::info!(
+                    "U-RELEASE: ISSI {} left call_id={}, {} subscriber(s) remaining",
+                    sender.ssi,
+                    call_id,
+                    call.active_subscribers.len()
+                );
+                return;
+            }
+            tracing::info!("U-RELEASE: last subscriber ISSI {} left call_id={}, releasing", sender.ssi, call_id);
+        }
         self.release_call(queue, call_id, DisconnectCause::UserRequestedDisconnection);
     }
 
@@ -1102,6 +1960,17 @@ impl CcBsSubentity {
         let call_id = pdu.call_identifier;
         let disconnect_cause = pdu.disconnect_cause;
 
+        // Either party may clear an individual call
+        if let Some(call) = self.individual_calls.get(&call_id) {
+            if call.caller_addr.ssi == sender.ssi || call.called_addr.ssi == sender.ssi {
+                tracing::info!("U-DISCONNECT: ISSI {} disconnecting individual call_id={}", sender.ssi, call_id);
+                self.disconnect_individual_call(queue, call_id, sender.ssi, disconnect_cause);
+            } else {
+                tracing::warn!("U-DISCONNECT from ISSI {} not party to individual call_id={}", sender.ssi, call_id);
+            }
+            return;
+        }
+
         let Some(call) = self.active_calls.get(&call_id) else {
             tracing::debug!("U-DISCONNECT for unknown call_id={} (likely duplicate)", call_id);
             return;
@@ -1137,11 +2006,11 @@ impl CcBsSubentity {
             tracing::info!("-> {:?} sdu {}", d_release, sdu.dump_bin());
 
             let sender_addr = TetraAddress::new(sender.ssi, SsiType::Issi);
-            let msg = SapMsg {
-                sap: Sap::LcmcSap,
-                src: TetraEntity::Cmce,
-                dest: TetraEntity::Mle,
-                msg: SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
+            let msg = SapMsg::new(
+                Sap::LcmcSap,
+                TetraEntity::Cmce,
+                TetraEntity::Mle,
+                SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
                     sdu,
                     handle: ul_handle,
                     endpoint_id: ul_endpoint_id,
@@ -1153,10 +2022,11 @@ impl CcBsSubentity {
                     stealing_repeats_flag: false,
                     chan_alloc: None,
                     main_address: sender_addr,
+                    redundant_transmission: 1,
                     tx_reporter: None,
                 }),
-            };
-            queue.push_back(msg);
+            );
+            let _ = queue.push_back(msg);
         }
     }
 
@@ -1188,7 +2058,7 @@ impl CcBsSubentity {
     }
 
     /// Handle network-initiated group call start
-    fn rx_network_call_start(&mut self, queue: &mut MessageQueue, brew_uuid: uuid::Uuid, source_issi: u32, dest_gssi: u32, _priority: u8) {
+    fn rx_network_call_start(&mut self, queue: &mut MessageQueue, brew_uuid: uuid::Uuid, source_issi: u32, dest_gssi: u32, priority: u8) {
         assert!(net_brew::is_brew_gssi_routable(&self.config, dest_gssi));
 
         if !self.has_listener(dest_gssi) {
@@ -1200,17 +2070,20 @@ impl CcBsSubentity {
             self.drop_group_calls_if_unlistened(queue, dest_gssi);
 
             // We already checked this is cleared for brew
-            queue.push_back(SapMsg {
-                sap: Sap::Control,
-                src: TetraEntity::Cmce,
-                dest: TetraEntity::Brew,
-                msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid }),
-            });
+            let _ = queue.push_back(SapMsg::new(
+                Sap::Control,
+                TetraEntity::Cmce,
+                TetraEntity::Brew,
+                SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid }),
+            ));
             return;
         }
 
         // Check if there is an active call for this GSSI (speaker change scenario)
         if let Some((call_id, call)) = self.active_calls.iter_mut().find(|(_, c)| c.dest_gssi == dest_gssi) {
+            let span = call.span.clone();
+            let _enter = span.enter();
+
             // Reject speaker change if a local MS is already transmitting
             if call.tx_active {
                 tracing::warn!(
@@ -1218,12 +2091,12 @@ impl CcBsSubentity {
                     call.source_issi,
                     dest_gssi
                 );
-                queue.push_back(SapMsg {
-                    sap: Sap::Control,
-                    src: TetraEntity::Cmce,
-                    dest: TetraEntity::Brew,
-                    msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid }),
-                });
+                let _ = queue.push_back(SapMsg::new(
+                    Sap::Control,
+                    TetraEntity::Cmce,
+                    TetraEntity::Brew,
+                    SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid }),
+                ));
                 return;
             }
 
@@ -1236,6 +2109,7 @@ impl CcBsSubentity {
             );
 
             call.source_issi = source_issi;
+            call.priority = priority;
             call.tx_active = true;
             call.hangtime_start = None;
             call.brew_uuid = Some(brew_uuid);
@@ -1260,44 +2134,39 @@ impl CcBsSubentity {
             self.send_d_tx_granted_facch(queue, call_id_val, source_issi, dest_gssi, ts);
 
             // Notify UMAC to resume traffic mode (exit hangtime) for this timeslot.
-            queue.push_back(SapMsg {
-                sap: Sap::Control,
-                src: TetraEntity::Cmce,
-                dest: TetraEntity::Umac,
-                msg: SapMsgInner::CmceCallControl(CallControl::FloorGranted {
+            self.send_umac_call_control(
+                queue,
+                call_id_val,
+                CallControl::FloorGranted {
                     call_id: call_id_val,
                     source_issi,
                     dest_gssi,
                     ts,
-                }),
-            });
+                    priority,
+                },
+            );
 
             // Respond to Brew with existing call resources, we already ensured it is cleared for brew
-            queue.push_back(SapMsg {
-                sap: Sap::Control,
-                src: TetraEntity::Cmce,
-                dest: TetraEntity::Brew,
-                msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallReady {
-                    brew_uuid,
-                    call_id: call_id_val,
-                    ts,
-                    usage,
-                }),
-            });
+            let _ = queue.push_back(
+                SapMsg::new(
+                    Sap::Control,
+                    TetraEntity::Cmce,
+                    TetraEntity::Brew,
+                    SapMsgInner::CmceCallControl(CallControl::NetworkCallReady {
+                        brew_uuid,
+                        call_id: call_id_val,
+                        ts,
+                        usage,
+                    }),
+                )
+                .with_call_id(call_id_val),
+            );
             return;
         }
 
         // New network call - allocate circuit
-        let circuit = match {
-            let mut state = self.config.state_write();
-            self.circuits.allocate_circuit_with_allocator(
-                Direction::Both,
-                CommunicationType::P2Mp,
-                &mut state.timeslot_alloc,
-                TimeslotOwner::Cmce,
-            )
-        } {
-            Ok(c) => c.clone(),
+        let circuit = match self.allocate_circuit(queue, CommunicationType::P2Mp, priority) {
+            Ok(c) => c,
             Err(err) => {
                 tracing::warn!("CMCE: failed to allocate circuit for network call: {:?}", err);
                 return;
@@ -1307,6 +2176,8 @@ impl CcBsSubentity {
         let call_id = circuit.call_id;
         let ts = circuit.ts;
         let usage = circuit.usage;
+        let span = Self::call_span(call_id, dest_gssi);
+        let _enter = span.enter();
 
         tracing::info!(
             "CMCE: starting NEW network call brew_uuid={} gssi={} speaker={} ts={} call_id={}",
@@ -1318,7 +2189,7 @@ impl CcBsSubentity {
         );
 
         // Signal UMAC to open DL and UL circuits
-        Self::signal_umac_circuit_open(queue, &circuit);
+        self.signal_umac_circuit_open(queue, &circuit);
 
         tracing::debug!(
             "CMCE: sending D-SETUP for NEW call call_id={} gssi={} (network-initiated)",
@@ -1342,7 +2213,7 @@ impl CcBsSubentity {
             },
             transmission_grant: TransmissionGrant::GrantedToOtherUser,
             transmission_request_permission: false,
-            call_priority: 0,
+            call_priority: priority,
             notification_indicator: None,
             temporary_address: None,
             calling_party_address_ssi: Some(source_issi),
@@ -1360,8 +2231,9 @@ impl CcBsSubentity {
         let (d_setup_ref, _, _) = self.cached_setups.get(&call_id).unwrap();
 
         let (setup_sdu, setup_chan_alloc) = Self::build_d_setup_prim(d_setup_ref, usage, ts, UlDlAssignment::Both);
-        let setup_msg = Self::build_sapmsg(setup_sdu, Some(setup_chan_alloc), dest_addr, Layer2Service::Unacknowledged, None);
-        queue.push_back(setup_msg);
+        let setup_msg =
+            Self::build_sapmsg(setup_sdu, Some(setup_chan_alloc), dest_addr, Layer2Service::Unacknowledged, None).with_call_id(call_id);
+        let _ = queue.push_back(setup_msg);
 
         // Send D-CONNECT to group
         let d_connect = DConnect {
@@ -1384,11 +2256,11 @@ impl CcBsSubentity {
         d_connect.to_bitbuf(&mut connect_sdu).expect("Failed to serialize DConnect");
         connect_sdu.seek(0);
 
-        let connect_msg = SapMsg {
-            sap: Sap::LcmcSap,
-            src: TetraEntity::Cmce,
-            dest: TetraEntity::Mle,
-            msg: SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
+        let connect_msg = SapMsg::new(
+            Sap::LcmcSap,
+            TetraEntity::Cmce,
+            TetraEntity::Mle,
+            SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
                 sdu: connect_sdu,
                 handle: 0, // Broadcast to group, no specific handle
                 endpoint_id: 0,
@@ -1400,10 +2272,12 @@ impl CcBsSubentity {
                 stealing_repeats_flag: false,
                 chan_alloc: None, // Already sent in D-SETUP
                 main_address: dest_addr,
+                redundant_transmission: 1,
                 tx_reporter: None,
             }),
-        };
-        queue.push_back(connect_msg);
+        )
+        .with_call_id(call_id);
+        let _ = queue.push_back(connect_msg);
 
         // Track the active call
         self.active_calls.insert(
@@ -1414,24 +2288,30 @@ impl CcBsSubentity {
                 source_issi,
                 ts,
                 usage,
+                priority,
                 tx_active: true,
                 hangtime_start: None,
                 brew_uuid: Some(brew_uuid),
+                active_subscribers: HashSet::new(),
+                span: span.clone(),
             },
         );
 
         // Respond to Brew with allocated resources, we already ensured it is cleared for brew
-        queue.push_back(SapMsg {
-            sap: Sap::Control,
-            src: TetraEntity::Cmce,
-            dest: TetraEntity::Brew,
-            msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallReady {
-                brew_uuid,
-                call_id,
-                ts,
-                usage,
-            }),
-        });
+        let _ = queue.push_back(
+            SapMsg::new(
+                Sap::Control,
+                TetraEntity::Cmce,
+                TetraEntity::Brew,
+                SapMsgInner::CmceCallControl(CallControl::NetworkCallReady {
+                    brew_uuid,
+                    call_id,
+                    ts,
+                    usage,
+                }),
+            )
+            .with_call_id(call_id),
+        );
     }
 
     /// Handle network call end request
@@ -1446,6 +2326,7 @@ impl CcBsSubentity {
             tracing::debug!("CMCE: network call end for unknown brew_uuid={}", brew_uuid);
             return;
         };
+        let _enter = call.span.enter();
 
         tracing::info!(
             "CMCE: network call ended brew_uuid={} call_id={} gssi={}",
@@ -1469,12 +2350,7 @@ impl CcBsSubentity {
             self.send_d_tx_ceased_facch(queue, call_id, dest_gssi, ts);
 
             // Notify UMAC to enter hangtime signalling mode on this traffic timeslot.
-            queue.push_back(SapMsg {
-                sap: Sap::Control,
-                src: TetraEntity::Cmce,
-                dest: TetraEntity::Umac,
-                msg: SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, ts }),
-            });
+            self.send_umac_call_control(queue, call_id, CallControl::FloorReleased { call_id, ts });
         } else {
             // Already in hangtime or idle, release immediately
             self.release_call(queue, call_id, DisconnectCause::SwmiRequestedDisconnection);
@@ -1505,8 +2381,8 @@ impl CcBsSubentity {
         tracing::info!("-> FACCH {:?} sdu {}", pdu, sdu.dump_bin());
 
         let dest_addr = TetraAddress::new(dest_gssi, SsiType::Gssi);
-        let msg = Self::build_sapmsg_stealing(sdu, dest_addr, ts);
-        queue.push_back(msg);
+        let msg = Self::build_sapmsg_stealing(sdu, dest_addr, ts).with_call_id(call_id);
+        let _ = queue.push_back(msg);
     }
 
     /// Handle UL inactivity timeout from UMAC: a radio disappeared mid-transmission.
@@ -1535,21 +2411,16 @@ impl CcBsSubentity {
         self.send_d_tx_ceased_facch(queue, call_id, dest_gssi, ts);
 
         // Notify UMAC to enter hangtime signalling mode
-        queue.push_back(SapMsg {
-            sap: Sap::Control,
-            src: TetraEntity::Cmce,
-            dest: TetraEntity::Umac,
-            msg: SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, ts }),
-        });
+        self.send_umac_call_control(queue, call_id, CallControl::FloorReleased { call_id, ts });
 
         // Notify Brew to stop forwarding audio
         if net_brew::is_brew_gssi_routable(&self.config, dest_gssi) {
-            queue.push_back(SapMsg {
-                sap: Sap::Control,
-                src: TetraEntity::Cmce,
-                dest: TetraEntity::Brew,
-                msg: SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, ts }),
-            });
+            let _ = queue.push_back(SapMsg::new(
+                Sap::Control,
+                TetraEntity::Cmce,
+                TetraEntity::Brew,
+                SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, ts }),
+            ));
         }
     }
 
@@ -1570,7 +2441,7 @@ impl CcBsSubentity {
         tracing::info!("-> FACCH {:?} sdu {}", pdu, sdu.dump_bin());
 
         let dest_addr = TetraAddress::new(dest_gssi, SsiType::Gssi);
-        let msg = Self::build_sapmsg_stealing(sdu, dest_addr, ts);
-        queue.push_back(msg);
+        let msg = Self::build_sapmsg_stealing(sdu, dest_addr, ts).with_call_id(call_id);
+        let _ = queue.push_back(msg);
     }
 }
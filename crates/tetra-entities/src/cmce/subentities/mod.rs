@@ -1,3 +1,11 @@
+use std::ops::RangeInclusive;
+
+use tetra_core::MleHandle;
+
+/// MLE request handles of the CC and SDS sub-entities. The ranges are disjoint, so CMCE handles are unique.
+pub const CC_MLE_HANDLES: RangeInclusive<MleHandle> = 1..=0x7FFF_FFFF;
+pub const SDS_MLE_HANDLES: RangeInclusive<MleHandle> = 0x8000_0000..=MleHandle::MAX;
+
 pub mod cc_bs;
 pub mod sds_bs;
 pub mod ss_bs;
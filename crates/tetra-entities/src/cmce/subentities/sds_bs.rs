@@ -1,6 +1,6 @@
 use tetra_config::bluestation::SharedConfig;
-use tetra_core::Layer2Service;
 use tetra_core::{BitBuffer, Sap, SsiType, TetraAddress, tetra_entities::TetraEntity, unimplemented_log};
+use tetra_core::{Layer2Service, MleHandleAllocator};
 use tetra_pdus::cmce::enums::pre_coded_status::PreCodedStatus;
 use tetra_pdus::cmce::enums::short_report_type::ShortReportType;
 use tetra_saps::control::enums::sds_user_data::SdsUserData;
@@ -14,6 +14,7 @@ use tetra_pdus::cmce::pdus::d_status::DStatus;
 use tetra_pdus::cmce::pdus::u_sds_data::USdsData;
 use tetra_pdus::cmce::pdus::u_status::UStatus;
 
+use super::SDS_MLE_HANDLES;
 use crate::MessageQueue;
 use crate::net_brew;
 use crate::net_control::ControlCommand;
@@ -21,11 +22,16 @@ use crate::net_control::ControlCommand;
 /// Clause 13 Short Data Service CMCE sub-entity
 pub struct SdsBsSubentity {
     config: SharedConfig,
+    /// Handles of our MLE-UNITDATA requests, see SDS_MLE_HANDLES
+    handles: MleHandleAllocator,
 }
 
 impl SdsBsSubentity {
     pub fn new(config: SharedConfig) -> Self {
-        SdsBsSubentity { config }
+        SdsBsSubentity {
+            config,
+            handles: MleHandleAllocator::new(SDS_MLE_HANDLES),
+        }
     }
 
     /// Handle incoming U-SDS-DATA from a local MS (via RF uplink)
@@ -76,16 +82,16 @@ impl SdsBsSubentity {
             self.send_d_sds_data(queue, source_ssi, dest_ssi, SsiType::Gssi, pdu.user_defined_data);
         } else if net_brew::feature_sds_enabled(&self.config) {
             tracing::info!("SDS: forwarding to Brew: {} -> {}", source_ssi, dest_ssi);
-            queue.push_back(SapMsg {
-                sap: Sap::Control,
-                src: TetraEntity::Cmce,
-                dest: TetraEntity::Brew,
-                msg: SapMsgInner::CmceSdsData(CmceSdsData {
+            let _ = queue.push_back(SapMsg::new(
+                Sap::Control,
+                TetraEntity::Cmce,
+                TetraEntity::Brew,
+                SapMsgInner::CmceSdsData(CmceSdsData {
                     source_issi: source_ssi,
                     dest_issi: dest_ssi,
                     user_defined_data: pdu.user_defined_data,
                 }),
-            });
+            ));
         } else {
             tracing::warn!("SDS: dest SSI {} not local and not Brew-routable, dropping", dest_ssi);
         }
@@ -224,16 +230,16 @@ impl SdsBsSubentity {
             };
 
             tracing::info!("SDS-STATUS: forwarding to Brew: {} -> {}", source_ssi, dest_ssi);
-            queue.push_back(SapMsg {
-                sap: Sap::Control,
-                src: TetraEntity::Cmce,
-                dest: TetraEntity::Brew,
-                msg: SapMsgInner::CmceSdsData(CmceSdsData {
+            let _ = queue.push_back(SapMsg::new(
+                Sap::Control,
+                TetraEntity::Cmce,
+                TetraEntity::Brew,
+                SapMsgInner::CmceSdsData(CmceSdsData {
                     source_issi: source_ssi,
                     dest_issi: dest_ssi,
                     user_defined_data,
                 }),
-            });
+            ));
         } else {
             tracing::warn!(
                 "SDS-STATUS: dest ISSI {} not locally registered and not Brew-routable, dropping",
@@ -243,7 +249,7 @@ impl SdsBsSubentity {
     }
 
     /// Build and send a D-STATUS PDU to a local MS
-    fn send_d_status(&self, queue: &mut MessageQueue, source_issi: u32, dest_issi: u32, pre_coded_status: PreCodedStatus) {
+    fn send_d_status(&mut self, queue: &mut MessageQueue, source_issi: u32, dest_issi: u32, pre_coded_status: PreCodedStatus) {
         let pdu = DStatus {
             calling_party_type_identifier: PartyTypeIdentifier::Ssi,
             calling_party_address_ssi: Some(source_issi as u64),
@@ -263,13 +269,13 @@ impl SdsBsSubentity {
         sdu.seek(0);
 
         let dest_addr = TetraAddress::new(dest_issi, SsiType::Issi);
-        let msg = SapMsg {
-            sap: Sap::LcmcSap,
-            src: TetraEntity::Cmce,
-            dest: TetraEntity::Mle,
-            msg: SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
+        let msg = SapMsg::new(
+            Sap::LcmcSap,
+            TetraEntity::Cmce,
+            TetraEntity::Mle,
+            SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
                 sdu,
-                handle: 0,
+                handle: self.handles.allocate(),
                 endpoint_id: 0,
                 link_id: 0,
                 layer2service: Layer2Service::Todo,
@@ -279,15 +285,16 @@ impl SdsBsSubentity {
                 stealing_repeats_flag: false,
                 chan_alloc: None,
                 main_address: dest_addr,
+                redundant_transmission: 1,
                 tx_reporter: None,
             }),
-        };
-        queue.push_back(msg);
+        );
+        let _ = queue.push_back(msg);
     }
 
     /// Build and send a D-SDS-DATA PDU to a local MS
     fn send_d_sds_data(
-        &self,
+        &mut self,
         queue: &mut MessageQueue,
         source_issi: u32,
         dest_ssi: u32,
@@ -318,13 +325,13 @@ impl SdsBsSubentity {
             SsiType::Gssi => Layer2Service::Unacknowledged,
             _ => panic!(),
         };
-        let msg = SapMsg {
-            sap: Sap::LcmcSap,
-            src: TetraEntity::Cmce,
-            dest: TetraEntity::Mle,
-            msg: SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
+        let msg = SapMsg::new(
+            Sap::LcmcSap,
+            TetraEntity::Cmce,
+            TetraEntity::Mle,
+            SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
                 sdu,
-                handle: 0,
+                handle: self.handles.allocate(),
                 endpoint_id: 0,
                 link_id: 0,
                 layer2service,
@@ -334,10 +341,11 @@ impl SdsBsSubentity {
                 stealing_repeats_flag: false,
                 chan_alloc: None,
                 main_address: dest_addr,
+                redundant_transmission: 1,
                 tx_reporter: None,
             }),
-        };
-        queue.push_back(msg);
+        );
+        let _ = queue.push_back(msg);
     }
 
     fn feature_check_u_sds_data(pdu: &USdsData) -> bool {
@@ -24,4 +24,8 @@ pub trait TetraEntityTrait: Send + AsAny {
     fn tick_end(&mut self, _queue: &mut MessageQueue, _ts: TdmaTime) -> bool {
         false
     }
+
+    /// Called once when the stack shuts down, in reverse registration order.
+    /// Messages pushed here are still delivered to the entities that have not been shut down yet.
+    fn shutdown(&mut self, _queue: &mut MessageQueue) {}
 }
@@ -13,9 +13,11 @@ pub mod umac;
 
 pub mod network;
 
+pub mod net_admin;
 pub mod net_brew;
 pub mod net_control;
 pub mod net_telemetry;
+pub mod net_tnmm;
 
 // Re-export commonly used items from router
 pub use entity_trait::TetraEntityTrait;
@@ -0,0 +1,246 @@
+use tetra_core::{EndpointId, LinkId, TdmaTime, TetraAddress};
+use tetra_pdus::llc::consts::consts::N262_AL_MAX_CONNECTION_SETUP_RETRIES;
+use tetra_pdus::llc::consts::timers::T261_SETUP_WAITING_TIMER;
+use tetra_pdus::llc::enums::al_setup_report::AlSetupReport;
+use tetra_pdus::llc::pdus::al_setup::AlSetup;
+
+/// Connection state of an advanced link, see Clause 22.3.3.1 for advanced link establishment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdvLinkState {
+    /// No advanced link
+    Idle,
+    /// We sent AL-SETUP after a TL-CONNECT request and wait for the peer to accept it
+    WaitSetupAccept,
+    /// The peer sent AL-SETUP, we issued a TL-CONNECT indication and wait for the TL-CONNECT response
+    PendingConnect,
+    /// Advanced link is established
+    Connected,
+}
+
+/// Events driving the advanced link state machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdvLinkEvent {
+    /// TL-CONNECT request from the service user
+    ConnectReq,
+    /// TL-CONNECT response from the service user
+    ConnectResp,
+    /// AL-SETUP received from the peer, with the given set-up report
+    SetupRx(AlSetupReport),
+    /// T.261 expired without the peer accepting our AL-SETUP
+    SetupTimeout,
+}
+
+/// Actions the LLC needs to take as a result of an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdvLinkAction {
+    /// Send AL-SETUP to the peer with the given set-up report
+    SendSetup(AlSetupReport),
+    /// Issue a TL-CONNECT indication to the service user
+    ConnectInd,
+    /// Issue a TL-CONNECT confirm to the service user
+    ConnectConf,
+    /// Set-up was abandoned after N.262 retries, the link is back to Idle
+    SetupFailed,
+    /// Event is not valid in the current state and was ignored
+    Ignore,
+}
+
+/// State for a single advanced link to an MS
+#[derive(Debug, Clone)]
+pub struct AdvLink {
+    pub addr: TetraAddress,
+    /// Identifier taken from the stack-wide LinkIdAllocator
+    pub link_id: LinkId,
+    /// Advanced link number carried in AL-SETUP
+    pub advanced_link_number: u8,
+    pub endpoint_id: EndpointId,
+    pub state: AdvLinkState,
+    /// Time the last AL-SETUP was sent, used for T.261
+    pub t_setup: Option<TdmaTime>,
+    /// Number of AL-SETUP retransmissions performed so far, bounded by N.262
+    pub setup_retries: u32,
+}
+
+impl AdvLink {
+    pub fn new(addr: TetraAddress, link_id: LinkId, advanced_link_number: u8, endpoint_id: EndpointId) -> Self {
+        Self {
+            addr,
+            link_id,
+            advanced_link_number,
+            endpoint_id,
+            state: AdvLinkState::Idle,
+            t_setup: None,
+            setup_retries: 0,
+        }
+    }
+
+    /// Feed an event into the state machine. Returns the action the LLC has to take.
+    pub fn handle(&mut self, event: AdvLinkEvent, now: TdmaTime) -> AdvLinkAction {
+        let (next_state, action) = match (self.state, event) {
+            // Locally initiated set-up, also resets an existing link
+            (AdvLinkState::Idle | AdvLinkState::Connected, AdvLinkEvent::ConnectReq) => {
+                self.setup_retries = 0;
+                self.t_setup = Some(now);
+                (
+                    AdvLinkState::WaitSetupAccept,
+                    AdvLinkAction::SendSetup(AlSetupReport::ServiceDefinition),
+                )
+            }
+
+            // Peer initiated set-up, or peer reset of an existing link
+            (
+                AdvLinkState::Idle | AdvLinkState::Connected | AdvLinkState::PendingConnect,
+                AdvLinkEvent::SetupRx(AlSetupReport::ServiceDefinition | AlSetupReport::ServiceChange),
+            ) => (AdvLinkState::PendingConnect, AdvLinkAction::ConnectInd),
+
+            (AdvLinkState::PendingConnect, AdvLinkEvent::ConnectResp) => {
+                (AdvLinkState::Connected, AdvLinkAction::SendSetup(AlSetupReport::ServiceAccepted))
+            }
+
+            (AdvLinkState::WaitSetupAccept, AdvLinkEvent::SetupRx(AlSetupReport::ServiceAccepted)) => {
+                self.t_setup = None;
+                (AdvLinkState::Connected, AdvLinkAction::ConnectConf)
+            }
+
+            (AdvLinkState::WaitSetupAccept, AdvLinkEvent::SetupTimeout) => {
+                if self.setup_retries < N262_AL_MAX_CONNECTION_SETUP_RETRIES {
+                    self.setup_retries += 1;
+                    self.t_setup = Some(now);
+                    (
+                        AdvLinkState::WaitSetupAccept,
+                        AdvLinkAction::SendSetup(AlSetupReport::ServiceDefinition),
+                    )
+                } else {
+                    self.t_setup = None;
+                    (AdvLinkState::Idle, AdvLinkAction::SetupFailed)
+                }
+            }
+
+            (state, event) => {
+                tracing::warn!("adv link SSI {}: ignoring {:?} in state {:?}", self.addr.ssi, event, state);
+                (state, AdvLinkAction::Ignore)
+            }
+        };
+
+        if next_state != self.state {
+            tracing::debug!("adv link SSI {}: {:?} -> {:?}", self.addr.ssi, self.state, next_state);
+        }
+        self.state = next_state;
+        action
+    }
+
+    /// Returns true if we are waiting for the peer to accept our AL-SETUP and T.261 has expired
+    pub fn setup_timer_expired(&self, now: TdmaTime) -> bool {
+        match (self.state, self.t_setup) {
+            (AdvLinkState::WaitSetupAccept, Some(t_setup)) => now.diff(t_setup) as u32 >= T261_SETUP_WAITING_TIMER,
+            _ => false,
+        }
+    }
+
+    /// Builds the AL-SETUP PDU for this link, proposing our default advanced link parameters
+    pub fn build_setup_pdu(&self, setup_report: AlSetupReport) -> AlSetup {
+        AlSetup {
+            advanced_link_service: 0,
+            advanced_link_number: self.advanced_link_number,
+            max_tlsdu_len: 7,
+            window_size_acked: 3,
+            max_tlsdu_retransmissions: 3,
+            max_segment_retransmissions: 3,
+            window_size_unacked: 3,
+            num_repetitions_unacked: 3,
+            setup_report,
+            num_timeslots: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::SsiType;
+
+    use super::*;
+
+    fn get_link() -> AdvLink {
+        let addr = TetraAddress {
+            ssi: 2040001,
+            ssi_type: SsiType::Issi,
+        };
+        AdvLink::new(addr, 1, 0, 0)
+    }
+
+    #[test]
+    fn test_connect_req_sends_setup() {
+        let mut link = get_link();
+        let action = link.handle(AdvLinkEvent::ConnectReq, TdmaTime::default());
+        assert_eq!(action, AdvLinkAction::SendSetup(AlSetupReport::ServiceDefinition));
+        assert_eq!(link.state, AdvLinkState::WaitSetupAccept);
+    }
+
+    #[test]
+    fn test_setup_accepted_confirms() {
+        let mut link = get_link();
+        link.handle(AdvLinkEvent::ConnectReq, TdmaTime::default());
+        let action = link.handle(AdvLinkEvent::SetupRx(AlSetupReport::ServiceAccepted), TdmaTime::default());
+        assert_eq!(action, AdvLinkAction::ConnectConf);
+        assert_eq!(link.state, AdvLinkState::Connected);
+    }
+
+    #[test]
+    fn test_peer_setup_indicates() {
+        let mut link = get_link();
+        let action = link.handle(AdvLinkEvent::SetupRx(AlSetupReport::ServiceDefinition), TdmaTime::default());
+        assert_eq!(action, AdvLinkAction::ConnectInd);
+        assert_eq!(link.state, AdvLinkState::PendingConnect);
+    }
+
+    #[test]
+    fn test_connect_resp_accepts() {
+        let mut link = get_link();
+        link.handle(AdvLinkEvent::SetupRx(AlSetupReport::ServiceDefinition), TdmaTime::default());
+        let action = link.handle(AdvLinkEvent::ConnectResp, TdmaTime::default());
+        assert_eq!(action, AdvLinkAction::SendSetup(AlSetupReport::ServiceAccepted));
+        assert_eq!(link.state, AdvLinkState::Connected);
+    }
+
+    #[test]
+    fn test_peer_reset_of_connected_link() {
+        let mut link = get_link();
+        link.handle(AdvLinkEvent::SetupRx(AlSetupReport::ServiceDefinition), TdmaTime::default());
+        link.handle(AdvLinkEvent::ConnectResp, TdmaTime::default());
+        let action = link.handle(AdvLinkEvent::SetupRx(AlSetupReport::ServiceDefinition), TdmaTime::default());
+        assert_eq!(action, AdvLinkAction::ConnectInd);
+        assert_eq!(link.state, AdvLinkState::PendingConnect);
+    }
+
+    #[test]
+    fn test_invalid_events_ignored() {
+        let mut link = get_link();
+        assert_eq!(link.handle(AdvLinkEvent::ConnectResp, TdmaTime::default()), AdvLinkAction::Ignore);
+        assert_eq!(
+            link.handle(AdvLinkEvent::SetupRx(AlSetupReport::ServiceAccepted), TdmaTime::default()),
+            AdvLinkAction::Ignore
+        );
+        assert_eq!(link.state, AdvLinkState::Idle);
+    }
+
+    #[test]
+    fn test_setup_timeout_retries_then_fails() {
+        let mut link = get_link();
+        let mut now = TdmaTime::default();
+        link.handle(AdvLinkEvent::ConnectReq, now);
+        assert!(!link.setup_timer_expired(now));
+
+        for _ in 0..N262_AL_MAX_CONNECTION_SETUP_RETRIES {
+            now = now.add_timeslots(T261_SETUP_WAITING_TIMER as i32);
+            assert!(link.setup_timer_expired(now));
+            let action = link.handle(AdvLinkEvent::SetupTimeout, now);
+            assert_eq!(action, AdvLinkAction::SendSetup(AlSetupReport::ServiceDefinition));
+            assert_eq!(link.state, AdvLinkState::WaitSetupAccept);
+        }
+
+        now = now.add_timeslots(T261_SETUP_WAITING_TIMER as i32);
+        assert_eq!(link.handle(AdvLinkEvent::SetupTimeout, now), AdvLinkAction::SetupFailed);
+        assert_eq!(link.state, AdvLinkState::Idle);
+        assert!(!link.setup_timer_expired(now));
+    }
+}
@@ -26,6 +26,13 @@ pub fn compute_fcs(bitbuf: &BitBuffer, start: usize, end: usize) -> u32 {
     !crc
 }
 
+/// Computes the FCS over the bits written from `start` up to the current position, and appends it there
+/// Offsets are relative to the bitbuffer window start.
+pub fn append_fcs(bitbuf: &mut BitBuffer, start: usize) {
+    let fcs = compute_fcs(bitbuf, start, bitbuf.get_pos());
+    bitbuf.write_bits(fcs as u64, 32);
+}
+
 /// Computes and checks the FCS checksum
 /// Computes over bitbuffer range [pos, end-32]. Checks with FCS at [end - 32, end]
 pub fn check_fcs(bitbuf: &BitBuffer) -> bool {
@@ -66,4 +73,37 @@ mod tests {
         let fcs_ok = check_fcs(&bitbuf);
         assert!(fcs_ok, "FCS check failed");
     }
+
+    #[test]
+    fn append_fcs_matches_captured_bldata() {
+        // Rebuild the captured BL-DATA from its header and TL-SDU, the appended FCS must match the captured one
+        let testvec = "010100100111101011010111110000100110000110001011000011000000000000000011000100000001001100110011000000110010001011000011001000110000001100100011000100110001001100010011000100110101001100100011000000110010001100000011000000110001011001111010000010101011000110101";
+        let mut captured = BitBuffer::from_bitstr(testvec);
+        let mut sdu = BitBuffer::from_bitstr(&testvec[5..5 + 224]);
+
+        let mut bitbuf = BitBuffer::new_autoexpand(261);
+        bitbuf.copy_bits(&mut captured, 5);
+        bitbuf.copy_bits(&mut sdu, 224);
+        append_fcs(&mut bitbuf, 5);
+        assert_eq!(bitbuf.to_bitstr(), testvec);
+    }
+
+    #[test]
+    fn check_fcs_detects_bit_errors() {
+        let mut sdu = BitBuffer::from_bitstr("1100101000111101");
+        let mut bitbuf = BitBuffer::new_autoexpand(48);
+        bitbuf.copy_bits(&mut sdu, 16);
+        append_fcs(&mut bitbuf, 0);
+        bitbuf.seek(0);
+        assert!(check_fcs(&bitbuf));
+
+        // Flip every bit in turn, including FCS bits, each must be detected
+        let bits = bitbuf.to_bitstr();
+        for i in 0..bits.len() {
+            let mut corrupted: Vec<char> = bits.chars().collect();
+            corrupted[i] = if corrupted[i] == '0' { '1' } else { '0' };
+            let corrupted = BitBuffer::from_bitstr(&corrupted.into_iter().collect::<String>());
+            assert!(!check_fcs(&corrupted), "bit error at {} not detected", i);
+        }
+    }
 }
@@ -1 +1,2 @@
+pub mod adv_link;
 pub mod fcs;
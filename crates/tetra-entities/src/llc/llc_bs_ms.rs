@@ -1,26 +1,33 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::panic;
+use std::time::{Duration, Instant};
 
 use crate::{MessageQueue, TetraEntityTrait};
 use tetra_config::bluestation::SharedConfig;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, Layer2Service, Sap, SsiType, TdmaTime, TetraAddress, TxReporter, unimplemented_log};
+use tetra_core::{
+    BitBuffer, EndpointId, Layer2Service, Sap, SsiType, TdmaTime, TetraAddress, Todo, TransferResult, TxReporter, unimplemented_log,
+};
+use tetra_saps::control::llc_stats::LlcStats;
 use tetra_saps::lcmc::enums::alloc_type::ChanAllocType;
 use tetra_saps::lcmc::enums::ul_dl_assignment::UlDlAssignment;
 use tetra_saps::lcmc::fields::chan_alloc_req::CmceChanAllocReq;
-use tetra_saps::tla::{TlaTlDataIndBl, TlaTlUnitdataIndBl};
+use tetra_saps::tla::{TlaTlConnectConf, TlaTlConnectInd, TlaTlDataIndBl, TlaTlReportInd, TlaTlUnitdataIndBl};
 use tetra_saps::tma::TmaUnitdataReq;
 use tetra_saps::{SapMsg, SapMsgInner};
 
+use crate::llc::components::adv_link::{AdvLink, AdvLinkAction, AdvLinkEvent};
 use crate::llc::components::fcs;
-use tetra_pdus::llc::consts::consts::N252_BL_MAX_TLSDU_RETRANSMITS_ACKED;
-use tetra_pdus::llc::consts::timers::T251_SENDER_RETRY_TIMER;
 use tetra_pdus::llc::enums::llc_pdu_type::LlcPduType;
+use tetra_pdus::llc::pdus::al_setup::AlSetup;
 use tetra_pdus::llc::pdus::bl_ack::BlAck;
 use tetra_pdus::llc::pdus::bl_adata::BlAdata;
 use tetra_pdus::llc::pdus::bl_data::BlData;
 use tetra_pdus::llc::pdus::bl_udata::BlUdata;
 
+/// Advanced links without activity for this many timeslots lose their link id
+const LINK_ID_MAX_IDLE: u64 = tetra_core::hyperframes!(1);
+
 /// Struct that maintains state expected acknowledgement data for a transmitted message.
 /// Aka, we still expect an ack for this.
 pub struct ExpectedInAck {
@@ -34,6 +41,10 @@ pub struct ExpectedInAck {
 
     pub bl_type: Layer2Service,
 
+    /// Request handle given by the MLE, returned in the TL-REPORT once the message is acknowledged or lost.
+    /// 0 if the MLE expects no report.
+    pub req_handle: Todo,
+
     /// Time this message was received from the MLE
     pub t_first: TdmaTime,
     /// Time this message was actually passed down to the Umac. If a previous message on the basic link is already
@@ -50,6 +61,8 @@ pub struct ExpectedInAck {
     pub retransmission_buf: SapMsg,
     /// Number of retransmissions performed so far
     pub retransmit_count: u8,
+    /// Set once this message had to wait for a previous message on the same link. Counted once in the stats.
+    pub stalled: bool,
 }
 
 /// Struct that maintains state for an ACK we still need to send back.
@@ -78,6 +91,15 @@ pub struct Llc {
 
     /// Per-link send sequence variable per SSI. Alternates between 0 and 1.
     link_send_seq: HashMap<u32, u8>,
+
+    /// Advanced link state per SSI
+    adv_links: HashMap<u32, AdvLink>,
+
+    /// PDU counters, returned on a QueryLlcStats control message
+    stats: LlcStats,
+    /// If set, the counters are logged at this interval
+    stats_interval: Option<Duration>,
+    stats_last_logged: Instant,
 }
 
 impl Llc {
@@ -89,9 +111,18 @@ impl Llc {
             outbound_messages: VecDeque::new(),
             outbound_udata_messages: VecDeque::new(),
             link_send_seq: HashMap::new(),
+            adv_links: HashMap::new(),
+            stats: LlcStats::default(),
+            stats_interval: None,
+            stats_last_logged: Instant::now(),
         }
     }
 
+    /// Periodically log the LLC counters. None disables logging.
+    pub fn set_stats_interval(&mut self, interval: Option<Duration>) {
+        self.stats_interval = interval;
+    }
+
     /// Schedule an ACK to be sent at a later time
     pub fn schedule_outgoing_ack(&mut self, dltime: TdmaTime, addr: TetraAddress, ns: u8) {
         self.scheduled_out_acks.push_back(ScheduledOutAck {
@@ -143,7 +174,7 @@ impl Llc {
 
     /// Process incoming ACK per ETSI 22.3.2.3(k).
     /// Matches by SSI and N(R) so that retransmitted BL-DATA entries are matched correctly.
-    fn process_incoming_ack(&mut self, addr: TetraAddress, nr: u8) {
+    fn process_incoming_ack(&mut self, queue: &mut MessageQueue, addr: TetraAddress, nr: u8) {
         // Get the expected ACK entry
         let Some(expected_ack) = self.take_expected_ack_for_ssi(addr.ssi) else {
             tracing::warn!("received unexpected ACK for SSI {} N(R) {}", addr.ssi, nr);
@@ -168,6 +199,7 @@ impl Llc {
             // Successful ACK: N(R) matches N(S)
             tracing::debug!("received ACK for SSI {} N(R) {}", addr.ssi, expected_ack.ns);
             expected_ack.tx_reporter.mark_acknowledged();
+            Self::send_tl_report(queue, expected_ack.req_handle, TransferResult::Successful);
             return;
         } else {
             // N(R) mismatch — per ETSI 22.3.2.3(k), not a successful ACK. Maybe a retransmission?
@@ -185,6 +217,26 @@ impl Llc {
         // The expected_ack is confirmed as matched and goes out of scope here
     }
 
+    /// Report the outcome of an acknowledged transfer to the MLE, if it asked for a report
+    fn send_tl_report(queue: &mut MessageQueue, req_handle: Todo, result: TransferResult) {
+        if req_handle == 0 {
+            return;
+        }
+        let _ = queue.push_back(SapMsg::new(
+            Sap::TlaSap,
+            TetraEntity::Llc,
+            TetraEntity::Mle,
+            SapMsgInner::TlaTlReportInd(TlaTlReportInd {
+                req_handle: Some(req_handle),
+                report: result,
+                chan_change_resp_req: None,
+                chan_change_handle: None,
+                chan_info: None,
+                endpoint_id: None,
+            }),
+        ));
+    }
+
     fn rx_tma_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tma_prim");
         match message.msg {
@@ -207,18 +259,18 @@ impl Llc {
         };
 
         let mut pdu_buf = BitBuffer::new_autoexpand(32);
-        let pdu = BlUdata { has_fcs: false };
+        let pdu = BlUdata { has_fcs: prim.fcs_flag };
         pdu.to_bitbuf(&mut pdu_buf);
-        let sdu_len = prim.tl_sdu.get_len_remaining();
-        pdu_buf.copy_bits(&mut prim.tl_sdu, sdu_len);
+        Self::append_tl_sdu(&mut pdu_buf, &mut prim.tl_sdu, prim.fcs_flag);
         pdu_buf.seek(0);
-        tracing::debug!("-> {:?} sdu {}", pdu, pdu_buf.dump_bin());
-
-        let sapmsg = SapMsg {
-            sap: Sap::TmaSap,
-            src: self.entity(),
-            dest: TetraEntity::Umac,
-            msg: SapMsgInner::TmaUnitdataReq(TmaUnitdataReq {
+        tracing::debug!("-> {} sdu {}", pdu, pdu_buf.dump_bin());
+        self.stats.bl_udata_tx += 1;
+
+        let sapmsg = SapMsg::new(
+            Sap::TmaSap,
+            self.entity(),
+            TetraEntity::Umac,
+            SapMsgInner::TmaUnitdataReq(TmaUnitdataReq {
                 req_handle: prim.req_handle,
                 pdu: pdu_buf,
                 main_address: prim.main_address,
@@ -230,13 +282,26 @@ impl Llc {
                 data_category: prim.data_class_info,
                 chan_alloc: prim.chan_alloc,
                 tx_reporter: prim.tx_reporter.take(),
+                repeat_count: prim.n_tlsdu_repeats.saturating_add(1),
             }),
-        };
+        )
+        .with_call_id(message.call_id);
 
         // Put into transmit queue
         self.outbound_udata_messages.push_back(sapmsg);
     }
 
+    /// Appends the TL-SDU after the already written LLC header. If requested, the FCS over the TL-SDU is appended
+    /// as well, see Clause 22.2.3.
+    fn append_tl_sdu(pdu_buf: &mut BitBuffer, tl_sdu: &mut BitBuffer, fcs_flag: bool) {
+        let sdu_start = pdu_buf.get_pos();
+        let sdu_len = tl_sdu.get_len_remaining();
+        pdu_buf.copy_bits(tl_sdu, sdu_len);
+        if fcs_flag {
+            fcs::append_fcs(pdu_buf, sdu_start);
+        }
+    }
+
     /// Schedules a message that was not acked in time for a retransmission
     fn submit_for_acknowledged_transmission(queue: &mut MessageQueue, ack: &mut ExpectedInAck, dltime: TdmaTime) {
         // Clone the sapmsg. Make sure we set (or for retransmission: reset) timers properly
@@ -246,7 +311,7 @@ impl Llc {
         ack.tx_reporter.reset();
 
         // Send the message
-        queue.push_back(sapmsg);
+        let _ = queue.push_back(sapmsg);
     }
 
     /// See Clause 22.3.2.3 for Acknowledged data transmission in basic link
@@ -281,11 +346,9 @@ impl Llc {
                 ns,
             };
             pdu.to_bitbuf(&mut pdu_buf);
-            // Append SDU
-            let sdu_len = prim.tl_sdu.get_len_remaining();
-            pdu_buf.copy_bits(&mut prim.tl_sdu, sdu_len);
+            Self::append_tl_sdu(&mut pdu_buf, &mut prim.tl_sdu, prim.fcs_flag);
             pdu_buf.seek(0);
-            tracing::debug!(ts=%self.dltime, "-> {:?} sdu {}", pdu, pdu_buf.dump_bin());
+            tracing::debug!(ts=%self.dltime, "-> {} sdu {}", pdu, pdu_buf.dump_bin());
         } else {
             // BL-DATA (acknowledged, with or without FCS) — ETSI Clause 22.3.2.3
             let pdu = BlData {
@@ -293,21 +356,19 @@ impl Llc {
                 ns,
             };
             pdu.to_bitbuf(&mut pdu_buf);
-            // Append SDU
-            let sdu_len = prim.tl_sdu.get_len_remaining();
-            pdu_buf.copy_bits(&mut prim.tl_sdu, sdu_len);
+            Self::append_tl_sdu(&mut pdu_buf, &mut prim.tl_sdu, prim.fcs_flag);
             pdu_buf.seek(0);
-            tracing::debug!(ts=%self.dltime, "-> {:?} sdu {}", pdu, pdu_buf.dump_bin());
+            tracing::debug!(ts=%self.dltime, "-> {} sdu {}", pdu, pdu_buf.dump_bin());
         }
 
         // Either take tx_reporter passed down or create a new one
         let tx_reporter = prim.tx_reporter.take().unwrap_or_else(|| TxReporter::new());
 
-        let sapmsg = SapMsg {
-            sap: Sap::TmaSap,
-            src: self.entity(),
-            dest: TetraEntity::Umac,
-            msg: SapMsgInner::TmaUnitdataReq(TmaUnitdataReq {
+        let sapmsg = SapMsg::new(
+            Sap::TmaSap,
+            self.entity(),
+            TetraEntity::Umac,
+            SapMsgInner::TmaUnitdataReq(TmaUnitdataReq {
                 req_handle: prim.req_handle,
                 pdu: pdu_buf,
                 main_address: prim.main_address,
@@ -319,8 +380,10 @@ impl Llc {
                 data_category: prim.data_class_info,
                 chan_alloc: prim.chan_alloc,
                 tx_reporter: Some(tx_reporter.clone()),
+                repeat_count: 1,
             }),
-        };
+        )
+        .with_call_id(message.call_id);
 
         // Register that we expect an ACK for this message
         tracing::warn!("setting expected ack for ts1, but that's not neccessarily correct");
@@ -329,18 +392,177 @@ impl Llc {
             addr: prim.main_address,
             ts: 1,
             bl_type: Layer2Service::Acknowledged,
+            req_handle: prim.req_handle,
             tx_reporter,
             t_first: self.dltime,
             t_submitted_to_umac: None,
             t_umac_done: None,
             retransmission_buf: sapmsg, // Clone the message to keep a copy for potential retransmission
             retransmit_count: 0,
+            stalled: false,
         });
 
         // The message will now be picked up for transmission at end-of-tick, if the ssi does not yet have
         // a pending message waiting for an ack.
     }
 
+    /// Clause 22.3.3.1 Advanced link establishment, initiated by the local service user
+    fn rx_tla_tlconnect_req(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        tracing::trace!("rx_tla_tlconnect_req");
+        let SapMsgInner::TlaTlConnectReq(prim) = message.msg else {
+            panic!()
+        };
+
+        let dltime = self.dltime;
+        let Some(link) = self.get_or_create_adv_link(prim.main_address, prim.link_id as u8 & 1, prim.endpoint_id) else {
+            return;
+        };
+        let action = link.handle(AdvLinkEvent::ConnectReq, dltime);
+        self.apply_adv_link_action(queue, prim.main_address.ssi, action);
+    }
+
+    /// Clause 22.3.3.1 Advanced link establishment, service user accepts a set-up requested by the peer
+    fn rx_tla_tlconnect_resp(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        tracing::trace!("rx_tla_tlconnect_resp");
+        let SapMsgInner::TlaTlConnectResp(prim) = message.msg else {
+            panic!()
+        };
+
+        let dltime = self.dltime;
+        let Some(link) = self.adv_links.get_mut(&prim.main_address.ssi) else {
+            tracing::warn!(
+                "TL-CONNECT response for SSI {} without advanced link, ignoring",
+                prim.main_address.ssi
+            );
+            return;
+        };
+        let action = link.handle(AdvLinkEvent::ConnectResp, dltime);
+        self.apply_adv_link_action(queue, prim.main_address.ssi, action);
+    }
+
+    /// Returns the advanced link for the address, creating it with a fresh link id if there is none
+    fn get_or_create_adv_link(&mut self, addr: TetraAddress, advanced_link_number: u8, endpoint_id: EndpointId) -> Option<&mut AdvLink> {
+        if !self.adv_links.contains_key(&addr.ssi) {
+            let Some(link_id) = self.config.state_write().link_ids.allocate(self.dltime) else {
+                tracing::warn!("no free link id for advanced link to SSI {}", addr.ssi);
+                return None;
+            };
+            self.adv_links
+                .insert(addr.ssi, AdvLink::new(addr, link_id, advanced_link_number, endpoint_id));
+        }
+        self.adv_links.get_mut(&addr.ssi)
+    }
+
+    /// Removes the advanced link for the SSI and releases its link id
+    fn remove_adv_link(&mut self, ssi: u32) {
+        if let Some(link) = self.adv_links.remove(&ssi) {
+            self.config.state_write().link_ids.release(link.link_id);
+        }
+    }
+
+    /// Releases link ids of advanced links without activity for LINK_ID_MAX_IDLE, and drops those links
+    fn gc_adv_links(&mut self) {
+        let stale = self.config.state_write().link_ids.gc_stale(self.dltime, LINK_ID_MAX_IDLE);
+        if stale.is_empty() {
+            return;
+        }
+        tracing::info!("released stale link ids {:?}", stale);
+        self.adv_links.retain(|_, link| !stale.contains(&link.link_id));
+    }
+
+    /// Performs the action resulting from an advanced link state transition
+    fn apply_adv_link_action(&mut self, queue: &mut MessageQueue, ssi: u32, action: AdvLinkAction) {
+        let Some(link) = self.adv_links.get(&ssi) else {
+            return;
+        };
+        self.config.state_write().link_ids.touch(link.link_id, self.dltime);
+
+        match action {
+            AdvLinkAction::SendSetup(setup_report) => {
+                let pdu = link.build_setup_pdu(setup_report);
+                let mut pdu_buf = BitBuffer::new_autoexpand(32);
+                pdu.to_bitbuf(&mut pdu_buf);
+                pdu_buf.seek(0);
+                tracing::debug!(ts=%self.dltime, "-> {} {}", pdu, pdu_buf.dump_bin());
+
+                let sapmsg = SapMsg::new(
+                    Sap::TmaSap,
+                    self.entity(),
+                    TetraEntity::Umac,
+                    SapMsgInner::TmaUnitdataReq(TmaUnitdataReq {
+                        req_handle: 0, // TODO FIXME
+                        pdu: pdu_buf,
+                        main_address: link.addr,
+                        endpoint_id: link.endpoint_id,
+                        stealing_permission: false,
+                        subscriber_class: 0,            // TODO FIXME
+                        air_interface_encryption: None, // TODO FIXME
+                        stealing_repeats_flag: None,
+                        data_category: None,
+                        chan_alloc: None,
+                        tx_reporter: None,
+                        repeat_count: 1,
+                    }),
+                );
+                // AL-SETUP is not acknowledged on the basic link, T.261 covers its retransmission
+                self.outbound_udata_messages.push_back(sapmsg);
+            }
+            AdvLinkAction::ConnectInd => {
+                let m = TlaTlConnectInd {
+                    main_address: link.addr,
+                    scrambling_code: 0, // TODO FIXME
+                    link_id: link.link_id,
+                    endpoint_id: link.endpoint_id,
+                    new_endpoint_id: None,
+                    css_endpoint_id: None,
+                    qos: 0,        // TODO FIXME
+                    al_service: 0, // TODO FIXME
+                    air_interface_encryption: 0,
+                    chan_change_resp_req: false,
+                    chan_change_handle: None,
+                    chan_info: None,
+                    req_handle: 0,   // TODO FIXME
+                    setup_report: 0, // TODO FIXME
+                };
+                let _ = queue.push_back(SapMsg::new(
+                    Sap::TlaSap,
+                    TetraEntity::Llc,
+                    TetraEntity::Mle,
+                    SapMsgInner::TlaTlConnectInd(m),
+                ));
+            }
+            AdvLinkAction::ConnectConf => {
+                let m = TlaTlConnectConf {
+                    main_address: link.addr,
+                    scrambling_code: 0, // TODO FIXME
+                    link_id: link.link_id,
+                    endpoint_id: link.endpoint_id,
+                    new_endpoint_id: None,
+                    css_endpoint_id: None,
+                    qos: 0,        // TODO FIXME
+                    al_service: 0, // TODO FIXME
+                    air_interface_encryption: 0,
+                    chan_change_resp_req: false,
+                    chan_change_handle: None,
+                    chan_info: None,
+                    req_handle: 0,   // TODO FIXME
+                    setup_report: 0, // TODO FIXME
+                };
+                let _ = queue.push_back(SapMsg::new(
+                    Sap::TlaSap,
+                    TetraEntity::Llc,
+                    TetraEntity::Mle,
+                    SapMsgInner::TlaTlConnectConf(m),
+                ));
+            }
+            AdvLinkAction::SetupFailed => {
+                tracing::warn!("advanced link set-up for SSI {} failed after {} retries", ssi, link.setup_retries);
+                self.remove_adv_link(ssi);
+            }
+            AdvLinkAction::Ignore => {}
+        }
+    }
+
     fn rx_tla_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tla_prim");
         match &message.msg {
@@ -350,10 +572,42 @@ impl Llc {
             SapMsgInner::TlaTlUnitdataReqBl(_) => {
                 self.rx_tla_tlunitdata_req_bl(queue, message);
             }
+            SapMsgInner::TlaTlConnectReq(_) => {
+                self.rx_tla_tlconnect_req(queue, message);
+            }
+            SapMsgInner::TlaTlConnectResp(_) => {
+                self.rx_tla_tlconnect_resp(queue, message);
+            }
             _ => panic!(),
         }
     }
 
+    fn rx_control_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        tracing::trace!("rx_control_prim");
+        match message.msg {
+            SapMsgInner::QueryLlcStats(_) => {
+                let _ = queue.push_back(SapMsg::new(
+                    Sap::Control,
+                    self.entity(),
+                    message.src,
+                    SapMsgInner::LlcStatsReply(self.stats.clone()),
+                ));
+            }
+            _ => panic!(),
+        }
+    }
+
+    /// Logs the counters if a stats interval is configured and it has elapsed
+    fn log_stats_if_due(&mut self) {
+        let Some(interval) = self.stats_interval else {
+            return;
+        };
+        if self.stats_last_logged.elapsed() >= interval {
+            self.stats_last_logged = Instant::now();
+            tracing::info!("LLC stats: {}", self.stats);
+        }
+    }
+
     fn rx_tma_report_ind(&mut self, _queue: &mut MessageQueue, mut _message: SapMsg) {
         tracing::trace!("rx_tma_report_ind, ignoring");
     }
@@ -398,8 +652,11 @@ impl Llc {
                 self.rx_tma_unitdata_ind_bl(queue, message);
             }
 
-            LlcPduType::AlSetup
-            | LlcPduType::AlDataAlFinal
+            LlcPduType::AlSetup => {
+                self.rx_tma_unitdata_ind_al_setup(queue, message);
+            }
+
+            LlcPduType::AlDataAlFinal
             | LlcPduType::AlAlUdataAlUfinal
             | LlcPduType::AlAckAlRnr
             | LlcPduType::AlReconnect
@@ -413,6 +670,32 @@ impl Llc {
         }
     }
 
+    /// Clause 22.3.3.1 AL-SETUP received from the peer, either a set-up request or the acceptance of ours
+    fn rx_tma_unitdata_ind_al_setup(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::trace!("rx_tma_unitdata_ind_al_setup");
+        let SapMsgInner::TmaUnitdataInd(prim) = &mut message.msg else {
+            panic!();
+        };
+        let Some(mut pdu) = prim.pdu.take() else {
+            panic!("no pdu");
+        };
+        let pdu = match AlSetup::from_bitbuf(&mut pdu) {
+            Ok(pdu) => pdu,
+            Err(e) => {
+                tracing::warn!("Failed parsing AlSetup: {:?} {}", e, pdu.dump_bin());
+                return;
+            }
+        };
+        tracing::debug!(ts=%self.dltime, "<- {}", pdu);
+
+        let dltime = self.dltime;
+        let Some(link) = self.get_or_create_adv_link(prim.main_address, pdu.advanced_link_number, prim.endpoint_id) else {
+            return;
+        };
+        let action = link.handle(AdvLinkEvent::SetupRx(pdu.setup_report), dltime);
+        self.apply_adv_link_action(queue, prim.main_address.ssi, action);
+    }
+
     fn rx_tma_unitdata_ind_bl(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("rx_tma_unitdata_ind_bl");
 
@@ -435,7 +718,7 @@ impl Llc {
         let (has_fcs, ns, nr) = match pdu_type {
             LlcPduType::BlAdata | LlcPduType::BlAdataFcs => match BlAdata::from_bitbuf(&mut pdu) {
                 Ok(pdu) => {
-                    tracing::debug!(ts=%self.dltime, "<- {:?}", pdu);
+                    tracing::debug!(ts=%self.dltime, "<- {}", pdu);
                     (pdu.has_fcs, Some(pdu.ns), Some(pdu.nr))
                 }
                 Err(e) => {
@@ -446,7 +729,7 @@ impl Llc {
 
             LlcPduType::BlData | LlcPduType::BlDataFcs => match BlData::from_bitbuf(&mut pdu) {
                 Ok(pdu) => {
-                    tracing::debug!(ts=%self.dltime, "<- {:?}", pdu);
+                    tracing::debug!(ts=%self.dltime, "<- {}", pdu);
                     (pdu.has_fcs, Some(pdu.ns), None)
                 }
                 Err(e) => {
@@ -456,7 +739,7 @@ impl Llc {
             },
             LlcPduType::BlAck | LlcPduType::BlAckFcs => match BlAck::from_bitbuf(&mut pdu) {
                 Ok(pdu) => {
-                    tracing::debug!(ts=%self.dltime, "<- {:?}", pdu);
+                    tracing::debug!(ts=%self.dltime, "<- {}", pdu);
                     (pdu.has_fcs, None, Some(pdu.nr))
                 }
                 Err(e) => {
@@ -466,7 +749,7 @@ impl Llc {
             },
             LlcPduType::BlUdata | LlcPduType::BlUdataFcs => match BlUdata::from_bitbuf(&mut pdu) {
                 Ok(pdu) => {
-                    tracing::debug!(ts=%self.dltime, "<- {:?}", pdu);
+                    tracing::debug!(ts=%self.dltime, "<- {}", pdu);
                     (pdu.has_fcs, None, None)
                 }
                 Err(e) => {
@@ -479,10 +762,21 @@ impl Llc {
             }
         };
 
-        // If FCS is present, check it. If wrong, we bail here
-        if has_fcs && !fcs::check_fcs(&pdu) {
-            tracing::warn!("FCS check failed");
-            return;
+        // If FCS is present, check it. If wrong, we bail here without acknowledging, so the sender retransmits.
+        // If correct, strip it so that only the TL-SDU is passed up.
+        if has_fcs {
+            if !fcs::check_fcs(&pdu) {
+                tracing::warn!("FCS check failed");
+                self.stats.fcs_errors += 1;
+                return;
+            }
+            pdu.set_raw_end(pdu.get_raw_end() - 32);
+        }
+
+        match pdu_type {
+            LlcPduType::BlAdata | LlcPduType::BlAdataFcs | LlcPduType::BlData | LlcPduType::BlDataFcs => self.stats.bl_data_rx += 1,
+            LlcPduType::BlUdata | LlcPduType::BlUdataFcs => self.stats.bl_udata_rx += 1,
+            _ => {}
         }
 
         // If ns is present, we need to send an ACK
@@ -494,7 +788,7 @@ impl Llc {
 
         // if nr is present, we have received an ACK on a previous message
         if let Some(nr) = nr {
-            self.process_incoming_ack(prim.main_address, nr);
+            self.process_incoming_ack(queue, prim.main_address, nr);
         }
 
         if pdu_type == LlcPduType::BlAck || pdu_type == LlcPduType::BlAckFcs {
@@ -526,12 +820,7 @@ impl Llc {
                 chan_info: prim.chan_info,
                 report: None, // TODO FIXME
             };
-            SapMsg {
-                sap: Sap::TlaSap,
-                src: TetraEntity::Llc,
-                dest: TetraEntity::Mle,
-                msg: SapMsgInner::TlaTlUnitdataIndBl(m),
-            }
+            SapMsg::new(Sap::TlaSap, TetraEntity::Llc, TetraEntity::Mle, SapMsgInner::TlaTlUnitdataIndBl(m))
         } else {
             // Acknowledged data transfer service
             let m = TlaTlDataIndBl {
@@ -550,15 +839,10 @@ impl Llc {
                 chan_info: prim.chan_info,
                 req_handle: 0, // TODO FIXME
             };
-            SapMsg {
-                sap: Sap::TlaSap,
-                src: TetraEntity::Llc,
-                dest: TetraEntity::Mle,
-                msg: SapMsgInner::TlaTlDataIndBl(m),
-            }
+            SapMsg::new(Sap::TlaSap, TetraEntity::Llc, TetraEntity::Mle, SapMsgInner::TlaTlDataIndBl(m))
         };
 
-        queue.push_back(s);
+        let _ = queue.push_back(s);
     }
 
     fn submit_retransmissions_to_umac(&mut self, queue: &mut MessageQueue) -> bool {
@@ -566,6 +850,11 @@ impl Llc {
         let dltime = self.dltime;
         let mut removals: Option<Vec<u32>> = None;
 
+        // T.251 and N.252 from the [llc] config section, defaulting to the Annex A values
+        let llc_cfg = &self.config.config().llc;
+        let t251 = llc_cfg.t251_timeslots();
+        let n252 = llc_cfg.n252;
+
         // if !self.outbound_messages.is_empty() {
         //     tracing::error!("{}", Self::format_expected_ack_list(&self.outbound_messages));
         // }
@@ -586,9 +875,9 @@ impl Llc {
             // Retransmit scenario 1: it was transmitted but no ack received within the expected window (ETSI T.251 / N.252)
             // Retransmission scenario 2: it has been dropped by Umac due to congestion. Retransmit after same window
             let age = dltime.diff(t_umac_done); // Never fails
-            if age as u32 >= T251_SENDER_RETRY_TIMER {
+            if age as u32 >= t251 {
                 // Time for either retransmitting or giving up
-                if ack.retransmit_count < N252_BL_MAX_TLSDU_RETRANSMITS_ACKED {
+                if ack.retransmit_count < n252 {
                     // Retransmit
                     ack.retransmit_count += 1;
                     tracing::info!(
@@ -599,6 +888,7 @@ impl Llc {
                     );
 
                     Self::submit_for_acknowledged_transmission(queue, ack, self.dltime.forward_to_timeslot(ack.t_first.t));
+                    self.stats.retransmissions += 1;
                     had_activity = true;
                 } else {
                     // Exhausted retransmissions, flag for discard
@@ -617,6 +907,7 @@ impl Llc {
                     ack.ns
                 );
                 ack.tx_reporter.mark_lost();
+                Self::send_tl_report(queue, ack.req_handle, TransferResult::Failed);
             }
             // The ack expires here
         }
@@ -638,6 +929,10 @@ impl Llc {
             // Not submitted; check if blocked
             if ssi_blocked.contains(&ack.addr.ssi) {
                 // SSI already has another message waiting for ack, so we cannot submit this one yet
+                if !ack.stalled {
+                    ack.stalled = true;
+                    self.stats.window_stalls += 1;
+                }
                 tracing::debug!(
                     "SSI {} N(S) {} still blocked by previous message, cannot submit next message",
                     ack.addr.ssi,
@@ -655,6 +950,7 @@ impl Llc {
                 ack.retransmission_buf.msg
             );
             Self::submit_for_acknowledged_transmission(queue, ack, self.dltime.forward_to_timeslot(ack.t_first.t));
+            self.stats.bl_data_tx += 1;
             ssi_blocked.insert(ack.addr.ssi);
             had_activity = true;
         }
@@ -662,6 +958,22 @@ impl Llc {
         had_activity
     }
 
+    /// Retransmits AL-SETUP for advanced links whose T.261 expired, or gives up after N.262 retries
+    fn process_adv_link_timers(&mut self, queue: &mut MessageQueue) {
+        let dltime = self.dltime;
+        let expired: Vec<u32> = self
+            .adv_links
+            .values()
+            .filter(|link| link.setup_timer_expired(dltime))
+            .map(|link| link.addr.ssi)
+            .collect();
+
+        for ssi in expired {
+            let action = self.adv_links.get_mut(&ssi).unwrap().handle(AdvLinkEvent::SetupTimeout, dltime); // Never fails
+            self.apply_adv_link_action(queue, ssi, action);
+        }
+    }
+
     /// Pops all elements from the scheduled_out_acks queue, prepares BL-ACK messages, and send them down
     fn submit_ack_replies_to_umac(&mut self, queue: &mut MessageQueue) -> bool {
         let had_activity = !self.scheduled_out_acks.is_empty();
@@ -678,7 +990,7 @@ impl Llc {
             };
             pdu.to_bitbuf(&mut pdu_buf);
             pdu_buf.seek(0);
-            tracing::debug!(ts=%self.dltime, "-> {:?} {}", pdu, pdu_buf.dump_bin());
+            tracing::debug!(ts=%self.dltime, "-> {} {}", pdu, pdu_buf.dump_bin());
 
             // We're sending an ACK for a received uplink message, however, we don't have that message here
             // Since DL is two slots ahead of UL, we will correct that. We now have the dltime for reception
@@ -697,11 +1009,11 @@ impl Llc {
                 }
                 false => None,
             };
-            let sapmsg = SapMsg {
-                sap: Sap::TmaSap,
-                src: TetraEntity::Llc,
-                dest: TetraEntity::Umac,
-                msg: SapMsgInner::TmaUnitdataReq(TmaUnitdataReq {
+            let sapmsg = SapMsg::new(
+                Sap::TmaSap,
+                TetraEntity::Llc,
+                TetraEntity::Umac,
+                SapMsgInner::TmaUnitdataReq(TmaUnitdataReq {
                     req_handle: 0, // TODO FIXME
                     pdu: pdu_buf,
                     main_address: ack.addr,
@@ -713,9 +1025,10 @@ impl Llc {
                     data_category: None,            // TODO FIXME
                     chan_alloc,
                     tx_reporter: None, // By definition, no higher layer entity is interested
+                    repeat_count: 1,
                 }),
-            };
-            queue.push_back(sapmsg);
+            );
+            let _ = queue.push_back(sapmsg);
         }
         had_activity
     }
@@ -725,7 +1038,7 @@ impl Llc {
         let had_activity = !self.outbound_udata_messages.is_empty();
         while let Some(msg) = self.outbound_udata_messages.pop_front() {
             tracing::debug!("submitting udata msg to umac: {:?}", msg.msg);
-            queue.push_back(msg);
+            let _ = queue.push_back(msg);
         }
         had_activity
     }
@@ -779,12 +1092,18 @@ impl TetraEntityTrait for Llc {
             Sap::TlaSap => {
                 self.rx_tla_prim(queue, message);
             }
+            Sap::Control => {
+                self.rx_control_prim(queue, message);
+            }
             _ => panic!(),
         }
     }
 
     fn tick_start(&mut self, _queue: &mut MessageQueue, ts: TdmaTime) {
         self.dltime = ts;
+        if ts.f == 1 && ts.t == 1 {
+            self.gc_adv_links();
+        }
     }
 
     fn tick_end(&mut self, queue: &mut MessageQueue, _ts: TdmaTime) -> bool {
@@ -802,9 +1121,12 @@ impl TetraEntityTrait for Llc {
         // Take oldest element from scheduled_out_acks, and remove it from the list
         had_activity |= self.submit_ack_replies_to_umac(queue);
 
-        // Step 4 / 4: Send any U-DATA messages
+        // Step 4 / 4: Send any U-DATA messages, including AL-SETUP PDUs for advanced link establishment
+        self.process_adv_link_timers(queue);
         had_activity |= self.submit_udata_msgs_to_umac(queue);
 
+        self.log_stats_if_due();
+
         had_activity
     }
 }
@@ -1,12 +1,19 @@
+use std::time::Instant;
+
 use tetra_config::bluestation::{SharedConfig, StackMode};
+use tetra_core::clock_drift::ClockDriftMonitor;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BurstType, PhyBlockNum, PhysicalChannel, Sap, TdmaTime, TrainingSequence};
-use tetra_saps::tmv::TmvUnitdataInd;
+use tetra_core::{BitBuffer, BurstType, PhyBlockNum, PhysicalChannel, Sap, TdmaTime, TrainingSequence, assert_warn};
+use tetra_pdus::mle::pdus::d_mle_sync::DMleSync;
+use tetra_pdus::umac::pdus::mac_resource::MacResource;
+use tetra_pdus::umac::pdus::mac_sync::MacSync;
 use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
+use tetra_saps::tmv::{TmvUnitdataInd, TmvUnitdataReq};
 use tetra_saps::tp::{TpUnitdataInd, TpUnitdataReqSlot};
 use tetra_saps::{SapMsg, SapMsgInner};
 
 use crate::lmac::components::{errorcontrol, scrambler};
+use crate::umac::subcomp::fillbits;
 use crate::{MessagePrio, MessageQueue, TetraEntityTrait};
 
 #[derive(Debug, Clone, Copy)]
@@ -53,6 +60,14 @@ pub struct LmacBs {
 
     /// Signalled by Umac per timeslot. Set to true when in a traffic burst, the 1st stolen block shows that the 2nd slot is also stolen
     blk2_stolen: bool,
+
+    /// Per-timeslot SYNC to send in place of the next traffic burst, for MSs entering an ongoing call
+    traffic_sync: [Option<(MacSync, DMleSync)>; 4],
+
+    /// TDMA clock compared to wall-clock time, sampled every multiframe
+    clock_drift: ClockDriftMonitor,
+    /// Whether the drift threshold was exceeded at the last check, to warn only on changes
+    clock_drifted: bool,
     // Details about current burst, parsed from BBK broadcast block
     // cur_burst: CurBurst,
 }
@@ -83,9 +98,33 @@ impl LmacBs {
             dltime: TdmaTime::default(),
             uplink_phy_chan: [PhysicalChannel::Unallocated; 4],
             blk2_stolen: false,
+            traffic_sync: [const { None }; 4],
+            clock_drift: ClockDriftMonitor::default(),
+            clock_drifted: false,
         }
     }
 
+    /// Sample the TDMA clock at the start of every multiframe and warn when the drift crosses the configured threshold
+    fn update_clock_drift(&mut self) {
+        if self.dltime.f != 1 || self.dltime.t != 1 {
+            return;
+        }
+        self.clock_drift.update(Instant::now(), self.dltime);
+
+        let threshold_ppm = self.config.config().phy_io.clock_drift_threshold_ppm;
+        let drifted = self.clock_drift.is_drifted(threshold_ppm);
+        if drifted && !self.clock_drifted {
+            tracing::warn!(
+                "LmacBs: TDMA clock drift {:.1} ppm exceeds threshold of {:.1} ppm",
+                self.clock_drift.drift_ppm(),
+                threshold_ppm
+            );
+        } else if !drifted && self.clock_drifted {
+            tracing::info!("LmacBs: TDMA clock drift back to {:.1} ppm", self.clock_drift.drift_ppm());
+        }
+        self.clock_drifted = drifted;
+    }
+
     // fn determine_phy_chan_ul(&self) -> PhysicalChannel {
     //     let ultime = self.dltime.add_timeslots(-2);
     //     // Frame 18 is always CP (I think)
@@ -116,6 +155,45 @@ impl LmacBs {
     //     }
     // }
 
+    /// Send a synchronization burst carrying the given SYNC PDUs on traffic timeslot `ts`, in place of its next
+    /// full-slot traffic burst. Lets a late entering MS synchronize to the traffic channel (clause 21.5.3).
+    /// The time in the MAC-SYNC is replaced with that of the slot it is sent in.
+    pub fn schedule_traffic_bnch(&mut self, ts: u8, mac_sync: MacSync, mle_sync: DMleSync) {
+        if !(2..=4).contains(&ts) {
+            tracing::warn!("schedule_traffic_bnch: ts {} is not a traffic timeslot", ts);
+            return;
+        }
+        self.traffic_sync[ts as usize - 1] = Some((mac_sync, mle_sync));
+    }
+
+    /// Build the blocks of a synchronization burst from scheduled SYNC PDUs: BSCH, followed by a SCH/HD Null PDU
+    fn build_traffic_sync_blks(&self, ts: TdmaTime, mut mac_sync: MacSync, mle_sync: DMleSync) -> (TmvUnitdataReq, TmvUnitdataReq) {
+        mac_sync.time = ts;
+        let mut bsch = BitBuffer::new(60);
+        mac_sync.to_bitbuf(&mut bsch);
+        mle_sync.to_bitbuf(&mut bsch);
+        bsch.seek(0);
+
+        let mut schhd = BitBuffer::new(124);
+        let (null_pdu, num_fill_bits) = MacResource::null_pdu_for_channel(LogicalChannel::SchHd);
+        null_pdu.to_bitbuf(&mut schhd);
+        fillbits::addition::write(&mut schhd, Some(num_fill_bits));
+        schhd.seek(0);
+
+        (
+            TmvUnitdataReq {
+                logical_channel: LogicalChannel::Bsch,
+                mac_block: bsch,
+                scrambling_code: scrambler::SCRAMB_INIT,
+            },
+            TmvUnitdataReq {
+                logical_channel: LogicalChannel::SchHd,
+                mac_block: schhd,
+                scrambling_code: self.scrambling_code,
+            },
+        )
+    }
+
     /// Yields logical channel for given block. Based on Clause 9.5.1
     fn determine_logical_channel_ul(blk: &TpUnitdataInd, burst_is_traffic: bool, block2_stolen: bool) -> LogicalChannel {
         match blk.burst_type {
@@ -201,13 +279,17 @@ impl LmacBs {
         bb.seek(0);
         bb.to_bitarr(&mut data);
 
-        let msg = SapMsg {
-            sap: Sap::TmdSap,
-            src: TetraEntity::Lmac,
-            dest: TetraEntity::Umac,
-            msg: SapMsgInner::TmdCircuitDataInd(tetra_saps::tmd::TmdCircuitDataInd { ts: ul_time.t, data }),
-        };
-        queue.push_back(msg);
+        let msg = SapMsg::new(
+            Sap::TmdSap,
+            TetraEntity::Lmac,
+            TetraEntity::Umac,
+            SapMsgInner::TmdCircuitDataInd(tetra_saps::tmd::TmdCircuitDataInd {
+                ts: ul_time.t,
+                data,
+                crc_pass: crc_ok,
+            }),
+        );
+        let _ = queue.push_back(msg);
     }
 
     fn rx_blk_control(&mut self, queue: &mut MessageQueue, blk: TpUnitdataInd, lchan: LogicalChannel) {
@@ -235,23 +317,23 @@ impl LmacBs {
         }
 
         // Pass block to the upper mac
-        let m = SapMsg {
-            sap: Sap::TmvSap,
-            src: TetraEntity::Lmac,
-            dest: TetraEntity::Umac,
-            msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+        let m = SapMsg::new(
+            Sap::TmvSap,
+            TetraEntity::Lmac,
+            TetraEntity::Umac,
+            SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
                 pdu: type1bits,
                 logical_channel: lchan,
                 block_num,
                 crc_pass,
                 scrambling_code: self.scrambling_code,
             }),
-        };
+        );
 
         // Suppose we've just parsed blk1 in a stolen traffic burst.
         // We then don't know whether blk2 is also stolen, as that will be shown by the Umac
         // We thus push this with prio, and the umac will signal with prio if blk2 is stolen too
-        queue.push_prio(m, MessagePrio::Immediate);
+        let _ = queue.push_prio(m, MessagePrio::Immediate);
     }
 
     fn rx_tp_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
@@ -262,7 +344,7 @@ impl LmacBs {
         let msg_dltime = self.dltime.add_timeslots(-2); // Msg on uplink was sent two timeslots ago. 
         let ts_idx = msg_dltime.t as usize - 1;
         let pchan = self.uplink_phy_chan[ts_idx];
-        let lchan = Self::determine_logical_channel_ul(&prim, pchan == PhysicalChannel::Tp, self.blk2_stolen);
+        let lchan = Self::determine_logical_channel_ul(&prim, pchan.is_traffic(), self.blk2_stolen);
 
         // Sanity checks
         assert!(
@@ -270,7 +352,7 @@ impl LmacBs {
             "blk2_stolen must be false when receiving block1"
         );
         assert!(
-            pchan == PhysicalChannel::Tp || !self.blk2_stolen,
+            pchan.is_traffic() || !self.blk2_stolen,
             "blk2_stolen must be false when not in a traffic burst"
         );
 
@@ -295,6 +377,13 @@ impl LmacBs {
         if let Some(stolen) = prim.blk2_stolen {
             self.blk2_stolen = stolen;
         }
+        if let Some((ts, bsch)) = &prim.traffic_bsch {
+            let mut bsch = BitBuffer::from_bitbuffer(bsch);
+            match (MacSync::from_bitbuf(&mut bsch), DMleSync::from_bitbuf(&mut bsch)) {
+                (Ok(mac_sync), Ok(mle_sync)) => self.schedule_traffic_bnch(*ts, mac_sync, mle_sync),
+                (mac_sync, mle_sync) => tracing::warn!("rx_tmv_configure_req: invalid BSCH: {:?} {:?}", mac_sync.err(), mle_sync.err()),
+            }
+        }
     }
 
     /// Request from Umac to transmit a message
@@ -306,14 +395,36 @@ impl LmacBs {
 
         // Update per-timeslot UL physical channel indicator
         let ts_idx = prim.ts.t as usize - 1;
+        if let Some(chan_ts) = prim.ul_phy_chan.ts() {
+            assert_warn!(chan_ts == prim.ts.t, "ul_phy_chan {:?} for ts {}", prim.ul_phy_chan, prim.ts.t);
+        }
         self.uplink_phy_chan[ts_idx] = prim.ul_phy_chan;
 
         assert!(prim.bbk.is_some(), "rx_tmv_unitdata_req_slot: bbk must be present");
         assert!(prim.blk1.is_some(), "rx_tmv_unitdata_req_slot: blk1 must be present");
 
         let bbk = prim.bbk.take().unwrap(); // Guaranteed for BS stack
-        let blk1 = prim.blk1.take().unwrap(); // Guaranteed for BS stack
-        let blk2 = prim.blk2.take();
+        let mut blk1 = prim.blk1.take().unwrap(); // Guaranteed for BS stack
+        let mut blk2 = prim.blk2.take();
+
+        // On a traffic timeslot with a half-slot stolen for FACCH, the stolen block is carried on STCH
+        if let PhysicalChannel::TrafficFacch { subslot, .. } = prim.ul_phy_chan {
+            let stolen = if subslot == 1 { Some(&mut blk1) } else { blk2.as_mut() };
+            if let Some(stolen) = stolen {
+                stolen.logical_channel = LogicalChannel::Stch;
+            }
+        }
+
+        // A scheduled SYNC for late entry replaces a full traffic burst, stolen blocks are left alone
+        if blk1.logical_channel.is_traffic()
+            && blk2.is_none()
+            && let Some((mac_sync, mle_sync)) = self.traffic_sync[ts_idx].take()
+        {
+            tracing::info!("rx_tmv_unitdata_req_slot: sending SYNC on traffic ts {} for late entry", prim.ts.t);
+            let (bsch, schhd) = self.build_traffic_sync_blks(prim.ts, mac_sync, mle_sync);
+            blk1 = bsch;
+            blk2 = Some(schhd);
+        }
 
         // Determine train and burst type
         let (burst_type, train_type) = match blk1.logical_channel {
@@ -367,13 +478,13 @@ impl LmacBs {
         }
 
         // Pass timeslot worth of blocks to Phy
-        let m = SapMsg {
-            sap: Sap::TpSap,
-            src: TetraEntity::Lmac,
-            dest: TetraEntity::Phy,
-            msg: SapMsgInner::TpUnitdataReq(prim_phy),
-        };
-        queue.push_back(m);
+        let m = SapMsg::new(
+            Sap::TpSap,
+            TetraEntity::Lmac,
+            TetraEntity::Phy,
+            SapMsgInner::TpUnitdataReq(prim_phy),
+        );
+        let _ = queue.push_back(m);
     }
 
     fn rx_tmv_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
@@ -442,5 +553,6 @@ impl TetraEntityTrait for LmacBs {
     fn tick_start(&mut self, _queue: &mut MessageQueue, ts: TdmaTime) {
         self.dltime = ts;
         self.blk2_stolen = false; // reset in case it was set during this tick
+        self.update_clock_drift();
     }
 }
@@ -78,22 +78,22 @@ impl LmacMs {
         let type1 = errorcontrol::decode_aach(type5, scrambling_code);
 
         // Pass block to the upper mac
-        let m = SapMsg {
-            sap: Sap::TmvSap,
-            src: TetraEntity::Lmac,
-            dest: TetraEntity::Umac,
-            msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+        let m = SapMsg::new(
+            Sap::TmvSap,
+            TetraEntity::Lmac,
+            TetraEntity::Umac,
+            SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
                 pdu: type1,
                 block_num: PhyBlockNum::Undefined,
                 logical_channel: LogicalChannel::Aach,
                 crc_pass: true,
                 scrambling_code,
             }),
-        };
+        );
 
         // This message needs to be processed immediately, as the BBK block contains the ACCESS-ASSIGN,
         // determining how to interpret the two half slots of the burst.
-        queue.push_prio(m, MessagePrio::Immediate);
+        let _ = queue.push_prio(m, MessagePrio::Immediate);
     }
 
     fn determine_logical_channel_dl(&self, blk: &TpUnitdataInd, t: &TdmaTime) -> LogicalChannel {
@@ -177,19 +177,19 @@ impl LmacMs {
             };
 
             // Pass block to the upper mac
-            let m = SapMsg {
-                sap: Sap::TmvSap,
-                src: TetraEntity::Lmac,
-                dest: TetraEntity::Umac,
-                msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+            let m = SapMsg::new(
+                Sap::TmvSap,
+                TetraEntity::Lmac,
+                TetraEntity::Umac,
+                SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
                     pdu: type1bits,
                     block_num,
                     logical_channel: lchan,
                     crc_pass,
                     scrambling_code: scramb_code,
                 }),
-            };
-            queue.push_back(m);
+            );
+            let _ = queue.push_back(m);
         }
     }
 
@@ -1,12 +1,149 @@
 use std::collections::{HashMap, VecDeque};
-use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
-
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
 use tetra_config::bluestation::SharedConfig;
-use tetra_core::{TdmaTime, tetra_entities::TetraEntity};
-use tetra_saps::SapMsg;
+use tetra_core::{Sap, TdmaTime, tetra_entities::TetraEntity};
+use tetra_saps::control::admin::{QueryStackState, StackStateSnapshot};
+use tetra_saps::{SapMsg, SapMsgInner};
 
 use crate::TetraEntityTrait;
+use crate::net_admin::{AdminLink, AdminReply, AdminRequest};
+
+/// Ticks the stack keeps running after a shutdown hook sent messages, one multiframe
+pub const SHUTDOWN_DRAIN_TICKS: usize = tetra_core::multiframes!(1) as usize;
+
+/// Time a shutdown through `run_stack` may spend draining messages sent from shutdown hooks
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Routing latency above which a warning is logged, unless changed with `set_latency_warn_threshold`
+pub const DEFAULT_LATENCY_WARN_THRESHOLD: Duration = Duration::from_millis(5);
+
+/// Default upper bounds of the LatencyHistogram buckets, in microseconds. The last bucket catches everything else.
+pub const DEFAULT_LATENCY_THRESHOLDS_US: [u64; 8] = [10, 50, 100, 500, 1000, 5000, 10000, u64::MAX];
+
+/// Distribution of durations over eight buckets. A sample is counted in the first bucket whose threshold it does not exceed.
+#[derive(Debug)]
+pub struct LatencyHistogram {
+    pub buckets: [AtomicU64; 8],
+    /// Inclusive upper bound of each bucket in microseconds, ascending
+    pub thresholds_us: [u64; 8],
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new(DEFAULT_LATENCY_THRESHOLDS_US)
+    }
+}
+
+impl LatencyHistogram {
+    pub fn new(thresholds_us: [u64; 8]) -> Self {
+        assert!(thresholds_us.is_sorted(), "thresholds must be ascending");
+        Self {
+            buckets: Default::default(),
+            thresholds_us,
+        }
+    }
+
+    pub fn record(&self, duration: Duration) {
+        let sample = duration.as_micros().min(u64::MAX as u128) as u64;
+        let index = self
+            .thresholds_us
+            .iter()
+            .position(|&t| sample <= t)
+            .unwrap_or(self.buckets.len() - 1);
+        self.buckets[index].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Current bucket counts
+    pub fn counts(&self) -> [u64; 8] {
+        std::array::from_fn(|i| self.buckets[i].load(Ordering::Relaxed))
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({ "thresholds_us": self.thresholds_us, "buckets": self.counts() })
+    }
+}
+
+/// Message routing statistics. Shared through an Arc so they can be read outside the stack thread.
+#[derive(Debug, Default)]
+pub struct RouterStats {
+    /// Number of messages delivered
+    pub delivered: AtomicU64,
+    /// Moving average over roughly the last 16 messages of the time from SapMsg creation to delivery, in microseconds
+    pub avg_latency_us: AtomicU64,
+    /// Time spent in rx_prim, per destination entity
+    processing_time: RwLock<HashMap<TetraEntity, LatencyHistogram>>,
+}
+
+impl RouterStats {
+    fn record_latency(&self, latency: Duration) {
+        let sample = latency.as_micros().min(u64::MAX as u128) as i64;
+        let avg = if self.delivered.fetch_add(1, Ordering::Relaxed) == 0 {
+            sample
+        } else {
+            let avg = self.avg_latency_us.load(Ordering::Relaxed) as i64;
+            avg + (sample - avg) / 16
+        };
+        self.avg_latency_us.store(avg as u64, Ordering::Relaxed);
+    }
+
+    fn record_processing_time(&self, entity: TetraEntity, duration: Duration) {
+        if let Some(histogram) = self.processing_time.read().unwrap().get(&entity) {
+            histogram.record(duration);
+            return;
+        }
+        self.processing_time.write().unwrap().entry(entity).or_default().record(duration);
+    }
+
+    /// Bucket counts of the rx_prim processing time of `entity`, None if it has not received any message yet
+    pub fn processing_time(&self, entity: TetraEntity) -> Option<[u64; 8]> {
+        self.processing_time.read().unwrap().get(&entity).map(LatencyHistogram::counts)
+    }
+
+    pub fn to_json(&self) -> Value {
+        let processing_time: serde_json::Map<String, Value> = self
+            .processing_time
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(entity, histogram)| (format!("{:?}", entity), histogram.to_json()))
+            .collect();
+        json!({
+            "delivered": self.delivered.load(Ordering::Relaxed),
+            "avg_latency_us": self.avg_latency_us.load(Ordering::Relaxed),
+            "processing_time_us": processing_time,
+        })
+    }
+}
+
+/// Message counters of one entity, see `MessageRouter::get_stats`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EntityStats {
+    /// Messages delivered to this entity
+    pub messages_received: u64,
+    /// Messages sent by this entity and taken from the queue for delivery, to any destination
+    pub messages_sent: u64,
+    /// Largest number of messages left in the router queue while a message was delivered to this entity
+    pub peak_queue_depth: usize,
+    /// Time of the last delivery to this entity
+    pub last_active: Option<Instant>,
+    /// Messages to this entity rejected because its queue was full, see `MessageRouter::set_max_queue_depth`
+    pub queue_overflows: u64,
+}
+
+/// One line of a message recording: a SapMsg and the downlink time at which it was delivered
+#[derive(Serialize, Deserialize)]
+struct RecordedMessage<M> {
+    dl_time: TdmaTime,
+    msg: M,
+}
 
 #[derive(Default)]
 pub enum MessagePrio {
@@ -15,44 +152,98 @@ pub enum MessagePrio {
     Normal,
 }
 
+/// Returned when a message is not queued because its destination already has `max_depth` messages queued
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueFull;
+
 pub struct MessageQueue {
     messages: VecDeque<SapMsg>,
+    /// Maximum number of queued messages per destination entity
+    max_depth: usize,
+    /// Number of queued messages per destination entity
+    depth: HashMap<TetraEntity, usize>,
+    /// Number of messages rejected because the destination was at max_depth
+    overflows: HashMap<TetraEntity, u64>,
 }
 
 impl MessageQueue {
     pub fn new() -> Self {
-        Self { messages: VecDeque::new() }
+        Self {
+            messages: VecDeque::new(),
+            max_depth: usize::MAX,
+            depth: HashMap::new(),
+            overflows: HashMap::new(),
+        }
     }
 
-    pub fn push_back(&mut self, message: SapMsg) {
+    /// Limit the number of queued messages per destination entity, unlimited by default
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+    }
+
+    /// Queue a message, unless its destination already has max_depth messages queued. On error the message is
+    /// dropped with a warning; callers that cannot afford to lose it have to keep a copy and send it again later.
+    pub fn push_back(&mut self, message: SapMsg) -> Result<(), QueueFull> {
+        let (sap, src, dest) = (*message.get_sap(), *message.get_source(), *message.get_dest());
+        let depth = self.depth.entry(dest).or_default();
+        if *depth >= self.max_depth {
+            *self.overflows.entry(dest).or_default() += 1;
+            tracing::warn!("push_back: queue for {:?} full, dropping {:?}: {:?} -> {:?}", dest, sap, src, dest);
+            return Err(QueueFull);
+        }
+        *depth += 1;
         self.messages.push_back(message);
+        Ok(())
     }
 
-    pub fn push_prio(&mut self, message: SapMsg, prio: MessagePrio) {
+    /// Queue a message with the given priority. Immediate messages are always accepted, so critical paths such as
+    /// FACCH stealing never stall; normal messages are subject to the depth limit as in `push_back`.
+    pub fn push_prio(&mut self, message: SapMsg, prio: MessagePrio) -> Result<(), QueueFull> {
         match prio {
             MessagePrio::Immediate => {
                 // Insert at the front for immediate processing
+                *self.depth.entry(*message.get_dest()).or_default() += 1;
                 self.messages.push_front(message);
+                Ok(())
             }
             MessagePrio::Normal => {
                 // Insert at the back for normal processing
-                self.messages.push_back(message);
+                self.push_back(message)
             }
         }
     }
 
     pub fn pop_front(&mut self) -> Option<SapMsg> {
-        self.messages.pop_front()
+        let message = self.messages.pop_front()?;
+        if let Some(depth) = self.depth.get_mut(message.get_dest()) {
+            *depth = depth.saturating_sub(1);
+        }
+        Some(message)
     }
 }
 
 pub struct MessageRouter {
-    /// While currently unused by the MessageRouter, this may change in the future
-    /// As such, we provide the MessageRouter with a copy of the SharedConfig
-    _config: SharedConfig,
+    /// Used to hand a reloaded config to the entities
+    config: SharedConfig,
     entities: HashMap<TetraEntity, Box<dyn TetraEntityTrait>>,
+    /// Entities in the order they were registered, shut down in reverse
+    registration_order: Vec<TetraEntity>,
     msg_queue: MessageQueue,
 
+    /// Link to the admin server, if enabled. Messages addressed to TetraEntity::Admin are returned over it.
+    admin: Option<AdminLink>,
+
+    /// Recording file, if enabled. Every delivered message is appended as one line of JSON.
+    recording: Option<BufWriter<File>>,
+
+    stats: Arc<RouterStats>,
+    /// Message counters per entity. Only read from the stack thread, so kept out of RouterStats.
+    entity_stats: HashMap<TetraEntity, EntityStats>,
+    latency_warn_threshold: Duration,
+    /// Log the RouterStats as JSON every interval, if set
+    stats_interval: Option<Duration>,
+    stats_last_logged: Instant,
+
     /// The current TDMA time, if applicable.
     /// For Bs mode, this is always available
     /// For Ms/Mon mode, it is recovered from a received SYNC frame and communicated in a different way
@@ -63,12 +254,154 @@ impl MessageRouter {
     pub fn new(config: SharedConfig) -> Self {
         Self {
             entities: HashMap::new(),
-            msg_queue: MessageQueue { messages: VecDeque::new() },
-            _config: config,
+            registration_order: Vec::new(),
+            msg_queue: MessageQueue::new(),
+            config,
+            admin: None,
+            recording: None,
+            stats: Arc::new(RouterStats::default()),
+            entity_stats: HashMap::new(),
+            latency_warn_threshold: DEFAULT_LATENCY_WARN_THRESHOLD,
+            stats_interval: None,
+            stats_last_logged: Instant::now(),
             ts: TdmaTime::default(),
         }
     }
 
+    /// Enable the admin interface. Requests are processed at the start of each tick.
+    pub fn set_admin_link(&mut self, link: AdminLink) {
+        self.admin = Some(link);
+    }
+
+    pub fn stats(&self) -> Arc<RouterStats> {
+        self.stats.clone()
+    }
+
+    /// Snapshot of the message counters of every entity that sent, received or overflowed a message so far
+    pub fn get_stats(&self) -> HashMap<TetraEntity, EntityStats> {
+        let mut stats = self.entity_stats.clone();
+        for (entity, overflows) in &self.msg_queue.overflows {
+            stats.entry(*entity).or_default().queue_overflows = *overflows;
+        }
+        stats
+    }
+
+    /// Limit the number of queued messages per destination entity. Further messages are rejected, except for
+    /// those pushed with MessagePrio::Immediate, and counted in EntityStats::queue_overflows.
+    pub fn set_max_queue_depth(&mut self, max_depth: usize) {
+        self.msg_queue.set_max_depth(max_depth);
+    }
+
+    /// Log a warning for every message that takes longer than `threshold` from creation to delivery
+    pub fn set_latency_warn_threshold(&mut self, threshold: Duration) {
+        self.latency_warn_threshold = threshold;
+    }
+
+    /// Log the router statistics every `interval`, None to disable
+    pub fn set_stats_interval(&mut self, interval: Option<Duration>) {
+        self.stats_interval = interval;
+    }
+
+    fn log_stats_if_due(&mut self) {
+        let Some(interval) = self.stats_interval else {
+            return;
+        };
+        if self.stats_last_logged.elapsed() >= interval {
+            self.stats_last_logged = Instant::now();
+            tracing::info!("Router stats: {}", self.stats.to_json());
+        }
+    }
+
+    /// Record every message delivered from now on to `path`, as newline-delimited JSON. An existing file is truncated.
+    pub fn enable_recording(&mut self, path: PathBuf) -> std::io::Result<()> {
+        let file = File::create(&path)?;
+        tracing::info!("Recording messages to {}", path.display());
+        self.recording = Some(BufWriter::new(file));
+        Ok(())
+    }
+
+    /// Flush and close the recording file, if any
+    pub fn stop_recording(&mut self) {
+        if let Some(mut recording) = self.recording.take()
+            && let Err(e) = recording.flush()
+        {
+            tracing::warn!("stop_recording: failed to flush recording: {}", e);
+        }
+    }
+
+    fn record_message(&mut self, message: &SapMsg) {
+        let Some(recording) = &mut self.recording else {
+            return;
+        };
+        let record = RecordedMessage {
+            dl_time: self.ts,
+            msg: message,
+        };
+        let result = serde_json::to_writer(&mut *recording, &record)
+            .map_err(std::io::Error::from)
+            .and_then(|_| recording.write_all(b"\n"));
+        if let Err(e) = result {
+            tracing::warn!("record_message: failed to write recording, disabling: {}", e);
+            self.recording = None;
+        }
+    }
+
+    /// Replay a file written by `enable_recording`. Only messages whose source is not registered in this router are
+    /// injected; everything the registered entities sent is regenerated by the stack itself. A replay stack built
+    /// without the PHY thus gets the recorded PHY traffic as input, along with anything injected by the admin
+    /// interface. Each injected message is delivered together with all messages it causes before the next one is
+    /// read, with the downlink time set to the recorded time.
+    /// Entity ticks are not run, so timer-driven behaviour is not reproduced.
+    /// Returns the number of injected messages.
+    pub fn replay_from_file(&mut self, path: PathBuf) -> Result<usize, Box<dyn std::error::Error>> {
+        let reader = BufReader::new(File::open(&path)?);
+        let mut injected = 0;
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: RecordedMessage<SapMsg> = serde_json::from_str(&line)?;
+            if self.entities.contains_key(record.msg.get_source()) {
+                continue;
+            }
+            self.ts = record.dl_time;
+            let _ = self.msg_queue.push_back(record.msg);
+            self.deliver_all_messages();
+            injected += 1;
+        }
+        tracing::info!("Replayed {} messages from {}", injected, path.display());
+        Ok(injected)
+    }
+
+    /// Submit injected messages and apply config reloads requested by the admin server
+    fn process_admin_requests(&mut self) {
+        let Some(admin) = &self.admin else {
+            return;
+        };
+        while let Some(request) = admin.try_recv() {
+            match request {
+                AdminRequest::Inject(message) => {
+                    tracing::debug!("admin: injecting {:?} -> {:?}", message.get_sap(), message.get_dest());
+                    let _ = self.msg_queue.push_back(*message);
+                }
+                AdminRequest::ReloadConfig(stack_config) => {
+                    let result = self.config.with_config(*stack_config).map(|config| {
+                        tracing::info!("admin: reloaded config, updating {} entities", self.entities.len());
+                        for entity in self.entities.values_mut() {
+                            entity.set_config(config.clone());
+                        }
+                        self.config = config;
+                    });
+                    if let Err(e) = &result {
+                        tracing::warn!("admin: rejected config reload: {}", e);
+                    }
+                    admin.reply(AdminReply::ConfigReloaded(result));
+                }
+            }
+        }
+    }
+
     /// For BS mode, sets global TDMA time
     /// Incremented each tick and passed to entities in tick() function
     pub fn set_dl_time(&mut self, ts: TdmaTime) {
@@ -78,7 +411,9 @@ impl MessageRouter {
     pub fn register_entity(&mut self, entity: Box<dyn TetraEntityTrait>) {
         let comp_type = entity.entity();
         tracing::debug!("register_entity {:?}", comp_type);
-        self.entities.insert(comp_type, entity);
+        if self.entities.insert(comp_type, entity).is_none() {
+            self.registration_order.push(comp_type);
+        }
     }
 
     /// Returns a mut ref to a component of the requested type
@@ -86,14 +421,15 @@ impl MessageRouter {
         self.entities.get_mut(&comp).map(|entity| entity.as_mut())
     }
 
-    pub fn submit_message(&mut self, message: SapMsg) {
+    /// Queue a message for delivery, see `MessageQueue::push_back`
+    pub fn submit_message(&mut self, message: SapMsg) -> Result<(), QueueFull> {
         tracing::debug!(
             "submit_message {:?}: {:?} -> {:?}",
             message.get_sap(),
             message.get_source(),
             message.get_dest()
         );
-        self.msg_queue.push_back(message);
+        self.msg_queue.push_back(message)
     }
 
     pub fn deliver_message(&mut self) {
@@ -105,13 +441,47 @@ impl MessageRouter {
                 message.get_source(),
                 message.get_dest()
             );
+            self.record_message(&message);
+            self.entity_stats.entry(*message.get_source()).or_default().messages_sent += 1;
+
+            let latency = message.created_at.elapsed();
+            self.stats.record_latency(latency);
+            if latency > self.latency_warn_threshold {
+                tracing::warn!(
+                    "deliver_message: {:?}: {:?} -> {:?} took {} us",
+                    message.get_sap(),
+                    message.get_source(),
+                    message.get_dest(),
+                    latency.as_micros()
+                );
+            }
 
             // Determine the destination entity
             let dest = message.get_dest();
 
+            // Replies to the admin interface leave the stack over the admin link
+            if *dest == TetraEntity::Admin {
+                if let Some(admin) = &self.admin {
+                    admin.reply(AdminReply::Message(Box::new(message)));
+                } else {
+                    tracing::warn!("deliver_message: admin interface not enabled, dropping {:?}", message.msg);
+                }
+                return;
+            }
+
             // Check if the destination entity registered and deliver if found
             if let Some(entity) = self.entities.get_mut(dest) {
+                let dest = *dest;
+                let start = Instant::now();
+                let entity_stats = self.entity_stats.entry(dest).or_default();
+                entity_stats.messages_received += 1;
+                entity_stats.peak_queue_depth = entity_stats.peak_queue_depth.max(self.msg_queue.messages.len());
+                entity_stats.last_active = Some(start);
+                // Messages tagged with a call are handled in a "call" span, so lower layer events can be correlated with CMCE's
+                let span = message.call_id.map(|call_id| tracing::info_span!("call", call_id));
+                let _enter = span.as_ref().map(|span| span.enter());
                 entity.rx_prim(&mut self.msg_queue, message);
+                self.stats.record_processing_time(dest, start.elapsed());
             } else {
                 tracing::warn!(
                     "deliver_message: entity {:?} not found for {:?}: {:?} -> {:?}",
@@ -124,6 +494,44 @@ impl MessageRouter {
         }
     }
 
+    /// Assemble a point-in-time snapshot of the stack state. MM, CMCE, UMAC and PHY are queried directly and
+    /// reply within the call, so the snapshot reflects the state between two message deliveries.
+    /// Entities that are not registered, or do not reply, leave their part empty.
+    pub fn get_stack_state(&mut self) -> StackStateSnapshot {
+        let mut snapshot = StackStateSnapshot::default();
+        for dest in [TetraEntity::Mm, TetraEntity::Cmce, TetraEntity::Umac, TetraEntity::Phy] {
+            let Some(entity) = self.entities.get_mut(&dest) else {
+                continue;
+            };
+            let query = SapMsg::new(
+                Sap::Control,
+                TetraEntity::Admin,
+                dest,
+                SapMsgInner::QueryStackState(QueryStackState),
+            );
+            let mut replies = MessageQueue::new();
+            entity.rx_prim(&mut replies, query);
+
+            let mut replied = false;
+            while let Some(reply) = replies.pop_front() {
+                match reply.msg {
+                    SapMsgInner::StackStateSnapshot(part) => {
+                        snapshot.merge(part);
+                        replied = true;
+                    }
+                    // Anything else the entity sent goes through the regular queue
+                    _ => {
+                        let _ = self.msg_queue.push_back(reply);
+                    }
+                }
+            }
+            if !replied {
+                tracing::warn!("get_stack_state: no reply from {:?}", dest);
+            }
+        }
+        snapshot
+    }
+
     pub fn deliver_all_messages(&mut self) {
         while !self.msg_queue.messages.is_empty() {
             self.deliver_message();
@@ -139,6 +547,8 @@ impl MessageRouter {
         //     self.ts, self.ts.add_timeslots(-2), self.ts.add_timeslots(MACSCHED_TX_AHEAD as i32));
         tracing::info!("--- tick dl {} ----------------------------", self.ts);
 
+        self.process_admin_requests();
+
         // Call tick on all entities
         for entity in self.entities.values_mut() {
             entity.tick_start(&mut self.msg_queue, self.ts);
@@ -178,13 +588,76 @@ impl MessageRouter {
         }
         self.deliver_all_messages();
 
+        if let Some(recording) = &mut self.recording
+            && let Err(e) = recording.flush()
+        {
+            tracing::warn!("tick_end: failed to flush recording, disabling: {}", e);
+            self.recording = None;
+        }
+
+        self.log_stats_if_due();
+
         // Increment the TDMA time if set
         self.ts = self.ts.add_timeslots(1);
     }
 
+    /// Runs a single tick: tick_start, delivery of all messages, tick_end
+    fn tick(&mut self) {
+        // Send tick_start event
+        self.tick_start();
+
+        // Deliver messages until queue empty
+        while self.get_msgqueue_len() > 0 {
+            self.deliver_all_messages();
+        }
+
+        // Send tick_end event and process final messages
+        self.tick_end();
+    }
+
+    /// Calls `shutdown` on all entities in reverse registration order, so upper layers go first.
+    /// If an entity sends messages from its shutdown hook, e.g. D-RELEASE for active calls, the stack keeps
+    /// running for SHUTDOWN_DRAIN_TICKS so they reach the air before the lower layers are shut down.
+    pub fn shutdown(&mut self) {
+        self.shutdown_entities(None);
+        self.stop_recording();
+    }
+
+    /// Like `shutdown`, but stops running the stack for drain ticks once `timeout` has passed. Shutdown hooks of the
+    /// remaining entities are still called and their messages delivered. Afterwards all entities are dropped, upper
+    /// layers first, so networked entities have disconnected when this returns.
+    pub fn graceful_shutdown(&mut self, timeout: Duration) {
+        self.shutdown_entities(Some(Instant::now() + timeout));
+        for comp in self.registration_order.drain(..).rev() {
+            tracing::debug!("graceful_shutdown: dropping {:?}", comp);
+            drop(self.entities.remove(&comp));
+        }
+        self.stop_recording();
+    }
+
+    fn shutdown_entities(&mut self, deadline: Option<Instant>) {
+        for comp in self.registration_order.clone().into_iter().rev() {
+            let Some(entity) = self.entities.get_mut(&comp) else {
+                continue;
+            };
+            tracing::debug!("shutdown {:?}", comp);
+            entity.shutdown(&mut self.msg_queue);
+            if self.get_msgqueue_len() > 0 {
+                self.deliver_all_messages();
+                for _ in 0..SHUTDOWN_DRAIN_TICKS {
+                    if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                        tracing::warn!("shutdown: timed out draining messages of {:?}", comp);
+                        break;
+                    }
+                    self.tick();
+                }
+            }
+        }
+    }
+
     /// Runs the full stack either forever or for a specified number of ticks.
     /// If `running` is provided, the loop will exit when the flag is set to false
-    /// (e.g. by a Ctrl+C signal handler), allowing entities to be dropped cleanly.
+    /// (e.g. by a Ctrl+C signal handler), after a graceful_shutdown of all entities.
     pub fn run_stack(&mut self, num_ticks: Option<usize>, running: Option<Arc<AtomicBool>>) {
         let mut ticks: usize = 0;
 
@@ -193,20 +666,12 @@ impl MessageRouter {
             if let Some(ref flag) = running {
                 if !flag.load(Ordering::Relaxed) {
                     eprintln!("\n[INFO] Shutting down gracefully...");
+                    self.graceful_shutdown(DEFAULT_SHUTDOWN_TIMEOUT);
                     break;
                 }
             }
 
-            // Send tick_start event
-            self.tick_start();
-
-            // Deliver messages until queue empty
-            while self.get_msgqueue_len() > 0 {
-                self.deliver_all_messages();
-            }
-
-            // Send tick_end event and process final messages
-            self.tick_end();
+            self.tick();
 
             // Check if we should stop
             ticks += 1;
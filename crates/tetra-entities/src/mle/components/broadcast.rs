@@ -67,7 +67,7 @@ impl MleBroadcast {
             cell_load_ca: 0,
             tetra_network_time: Some(time_value),
             number_of_ca_neighbour_cells: Some(0),
-            neighbour_cell_information_for_ca: None,
+            neighbour_cell_information_for_ca: Vec::new(),
         };
 
         // Serialize the PDU (includes 3-bit MLE PDU type)
@@ -85,11 +85,11 @@ impl MleBroadcast {
         tl_sdu.copy_bits(&mut pdu_buf, pdu_len);
         tl_sdu.seek(0);
 
-        let sapmsg = SapMsg {
-            sap: Sap::TlaSap,
-            src: TetraEntity::Mle,
-            dest: TetraEntity::Llc,
-            msg: SapMsgInner::TlaTlUnitdataReqBl(TlaTlUnitdataReqBl {
+        let sapmsg = SapMsg::new(
+            Sap::TlaSap,
+            TetraEntity::Mle,
+            TetraEntity::Llc,
+            SapMsgInner::TlaTlUnitdataReqBl(TlaTlUnitdataReqBl {
                 main_address: TetraAddress {
                     ssi: 0xFFFFFF,
                     ssi_type: SsiType::Gssi, // TODO FIXME is this actually the SMI?
@@ -113,8 +113,8 @@ impl MleBroadcast {
                 chan_alloc: None,
                 tx_reporter: None,
             }),
-        };
-        queue.push_back(sapmsg);
+        );
+        let _ = queue.push_back(sapmsg);
         tracing::info!("D-NWRK-BROADCAST sent (tz={}, time=0x{:012X})", tz, time_value);
     }
 }
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{MleHandle, MleHandleAllocator, TdmaTime};
+
+/// Time after which a request handle without a report is dropped, about 5 seconds
+pub const MLE_HANDLE_TIMEOUT: i32 = tetra_core::multiframes!(5);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HandleError {
+    /// No request with this handle is pending
+    Unknown(MleHandle),
+}
+
+impl std::fmt::Display for HandleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HandleError::Unknown(handle) => write!(f, "unknown MLE handle {}", handle),
+        }
+    }
+}
+
+impl std::error::Error for HandleError {}
+
+/// Correlates MLE-UNITDATA requests with the reports that refer to them.
+/// MM and CMCE number their requests independently, so each tracked request gets a handle of its own towards LLC,
+/// which LLC returns in its TL-REPORT. Handle 0 is used by service users that do not expect a report and is never
+/// tracked.
+pub struct HandleTracker {
+    /// LLC request handle -> (time the request was issued, entity that issued it, handle of that entity)
+    pending: HashMap<MleHandle, (TdmaTime, TetraEntity, MleHandle)>,
+    /// Handles passed to LLC
+    llc_handles: MleHandleAllocator,
+    /// Number of timeslots a handle is kept without receiving a report
+    timeout: i32,
+}
+
+impl Default for HandleTracker {
+    fn default() -> Self {
+        Self::new(MLE_HANDLE_TIMEOUT)
+    }
+}
+
+impl HandleTracker {
+    pub fn new(timeout: i32) -> Self {
+        Self {
+            pending: HashMap::new(),
+            llc_handles: MleHandleAllocator::default(),
+            timeout,
+        }
+    }
+
+    /// Record an outgoing request and return the handle to pass to LLC, or 0 if no report is expected
+    pub fn register(&mut self, handle: MleHandle, owner: TetraEntity, now: TdmaTime) -> MleHandle {
+        if handle == 0 {
+            return 0;
+        }
+        let llc_handle = self.llc_handles.allocate();
+        if let Some((_, previous, previous_handle)) = self.pending.insert(llc_handle, (now, owner, handle)) {
+            tracing::warn!(
+                "MLE handle {} reused for {:?} while pending for {:?} handle {}",
+                llc_handle,
+                owner,
+                previous,
+                previous_handle
+            );
+        }
+        llc_handle
+    }
+
+    /// Look up the request a report from LLC refers to, returning the entity that issued it and its handle.
+    /// The handle is completed and removed.
+    pub fn resolve(&mut self, llc_handle: MleHandle) -> Result<(TetraEntity, MleHandle), HandleError> {
+        self.pending
+            .remove(&llc_handle)
+            .map(|(_, owner, handle)| (owner, handle))
+            .ok_or(HandleError::Unknown(llc_handle))
+    }
+
+    pub fn is_pending(&self, llc_handle: MleHandle) -> bool {
+        self.pending.contains_key(&llc_handle)
+    }
+
+    /// Drop all handles older than the timeout. Returns the dropped LLC handles, sorted.
+    pub fn expire(&mut self, now: TdmaTime) -> Vec<MleHandle> {
+        let mut expired: Vec<MleHandle> = self
+            .pending
+            .iter()
+            .filter(|(_, (issued, _, _))| issued.add_timeslots(self.timeout).is_before(now))
+            .map(|(llc_handle, _)| *llc_handle)
+            .collect();
+        expired.sort_unstable();
+        for llc_handle in &expired {
+            if let Some((issued, owner, handle)) = self.pending.remove(llc_handle) {
+                tracing::warn!(
+                    "MLE handle {} from {:?} issued at {} got no report, dropping",
+                    handle,
+                    owner,
+                    issued
+                );
+            }
+        }
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_correlation() {
+        let mut tracker = HandleTracker::default();
+        let now = TdmaTime::default();
+        // MM and CMCE may use the same handle, LLC sees two different ones
+        let cmce = tracker.register(5, TetraEntity::Cmce, now);
+        let mm = tracker.register(5, TetraEntity::Mm, now);
+        assert_ne!(cmce, mm);
+        assert_eq!(tracker.resolve(cmce), Ok((TetraEntity::Cmce, 5)));
+        assert_eq!(tracker.resolve(mm), Ok((TetraEntity::Mm, 5)));
+        assert!(!tracker.is_pending(cmce));
+        assert_eq!(tracker.resolve(cmce), Err(HandleError::Unknown(cmce)));
+    }
+
+    #[test]
+    fn test_unknown_handle() {
+        let mut tracker = HandleTracker::default();
+        assert_eq!(tracker.register(0, TetraEntity::Cmce, TdmaTime::default()), 0);
+        assert!(!tracker.is_pending(0), "handle 0 is never tracked");
+        assert_eq!(tracker.resolve(0), Err(HandleError::Unknown(0)));
+        assert_eq!(tracker.resolve(42), Err(HandleError::Unknown(42)));
+    }
+
+    #[test]
+    fn test_timeout_eviction() {
+        let mut tracker = HandleTracker::new(100);
+        let start = TdmaTime::default();
+        let first = tracker.register(1, TetraEntity::Cmce, start);
+        let second = tracker.register(2, TetraEntity::Mm, start.add_timeslots(50));
+
+        assert!(tracker.expire(start.add_timeslots(100)).is_empty());
+        assert_eq!(tracker.expire(start.add_timeslots(101)), vec![first]);
+        assert!(tracker.is_pending(second));
+        assert_eq!(tracker.expire(start.add_timeslots(151)), vec![second]);
+        assert_eq!(tracker.resolve(second), Err(HandleError::Unknown(second)));
+    }
+}
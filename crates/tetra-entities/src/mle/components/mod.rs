@@ -1,3 +1,4 @@
 pub mod broadcast;
+pub mod handle_tracker;
 pub mod mle_router;
 pub mod network_time;
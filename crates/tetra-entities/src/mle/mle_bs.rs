@@ -1,20 +1,29 @@
 use crate::mle::components::broadcast::MleBroadcast;
+use crate::mle::components::handle_tracker::HandleTracker;
 use crate::{MessageQueue, TetraEntityTrait};
 use tetra_config::bluestation::SharedConfig;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, Layer2Service, Sap, TdmaTime, unimplemented_log};
-use tetra_saps::lcmc::LcmcMleUnitdataInd;
-use tetra_saps::lmm::LmmMleUnitdataInd;
+use tetra_core::{BitBuffer, ChannelClassInfo, Layer2Service, MleHandle, Sap, TdmaTime, TetraAddress, Todo, unimplemented_log};
+use tetra_saps::lcmc::{LcmcMleReportInd, LcmcMleUnitdataInd};
+use tetra_saps::lmm::{LmmMleReportInd, LmmMleUnitdataInd};
 use tetra_saps::ltpd::LtpdMleUnitdataInd;
-use tetra_saps::tla::{TlaTlDataReqBl, TlaTlUnitdataReqBl};
+use tetra_saps::tla::{TlaTlDataReqBl, TlaTlReportInd, TlaTlUnitdataReqBl};
+use tetra_saps::tlmc::{TlmcConfigureConf, TlmcConfigureReq};
 use tetra_saps::{SapMsg, SapMsgInner};
 
-use tetra_pdus::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
+use tetra_pdus::mle::enums::mle_pdu_type_ul::MlePduTypeUl;
 use tetra_pdus::mle::enums::mle_protocol_discriminator::MleProtocolDiscriminator;
+use tetra_pdus::mle::pdus::u_channel_class_advice::UChannelClassAdvice;
+use tetra_pdus::mle::pdus::u_prepare::UPrepare;
 
 pub struct MleBs {
     config: SharedConfig,
     broadcast: MleBroadcast,
+    /// Outstanding unitdata requests awaiting a report from LLC
+    handles: HandleTracker,
+    dltime: TdmaTime,
+    /// Whether the initial TL-CONFIGURE has been sent to the MAC
+    tlmc_configured: bool,
 }
 
 /// Multiframe at which D-NWRK-BROADCAST is sent within each hyperframe, 1-60
@@ -26,64 +35,147 @@ const MLE_BROADCAST_FRAME: u8 = 1;
 impl MleBs {
     pub fn new(config: SharedConfig) -> Self {
         let broadcast = MleBroadcast::new(config.clone());
-        Self { config, broadcast }
+        Self {
+            config,
+            broadcast,
+            handles: HandleTracker::default(),
+            dltime: TdmaTime::default(),
+            tlmc_configured: false,
+        }
     }
 
-    fn rx_tla_mle_pdu(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
-        tracing::trace!("rx_tla_mle_pdu");
-
-        // Extract tm_sdu from whatever primitive we have
-        let tm_sdu = {
-            match message.msg {
-                SapMsgInner::TlaTlDataIndBl(prim) => prim.tl_sdu,
-                _ => {
-                    panic!();
-                }
-            }
-        };
-        let Some(sdu) = tm_sdu else {
-            tracing::debug!("rx_tla_mle_pdu: no tm_sdu");
+    /// Configure the MAC with the cell parameters that affect layer 2. Only energy economy is passed on for now,
+    /// TLMC primitives go directly between MLE and MAC as the BS LLC doesn't act on them.
+    fn send_tlmc_configure_req(&mut self, queue: &mut MessageQueue) {
+        self.tlmc_configured = true;
+        let Some(ee) = self.config.config().cell.energy_economy else {
             return;
         };
+        tracing::info!(
+            "Configuring MAC for energy economy group EG{} from frame {}",
+            ee.group,
+            ee.startpoint
+        );
+        let msg = SapMsg::new(
+            Sap::TlmcSap,
+            TetraEntity::Mle,
+            TetraEntity::Umac,
+            SapMsgInner::TlmcConfigureReq(TlmcConfigureReq {
+                energy_economy_group: Some(ee.group),
+                energy_economy_startpoint: Some(ee.startpoint),
+                dual_watch_energy_economy_group: ee.dual_watch_group,
+                ..Default::default()
+            }),
+        );
+        let _ = queue.push_back(msg);
+    }
+
+    fn rx_tla_mle_pdu(&mut self, queue: &mut MessageQueue, mut sdu: BitBuffer, received_address: TetraAddress) {
+        tracing::trace!("rx_tla_mle_pdu");
 
         // Determine which type of TL-SDU we have and call handler function
         let Some(bits) = sdu.peek_bits(3) else {
             tracing::warn!("insufficient bits: {}", sdu.dump_bin());
             return;
         };
-        let Ok(pdu_type) = MlePduTypeDl::try_from(bits) else {
+        let Ok(pdu_type) = MlePduTypeUl::try_from(bits) else {
             tracing::warn!("invalid pdu type: {} in {}", bits, sdu.dump_bin());
             return;
         };
 
         match pdu_type {
-            MlePduTypeDl::DNewCell => {
-                unimplemented_log!("DNewCell")
+            MlePduTypeUl::UPrepare => {
+                self.rx_u_prepare(queue, &mut sdu, received_address);
             }
-            MlePduTypeDl::DPrepareFail => {
-                unimplemented_log!("DPrepareFail")
+            MlePduTypeUl::UPrepareDa => {
+                unimplemented_log!("UPrepareDa")
             }
-            MlePduTypeDl::DNwrkBroadcast => {
-                unimplemented_log!("DNwrkBroadcast")
+            MlePduTypeUl::UIrregularChannelAdvice => {
+                unimplemented_log!("UIrregularChannelAdvice")
             }
-            MlePduTypeDl::DNwrkBroadcastExt => {
-                unimplemented_log!("DNwrkBroadcastExt")
-            } // TODO FIXME CHECK this option and assocaited int
-            MlePduTypeDl::DRestoreAck => {
-                unimplemented_log!("DRestoreAck")
+            MlePduTypeUl::UChannelClassAdvice => {
+                self.rx_u_channel_class_advice(&mut sdu, received_address);
             }
-            MlePduTypeDl::DRestoreFail => {
-                unimplemented_log!("DRestoreFail")
+            MlePduTypeUl::URestore => {
+                unimplemented_log!("URestore")
             }
-            MlePduTypeDl::DChannelResponse => {
-                unimplemented_log!("DChannelResponse")
+            MlePduTypeUl::UChannelRequest => {
+                unimplemented_log!("UChannelRequest")
             }
-            MlePduTypeDl::ExtPdu => {
+            MlePduTypeUl::ExtPdu => {
                 unimplemented_log!("ExtPdu")
             }
         }
     }
 
+    /// U-PREPARE is sent by an MS announcing cell reselection. We don't have neighbour cells to hand over to,
+    /// but the PDU may carry an MM PDU (e.g. a forward registration), which is passed on to MM.
+    fn rx_u_prepare(&mut self, queue: &mut MessageQueue, sdu: &mut BitBuffer, received_address: TetraAddress) {
+        tracing::trace!("rx_u_prepare");
+        let pdu = match UPrepare::from_bitbuf(sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing UPrepare: {:?} {}", e, sdu.dump_bin());
+                return;
+            }
+        };
+
+        if pdu.cell_identifier_ca.is_some() {
+            unimplemented_log!("UPrepare cell reselection preparation to cell {:?}", pdu.cell_identifier_ca);
+        }
+
+        let Some(mm_sdu) = pdu.sdu else {
+            return;
+        };
+        let msg = SapMsg::new(
+            Sap::LmmSap,
+            TetraEntity::Mle,
+            TetraEntity::Mm,
+            SapMsgInner::LmmMleUnitdataInd(LmmMleUnitdataInd {
+                sdu: mm_sdu,
+                handle: 0,
+                received_address,
+            }),
+        );
+        let _ = queue.push_back(msg);
+    }
+
+    /// U-CHANNEL CLASS ADVICE lists the packet data channel classes an MS can use. The advice is kept with the
+    /// subscriber's registration, the SN PDU it may carry is dropped as we have no SNDCP.
+    fn rx_u_channel_class_advice(&mut self, sdu: &mut BitBuffer, received_address: TetraAddress) {
+        tracing::trace!("rx_u_channel_class_advice");
+        let pdu = match UChannelClassAdvice::from_bitbuf(sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing UChannelClassAdvice: {:?} {}", e, sdu.dump_bin());
+                return;
+            }
+        };
+
+        if pdu.sdu.is_some() {
+            unimplemented_log!("UChannelClassAdvice SDU with protocol {:?}", pdu.protocol_discriminator);
+        }
+
+        let channel_class = ChannelClassInfo {
+            identifiers: pdu.channel_class_identifiers,
+            data_priority: pdu.data_priority.map(|p| p as u8),
+        };
+        if !self
+            .config
+            .state_write()
+            .subscribers
+            .set_channel_class(received_address.ssi, channel_class)
+        {
+            tracing::warn!("rx_u_channel_class_advice: ISSI {} not registered, ignoring", received_address.ssi);
+        }
+    }
+
     fn rx_tla_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tla_prim");
         match message.msg {
@@ -94,12 +186,65 @@ impl MleBs {
                 // self.rx_tla_unitdata_ind_bl(queue, message);
                 panic!("BS can't receive TL-UNITDATA");
             }
+            SapMsgInner::TlaTlReportInd(prim) => {
+                self.rx_tla_report_ind(queue, prim);
+            }
+            SapMsgInner::TlaTlConnectInd(_) | SapMsgInner::TlaTlConnectConf(_) => {
+                unimplemented_log!("advanced link TL-CONNECT routing to service users");
+            }
             _ => {
                 panic!();
             }
         }
     }
 
+    /// Forward a report from LLC to the entity that issued the request it refers to
+    fn rx_tla_report_ind(&mut self, queue: &mut MessageQueue, prim: TlaTlReportInd) {
+        let Some(req_handle) = prim.req_handle else {
+            tracing::warn!("rx_tla_report_ind: report without request handle, dropping");
+            return;
+        };
+        let (owner, handle) = match self.handles.resolve(req_handle as MleHandle) {
+            Ok(request) => request,
+            Err(e) => {
+                tracing::warn!("rx_tla_report_ind: {}, dropping report", e);
+                return;
+            }
+        };
+
+        let msg = match owner {
+            TetraEntity::Mm => SapMsg::new(
+                Sap::LmmSap,
+                TetraEntity::Mle,
+                TetraEntity::Mm,
+                SapMsgInner::LmmMleReportInd(LmmMleReportInd {
+                    handle,
+                    transfer_result: prim.report,
+                }),
+            ),
+            TetraEntity::Cmce => SapMsg::new(
+                Sap::LcmcSap,
+                TetraEntity::Mle,
+                TetraEntity::Cmce,
+                SapMsgInner::LcmcMleReportInd(LcmcMleReportInd {
+                    handle,
+                    transfer_result: prim.report,
+                    channel_change_response_required: prim.chan_change_resp_req.unwrap_or(false),
+                    channel_change_handle: prim.chan_change_handle.unwrap_or_default(),
+                }),
+            ),
+            other => {
+                tracing::warn!(
+                    "rx_tla_report_ind: handle {} was issued by {:?}, which takes no reports",
+                    handle,
+                    other
+                );
+                return;
+            }
+        };
+        let _ = queue.push_back(msg);
+    }
+
     fn rx_tla_data_ind_bl(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         // Take ownership of bitbuf and read protocol discriminator
         let SapMsgInner::TlaTlDataIndBl(prim) = &mut message.msg else {
@@ -124,13 +269,8 @@ impl MleBs {
                     handle: 0,
                     received_address: prim.main_address,
                 };
-                let msg = SapMsg {
-                    sap: Sap::LmmSap,
-                    src: TetraEntity::Mle,
-                    dest: TetraEntity::Mm,
-                    msg: SapMsgInner::LmmMleUnitdataInd(m),
-                };
-                queue.push_back(msg);
+                let msg = SapMsg::new(Sap::LmmSap, TetraEntity::Mle, TetraEntity::Mm, SapMsgInner::LmmMleUnitdataInd(m));
+                let _ = queue.push_back(msg);
             }
             MleProtocolDiscriminator::Cmce => {
                 let m = LcmcMleUnitdataInd {
@@ -142,13 +282,13 @@ impl MleBs {
                     chan_change_resp_req: false, // TODO FIXME
                     chan_change_handle: None,    // TODO FIXME
                 };
-                let msg = SapMsg {
-                    sap: Sap::LcmcSap,
-                    src: TetraEntity::Mle,
-                    dest: TetraEntity::Cmce,
-                    msg: SapMsgInner::LcmcMleUnitdataInd(m),
-                };
-                queue.push_back(msg);
+                let msg = SapMsg::new(
+                    Sap::LcmcSap,
+                    TetraEntity::Mle,
+                    TetraEntity::Cmce,
+                    SapMsgInner::LcmcMleUnitdataInd(m),
+                );
+                let _ = queue.push_back(msg);
             }
             MleProtocolDiscriminator::Sndcp => {
                 let m = LtpdMleUnitdataInd {
@@ -159,16 +299,16 @@ impl MleBs {
                     chan_change_resp_req: false, // TODO FIXME
                     chan_change_handle: None,    // TODO FIXME
                 };
-                let msg = SapMsg {
-                    sap: Sap::LcmcSap,
-                    src: TetraEntity::Mle,
-                    dest: TetraEntity::Cmce,
-                    msg: SapMsgInner::LtpdMleUnitdataInd(m),
-                };
-                queue.push_back(msg);
+                let msg = SapMsg::new(
+                    Sap::LcmcSap,
+                    TetraEntity::Mle,
+                    TetraEntity::Cmce,
+                    SapMsgInner::LtpdMleUnitdataInd(m),
+                );
+                let _ = queue.push_back(msg);
             }
             MleProtocolDiscriminator::Mle => {
-                self.rx_tla_mle_pdu(queue, message);
+                self.rx_tla_mle_pdu(queue, sdu, prim.main_address);
             }
             MleProtocolDiscriminator::TetraManagementEntity => {
                 unimplemented_log!("MleProtocolDiscriminator::TetraManagementEntity");
@@ -176,9 +316,30 @@ impl MleBs {
         }
     }
 
-    fn rx_tlmc_prim(&mut self, _queue: &mut MessageQueue, _message: SapMsg) {
+    fn rx_tlmc_prim(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tlmc_prim");
-        unimplemented!("rx_tlmc_prim");
+        match message.msg {
+            SapMsgInner::TlmcConfigureConf(prim) => {
+                self.rx_tlmc_configure_conf(prim);
+            }
+            _ => {
+                unimplemented!("rx_tlmc_prim");
+            }
+        }
+    }
+
+    fn rx_tlmc_configure_conf(&mut self, prim: TlmcConfigureConf) {
+        let expected = self.config.config().cell.energy_economy;
+        let accepted = prim.energy_economy_group.zip(prim.energy_economy_startpoint);
+        if expected.map(|ee| (ee.group, ee.startpoint)) != accepted {
+            tracing::warn!(
+                "rx_tlmc_configure_conf: MAC accepted energy economy {:?}, configured {:?}",
+                accepted,
+                expected
+            );
+        } else {
+            tracing::debug!("rx_tlmc_configure_conf: energy economy {:?}", accepted);
+        }
     }
 
     fn rx_lmm_mle_unitdata_req(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
@@ -195,14 +356,15 @@ impl MleBs {
         pdu.seek(0);
 
         assert!(prim.layer2service != Layer2Service::Unacknowledged, "not implemented");
+        let req_handle = self.handles.register(prim.handle, message.src, self.dltime);
 
         // let (addr, link, endpoint) = self.router.use_handle(prim.handle, message.dltime);
         // assert_eq!(addr.ssi, prim.address.ssi);
-        let sapmsg = SapMsg {
-            sap: Sap::TlaSap,
-            src: TetraEntity::Mle,
-            dest: TetraEntity::Llc,
-            msg: SapMsgInner::TlaTlDataReqBl(TlaTlDataReqBl {
+        let sapmsg = SapMsg::new(
+            Sap::TlaSap,
+            TetraEntity::Mle,
+            TetraEntity::Llc,
+            SapMsgInner::TlaTlDataReqBl(TlaTlDataReqBl {
                 main_address: prim.address,
                 link_id: 0,
                 endpoint_id: 0,
@@ -213,13 +375,13 @@ impl MleBs {
                 air_interface_encryption: None,
                 stealing_repeats_flag: None,
                 data_class_info: None,
-                req_handle: 0, // TODO FIXME; should we pass the same handle here?
+                req_handle: req_handle as Todo,
                 graceful_degradation: None,
                 chan_alloc: None,
                 tx_reporter: prim.tx_reporter.take(),
             }),
-        };
-        queue.push_back(sapmsg);
+        );
+        let _ = queue.push_back(sapmsg);
     }
 
     fn rx_lmm_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
@@ -260,14 +422,20 @@ impl MleBs {
         // assert_eq!(endpoint, prim.endpoint_id);
         // Take Channel Allocation Request if any
         let chan_alloc = prim.chan_alloc.take();
+        // LLC only reports on transfers with the acknowledged service, so only those are tracked
+        let req_handle = if prim.layer2service == Layer2Service::Unacknowledged {
+            0
+        } else {
+            self.handles.register(prim.handle, message.src, self.dltime)
+        };
 
         let sapmsg = if prim.layer2service == Layer2Service::Unacknowledged {
             // Unacknowledged service, send a TlUnitdataReqBl
-            SapMsg {
-                sap: Sap::TlaSap,
-                src: TetraEntity::Mle,
-                dest: TetraEntity::Llc,
-                msg: SapMsgInner::TlaTlUnitdataReqBl(TlaTlUnitdataReqBl {
+            SapMsg::new(
+                Sap::TlaSap,
+                TetraEntity::Mle,
+                TetraEntity::Llc,
+                SapMsgInner::TlaTlUnitdataReqBl(TlaTlUnitdataReqBl {
                     main_address: prim.main_address,
                     link_id: prim.link_id,
                     endpoint_id: prim.endpoint_id,
@@ -277,21 +445,22 @@ impl MleBs {
                     fcs_flag: false,
                     air_interface_encryption: None,
                     packet_data_flag: false,
-                    n_tlsdu_repeats: 0,
+                    n_tlsdu_repeats: prim.redundant_transmission.saturating_sub(1),
                     data_class_info: None,
-                    req_handle: 0,
+                    req_handle: req_handle as Todo,
 
                     chan_alloc,
                     tx_reporter: prim.tx_reporter.take(),
                 }),
-            }
+            )
+            .with_call_id(message.call_id)
         } else {
             // Acknowledged service, send a TlDataReqBl
-            SapMsg {
-                sap: Sap::TlaSap,
-                src: TetraEntity::Mle,
-                dest: TetraEntity::Llc,
-                msg: SapMsgInner::TlaTlDataReqBl(TlaTlDataReqBl {
+            SapMsg::new(
+                Sap::TlaSap,
+                TetraEntity::Mle,
+                TetraEntity::Llc,
+                SapMsgInner::TlaTlDataReqBl(TlaTlDataReqBl {
                     main_address: prim.main_address,
                     link_id: prim.link_id,
                     endpoint_id: prim.endpoint_id,
@@ -302,15 +471,16 @@ impl MleBs {
                     air_interface_encryption: None,
                     stealing_repeats_flag: None,
                     data_class_info: None,
-                    req_handle: 0, // TODO FIXME
+                    req_handle: req_handle as Todo,
                     graceful_degradation: None,
                     chan_alloc,
                     tx_reporter: prim.tx_reporter.take(),
                 }),
-            }
+            )
+            .with_call_id(message.call_id)
         };
 
-        queue.push_back(sapmsg);
+        let _ = queue.push_back(sapmsg);
     }
 
     fn rx_lcmc_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
@@ -330,6 +500,13 @@ impl TetraEntityTrait for MleBs {
     }
 
     fn tick_start(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
+        self.dltime = ts;
+        self.handles.expire(ts);
+
+        if !self.tlmc_configured {
+            self.send_tlmc_configure_req(queue);
+        }
+
         // Broadcast D-NWRK-BROADCAST once per hyperframe if timezone is configured.
         // Use a constant multiframe/frame offset to avoid congestion with other
         // hyperframe-triggered events.
@@ -1,3 +1,4 @@
+
 use crate::mle::components::mle_router::MleRouter;
 use crate::mle::components::network_time::encode_tetra_network_time;
 use crate::{MessageQueue, TetraEntityTrait};
@@ -93,6 +94,9 @@ impl MleMs {
             SapMsgInner::TlaTlUnitdataIndBl(_) => {
                 self.rx_tla_unitdata_ind_bl(queue, message);
             }
+            SapMsgInner::TlaTlConnectInd(_) | SapMsgInner::TlaTlConnectConf(_) => {
+                unimplemented_log!("advanced link TL-CONNECT routing to service users");
+            }
             _ => {
                 panic!();
             }
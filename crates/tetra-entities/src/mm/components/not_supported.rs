@@ -23,11 +23,11 @@ pub fn make_ul_mm_pdu_function_not_supported(
     let debug_str = format!("{:?} sdu {}", pdu, sdu.dump_bin());
 
     // Package
-    let msg = SapMsg {
-        sap: Sap::LmmSap,
-        src: TetraEntity::Mm,
-        dest: TetraEntity::Mle,
-        msg: SapMsgInner::LmmMleUnitdataReq(LmmMleUnitdataReq {
+    let msg = SapMsg::new(
+        Sap::LmmSap,
+        TetraEntity::Mm,
+        TetraEntity::Mle,
+        SapMsgInner::LmmMleUnitdataReq(LmmMleUnitdataReq {
             sdu,
             handle,
             address: ssi,
@@ -38,6 +38,6 @@ pub fn make_ul_mm_pdu_function_not_supported(
             is_null_pdu: false,
             tx_reporter: None,
         }),
-    };
+    );
     (msg, debug_str)
 }
@@ -1,9 +1,10 @@
 use crate::net_control::ControlEndpoint;
 use crate::net_telemetry::channel::TelemetrySink;
 use crate::{MessageQueue, TetraEntityTrait, net_brew};
-use tetra_config::bluestation::SharedConfig;
+use tetra_config::bluestation::{SharedConfig, SubscriberPolicy};
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, Layer2Service, Sap, TdmaTime, TetraAddress, assert_warn, unimplemented_log};
+use tetra_core::{BitBuffer, Layer2Service, MleHandleAllocator, Sap, SsiType, TdmaTime, TetraAddress, assert_warn, unimplemented_log};
+use tetra_saps::control::admin::{RegisteredMs, RegisteredMsReply, StackStateSnapshot};
 use tetra_saps::control::brew::{BrewSubscriberAction, MmSubscriberUpdate};
 use tetra_saps::lmm::LmmMleUnitdataReq;
 use tetra_saps::{SapMsg, SapMsgInner};
@@ -36,6 +37,8 @@ pub struct MmBs {
     telemetry: Option<TelemetrySink>,
     control: Option<ControlEndpoint>,
     client_mgr: MmClientMgr,
+    /// Handles of our MLE-UNITDATA requests, returned in the MLE-REPORT for each
+    handles: MleHandleAllocator,
 }
 
 impl MmBs {
@@ -46,6 +49,7 @@ impl MmBs {
             telemetry,
             control,
             client_mgr,
+            handles: MleHandleAllocator::default(),
         }
     }
 
@@ -71,25 +75,25 @@ impl MmBs {
                     groups: brew_groups,
                     action,
                 };
-                let msg = SapMsg {
-                    sap: Sap::Control,
-                    src: TetraEntity::Mm,
-                    dest: TetraEntity::Brew,
-                    msg: SapMsgInner::MmSubscriberUpdate(brew_update),
-                };
-                queue.push_back(msg);
+                let msg = SapMsg::new(
+                    Sap::Control,
+                    TetraEntity::Mm,
+                    TetraEntity::Brew,
+                    SapMsgInner::MmSubscriberUpdate(brew_update),
+                );
+                let _ = queue.push_back(msg);
             }
         }
 
         // Always emit an update to the Cmce entity
         let mm_update = MmSubscriberUpdate { issi, groups, action };
-        let msg = SapMsg {
-            sap: Sap::Control,
-            src: TetraEntity::Mm,
-            dest: TetraEntity::Cmce,
-            msg: SapMsgInner::MmSubscriberUpdate(mm_update),
-        };
-        queue.push_back(msg);
+        let msg = SapMsg::new(
+            Sap::Control,
+            TetraEntity::Mm,
+            TetraEntity::Cmce,
+            SapMsgInner::MmSubscriberUpdate(mm_update),
+        );
+        let _ = queue.push_back(msg);
     }
 
     fn rx_u_itsi_detach(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
@@ -147,6 +151,12 @@ impl MmBs {
             }
         };
 
+        // A reserved ISSI cannot be registered, and there is no valid address to reject it to
+        if let Err(e) = TetraAddress::try_new(prim.received_address.ssi, SsiType::Issi) {
+            tracing::warn!("Ignoring location update demand: {}", e);
+            return;
+        }
+
         // Migration not supported: ETSI 16.4.1.1 case b) requires identity exchange via
         // D-LOCATION-UPDATE-PROCEEDING which we don't implement. Reject with cause
         // "Migration not supported" (12, Table 16.81) so the MS can act on it.
@@ -161,13 +171,59 @@ impl MmBs {
             Self::send_d_location_update_reject(
                 queue,
                 prim.received_address.ssi,
-                prim.handle,
+                self.handles.allocate(),
                 pdu.location_update_type,
+                RejectCause::MigrationNotSupported,
                 pdu.address_extension,
             );
             return;
         }
 
+        // Closed networks: only provisioned subscribers may register
+        let config = self.config.config();
+        let policy = &config.cell.subscriber_policy;
+        if !policy.permits(prim.received_address.ssi) {
+            let reject_cause = match policy {
+                // Not on the whitelist: the subscription is not provisioned in this network
+                SubscriberPolicy::Whitelist { .. } => RejectCause::ItsiAtsiUnknown,
+                _ => RejectCause::IllegalMs,
+            };
+            tracing::warn!(
+                "Rejecting location update from SSI {} by subscriber policy: {:?}",
+                prim.received_address.ssi,
+                reject_cause
+            );
+            Self::send_d_location_update_reject(
+                queue,
+                prim.received_address.ssi,
+                self.handles.allocate(),
+                pdu.location_update_type,
+                reject_cause,
+                None,
+            );
+            return;
+        }
+
+        // MSs assigned to subscriber classes that are all barred in this cell may not register here
+        if !config.cell.subscriber_class_permits(prim.received_address.ssi) {
+            tracing::warn!(
+                "Rejecting location update from SSI {}: subscriber class {:#06x} not allowed in cell ({:#06x})",
+                prim.received_address.ssi,
+                config.cell.ms_subscriber_class(prim.received_address.ssi).unwrap_or_default(),
+                config.cell.subscriber_class
+            );
+            Self::send_d_location_update_reject(
+                queue,
+                prim.received_address.ssi,
+                self.handles.allocate(),
+                pdu.location_update_type,
+                RejectCause::LaNotAllowed,
+                None,
+            );
+            return;
+        }
+        let subscriber_class = config.cell.ms_subscriber_class(prim.received_address.ssi);
+
         // Check if we can satisfy this request, print unsupported stuff
         if !Self::feature_check_u_location_update_demand(&pdu) {
             tracing::error!("Unsupported critical features in ULocationUpdateDemand");
@@ -198,7 +254,6 @@ impl MmBs {
 
         // Try to register the client
         let issi = prim.received_address.ssi;
-        let handle = prim.handle;
         let is_new = !self.client_mgr.client_is_known(issi);
         if is_new {
             match self.client_mgr.try_register_client(issi, true) {
@@ -274,7 +329,7 @@ impl MmBs {
             location_update_accept_type: pdu.location_update_type,
             ssi: Some(issi as u64),
             address_extension: None,
-            subscriber_class: None,
+            subscriber_class: subscriber_class.map(|class| class as u64),
             energy_saving_information: esi,
             scch_information_and_distribution_on_18th_frame: None,
             new_registered_area: None,
@@ -295,13 +350,13 @@ impl MmBs {
         tracing::debug!("-> {} sdu {}", pdu_response, sdu.dump_bin());
 
         // Build and submit response prim
-        let msg = SapMsg {
-            sap: Sap::LmmSap,
-            src: TetraEntity::Mm,
-            dest: TetraEntity::Mle,
-            msg: SapMsgInner::LmmMleUnitdataReq(LmmMleUnitdataReq {
+        let msg = SapMsg::new(
+            Sap::LmmSap,
+            TetraEntity::Mm,
+            TetraEntity::Mle,
+            SapMsgInner::LmmMleUnitdataReq(LmmMleUnitdataReq {
                 sdu,
-                handle: prim.handle,
+                handle: self.handles.allocate(),
                 address: TetraAddress::issi(issi),
                 layer2service: Layer2Service::Acknowledged,
                 stealing_permission: false,
@@ -310,14 +365,14 @@ impl MmBs {
                 is_null_pdu: false,
                 tx_reporter: None,
             }),
-        };
-        queue.push_back(msg);
+        );
+        let _ = queue.push_back(msg);
 
         // If this is an unknown returning radio (not ITSI attach), force it to
         // re-register with full group report via D-LOCATION UPDATE COMMAND
         if is_new && pdu.location_update_type != LocationUpdateType::ItsiAttach && !has_groups {
             tracing::info!("Sending D-LOCATION UPDATE COMMAND to returning MS {} to request group report", issi);
-            Self::send_d_location_update_command(queue, issi, handle);
+            Self::send_d_location_update_command(queue, issi, self.handles.allocate());
         }
     }
 
@@ -339,7 +394,6 @@ impl MmBs {
         };
 
         let issi = prim.received_address.ssi;
-        let handle = prim.handle;
 
         let mut handled = false;
         match pdu.status_uplink {
@@ -377,7 +431,7 @@ impl MmBs {
                     frame_number: None,
                     multiframe_number: None,
                 };
-                Self::send_d_mm_status_energy_saving(queue, issi, handle, esi);
+                Self::send_d_mm_status_energy_saving(queue, issi, self.handles.allocate(), esi);
                 handled = true;
             }
             StatusUplink::ChangeOfEnergySavingModeResponse => {
@@ -423,13 +477,13 @@ impl MmBs {
             // A fairly untested, best-effort way of sending a PDU not supported error back
             // Note that an MS is not required to really do anything with this message.
             let (sapmsg, debug_str) = make_ul_mm_pdu_function_not_supported(
-                handle,
+                self.handles.allocate(),
                 MmPduTypeUl::UMmStatus,
                 Some((6, pdu.status_uplink.into())),
                 prim.received_address,
             );
             tracing::debug!("-> {}", debug_str);
-            queue.push_back(sapmsg);
+            let _ = queue.push_back(sapmsg);
         }
     }
 
@@ -439,7 +493,13 @@ impl MmBs {
             panic!()
         };
 
-        let issi = prim.received_address.ssi;
+        let issi = match TetraAddress::try_new(prim.received_address.ssi, SsiType::Issi) {
+            Ok(addr) => addr.ssi,
+            Err(e) => {
+                tracing::warn!("Ignoring group attach/detach: {}", e);
+                return;
+            }
+        };
 
         let pdu = match UAttachDetachGroupIdentity::from_bitbuf(&mut prim.sdu) {
             Ok(pdu) => {
@@ -519,13 +579,13 @@ impl MmBs {
         sdu.seek(0);
         tracing::debug!("-> {:?} sdu {}", pdu_response, sdu.dump_bin());
 
-        let msg = SapMsg {
-            sap: Sap::LmmSap,
-            src: TetraEntity::Mm,
-            dest: TetraEntity::Mle,
-            msg: SapMsgInner::LmmMleUnitdataReq(LmmMleUnitdataReq {
+        let msg = SapMsg::new(
+            Sap::LmmSap,
+            TetraEntity::Mm,
+            TetraEntity::Mle,
+            SapMsgInner::LmmMleUnitdataReq(LmmMleUnitdataReq {
                 sdu,
-                handle: prim.handle,
+                handle: self.handles.allocate(),
                 address: TetraAddress::issi(issi),
                 layer2service: Layer2Service::Acknowledged,
                 stealing_permission: false,
@@ -534,8 +594,8 @@ impl MmBs {
                 is_null_pdu: false,
                 tx_reporter: None,
             }),
-        };
-        queue.push_back(msg);
+        );
+        let _ = queue.push_back(msg);
     }
 
     fn rx_lmm_mle_unitdata_ind(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
@@ -586,7 +646,13 @@ impl MmBs {
                 continue;
             }
 
-            let gssi = giu.gssi.unwrap(); // can't fail
+            let gssi = match TetraAddress::try_new(giu.gssi.unwrap(), SsiType::Gssi) {
+                Ok(addr) => addr.ssi,
+                Err(e) => {
+                    tracing::warn!("MS {} group attach/detach with invalid GSSI: {}", issi, e);
+                    continue;
+                }
+            };
             let is_detach = giu.group_identity_detachment_uplink.is_some();
 
             if is_detach {
@@ -663,11 +729,11 @@ impl MmBs {
         sdu.seek(0);
         tracing::debug!("-> DLocationUpdateCommand sdu {}", sdu.dump_bin());
 
-        let msg = SapMsg {
-            sap: Sap::LmmSap,
-            src: TetraEntity::Mm,
-            dest: TetraEntity::Mle,
-            msg: SapMsgInner::LmmMleUnitdataReq(LmmMleUnitdataReq {
+        let msg = SapMsg::new(
+            Sap::LmmSap,
+            TetraEntity::Mm,
+            TetraEntity::Mle,
+            SapMsgInner::LmmMleUnitdataReq(LmmMleUnitdataReq {
                 sdu,
                 handle,
                 address: TetraAddress::issi(issi),
@@ -678,8 +744,8 @@ impl MmBs {
                 is_null_pdu: false,
                 tx_reporter: None,
             }),
-        };
-        queue.push_back(msg);
+        );
+        let _ = queue.push_back(msg);
     }
 
     /// Sends a D-LOCATION UPDATE REJECT PDU (ETSI clause 16.9.2.9)
@@ -688,11 +754,12 @@ impl MmBs {
         issi: u32,
         handle: u32,
         location_update_type: LocationUpdateType,
+        reject_cause: RejectCause,
         address_extension: Option<u64>,
     ) {
         let pdu = DLocationUpdateReject {
             location_update_type,
-            reject_cause: RejectCause::MigrationNotSupported as u8,
+            reject_cause: reject_cause as u8,
             cipher_control: false,
             ciphering_parameters: None,
             // Echo back MNI if present, required for case b) per ETSI 16.4.1.1
@@ -706,11 +773,11 @@ impl MmBs {
         sdu.seek(0);
         tracing::debug!("-> {} sdu {}", pdu, sdu.dump_bin());
 
-        let msg = SapMsg {
-            sap: Sap::LmmSap,
-            src: TetraEntity::Mm,
-            dest: TetraEntity::Mle,
-            msg: SapMsgInner::LmmMleUnitdataReq(LmmMleUnitdataReq {
+        let msg = SapMsg::new(
+            Sap::LmmSap,
+            TetraEntity::Mm,
+            TetraEntity::Mle,
+            SapMsgInner::LmmMleUnitdataReq(LmmMleUnitdataReq {
                 sdu,
                 handle,
                 address: TetraAddress::issi(issi),
@@ -721,8 +788,8 @@ impl MmBs {
                 is_null_pdu: false,
                 tx_reporter: None,
             }),
-        };
-        queue.push_back(msg);
+        );
+        let _ = queue.push_back(msg);
     }
 
     /// Sends a D-MM-STATUS with ChangeOfEnergySavingModeResponse
@@ -737,11 +804,11 @@ impl MmBs {
         sdu.seek(0);
         tracing::debug!("-> {} sdu {}", pdu, sdu.dump_bin());
 
-        let msg = SapMsg {
-            sap: Sap::LmmSap,
-            src: TetraEntity::Mm,
-            dest: TetraEntity::Mle,
-            msg: SapMsgInner::LmmMleUnitdataReq(LmmMleUnitdataReq {
+        let msg = SapMsg::new(
+            Sap::LmmSap,
+            TetraEntity::Mm,
+            TetraEntity::Mle,
+            SapMsgInner::LmmMleUnitdataReq(LmmMleUnitdataReq {
                 sdu,
                 handle,
                 address: TetraAddress::issi(issi),
@@ -752,8 +819,8 @@ impl MmBs {
                 is_null_pdu: false,
                 tx_reporter: None,
             }),
-        };
-        queue.push_back(msg);
+        );
+        let _ = queue.push_back(msg);
     }
 
     fn feature_check_u_itsi_detach(pdu: &UItsiDetach) -> bool {
@@ -832,6 +899,54 @@ impl MmBs {
 
         supported
     }
+
+    /// All registered subscribers, sorted by ISSI
+    fn registered_ms(&self) -> Vec<RegisteredMs> {
+        let config = self.config.config();
+        let aliases = config.brew.as_ref().map(|brew| &brew.alias_map);
+        let mut subscribers: Vec<RegisteredMs> = self
+            .config
+            .state_read()
+            .subscribers
+            .subscribers()
+            .map(|subscriber| {
+                let mut groups: Vec<u32> = subscriber.attached_groups.iter().copied().collect();
+                groups.sort_unstable();
+                RegisteredMs {
+                    issi: subscriber.issi,
+                    groups,
+                    alias: aliases.and_then(|aliases| aliases.get(&subscriber.issi)).cloned(),
+                    channel_class: subscriber.channel_class.clone(),
+                }
+            })
+            .collect();
+        subscribers.sort_unstable_by_key(|subscriber| subscriber.issi);
+        subscribers
+    }
+
+    /// Reply to the sender with the registered subscribers and their attached groups
+    fn rx_query_registered_ms(&mut self, queue: &mut MessageQueue, sender: TetraEntity) {
+        let _ = queue.push_back(SapMsg::new(
+            Sap::Control,
+            TetraEntity::Mm,
+            sender,
+            SapMsgInner::RegisteredMsReply(RegisteredMsReply {
+                subscribers: self.registered_ms(),
+            }),
+        ));
+    }
+
+    fn rx_query_stack_state(&mut self, queue: &mut MessageQueue, sender: TetraEntity) {
+        let _ = queue.push_back(SapMsg::new(
+            Sap::Control,
+            TetraEntity::Mm,
+            sender,
+            SapMsgInner::StackStateSnapshot(StackStateSnapshot {
+                registered_ms: self.registered_ms(),
+                ..Default::default()
+            }),
+        ));
+    }
 }
 
 impl TetraEntityTrait for MmBs {
@@ -862,15 +977,31 @@ impl TetraEntityTrait for MmBs {
         tracing::debug!("rx_prim: {:?}", message);
         // tracing::debug!(ts=%message.dltime, "rx_prim: {:?}", message);
 
-        // There is only one SAP for MM
-        assert!(message.sap == Sap::LmmSap);
-
-        match message.msg {
-            SapMsgInner::LmmMleUnitdataInd(_) => {
-                self.rx_lmm_mle_unitdata_ind(queue, message);
-            }
+        match message.sap {
+            Sap::LmmSap => match message.msg {
+                SapMsgInner::LmmMleUnitdataInd(_) => {
+                    self.rx_lmm_mle_unitdata_ind(queue, message);
+                }
+                SapMsgInner::LmmMleReportInd(prim) => {
+                    tracing::debug!("rx_prim: report for handle {}: {:?}", prim.handle, prim);
+                }
+                _ => {
+                    panic!();
+                }
+            },
+            Sap::Control => match message.msg {
+                SapMsgInner::QueryRegisteredMs(_) => {
+                    self.rx_query_registered_ms(queue, message.src);
+                }
+                SapMsgInner::QueryStackState(_) => {
+                    self.rx_query_stack_state(queue, message.src);
+                }
+                _ => {
+                    panic!("Unexpected control message: {:?}", message.msg);
+                }
+            },
             _ => {
-                panic!();
+                panic!("Unexpected SAP: {:?}", message.sap);
             }
         }
     }
@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use crossbeam_channel::{Receiver, Sender, unbounded};
+use tetra_config::bluestation::StackConfig;
+use tetra_saps::SapMsg;
+
+/// Request from the admin server to the stack
+pub enum AdminRequest {
+    /// Message to submit to the stack. The reply addressed to `TetraEntity::Admin` is returned as [`AdminReply::Message`].
+    Inject(Box<SapMsg>),
+    /// Replace the configuration of all entities
+    ReloadConfig(Box<StackConfig>),
+}
+
+/// Reply from the stack to the admin server
+pub enum AdminReply {
+    /// A message addressed to `TetraEntity::Admin`
+    Message(Box<SapMsg>),
+    /// Outcome of a [`AdminRequest::ReloadConfig`]
+    ConfigReloaded(Result<(), String>),
+}
+
+/// Admin server side of the link
+pub struct AdminClient {
+    req_tx: Sender<AdminRequest>,
+    reply_rx: Receiver<AdminReply>,
+}
+
+impl AdminClient {
+    /// Send a request and wait for its reply. Replies to earlier requests that timed out are discarded first.
+    /// Returns None if the stack did not answer within the timeout.
+    pub fn request(&self, request: AdminRequest, timeout: Duration) -> Option<AdminReply> {
+        while self.reply_rx.try_recv().is_ok() {}
+        self.req_tx.send(request).ok()?;
+        self.reply_rx.recv_timeout(timeout).ok()
+    }
+}
+
+/// Stack side of the link, held by the MessageRouter
+pub struct AdminLink {
+    req_rx: Receiver<AdminRequest>,
+    reply_tx: Sender<AdminReply>,
+}
+
+impl AdminLink {
+    /// Non-blocking: receive a pending request, if any.
+    pub fn try_recv(&self) -> Option<AdminRequest> {
+        self.req_rx.try_recv().ok()
+    }
+
+    /// Send a reply to the admin server. Silently dropped if the server is gone.
+    pub fn reply(&self, reply: AdminReply) {
+        let _ = self.reply_tx.send(reply);
+    }
+}
+
+/// Create a connected (client, link) pair
+pub fn make_admin_link() -> (AdminClient, AdminLink) {
+    let (req_tx, req_rx) = unbounded();
+    let (reply_tx, reply_rx) = unbounded();
+    (AdminClient { req_tx, reply_rx }, AdminLink { req_rx, reply_tx })
+}
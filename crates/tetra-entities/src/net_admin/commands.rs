@@ -0,0 +1,37 @@
+use serde::Deserialize;
+
+/// Command received on the admin port, one JSON object per line, e.g. `{ "cmd": "release_call", "call_id": 42 }`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum AdminCommand {
+    /// LLC counters
+    Stats,
+    /// Registered subscribers and their attached groups
+    RegisteredMs,
+    /// Active group and individual calls
+    ActiveCalls,
+    /// Re-read the config file and hand it to all entities
+    ReloadConfig,
+    /// Release a call
+    ReleaseCall { call_id: u16 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        let parse = |line: &str| serde_json::from_str::<AdminCommand>(line).unwrap();
+        assert_eq!(parse(r#"{ "cmd": "stats" }"#), AdminCommand::Stats);
+        assert_eq!(parse(r#"{ "cmd": "registered_ms" }"#), AdminCommand::RegisteredMs);
+        assert_eq!(parse(r#"{ "cmd": "active_calls" }"#), AdminCommand::ActiveCalls);
+        assert_eq!(parse(r#"{ "cmd": "reload_config" }"#), AdminCommand::ReloadConfig);
+        assert_eq!(
+            parse(r#"{ "cmd": "release_call", "call_id": 42 }"#),
+            AdminCommand::ReleaseCall { call_id: 42 }
+        );
+        assert!(serde_json::from_str::<AdminCommand>(r#"{ "cmd": "release_call" }"#).is_err());
+        assert!(serde_json::from_str::<AdminCommand>(r#"{ "cmd": "reboot" }"#).is_err());
+    }
+}
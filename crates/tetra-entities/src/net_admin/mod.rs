@@ -0,0 +1,19 @@
+//! Local admin interface
+//!
+//! Runs outside the real-time core in its own thread. Accepts newline-delimited
+//! JSON [`AdminCommand`]s on a local TCP port and executes them by injecting
+//! SAP messages into the stack through the [`AdminLink`] held by the MessageRouter.
+//! Replies from the entities are addressed to `TetraEntity::Admin`.
+
+pub mod channel;
+pub mod commands;
+pub mod server;
+
+use std::time::Duration;
+
+pub use self::channel::{AdminClient, AdminLink, AdminReply, AdminRequest, make_admin_link};
+pub use self::commands::AdminCommand;
+pub use self::server::AdminServer;
+
+/// How long the admin server waits for the stack to answer a request
+pub const ADMIN_REPLY_TIMEOUT: Duration = Duration::from_secs(2);
@@ -0,0 +1,185 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde_json::{Value, json};
+use tetra_config::bluestation::parsing;
+use tetra_core::Sap;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_saps::control::admin::{QueryActiveCalls, QueryRegisteredMs, ReleaseCallReq};
+use tetra_saps::control::llc_stats::QueryLlcStats;
+use tetra_saps::{SapMsg, SapMsgInner};
+
+use crate::messagerouter::RouterStats;
+use crate::net_admin::ADMIN_REPLY_TIMEOUT;
+use crate::net_admin::channel::{AdminClient, AdminReply, AdminRequest};
+use crate::net_admin::commands::AdminCommand;
+
+/// TCP server for the admin interface. Listens on localhost only and serves one connection at a time.
+/// Every command line is answered with one JSON object line, carrying `"ok": true` and the result,
+/// or `"ok": false` and an `"error"` string.
+pub struct AdminServer {
+    listener: TcpListener,
+    client: AdminClient,
+    /// Config file re-read on reload_config. None disables reloading.
+    config_path: Option<PathBuf>,
+    /// Router statistics included in the stats reply, if set
+    router_stats: Option<Arc<RouterStats>>,
+}
+
+impl AdminServer {
+    /// Bind to 127.0.0.1 on the given port, 0 picks a free port
+    pub fn bind(port: u16, client: AdminClient, config_path: Option<PathBuf>) -> io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+        Ok(Self {
+            listener,
+            client,
+            config_path,
+            router_stats: None,
+        })
+    }
+
+    /// Include the message router statistics in the reply to the stats command
+    pub fn set_router_stats(&mut self, stats: Arc<RouterStats>) {
+        self.router_stats = Some(stats);
+    }
+
+    pub fn local_port(&self) -> u16 {
+        self.listener.local_addr().map(|addr| addr.port()).unwrap_or_default()
+    }
+
+    pub fn run(&mut self) {
+        tracing::info!("Admin server listening on 127.0.0.1:{}", self.local_port());
+        for stream in self.listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let peer = stream.peer_addr().map(|addr| addr.to_string()).unwrap_or_default();
+                    tracing::info!("Admin connection from {}", peer);
+                    if let Err(e) = self.serve(stream) {
+                        tracing::warn!("Admin connection from {} failed: {}", peer, e);
+                    }
+                }
+                Err(e) => tracing::warn!("Admin server accept failed: {}", e),
+            }
+        }
+    }
+
+    fn serve(&self, stream: TcpStream) -> io::Result<()> {
+        let mut writer = stream.try_clone()?;
+        for line in BufReader::new(stream).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let response = self.handle_line(&line);
+            writeln!(writer, "{}", response)?;
+        }
+        Ok(())
+    }
+
+    /// Parse and execute one command line, returning the JSON response object
+    pub fn handle_line(&self, line: &str) -> Value {
+        let result = serde_json::from_str::<AdminCommand>(line)
+            .map_err(|e| format!("invalid command: {}", e))
+            .and_then(|command| {
+                tracing::debug!("Admin command {:?}", command);
+                self.execute(command)
+            });
+        match result {
+            Ok(mut response) => {
+                response["ok"] = json!(true);
+                response
+            }
+            Err(error) => json!({ "ok": false, "error": error }),
+        }
+    }
+
+    fn execute(&self, command: AdminCommand) -> Result<Value, String> {
+        match command {
+            AdminCommand::Stats => match self.query(TetraEntity::Llc, SapMsgInner::QueryLlcStats(QueryLlcStats))? {
+                SapMsgInner::LlcStatsReply(stats) => {
+                    let mut response = json!({
+                        "llc": {
+                            "bl_data_tx": stats.bl_data_tx,
+                            "bl_data_rx": stats.bl_data_rx,
+                            "bl_udata_tx": stats.bl_udata_tx,
+                            "bl_udata_rx": stats.bl_udata_rx,
+                            "retransmissions": stats.retransmissions,
+                            "fcs_errors": stats.fcs_errors,
+                            "window_stalls": stats.window_stalls,
+                        }
+                    });
+                    if let Some(router_stats) = &self.router_stats {
+                        response["router"] = router_stats.to_json();
+                    }
+                    Ok(response)
+                }
+                other => Err(format!("unexpected reply {:?}", other)),
+            },
+            AdminCommand::RegisteredMs => match self.query(TetraEntity::Mm, SapMsgInner::QueryRegisteredMs(QueryRegisteredMs))? {
+                SapMsgInner::RegisteredMsReply(reply) => {
+                    let subscribers: Vec<Value> = reply
+                        .subscribers
+                        .iter()
+                        .map(|ms| json!({ "issi": ms.issi, "groups": ms.groups, "alias": ms.alias }))
+                        .collect();
+                    Ok(json!({ "registered_ms": subscribers }))
+                }
+                other => Err(format!("unexpected reply {:?}", other)),
+            },
+            AdminCommand::ActiveCalls => match self.query(TetraEntity::Cmce, SapMsgInner::QueryActiveCalls(QueryActiveCalls))? {
+                SapMsgInner::ActiveCallsReply(reply) => {
+                    let calls: Vec<Value> = reply
+                        .calls
+                        .iter()
+                        .map(|call| {
+                            json!({
+                                "call_id": call.call_id,
+                                "type": if call.group { "group" } else { "individual" },
+                                "origin": if call.network { "network" } else { "local" },
+                                "source_ssi": call.source_ssi,
+                                "dest_ssi": call.dest_ssi,
+                                "ts": call.ts,
+                                "priority": call.priority,
+                            })
+                        })
+                        .collect();
+                    Ok(json!({ "active_calls": calls }))
+                }
+                other => Err(format!("unexpected reply {:?}", other)),
+            },
+            AdminCommand::ReleaseCall { call_id } => {
+                match self.query(TetraEntity::Cmce, SapMsgInner::ReleaseCallReq(ReleaseCallReq { call_id }))? {
+                    SapMsgInner::ReleaseCallConf(conf) if conf.released => Ok(json!({ "call_id": conf.call_id })),
+                    SapMsgInner::ReleaseCallConf(conf) => Err(format!("no active call with call_id {}", conf.call_id)),
+                    other => Err(format!("unexpected reply {:?}", other)),
+                }
+            }
+            AdminCommand::ReloadConfig => {
+                let Some(path) = &self.config_path else {
+                    return Err("no config file to reload".to_string());
+                };
+                let config = parsing::from_file(path).map_err(|e| format!("failed to load {}: {}", path.display(), e))?;
+                match self
+                    .client
+                    .request(AdminRequest::ReloadConfig(Box::new(config)), ADMIN_REPLY_TIMEOUT)
+                {
+                    Some(AdminReply::ConfigReloaded(result)) => result.map(|_| json!({ "config": path.display().to_string() })),
+                    Some(AdminReply::Message(msg)) => Err(format!("unexpected reply {:?}", msg.msg)),
+                    None => Err("stack did not respond".to_string()),
+                }
+            }
+        }
+    }
+
+    /// Inject a control message into the stack and wait for the reply addressed to the admin interface
+    fn query(&self, dest: TetraEntity, msg: SapMsgInner) -> Result<SapMsgInner, String> {
+        let request = AdminRequest::Inject(Box::new(SapMsg::new(Sap::Control, TetraEntity::Admin, dest, msg)));
+        match self.client.request(request, ADMIN_REPLY_TIMEOUT) {
+            Some(AdminReply::Message(reply)) => Ok(reply.msg),
+            Some(AdminReply::ConfigReloaded(_)) => Err("unexpected reply to query".to_string()),
+            None => Err("stack did not respond".to_string()),
+        }
+    }
+}
@@ -7,8 +7,8 @@ use uuid::Uuid;
 
 /// Minimum playout buffer depth in frames.
 const BREW_JITTER_MIN_FRAMES: usize = 2;
-/// Default playout buffer depth in frames.
-const BREW_JITTER_BASE_FRAMES: usize = 4;
+/// Default playout buffer depth in frames, used when no depth is configured.
+const BREW_JITTER_DEFAULT_BASE_FRAMES: usize = 4;
 /// Maximum adaptive playout target depth in frames.
 const BREW_JITTER_TARGET_MAX_FRAMES: usize = 12;
 /// Maximum queued frames kept per call before oldest frames are dropped.
@@ -40,22 +40,35 @@ pub struct VoiceJitterBuffer {
     dropped_overflow: u64,
     underruns: u64,
     last_warn_at: Option<Instant>,
+    base_frames: usize,
     initial_latency_frames: usize,
 }
 
 impl VoiceJitterBuffer {
-    pub fn with_initial_latency(initial_latency_frames: usize) -> Self {
-        let initial = initial_latency_frames.min(BREW_JITTER_TARGET_MAX_FRAMES - BREW_JITTER_MIN_FRAMES);
+    /// Creates a playout buffer holding back `base_frames` frames before playout starts, plus
+    /// `initial_latency_frames` of extra startup delay. The depth adapts upward with measured jitter.
+    pub fn new(base_frames: usize, initial_latency_frames: usize) -> Self {
+        let base = base_frames.clamp(BREW_JITTER_MIN_FRAMES, BREW_JITTER_TARGET_MAX_FRAMES);
+        let initial = initial_latency_frames.min(BREW_JITTER_TARGET_MAX_FRAMES - base);
         Self {
-            target_frames: BREW_JITTER_BASE_FRAMES + initial,
+            target_frames: base + initial,
+            base_frames: base,
             initial_latency_frames: initial,
             ..Default::default()
         }
     }
 
+    fn base_frames(&self) -> usize {
+        if self.base_frames == 0 {
+            BREW_JITTER_DEFAULT_BASE_FRAMES
+        } else {
+            self.base_frames
+        }
+    }
+
     pub fn push(&mut self, acelp_data: Vec<u8>) {
         if self.target_frames == 0 {
-            self.target_frames = BREW_JITTER_BASE_FRAMES + self.initial_latency_frames;
+            self.target_frames = self.base_frames() + self.initial_latency_frames;
         }
         let now = Instant::now();
         if let Some(prev) = self.prev_rx_at {
@@ -81,7 +94,7 @@ impl VoiceJitterBuffer {
 
     pub fn pop_ready(&mut self) -> Option<JitterFrame> {
         if self.target_frames == 0 {
-            self.target_frames = BREW_JITTER_BASE_FRAMES + self.initial_latency_frames;
+            self.target_frames = self.base_frames() + self.initial_latency_frames;
         }
 
         if !self.started {
@@ -124,7 +137,7 @@ impl VoiceJitterBuffer {
 
     fn recompute_target(&mut self) {
         let jitter_component = ((self.jitter_us_ewma * 2.0) / BREW_EXPECTED_FRAME_INTERVAL_US).ceil() as usize;
-        let target = BREW_JITTER_BASE_FRAMES + self.initial_latency_frames + jitter_component + self.underrun_boost;
+        let target = self.base_frames() + self.initial_latency_frames + jitter_component + self.underrun_boost;
         self.target_frames = target.clamp(BREW_JITTER_MIN_FRAMES, BREW_JITTER_TARGET_MAX_FRAMES);
     }
 
@@ -136,7 +149,7 @@ impl VoiceJitterBuffer {
             }
         }
 
-        if self.target_frames() < BREW_JITTER_WARN_TARGET_FRAMES && self.underruns == 0 {
+        if self.target_frames() < BREW_JITTER_WARN_TARGET_FRAMES && self.underruns == 0 && self.dropped_overflow == 0 {
             return;
         }
 
@@ -152,3 +165,36 @@ impl VoiceJitterBuffer {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burst_is_played_out_evenly() {
+        let mut jitter = VoiceJitterBuffer::new(4, 0);
+
+        // Ten frames arrive at once, followed by silence
+        for i in 0..10u8 {
+            jitter.push(vec![i; 18]);
+        }
+
+        // One pop per frame tick: frames must come out on consecutive ticks, in order, without gaps
+        let played: Vec<Option<u8>> = (0..14).map(|_| jitter.pop_ready().map(|f| f.acelp_data[0])).collect();
+        let first = played.iter().position(Option::is_some).expect("No frames played out");
+        let expected: Vec<Option<u8>> = (0..10).map(Some).collect();
+        assert_eq!(&played[first..first + 10], expected.as_slice());
+        assert!(played[first + 10..].iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn test_overflow_drops_oldest() {
+        let mut jitter = VoiceJitterBuffer::new(4, 0);
+        for i in 0..(BREW_JITTER_MAX_FRAMES + 2) {
+            jitter.push(vec![i as u8; 18]);
+        }
+        assert_eq!(jitter.frames.len(), BREW_JITTER_MAX_FRAMES);
+        assert_eq!(jitter.dropped_overflow, 2);
+        assert_eq!(jitter.pop_ready().map(|f| f.acelp_data[0]), Some(2));
+    }
+}
@@ -1,2 +1,3 @@
 pub mod brew_routable;
 pub mod jitter_buffer;
+pub mod session_recorder;
@@ -0,0 +1,239 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufWriter, Seek, SeekFrom, Write},
+    path::PathBuf,
+};
+
+use chrono::Utc;
+
+/// Packed size of one TCH/S ACELP frame: 274 bits padded to 35 bytes
+const ACELP_FRAME_BYTES: u16 = 35;
+/// WAVE format tag for data that is not PCM, the recordings hold raw ACELP frames
+const WAVE_FORMAT_DEVELOPMENT: u16 = 0xFFFF;
+/// One ACELP frame carries 60 ms of speech
+const ACELP_BYTES_PER_SEC: u32 = ACELP_FRAME_BYTES as u32 * 1000 / 60;
+
+/// A recording in progress for one timeslot
+struct Recording {
+    call_id: u16,
+    path: PathBuf,
+    writer: BufWriter<File>,
+    /// Offset of the RIFF size field, patched when the recording is finalized
+    riff_size_offset: u64,
+    /// Offset of the data chunk size field, patched when the recording is finalized
+    data_size_offset: u64,
+    data_len: u32,
+}
+
+/// Records local UL calls to WAVE files, one file per floor grant.
+/// No ACELP decoder is available, so the data chunk holds the packed codec frames
+/// (35 bytes per 274-bit frame) and the fmt chunk is tagged as WAVE_FORMAT_DEVELOPMENT.
+/// Call metadata is stored in a LIST/INFO chunk.
+pub struct SessionRecorder {
+    pub enabled: bool,
+    pub output_dir: PathBuf,
+    /// Recordings in progress, keyed by timeslot
+    recordings: HashMap<u8, Recording>,
+}
+
+impl SessionRecorder {
+    pub fn new(output_dir: Option<PathBuf>) -> Self {
+        Self {
+            enabled: output_dir.is_some(),
+            output_dir: output_dir.unwrap_or_default(),
+            recordings: HashMap::new(),
+        }
+    }
+
+    /// Start a new recording on a timeslot, finalizing any recording still open there
+    pub fn start(&mut self, call_id: u16, issi: u32, gssi: u32, ts: u8) {
+        if !self.enabled {
+            return;
+        }
+        self.stop(ts);
+
+        let now = Utc::now();
+        let path = self
+            .output_dir
+            .join(format!("{}_{}_{}.wav", call_id, issi, now.format("%Y%m%dT%H%M%SZ")));
+        let comment = format!("call_id={} issi={} gssi={} ts={}", call_id, issi, gssi, ts);
+        match Self::create(path.clone(), call_id, &comment, &now.to_rfc3339()) {
+            Ok(recording) => {
+                tracing::info!("SessionRecorder: recording call_id={} ts={} to {}", call_id, ts, path.display());
+                self.recordings.insert(ts, recording);
+            }
+            Err(e) => tracing::warn!("SessionRecorder: failed to create {}: {}", path.display(), e),
+        }
+    }
+
+    /// Append a UL traffic frame to the recording on the timeslot, if any.
+    /// Accepts 274 unpacked bits (one per byte), 35 packed bytes or a 36-byte STE frame.
+    pub fn record_frame(&mut self, ts: u8, acelp_bits: &[u8]) {
+        let Some(recording) = self.recordings.get_mut(&ts) else {
+            return;
+        };
+        let Some(frame) = pack_acelp_frame(acelp_bits) else {
+            tracing::warn!("SessionRecorder: unexpected UL voice frame length {}", acelp_bits.len());
+            return;
+        };
+        if let Err(e) = recording.writer.write_all(&frame) {
+            tracing::warn!("SessionRecorder: write to {} failed: {}", recording.path.display(), e);
+            self.recordings.remove(&ts);
+            return;
+        }
+        recording.data_len += frame.len() as u32;
+    }
+
+    /// Finalize and close the recording on the timeslot, if any
+    pub fn stop(&mut self, ts: u8) {
+        let Some(recording) = self.recordings.remove(&ts) else {
+            return;
+        };
+        let path = recording.path.clone();
+        let call_id = recording.call_id;
+        match Self::finalize(recording) {
+            Ok(len) => tracing::info!("SessionRecorder: finished call_id={} {} ({} bytes)", call_id, path.display(), len),
+            Err(e) => tracing::warn!("SessionRecorder: failed to finalize {}: {}", path.display(), e),
+        }
+    }
+
+    fn create(path: PathBuf, call_id: u16, comment: &str, created: &str) -> io::Result<Recording> {
+        let mut writer = BufWriter::new(File::create(&path)?);
+
+        // RIFF header, sizes are patched on finalize
+        writer.write_all(b"RIFF")?;
+        let riff_size_offset = 4;
+        writer.write_all(&0u32.to_le_bytes())?;
+        writer.write_all(b"WAVE")?;
+
+        // fmt chunk
+        writer.write_all(b"fmt ")?;
+        writer.write_all(&16u32.to_le_bytes())?;
+        writer.write_all(&WAVE_FORMAT_DEVELOPMENT.to_le_bytes())?;
+        writer.write_all(&1u16.to_le_bytes())?; // channels
+        writer.write_all(&8000u32.to_le_bytes())?; // sample rate of the decoded speech
+        writer.write_all(&ACELP_BYTES_PER_SEC.to_le_bytes())?;
+        writer.write_all(&ACELP_FRAME_BYTES.to_le_bytes())?; // block align
+        writer.write_all(&0u16.to_le_bytes())?; // bits per sample, not applicable
+
+        // LIST/INFO chunk with the call metadata
+        let mut info = Vec::new();
+        info.extend_from_slice(b"INFO");
+        for (id, value) in [(b"ICMT", comment), (b"ICRD", created)] {
+            // Zero terminated, padded to an even length
+            let mut value = value.as_bytes().to_vec();
+            value.push(0);
+            info.extend_from_slice(id);
+            info.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            if value.len() % 2 == 1 {
+                value.push(0);
+            }
+            info.extend_from_slice(&value);
+        }
+        writer.write_all(b"LIST")?;
+        writer.write_all(&(info.len() as u32).to_le_bytes())?;
+        writer.write_all(&info)?;
+
+        // data chunk
+        writer.write_all(b"data")?;
+        let data_size_offset = writer.stream_position()?;
+        writer.write_all(&0u32.to_le_bytes())?;
+
+        Ok(Recording {
+            call_id,
+            path,
+            writer,
+            riff_size_offset,
+            data_size_offset,
+            data_len: 0,
+        })
+    }
+
+    /// Patch the chunk sizes and flush. Returns the total file length.
+    fn finalize(mut recording: Recording) -> io::Result<u64> {
+        if recording.data_len % 2 == 1 {
+            recording.writer.write_all(&[0])?;
+        }
+        let file_len = recording.writer.stream_position()?;
+        recording.writer.seek(SeekFrom::Start(recording.riff_size_offset))?;
+        recording.writer.write_all(&((file_len - 8) as u32).to_le_bytes())?;
+        recording.writer.seek(SeekFrom::Start(recording.data_size_offset))?;
+        recording.writer.write_all(&recording.data_len.to_le_bytes())?;
+        recording.writer.flush()?;
+        Ok(file_len)
+    }
+}
+
+impl Drop for SessionRecorder {
+    fn drop(&mut self) {
+        let timeslots: Vec<u8> = self.recordings.keys().copied().collect();
+        for ts in timeslots {
+            self.stop(ts);
+        }
+    }
+}
+
+/// Normalizes a UL voice frame to 35 packed bytes, MSB first
+fn pack_acelp_frame(acelp_bits: &[u8]) -> Option<Vec<u8>> {
+    match acelp_bits.len() {
+        35 => Some(acelp_bits.to_vec()),
+        36 => Some(acelp_bits[1..].to_vec()), // Strip STE header byte
+        len if len >= 274 => {
+            let mut packed = vec![0u8; ACELP_FRAME_BYTES as usize];
+            for (bit_idx, bit) in acelp_bits[..274].iter().enumerate() {
+                packed[bit_idx / 8] |= (bit & 1) << (7 - bit_idx % 8);
+            }
+            Some(packed)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recording_lifecycle() {
+        let dir = std::env::temp_dir().join(format!("bluestation_rec_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut recorder = SessionRecorder::new(Some(dir.clone()));
+        recorder.start(5, 1000001, 91, 2);
+        for _ in 0..10 {
+            recorder.record_frame(2, &[1u8; 274]);
+        }
+        // Frames on other timeslots are not recorded
+        recorder.record_frame(3, &[1u8; 274]);
+        recorder.stop(2);
+
+        let files: Vec<PathBuf> = std::fs::read_dir(&dir).unwrap().map(|e| e.unwrap().path()).collect();
+        assert_eq!(files.len(), 1);
+        let name = files[0].file_name().unwrap().to_string_lossy().to_string();
+        assert!(
+            name.starts_with("5_1000001_") && name.ends_with(".wav"),
+            "unexpected file name {}",
+            name
+        );
+
+        let contents = std::fs::read(&files[0]).unwrap();
+        assert_eq!(&contents[0..4], b"RIFF");
+        assert_eq!(&contents[8..12], b"WAVE");
+        assert_eq!(u32::from_le_bytes(contents[4..8].try_into().unwrap()) as usize, contents.len() - 8);
+        let data_pos = contents.windows(4).position(|w| w == b"data").unwrap();
+        let data_len = u32::from_le_bytes(contents[data_pos + 4..data_pos + 8].try_into().unwrap());
+        assert_eq!(data_len, 10 * ACELP_FRAME_BYTES as u32);
+        assert!(contents[data_pos + 8..].iter().take(34).all(|&b| b == 0xFF));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_disabled_recorder_writes_nothing() {
+        let mut recorder = SessionRecorder::new(None);
+        recorder.start(5, 1000001, 91, 2);
+        recorder.record_frame(2, &[1u8; 274]);
+        assert!(recorder.recordings.is_empty());
+    }
+}
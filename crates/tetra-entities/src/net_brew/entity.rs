@@ -13,6 +13,7 @@ use tetra_saps::control::sds::CmceSdsData;
 use uuid::Uuid;
 
 use crate::net_brew::components::jitter_buffer::{JitterFrame, VoiceJitterBuffer};
+use crate::net_brew::components::session_recorder::SessionRecorder;
 use crate::network::transports::NetworkTransport;
 use crate::{MessageQueue, TetraEntityTrait};
 use tetra_config::bluestation::{CfgBrew, SharedConfig};
@@ -109,6 +110,12 @@ pub struct BrewEntity {
     /// UL calls being forwarded to TetraPack, keyed by timeslot
     ul_forwarded: HashMap<u8, UlForwardedCall>,
 
+    /// Records local UL calls to files, if a recording directory is configured
+    recorder: SessionRecorder,
+
+    /// ISSI currently holding the floor in a local call, keyed by timeslot. Used for call logs.
+    floor_holders: HashMap<u8, u32>,
+
     /// Registered subscriber groups (ISSI -> set of GSSIs)
     subscriber_groups: HashMap<u32, HashSet<u32>>,
 
@@ -145,6 +152,8 @@ impl BrewEntity {
             state.network_connected = false;
         }
 
+        let recorder = SessionRecorder::new(brew_config.recording_dir.clone());
+
         Self {
             config,
             brew_config,
@@ -155,6 +164,8 @@ impl BrewEntity {
             dl_jitter: HashMap::new(),
             hanging_calls: HashMap::new(),
             ul_forwarded: HashMap::new(),
+            recorder,
+            floor_holders: HashMap::new(),
             subscriber_groups: HashMap::new(),
             connected: false,
             worker_handle: Some(handle),
@@ -211,6 +222,14 @@ impl BrewEntity {
                 BrewEvent::ServerError { error_type, data } => {
                     tracing::error!("BrewEntity: server error type={} data={} bytes", error_type, data.len());
                 }
+                BrewEvent::TransportMetrics { requester, metrics } => {
+                    let _ = queue.push_back(SapMsg::new(
+                        Sap::Control,
+                        TetraEntity::Brew,
+                        requester,
+                        SapMsgInner::TransportMetricsReply(metrics),
+                    ));
+                }
             }
         }
     }
@@ -319,6 +338,11 @@ impl BrewEntity {
         }
     }
 
+    /// Human-readable name configured for an ISSI in [brew.aliases], if any
+    pub fn resolve_alias(&self, issi: u32) -> Option<&str> {
+        self.brew_config.alias_map.get(&issi).map(String::as_str)
+    }
+
     fn set_network_connected(&mut self, connected: bool) {
         self.connected = connected;
         let mut state = self.config.state_write();
@@ -329,7 +353,21 @@ impl BrewEntity {
     }
 
     /// Handle new group call from Brew, reusing hanging call circuits if available.
-    fn handle_group_call_start(&mut self, queue: &mut MessageQueue, uuid: Uuid, source_issi: u32, dest_gssi: u32, priority: u8) {
+    fn handle_group_call_start(&mut self, queue: &mut MessageQueue, uuid: Uuid, source_issi: u32, dest_gssi: u32, brew_priority: u8) {
+        let priority = self.brew_config.tetra_priority(brew_priority);
+
+        // Monitored groups are receive-only towards the network, calls from Brew are never put on air.
+        // The call is not tracked, so its voice frames are dropped as well.
+        if self.brew_config.monitoring_only.contains(&dest_gssi) {
+            tracing::debug!(
+                "BrewEntity: ignoring GROUP_TX uuid={} src={} to monitoring-only gssi={}",
+                uuid,
+                source_issi,
+                dest_gssi
+            );
+            return;
+        }
+
         // Check if this call is already active (speaker change or repeated GROUP_TX)
         if let Some(call) = self.active_calls.get_mut(&uuid) {
             // Only notify CMCE if the speaker actually changed
@@ -343,17 +381,17 @@ impl BrewEntity {
                 call.source_issi = source_issi;
 
                 // Forward speaker change to CMCE
-                queue.push_back(SapMsg {
-                    sap: Sap::Control,
-                    src: TetraEntity::Brew,
-                    dest: TetraEntity::Cmce,
-                    msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallStart {
+                let _ = queue.push_back(SapMsg::new(
+                    Sap::Control,
+                    TetraEntity::Brew,
+                    TetraEntity::Cmce,
+                    SapMsgInner::CmceCallControl(CallControl::NetworkCallStart {
                         brew_uuid: uuid,
                         source_issi,
                         dest_gssi,
                         priority,
                     }),
-                });
+                ));
             } else {
                 // Repeated GROUP_TX with same speaker - this is normal, just log at trace level
                 tracing::trace!("BrewEntity: repeated GROUP_TX on uuid={} speaker={}", uuid, source_issi);
@@ -381,22 +419,25 @@ impl BrewEntity {
                 frame_count: hanging.frame_count,
             };
             self.active_calls.insert(uuid, call);
-            self.dl_jitter
-                .entry(uuid)
-                .or_insert_with(|| VoiceJitterBuffer::with_initial_latency(self.brew_config.jitter_initial_latency_frames as usize));
+            self.dl_jitter.entry(uuid).or_insert_with(|| {
+                VoiceJitterBuffer::new(
+                    self.brew_config.jitter_buffer_frames as usize,
+                    self.brew_config.jitter_initial_latency_frames as usize,
+                )
+            });
 
             // Forward to CMCE (will reuse circuit automatically)
-            queue.push_back(SapMsg {
-                sap: Sap::Control,
-                src: TetraEntity::Brew,
-                dest: TetraEntity::Cmce,
-                msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallStart {
+            let _ = queue.push_back(SapMsg::new(
+                Sap::Control,
+                TetraEntity::Brew,
+                TetraEntity::Cmce,
+                SapMsgInner::CmceCallControl(CallControl::NetworkCallStart {
                     brew_uuid: uuid,
                     source_issi,
                     dest_gssi,
                     priority,
                 }),
-            });
+            ));
             return;
         }
 
@@ -419,21 +460,24 @@ impl BrewEntity {
             frame_count: 0,
         };
         self.active_calls.insert(uuid, call);
-        self.dl_jitter
-            .entry(uuid)
-            .or_insert_with(|| VoiceJitterBuffer::with_initial_latency(self.brew_config.jitter_initial_latency_frames as usize));
+        self.dl_jitter.entry(uuid).or_insert_with(|| {
+            VoiceJitterBuffer::new(
+                self.brew_config.jitter_buffer_frames as usize,
+                self.brew_config.jitter_initial_latency_frames as usize,
+            )
+        });
 
-        queue.push_back(SapMsg {
-            sap: Sap::Control,
-            src: TetraEntity::Brew,
-            dest: TetraEntity::Cmce,
-            msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallStart {
+        let _ = queue.push_back(SapMsg::new(
+            Sap::Control,
+            TetraEntity::Brew,
+            TetraEntity::Cmce,
+            SapMsgInner::CmceCallControl(CallControl::NetworkCallStart {
                 brew_uuid: uuid,
                 source_issi,
                 dest_gssi,
                 priority,
             }),
-        });
+        ));
     }
 
     /// Handle GROUP_IDLE by forwarding to CMCE and tracking for hangtime reuse
@@ -453,12 +497,12 @@ impl BrewEntity {
         );
 
         // Request CMCE to end the call
-        queue.push_back(SapMsg {
-            sap: Sap::Control,
-            src: TetraEntity::Brew,
-            dest: TetraEntity::Cmce,
-            msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid: uuid }),
-        });
+        let _ = queue.push_back(SapMsg::new(
+            Sap::Control,
+            TetraEntity::Brew,
+            TetraEntity::Cmce,
+            SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid: uuid }),
+        ));
 
         // Track as hanging for potential reuse (only if resources were allocated)
         if let (Some(call_id), Some(ts), Some(usage)) = (call.call_id, call.ts, call.usage) {
@@ -538,12 +582,17 @@ impl BrewEntity {
 
         self.dl_jitter
             .entry(uuid)
-            .or_insert_with(|| VoiceJitterBuffer::with_initial_latency(self.brew_config.jitter_initial_latency_frames as usize))
+            .or_insert_with(|| {
+                VoiceJitterBuffer::new(
+                    self.brew_config.jitter_buffer_frames as usize,
+                    self.brew_config.jitter_initial_latency_frames as usize,
+                )
+            })
             .push(acelp_data);
     }
 
     fn drain_jitter_playout(&mut self, queue: &mut MessageQueue) {
-        if self.dltime.f == 18 {
+        if self.dltime.is_frame18() {
             return;
         }
 
@@ -574,15 +623,15 @@ impl BrewEntity {
                 frame.rx_at.elapsed().as_millis(),
                 target_frames
             );
-            queue.push_back(SapMsg {
-                sap: Sap::TmdSap,
-                src: TetraEntity::Brew,
-                dest: TetraEntity::Umac,
-                msg: SapMsgInner::TmdCircuitDataReq(TmdCircuitDataReq {
+            let _ = queue.push_back(SapMsg::new(
+                Sap::TmdSap,
+                TetraEntity::Brew,
+                TetraEntity::Umac,
+                SapMsgInner::TmdCircuitDataReq(TmdCircuitDataReq {
                     ts,
                     data: frame.acelp_data,
                 }),
-            });
+            ));
         }
     }
 
@@ -592,12 +641,12 @@ impl BrewEntity {
         let calls: Vec<(Uuid, ActiveCall)> = self.active_calls.drain().collect();
         for (uuid, _) in calls {
             self.dl_jitter.remove(&uuid);
-            queue.push_back(SapMsg {
-                sap: Sap::Control,
-                src: TetraEntity::Brew,
-                dest: TetraEntity::Cmce,
-                msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid: uuid }),
-            });
+            let _ = queue.push_back(SapMsg::new(
+                Sap::Control,
+                TetraEntity::Brew,
+                TetraEntity::Cmce,
+                SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid: uuid }),
+            ));
         }
 
         // Clear hanging call tracking
@@ -661,6 +710,15 @@ impl TetraEntityTrait for BrewEntity {
         self.config = config;
     }
 
+    fn shutdown(&mut self, queue: &mut MessageQueue) {
+        tracing::info!("BrewEntity: shutting down, closing connection");
+        // Network calls end with the connection
+        self.release_all_calls(queue);
+        // The worker deregisters all subscribers and closes the WebSocket, Drop waits for it to finish
+        let _ = self.command_sender.send(BrewCommand::Disconnect);
+        self.set_network_connected(false);
+    }
+
     fn tick_start(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
         self.dltime = ts;
         // Process all pending events from the worker thread
@@ -675,6 +733,7 @@ impl TetraEntityTrait for BrewEntity {
         match message.msg {
             // UL voice from UMAC — forward to TetraPack if this timeslot is being forwarded
             SapMsgInner::TmdCircuitDataInd(prim) => {
+                self.recorder.record_frame(prim.ts, &prim.data);
                 self.handle_ul_voice(prim.ts, prim.data);
             }
             // Floor-control and call lifecycle notifications from CMCE
@@ -683,13 +742,41 @@ impl TetraEntityTrait for BrewEntity {
                 source_issi,
                 dest_gssi,
                 ts,
+                priority,
             }) => {
-                self.handle_local_call_start(call_id, source_issi, dest_gssi, ts);
+                tracing::info!(
+                    call_id,
+                    issi = source_issi,
+                    gssi = dest_gssi,
+                    ts,
+                    alias = self.resolve_alias(source_issi).unwrap_or(""),
+                    "BrewEntity: floor granted"
+                );
+                self.floor_holders.insert(ts, source_issi);
+                self.recorder.start(call_id, source_issi, dest_gssi, ts);
+                self.handle_local_call_start(call_id, source_issi, dest_gssi, ts, priority);
             }
             SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, ts }) => {
+                let issi = self.floor_holders.get(&ts).copied().unwrap_or(0);
+                tracing::info!(
+                    call_id,
+                    issi,
+                    ts,
+                    alias = self.resolve_alias(issi).unwrap_or(""),
+                    "BrewEntity: floor released"
+                );
                 self.handle_local_call_tx_stopped(call_id, ts);
             }
             SapMsgInner::CmceCallControl(CallControl::CallEnded { call_id, ts }) => {
+                let issi = self.floor_holders.remove(&ts).unwrap_or(0);
+                tracing::info!(
+                    call_id,
+                    issi,
+                    ts,
+                    alias = self.resolve_alias(issi).unwrap_or(""),
+                    "BrewEntity: call ended"
+                );
+                self.recorder.stop(ts);
                 self.handle_local_call_end(call_id, ts);
             }
             SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid }) => {
@@ -705,12 +792,22 @@ impl TetraEntityTrait for BrewEntity {
             }
             // UlInactivityTimeout is UMAC→CMCE only; Brew handles FloorReleased instead
             SapMsgInner::CmceCallControl(CallControl::UlInactivityTimeout { .. }) => {}
+            SapMsgInner::CmceEmergencyCall(emergency) => {
+                // The emergency priority itself reaches TetraPack with the GROUP_TX of the call
+                tracing::warn!("BrewEntity: emergency call from issi={} to gssi={}", emergency.issi, emergency.gssi);
+            }
             SapMsgInner::MmSubscriberUpdate(update) => {
                 self.handle_subscriber_update(update);
             }
             SapMsgInner::CmceSdsData(sds) => {
                 self.handle_sds_send(sds);
             }
+            SapMsgInner::QueryTransportMetrics(_) => {
+                // Answered from process_events once the worker reports back
+                let _ = self
+                    .command_sender
+                    .send(BrewCommand::QueryTransportMetrics { requester: message.src });
+            }
             _ => {
                 tracing::debug!("BrewEntity: unexpected rx_prim from {:?} on {:?}", message.src, message.sap);
             }
@@ -723,7 +820,7 @@ impl TetraEntityTrait for BrewEntity {
 impl BrewEntity {
     /// Handle notification that a local UL group call has started.
     /// If the group is subscribed (in config.groups), start forwarding to TetraPack.
-    fn handle_local_call_start(&mut self, call_id: u16, source_issi: u32, dest_gssi: u32, ts: u8) {
+    fn handle_local_call_start(&mut self, call_id: u16, source_issi: u32, dest_gssi: u32, ts: u8, priority: u8) {
         if !self.connected {
             tracing::trace!("BrewEntity: not connected, ignoring local call start");
             return;
@@ -759,7 +856,7 @@ impl BrewEntity {
                 uuid: fwd.uuid,
                 source_issi,
                 dest_gssi,
-                priority: 0,
+                priority: self.brew_config.brew_priority(priority),
                 service: 0, // TETRA encoded speech
             });
             return;
@@ -781,7 +878,7 @@ impl BrewEntity {
             uuid,
             source_issi,
             dest_gssi,
-            priority: 0,
+            priority: self.brew_config.brew_priority(priority),
             service: 0, // TETRA encoded speech
         });
 
@@ -933,16 +1030,16 @@ impl BrewEntity {
 
         // Forward to CMCE SDS subentity for downlink delivery
         // Set dltime to next ts1 to ensure it gets sent on MCCH
-        queue.push_back(SapMsg {
-            sap: Sap::Control,
-            src: TetraEntity::Brew,
-            dest: TetraEntity::Cmce,
-            msg: SapMsgInner::CmceSdsData(CmceSdsData {
+        let _ = queue.push_back(SapMsg::new(
+            Sap::Control,
+            TetraEntity::Brew,
+            TetraEntity::Cmce,
+            SapMsgInner::CmceSdsData(CmceSdsData {
                 source_issi: source,
                 dest_issi: destination,
                 user_defined_data,
             }),
-        });
+        ));
 
         // Send SDS_REPORT (status=0) back to Brew to release session resources.
         // Without this, sessions are killed by timeout instead of being released cleanly.
@@ -10,10 +10,11 @@ use std::time::{Duration, Instant};
 use crossbeam_channel::{Receiver, Sender};
 use tetra_config::bluestation::CfgBrew;
 use tetra_config::bluestation::SharedConfig;
+use tetra_core::tetra_entities::TetraEntity;
 use uuid::Uuid;
 
 use crate::net_brew;
-use crate::network::transports::NetworkTransport;
+use crate::network::transports::{NetworkTransport, TransportMetrics};
 
 use super::protocol::*;
 
@@ -60,6 +61,9 @@ pub enum BrewEvent {
 
     /// Error from server
     ServerError { error_type: u8, data: Vec<u8> },
+
+    /// Transport counters, in response to QueryTransportMetrics
+    TransportMetrics { requester: TetraEntity, metrics: TransportMetrics },
 }
 
 /// Commands the BrewEntity sends to the worker
@@ -104,6 +108,9 @@ pub enum BrewCommand {
     /// Send SDS report to Brew (delivery acknowledgement)
     SendSdsReport { uuid: Uuid, status: u8 },
 
+    /// Report the transport counters back to the requesting entity
+    QueryTransportMetrics { requester: TetraEntity },
+
     /// Disconnect gracefully
     Disconnect,
 }
@@ -377,6 +384,10 @@ impl<T: NetworkTransport> BrewWorker<T> {
                             tracing::debug!("BrewWorker: sent SDS_REPORT uuid={} status={}", uuid, status);
                         }
                     }
+                    BrewCommand::QueryTransportMetrics { requester } => {
+                        let metrics = self.transport.metrics();
+                        let _ = self.event_sender.send(BrewEvent::TransportMetrics { requester, metrics });
+                    }
                     BrewCommand::Disconnect => {
                         self.graceful_teardown();
                         return Ok(());
@@ -29,4 +29,11 @@ pub enum TelemetryEvent {
         issi: u32,
         gssis: Vec<u32>,
     },
+    /// Periodic RF link quality of an active call, from the UL traffic channel CRC pass rate
+    CallQuality {
+        ts: u8,
+        crc_pass_rate: f32,
+        frame_count: u32,
+        mos_estimate: f32,
+    },
 }
@@ -0,0 +1,159 @@
+//! TNMM codec — versioned, length-prefixed framing of TNMM messages.
+//!
+//! Frame layout, multi-byte fields big endian:
+//!
+//! | bytes | field                      |
+//! |-------|----------------------------|
+//! | 2     | magic, [`TNMM_MAGIC`]      |
+//! | 1     | version, [`TNMM_VERSION`]  |
+//! | 1     | message type               |
+//! | 4     | payload length             |
+//! | n     | payload                    |
+
+/// Frame magic, "T" followed by 0x7A
+pub const TNMM_MAGIC: [u8; 2] = [0x54, 0x7A];
+/// Current framing version
+pub const TNMM_VERSION: u8 = 0x01;
+/// Length of the frame header preceding the payload
+pub const TNMM_HEADER_LEN: usize = 8;
+
+// Message types
+/// Version negotiation, sent by both sides after connecting
+pub const TNMM_MSG_HELLO: u8 = 0x01;
+pub const TNMM_MSG_CALL_EVENT: u8 = 0x02;
+pub const TNMM_MSG_REGISTRATION_EVENT: u8 = 0x03;
+pub const TNMM_MSG_STATS_QUERY: u8 = 0x04;
+pub const TNMM_MSG_STATS_RESPONSE: u8 = 0x05;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodecError {
+    /// Frame does not start with TNMM_MAGIC
+    InvalidMagic,
+    /// Frame uses a framing version we do not speak
+    UnsupportedVersion(u8),
+    /// Buffer ends before the header or the announced payload
+    Truncated { needed: usize, available: usize },
+    /// Buffer holds more bytes than the announced payload
+    TrailingBytes(usize),
+}
+
+impl std::fmt::Display for CodecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodecError::InvalidMagic => write!(f, "invalid TNMM frame magic"),
+            CodecError::UnsupportedVersion(version) => write!(f, "unsupported TNMM framing version {}", version),
+            CodecError::Truncated { needed, available } => {
+                write!(f, "truncated TNMM frame, need {} bytes, have {}", needed, available)
+            }
+            CodecError::TrailingBytes(count) => write!(f, "{} trailing bytes after TNMM frame", count),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+#[derive(Default)]
+pub struct TnmmCodec;
+
+impl TnmmCodec {
+    /// Frame a payload with the given message type
+    pub fn encode(msg_type: u8, payload: &[u8]) -> Vec<u8> {
+        let mut frame = Vec::with_capacity(TNMM_HEADER_LEN + payload.len());
+        frame.extend_from_slice(&TNMM_MAGIC);
+        frame.push(TNMM_VERSION);
+        frame.push(msg_type);
+        frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    /// Parse exactly one frame, returning the message type and payload
+    pub fn decode(buf: &[u8]) -> Result<(u8, Vec<u8>), CodecError> {
+        if buf.len() < TNMM_HEADER_LEN {
+            return Err(CodecError::Truncated {
+                needed: TNMM_HEADER_LEN,
+                available: buf.len(),
+            });
+        }
+        if buf[0..2] != TNMM_MAGIC {
+            return Err(CodecError::InvalidMagic);
+        }
+        if buf[2] != TNMM_VERSION {
+            return Err(CodecError::UnsupportedVersion(buf[2]));
+        }
+        let msg_type = buf[3];
+        let payload_len = u32::from_be_bytes([buf[4], buf[5], buf[6], buf[7]]) as usize;
+        let frame_len = TNMM_HEADER_LEN + payload_len;
+        if buf.len() < frame_len {
+            return Err(CodecError::Truncated {
+                needed: frame_len,
+                available: buf.len(),
+            });
+        }
+        if buf.len() > frame_len {
+            return Err(CodecError::TrailingBytes(buf.len() - frame_len));
+        }
+        Ok((msg_type, buf[TNMM_HEADER_LEN..].to_vec()))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        for msg_type in [
+            TNMM_MSG_HELLO,
+            TNMM_MSG_CALL_EVENT,
+            TNMM_MSG_REGISTRATION_EVENT,
+            TNMM_MSG_STATS_QUERY,
+            TNMM_MSG_STATS_RESPONSE,
+        ] {
+            let frame = TnmmCodec::encode(msg_type, b"payload");
+            assert_eq!(frame.len(), TNMM_HEADER_LEN + 7);
+            assert_eq!(TnmmCodec::decode(&frame), Ok((msg_type, b"payload".to_vec())));
+        }
+        let frame = TnmmCodec::encode(TNMM_MSG_STATS_QUERY, &[]);
+        assert_eq!(frame, vec![0x54, 0x7A, 0x01, 0x04, 0, 0, 0, 0]);
+        assert_eq!(TnmmCodec::decode(&frame), Ok((TNMM_MSG_STATS_QUERY, vec![])));
+    }
+
+    #[test]
+    fn test_invalid_magic() {
+        let mut frame = TnmmCodec::encode(TNMM_MSG_HELLO, &[1]);
+        frame[0] = 0x00;
+        assert_eq!(TnmmCodec::decode(&frame), Err(CodecError::InvalidMagic));
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let mut frame = TnmmCodec::encode(TNMM_MSG_HELLO, &[1]);
+        frame[2] = 0x02;
+        assert_eq!(TnmmCodec::decode(&frame), Err(CodecError::UnsupportedVersion(0x02)));
+    }
+
+    #[test]
+    fn test_truncated() {
+        let frame = TnmmCodec::encode(TNMM_MSG_CALL_EVENT, &[1, 2, 3]);
+        assert_eq!(
+            TnmmCodec::decode(&frame[..5]),
+            Err(CodecError::Truncated { needed: 8, available: 5 })
+        );
+        assert_eq!(
+            TnmmCodec::decode(&frame[..10]),
+            Err(CodecError::Truncated { needed: 11, available: 10 })
+        );
+    }
+
+    #[test]
+    fn test_trailing_bytes() {
+        let mut frame = TnmmCodec::encode(TNMM_MSG_CALL_EVENT, &[1, 2, 3]);
+        frame.push(0xFF);
+        assert_eq!(TnmmCodec::decode(&frame), Err(CodecError::TrailingBytes(1)));
+    }
+}
@@ -0,0 +1,8 @@
+//! TNMM network interface
+//!
+//! Framing for TNMM messages exchanged with an external network management
+//! peer. Only the wire codec exists so far.
+
+pub mod codec;
+
+pub use self::codec::{CodecError, TnmmCodec};
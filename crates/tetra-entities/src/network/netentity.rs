@@ -94,7 +94,7 @@ impl<W: NetEntityWorker> NetEntity<W> {
 
     /// Handle responses from the worker thread
     fn handle_worker_response(&mut self, response: SapMsg, queue: &mut MessageQueue) {
-        queue.push_back(response);
+        let _ = queue.push_back(response);
     }
 
     /// Clean up expired pending requests
@@ -0,0 +1,50 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use super::{NetworkError, TransportMetrics};
+
+/// Counters backing [`TransportMetrics`], updated by transports from their send, receive and connect paths
+#[derive(Debug, Default)]
+pub struct TransportCounters {
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    send_errors: AtomicU64,
+    receive_errors: AtomicU64,
+    connects: AtomicU64,
+    last_connected: Mutex<Option<Instant>>,
+}
+
+impl TransportCounters {
+    /// Count a send attempt of `len` payload bytes by its outcome
+    pub fn record_send(&self, len: usize, result: &Result<(), NetworkError>) {
+        match result {
+            Ok(()) => self.bytes_sent.fetch_add(len as u64, Ordering::Relaxed),
+            Err(_) => self.send_errors.fetch_add(1, Ordering::Relaxed),
+        };
+    }
+
+    pub fn record_received(&self, len: usize) {
+        self.bytes_received.fetch_add(len as u64, Ordering::Relaxed);
+    }
+
+    pub fn record_receive_error(&self) {
+        self.receive_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_connected(&self) {
+        self.connects.fetch_add(1, Ordering::Relaxed);
+        *self.last_connected.lock().expect("TransportCounters lock poisoned") = Some(Instant::now());
+    }
+
+    pub fn snapshot(&self) -> TransportMetrics {
+        TransportMetrics {
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            send_errors: self.send_errors.load(Ordering::Relaxed),
+            receive_errors: self.receive_errors.load(Ordering::Relaxed),
+            reconnections: self.connects.load(Ordering::Relaxed).saturating_sub(1),
+            last_connected: *self.last_connected.lock().expect("TransportCounters lock poisoned"),
+        }
+    }
+}
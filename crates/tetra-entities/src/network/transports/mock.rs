@@ -3,7 +3,7 @@
 use std::collections::VecDeque;
 use std::time::Instant;
 
-use super::{NetworkAddress, NetworkError, NetworkMessage, NetworkTransport};
+use super::{NetworkAddress, NetworkError, NetworkMessage, NetworkTransport, TransportMetrics};
 
 pub struct MockTransport {
     connected: bool,
@@ -77,4 +77,11 @@ impl NetworkTransport for MockTransport {
     fn disconnect(&mut self) {
         self.connected = false;
     }
+
+    fn metrics(&self) -> TransportMetrics {
+        TransportMetrics {
+            bytes_sent: self.sent.iter().map(|payload| payload.len() as u64).sum(),
+            ..Default::default()
+        }
+    }
 }
@@ -1,10 +1,14 @@
 use std::time::Instant;
 
-use serde::{Deserialize, Serialize};
+pub use tetra_core::NetworkAddress;
+pub use tetra_saps::control::transport_metrics::TransportMetrics;
 
 #[cfg(test)]
 pub mod mock;
 
+/// Counters shared by the transport implementations
+pub mod metrics;
+
 /// QUIC transport implementation
 pub mod quic;
 
@@ -45,6 +49,9 @@ pub trait NetworkTransport: Send {
     fn is_connected(&self) -> bool {
         true
     }
+
+    /// Snapshot of the traffic and connection counters of this transport
+    fn metrics(&self) -> TransportMetrics;
 }
 
 /// Factory trait for creating transport instances
@@ -60,17 +67,6 @@ pub trait TransportFactory: NetworkTransport + Sized {
     fn create(config: Self::Config) -> Result<Self, NetworkError>;
 }
 
-/// Network address abstraction
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum NetworkAddress {
-    /// TCP endpoint
-    Tcp { host: String, port: u16 },
-    /// UDP endpoint  
-    Udp { host: String, port: u16 },
-    /// Custom addressing scheme
-    Custom { scheme: String, address: String },
-}
-
 /// Network message received from external source
 #[derive(Debug, Clone)]
 pub struct NetworkMessage {
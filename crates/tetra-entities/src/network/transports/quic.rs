@@ -5,7 +5,8 @@ use std::time::{Duration, Instant};
 use quinn::{Connection, Endpoint, RecvStream, SendStream, VarInt};
 use rustls::pki_types::{CertificateDer, ServerName};
 
-use super::{NetworkAddress, NetworkError, NetworkMessage, NetworkTransport};
+use super::metrics::TransportCounters;
+use super::{NetworkAddress, NetworkError, NetworkMessage, NetworkTransport, TransportMetrics};
 
 /// Channel type for QUIC streams
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -70,6 +71,7 @@ pub struct QuicTransport {
     reliable_recv: Option<RecvStream>,
     /// Tokio runtime handle for async operations
     runtime: tokio::runtime::Runtime,
+    counters: TransportCounters,
 }
 
 impl QuicTransport {
@@ -128,9 +130,22 @@ impl QuicTransport {
             reliable_send: None,
             reliable_recv: None,
             runtime,
+            counters: TransportCounters::default(),
         })
     }
 
+    /// Receive from a channel and count the outcome
+    fn receive_counted(&mut self, channel_type: QuicChannelType) -> Result<Option<Vec<u8>>, NetworkError> {
+        let runtime = self.runtime.handle().clone();
+        let result = runtime.block_on(async { self.receive_from_channel(channel_type).await });
+        match &result {
+            Ok(Some(payload)) => self.counters.record_received(payload.len()),
+            Ok(None) => {}
+            Err(_) => self.counters.record_receive_error(),
+        }
+        result
+    }
+
     /// Parse NetworkAddress to SocketAddr
     fn parse_socket_addr(addr: &NetworkAddress) -> Result<SocketAddr, NetworkError> {
         match addr {
@@ -308,6 +323,7 @@ impl NetworkTransport for QuicTransport {
         })?;
 
         self.connection = Some(connection);
+        self.counters.record_connected();
 
         Ok(())
     }
@@ -315,21 +331,24 @@ impl NetworkTransport for QuicTransport {
     fn send_reliable(&mut self, payload: &[u8]) -> Result<(), NetworkError> {
         // Synchronous wrapper around async send_on_channel
         let runtime = self.runtime.handle().clone();
-        runtime.block_on(async { self.send_on_channel(payload, QuicChannelType::Reliable).await })
+        let result = runtime.block_on(async { self.send_on_channel(payload, QuicChannelType::Reliable).await });
+        self.counters.record_send(payload.len(), &result);
+        result
     }
 
     fn send_unreliable(&mut self, payload: &[u8]) -> Result<(), NetworkError> {
         // Synchronous wrapper around async send_on_channel
         let runtime = self.runtime.handle().clone();
-        runtime.block_on(async { self.send_on_channel(payload, QuicChannelType::Unreliable).await })
+        let result = runtime.block_on(async { self.send_on_channel(payload, QuicChannelType::Unreliable).await });
+        self.counters.record_send(payload.len(), &result);
+        result
     }
 
     fn receive_reliable(&mut self) -> Vec<NetworkMessage> {
         // Non-blocking receive from reliable channel
         let mut messages = Vec::new();
 
-        let runtime = self.runtime.handle().clone();
-        if let Ok(Some(payload)) = runtime.block_on(async { self.receive_from_channel(QuicChannelType::Reliable).await }) {
+        if let Ok(Some(payload)) = self.receive_counted(QuicChannelType::Reliable) {
             messages.push(NetworkMessage {
                 source: self.server_addr.clone(),
                 payload,
@@ -344,8 +363,7 @@ impl NetworkTransport for QuicTransport {
         // Non-blocking receive from unreliable channel (datagrams)
         let mut messages = Vec::new();
 
-        let runtime = self.runtime.handle().clone();
-        if let Ok(Some(payload)) = runtime.block_on(async { self.receive_from_channel(QuicChannelType::Unreliable).await }) {
+        if let Ok(Some(payload)) = self.receive_counted(QuicChannelType::Unreliable) {
             messages.push(NetworkMessage {
                 source: self.server_addr.clone(),
                 payload,
@@ -358,17 +376,18 @@ impl NetworkTransport for QuicTransport {
 
     fn wait_for_response_reliable(&mut self) -> Result<NetworkMessage, NetworkError> {
         // Blocking receive from reliable channel
-        let runtime = self.runtime.handle().clone();
-        runtime.block_on(async {
-            match self.receive_from_channel(QuicChannelType::Reliable).await? {
-                Some(payload) => Ok(NetworkMessage {
-                    source: self.server_addr.clone(),
-                    payload,
-                    timestamp: Instant::now(),
-                }),
-                None => Err(NetworkError::ReceiveFailed("Connection closed".to_string())),
-            }
-        })
+        match self.receive_counted(QuicChannelType::Reliable)? {
+            Some(payload) => Ok(NetworkMessage {
+                source: self.server_addr.clone(),
+                payload,
+                timestamp: Instant::now(),
+            }),
+            None => Err(NetworkError::ReceiveFailed("Connection closed".to_string())),
+        }
+    }
+
+    fn metrics(&self) -> TransportMetrics {
+        self.counters.snapshot()
     }
 }
 
@@ -2,7 +2,8 @@ use std::io::{Read, Write};
 use std::net::TcpStream;
 use std::time::{Duration, Instant};
 
-use super::{NetworkAddress, NetworkError, NetworkMessage, NetworkTransport};
+use super::metrics::TransportCounters;
+use super::{NetworkAddress, NetworkError, NetworkMessage, NetworkTransport, TransportMetrics};
 
 /// Configuration for creating a TCP transport
 #[derive(Debug, Clone)]
@@ -41,6 +42,7 @@ pub struct TcpTransport {
     server_addr: NetworkAddress,
     connect_timeout: Duration,
     read_timeout: Duration,
+    counters: TransportCounters,
 }
 
 impl TcpTransport {
@@ -50,6 +52,7 @@ impl TcpTransport {
             server_addr,
             connect_timeout,
             read_timeout,
+            counters: TransportCounters::default(),
         }
     }
 
@@ -98,6 +101,44 @@ impl TcpTransport {
             Err(NetworkError::SendFailed("No active connection".to_string()))
         }
     }
+
+    /// Blocking read of a single message, see wait_for_response_reliable
+    fn read_response(&mut self) -> Result<NetworkMessage, NetworkError> {
+        if let Some(ref mut stream) = self.stream {
+            // Ensure blocking mode (should already be set from reconnect)
+            stream
+                .set_nonblocking(false)
+                .map_err(|e| NetworkError::ReceiveFailed(format!("Failed to set blocking mode: {}", e)))?;
+
+            // Read message length (blocking with timeout)
+            let mut len_bytes = [0u8; 4];
+            stream
+                .read_exact(&mut len_bytes)
+                .map_err(|e| NetworkError::ReceiveFailed(format!("Failed to read length header: {}", e)))?;
+
+            let payload_len = u32::from_be_bytes(len_bytes) as usize;
+            tracing::info!("Received message length: {} bytes", payload_len);
+
+            // Reasonable message size limit
+            if payload_len > 1024 * 1024 {
+                return Err(NetworkError::ReceiveFailed(format!("Message too large: {} bytes", payload_len)));
+            }
+
+            // Read payload (blocking with timeout)
+            let mut payload = vec![0u8; payload_len];
+            stream
+                .read_exact(&mut payload)
+                .map_err(|e| NetworkError::ReceiveFailed(format!("Failed to read payload: {}", e)))?;
+
+            Ok(NetworkMessage {
+                source: self.server_addr.clone(),
+                payload,
+                timestamp: Instant::now(),
+            })
+        } else {
+            Err(NetworkError::ReceiveFailed("No active connection".to_string()))
+        }
+    }
 }
 
 impl NetworkTransport for TcpTransport {
@@ -120,6 +161,7 @@ impl NetworkTransport for TcpTransport {
                     .map_err(|e| NetworkError::ConnectionFailed(format!("Failed to set timeout: {}", e)))?;
 
                 self.stream = Some(stream);
+                self.counters.record_connected();
                 Ok(())
             }
             Err(e) => Err(NetworkError::ConnectionFailed(format!("TCP connect failed: {}", e))),
@@ -127,18 +169,20 @@ impl NetworkTransport for TcpTransport {
     }
 
     fn send_reliable(&mut self, payload: &[u8]) -> Result<(), NetworkError> {
-        self.ensure_stream_exists()?;
-
-        // Try to send, and retry once after reconnect if the connection was stale
-        match self.try_send(payload) {
-            Ok(()) => Ok(()),
-            Err(e) => {
-                // Connection may have been closed by server (idle timeout, etc.)
-                tracing::trace!("Send failed, attempting reconnect: {}", e);
-                self.connect()?;
-                self.try_send(payload)
+        let result = self.ensure_stream_exists().and_then(|()| {
+            // Try to send, and retry once after reconnect if the connection was stale
+            match self.try_send(payload) {
+                Ok(()) => Ok(()),
+                Err(e) => {
+                    // Connection may have been closed by server (idle timeout, etc.)
+                    tracing::trace!("Send failed, attempting reconnect: {}", e);
+                    self.connect()?;
+                    self.try_send(payload)
+                }
             }
-        }
+        });
+        self.counters.record_send(payload.len(), &result);
+        result
     }
 
     fn send_unreliable(&mut self, _payload: &[u8]) -> Result<(), NetworkError> {
@@ -174,13 +218,18 @@ impl NetworkTransport for TcpTransport {
                         let mut payload = vec![0u8; payload_len];
                         match stream.read_exact(&mut payload) {
                             Ok(()) => {
+                                self.counters.record_received(payload_len);
                                 messages.push(NetworkMessage {
                                     source: self.server_addr.clone(),
                                     payload,
                                     timestamp: Instant::now(),
                                 });
                             }
-                            Err(_) => break, // Connection closed or error
+                            Err(_) => {
+                                // Connection closed or error
+                                self.counters.record_receive_error();
+                                break;
+                            }
                         }
                     }
                     Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
@@ -190,6 +239,7 @@ impl NetworkTransport for TcpTransport {
                     }
                     Err(e) => {
                         tracing::debug!("TCP receive: error reading length header: {}", e);
+                        self.counters.record_receive_error();
                         break; // Connection error
                     }
                 }
@@ -209,39 +259,65 @@ impl NetworkTransport for TcpTransport {
     /// Wait for a single response message with blocking read and timeout
     /// Used for request-response patterns where we expect a reply
     fn wait_for_response_reliable(&mut self) -> Result<NetworkMessage, NetworkError> {
-        if let Some(ref mut stream) = self.stream {
-            // Ensure blocking mode (should already be set from reconnect)
-            stream
-                .set_nonblocking(false)
-                .map_err(|e| NetworkError::ReceiveFailed(format!("Failed to set blocking mode: {}", e)))?;
+        let result = self.read_response();
+        match &result {
+            Ok(msg) => self.counters.record_received(msg.payload.len()),
+            Err(_) => self.counters.record_receive_error(),
+        }
+        result
+    }
 
-            // Read message length (blocking with timeout)
-            let mut len_bytes = [0u8; 4];
-            stream
-                .read_exact(&mut len_bytes)
-                .map_err(|e| NetworkError::ReceiveFailed(format!("Failed to read length header: {}", e)))?;
+    fn metrics(&self) -> TransportMetrics {
+        self.counters.snapshot()
+    }
+}
 
-            let payload_len = u32::from_be_bytes(len_bytes) as usize;
-            tracing::info!("Received message length: {} bytes", payload_len);
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
 
-            // Reasonable message size limit
-            if payload_len > 1024 * 1024 {
-                return Err(NetworkError::ReceiveFailed(format!("Message too large: {} bytes", payload_len)));
-            }
+    use super::*;
 
-            // Read payload (blocking with timeout)
-            let mut payload = vec![0u8; payload_len];
-            stream
-                .read_exact(&mut payload)
-                .map_err(|e| NetworkError::ReceiveFailed(format!("Failed to read payload: {}", e)))?;
+    /// Echo server that returns every length-prefixed message on the first connection
+    fn spawn_echo_server() -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut len_bytes = [0u8; 4];
+            while stream.read_exact(&mut len_bytes).is_ok() {
+                let mut payload = vec![0u8; u32::from_be_bytes(len_bytes) as usize];
+                stream.read_exact(&mut payload).unwrap();
+                stream.write_all(&len_bytes).unwrap();
+                stream.write_all(&payload).unwrap();
+            }
+        });
+        port
+    }
 
-            Ok(NetworkMessage {
-                source: self.server_addr.clone(),
-                payload,
-                timestamp: Instant::now(),
-            })
-        } else {
-            Err(NetworkError::ReceiveFailed("No active connection".to_string()))
+    #[test]
+    fn test_loopback_metrics() {
+        let port = spawn_echo_server();
+        let addr = NetworkAddress::Tcp {
+            host: "127.0.0.1".to_string(),
+            port,
+        };
+        let mut transport = TcpTransport::new(addr, Duration::from_secs(1), Duration::from_secs(5));
+        transport.connect().unwrap();
+
+        for i in 0..100usize {
+            let payload = vec![i as u8; i + 1];
+            transport.send_reliable(&payload).unwrap();
+            let response = transport.wait_for_response_reliable().unwrap();
+            assert_eq!(response.payload, payload);
         }
+
+        let metrics = transport.metrics();
+        assert_eq!(metrics.bytes_sent, (1..=100).sum::<u64>());
+        assert_eq!(metrics.bytes_sent, metrics.bytes_received);
+        assert_eq!(metrics.send_errors, 0);
+        assert_eq!(metrics.receive_errors, 0);
+        assert_eq!(metrics.reconnections, 0);
+        assert!(metrics.last_connected.is_some());
     }
 }
@@ -15,7 +15,8 @@ use base64::Engine as _;
 use tetra_config::bluestation::SecretField;
 use tungstenite::{Connector, Message, WebSocket, stream::MaybeTlsStream};
 
-use super::{NetworkAddress, NetworkError, NetworkMessage, NetworkTransport};
+use super::metrics::TransportCounters;
+use super::{NetworkAddress, NetworkError, NetworkMessage, NetworkTransport, TransportMetrics};
 
 const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
 const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(10);
@@ -229,6 +230,7 @@ pub struct WebSocketTransport {
     last_ping_sent_at: Option<Instant>,
     last_ping_id: Option<u64>,
     ping_seq: u64,
+    counters: TransportCounters,
 }
 
 impl WebSocketTransport {
@@ -242,6 +244,7 @@ impl WebSocketTransport {
             last_ping_sent_at: None,
             last_ping_id: None,
             ping_seq: 0,
+            counters: TransportCounters::default(),
         }
     }
 
@@ -478,17 +481,20 @@ impl NetworkTransport for WebSocketTransport {
         self.ping_seq = 0;
         self.last_ping_id = None;
         self.last_ping_sent_at = None;
+        self.counters.record_connected();
 
         Ok(())
     }
 
     fn send_reliable(&mut self, payload: &[u8]) -> Result<(), NetworkError> {
-        let ws = self
-            .ws
-            .as_mut()
-            .ok_or_else(|| NetworkError::SendFailed("not connected".to_string()))?;
-        ws.send(Message::Binary(payload.to_vec().into()))
-            .map_err(|e| NetworkError::SendFailed(format!("WebSocket send failed: {}", e)))
+        let result = match self.ws.as_mut() {
+            Some(ws) => ws
+                .send(Message::Binary(payload.to_vec().into()))
+                .map_err(|e| NetworkError::SendFailed(format!("WebSocket send failed: {}", e))),
+            None => Err(NetworkError::SendFailed("not connected".to_string())),
+        };
+        self.counters.record_send(payload.len(), &result);
+        result
     }
 
     fn send_unreliable(&mut self, payload: &[u8]) -> Result<(), NetworkError> {
@@ -535,6 +541,7 @@ impl NetworkTransport for WebSocketTransport {
             match ws.read() {
                 Ok(Message::Binary(data)) => {
                     self.last_activity_at = Instant::now();
+                    self.counters.record_received(data.len());
                     messages.push(NetworkMessage {
                         source: source.clone(),
                         payload: data.into(),
@@ -586,6 +593,7 @@ impl NetworkTransport for WebSocketTransport {
                 }
                 Err(e) => {
                     tracing::warn!("WebSocketTransport: read error: {}", e);
+                    self.counters.record_receive_error();
                     self.ws = None;
                     break;
                 }
@@ -630,4 +638,8 @@ impl NetworkTransport for WebSocketTransport {
     fn is_connected(&self) -> bool {
         self.ws.is_some()
     }
+
+    fn metrics(&self) -> TransportMetrics {
+        self.counters.snapshot()
+    }
 }
@@ -61,6 +61,9 @@ pub struct Demodulator {
 
     slots_since_last_valid_burst: u32,
 
+    /// Number of slots skipped because slot timing slipped, for statistics
+    slots_skipped: u64,
+
     /// Timeslot of latest demodulated slot
     demodulated_slot_time: TdmaTime,
     demodulated_slot_available: bool,
@@ -88,6 +91,7 @@ impl Demodulator {
             next_input_sample_count: 0,
             averaged_timing_error: 0.0,
             slots_since_last_valid_burst: 0,
+            slots_skipped: 0,
 
             demodulated_slot_time: Default::default(),
             demodulated_slot_available: false,
@@ -174,6 +178,7 @@ impl Demodulator {
             // so use it with negations to round up to the next slot.
             let slots_to_skip = -(-tdiff).div_euclid(SAMPLES_SLOT) as i32;
             tracing::warn!("Skipping demodulation of {} slots due to lost samples", slots_to_skip);
+            self.slots_skipped += slots_to_skip.max(0) as u64;
             self.add_slots(slots_to_skip);
         }
 
@@ -390,6 +395,11 @@ impl Demodulator {
         }
     }
 
+    /// Number of slots skipped so far because slot timing slipped
+    pub fn slots_skipped(&self) -> u64 {
+        self.slots_skipped
+    }
+
     pub fn demodulated_slot_available(&self) -> bool {
         self.demodulated_slot_available
     }
@@ -8,6 +8,7 @@ use tetra_pdus::phy::traits::rxtx_dev::RxSlotBits;
 use tetra_pdus::phy::traits::rxtx_dev::RxTxDev;
 use tetra_pdus::phy::traits::rxtx_dev::RxTxDevError;
 use tetra_pdus::phy::traits::rxtx_dev::TxSlotBits;
+use tetra_saps::control::admin::PhyRxStats;
 
 use crate::phy::components::soapy_dev;
 
@@ -145,6 +146,21 @@ impl RxTxDev for RxTxDevSoapySdr {
             Ok(Default::default())
         }
     }
+
+    fn shutdown(&mut self) {
+        self.sdr.shutdown();
+    }
+
+    fn rx_stats(&self) -> PhyRxStats {
+        let Some(rx_dsp) = &self.rx_dsp else {
+            return PhyRxStats::default();
+        };
+        PhyRxStats {
+            timing_errors: rx_dsp.demodulators().map(|demod| demod.slots_skipped()).sum(),
+            buffer_overruns: rx_dsp.buffer_overruns,
+            ..Default::default()
+        }
+    }
 }
 
 struct RxDsp {
@@ -158,6 +174,9 @@ struct RxDsp {
 
     monitors: Vec<MonitorDlUlPair>,
     ul_demodulators: Vec<DemodulatorChannel>,
+
+    /// Number of reads from the SDR that lost samples
+    buffer_overruns: u64,
 }
 
 impl RxDsp {
@@ -199,9 +218,20 @@ impl RxDsp {
                 .iter()
                 .map(|ul_freq| DemodulatorChannel::new(fft_planner, rx_fcfb_params, *ul_freq, demodulator::Mode::Ul))
                 .collect(),
+
+            buffer_overruns: 0,
         }
     }
 
+    /// All demodulators, monitor pairs first
+    fn demodulators(&self) -> impl Iterator<Item = &demodulator::Demodulator> {
+        self.monitors
+            .iter()
+            .flat_map(|pair| std::iter::once(&pair.dl).chain(pair.ul.as_ref()))
+            .chain(self.ul_demodulators.iter())
+            .map(|channel| &channel.demodulator)
+    }
+
     fn process_block(&mut self, sdr: &mut soapyio::SoapyIo) -> Result<bool, RxTxDevError> {
         self.receive_block(sdr)?;
 
@@ -254,6 +284,7 @@ impl RxDsp {
                 let next_block_beginning = next_possible_block * block_size;
 
                 let mut samples_to_skip = next_block_beginning - next_count;
+                self.buffer_overruns += 1;
 
                 tracing::warn!(
                     "Lost {} samples, skipping {} more samples and {} processing blocks",
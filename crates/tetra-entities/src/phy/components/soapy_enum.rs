@@ -0,0 +1,94 @@
+use serde::Serialize;
+
+/// A SoapySDR device as reported by enumeration
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SoapyDeviceInfo {
+    pub driver: String,
+    pub label: String,
+    pub serial: String,
+    /// Number of channels of the device, the larger of the RX and TX channel counts.
+    /// 0 if the device could not be opened to query it.
+    pub num_channels: usize,
+}
+
+impl SoapyDeviceInfo {
+    fn from_args(args: &soapysdr::Args, num_channels: usize) -> Self {
+        let get = |key: &str| args.get(key).unwrap_or_default().to_string();
+        Self {
+            driver: get("driver"),
+            label: get("label"),
+            serial: get("serial"),
+            num_channels,
+        }
+    }
+}
+
+/// Enumerate all SoapySDR devices. Each device is opened briefly to query its channel count.
+pub fn enumerate_devices() -> Result<Vec<SoapyDeviceInfo>, soapysdr::Error> {
+    let devices = soapysdr::enumerate(soapysdr::Args::new())?
+        .iter()
+        .map(|dev_args| {
+            let dev_args_copy: soapysdr::Args = dev_args.iter().collect();
+            let num_channels = match soapysdr::Device::new(dev_args_copy) {
+                Ok(dev) => {
+                    let rx = dev.num_channels(soapysdr::Direction::Rx).unwrap_or(0);
+                    let tx = dev.num_channels(soapysdr::Direction::Tx).unwrap_or(0);
+                    rx.max(tx)
+                }
+                Err(err) => {
+                    tracing::warn!("Could not open SoapySDR device {} to query channels: {}", dev_args, err);
+                    0
+                }
+            };
+            SoapyDeviceInfo::from_args(dev_args, num_channels)
+        })
+        .collect();
+    Ok(devices)
+}
+
+/// Runs the given enumeration and formats the result as a JSON array
+pub fn devices_json<E>(enumerate: impl FnOnce() -> Result<Vec<SoapyDeviceInfo>, E>) -> Result<String, E> {
+    let devices = enumerate()?;
+    Ok(serde_json::to_string_pretty(&devices).unwrap_or_else(|_| "[]".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_devices_json() {
+        let json = devices_json(|| -> Result<_, ()> {
+            Ok(vec![SoapyDeviceInfo {
+                driver: "lime".to_string(),
+                label: "LimeSDR Mini [USB 3.0] 1D3AC6D2E8F1A2".to_string(),
+                serial: "1D3AC6D2E8F1A2".to_string(),
+                num_channels: 2,
+            }])
+        })
+        .unwrap();
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let devices = value.as_array().unwrap();
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0]["driver"], "lime");
+        assert_eq!(devices[0]["label"], "LimeSDR Mini [USB 3.0] 1D3AC6D2E8F1A2");
+        assert_eq!(devices[0]["serial"], "1D3AC6D2E8F1A2");
+        assert_eq!(devices[0]["num_channels"], 2);
+    }
+
+    #[test]
+    fn test_no_devices_is_empty_array() {
+        let json = devices_json(|| -> Result<_, ()> { Ok(vec![]) }).unwrap();
+        assert_eq!(json, "[]");
+    }
+
+    #[test]
+    fn test_missing_args_are_empty() {
+        let args: soapysdr::Args = [("driver", "uhd")].into_iter().collect();
+        let info = SoapyDeviceInfo::from_args(&args, 1);
+        assert_eq!(info.driver, "uhd");
+        assert_eq!(info.label, "");
+        assert_eq!(info.serial, "");
+    }
+}
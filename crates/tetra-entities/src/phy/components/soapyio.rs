@@ -274,6 +274,20 @@ impl SoapyIo {
         }
     }
 
+    /// Deactivate the streams, TX first so the transmitter is not left keyed
+    pub fn shutdown(&mut self) {
+        if let Some(tx) = &mut self.tx
+            && let Err(err) = tx.deactivate(None)
+        {
+            tracing::error!("SoapySDR: Failed to deactivate TX stream: {}", err);
+        }
+        if let Some(rx) = &mut self.rx
+            && let Err(err) = rx.deactivate(None)
+        {
+            tracing::error!("SoapySDR: Failed to deactivate RX stream: {}", err);
+        }
+    }
+
     pub fn current_time(&self) -> Result<i64, RxTxDevError> {
         self.dev.get_hardware_time(None).map_err(|_| RxTxDevError::RxReadError)
     }
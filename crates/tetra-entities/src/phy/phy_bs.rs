@@ -4,8 +4,9 @@ use std::panic;
 use tetra_config::bluestation::SharedConfig;
 use tetra_core::tetra_entities::TetraEntity;
 use tetra_core::{BitBuffer, BurstType, PhyBlockNum, PhyBlockType, Sap, TdmaTime, TrainingSequence};
-use tetra_pdus::phy::traits::rxtx_dev::RxBurstBits;
+use tetra_pdus::phy::traits::rxtx_dev::{RxBurstBits, RxSlotBits};
 use tetra_pdus::phy::traits::rxtx_dev::{RxTxDev, TxSlotBits};
+use tetra_saps::control::admin::{PhyRxStats, StackStateSnapshot};
 use tetra_saps::tp::TpUnitdataInd;
 use tetra_saps::{SapMsg, SapMsgInner};
 
@@ -34,6 +35,11 @@ pub struct PhyBs<D: RxTxDev> {
     rxtxdev: D,
 
     tick: u64,
+
+    /// Burst counters, the device keeps the others
+    rx_stats: PhyRxStats,
+    /// Receive statistics at the start of the current multiframe, to check the timing error rate against
+    rx_stats_window_start: PhyRxStats,
 }
 
 impl<D: RxTxDev> PhyBs<D> {
@@ -71,9 +77,61 @@ impl<D: RxTxDev> PhyBs<D> {
             ul_input_file,
             rxtxdev,
             tick: 0,
+            rx_stats: PhyRxStats::default(),
+            rx_stats_window_start: PhyRxStats::default(),
+        }
+    }
+
+    /// Receive statistics since startup
+    pub fn rx_stats(&self) -> PhyRxStats {
+        PhyRxStats {
+            bursts_attempted: self.rx_stats.bursts_attempted,
+            bursts_decoded: self.rx_stats.bursts_decoded,
+            correlation_failures: self.rx_stats.correlation_failures,
+            ..self.rxtxdev.rx_stats()
+        }
+    }
+
+    /// Warn if more than 10% of the slots of the last multiframe (18 frames) were lost to timing errors
+    fn check_rx_timing_errors(&mut self) {
+        let stats = self.rx_stats();
+        let attempted = stats.bursts_attempted - self.rx_stats_window_start.bursts_attempted;
+        let timing_errors = stats.timing_errors - self.rx_stats_window_start.timing_errors;
+        if timing_errors * 10 > attempted {
+            tracing::warn!(
+                "PhyBs: {} timing errors for {} bursts attempted during the last multiframe",
+                timing_errors,
+                attempted
+            );
+        }
+        self.rx_stats_window_start = stats;
+    }
+
+    /// Count a slot the demodulator searched for bursts
+    fn count_rx_slot(rx_stats: &mut PhyRxStats, rx_slot: &RxSlotBits<'_>) {
+        rx_stats.bursts_attempted += 1;
+        let found = [&rx_slot.slot, &rx_slot.subslot1, &rx_slot.subslot2]
+            .iter()
+            .any(|burst| burst.train_type != TrainingSequence::NotFound);
+        if found {
+            rx_stats.bursts_decoded += 1;
+        } else {
+            rx_stats.correlation_failures += 1;
         }
     }
 
+    fn rx_query_stack_state(&self, queue: &mut MessageQueue, sender: TetraEntity) {
+        let _ = queue.push_back(SapMsg::new(
+            Sap::Control,
+            TetraEntity::Phy,
+            sender,
+            SapMsgInner::StackStateSnapshot(StackStateSnapshot {
+                phy_rx_stats: Some(self.rx_stats()),
+                ..Default::default()
+            }),
+        ));
+    }
+
     fn send_rxblock_to_lmac(
         queue: &mut MessageQueue,
         train_type: TrainingSequence,
@@ -83,19 +141,19 @@ impl<D: RxTxDev> PhyBs<D> {
         bits: BitBuffer,
     ) {
         // Uplink timeslot is two after downlink. Thus was transmitted at dltime - 2
-        let sapmsg = SapMsg {
-            sap: Sap::TpSap,
-            src: TetraEntity::Phy,
-            dest: TetraEntity::Lmac,
-            msg: SapMsgInner::TpUnitdataInd(TpUnitdataInd {
+        let sapmsg = SapMsg::new(
+            Sap::TpSap,
+            TetraEntity::Phy,
+            TetraEntity::Lmac,
+            SapMsgInner::TpUnitdataInd(TpUnitdataInd {
                 train_type,
                 burst_type,
                 block_type,
                 block_num,
                 block: bits,
             }),
-        };
-        queue.push_back(sapmsg);
+        );
+        let _ = queue.push_back(sapmsg);
     }
 
     fn split_rxslot_and_send_to_lmac(queue: &mut MessageQueue, burst: &RxBurstBits<'_>) {
@@ -223,6 +281,7 @@ impl<D: RxTxDev> PhyBs<D> {
         // The Lmac error correction will eliminate the false positives
         for rx_slot in rx {
             if let Some(rx_slot) = rx_slot {
+                Self::count_rx_slot(&mut self.rx_stats, &rx_slot);
                 let mut slot_sent = false;
                 if rx_slot.slot.train_type != TrainingSequence::NotFound {
                     tracing::info!(ts=%self.dltime, "rx_tpsap_prim got {:?} in fullslot", rx_slot.slot.train_type);
@@ -285,6 +344,9 @@ impl<D: RxTxDev + Send + 'static> TetraEntityTrait for PhyBs<D> {
             Sap::TpcSap => {
                 self.rx_tpc_prim(queue, message);
             }
+            Sap::Control if matches!(message.msg, SapMsgInner::QueryStackState(_)) => {
+                self.rx_query_stack_state(queue, message.src);
+            }
             _ => {
                 panic!();
             }
@@ -293,5 +355,18 @@ impl<D: RxTxDev + Send + 'static> TetraEntityTrait for PhyBs<D> {
 
     fn tick_start(&mut self, _queue: &mut MessageQueue, ts: TdmaTime) {
         self.dltime = ts;
+        if ts.f == 1 && ts.t == 1 {
+            self.check_rx_timing_errors();
+        }
+    }
+
+    fn shutdown(&mut self, _queue: &mut MessageQueue) {
+        tracing::info!("PhyBs: stopping RF device");
+        self.rxtxdev.shutdown();
+
+        // Let the file writers write out what is queued and close their files
+        for sender in [self.dl_tx_sender.take(), self.ul_rx_sender.take()].into_iter().flatten() {
+            let _ = sender.send(FileWriteMsg::Shutdown);
+        }
     }
 }
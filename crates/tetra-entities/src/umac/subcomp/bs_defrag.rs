@@ -27,19 +27,29 @@ impl BsDefrag {
         }
     }
 
-    pub fn age_buffers(&mut self, t: TdmaTime) {
+    /// Removes buffers whose last fragment did not arrive in time, along with those already reset after an
+    /// overflow. Without this, an entry is kept for every SSI that ever left a fragmented message unfinished.
+    /// Returns the number of active buffers that timed out; buffers no longer active are removed without being counted.
+    pub fn age_buffers(&mut self, t: TdmaTime) -> usize {
+        let mut evicted = 0;
         for map in &mut self.buffers {
-            for buffer in map.values_mut() {
-                if buffer.state != DefragBufferState::Inactive && t.diff(buffer.t_last) > DEFRAG_TS_BEFORE_TIMEOUT {
-                    tracing::info!("defrag_buffer for {} timed out", buffer.t_last.t);
-                    buffer.reset();
+            map.retain(|ssi, buffer| {
+                if buffer.state != DefragBufferState::Active {
+                    return false;
                 }
-            }
+                let timed_out = t.diff(buffer.t_last) > DEFRAG_TS_BEFORE_TIMEOUT;
+                if timed_out {
+                    tracing::info!("defrag_buffer for ts {} ssi {} timed out", buffer.t_last.t, ssi);
+                    evicted += 1;
+                }
+                !timed_out
+            });
         }
+        evicted
     }
 
-    /// Inserts a first fragment into a fragbuffer.
-    pub fn insert_first(&mut self, bitbuffer: &mut BitBuffer, t: TdmaTime, addr: TetraAddress, aie_info: Option<Todo>) {
+    /// Inserts a first fragment into a fragbuffer. Takes ownership of the fragment, the data from pos to end is used.
+    pub fn insert_first(&mut self, mut bitbuffer: BitBuffer, t: TdmaTime, addr: TetraAddress, aie_info: Option<Todo>) {
         // Check if buffer already exists for this ssi/timeslot
         // Remove and discard, if so.
         let ts = (t.t - 1) as usize;
@@ -61,7 +71,8 @@ impl BsDefrag {
         buf.aie_info = aie_info;
 
         // Copy the bitbuffer data from pos to end into our fragbuffer
-        buf.buffer.copy_bits(bitbuffer, bitbuffer.get_len_remaining());
+        let frag_len = bitbuffer.get_len_remaining();
+        buf.buffer.copy_bits(&mut bitbuffer, frag_len);
 
         tracing::debug!(
             "defrag_buffer for ts {} ssi: {}, t: {}-{}, frags: {}: {}",
@@ -129,6 +140,12 @@ impl BsDefrag {
             }
         };
 
+        // A buffer that timed out or overflowed holds no valid message; discard it
+        if buf.state != DefragBufferState::Active {
+            tracing::warn!("defrag_buffer for ts {} ssi {} not active, discarding", t.t, ssi);
+            return None;
+        }
+
         // Update state to complete and return
         buf.state = DefragBufferState::Complete;
         buf.buffer.set_raw_pos(0);
@@ -156,14 +173,22 @@ impl BsDefrag {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::{Rng, SeedableRng, rngs::StdRng};
     use tetra_core::{address::SsiType, bitbuffer::BitBuffer, debug};
 
+    fn issi(ssi: u32) -> TetraAddress {
+        TetraAddress {
+            ssi,
+            ssi_type: SsiType::Issi,
+        }
+    }
+
     #[test]
     fn test_3_chunks() {
         debug::setup_logging_verbose();
 
         let ssi = 1234;
-        let mut buf1 = BitBuffer::from_bitstr("000");
+        let buf1 = BitBuffer::from_bitstr("000");
         let t1 = TdmaTime::default().add_timeslots(2); // UL time 0
         let mut buf2 = BitBuffer::from_bitstr("111");
         let t2 = t1.add_timeslots(4);
@@ -175,10 +200,125 @@ mod tests {
             ssi,
             ssi_type: SsiType::Issi,
         };
-        defragger.insert_first(&mut buf1, t1, addr, None);
+        defragger.insert_first(buf1, t1, addr, None);
         defragger.insert_next(&mut buf2, ssi, t2);
         let out = defragger.insert_last(&mut buf3, ssi, t3).unwrap();
         assert_eq!(out.buffer.to_bitstr(), "0001110011");
         assert_eq!(out.buffer.get_pos(), 0);
     }
+
+    #[test]
+    fn test_interleaved_ssis() {
+        debug::setup_logging_verbose();
+        let mut rng = StdRng::seed_from_u64(1329);
+        let num_ssis = 32;
+        let t0 = TdmaTime::default();
+
+        // Per SSI: a random payload, split into 2..=6 fragments at random boundaries
+        let mut payloads = vec![];
+        let mut frags: Vec<Vec<BitBuffer>> = vec![];
+        for _ in 0..num_ssis {
+            let len = rng.random_range(16..=1024);
+            let payload: String = (0..len).map(|_| if rng.random_bool(0.5) { '1' } else { '0' }).collect();
+            let num_frags = rng.random_range(2..=6);
+            let mut cuts: Vec<usize> = (0..num_frags - 1).map(|_| rng.random_range(1..len)).collect();
+            cuts.sort();
+            let bounds: Vec<usize> = [vec![0], cuts, vec![len]].concat();
+            frags.push(bounds.windows(2).map(|w| BitBuffer::from_bitstr(&payload[w[0]..w[1]])).collect());
+            payloads.push(payload);
+        }
+
+        // Deliver fragments in random interleaved order, keeping per-SSI order, spread over all timeslots
+        let mut defragger = BsDefrag::new();
+        let mut next_frag = vec![0; num_ssis];
+        let mut completed = 0;
+        let mut step = 0;
+        while completed < num_ssis {
+            let i = rng.random_range(0..num_ssis);
+            if next_frag[i] == frags[i].len() {
+                continue;
+            }
+            let ssi = 1000 + i as u32;
+            let t = t0.add_timeslots((step * 4 + i % 4) as i32);
+            let frag_idx = next_frag[i];
+            let num_frags = frags[i].len();
+            let frag = &mut frags[i][frag_idx];
+            next_frag[i] += 1;
+            step += 1;
+
+            if frag_idx == 0 {
+                defragger.insert_first(frag.clone(), t, issi(ssi), None);
+            } else if frag_idx < num_frags - 1 {
+                defragger.insert_next(frag, ssi, t);
+            } else {
+                let out = defragger.insert_last(frag, ssi, t).expect("reassembly failed");
+                assert_eq!(out.addr.ssi, ssi);
+                assert_eq!(out.num_frags, num_frags);
+                assert_eq!(out.buffer.to_bitstr(), payloads[i], "payload mismatch for ssi {}", ssi);
+                completed += 1;
+            }
+        }
+
+        // Nothing left behind
+        assert!(defragger.buffers.iter().all(|map| map.is_empty()));
+    }
+
+    #[test]
+    fn test_timeout_discards_sequence() {
+        debug::setup_logging_verbose();
+        let ssi = 1234;
+        let t1 = TdmaTime::default();
+        let mut defragger = BsDefrag::new();
+        defragger.insert_first(BitBuffer::from_bitstr("000"), t1, issi(ssi), None);
+
+        // Age past the timeout; the sequence is dropped and late fragments are ignored
+        let t2 = t1.add_timeslots(DEFRAG_TS_BEFORE_TIMEOUT + 4);
+        defragger.age_buffers(t2);
+        defragger.insert_next(&mut BitBuffer::from_bitstr("111"), ssi, t2);
+        assert!(defragger.insert_last(&mut BitBuffer::from_bitstr("0011"), ssi, t2).is_none());
+
+        // A new first fragment starts a fresh sequence
+        let t3 = t2.add_timeslots(4);
+        defragger.insert_first(BitBuffer::from_bitstr("101"), t3, issi(ssi), None);
+        let out = defragger
+            .insert_last(&mut BitBuffer::from_bitstr("01"), ssi, t3.add_timeslots(4))
+            .unwrap();
+        assert_eq!(out.buffer.to_bitstr(), "10101");
+        assert_eq!(out.num_frags, 2);
+        assert_eq!(out.t_first, t3);
+    }
+
+    #[test]
+    fn test_age_buffers_evicts_stale() {
+        let t1 = TdmaTime::default();
+        let mut defragger = BsDefrag::new();
+        for ssi in 0..1000 {
+            defragger.insert_first(BitBuffer::from_bitstr("1010"), t1, issi(ssi), None);
+        }
+
+        // Still waiting for further fragments
+        assert_eq!(defragger.age_buffers(t1.add_timeslots(DEFRAG_TS_BEFORE_TIMEOUT)), 0);
+        assert_eq!(defragger.buffers.iter().map(|map| map.len()).sum::<usize>(), 1000);
+
+        assert_eq!(defragger.age_buffers(t1.add_timeslots(DEFRAG_TS_BEFORE_TIMEOUT + 1)), 1000);
+        assert!(defragger.buffers.iter().all(|map| map.is_empty()));
+    }
+
+    #[test]
+    fn test_age_buffers_counts_only_timed_out() {
+        let t1 = TdmaTime::default();
+        let mut defragger = BsDefrag::new();
+        for ssi in 0..3 {
+            defragger.insert_first(BitBuffer::from_bitstr("1010"), t1, issi(ssi), None);
+        }
+        let ts = (t1.t - 1) as usize;
+        defragger.buffers[ts].get_mut(&0).unwrap().state = DefragBufferState::Complete;
+        defragger.buffers[ts].get_mut(&1).unwrap().state = DefragBufferState::Inactive;
+
+        // Buffers that are no longer active are dropped, but are not counted as evictions
+        assert_eq!(defragger.age_buffers(t1.add_timeslots(1)), 0);
+        assert_eq!(defragger.buffers[ts].len(), 1);
+        assert_eq!(defragger.age_buffers(t1.add_timeslots(DEFRAG_TS_BEFORE_TIMEOUT + 1)), 1);
+        assert!(defragger.buffers[ts].is_empty());
+    }
 }
@@ -1,9 +1,11 @@
 use std::cmp::min;
 
-use tetra_core::{BitBuffer, TxReporter};
+use tetra_core::typed_pdu_fields::FieldU6;
+use tetra_core::{BitBuffer, TxReporter, field_range_check};
 
 use tetra_pdus::umac::pdus::{mac_end_dl::MacEndDl, mac_frag_dl::MacFragDl, mac_resource::MacResource};
 
+use crate::umac::subcomp::bs_sched::SCH_F_CAP;
 use crate::umac::subcomp::fillbits;
 
 #[derive(Debug)]
@@ -13,6 +15,8 @@ pub struct BsFragger {
     is_fully_transmitted: bool,
     sdu: BitBuffer,
     tx_reporter: Option<TxReporter>,
+    /// Number of SDU bits in the first fragment, if it was decided upfront that the PDU is fragmented
+    first_frag_sdu_len: Option<usize>,
 }
 
 /// We won't start fragmentation if less than MIN_SLOT_CAP_FOR_FRAG_START bits are free in the slot
@@ -32,9 +36,53 @@ impl BsFragger {
             is_fully_transmitted: false,
             sdu,
             tx_reporter,
+            first_frag_sdu_len: None,
         }
     }
 
+    /// Create a fragger for a PDU known not to fit a SCH/F. The first fragment is a MAC-RESOURCE
+    /// filling an entire SCH/F, the remaining SDU bits follow in MAC-FRAGs and a MAC-END.
+    pub fn new_frag_start(mut resource: MacResource, sdu: BitBuffer, tx_reporter: Option<TxReporter>) -> Self {
+        let hdr_len_bits = resource.compute_header_len();
+        assert!(
+            hdr_len_bits + sdu.get_len() > SCH_F_CAP,
+            "PDU of {} bits fits a SCH/F",
+            hdr_len_bits + sdu.get_len()
+        );
+        resource.length_ind = FieldU6::new(field_range_check!(0b111111, 6)); // Start of fragmentation
+        resource.fill_bits = false;
+
+        let mut fragger = Self::new(resource, sdu, tx_reporter);
+        fragger.first_frag_sdu_len = Some(SCH_F_CAP - hdr_len_bits);
+        fragger
+    }
+
+    /// Writes the MAC-RESOURCE starting a fragmentation decided upon in new_frag_start.
+    /// Needs an empty SCH/F, returns false without writing anything otherwise.
+    fn get_frag_start_chunk(&mut self, mac_block: &mut BitBuffer, first_frag_sdu_len: usize) -> bool {
+        let hdr_len_bits = self.resource.compute_header_len();
+        if mac_block.get_len_remaining() < hdr_len_bits + first_frag_sdu_len {
+            tracing::debug!(
+                "-> does_not_fit (cap={} needed={}), trying again next frame",
+                mac_block.get_len_remaining(),
+                hdr_len_bits + first_frag_sdu_len
+            );
+            return false;
+        }
+
+        tracing::debug!(
+            "-> Fragged {:?} sdu {}",
+            self.resource,
+            self.sdu.raw_dump_bin(false, false, 0, first_frag_sdu_len)
+        );
+
+        self.resource.to_bitbuf(mac_block);
+        mac_block.copy_bits(&mut self.sdu, first_frag_sdu_len);
+        fillbits::addition::write(mac_block, None);
+        self.mac_hdr_is_written = true;
+        true
+    }
+
     /// Writes MAC-RESOURCE to dest_buf, starting fragmentation if needed.
     /// Then, writes as many SDU bits as possible.
     /// Returns true if the entire SDU was consumed, false if the PDU is fragmented
@@ -69,7 +117,7 @@ impl BsFragger {
             );
 
             // Update PDU fields
-            self.resource.length_ind = total_len_bytes as u8;
+            self.resource.length_ind = FieldU6::new(total_len_bytes as u8);
             self.resource.fill_bits = num_fill_bits > 0;
 
             tracing::debug!(
@@ -99,7 +147,7 @@ impl BsFragger {
             false
         } else {
             // We need to start fragmentation. No fill bits are needed
-            self.resource.length_ind = 0b111111; // Start of fragmentation
+            self.resource.length_ind = FieldU6::new(field_range_check!(0b111111, 6)); // Start of fragmentation
             self.resource.fill_bits = false;
             assert!(num_fill_bits == 0, "Got {} fill bits upon frag start", num_fill_bits);
             let sdu_bits = slot_cap_bits - hdr_len_bits;
@@ -212,7 +260,11 @@ impl BsFragger {
             "mac_block must be full or byte aligned before writing"
         );
 
-        self.is_fully_transmitted = if !self.mac_hdr_is_written {
+        self.is_fully_transmitted = if let (false, Some(first_frag_sdu_len)) = (self.mac_hdr_is_written, self.first_frag_sdu_len) {
+            // First fragment of a PDU known to need fragmentation, more chunks always follow
+            self.get_frag_start_chunk(mac_block, first_frag_sdu_len);
+            false
+        } else if !self.mac_hdr_is_written {
             // First chunk, write MAC-RESOURCE
             self.get_resource_chunk(mac_block)
         } else {
@@ -248,6 +300,7 @@ mod tests {
         TxState,
         address::{SsiType, TetraAddress},
         debug,
+        typed_pdu_fields::FieldU2,
     };
 
     use crate::umac::subcomp::bs_sched::{SCH_F_CAP, SCH_HD_CAP};
@@ -257,9 +310,9 @@ mod tests {
         MacResource {
             fill_bits: false,
             pos_of_grant: 0,
-            encryption_mode: 0,
+            encryption_mode: FieldU2::new(0),
             random_access_flag: false,
-            length_ind: 0,
+            length_ind: FieldU6::new(0),
             addr: Some(TetraAddress {
                 ssi_type: SsiType::Issi,
                 ssi: 1234,
@@ -420,4 +473,38 @@ mod tests {
         assert!(reporter.is_in_final_state());
         assert!(!reporter.is_transmitted());
     }
+
+    #[test]
+    fn test_frag_start_needs_empty_schf() {
+        debug::setup_logging_verbose();
+        let pdu = get_default_resource();
+        let first_frag_len = SCH_F_CAP - pdu.compute_header_len();
+        let sdu = BitBuffer::from_bitstr(&"10".repeat(first_frag_len));
+        let reporter = TxReporter::new_unacked();
+        let mut fragger = BsFragger::new_frag_start(pdu, sdu, Some(reporter.clone()));
+
+        // A partially used slot is skipped, the first fragment needs a full SCH/F
+        let mut mac_block = BitBuffer::new(SCH_F_CAP);
+        mac_block.write_zeroes(16);
+        assert!(!fragger.get_next_chunk(&mut mac_block));
+        assert_eq!(mac_block.get_len_written(), 16);
+
+        let mut mac_block = BitBuffer::new(SCH_F_CAP);
+        assert!(!fragger.get_next_chunk(&mut mac_block));
+        assert_eq!(mac_block.get_len_remaining(), 0);
+        mac_block.seek(0);
+        let pdu = MacResource::from_bitbuf(&mut mac_block).unwrap();
+        assert_eq!(pdu.length_ind.get(), 0b111111);
+        assert!(!reporter.is_transmitted());
+
+        let mut mac_block = BitBuffer::new(SCH_F_CAP);
+        assert!(fragger.get_next_chunk(&mut mac_block));
+        mac_block.seek(0);
+        let pdu = MacEndDl::from_bitbuf(&mut mac_block).unwrap();
+        assert_eq!(
+            pdu.length_ind as usize,
+            (MacEndDl::compute_hdr_len(false, false) + first_frag_len).div_ceil(8)
+        );
+        assert!(reporter.is_transmitted());
+    }
 }
@@ -1,11 +1,25 @@
-use tetra_core::{BitBuffer, Direction, PhyBlockNum, PhysicalChannel, TdmaTime, TetraAddress, Todo, TxReporter, unimplemented_log};
+use std::collections::VecDeque;
+
+use tetra_core::pdu_parse_error::PduParseErr;
+use tetra_core::typed_pdu_fields::{FieldU2, FieldU6};
+use tetra_core::{BitBuffer, Direction, MAIN_CARRIER_ID, PhyBlockNum, PhysicalChannel, SsiType, TdmaTime, TetraAddress, TxReporter};
 use tetra_saps::{
-    control::call_control::Circuit,
+    control::{
+        admin::{CircuitInfo, MultiframeUtilization, UlGrantInfo},
+        call_control::Circuit,
+    },
     tmv::{TmvUnitdataReq, TmvUnitdataReqSlot, enums::logical_chans::LogicalChannel},
 };
 
 use tetra_pdus::{
-    mle::pdus::{d_mle_sync::DMleSync, d_mle_sysinfo::DMleSysinfo},
+    llc::pdus::bl_udata::BlUdata,
+    mle::{
+        enums::mle_protocol_discriminator::MleProtocolDiscriminator,
+        pdus::{
+            d_mle_sync::DMleSync, d_mle_sysinfo::DMleSysinfo, d_nwrk_broadcast::DNwrkBroadcast,
+            d_nwrk_broadcast_remove::DNwrkBroadcastRemove,
+        },
+    },
     umac::{
         enums::{
             access_assign_dl_usage::AccessAssignDlUsage, access_assign_ul_usage::AccessAssignUlUsage,
@@ -25,7 +39,12 @@ use tetra_pdus::{
 
 use crate::{
     lmac::components::scrambler,
-    umac::subcomp::{bs_frag::BsFragger, circuit_mgr::CircuitMgr},
+    umac::subcomp::{
+        bs_frag::BsFragger,
+        circuit_mgr::CircuitMgr,
+        fillbits,
+        utilization::{FrameUtilization, UtilizationBuffer},
+    },
 };
 
 /// We submit this many TX timeslots ahead of the current time
@@ -40,6 +59,9 @@ pub const SCH_HD_CAP: usize = 124;
 pub const SCH_F_CAP: usize = 268;
 pub const TCH_S_CAP: usize = 274;
 
+/// Multiframe in which the neighbour cell D-NWRK-BROADCAST is sent on frame 18 of the MCCH, once per hyperframe
+pub const NEIGHBOR_BROADCAST_MULTIFRAME: u8 = 2;
+
 /// Number of timeslots the scheduler operates on. May become larger when secondary carriers are supported.
 pub const NUM_TIMESLOTS: usize = 4;
 
@@ -50,6 +72,8 @@ pub struct PrecomputedUmacPdus {
     pub mle_sysinfo: DMleSysinfo,
     pub mac_sync: MacSync,
     pub mle_sync: DMleSync,
+    /// D-NWRK-BROADCAST announcing the neighbour cells, None if none are configured
+    pub mle_nwrk_broadcast: Option<DNwrkBroadcast>,
 }
 
 #[derive(Debug)]
@@ -63,6 +87,8 @@ pub struct TimeslotSchedule {
 pub struct BsChannelScheduler {
     pub cur_dltime: TdmaTime,
     scrambling_code: u32,
+    /// Carrier this scheduler serves, put into the physical channel of every slot
+    carrier_id: u8,
     precomps: PrecomputedUmacPdus,
     /// Collect dltx traffic here that can't be sent this slot.
     /// Swapped back into the dltx_queues method at the end of the tick.
@@ -82,12 +108,23 @@ pub struct BsChannelScheduler {
     /// The next STCH built for a matching SSI should carry random_access_flag=true to properly
     /// acknowledge the random access per ETSI 21.4.3.1.
     pending_ra_acks: [Vec<u32>; 4],
+
+    /// DL/UL usage of every finalized timeslot over the last hyperframe
+    utilization: UtilizationBuffer,
+
+    /// Neighbour cells to be removed from the MSs' memory, sent one per frame 18 before the updated broadcast
+    nwrk_broadcast_removals: VecDeque<DNwrkBroadcastRemove>,
+    /// The neighbour cells changed, send the D-NWRK-BROADCAST on the next free frame 18 instead of waiting a hyperframe
+    nwrk_broadcast_changed: bool,
 }
 
 #[derive(Debug)]
 pub enum DlSchedElem {
-    /// A SYSINFO or neighboring cells info block. The integer determines which of the precomputed blocks to use (SYSINFO1, SYSINFO2, NEIGHBORING_CELLS
-    Broadcast(Todo),
+    /// Neighbour cell information, sent as a full-slot broadcast in frame 18 of the MCCH
+    NeighborBroadcast(DNwrkBroadcast),
+
+    /// Removal of a previously announced neighbour cell, sent in frame 18 of the MCCH like the broadcast
+    NeighborBroadcastRemove(DNwrkBroadcastRemove),
 
     /// A received MAC-ACCESS PDU still has to be acknowledged
     RandomAccessAck(TetraAddress),
@@ -97,9 +134,11 @@ pub enum DlSchedElem {
     Grant(TetraAddress, BasicSlotgrant),
 
     /// A MAC-RESOURCE PDU. May be split into fragments upon processing, in which case a FragBuf will be inserted after processing the resource.
-    Resource(MacResource, BitBuffer, Option<TxReporter>),
+    /// The last field is the number of further transmissions of the PDU, each in the next frame on the same timeslot.
+    Resource(MacResource, BitBuffer, Option<TxReporter>, u8),
 
-    /// A FragBuf containing remaining non-transmitted information after a MAC-RESOURCE start has been transmitted
+    /// A FragBuf containing remaining non-transmitted information after a MAC-RESOURCE start has been transmitted,
+    /// or a PDU too large for a SCH/F whose fragmentation was decided upon enqueueing
     FragBuf(BsFragger),
 
     /// Pre-built STCH block for FACCH/stealing a half-slot from traffic channel.
@@ -121,6 +160,7 @@ impl BsChannelScheduler {
         BsChannelScheduler {
             cur_dltime: TdmaTime { t: 0, f: 0, m: 0, h: 0 }, // Intentionally invalid, updated in tick function
             scrambling_code,
+            carrier_id: MAIN_CARRIER_ID,
             precomps,
             dltx_next_slot_queue: Vec::new(),
             dltx_queues: [Vec::new(), Vec::new(), Vec::new(), Vec::new()],
@@ -128,6 +168,9 @@ impl BsChannelScheduler {
             circuits: CircuitMgr::new(),
             hangtime: [false, false, false, false],
             pending_ra_acks: [Vec::new(), Vec::new(), Vec::new(), Vec::new()],
+            utilization: UtilizationBuffer::new(),
+            nwrk_broadcast_removals: VecDeque::new(),
+            nwrk_broadcast_changed: false,
         }
     }
 
@@ -155,13 +198,14 @@ impl BsChannelScheduler {
         );
     }
 
-    pub fn is_hangtime(&self, ts: u8) -> bool {
+    /// True once the timeslot entered hangtime, even while queued FACCH still keeps it in traffic mode
+    pub fn hangtime_active(&self, ts: u8) -> bool {
         self.hangtime[ts as usize - 1]
     }
 
-    fn is_hangtime_effective(&self, ts: u8) -> bool {
-        let idx = ts as usize - 1;
-        if !self.hangtime[idx] {
+    /// True if the timeslot is in hangtime and has switched to signalling mode, so no traffic is sent on it
+    pub fn hangtime_effective(&self, ts: u8) -> bool {
+        if !self.hangtime_active(ts) {
             return false;
         }
         // If a stealing block is still queued for this slot, keep traffic mode
@@ -180,8 +224,9 @@ impl BsChannelScheduler {
     fn generate_hangtime_idle_schf(&self) -> BitBuffer {
         // Full-slot SCH/F carrying a Null PDU (idle).
         let mut buf = BitBuffer::new(SCH_F_CAP);
-        let pdu = MacResource::null_pdu();
+        let (pdu, num_fill_bits) = MacResource::null_pdu_for_channel(LogicalChannel::SchF);
         pdu.to_bitbuf(&mut buf);
+        fillbits::addition::write(&mut buf, Some(num_fill_bits));
         buf
     }
 
@@ -207,6 +252,35 @@ impl BsChannelScheduler {
         }
     }
 
+    /// Replace the neighbour cell D-NWRK-BROADCAST. The removals are sent first, after which the new
+    /// broadcast is sent without waiting for its multiframe.
+    pub fn set_neighbor_broadcast(&mut self, broadcast: Option<DNwrkBroadcast>, removals: Vec<DNwrkBroadcastRemove>) {
+        for removal in &removals {
+            tracing::debug!("BsChannelScheduler: removing neighbour cell {}", removal);
+        }
+        self.nwrk_broadcast_removals.extend(removals);
+        self.precomps.mle_nwrk_broadcast = broadcast;
+        self.nwrk_broadcast_changed = true;
+    }
+
+    /// Update the Cell load CA value in the broadcast D-MLE-SYNC
+    pub fn set_cell_load(&mut self, cell_load_ca: u8) {
+        if self.precomps.mle_sync.cell_load_ca != cell_load_ca {
+            tracing::debug!("BsChannelScheduler: cell_load_ca {}", cell_load_ca);
+            self.precomps.mle_sync.cell_load_ca = cell_load_ca;
+        }
+    }
+
+    pub fn cell_load(&self) -> u8 {
+        self.precomps.mle_sync.cell_load_ca
+    }
+
+    /// Write the precomputed MAC-SYNC and D-MLE-SYNC as a BSCH block. The time is that of the last finalized timeslot.
+    pub fn write_sync(&self, buf: &mut BitBuffer) {
+        self.precomps.mac_sync.to_bitbuf(buf);
+        self.precomps.mle_sync.to_bitbuf(buf);
+    }
+
     /// Fully wipe the schedule
     pub fn purge_schedule(&mut self) {
         self.dltx_queues = [Vec::new(), Vec::new(), Vec::new(), Vec::new()];
@@ -418,7 +492,22 @@ impl BsChannelScheduler {
         self.dltx_queues[ts as usize - 1].push(elem);
     }
 
-    pub fn dl_enqueue_tma(&mut self, pdu: MacResource, sdu: BitBuffer, tx_reporter: Option<TxReporter>) {
+    /// Enqueue a MAC-RESOURCE for transmission, `repeat_count` times in consecutive frames. 0 and 1 both mean once.
+    /// A PDU that does not fit a SCH/F is fragmented right away: it is queued as a FragBuf whose first fragment fills a SCH/F.
+    pub fn dl_enqueue_tma(&mut self, pdu: MacResource, sdu: BitBuffer, tx_reporter: Option<TxReporter>, repeat_count: u8) {
+        let repeats = repeat_count.saturating_sub(1);
+        let fragmented = pdu.compute_header_len() + sdu.get_len() > SCH_F_CAP;
+        if fragmented && repeats > 0 {
+            tracing::warn!("dl_enqueue_tma: not repeating fragmented resource");
+        }
+        let make_elem = |pdu, sdu, tx_reporter| {
+            if fragmented {
+                DlSchedElem::FragBuf(BsFragger::new_frag_start(pdu, sdu, tx_reporter))
+            } else {
+                DlSchedElem::Resource(pdu, sdu, tx_reporter, repeats)
+            }
+        };
+
         // Get all timeslots on which a relevant MS is listening
         // let timeslots: [u8; NUM_TIMESLOTS] = self.identify_timeslots_for_ssi(pdu.addr);
         tracing::warn!("identify_timeslots_for_ssi not implemented yet, defaulting to ts1");
@@ -442,11 +531,11 @@ impl BsChannelScheduler {
             if next_ts > 0 {
                 // There is another ts for which we need to transmit this message.
                 // Clone the message now and push it to the current ts.
-                let elem = DlSchedElem::Resource(pdu.clone(), sdu.clone(), tx_reporter.clone());
+                let elem = make_elem(pdu.clone(), sdu.clone(), tx_reporter.clone());
                 self.dltx_queues[ts as usize - 1].push(elem);
             } else {
                 // This is the last ts on which we need to transmit this message
-                let elem = DlSchedElem::Resource(pdu, sdu, tx_reporter);
+                let elem = make_elem(pdu, sdu, tx_reporter);
                 self.dltx_queues[ts as usize - 1].push(elem);
                 break;
             }
@@ -495,6 +584,70 @@ impl BsChannelScheduler {
         self.circuits.is_active(dir, ts)
     }
 
+    /// Number of timeslots with a DL or UL circuit
+    pub fn num_active_timeslots(&self) -> u8 {
+        (1..=4)
+            .filter(|&ts| self.circuits.is_active(Direction::Dl, ts) || self.circuits.is_active(Direction::Ul, ts))
+            .count() as u8
+    }
+
+    /// All open circuits, sorted by timeslot with DL before UL
+    pub fn circuit_info(&self) -> Vec<CircuitInfo> {
+        let info = |direction: Direction, circuit: &Circuit| CircuitInfo {
+            ts: circuit.ts,
+            direction,
+            usage: circuit.usage,
+            circuit_mode: circuit.circuit_mode,
+        };
+        (0..4)
+            .flat_map(|i| {
+                let dl = self.circuits.dl[i].as_ref().map(|c| info(Direction::Dl, c));
+                let ul = self.circuits.ul[i].as_ref().map(|c| info(Direction::Ul, c));
+                dl.into_iter().chain(ul)
+            })
+            .collect()
+    }
+
+    /// Timeslot usage bitmaps over the last hyperframe, oldest first
+    pub fn utilization(&self) -> Vec<MultiframeUtilization> {
+        self.utilization.multiframes()
+    }
+
+    /// Number of slot grants queued for transmission on the given timeslot
+    pub fn pending_grant_count(&self, ts: u8) -> usize {
+        self.pending_grant_ssi_list(ts).len()
+    }
+
+    /// SSIs of the slot grants queued for transmission on the given timeslot, in queue order
+    pub fn pending_grant_ssi_list(&self, ts: u8) -> Vec<u32> {
+        self.dltx_queues[ts as usize - 1]
+            .iter()
+            .filter_map(|elem| match elem {
+                DlSchedElem::Grant(addr, _) => Some(addr.ssi),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Number of uplink subslots on the given timeslot reserved for granted MSs, over all scheduled frames
+    pub fn reserved_ul_slots(&self, ts: u8) -> usize {
+        self.ulsched[ts as usize - 1]
+            .iter()
+            .map(|sched| sched.ul1.is_some() as usize + sched.ul2.is_some() as usize)
+            .sum()
+    }
+
+    /// Uplink grant state of all timeslots, for the stack state snapshot
+    pub fn ul_grant_info(&self) -> Vec<UlGrantInfo> {
+        (1..=NUM_TIMESLOTS as u8)
+            .map(|ts| UlGrantInfo {
+                ts,
+                pending_grant_ssis: self.pending_grant_ssi_list(ts),
+                reserved_ul_slots: self.reserved_ul_slots(ts),
+            })
+            .collect()
+    }
+
     pub fn close_circuit(&mut self, dir: Direction, ts: u8) -> Option<Circuit> {
         // Clearing hangtime here is safe: if the circuit is gone, this timeslot is no longer in use.
         if (1..=4).contains(&ts) {
@@ -531,7 +684,7 @@ impl BsChannelScheduler {
 
                     // We have room for a Null PDU
                     let mut null_pdu = MacResource::null_pdu();
-                    null_pdu.length_ind = 2; // Null PDU is 16 bits
+                    null_pdu.length_ind = FieldU6::new(2); // Null PDU is 16 bits
                     let _ = null_pdu.update_len_and_fill_ind(0);
                     null_pdu.to_bitbuf(&mut b.mac_block);
 
@@ -557,7 +710,7 @@ impl BsChannelScheduler {
 
         for index in 0..queue.len() {
             let elem = &mut queue[index];
-            if let DlSchedElem::Resource(pdu, _sdu, _repeat) = elem {
+            if let DlSchedElem::Resource(pdu, _sdu, _reporter, _repeats) = elem {
                 if let Some(pdu_ssi) = pdu.addr {
                     if pdu_ssi.ssi == addr.ssi {
                         // Found a resource for this address
@@ -575,9 +728,9 @@ impl BsChannelScheduler {
         let mut pdu = MacResource {
             fill_bits: false, // updated later
             pos_of_grant: 0,
-            encryption_mode: 0,
+            encryption_mode: FieldU2::new(0),
             random_access_flag: random_access_ack,
-            length_ind: 0, // updated later
+            length_ind: FieldU6::new(0), // updated later
             addr: Some(*addr),
             event_label: None,
             usage_marker: None,
@@ -625,7 +778,7 @@ impl BsChannelScheduler {
                 tracing::warn!("dl_drop_all_except_stolen: discarding scheduled {:?} on ts {}", elem, timeslot);
 
                 match elem {
-                    DlSchedElem::Resource(_, _, tx_reporter) => {
+                    DlSchedElem::Resource(_, _, tx_reporter, _) => {
                         // Report as discarded manually
                         if let Some(tx_reporter) = tx_reporter {
                             tx_reporter.mark_discarded();
@@ -642,7 +795,7 @@ impl BsChannelScheduler {
                         self.pending_ra_acks[timeslot as usize - 1].push(addr.ssi);
                     }
 
-                    DlSchedElem::Grant(..) | DlSchedElem::Broadcast(_) => {
+                    DlSchedElem::Grant(..) | DlSchedElem::NeighborBroadcast(_) | DlSchedElem::NeighborBroadcastRemove(_) => {
                         // Silently dropped as internal or not equipped with a tx_reporter
                     }
                     _ => unreachable!(),
@@ -667,7 +820,7 @@ impl BsChannelScheduler {
             };
             let mac_resource = self.dl_get_scheduled_resource_for_ssi(ts, addr);
             match mac_resource {
-                Some(DlSchedElem::Resource(pdu, _sdu, _repeat)) => {
+                Some(DlSchedElem::Resource(pdu, _sdu, _reporter, _repeats)) => {
                     // Integrate grant into the resource
                     match &elem {
                         DlSchedElem::Grant(_, grant) => {
@@ -711,7 +864,7 @@ impl BsChannelScheduler {
                     };
 
                     // Push new resource into the queue. These do not need a tx_reporter
-                    let dlsched_res = DlSchedElem::Resource(pdu, BitBuffer::new(0), None);
+                    let dlsched_res = DlSchedElem::Resource(pdu, BitBuffer::new(0), None, 0);
                     self.dltx_queues[ts.t as usize - 1].push(dlsched_res);
                 }
                 _ => panic!(),
@@ -721,6 +874,8 @@ impl BsChannelScheduler {
 
     fn dl_build_block_from_signalling_schedule(&mut self, ts: TdmaTime) -> Option<BitBuffer> {
         let mut buf_opt = None;
+        // Resources sent in this block that are to be transmitted again next frame
+        let mut repeats_next_frame = Vec::new();
 
         while !self.dltx_queues[ts.t as usize - 1].is_empty() {
             let opt = self.dl_take_prioritized_sched_item(ts);
@@ -728,19 +883,42 @@ impl BsChannelScheduler {
             match opt {
                 Some(sched_elem) => {
                     match sched_elem {
-                        DlSchedElem::Broadcast(_) => {
-                            unimplemented_log!("finalize_ts_for_tick: Broadcast scheduling not implemented");
+                        DlSchedElem::NeighborBroadcast(pdu) => {
+                            let mut buf = buf_opt.unwrap_or_else(|| BitBuffer::new(SCH_F_CAP));
+                            if let Err(e) = Self::dl_write_mle_broadcast(|sdu| pdu.to_bitbuf(sdu), &mut buf) {
+                                tracing::warn!("dl_build_block_from_signalling_schedule: dropping neighbour broadcast: {:?}", e);
+                            }
+                            buf_opt = Some(buf);
+                        }
+
+                        DlSchedElem::NeighborBroadcastRemove(pdu) => {
+                            let mut buf = buf_opt.unwrap_or_else(|| BitBuffer::new(SCH_F_CAP));
+                            if let Err(e) = Self::dl_write_mle_broadcast(|sdu| pdu.to_bitbuf(sdu), &mut buf) {
+                                tracing::warn!("dl_build_block_from_signalling_schedule: dropping neighbour removal: {:?}", e);
+                            }
+                            buf_opt = Some(buf);
                         }
 
-                        DlSchedElem::Resource(pdu, sdu, tx_reporter) => {
+                        DlSchedElem::Resource(pdu, sdu, tx_reporter, repeats) => {
                             // Allocate bitbuf if not already done
                             let mut buf = buf_opt.unwrap_or_else(|| BitBuffer::new(SCH_F_CAP));
+                            // Keep a copy for the next transmission. Grants are only valid once, so they are not repeated.
+                            let repeat = (repeats > 0).then(|| {
+                                let mut pdu = pdu.clone();
+                                pdu.slot_granting_element = None;
+                                DlSchedElem::Resource(pdu, sdu.clone(), None, repeats - 1)
+                            });
                             // Create fragger, either to send the whole PDU or to start fragmentation
                             let mut fragger = BsFragger::new(pdu, sdu, tx_reporter);
                             if !fragger.get_next_chunk(&mut buf) {
                                 // Fragmentation was started and we have more chunks to send
                                 // Enqueue fragger with remaining data for retrieval next frame
                                 self.dl_enqueue_tma_frag_next_frame(fragger);
+                                if repeat.is_some() {
+                                    tracing::warn!("dl_build_block_from_signalling_schedule: not repeating fragmented resource");
+                                }
+                            } else if let Some(repeat) = repeat {
+                                repeats_next_frame.push(repeat);
                             }
                             buf_opt = Some(buf);
                         }
@@ -785,6 +963,7 @@ impl BsChannelScheduler {
             assert!(a.is_empty(), "queue should be empty");
             std::mem::swap(a, b);
         }
+        self.dltx_queues[ts.t as usize - 1].append(&mut repeats_next_frame);
 
         buf_opt
     }
@@ -838,15 +1017,18 @@ impl BsChannelScheduler {
     /// If none; return first to-be-transmitted resource.
     /// If none, return None.
     pub fn dl_take_prioritized_sched_item(&mut self, ts: TdmaTime) -> Option<DlSchedElem> {
-        if ts.f == 18 {
-            // No resources on frame 18
-            return None;
-        }
-
         // Map 1-based ts to 0-based index, bail on 0 or out of range.
         let slot = ts.t as usize - 1;
         let q = self.dltx_queues.get_mut(slot).unwrap();
 
+        if ts.is_frame18() {
+            // No resources on frame 18, only the neighbour broadcast or its removals
+            let i = q
+                .iter()
+                .position(|e| matches!(e, DlSchedElem::NeighborBroadcast(_) | DlSchedElem::NeighborBroadcastRemove(_)))?;
+            return Some(q.remove(i));
+        }
+
         // Return grants first
         if let Some(i) = q.iter().position(|e| matches!(e, DlSchedElem::Grant(_, _))) {
             return Some(q.remove(i));
@@ -858,13 +1040,43 @@ impl BsChannelScheduler {
         }
 
         // Return Resources last
-        if let Some(i) = q.iter().position(|e| matches!(e, DlSchedElem::Resource(_, _, _))) {
+        if let Some(i) = q.iter().position(|e| matches!(e, DlSchedElem::Resource(..))) {
             return Some(q.remove(i));
         }
 
         None
     }
 
+    /// Write an MLE broadcast PDU into a MAC-RESOURCE addressed to all MSs, carried in LLC BL-UDATA
+    fn dl_write_mle_broadcast(
+        write_pdu: impl FnOnce(&mut BitBuffer) -> Result<(), PduParseErr>,
+        buf: &mut BitBuffer,
+    ) -> Result<(), PduParseErr> {
+        let mut sdu = BitBuffer::new_autoexpand(SCH_F_CAP);
+        BlUdata { has_fcs: false }.to_bitbuf(&mut sdu);
+        sdu.write_bits(MleProtocolDiscriminator::Mle.into_raw(), 3);
+        write_pdu(&mut sdu)?;
+        sdu.seek(0);
+
+        let addr = TetraAddress {
+            ssi: 0xFFFFFF,
+            ssi_type: SsiType::Gssi,
+        };
+        let resource = Self::dl_make_minimal_resource(&addr, None, false);
+        let sdu_len = sdu.get_len();
+        let bit_offset = buf.get_pos();
+        let mut fragger = BsFragger::new(resource, sdu, None);
+        if !fragger.get_next_chunk(buf) {
+            // Frame 18 has no room for continuation fragments
+            return Err(PduParseErr::BufferEnded {
+                field: Some("neighbour_cell_information_for_ca"),
+                bit_offset,
+                bit_length: sdu_len,
+            });
+        }
+        Ok(())
+    }
+
     pub fn tick_start(&mut self, ts: TdmaTime) {
         // Increment current time
         self.cur_dltime = self.cur_dltime.add_timeslots(1);
@@ -881,20 +1093,34 @@ impl BsChannelScheduler {
     /// If the timeslot is not full, generates SYNC SB1/SB2 blocks.
     /// Increments cur_ts by one timeslot.
     /// Caller should check timestamp of returned DlTxElem to prevent desync
-    pub fn finalize_ts_for_tick(&mut self) -> TmvUnitdataReqSlot {
+    /// Returns None if the slot failed validation and should not be transmitted.
+    pub fn finalize_ts_for_tick(&mut self) -> Option<TmvUnitdataReqSlot> {
         // We finalize a FUTURE slot: cur_ts plus some number of timeslots
         let ts = self.cur_dltime.add_timeslots(MACSCHED_TX_AHEAD as i32);
         self.precomps.mac_sync.time = ts;
         self.precomps.mac_sysinfo1.hyperframe_number = Some(ts.h);
         self.precomps.mac_sysinfo2.hyperframe_number = Some(ts.h);
 
-        let dl_circuit_active = self.circuits.is_active(Direction::Dl, ts.t) && ts.f != 18;
-        let ul_circuit_active = self.circuits.is_active(Direction::Ul, ts.t) && ts.f != 18;
+        // Neighbour cells are announced on the MCCH, taking the place of SYNC and SYSINFO once per hyperframe.
+        // After a change, removed cells are withdrawn first and the new broadcast follows on the next frame 18.
+        if ts.t == 1 && ts.is_frame18() {
+            if let Some(removal) = self.nwrk_broadcast_removals.pop_front() {
+                self.dltx_queues[0].push(DlSchedElem::NeighborBroadcastRemove(removal));
+            } else if ts.m == NEIGHBOR_BROADCAST_MULTIFRAME || self.nwrk_broadcast_changed {
+                self.nwrk_broadcast_changed = false;
+                if let Some(pdu) = &self.precomps.mle_nwrk_broadcast {
+                    self.dltx_queues[0].push(DlSchedElem::NeighborBroadcast(pdu.clone()));
+                }
+            }
+        }
+
+        let dl_circuit_active = self.circuits.is_active(Direction::Dl, ts.t) && !ts.is_frame18();
+        let ul_circuit_active = self.circuits.is_active(Direction::Ul, ts.t) && !ts.is_frame18();
 
         // During hangtime we stop sending traffic frames and switch to signalling mode.
         // Keep traffic mode while FACCH/stealing is still queued for delivery.
         let hang_effective = if (2..=4).contains(&ts.t) {
-            self.is_hangtime_effective(ts.t)
+            self.hangtime_effective(ts.t)
         } else {
             false
         };
@@ -904,8 +1130,15 @@ impl BsChannelScheduler {
 
         // Build the block for this timeslot with anything scheduled (traffic or signalling)
         // For traffic timeslots, also check for FACCH/stealing (STCH half-slot)
-        let ul_phy = if ul_is_traffic { PhysicalChannel::Tp } else { PhysicalChannel::Cp };
+        let carrier_id = self.carrier_id;
+        let ul_phy = if ul_is_traffic {
+            PhysicalChannel::Tp { ts: ts.t, carrier_id }
+        } else {
+            PhysicalChannel::Cp { carrier_id }
+        };
 
+        // Signalling mode only uses the DL if something was scheduled
+        let mut dl_used = dl_is_traffic;
         let mut elem = if dl_is_traffic {
             let (tch_buf, stch_opt) = self.dl_build_traffic_block(ts);
 
@@ -931,7 +1164,16 @@ impl BsChannelScheduler {
                         scrambling_code: self.scrambling_code,
                     }),
                     bbk: None,
-                    ul_phy_chan: ul_phy,
+                    // The first half-slot is stolen, the uplink remains a traffic channel
+                    ul_phy_chan: if ul_is_traffic {
+                        PhysicalChannel::TrafficFacch {
+                            ts: ts.t,
+                            subslot: 1,
+                            carrier_id,
+                        }
+                    } else {
+                        ul_phy
+                    },
                 }
             } else {
                 // Normal traffic: full-slot TCH
@@ -954,6 +1196,7 @@ impl BsChannelScheduler {
 
             // Fill our signalling block with scheduled items (if any)
             let buf = self.dl_build_block_from_signalling_schedule(ts);
+            dl_used = buf.is_some();
             if let Some(buf) = buf {
                 TmvUnitdataReqSlot {
                     ts,
@@ -994,9 +1237,9 @@ impl BsChannelScheduler {
             }
         };
 
-        // Sanity check: frame 18 should not carry user blocks
+        // Sanity check: frame 18 should not carry user blocks, only the neighbour broadcast on the MCCH
         if elem.blk1.is_some() {
-            assert!(ts.f != 18, "frame 18 shouldn't have blk1 set");
+            assert!(!ts.is_frame18() || ts.t == 1, "frame 18 shouldn't have blk1 set");
         }
 
         // Construct the BBK block to reflect UL/DL usage
@@ -1066,6 +1309,9 @@ impl BsChannelScheduler {
 
         // Clear UL schedule for this timeslot
         let index = self.ul_ts_to_sched_index(&ts.add_timeslots(-4));
+        let ul_sched = &self.ulsched[ts.t as usize - 1][self.ul_ts_to_sched_index(&ts)];
+        let ul_used = ul_is_traffic || ul_sched.ul1.is_some() || ul_sched.ul2.is_some();
+        self.utilization.record(FrameUtilization::new(ts, dl_used, ul_used));
         self.ulsched[ts.t as usize - 1][index].ul1 = None;
         self.ulsched[ts.t as usize - 1][index].ul2 = None;
 
@@ -1073,11 +1319,15 @@ impl BsChannelScheduler {
         // self.dump_ul_schedule_full(true);
 
         // We now have our bbk, blk1 and (optional) blk2
-        elem
+        if let Err(e) = elem.validate() {
+            tracing::error!("finalize_ts_for_tick: skipping invalid slot {}: {}", elem, e);
+            return None;
+        }
+        Some(elem)
     }
 
     fn generate_bbk_block(&self, ts: TdmaTime) -> TmvUnitdataReq {
-        let (ul_traffic_usage, dl_traffic_usage) = if ts.f == 18 {
+        let (ul_traffic_usage, dl_traffic_usage) = if ts.is_frame18() {
             (None, None)
         } else {
             (
@@ -1088,7 +1338,7 @@ impl BsChannelScheduler {
 
         // Generate BBK block
         let mut aach_bb = BitBuffer::new(14);
-        if ts.f != 18 {
+        if !ts.is_frame18() {
             let mut aach = AccessAssign::default();
 
             match ts.t {
@@ -1179,8 +1429,9 @@ impl BsChannelScheduler {
                     0 => {
                         // Half-slot Null PDU on SCH/HD, SYSINFO gets added later as BNCH blk2
                         let mut buf1 = BitBuffer::new(SCH_HD_CAP);
-                        let blk1 = MacResource::null_pdu();
+                        let (blk1, num_fill_bits) = MacResource::null_pdu_for_channel(LogicalChannel::SchHd);
                         blk1.to_bitbuf(&mut buf1);
+                        fillbits::addition::write(&mut buf1, Some(num_fill_bits));
                         TmvUnitdataReq {
                             logical_channel: LogicalChannel::SchHd,
                             mac_block: buf1,
@@ -1190,8 +1441,9 @@ impl BsChannelScheduler {
                     1 => {
                         // Full-slot Null PDU
                         let mut buf = BitBuffer::new(SCH_F_CAP);
-                        let blk = MacResource::null_pdu();
+                        let (blk, num_fill_bits) = MacResource::null_pdu_for_channel(LogicalChannel::SchF);
                         blk.to_bitbuf(&mut buf);
+                        fillbits::addition::write(&mut buf, Some(num_fill_bits));
                         TmvUnitdataReq {
                             logical_channel: LogicalChannel::SchF,
                             mac_block: buf,
@@ -1274,24 +1526,28 @@ impl BsChannelScheduler {
 }
 
 #[cfg(test)]
-mod tests {
+pub(super) mod tests {
 
     use tetra_core::{
         address::{SsiType, TetraAddress},
         debug::setup_logging_default,
     };
+    use tetra_saps::control::admin::{UTILIZATION_DL, UTILIZATION_UL};
+    use tetra_saps::control::enums::circuit_mode_type::CircuitModeType;
+    use tetra_saps::lcmc::enums::{alloc_type::ChanAllocType, ul_dl_assignment::UlDlAssignment};
 
     use tetra_pdus::{
         mle::{
-            fields::bs_service_details::BsServiceDetails,
+            fields::{bs_service_details::BsServiceDetails, neighbour_cell_information_for_ca::NeighbourCellInformationForCa},
             pdus::{d_mle_sync::DMleSync, d_mle_sysinfo::DMleSysinfo},
         },
         umac::{
             enums::sysinfo_opt_field_flag::SysinfoOptFieldFlag,
             fields::{
-                sysinfo_default_def_for_access_code_a::SysinfoDefaultDefForAccessCodeA, sysinfo_ext_services::SysinfoExtendedServices,
+                channel_allocation::ChanAllocElement, sysinfo_default_def_for_access_code_a::SysinfoDefaultDefForAccessCodeA,
+                sysinfo_ext_services::SysinfoExtendedServices,
             },
-            pdus::{mac_sync::MacSync, mac_sysinfo::MacSysinfo},
+            pdus::{mac_end_dl::MacEndDl, mac_frag_dl::MacFragDl, mac_sync::MacSync, mac_sysinfo::MacSysinfo},
         },
     };
 
@@ -1404,6 +1660,7 @@ mod tests {
             mle_sysinfo: mle_sysinfo_pdu,
             mac_sync: mac_sync_pdu,
             mle_sync: mle_sync_pdu,
+            mle_nwrk_broadcast: None,
         };
 
         let mut sched = BsChannelScheduler::new(1, precomps);
@@ -1507,7 +1764,7 @@ mod tests {
         };
         let pdu = BsChannelScheduler::dl_make_minimal_resource(&addr, None, false);
         let sdu = BitBuffer::new(0);
-        sched.dl_enqueue_tma(pdu, sdu, None);
+        sched.dl_enqueue_tma(pdu, sdu, None, 1);
 
         let grant = BasicSlotgrant {
             capacity_allocation: BasicSlotgrantCapAlloc::FirstSubslotGranted,
@@ -1530,4 +1787,449 @@ mod tests {
 
         assert!(sched.dltx_queues[ts.t as usize - 1].len() == 1);
     }
+
+    #[test]
+    fn test_pending_grants_and_reserved_slots() {
+        let mut sched = get_testing_slotter();
+        let ssis = [1001, 1002, 1003, 1004, 1005];
+        for ssi in ssis {
+            let addr = TetraAddress::new(ssi, SsiType::Issi);
+            let grant = sched.ul_process_cap_req(1, addr, &ReservationRequirement::Req1Slot).unwrap();
+            sched.dl_enqueue_grant(1, addr, grant);
+        }
+
+        assert_eq!(sched.pending_grant_count(1), 5);
+        assert_eq!(sched.pending_grant_ssi_list(1), ssis);
+        assert_eq!(sched.reserved_ul_slots(1), 10, "five full slots of two subslots each");
+        assert_eq!(sched.pending_grant_count(2), 0);
+        assert_eq!(sched.reserved_ul_slots(2), 0);
+
+        let info = sched.ul_grant_info();
+        assert_eq!(info.len(), 4);
+        assert_eq!(info[0].pending_grant_ssis, ssis);
+        assert_eq!(info[0].reserved_ul_slots, 10);
+    }
+
+    #[test]
+    fn test_dl_resource_repeat() {
+        let mut sched = get_testing_slotter();
+        let addr = TetraAddress {
+            ssi_type: SsiType::Gssi,
+            ssi: 91,
+        };
+        let pdu = BsChannelScheduler::dl_make_minimal_resource(&addr, None, false);
+        sched.dl_enqueue_tma(pdu, BitBuffer::from_bitstr("1010101010101010"), None, 3);
+
+        // Collect the frames in which the resource is sent on ts1
+        let mut frames = Vec::new();
+        for _ in 0..4 * 6 {
+            let elem = sched.finalize_ts_for_tick().unwrap();
+            if elem.ts.t == 1 {
+                let mut block = elem.blk1.unwrap().mac_block;
+                block.seek(0);
+                if MacResource::from_bitbuf(&mut block).is_ok_and(|res| res.addr.is_some_and(|a| a.ssi == addr.ssi)) {
+                    frames.push(elem.ts.f);
+                }
+            }
+            let next = sched.cur_dltime.add_timeslots(1);
+            sched.tick_start(next);
+        }
+        assert_eq!(frames.len(), 3, "expected three transmissions, got frames {:?}", frames);
+        assert!(frames.windows(2).all(|w| w[1] == w[0] + 1), "frames {:?} not consecutive", frames);
+    }
+
+    /// Strips the fill bits from a bit string, if present
+    fn strip_fill_bits(bits: &str, fill_bits: bool) -> String {
+        if !fill_bits {
+            return bits.to_string();
+        }
+        let trimmed = bits.trim_end_matches('0');
+        trimmed[..trimmed.len() - 1].to_string()
+    }
+
+    /// Run the scheduler and collect the MAC PDUs sent in full slots on ts1, until a MAC-END or a complete
+    /// MAC-RESOURCE is found. Returns the chunks as (MAC-RESOURCE, or None for MAC-FRAG/MAC-END, TM-SDU bits).
+    fn collect_ts1_chunks(sched: &mut BsChannelScheduler) -> Vec<(Option<MacResource>, String)> {
+        let mut chunks = Vec::new();
+        for _ in 0..4 * 18 * 2 {
+            let slot = sched.finalize_ts_for_tick().unwrap();
+            let next = sched.cur_dltime.add_timeslots(1);
+            sched.tick_start(next);
+
+            let blk1 = slot.blk1.unwrap();
+            if slot.ts.t != 1 || blk1.logical_channel != LogicalChannel::SchF {
+                continue;
+            }
+            let mut block = blk1.mac_block;
+            block.seek(0);
+            match block.peek_bits(3).unwrap() {
+                0b000 | 0b001 => {
+                    let pdu = MacResource::from_bitbuf(&mut block).unwrap();
+                    if pdu.is_null_pdu() {
+                        continue;
+                    }
+                    let rest = block.to_bitstr()[block.get_raw_pos()..].to_string();
+                    if pdu.length_ind.get() == 0b111111 {
+                        chunks.push((Some(pdu), rest));
+                    } else {
+                        // A PDU ending within the last byte of the slot is padded up to the slot end
+                        let pdu_len = pdu.length_ind.get() as usize * 8;
+                        let pdu_len = if pdu_len + 8 > SCH_F_CAP { SCH_F_CAP } else { pdu_len };
+                        let sdu_len = pdu_len - pdu.compute_header_len();
+                        let sdu = strip_fill_bits(&rest[..sdu_len], pdu.fill_bits);
+                        chunks.push((Some(pdu), sdu));
+                        break;
+                    }
+                }
+                0b010 => {
+                    let pdu = MacFragDl::from_bitbuf(&mut block).unwrap();
+                    let rest = block.to_bitstr()[block.get_raw_pos()..].to_string();
+                    chunks.push((None, strip_fill_bits(&rest, pdu.fill_bits)));
+                }
+                0b011 => {
+                    let pdu = MacEndDl::from_bitbuf(&mut block).unwrap();
+                    let hdr_len = block.get_raw_pos();
+                    let rest = block.to_bitstr()[hdr_len..pdu.length_ind as usize * 8].to_string();
+                    chunks.push((None, strip_fill_bits(&rest, pdu.fill_bits)));
+                    break;
+                }
+                _ => {}
+            }
+        }
+        chunks
+    }
+
+    fn test_sdu(len: usize) -> String {
+        (0..len).map(|i| if (i * 7) % 3 == 0 { '1' } else { '0' }).collect()
+    }
+
+    #[test]
+    fn test_dl_enqueue_tma_exact_fit() {
+        let mut sched = get_testing_slotter();
+        let addr = TetraAddress::new(1234, SsiType::Issi);
+        let pdu = BsChannelScheduler::dl_make_minimal_resource(&addr, None, false);
+        let sdu = test_sdu(SCH_F_CAP - pdu.compute_header_len());
+        sched.dl_enqueue_tma(pdu, BitBuffer::from_bitstr(&sdu), None, 1);
+        assert!(matches!(sched.dltx_queues[0][..], [DlSchedElem::Resource(..)]));
+
+        let chunks = collect_ts1_chunks(&mut sched);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].0.as_ref().unwrap().length_ind.get(), (SCH_F_CAP / 8) as u8);
+        assert_eq!(chunks[0].1, sdu);
+    }
+
+    #[test]
+    fn test_dl_enqueue_tma_fragmentation() {
+        let addr = TetraAddress::new(1234, SsiType::Issi);
+        let hdr_len = BsChannelScheduler::dl_make_minimal_resource(&addr, None, false).compute_header_len();
+        let first_frag_len = SCH_F_CAP - hdr_len;
+
+        // SDU length, number of MAC-FRAGs following the MAC-RESOURCE
+        for (sdu_len, num_frags) in [(first_frag_len + 1, 0), (268, 0), (269, 0), (536, 1)] {
+            let mut sched = get_testing_slotter();
+            let pdu = BsChannelScheduler::dl_make_minimal_resource(&addr, None, false);
+            let sdu = test_sdu(sdu_len);
+            sched.dl_enqueue_tma(pdu, BitBuffer::from_bitstr(&sdu), None, 1);
+            assert!(
+                matches!(sched.dltx_queues[0][..], [DlSchedElem::FragBuf(_)]),
+                "SDU of {} bits not fragmented upon enqueueing",
+                sdu_len
+            );
+
+            let chunks = collect_ts1_chunks(&mut sched);
+            assert_eq!(chunks.len(), num_frags + 2, "SDU of {} bits", sdu_len);
+            // The MAC-RESOURCE starts fragmentation and fills the whole SCH/F
+            assert_eq!(chunks[0].0.as_ref().unwrap().length_ind.get(), 0b111111);
+            assert_eq!(chunks[0].1.len(), first_frag_len);
+            assert!(chunks[1..].iter().all(|(resource, _)| resource.is_none()));
+            let reassembled: String = chunks.into_iter().map(|(_, bits)| bits).collect();
+            assert_eq!(reassembled, sdu, "SDU of {} bits", sdu_len);
+        }
+    }
+
+    #[test]
+    fn test_dl_fragment_pdu_sequence() {
+        let mut sched = get_testing_slotter();
+        let addr = TetraAddress::new(1234, SsiType::Issi);
+        let pdu = BsChannelScheduler::dl_make_minimal_resource(&addr, None, false);
+        sched.dl_enqueue_tma(pdu, BitBuffer::from_bitstr(&test_sdu(900)), None, 1);
+
+        // MAC PDU type and subtype of each SCH/F block sent on ts1, until the MAC-END
+        let mut types = Vec::new();
+        for _ in 0..4 * 18 * 2 {
+            let slot = sched.finalize_ts_for_tick().unwrap();
+            let next = sched.cur_dltime.add_timeslots(1);
+            sched.tick_start(next);
+
+            let blk1 = slot.blk1.unwrap();
+            if slot.ts.t != 1 || blk1.logical_channel != LogicalChannel::SchF {
+                continue;
+            }
+            let mut block = blk1.mac_block;
+            block.seek(0);
+            let pdu_type = block.peek_bits(3).unwrap();
+            if pdu_type == 0b000 && MacResource::from_bitbuf(&mut block).unwrap().is_null_pdu() {
+                continue;
+            }
+            types.push(pdu_type);
+            if pdu_type == 0b011 {
+                break;
+            }
+        }
+
+        // MAC-RESOURCE, then MAC-FRAGs for the middle fragments, then MAC-END
+        assert_eq!(types, vec![0b000, 0b010, 0b010, 0b011]);
+    }
+
+    #[test]
+    fn test_dl_resource_multi_timeslot_chan_alloc() {
+        let mut sched = get_testing_slotter();
+        let addr = TetraAddress::new(1234, SsiType::Issi);
+        let mut pdu = BsChannelScheduler::dl_make_minimal_resource(&addr, None, false);
+        let chan_alloc = ChanAllocElement {
+            alloc_type: ChanAllocType::Replace,
+            ts_assigned: [false, true, true, true],
+            ul_dl_assigned: UlDlAssignment::Both,
+            clch_permission: true,
+            cell_change_flag: false,
+            carrier_num: 1528,
+            ext: None,
+            mon_pattern: 0,
+            frame18_mon_pattern: Some(0),
+        };
+        pdu.chan_alloc_element = Some(chan_alloc.clone());
+        let sdu = test_sdu(SCH_F_CAP);
+        sched.dl_enqueue_tma(pdu, BitBuffer::from_bitstr(&sdu), None, 1);
+
+        // The assignment of all three timeslots travels in the first fragment's MAC-RESOURCE
+        let chunks = collect_ts1_chunks(&mut sched);
+        let resource = chunks[0].0.as_ref().unwrap();
+        let decoded = resource.chan_alloc_element.as_ref().unwrap();
+        assert_eq!(decoded.ts_assigned, chan_alloc.ts_assigned);
+        assert_eq!(decoded.carrier_num, chan_alloc.carrier_num);
+        assert_eq!(chunks[0].1.len(), SCH_F_CAP - resource.compute_header_len());
+        let reassembled: String = chunks.into_iter().map(|(_, bits)| bits).collect();
+        assert_eq!(reassembled, sdu);
+    }
+
+    #[test]
+    fn test_facch_stealing_phy_chan() {
+        let mut sched = get_testing_slotter();
+        let ts = sched.cur_dltime.add_timeslots(MACSCHED_TX_AHEAD as i32).t;
+        for dir in [Direction::Dl, Direction::Ul] {
+            sched.create_circuit(
+                dir,
+                Circuit {
+                    direction: dir,
+                    ts,
+                    usage: 4,
+                    circuit_mode: CircuitModeType::TchS,
+                    speech_service: Some(0),
+                    etee_encrypted: false,
+                },
+            );
+        }
+
+        // A queued stealing block marks the slot as traffic with the first half-slot stolen
+        sched.dl_enqueue_stealing(ts, BitBuffer::new(124), None);
+        let elem = sched.finalize_ts_for_tick().unwrap();
+        assert_eq!(elem.ts.t, ts);
+        assert_eq!(
+            elem.ul_phy_chan,
+            PhysicalChannel::TrafficFacch {
+                ts,
+                subslot: 1,
+                carrier_id: MAIN_CARRIER_ID
+            }
+        );
+        assert!(elem.ul_phy_chan.is_traffic());
+        assert_eq!(elem.blk1.unwrap().logical_channel, LogicalChannel::Stch);
+
+        // One TDMA frame later, without stealing, the same timeslot is plain traffic
+        for _ in 0..4 {
+            let next = sched.cur_dltime.add_timeslots(1);
+            sched.tick_start(next);
+        }
+        let elem = sched.finalize_ts_for_tick().unwrap();
+        assert_eq!(elem.ts.t, ts);
+        assert_eq!(elem.ul_phy_chan, PhysicalChannel::tp(ts));
+        assert_eq!(elem.blk1.unwrap().logical_channel, LogicalChannel::TchS);
+    }
+
+    #[test]
+    fn test_hangtime_guard_window() {
+        let mut sched = get_testing_slotter();
+        // Move to the frame before traffic timeslot 2 is finalized
+        while sched.cur_dltime.add_timeslots(MACSCHED_TX_AHEAD as i32).t != 2 {
+            let next = sched.cur_dltime.add_timeslots(1);
+            sched.tick_start(next);
+        }
+        let ts = 2;
+        for dir in [Direction::Dl, Direction::Ul] {
+            sched.create_circuit(
+                dir,
+                Circuit {
+                    direction: dir,
+                    ts,
+                    usage: 4,
+                    circuit_mode: CircuitModeType::TchS,
+                    speech_service: Some(0),
+                    etee_encrypted: false,
+                },
+            );
+        }
+        assert!(!sched.hangtime_active(ts));
+        assert!(!sched.hangtime_effective(ts));
+
+        // Entering hangtime with D-TX CEASED still queued for FACCH keeps the slot in traffic mode
+        sched.dl_enqueue_stealing(ts, BitBuffer::new(124), None);
+        sched.set_hangtime(ts, true);
+        assert!(sched.hangtime_active(ts));
+        assert!(!sched.hangtime_effective(ts));
+        let elem = sched.finalize_ts_for_tick().unwrap();
+        assert_eq!(elem.blk1.unwrap().logical_channel, LogicalChannel::Stch);
+
+        // Once the stealing block is sent, hangtime is effective and the slot carries signalling
+        assert!(sched.hangtime_active(ts));
+        assert!(sched.hangtime_effective(ts));
+        for _ in 0..4 {
+            let next = sched.cur_dltime.add_timeslots(1);
+            sched.tick_start(next);
+        }
+        let elem = sched.finalize_ts_for_tick().unwrap();
+        assert_eq!(elem.ts.t, ts);
+        assert_eq!(elem.blk1.unwrap().logical_channel, LogicalChannel::SchF);
+
+        sched.set_hangtime(ts, false);
+        assert!(!sched.hangtime_active(ts));
+        assert!(!sched.hangtime_effective(ts));
+    }
+
+    #[test]
+    fn test_carrier_id_in_phy_chan() {
+        let mut sched = get_testing_slotter();
+        sched.carrier_id = 2;
+        let ts = sched.cur_dltime.add_timeslots(MACSCHED_TX_AHEAD as i32).t;
+        sched.create_circuit(
+            Direction::Ul,
+            Circuit {
+                direction: Direction::Ul,
+                ts,
+                usage: 4,
+                circuit_mode: CircuitModeType::TchS,
+                speech_service: Some(0),
+                etee_encrypted: false,
+            },
+        );
+
+        // Traffic and control slots both carry the scheduler's carrier
+        for expected_ts in [Some(ts), None, None, None] {
+            let elem = sched.finalize_ts_for_tick().unwrap();
+            assert_eq!(elem.ul_phy_chan.carrier_id(), Some(2));
+            assert_eq!(elem.ul_phy_chan.ts(), expected_ts);
+            let next = sched.cur_dltime.add_timeslots(1);
+            sched.tick_start(next);
+        }
+    }
+
+    #[test]
+    fn test_utilization() {
+        let mut sched = get_testing_slotter();
+        let tick = |sched: &mut BsChannelScheduler| {
+            let next = sched.cur_dltime.add_timeslots(1);
+            sched.tick_start(next);
+            sched.finalize_ts_for_tick().unwrap()
+        };
+
+        // Skip to the start of the next frame so that a full frame is recorded
+        while sched.cur_dltime.add_timeslots(MACSCHED_TX_AHEAD as i32).t != 4 {
+            tick(&mut sched);
+        }
+        let start = sched.cur_dltime.add_timeslots(MACSCHED_TX_AHEAD as i32 + 1);
+        assert_eq!(start.t, 1);
+
+        // DL traffic on ts 2, an UL grant on ts 3, ts 1 and 4 idle
+        sched.create_circuit(
+            Direction::Dl,
+            Circuit {
+                direction: Direction::Dl,
+                ts: 2,
+                usage: 4,
+                circuit_mode: CircuitModeType::TchS,
+                speech_service: Some(0),
+                etee_encrypted: false,
+            },
+        );
+        sched.ul_reserve_grant(1001, vec![start.add_timeslots(2)], false);
+        for _ in 0..4 {
+            tick(&mut sched);
+        }
+
+        let utilization = sched.utilization();
+        let last = utilization.last().unwrap();
+        assert_eq!(last.multiframe, start.multiframe_number());
+        assert_eq!(last.bitmap[1..4], [UTILIZATION_DL, UTILIZATION_UL, 0]);
+    }
+
+    #[test]
+    fn test_neighbor_broadcast_on_frame_18() {
+        let mut sched = get_testing_slotter();
+        let neighbours: Vec<_> = [1522, 1530]
+            .into_iter()
+            .enumerate()
+            .map(|(i, carrier)| NeighbourCellInformationForCa {
+                cell_identifier_ca: i as u8 + 1,
+                cell_reselection_types_supported: 0,
+                neighbour_cell_synchronized: false,
+                cell_load_ca: 0,
+                main_carrier_number: carrier,
+                main_carrier_number_extension: None,
+                mcc: Some(204),
+                mnc: Some(1337),
+                location_area: None,
+                maximum_ms_transmit_power: None,
+                minimum_rx_access_level: None,
+                subscriber_class: None,
+                bs_service_details: None,
+                timeshare_cell_information_or_security_parameters: None,
+                tdma_frame_offset: None,
+            })
+            .collect();
+        sched.precomps.mle_nwrk_broadcast = Some(DNwrkBroadcast {
+            cell_re_select_parameters: 0,
+            cell_load_ca: 0,
+            tetra_network_time: None,
+            number_of_ca_neighbour_cells: Some(2),
+            neighbour_cell_information_for_ca: neighbours,
+        });
+
+        // Run through the first three multiframes and collect all full-slot blocks sent in frame 18
+        let mut frame_18_blocks = Vec::new();
+        for _ in 0..3 * 18 * 4 {
+            let next = sched.cur_dltime.add_timeslots(1);
+            sched.tick_start(next);
+            let slot = sched.finalize_ts_for_tick().unwrap();
+            let blk1 = slot.blk1.unwrap();
+            if slot.ts.is_frame18() && blk1.logical_channel == LogicalChannel::SchF {
+                assert!(slot.blk2.is_none());
+                frame_18_blocks.push((slot.ts, blk1.mac_block));
+            }
+        }
+        assert_eq!(frame_18_blocks.len(), 1);
+        let (ts, mut block) = frame_18_blocks.pop().unwrap();
+        assert_eq!((ts.m, ts.f, ts.t), (NEIGHBOR_BROADCAST_MULTIFRAME, 18, 1));
+
+        // MAC-RESOURCE to all MSs, carrying LLC BL-UDATA and the MLE D-NWRK-BROADCAST
+        let resource = MacResource::from_bitbuf(&mut block).unwrap();
+        assert_eq!(resource.addr.unwrap().ssi, 0xFFFFFF);
+        BlUdata::from_bitbuf(&mut block).unwrap();
+        assert_eq!(block.read_field(3, "pd").unwrap(), MleProtocolDiscriminator::Mle.into_raw());
+        let pdu = DNwrkBroadcast::from_bitbuf(&mut block).unwrap();
+        let carriers: Vec<_> = pdu
+            .neighbour_cell_information_for_ca
+            .iter()
+            .map(|c| c.main_carrier_number)
+            .collect();
+        assert_eq!(carriers, vec![1522, 1530]);
+    }
 }
@@ -0,0 +1,145 @@
+//! Unit tests for uplink grant allocation in the BsChannelScheduler
+
+use tetra_core::{PhyBlockNum, SsiType, TdmaTime, TetraAddress};
+use tetra_pdus::umac::enums::{
+    basic_slotgrant_cap_alloc::BasicSlotgrantCapAlloc, basic_slotgrant_granting_delay::BasicSlotgrantGrantingDelay,
+    reservation_requirement::ReservationRequirement,
+};
+
+use super::bs_sched::{BsChannelScheduler, MACSCHED_NUM_FRAMES, tests::get_testing_slotter};
+
+fn addr(ssi: u32) -> TetraAddress {
+    TetraAddress {
+        ssi_type: SsiType::Issi,
+        ssi,
+    }
+}
+
+/// All uplink opportunities on timeslot `t` the scheduler considers, excluding mandatory CLCH slots
+fn candidate_opportunities(sched: &BsChannelScheduler, t: u8) -> Vec<TdmaTime> {
+    let first = sched.cur_dltime.forward_to_timeslot(t);
+    (0..MACSCHED_NUM_FRAMES - 1)
+        .map(|dist| first.add_timeslots(dist as i32 * 4))
+        .filter(|ts| !ts.is_mandatory_clch())
+        .collect()
+}
+
+#[test]
+fn test_fullslot_grant_on_empty_schedule() {
+    let mut sched = get_testing_slotter();
+    let first = candidate_opportunities(&sched, 1)[0];
+
+    let grant = sched.ul_process_cap_req(1, addr(1001), &ReservationRequirement::Req1Slot).unwrap();
+    assert_eq!(grant.capacity_allocation, BasicSlotgrantCapAlloc::Grant1Slot);
+    assert_eq!(grant.granting_delay, BasicSlotgrantGrantingDelay::CapAllocAtNextOpportunity);
+    assert_eq!(sched.ul_get_slot_owner(first, PhyBlockNum::Both), Some(1001));
+}
+
+#[test]
+fn test_halfslot_grant_with_first_subslot_occupied() {
+    let mut sched = get_testing_slotter();
+    let first = candidate_opportunities(&sched, 1)[0];
+    assert_eq!(sched.ul_reserve_grant(1001, vec![first], true), 1);
+
+    let grant = sched
+        .ul_process_cap_req(1, addr(1002), &ReservationRequirement::Req1Subslot)
+        .unwrap();
+    assert_eq!(grant.capacity_allocation, BasicSlotgrantCapAlloc::SecondSubslotGranted);
+    assert_eq!(grant.granting_delay, BasicSlotgrantGrantingDelay::CapAllocAtNextOpportunity);
+    assert_eq!(sched.ul_get_slot_owner(first, PhyBlockNum::Block1), Some(1001));
+    assert_eq!(sched.ul_get_slot_owner(first, PhyBlockNum::Block2), Some(1002));
+}
+
+#[test]
+fn test_grant_on_fully_occupied_schedule() {
+    let mut sched = get_testing_slotter();
+    let candidates = candidate_opportunities(&sched, 1);
+
+    // Every opportunity in the window can be granted exactly once
+    for (i, ts) in candidates.iter().enumerate() {
+        let grant = sched.ul_process_cap_req(1, addr(1000 + i as u32), &ReservationRequirement::Req1Slot);
+        assert!(grant.is_some(), "no grant for opportunity {} at {}", i, ts);
+        assert_eq!(sched.ul_get_slot_owner(*ts, PhyBlockNum::Both), Some(1000 + i as u32));
+    }
+
+    assert!(sched.ul_find_grant_opportunity(1, 1, false).is_none());
+    assert!(sched.ul_process_cap_req(1, addr(2000), &ReservationRequirement::Req1Slot).is_none());
+    assert!(
+        sched
+            .ul_process_cap_req(1, addr(2000), &ReservationRequirement::Req1Subslot)
+            .is_none()
+    );
+
+    // Other timeslots are unaffected
+    assert!(sched.ul_process_cap_req(2, addr(2000), &ReservationRequirement::Req1Slot).is_some());
+}
+
+#[test]
+fn test_grant_across_multiframe_wrap() {
+    let mut sched = get_testing_slotter();
+    // On multiframe 1, timeslot 2 of frame 18 carries the mandatory CLCH and is no opportunity
+    sched.set_dl_time(TdmaTime { h: 0, m: 1, f: 17, t: 1 });
+
+    let (skips, slots) = sched.ul_find_grant_opportunity(2, 4, false).unwrap();
+    assert_eq!(skips, 0);
+    let expected = vec![
+        TdmaTime { h: 0, m: 1, f: 17, t: 2 },
+        TdmaTime { h: 0, m: 2, f: 1, t: 2 },
+        TdmaTime { h: 0, m: 2, f: 2, t: 2 },
+        TdmaTime { h: 0, m: 2, f: 3, t: 2 },
+    ];
+    assert_eq!(slots, expected);
+
+    let grant = sched.ul_process_cap_req(2, addr(1001), &ReservationRequirement::Req4Slots).unwrap();
+    assert_eq!(grant.capacity_allocation, BasicSlotgrantCapAlloc::Grant4Slots);
+    assert_eq!(grant.granting_delay, BasicSlotgrantGrantingDelay::CapAllocAtNextOpportunity);
+    for ts in expected {
+        assert_eq!(sched.ul_get_slot_owner(ts, PhyBlockNum::Both), Some(1001));
+    }
+    assert_eq!(
+        sched.ul_get_slot_owner(TdmaTime { h: 0, m: 1, f: 18, t: 2 }, PhyBlockNum::Block1),
+        None
+    );
+}
+
+#[test]
+fn test_two_halfslot_requests_share_one_slot() {
+    let mut sched = get_testing_slotter();
+    let first = candidate_opportunities(&sched, 3)[0];
+
+    let grant1 = sched
+        .ul_process_cap_req(3, addr(1001), &ReservationRequirement::Req1Subslot)
+        .unwrap();
+    let grant2 = sched
+        .ul_process_cap_req(3, addr(1002), &ReservationRequirement::Req1Subslot)
+        .unwrap();
+    assert_eq!(grant1.capacity_allocation, BasicSlotgrantCapAlloc::FirstSubslotGranted);
+    assert_eq!(grant2.capacity_allocation, BasicSlotgrantCapAlloc::SecondSubslotGranted);
+    assert_eq!(grant1.granting_delay, BasicSlotgrantGrantingDelay::CapAllocAtNextOpportunity);
+    assert_eq!(grant2.granting_delay, BasicSlotgrantGrantingDelay::CapAllocAtNextOpportunity);
+    assert_eq!(sched.ul_get_slot_owner(first, PhyBlockNum::Block1), Some(1001));
+    assert_eq!(sched.ul_get_slot_owner(first, PhyBlockNum::Block2), Some(1002));
+
+    // A third requester has to wait for the next frame
+    let grant3 = sched
+        .ul_process_cap_req(3, addr(1003), &ReservationRequirement::Req1Subslot)
+        .unwrap();
+    assert_eq!(grant3.capacity_allocation, BasicSlotgrantCapAlloc::FirstSubslotGranted);
+    assert_eq!(grant3.granting_delay, BasicSlotgrantGrantingDelay::DelayNOpportunities(1));
+}
+
+#[test]
+fn test_grant_after_set_dl_time_reset() {
+    let mut sched = get_testing_slotter();
+    let first = candidate_opportunities(&sched, 1)[0];
+    sched.ul_process_cap_req(1, addr(1001), &ReservationRequirement::Req2Slots).unwrap();
+    let grant = sched.ul_process_cap_req(1, addr(1002), &ReservationRequirement::Req1Slot).unwrap();
+    assert_eq!(grant.granting_delay, BasicSlotgrantGrantingDelay::DelayNOpportunities(2));
+
+    // Resetting the time wipes the schedule, so the first opportunity is free again
+    sched.set_dl_time(sched.cur_dltime);
+    assert_eq!(sched.ul_get_slot_owner(first, PhyBlockNum::Both), None);
+    let grant = sched.ul_process_cap_req(1, addr(1003), &ReservationRequirement::Req1Slot).unwrap();
+    assert_eq!(grant.granting_delay, BasicSlotgrantGrantingDelay::CapAllocAtNextOpportunity);
+    assert_eq!(sched.ul_get_slot_owner(first, PhyBlockNum::Both), Some(1003));
+}
@@ -0,0 +1,138 @@
+use std::collections::VecDeque;
+
+/// Number of most recent UL traffic frames the quality indicator is computed over
+pub const CALL_QUALITY_WINDOW: usize = 100;
+
+/// Approximate MOS per frame erasure percentage, derived from the ITU-T G.107 E-model
+/// (R0 = 93.2, Ie = 15, Bpl = 10, random loss). Values in between are linearly interpolated.
+const MOS_TABLE: [(f32, f32); 14] = [
+    (0.0, 3.95),
+    (1.0, 3.64),
+    (2.0, 3.35),
+    (3.0, 3.09),
+    (5.0, 2.66),
+    (7.0, 2.33),
+    (10.0, 1.98),
+    (15.0, 1.62),
+    (20.0, 1.41),
+    (30.0, 1.20),
+    (40.0, 1.11),
+    (50.0, 1.06),
+    (75.0, 1.01),
+    (100.0, 1.00),
+];
+
+/// RF link quality of an active call, as seen on the uplink traffic channel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CallQualityIndicator {
+    /// Fraction of frames in the window that passed CRC, 0.0 - 1.0
+    pub crc_pass_rate: f32,
+    /// Number of frames in the window
+    pub frame_count: u32,
+    /// Approximate mean opinion score, 1.0 - 5.0
+    pub mos_estimate: f32,
+}
+
+/// Tracks the CRC results of the last CALL_QUALITY_WINDOW UL traffic frames on a timeslot
+#[derive(Debug, Default)]
+pub struct CallQualityTracker {
+    window: VecDeque<bool>,
+}
+
+impl CallQualityTracker {
+    pub fn new() -> Self {
+        Self {
+            window: VecDeque::with_capacity(CALL_QUALITY_WINDOW),
+        }
+    }
+
+    pub fn record(&mut self, crc_pass: bool) {
+        if self.window.len() == CALL_QUALITY_WINDOW {
+            self.window.pop_front();
+        }
+        self.window.push_back(crc_pass);
+    }
+
+    pub fn reset(&mut self) {
+        self.window.clear();
+    }
+
+    /// Returns the quality indicator, or None if no frames were received yet
+    pub fn indicator(&self) -> Option<CallQualityIndicator> {
+        if self.window.is_empty() {
+            return None;
+        }
+        let passed = self.window.iter().filter(|&&crc_pass| crc_pass).count();
+        let crc_pass_rate = passed as f32 / self.window.len() as f32;
+        Some(CallQualityIndicator {
+            crc_pass_rate,
+            frame_count: self.window.len() as u32,
+            mos_estimate: mos_from_crc_pass_rate(crc_pass_rate),
+        })
+    }
+}
+
+/// Maps a CRC pass rate (0.0 - 1.0) to an approximate MOS using MOS_TABLE
+pub fn mos_from_crc_pass_rate(crc_pass_rate: f32) -> f32 {
+    let loss_percent = (1.0 - crc_pass_rate.clamp(0.0, 1.0)) * 100.0;
+    for pair in MOS_TABLE.windows(2) {
+        let (loss_lo, mos_lo) = pair[0];
+        let (loss_hi, mos_hi) = pair[1];
+        if loss_percent <= loss_hi {
+            let frac = (loss_percent - loss_lo) / (loss_hi - loss_lo);
+            return mos_lo + (mos_hi - mos_lo) * frac;
+        }
+    }
+    MOS_TABLE[MOS_TABLE.len() - 1].1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mos_boundaries() {
+        assert!((mos_from_crc_pass_rate(1.0) - 3.95).abs() < 0.001);
+        assert!((mos_from_crc_pass_rate(0.0) - 1.0).abs() < 0.001);
+        let mos_half = mos_from_crc_pass_rate(0.5);
+        assert!((mos_half - 1.06).abs() < 0.001);
+        for rate in [0.0, 0.25, 0.5, 0.9, 0.99, 1.0] {
+            let mos = mos_from_crc_pass_rate(rate);
+            assert!((1.0..=5.0).contains(&mos), "MOS {} out of range for rate {}", mos, rate);
+        }
+    }
+
+    #[test]
+    fn test_mos_decreases_with_loss() {
+        let mut prev = mos_from_crc_pass_rate(1.0);
+        for i in 1..=100 {
+            let mos = mos_from_crc_pass_rate(1.0 - i as f32 / 100.0);
+            assert!(mos <= prev, "MOS not monotonic at {}% loss", i);
+            prev = mos;
+        }
+    }
+
+    #[test]
+    fn test_tracker_sliding_window() {
+        let mut tracker = CallQualityTracker::new();
+        assert!(tracker.indicator().is_none());
+
+        for _ in 0..CALL_QUALITY_WINDOW {
+            tracker.record(false);
+        }
+        let ind = tracker.indicator().unwrap();
+        assert_eq!(ind.frame_count, CALL_QUALITY_WINDOW as u32);
+        assert_eq!(ind.crc_pass_rate, 0.0);
+
+        // Half the window replaced by passing frames
+        for _ in 0..CALL_QUALITY_WINDOW / 2 {
+            tracker.record(true);
+        }
+        let ind = tracker.indicator().unwrap();
+        assert_eq!(ind.frame_count, CALL_QUALITY_WINDOW as u32);
+        assert_eq!(ind.crc_pass_rate, 0.5);
+
+        tracker.reset();
+        assert!(tracker.indicator().is_none());
+    }
+}
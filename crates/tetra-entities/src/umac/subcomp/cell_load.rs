@@ -0,0 +1,66 @@
+/// Cell load CA value meaning no cell load information is available
+pub const CELL_LOAD_UNAVAILABLE: u8 = 0;
+/// Cell load CA value for low load
+pub const CELL_LOAD_LOW: u8 = 1;
+/// Cell load CA value for medium load
+pub const CELL_LOAD_MEDIUM: u8 = 2;
+/// Cell load CA value for high load
+pub const CELL_LOAD_HIGH: u8 = 3;
+
+/// Estimates the cell load from the number of timeslots carrying a circuit, for the 2-bit
+/// Cell load CA element in D-MLE-SYNC (clause 18.5.3). MSs use it for cell reselection.
+#[derive(Debug)]
+pub struct CellLoadEstimator {
+    /// Number of timeslots on the carrier
+    max_timeslots: u8,
+    /// Number of timeslots with an active DL or UL circuit
+    active_timeslots: u8,
+}
+
+impl CellLoadEstimator {
+    pub fn new(max_timeslots: u8) -> Self {
+        Self {
+            max_timeslots,
+            active_timeslots: 0,
+        }
+    }
+
+    pub fn update(&mut self, active_timeslots: u8) {
+        self.active_timeslots = active_timeslots.min(self.max_timeslots);
+    }
+
+    /// Load as the Cell load CA value: low below a third of the timeslots in use, high from two thirds on
+    pub fn load_indicator(&self) -> u8 {
+        let active = self.active_timeslots as u32 * 3;
+        let max = self.max_timeslots as u32;
+        if max == 0 {
+            CELL_LOAD_UNAVAILABLE
+        } else if active < max {
+            CELL_LOAD_LOW
+        } else if active < 2 * max {
+            CELL_LOAD_MEDIUM
+        } else {
+            CELL_LOAD_HIGH
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_indicator() {
+        let mut estimator = CellLoadEstimator::new(4);
+        let expected = [CELL_LOAD_LOW, CELL_LOAD_LOW, CELL_LOAD_MEDIUM, CELL_LOAD_HIGH, CELL_LOAD_HIGH];
+        for (active, expected) in expected.into_iter().enumerate() {
+            estimator.update(active as u8);
+            assert_eq!(estimator.load_indicator(), expected, "{} active", active);
+        }
+
+        // More active timeslots than exist is clamped
+        estimator.update(9);
+        assert_eq!(estimator.load_indicator(), CELL_LOAD_HIGH);
+        assert_eq!(CellLoadEstimator::new(0).load_indicator(), CELL_LOAD_UNAVAILABLE);
+    }
+}
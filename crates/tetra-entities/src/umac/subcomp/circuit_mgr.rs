@@ -20,14 +20,17 @@ impl CircuitMgr {
         }
     }
 
+    /// For Both, both directions must be active. None is never active.
     pub fn is_active(&self, dir: Direction, ts: u8) -> bool {
         match dir {
             Direction::Dl => self.dl[ts as usize - 1].is_some(),
             Direction::Ul => self.ul[ts as usize - 1].is_some(),
-            _ => panic!("can only use with specific ul/dl direction"),
+            Direction::Both => self.dl[ts as usize - 1].is_some() && self.ul[ts as usize - 1].is_some(),
+            Direction::None => false,
         }
     }
 
+    /// For Both, returns the usage only if the DL and UL circuits share it
     pub fn get_usage(&self, dir: Direction, ts: u8) -> Option<u8> {
         match dir {
             Direction::Dl => {
@@ -44,11 +47,20 @@ impl CircuitMgr {
                     None
                 }
             }
-            _ => panic!("can only use with specific ul/dl direction"),
+            Direction::Both => {
+                let dl_usage = self.get_usage(Direction::Dl, ts);
+                if dl_usage == self.get_usage(Direction::Ul, ts) {
+                    dl_usage
+                } else {
+                    None
+                }
+            }
+            Direction::None => None,
         }
     }
 
     /// Closes an active circuit, and return the Circuit to the caller
+    /// Both closes the DL and UL circuits, returning the DL one if present
     pub fn close_circuit(&mut self, dir: Direction, ts: u8) -> Option<Circuit> {
         match dir {
             Direction::Dl => {
@@ -56,14 +68,44 @@ impl CircuitMgr {
                 self.dl[ts as usize - 1].take()
             }
             Direction::Ul => self.ul[ts as usize - 1].take(),
-            _ => panic!("can only use with specific ul/dl direction"),
+            Direction::Both => {
+                let dl = self.close_circuit(Direction::Dl, ts);
+                let ul = self.close_circuit(Direction::Ul, ts);
+                dl.or(ul)
+            }
+            Direction::None => None,
         }
     }
 
     /// Creates a new circuit on the given direction and timeslot
     /// This channel should be free, if not, warnings will be issued and the existing circuit will be closed first
+    /// Both creates a DL and a UL circuit with the same parameters
     pub fn create_circuit(&mut self, dir: Direction, circuit: Circuit) {
         let ts = circuit.ts;
+        match dir {
+            Direction::Both => {
+                self.create_circuit(
+                    Direction::Dl,
+                    Circuit {
+                        direction: Direction::Dl,
+                        ..circuit.clone()
+                    },
+                );
+                self.create_circuit(
+                    Direction::Ul,
+                    Circuit {
+                        direction: Direction::Ul,
+                        ..circuit
+                    },
+                );
+                return;
+            }
+            Direction::None => {
+                tracing::warn!("CircuitMgr::create with Direction::None on ts {}, ignoring", ts);
+                return;
+            }
+            Direction::Dl | Direction::Ul => {}
+        }
 
         // Sanity check
         if self.is_active(dir, ts) {
@@ -80,7 +122,7 @@ impl CircuitMgr {
                 self.dl[ts as usize - 1] = Some(circuit);
             }
             Direction::Ul => self.ul[ts as usize - 1] = Some(circuit),
-            _ => panic!("can only use with specific ul/dl direction"),
+            Direction::Both | Direction::None => unreachable!(),
         }
     }
 
@@ -102,3 +144,50 @@ impl CircuitMgr {
         self.tx_data[ts as usize - 1].pop_front()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tetra_saps::control::enums::circuit_mode_type::CircuitModeType;
+
+    fn circuit(ts: u8, usage: u8) -> Circuit {
+        Circuit {
+            direction: Direction::Both,
+            ts,
+            usage,
+            circuit_mode: CircuitModeType::TchS,
+            speech_service: Some(0),
+            etee_encrypted: false,
+        }
+    }
+
+    #[test]
+    fn test_both_is_split() {
+        let mut mgr = CircuitMgr::new();
+        mgr.create_circuit(Direction::Both, circuit(2, 5));
+        assert_eq!(mgr.dl[1].as_ref().unwrap().direction, Direction::Dl);
+        assert_eq!(mgr.ul[1].as_ref().unwrap().direction, Direction::Ul);
+        assert!(mgr.is_active(Direction::Both, 2));
+        assert_eq!(mgr.get_usage(Direction::Both, 2), Some(5));
+
+        mgr.close_circuit(Direction::Ul, 2);
+        assert!(mgr.is_active(Direction::Dl, 2));
+        assert!(!mgr.is_active(Direction::Both, 2));
+        assert_eq!(mgr.get_usage(Direction::Both, 2), None);
+
+        mgr.create_circuit(Direction::Ul, circuit(2, 5));
+        assert_eq!(mgr.close_circuit(Direction::Both, 2).unwrap().direction, Direction::Dl);
+        assert!(!mgr.is_active(Direction::Dl, 2));
+        assert!(!mgr.is_active(Direction::Ul, 2));
+    }
+
+    #[test]
+    fn test_direction_none() {
+        let mut mgr = CircuitMgr::new();
+        mgr.create_circuit(Direction::None, circuit(3, 6));
+        assert!(mgr.dl.iter().chain(mgr.ul.iter()).all(Option::is_none));
+        assert!(!mgr.is_active(Direction::None, 3));
+        assert_eq!(mgr.get_usage(Direction::None, 3), None);
+        assert!(mgr.close_circuit(Direction::None, 3).is_none());
+    }
+}
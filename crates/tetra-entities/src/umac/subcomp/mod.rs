@@ -1,6 +1,10 @@
 pub mod bs_defrag;
 pub mod bs_frag;
 pub mod bs_sched;
+#[cfg(test)]
+mod bs_sched_tests;
+pub mod call_quality;
+pub mod cell_load;
 pub mod defrag;
 
 pub mod circuit_mgr;
@@ -9,3 +13,4 @@ pub mod ms_defrag;
 
 pub mod event_label_store;
 pub mod fillbits;
+pub mod utilization;
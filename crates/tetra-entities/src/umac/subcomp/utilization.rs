@@ -0,0 +1,161 @@
+use std::collections::VecDeque;
+
+use serde_json::json;
+use tetra_core::TdmaTime;
+use tetra_saps::control::admin::{MultiframeUtilization, UTILIZATION_DL, UTILIZATION_UL};
+
+/// Number of multiframes kept in the UtilizationBuffer, 18 x 60 frames or one hyperframe
+pub const UTILIZATION_WINDOW: usize = 60;
+
+/// Usage of a single timeslot, emitted by the BsChannelScheduler for every finalized timeslot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameUtilization {
+    /// Timeslot, from 1 to 4
+    pub ts: u8,
+    /// Frame number, from 1 to 18
+    pub frame: u8,
+    /// Multiframes since the start of hyperframe 0
+    pub multiframe: u64,
+    /// Downlink carried traffic or scheduled signalling, rather than the default SYNC/SYSINFO
+    pub dl_used: bool,
+    /// Uplink was reserved for traffic or granted to an MS
+    pub ul_used: bool,
+}
+
+impl FrameUtilization {
+    pub fn new(time: TdmaTime, dl_used: bool, ul_used: bool) -> Self {
+        Self {
+            ts: time.t,
+            frame: time.f,
+            multiframe: time.multiframe_number(),
+            dl_used,
+            ul_used,
+        }
+    }
+}
+
+/// Rolling per-multiframe timeslot usage bitmaps over the last UTILIZATION_WINDOW multiframes
+#[derive(Debug, Default)]
+pub struct UtilizationBuffer {
+    multiframes: VecDeque<MultiframeUtilization>,
+}
+
+impl UtilizationBuffer {
+    pub fn new() -> Self {
+        Self {
+            multiframes: VecDeque::with_capacity(UTILIZATION_WINDOW),
+        }
+    }
+
+    pub fn record(&mut self, event: FrameUtilization) {
+        assert!((1..=4).contains(&event.ts), "invalid timeslot {}", event.ts);
+        let bits = if event.dl_used { UTILIZATION_DL } else { 0 } | if event.ul_used { UTILIZATION_UL } else { 0 };
+
+        match self.multiframes.back_mut() {
+            Some(last) if last.multiframe == event.multiframe => {
+                last.bitmap[event.ts as usize - 1] |= bits;
+            }
+            Some(last) if last.multiframe > event.multiframe => {
+                // Time was reset, e.g. by a resync. Start over rather than mixing up the order
+                self.multiframes.clear();
+                self.push(event.multiframe, event.ts, bits);
+            }
+            _ => self.push(event.multiframe, event.ts, bits),
+        }
+    }
+
+    fn push(&mut self, multiframe: u64, ts: u8, bits: u8) {
+        if self.multiframes.len() == UTILIZATION_WINDOW {
+            self.multiframes.pop_front();
+        }
+        let mut bitmap = [0; 4];
+        bitmap[ts as usize - 1] = bits;
+        self.multiframes.push_back(MultiframeUtilization { multiframe, bitmap });
+    }
+
+    /// Bitmaps of the recorded multiframes, oldest first
+    pub fn multiframes(&self) -> Vec<MultiframeUtilization> {
+        self.multiframes.iter().cloned().collect()
+    }
+
+    /// Heatmap as a JSON array with one `[multiframe, ts1, ts2, ts3, ts4]` row per multiframe, oldest first
+    pub fn export_json(&self) -> String {
+        let rows: Vec<_> = self
+            .multiframes
+            .iter()
+            .map(|mf| {
+                let [ts1, ts2, ts3, ts4] = mf.bitmap;
+                json!([mf.multiframe, ts1, ts2, ts3, ts4])
+            })
+            .collect();
+        json!(rows).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(m: u8, f: u8, t: u8) -> TdmaTime {
+        TdmaTime { h: 0, m, f, t }
+    }
+
+    #[test]
+    fn test_bitmap_per_multiframe() {
+        let mut buffer = UtilizationBuffer::new();
+
+        // Multiframe 1: DL on ts 1 in every frame, UL on ts 2 in a single frame, ts 3 and 4 idle
+        for f in 1..=18 {
+            buffer.record(FrameUtilization::new(time(1, f, 1), true, false));
+            buffer.record(FrameUtilization::new(time(1, f, 2), false, f == 7));
+            buffer.record(FrameUtilization::new(time(1, f, 3), false, false));
+            buffer.record(FrameUtilization::new(time(1, f, 4), false, false));
+        }
+        // Multiframe 2: traffic in both directions on ts 3
+        buffer.record(FrameUtilization::new(time(2, 1, 3), true, true));
+
+        assert_eq!(
+            buffer.multiframes(),
+            vec![
+                MultiframeUtilization {
+                    multiframe: 0,
+                    bitmap: [UTILIZATION_DL, UTILIZATION_UL, 0, 0],
+                },
+                MultiframeUtilization {
+                    multiframe: 1,
+                    bitmap: [0, 0, UTILIZATION_DL | UTILIZATION_UL, 0],
+                },
+            ]
+        );
+        assert_eq!(buffer.export_json(), "[[0,1,2,0,0],[1,0,0,3,0]]");
+    }
+
+    #[test]
+    fn test_rolling_window() {
+        let mut buffer = UtilizationBuffer::new();
+        let mut t = TdmaTime { h: 3, m: 1, f: 1, t: 2 };
+        for _ in 0..(UTILIZATION_WINDOW + 5) * 18 {
+            buffer.record(FrameUtilization::new(t, true, false));
+            t = t.add_timeslots(4);
+        }
+
+        let multiframes = buffer.multiframes();
+        assert_eq!(multiframes.len(), UTILIZATION_WINDOW);
+        assert_eq!(multiframes[0].multiframe, 3 * 60 + 5);
+        assert!(multiframes.iter().all(|mf| mf.bitmap == [0, UTILIZATION_DL, 0, 0]));
+    }
+
+    #[test]
+    fn test_time_reset() {
+        let mut buffer = UtilizationBuffer::new();
+        buffer.record(FrameUtilization::new(time(10, 1, 1), true, false));
+        buffer.record(FrameUtilization::new(time(2, 1, 4), false, true));
+        assert_eq!(
+            buffer.multiframes(),
+            vec![MultiframeUtilization {
+                multiframe: 1,
+                bitmap: [0, 0, 0, UTILIZATION_UL],
+            }]
+        );
+    }
+}
@@ -3,10 +3,16 @@ use std::panic;
 use tetra_config::bluestation::SharedConfig;
 use tetra_core::freqs::FreqInfo;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, Direction, PhyBlockNum, Sap, SsiType, TdmaTime, TetraAddress, Todo, unimplemented_log};
+use tetra_core::typed_pdu_fields::{FieldU2, FieldU6};
+use tetra_core::{
+    BitBuffer, Direction, EndpointId, EndpointInfo, PhyBlockNum, Sap, SsiType, TdmaTime, TetraAddress, Todo, unimplemented_log,
+};
 use tetra_pdus::mle::fields::bs_service_details::BsServiceDetails;
+use tetra_pdus::mle::fields::neighbour_cell_information_for_ca::NeighbourCellInformationForCa;
 use tetra_pdus::mle::pdus::d_mle_sync::DMleSync;
 use tetra_pdus::mle::pdus::d_mle_sysinfo::DMleSysinfo;
+use tetra_pdus::mle::pdus::d_nwrk_broadcast::DNwrkBroadcast;
+use tetra_pdus::mle::pdus::d_nwrk_broadcast_remove::DNwrkBroadcastRemove;
 use tetra_pdus::umac::enums::mac_pdu_type::MacPduType;
 use tetra_pdus::umac::enums::sysinfo_opt_field_flag::SysinfoOptFieldFlag;
 use tetra_pdus::umac::fields::channel_allocation::ChanAllocElement;
@@ -22,17 +28,22 @@ use tetra_pdus::umac::pdus::mac_sync::MacSync;
 use tetra_pdus::umac::pdus::mac_sysinfo::MacSysinfo;
 use tetra_pdus::umac::pdus::mac_u_blck::MacUBlck;
 use tetra_pdus::umac::pdus::mac_u_signal::MacUSignal;
+use tetra_saps::control::admin::StackStateSnapshot;
 use tetra_saps::control::call_control::{CallControl, Circuit};
 use tetra_saps::lcmc::enums::alloc_type::ChanAllocType;
 use tetra_saps::lcmc::enums::ul_dl_assignment::UlDlAssignment;
 use tetra_saps::lcmc::fields::chan_alloc_req::CmceChanAllocReq;
+use tetra_saps::tlmc::{TlmcConfigureConf, TlmcConfigureReq};
 use tetra_saps::tma::{TmaReport, TmaReportInd, TmaUnitdataInd};
 use tetra_saps::tmv::TmvConfigureReq;
 use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
 use tetra_saps::{SapMsg, SapMsgInner};
 
 use crate::lmac::components::scrambler;
+use crate::net_telemetry::{TelemetryEvent, TelemetrySink};
 use crate::umac::subcomp::bs_sched::{BsChannelScheduler, PrecomputedUmacPdus, TCH_S_CAP};
+use crate::umac::subcomp::call_quality::CallQualityTracker;
+use crate::umac::subcomp::cell_load::CellLoadEstimator;
 use crate::umac::subcomp::fillbits;
 use crate::{MessagePrio, MessageQueue, TetraEntityTrait};
 
@@ -50,6 +61,10 @@ pub struct UmacBs {
 
     /// Subcomponents
     defrag: BsDefrag,
+    /// Defragmentation buffers dropped because the MS never finished its message, since startup
+    defrag_evicted: u64,
+    /// Value of defrag_evicted at the last summary log
+    defrag_evicted_logged: u64,
     /// Pending STCH MAC-DATA spanning block1+block2 (length_ind=0b111110), keyed by timeslot.
     pending_stch: Option<PendingStch>,
     // event_label_store: EventLabelStore,
@@ -60,6 +75,15 @@ pub struct UmacBs {
     /// Timestamp of last received UL voice frame per timeslot (0-indexed: ts1..ts4).
     /// Used to detect UL inactivity when a radio disappears mid-transmission.
     last_ul_voice: [Option<TdmaTime>; 4],
+    /// UL traffic CRC results per timeslot (0-indexed: ts1..ts4), reported as call quality once per multiframe
+    call_quality: [CallQualityTracker; 4],
+    /// Derives the broadcast Cell load CA from the circuits in use
+    cell_load: CellLoadEstimator,
+    /// Energy economy group and startpoint set by MLE through TL-CONFIGURE. Not yet honoured by the
+    /// DL scheduler, which may still address an MS in any frame.
+    energy_economy: Option<(u8, u8)>,
+    dual_watch_energy_economy_group: Option<u8>,
+    telemetry: Option<TelemetrySink>,
 }
 
 struct PendingStch {
@@ -71,7 +95,7 @@ struct PendingStch {
 }
 
 impl UmacBs {
-    pub fn new(config: SharedConfig) -> Self {
+    pub fn new(config: SharedConfig, telemetry: Option<TelemetrySink>) -> Self {
         let c = config.config();
         let scrambling_code = scrambler::tetra_scramb_get_init(c.net.mcc, c.net.mnc, c.cell.colour_code);
         let system_wide_services = Self::get_system_wide_services_state(&config);
@@ -83,10 +107,17 @@ impl UmacBs {
             system_wide_services,
             endpoint_id: 1,
             defrag: BsDefrag::new(),
+            defrag_evicted: 0,
+            defrag_evicted_logged: 0,
             pending_stch: None,
             // event_label_store: EventLabelStore::new(),
             channel_scheduler: BsChannelScheduler::new(scrambling_code, precomps),
             last_ul_voice: [None; 4],
+            call_quality: Default::default(),
+            cell_load: CellLoadEstimator::new(4),
+            energy_economy: None,
+            dual_watch_energy_economy_group: None,
+            telemetry,
         }
     }
 
@@ -128,7 +159,9 @@ impl UmacBs {
             freq_offset_index: FreqInfo::freq_offset_hz_to_id(c.cell.freq_offset_hz).unwrap(),
             duplex_spacing: c.cell.duplex_spacing_id,
             reverse_operation: c.cell.reverse_operation,
-            num_of_csch: 0, // Common secondary control channels
+            // Common secondary control channels. Energy economy doesn't need these, MSs in an energy
+            // economy group wake up on the MCCH, so this stays 0 with or without it.
+            num_of_csch: 0,
             ms_txpwr_max_cell: c.cell.ms_txpwr_max_cell,
             rxlev_access_min: 3, // -110 dBm (permissive, suitable for single-cell)
             access_parameter: 7, // -39 dBm (MS open-loop power control setpoint)
@@ -193,19 +226,72 @@ impl UmacBs {
             mcc: c.net.mcc,
             mnc: c.net.mnc,
             neighbor_cell_broadcast: 2, // Broadcast supported, but enquiry not supported
-            cell_load_ca: 0,            // TODO implement dynamic setting. 0 = info unavailable
+            cell_load_ca: 0,            // Info unavailable until updated in tick_start
             late_entry_supported: c.cell.late_entry_supported,
         };
 
+        let mle_nwrk_broadcast_pdu = Self::generate_nwrk_broadcast(config);
+
         PrecomputedUmacPdus {
             mac_sysinfo1: sysinfo1,
             mac_sysinfo2: sysinfo2,
             mle_sysinfo: mle_sysinfo_pdu,
             mac_sync: mac_sync_pdu,
             mle_sync: mle_sync_pdu,
+            mle_nwrk_broadcast: mle_nwrk_broadcast_pdu,
         }
     }
 
+    /// Build the D-NWRK-BROADCAST announcing the configured neighbour cells, None if there are none
+    fn generate_nwrk_broadcast(config: &SharedConfig) -> Option<DNwrkBroadcast> {
+        let c = config.config();
+
+        // Neighbour cells are numbered from 1 in the order they are configured
+        let neighbour_cells: Vec<_> = c
+            .cell
+            .neighbor_cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| NeighbourCellInformationForCa {
+                cell_identifier_ca: i as u8 + 1,
+                cell_reselection_types_supported: 0,
+                neighbour_cell_synchronized: false,
+                cell_load_ca: 0, // Unknown
+                main_carrier_number: cell.carrier,
+                main_carrier_number_extension: None, // Same band and duplex spacing as this cell
+                mcc: cell.mcc,
+                mnc: cell.mnc,
+                location_area: cell.location_area,
+                maximum_ms_transmit_power: None,
+                minimum_rx_access_level: None,
+                subscriber_class: None,
+                bs_service_details: None,
+                timeshare_cell_information_or_security_parameters: None,
+                tdma_frame_offset: None,
+            })
+            .collect();
+        (!neighbour_cells.is_empty()).then_some(DNwrkBroadcast {
+            cell_re_select_parameters: 0,
+            cell_load_ca: 0,
+            tetra_network_time: None,
+            number_of_ca_neighbour_cells: Some(neighbour_cells.len() as u64),
+            neighbour_cell_information_for_ca: neighbour_cells,
+        })
+    }
+
+    /// D-NWRK-BROADCAST REMOVE for each cell identifier CA of the old config that the new one no longer uses.
+    /// Identifiers that remain in use are simply re-announced by the new broadcast.
+    fn generate_nwrk_broadcast_removals(old: &SharedConfig, new: &SharedConfig) -> Vec<DNwrkBroadcastRemove> {
+        let old_count = old.config().cell.neighbor_cells.len();
+        let new_count = new.config().cell.neighbor_cells.len();
+        // Same numbering as generate_nwrk_broadcast
+        (new_count + 1..=old_count)
+            .map(|i| DNwrkBroadcastRemove {
+                cell_identifier_ca: i as u8,
+            })
+            .collect()
+    }
+
     /// Retrieve currently set value of system-wide services. If SwMI is active, this governs connection state
     /// Otherwise, value from config is used.
     fn get_system_wide_services_state(config: &SharedConfig) -> bool {
@@ -251,13 +337,13 @@ impl UmacBs {
             req_handle: handle,
             report,
         };
-        let msg = SapMsg {
-            sap: Sap::TmaSap,
-            src: TetraEntity::Umac,
-            dest: TetraEntity::Llc,
-            msg: SapMsgInner::TmaReportInd(tma_report_ind),
-        };
-        queue.push_back(msg);
+        let msg = SapMsg::new(
+            Sap::TmaSap,
+            TetraEntity::Umac,
+            TetraEntity::Llc,
+            SapMsgInner::TmaReportInd(tma_report_ind),
+        );
+        let _ = queue.push_back(msg);
     }
 
     fn rx_tmv_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
@@ -272,34 +358,31 @@ impl UmacBs {
         }
     }
 
-    pub fn rx_tmv_unitdata_ind(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
-        let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
+    pub fn rx_tmv_unitdata_ind(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        let SapMsgInner::TmvUnitdataInd(prim) = &message.msg else {
             panic!()
         };
         tracing::trace!("rx_tmv_unitdata_ind: {:?}", prim.logical_channel);
 
-        match prim.logical_channel {
-            LogicalChannel::SchF => {
-                // Full slot signalling
-                assert!(
-                    prim.block_num == PhyBlockNum::Both,
-                    "{:?} can't have block_num {:?}",
-                    prim.logical_channel,
-                    prim.block_num
-                );
-                self.rx_tmv_sch(queue, message);
-            }
-            LogicalChannel::Stch | LogicalChannel::SchHu => {
-                // Half slot signalling
-                assert!(
-                    matches!(prim.block_num, PhyBlockNum::Block1 | PhyBlockNum::Block2),
-                    "{:?} can't have block_num {:?}",
-                    prim.logical_channel,
-                    prim.block_num
-                );
-                self.rx_tmv_sch(queue, message);
-            }
-            _ => unreachable!("invalid channel: {:?}", prim.logical_channel),
+        // A block number that does not fit the channel is an LMAC fault; drop the block rather than the stack
+        if !Self::block_num_valid_for_channel(prim.logical_channel, prim.block_num) {
+            tracing::error!(
+                "rx_tmv_unitdata_ind: dropping {:?} with block_num {:?}",
+                prim.logical_channel,
+                prim.block_num
+            );
+            return;
+        }
+        self.rx_tmv_sch(queue, message);
+    }
+
+    /// Whether an UL signalling block on `lchan` may carry `block_num`: full slot for SCH/F,
+    /// half slot for STCH and SCH/HU. Other channels are not handled by rx_tmv_unitdata_ind at all.
+    pub fn block_num_valid_for_channel(lchan: LogicalChannel, block_num: PhyBlockNum) -> bool {
+        match lchan {
+            LogicalChannel::SchF => block_num == PhyBlockNum::Both,
+            LogicalChannel::Stch | LogicalChannel::SchHu => matches!(block_num, PhyBlockNum::Block1 | PhyBlockNum::Block2),
+            _ => false,
         }
     }
 
@@ -424,7 +507,7 @@ impl UmacBs {
 
         let pdu = match MacData::from_bitbuf(&mut prim.pdu) {
             Ok(pdu) => {
-                tracing::debug!("<- {:?}", pdu);
+                tracing::debug!("<- {}", pdu);
                 pdu
             }
             Err(e) => {
@@ -527,7 +610,8 @@ impl UmacBs {
         tracing::debug!("rx_mac_data: {}", prim.pdu.dump_bin_full(true));
         if is_frag_start {
             // Fragmentation start, add to defragmenter
-            self.defrag.insert_first(&mut prim.pdu, msg_dltime, addr, None);
+            let (frag, _) = prim.pdu.split_at(pdu_len_bits);
+            self.defrag.insert_first(frag, msg_dltime, addr, None);
         } else {
             // Pass directly to LLC
             let sdu = {
@@ -542,16 +626,15 @@ impl UmacBs {
 
             if sdu.is_some() {
                 // We have an SDU for the LLC, deliver it.
-                let m = SapMsg {
-                    sap: Sap::TmaSap,
-                    src: TetraEntity::Umac,
-                    dest: TetraEntity::Llc,
-
-                    msg: SapMsgInner::TmaUnitdataInd(TmaUnitdataInd {
+                let m = SapMsg::new(
+                    Sap::TmaSap,
+                    TetraEntity::Umac,
+                    TetraEntity::Llc,
+                    SapMsgInner::TmaUnitdataInd(TmaUnitdataInd {
                         pdu: sdu,
                         main_address: addr,
                         scrambling_code: prim.scrambling_code,
-                        endpoint_id: 0,        // TODO FIXME
+                        endpoint_id: self.endpoint_for_ts(msg_dltime.t),
                         new_endpoint_id: None, // TODO FIXME
                         css_endpoint_id: None, // TODO FIXME
                         air_interface_encryption: pdu.encrypted as Todo,
@@ -559,8 +642,8 @@ impl UmacBs {
                         chan_change_handle: None,
                         chan_info: None,
                     }),
-                };
-                queue.push_back(m);
+                );
+                let _ = queue.push_back(m);
             } else {
                 // Either this is a null pdu or we are at the end of the block
                 // For now, we don't deliver this. However, important data may need to be signalled upwards
@@ -584,7 +667,7 @@ impl UmacBs {
 
         let pdu = match MacAccess::from_bitbuf(&mut prim.pdu) {
             Ok(pdu) => {
-                tracing::debug!("<- {:?}", pdu);
+                tracing::debug!("<- {}", pdu);
                 pdu
             }
             Err(e) => {
@@ -676,7 +759,8 @@ impl UmacBs {
         // tracing::debug!("rx_mac_access: {}", prim.pdu.dump_bin_full(true));
         if pdu.is_frag_start() {
             // Fragmentation start, add to defragmenter
-            self.defrag.insert_first(&mut prim.pdu, msg_dltime, addr, None);
+            let (frag, _) = prim.pdu.split_at(pdu_len_bits);
+            self.defrag.insert_first(frag, msg_dltime, addr, None);
         } else {
             // Pass directly to LLC
             if prim.pdu.get_len_remaining() == 0 {
@@ -698,15 +782,15 @@ impl UmacBs {
 
             if sdu.is_some() {
                 // We have an SDU for the LLC, deliver it.
-                let m = SapMsg {
-                    sap: Sap::TmaSap,
-                    src: TetraEntity::Umac,
-                    dest: TetraEntity::Llc,
-                    msg: SapMsgInner::TmaUnitdataInd(TmaUnitdataInd {
+                let m = SapMsg::new(
+                    Sap::TmaSap,
+                    TetraEntity::Umac,
+                    TetraEntity::Llc,
+                    SapMsgInner::TmaUnitdataInd(TmaUnitdataInd {
                         pdu: sdu,
                         main_address: addr,
                         scrambling_code: prim.scrambling_code,
-                        endpoint_id: 0,        // TODO FIXME
+                        endpoint_id: self.endpoint_for_ts(msg_dltime.t),
                         new_endpoint_id: None, // TODO FIXME
                         css_endpoint_id: None, // TODO FIXME
                         air_interface_encryption: pdu.encrypted as Todo,
@@ -714,8 +798,8 @@ impl UmacBs {
                         chan_change_handle: None,
                         chan_info: None,
                     }),
-                };
-                queue.push_back(m);
+                );
+                let _ = queue.push_back(m);
             } else {
                 // Either this is a null pdu or we are at the end of the block
                 // For now, we don't deliver this. However, important data may need to be signalled upwards
@@ -740,7 +824,7 @@ impl UmacBs {
         // Parse header and optional ChanAlloc
         let pdu = match MacFragUl::from_bitbuf(&mut prim.pdu) {
             Ok(pdu) => {
-                tracing::debug!("<- {:?}", pdu);
+                tracing::debug!("<- {}", pdu);
                 pdu
             }
             Err(e) => {
@@ -789,7 +873,7 @@ impl UmacBs {
         // Parse header and optional ChanAlloc
         let pdu = match MacEndUl::from_bitbuf(&mut prim.pdu) {
             Ok(pdu) => {
-                tracing::debug!("<- {:?}", pdu);
+                tracing::debug!("<- {}", pdu);
                 pdu
             }
             Err(e) => {
@@ -837,7 +921,8 @@ impl UmacBs {
             return;
         };
         if let Some(_aie_info) = self.defrag.get_aie_info(slot_owner, msg_dltime) {
-            unimplemented!("rx_mac_end_ul: Encryption not supported");
+            unimplemented_log!("rx_mac_end_ul: Encryption not supported");
+            return;
         }
 
         // Insert last fragment and retrieve finalized block
@@ -861,15 +946,15 @@ impl UmacBs {
         // Pass completed block to LLC
         tracing::debug!("rx_mac_end_ul: sdu: {:?}", defragbuf.buffer.dump_bin());
 
-        let m = SapMsg {
-            sap: Sap::TmaSap,
-            src: TetraEntity::Umac,
-            dest: TetraEntity::Llc,
-            msg: SapMsgInner::TmaUnitdataInd(TmaUnitdataInd {
+        let m = SapMsg::new(
+            Sap::TmaSap,
+            TetraEntity::Umac,
+            TetraEntity::Llc,
+            SapMsgInner::TmaUnitdataInd(TmaUnitdataInd {
                 pdu: Some(defragbuf.buffer),
                 main_address: defragbuf.addr,
                 scrambling_code: prim.scrambling_code,
-                endpoint_id: 0,              // TODO FIXME
+                endpoint_id: self.endpoint_for_ts(msg_dltime.t),
                 new_endpoint_id: None,       // TODO FIXME
                 css_endpoint_id: None,       // TODO FIXME
                 air_interface_encryption: 0, // TODO FIXME implement
@@ -877,8 +962,8 @@ impl UmacBs {
                 chan_change_handle: None,
                 chan_info: None,
             }),
-        };
-        queue.push_back(m);
+        );
+        let _ = queue.push_back(m);
 
         // Since this is not a null pdu, more MAC PDUs may follow
         // This allows parent function to continue parsing
@@ -897,7 +982,7 @@ impl UmacBs {
         // Parse header and optional ChanAlloc
         let pdu = match MacEndHu::from_bitbuf(&mut prim.pdu) {
             Ok(pdu) => {
-                tracing::debug!("<- {:?}", pdu);
+                tracing::debug!("<- {}", pdu);
                 pdu
             }
             Err(e) => {
@@ -953,7 +1038,8 @@ impl UmacBs {
             return;
         };
         if let Some(_aie_info) = self.defrag.get_aie_info(slot_owner, msg_dltime) {
-            unimplemented!("rx_mac_end_hu: Encryption not supported");
+            unimplemented_log!("rx_mac_end_hu: Encryption not supported");
+            return;
         }
 
         // Insert last fragment and retrieve finalized block
@@ -977,15 +1063,15 @@ impl UmacBs {
         // Pass completed block to LLC
         tracing::debug!("rx_mac_end_hu: sdu: {:?}", defragbuf.buffer.dump_bin());
 
-        let m = SapMsg {
-            sap: Sap::TmaSap,
-            src: TetraEntity::Umac,
-            dest: TetraEntity::Llc,
-            msg: SapMsgInner::TmaUnitdataInd(TmaUnitdataInd {
+        let m = SapMsg::new(
+            Sap::TmaSap,
+            TetraEntity::Umac,
+            TetraEntity::Llc,
+            SapMsgInner::TmaUnitdataInd(TmaUnitdataInd {
                 pdu: Some(defragbuf.buffer),
                 main_address: defragbuf.addr,
                 scrambling_code: prim.scrambling_code,
-                endpoint_id: 0,              // TODO FIXME
+                endpoint_id: self.endpoint_for_ts(msg_dltime.t),
                 new_endpoint_id: None,       // TODO FIXME
                 css_endpoint_id: None,       // TODO FIXME
                 air_interface_encryption: 0, // TODO FIXME implement
@@ -993,8 +1079,8 @@ impl UmacBs {
                 chan_change_handle: None,
                 chan_info: None,
             }),
-        };
-        queue.push_back(m);
+        );
+        let _ = queue.push_back(m);
 
         // Since this is not a null pdu, more MAC PDUs may follow
         // This allows parent function to continue parsing
@@ -1016,7 +1102,7 @@ impl UmacBs {
 
         let pdu = match MacUSignal::from_bitbuf(&mut prim.pdu) {
             Ok(pdu) => {
-                tracing::debug!("<- {:?}", pdu);
+                tracing::debug!("<- {}", pdu);
                 pdu
             }
             Err(e) => {
@@ -1042,15 +1128,15 @@ impl UmacBs {
         // Forward to LLC via TMA-SAP, same path as MAC-DATA.
         // Address is not known from MAC-U-SIGNAL (no address field); use a placeholder.
         // The CMCE layer identifies the call by call_identifier in the PDU, not by address.
-        let m = SapMsg {
-            sap: Sap::TmaSap,
-            src: TetraEntity::Umac,
-            dest: TetraEntity::Llc,
-            msg: SapMsgInner::TmaUnitdataInd(TmaUnitdataInd {
+        let m = SapMsg::new(
+            Sap::TmaSap,
+            TetraEntity::Umac,
+            TetraEntity::Llc,
+            SapMsgInner::TmaUnitdataInd(TmaUnitdataInd {
                 pdu: Some(sdu),
                 main_address: TetraAddress::issi(0), // Address unknown from MAC-U-SIGNAL
                 scrambling_code: prim.scrambling_code,
-                endpoint_id: 0,
+                endpoint_id: self.endpoint_for_ts(self.dltime.add_timeslots(-2).t),
                 new_endpoint_id: None,
                 css_endpoint_id: None,
                 air_interface_encryption: 0,
@@ -1058,8 +1144,8 @@ impl UmacBs {
                 chan_change_handle: None,
                 chan_info: None,
             }),
-        };
-        queue.push_back(m);
+        );
+        let _ = queue.push_back(m);
     }
 
     /// TMA-SAP MAC-U-BLCK
@@ -1073,7 +1159,7 @@ impl UmacBs {
 
         let _pdu = match MacUBlck::from_bitbuf(&mut prim.pdu) {
             Ok(pdu) => {
-                tracing::debug!("<- {:?}", pdu);
+                tracing::debug!("<- {}", pdu);
                 pdu
             }
             Err(e) => {
@@ -1124,9 +1210,9 @@ impl UmacBs {
                 let mut mac_pdu = MacResource {
                     fill_bits: false,
                     pos_of_grant: 0,
-                    encryption_mode: 0,
+                    encryption_mode: FieldU2::new(0),
                     random_access_flag: is_random_access_response,
-                    length_ind: 0,
+                    length_ind: FieldU6::new(0),
                     addr: Some(prim.main_address),
                     event_label: None,
                     usage_marker,
@@ -1153,6 +1239,10 @@ impl UmacBs {
                     stch_block.get_len()
                 );
 
+                // Repeats are stolen from the following frames, the reporter goes with the last transmission
+                for _ in 1..prim.repeat_count {
+                    self.channel_scheduler.dl_enqueue_stealing(ts, stch_block.clone(), None);
+                }
                 self.channel_scheduler.dl_enqueue_stealing(ts, stch_block, prim.tx_reporter);
 
                 return;
@@ -1180,9 +1270,9 @@ impl UmacBs {
         let mut pdu = MacResource {
             fill_bits: false, // Updated later
             pos_of_grant: 0,
-            encryption_mode: 0,
+            encryption_mode: FieldU2::new(0),
             random_access_flag: is_random_access_response,
-            length_ind: 0, // Updated later
+            length_ind: FieldU6::new(0), // Updated later
             addr: Some(prim.main_address),
             event_label: None,
             usage_marker,
@@ -1200,7 +1290,7 @@ impl UmacBs {
         // }
         // self.channel_scheduler.dl_enqueue_tma(message.dltime.t, pdu, sdu, prim.tx_reporter);
 
-        self.channel_scheduler.dl_enqueue_tma(pdu, sdu, prim.tx_reporter);
+        self.channel_scheduler.dl_enqueue_tma(pdu, sdu, prim.tx_reporter, prim.repeat_count);
 
         // let enqueue_ts = 1;
         // self.channel_scheduler.dl_enqueue_tma(enqueue_ts, pdu, sdu, prim.tx_reporter);
@@ -1221,6 +1311,43 @@ impl UmacBs {
         panic!()
     }
 
+    fn rx_tlmc_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        tracing::trace!("rx_tlmc_prim");
+        match message.msg {
+            SapMsgInner::TlmcConfigureReq(prim) => {
+                self.rx_tlmc_configure_req(queue, prim);
+            }
+            _ => {
+                panic!();
+            }
+        }
+    }
+
+    /// TL-CONFIGURE from MLE. Takes over the energy economy parameters and confirms the accepted values.
+    fn rx_tlmc_configure_req(&mut self, queue: &mut MessageQueue, prim: TlmcConfigureReq) {
+        tracing::trace!("rx_tlmc_configure_req");
+        self.energy_economy = prim.energy_economy_group.zip(prim.energy_economy_startpoint);
+        self.dual_watch_energy_economy_group = prim.dual_watch_energy_economy_group;
+        tracing::debug!(
+            "rx_tlmc_configure_req: energy economy {:?} dual watch {:?}",
+            self.energy_economy,
+            self.dual_watch_energy_economy_group
+        );
+
+        let msg = SapMsg::new(
+            Sap::TlmcSap,
+            self.self_component,
+            TetraEntity::Mle,
+            SapMsgInner::TlmcConfigureConf(TlmcConfigureConf {
+                energy_economy_group: self.energy_economy.map(|(group, _)| group),
+                energy_economy_startpoint: self.energy_economy.map(|(_, startpoint)| startpoint),
+                dual_watch_energy_economy_group: self.dual_watch_energy_economy_group,
+                ..Default::default()
+            }),
+        );
+        let _ = queue.push_back(msg);
+    }
+
     fn rx_tmd_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tmd_prim");
 
@@ -1234,7 +1361,10 @@ impl UmacBs {
                 if (1..=4).contains(&ts) && self.channel_scheduler.circuit_is_active(Direction::Ul, ts) {
                     self.last_ul_voice[ts as usize - 1] = Some(self.dltime);
                 }
-                if self.channel_scheduler.circuit_is_active(Direction::Dl, ts) {
+                if self.channel_scheduler.circuit_is_active(Direction::Dl, ts) && self.channel_scheduler.hangtime_effective(ts) {
+                    // The timeslot is in signalling mode, the frame would only be sent late once traffic resumes
+                    tracing::trace!("rx_tmd_prim: dropping DL voice during hangtime on ts={}", ts);
+                } else if self.channel_scheduler.circuit_is_active(Direction::Dl, ts) {
                     self.channel_scheduler.dl_schedule_tmd(ts, prim.data);
                 } else {
                     tracing::warn!(
@@ -1253,25 +1383,34 @@ impl UmacBs {
                 // Track last UL voice frame time for inactivity detection
                 if (1..=4).contains(&ts) {
                     self.last_ul_voice[ts as usize - 1] = Some(self.dltime);
+                    if self.channel_scheduler.circuit_is_active(Direction::Ul, ts) {
+                        self.call_quality[ts as usize - 1].record(prim.crc_pass);
+                    }
                 }
 
                 // Forward UL voice to Brew (User plane) if loaded
                 if self.config.config().brew.is_some() {
                     if self.channel_scheduler.circuit_is_active(Direction::Ul, ts) {
-                        let msg = SapMsg {
-                            sap: Sap::TmdSap,
-                            src: TetraEntity::Umac,
-                            dest: TetraEntity::Brew,
-                            msg: SapMsgInner::TmdCircuitDataInd(tetra_saps::tmd::TmdCircuitDataInd { ts, data: data.clone() }),
-                        };
-                        queue.push_back(msg);
+                        let msg = SapMsg::new(
+                            Sap::TmdSap,
+                            TetraEntity::Umac,
+                            TetraEntity::Brew,
+                            SapMsgInner::TmdCircuitDataInd(tetra_saps::tmd::TmdCircuitDataInd {
+                                ts,
+                                data: data.clone(),
+                                crc_pass: prim.crc_pass,
+                            }),
+                        );
+                        let _ = queue.push_back(msg);
                     } else {
                         tracing::trace!("rx_tmd_prim: no active UL circuit on ts={}, dropping UL voice to Brew", ts);
                     }
                 }
 
-                // Loopback only if there's an active DL circuit on this timeslot
-                if self.channel_scheduler.circuit_is_active(Direction::Dl, ts) {
+                // Loopback only if there's an active DL circuit on this timeslot, sending traffic
+                if self.channel_scheduler.circuit_is_active(Direction::Dl, ts) && self.channel_scheduler.hangtime_effective(ts) {
+                    tracing::trace!("rx_tmd_prim: ts={} in hangtime, skipping loopback", ts);
+                } else if self.channel_scheduler.circuit_is_active(Direction::Dl, ts) {
                     tracing::trace!("rx_tmd_prim: loopback UL voice on ts={}", ts);
                     if let Some(packed) = pack_ul_acelp_bits(&data) {
                         self.channel_scheduler.dl_schedule_tmd(ts, packed);
@@ -1292,19 +1431,41 @@ impl UmacBs {
         }
     }
 
+    /// Have the LMAC send the current SYNC in a synchronization burst on a traffic timeslot, for late entry
+    fn signal_lmac_traffic_bsch(&mut self, queue: &mut MessageQueue, ts: u8) {
+        if !(2..=4).contains(&ts) || !self.channel_scheduler.circuit_is_active(Direction::Dl, ts) {
+            tracing::warn!("signal_lmac_traffic_bsch: no DL circuit on ts {}", ts);
+            return;
+        }
+        let mut bsch = BitBuffer::new(60);
+        self.channel_scheduler.write_sync(&mut bsch);
+        bsch.seek(0);
+
+        let m = SapMsg::new(
+            Sap::TmvSap,
+            self.self_component,
+            TetraEntity::Lmac,
+            SapMsgInner::TmvConfigureReq(TmvConfigureReq {
+                traffic_bsch: Some((ts, bsch)),
+                ..Default::default()
+            }),
+        );
+        let _ = queue.push_back(m);
+    }
+
     fn signal_lmac_second_half_stolen(&mut self, queue: &mut MessageQueue) {
         // Signal LMAC that Block2 is also stolen (STCH, not TCH).
         // Must be Immediate priority so LMAC sees it before processing Block2.
-        let m = SapMsg {
-            sap: Sap::TmvSap,
-            src: self.self_component,
-            dest: TetraEntity::Lmac,
-            msg: SapMsgInner::TmvConfigureReq(TmvConfigureReq {
+        let m = SapMsg::new(
+            Sap::TmvSap,
+            self.self_component,
+            TetraEntity::Lmac,
+            SapMsgInner::TmvConfigureReq(TmvConfigureReq {
                 blk2_stolen: Some(true),
                 ..Default::default()
             }),
-        };
-        queue.push_prio(m, MessagePrio::Immediate);
+        );
+        let _ = queue.push_prio(m, MessagePrio::Immediate);
     }
 
     // fn rx_stch_second_half(&mut self, queue: &mut MessageQueue, message: &mut SapMsg, pending: PendingStch) {
@@ -1360,7 +1521,7 @@ impl UmacBs {
     //     queue.push_back(m);
     // }
 
-    fn rx_control_circuit_open(&mut self, _queue: &mut MessageQueue, prim: CallControl) {
+    fn rx_control_circuit_open(&mut self, _queue: &mut MessageQueue, prim: CallControl, owner: TetraEntity) {
         let CallControl::Open(circuit) = prim else { panic!() };
         let ts = circuit.ts;
         let dir = circuit.direction;
@@ -1393,13 +1554,15 @@ impl UmacBs {
             };
             self.channel_scheduler.create_circuit(d, c);
 
-            // Start UL inactivity timer when opening a UL circuit
+            // Start UL inactivity timer and call quality tracking when opening a UL circuit
             if d == Direction::Ul && (1..=4).contains(&ts) {
                 self.last_ul_voice[ts as usize - 1] = Some(self.dltime);
+                self.call_quality[ts as usize - 1].reset();
             }
 
             tracing::debug!("  rx_control_circuit_open: Setup {:?} circuit for ts {}", d, ts);
         }
+        self.register_endpoint(ts, dir, owner);
     }
 
     fn rx_control_circuit_close(&mut self, _queue: &mut MessageQueue, prim: CallControl) {
@@ -1418,10 +1581,12 @@ impl UmacBs {
         for d in dirs {
             match self.channel_scheduler.close_circuit(d, ts) {
                 Some(_) => {
-                    // Clear UL inactivity timer when closing a UL circuit
+                    // Clear UL inactivity timer and call quality when closing a UL circuit
                     if d == Direction::Ul && (1..=4).contains(&ts) {
                         self.last_ul_voice[ts as usize - 1] = None;
+                        self.call_quality[ts as usize - 1].reset();
                     }
+                    self.deregister_endpoint(ts, d);
                     tracing::info!("  rx_control_circuit_close: Closed {:?} circuit for ts {}", d, ts);
                 }
                 None => {
@@ -1431,6 +1596,76 @@ impl UmacBs {
         }
     }
 
+    /// Endpoint identifier for a timeslot. By convention the endpoint id of a circuit is its timeslot number.
+    /// Falls back to the timeslot number if no circuit is registered, e.g. for signalling on the MCCH.
+    fn endpoint_for_ts(&self, ts: u8) -> EndpointId {
+        self.config
+            .state_read()
+            .endpoints
+            .reverse_lookup(ts)
+            .first()
+            .copied()
+            .unwrap_or(ts as EndpointId)
+    }
+
+    /// Register the endpoint of a newly opened circuit, merging with an already open opposite direction
+    fn register_endpoint(&mut self, ts: u8, direction: Direction, owner: TetraEntity) {
+        let id = ts as EndpointId;
+        let mut state = self.config.state_write();
+        let info = match state.endpoints.resolve(id) {
+            Some(existing) if existing.direction != direction => EndpointInfo {
+                direction: Direction::Both,
+                owner,
+                ..existing.clone()
+            },
+            _ => EndpointInfo {
+                ts,
+                direction,
+                owner,
+                allocated_at: self.dltime,
+            },
+        };
+        state.endpoints.register(id, info);
+    }
+
+    /// Remove one direction from the endpoint of a closed circuit, dropping it once no direction is left
+    fn deregister_endpoint(&mut self, ts: u8, direction: Direction) {
+        let id = ts as EndpointId;
+        let mut state = self.config.state_write();
+        let Some(existing) = state.endpoints.resolve(id) else {
+            return;
+        };
+        let remaining = match (existing.direction, direction) {
+            (Direction::Both, Direction::Dl) => Direction::Ul,
+            (Direction::Both, Direction::Ul) => Direction::Dl,
+            _ => Direction::None,
+        };
+        if remaining == Direction::None {
+            state.endpoints.deregister(id);
+        } else {
+            let info = EndpointInfo {
+                direction: remaining,
+                ..existing.clone()
+            };
+            state.endpoints.register(id, info);
+        }
+    }
+
+    fn age_defrag_buffers(&mut self, ts: TdmaTime) {
+        // Summary of evictions every 60 frames
+        const DEFRAG_SUMMARY_TIMESLOTS: i32 = 60 * 4;
+
+        self.defrag_evicted += self.defrag.age_buffers(ts) as u64;
+        if ts.to_int() % DEFRAG_SUMMARY_TIMESLOTS == 0 && self.defrag_evicted != self.defrag_evicted_logged {
+            tracing::info!(
+                "UmacBs: evicted {} stale defrag buffers, {} since startup",
+                self.defrag_evicted - self.defrag_evicted_logged,
+                self.defrag_evicted
+            );
+            self.defrag_evicted_logged = self.defrag_evicted;
+        }
+    }
+
     /// Check for UL inactivity on traffic timeslots. If no voice frames have arrived
     /// for UL_INACTIVITY_TIMESLOTS on a timeslot with an active UL circuit (and not in
     /// hangtime), send UlInactivityTimeout to CMCE.
@@ -1447,13 +1682,13 @@ impl UmacBs {
             }
 
             // Skip if in hangtime (no voice expected)
-            if self.channel_scheduler.is_hangtime(ts) {
+            if self.channel_scheduler.hangtime_active(ts) {
                 continue;
             }
 
             // Check if we've exceeded the inactivity threshold
             let timed_out = match self.last_ul_voice[idx] {
-                Some(t) => t.age(self.dltime) > UL_INACTIVITY_TIMESLOTS,
+                Some(t) => t.add_timeslots(UL_INACTIVITY_TIMESLOTS).is_before(self.dltime),
                 None => false, // Initialized at circuit open; shouldn't be None here
             };
 
@@ -1461,16 +1696,58 @@ impl UmacBs {
                 tracing::warn!("UL inactivity timeout on ts={}, sending notification to CMCE", ts);
                 self.last_ul_voice[idx] = None;
 
-                queue.push_back(SapMsg {
-                    sap: Sap::Control,
-                    src: TetraEntity::Umac,
-                    dest: TetraEntity::Cmce,
-                    msg: SapMsgInner::CmceCallControl(CallControl::UlInactivityTimeout { ts }),
+                let _ = queue.push_back(SapMsg::new(
+                    Sap::Control,
+                    TetraEntity::Umac,
+                    TetraEntity::Cmce,
+                    SapMsgInner::CmceCallControl(CallControl::UlInactivityTimeout { ts }),
+                ));
+            }
+        }
+    }
+
+    /// Report the call quality of every timeslot with an active UL circuit. Called once per multiframe.
+    fn report_call_quality(&self) {
+        for ts in 1..=4u8 {
+            if !self.channel_scheduler.circuit_is_active(Direction::Ul, ts) {
+                continue;
+            }
+            let Some(quality) = self.call_quality[ts as usize - 1].indicator() else {
+                continue;
+            };
+            tracing::debug!(
+                "call quality ts={}: crc_pass_rate={:.2} frames={} mos={:.2}",
+                ts,
+                quality.crc_pass_rate,
+                quality.frame_count,
+                quality.mos_estimate
+            );
+            if let Some(sink) = &self.telemetry {
+                sink.send(TelemetryEvent::CallQuality {
+                    ts,
+                    crc_pass_rate: quality.crc_pass_rate,
+                    frame_count: quality.frame_count,
+                    mos_estimate: quality.mos_estimate,
                 });
             }
         }
     }
 
+    /// Reply to the sender with the open circuits and the timeslot utilization
+    fn rx_query_stack_state(&self, queue: &mut MessageQueue, sender: TetraEntity) {
+        let _ = queue.push_back(SapMsg::new(
+            Sap::Control,
+            TetraEntity::Umac,
+            sender,
+            SapMsgInner::StackStateSnapshot(StackStateSnapshot {
+                active_circuits: self.channel_scheduler.circuit_info(),
+                frame_utilization: self.channel_scheduler.utilization(),
+                ul_grants: self.channel_scheduler.ul_grant_info(),
+                ..Default::default()
+            }),
+        ));
+    }
+
     fn rx_control(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_control");
         let SapMsgInner::CmceCallControl(prim) = message.msg else {
@@ -1479,7 +1756,7 @@ impl UmacBs {
 
         match prim {
             CallControl::Open(_) => {
-                self.rx_control_circuit_open(queue, prim);
+                self.rx_control_circuit_open(queue, prim, message.src);
             }
             CallControl::Close(_, _) => {
                 self.rx_control_circuit_close(queue, prim);
@@ -1506,6 +1783,10 @@ impl UmacBs {
                 }
             }
 
+            CallControl::LateEntry { ts, .. } => {
+                self.signal_lmac_traffic_bsch(queue, ts);
+            }
+
             // UlInactivityTimeout is UMAC→CMCE only, UMAC won't receive it back
             CallControl::UlInactivityTimeout { .. } => {}
 
@@ -1523,6 +1804,11 @@ impl TetraEntityTrait for UmacBs {
     }
 
     fn set_config(&mut self, config: SharedConfig) {
+        if config.config().cell.neighbor_cells != self.config.config().cell.neighbor_cells {
+            let removals = Self::generate_nwrk_broadcast_removals(&self.config, &config);
+            let broadcast = Self::generate_nwrk_broadcast(&config);
+            self.channel_scheduler.set_neighbor_broadcast(broadcast, removals);
+        }
         self.config = config;
     }
 
@@ -1544,10 +1830,14 @@ impl TetraEntityTrait for UmacBs {
                 self.rx_tlmb_prim(queue, message);
             }
             Sap::TlmcSap => {
-                unimplemented!();
+                self.rx_tlmc_prim(queue, message);
             }
             Sap::Control => {
-                self.rx_control(queue, message);
+                if let SapMsgInner::QueryStackState(_) = message.msg {
+                    self.rx_query_stack_state(queue, message.src);
+                } else {
+                    self.rx_control(queue, message);
+                }
             }
             _ => {
                 panic!()
@@ -1570,17 +1860,33 @@ impl TetraEntityTrait for UmacBs {
         // Check for UL inactivity (stuck transmitter detection)
         self.check_ul_inactivity(queue);
 
+        // Drop unfinished fragmented messages once per frame
+        if ts.t == 1 {
+            self.age_defrag_buffers(ts);
+        }
+
+        // Report call quality once every multiframe (18 frames)
+        if ts.f == 1 && ts.t == 1 {
+            self.report_call_quality();
+        }
+
+        // Refresh the cell load broadcast in SYNC
+        self.cell_load.update(self.channel_scheduler.num_active_timeslots());
+        self.channel_scheduler.set_cell_load(self.cell_load.load_indicator());
+
         // Collect/construct traffic that should be sent down to the LMAC
         // This is basically the _previous_ timeslot
-        let elem = self.channel_scheduler.finalize_ts_for_tick();
-        let s = SapMsg {
-            sap: Sap::TmvSap,
-            src: self.self_component,
-            dest: TetraEntity::Lmac,
-            msg: SapMsgInner::TmvUnitdataReq(elem),
+        let Some(elem) = self.channel_scheduler.finalize_ts_for_tick() else {
+            return;
         };
+        let s = SapMsg::new(
+            Sap::TmvSap,
+            self.self_component,
+            TetraEntity::Lmac,
+            SapMsgInner::TmvUnitdataReq(elem),
+        );
         tracing::trace!("UmacBs tick: Pushing finalized timeslot to LMAC: {:?}", s);
-        queue.push_back(s);
+        let _ = queue.push_back(s);
     }
 }
 
@@ -13,6 +13,7 @@ use tetra_pdus::umac::enums::broadcast_type::BroadcastType;
 use tetra_pdus::umac::enums::mac_pdu_type::MacPduType;
 use tetra_pdus::umac::pdus::access_assign::AccessAssign;
 use tetra_pdus::umac::pdus::access_assign_fr18::AccessAssignFr18;
+use tetra_pdus::umac::pdus::mac_d_blck::MacDBlck;
 use tetra_pdus::umac::pdus::mac_end_dl::MacEndDl;
 use tetra_pdus::umac::pdus::mac_frag_dl::MacFragDl;
 use tetra_pdus::umac::pdus::mac_resource::MacResource;
@@ -181,7 +182,7 @@ impl UmacMs {
         };
         assert!(prim.pdu.peek_bits(2).unwrap() == MacPduType::Broadcast.into_raw()); // MAC PDU type
 
-        let bits = prim.pdu.peek_bits_posoffset(2, 2).unwrap();
+        let bits = prim.pdu.peek_bits_at_offset(2, 2).unwrap();
         let bcast_type = BroadcastType::try_from(bits).expect("invalid broadcast type");
 
         match bcast_type {
@@ -224,32 +225,32 @@ impl UmacMs {
                 m: self.dltime.m,
                 h: pdu.hyperframe_number.unwrap(),
             };
-            let m = SapMsg {
-                sap: Sap::TmvSap,
-                src: self.self_component,
-                dest: TetraEntity::Lmac,
-                msg: SapMsgInner::TmvConfigureReq(TmvConfigureReq {
+            let m = SapMsg::new(
+                Sap::TmvSap,
+                self.self_component,
+                TetraEntity::Lmac,
+                SapMsgInner::TmvConfigureReq(TmvConfigureReq {
                     time: Some(t),
                     ..Default::default()
                 }),
-            };
+            );
             tracing::info!("rx_broadcast_sysinfo: Updated TdmaTime: {:?} -> {:?}", self.dltime, new_time);
-            queue.push_back(m);
+            let _ = queue.push_back(m);
         }
 
         let tlsdu = BitBuffer::from_bitbuffer_pos(&prim.pdu);
-        let m = SapMsg {
-            sap: Sap::TlmbSap,
-            src: TetraEntity::Umac,
-            dest: TetraEntity::Mle,
-            msg: SapMsgInner::TlmbSysinfoInd(TlmbSysinfoInd {
+        let m = SapMsg::new(
+            Sap::TlmbSap,
+            TetraEntity::Umac,
+            TetraEntity::Mle,
+            SapMsgInner::TlmbSysinfoInd(TlmbSysinfoInd {
                 endpoint_id: 0,
                 tl_sdu: tlsdu,
                 mac_broadcast_info: None,
             }),
-        };
+        );
 
-        queue.push_back(m);
+        let _ = queue.push_back(m);
     }
 
     fn rx_mac_resource(&mut self, queue: &mut MessageQueue, message: &mut SapMsg) {
@@ -277,10 +278,10 @@ impl UmacMs {
 
         // Compute len
         let mut pdu_len_bits = {
-            match pdu.length_ind {
+            match pdu.length_ind.get() {
                 0b000001..0b111010 => {
                     // tracing::trace!("rx_mac_resource: length_ind {}", pdu.length_ind);
-                    pdu.length_ind as usize * 8
+                    pdu.length_ind.get() as usize * 8
                 }
                 0b111110 => {
                     // Second half slot stolen in STCH
@@ -370,24 +371,24 @@ impl UmacMs {
 
             if sdu.is_some() {
                 // We have an SDU for the LLC, deliver it.
-                let m = SapMsg {
-                    sap: Sap::TmaSap,
-                    src: TetraEntity::Umac,
-                    dest: TetraEntity::Llc,
-                    msg: SapMsgInner::TmaUnitdataInd(TmaUnitdataInd {
+                let m = SapMsg::new(
+                    Sap::TmaSap,
+                    TetraEntity::Umac,
+                    TetraEntity::Llc,
+                    SapMsgInner::TmaUnitdataInd(TmaUnitdataInd {
                         pdu: sdu,
                         main_address: pdu.addr.unwrap(),
                         scrambling_code: prim.scrambling_code,
                         endpoint_id: 0,        // TODO FIXME
                         new_endpoint_id: None, // TODO FIXME
                         css_endpoint_id: None, // TODO FIXME
-                        air_interface_encryption: pdu.encryption_mode as Todo,
+                        air_interface_encryption: pdu.encryption_mode.get() as Todo,
                         chan_change_response_req: false,
                         chan_change_handle: None,
                         chan_info: None,
                     }),
-                };
-                queue.push_back(m);
+                );
+                let _ = queue.push_back(m);
             } else {
                 // Either this is a null pdu or we are at the end of the block
                 // For now, we don't deliver this. However, important data may need to be signalled upwards
@@ -501,11 +502,11 @@ impl UmacMs {
         // Pass block directly to LLC
         tracing::debug!("rx_mac_end: sdu: {:?}", defragbuf.buffer.dump_bin());
 
-        let m = SapMsg {
-            sap: Sap::TmaSap,
-            src: TetraEntity::Umac,
-            dest: TetraEntity::Llc,
-            msg: SapMsgInner::TmaUnitdataInd(TmaUnitdataInd {
+        let m = SapMsg::new(
+            Sap::TmaSap,
+            TetraEntity::Umac,
+            TetraEntity::Llc,
+            SapMsgInner::TmaUnitdataInd(TmaUnitdataInd {
                 pdu: Some(defragbuf.buffer),
                 main_address: defragbuf.addr,
                 scrambling_code: prim.scrambling_code,
@@ -517,8 +518,8 @@ impl UmacMs {
                 chan_change_handle: None,
                 chan_info: None,
             }),
-        };
-        queue.push_back(m);
+        );
+        let _ = queue.push_back(m);
 
         // Since this is not a null pdu, more MAC PDUs may follow
         // This allows parent function to continue parsing
@@ -543,7 +544,17 @@ impl UmacMs {
         };
         // Check we're indeed on the right channel (Clause 21.4.1 Table 21.48)
         assert!(prim.logical_channel != LogicalChannel::Stch && prim.logical_channel != LogicalChannel::SchHd);
-        unimplemented!("rx_supp");
+
+        // MAC-D-BLCK is the only supplementary DL PDU. It fills the slot, so nothing follows it
+        match MacDBlck::from_bitbuf(&mut prim.pdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {}", pdu);
+                unimplemented_log!("rx_supp: MAC-D-BLCK event label addressing not implemented, dropping");
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing MacDBlck: {:?} {}", e, prim.pdu.dump_bin());
+            }
+        }
     }
 
     pub fn rx_tmv_aach(&self, queue: &mut MessageQueue, mut message: SapMsg) {
@@ -556,7 +567,7 @@ impl UmacMs {
             panic!()
         };
 
-        let is_traffic = if self.dltime.f != 18 {
+        let is_traffic = if !self.dltime.is_frame18() {
             let pdu = match AccessAssign::from_bitbuf(&mut prim.pdu) {
                 Ok(pdu) => {
                     tracing::debug!("<- {:?}", pdu);
@@ -584,17 +595,17 @@ impl UmacMs {
             false
         };
 
-        let m = SapMsg {
-            sap: Sap::TmvSap,
-            src: TetraEntity::Umac,
-            dest: TetraEntity::Lmac,
-            msg: SapMsgInner::TmvConfigureReq(TmvConfigureReq {
+        let m = SapMsg::new(
+            Sap::TmvSap,
+            TetraEntity::Umac,
+            TetraEntity::Lmac,
+            SapMsgInner::TmvConfigureReq(TmvConfigureReq {
                 is_traffic: Some(is_traffic),
                 ..Default::default()
             }),
-        };
+        );
         // This message needs to be processed NOW since it affects the other blocks in this timeslot
-        queue.push_prio(m, MessagePrio::Immediate);
+        let _ = queue.push_prio(m, MessagePrio::Immediate);
     }
 
     pub fn rx_tmv_bsch(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
@@ -720,16 +731,16 @@ impl UmacMs {
                 self.scrambling_code.unwrap()
             );
 
-            let m = SapMsg {
-                sap: Sap::TmvSap,
-                src: self.self_component,
-                dest: TetraEntity::Lmac,
-                msg: SapMsgInner::TmvConfigureReq(TmvConfigureReq {
+            let m = SapMsg::new(
+                Sap::TmvSap,
+                self.self_component,
+                TetraEntity::Lmac,
+                SapMsgInner::TmvConfigureReq(TmvConfigureReq {
                     scrambling_code: self.scrambling_code,
                     ..Default::default()
                 }),
-            };
-            queue.push_back(m);
+            );
+            let _ = queue.push_back(m);
         }
     }
 
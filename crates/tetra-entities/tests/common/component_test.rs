@@ -20,6 +20,7 @@ use tetra_entities::umac::umac_ms::UmacMs;
 
 use crate::common::default_stack;
 
+use super::mock_entity::MockEntity;
 use super::sink::Sink;
 
 /// Infrastructure for testing TETRA components
@@ -101,7 +102,7 @@ impl ComponentTest {
                     self.register_entity(lmac);
                 }
                 TetraEntity::Umac => {
-                    let mut umac = UmacBs::new(self.config.clone());
+                    let mut umac = UmacBs::new(self.config.clone(), None);
                     // Prepare channel scheduler for next tick_start
                     umac.channel_scheduler.set_dl_time(self.start_dl_time.add_timeslots(-1));
                     self.router.register_entity(Box::new(umac));
@@ -188,13 +189,18 @@ impl ComponentTest {
     }
 
     pub fn submit_message(&mut self, message: SapMsg) {
-        self.router.submit_message(message);
+        self.router.submit_message(message).expect("router queue full");
     }
 
     pub fn deliver_all_messages(&mut self) {
         self.router.deliver_all_messages();
     }
 
+    /// Get a registered MockEntity for inspection, or None if the entity is not a MockEntity
+    pub fn get_mock(&mut self, entity: TetraEntity) -> Option<&mut MockEntity> {
+        self.router.get_entity(entity)?.as_any_mut().downcast_mut::<MockEntity>()
+    }
+
     pub fn dump_sinks(&mut self) -> Vec<SapMsg> {
         let mut msgs = vec![];
         for sink in self.sinks.iter() {
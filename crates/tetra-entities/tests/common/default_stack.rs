@@ -1,4 +1,9 @@
-use tetra_config::bluestation::{CfgCellInfo, CfgNetInfo, CfgPhyIo, PhyBackend, StackConfig, StackMode};
+use std::collections::HashMap;
+
+use tetra_config::bluestation::{
+    CURRENT_SCHEMA_VERSION, CfgCellInfo, CfgCmce, CfgLlc, CfgNetInfo, CfgPhyIo, DEFAULT_CLOCK_DRIFT_THRESHOLD_PPM, PhyBackend, StackConfig,
+    StackMode, SubscriberPolicy,
+};
 use tetra_core::{freqs::FreqInfo, ranges::SortedDisjointSsiRanges};
 
 /// Creates a default config for testing. It can still be modified as needed
@@ -11,11 +16,17 @@ pub fn default_test_config_bs() -> StackConfig {
 
     // Put together components and return this proto config
     StackConfig {
+        schema_version: CURRENT_SCHEMA_VERSION,
         stack_mode: StackMode::Bs,
         debug_log: None,
+        recording: None,
+        state_file: None,
+        tnmm_address: None,
         phy_io,
         net: net_info,
         cell: cell_info,
+        llc: CfgLlc::default(),
+        cmce: CfgCmce::default(),
         brew: None,
         telemetry: None,
         control: None,
@@ -30,6 +41,7 @@ pub fn default_phy_io() -> CfgPhyIo {
         ul_input_file: None,
         dl_input_file: None,
         soapysdr: None,
+        clock_drift_threshold_ppm: DEFAULT_CLOCK_DRIFT_THRESHOLD_PPM,
     }
 }
 
@@ -50,6 +62,7 @@ pub fn default_cell_info(freq_info: FreqInfo) -> CfgCellInfo {
         neighbor_cell_broadcast: 0,
         late_entry_supported: false,
         subscriber_class: 65535, // All subscriber classes allowed
+        ms_subscriber_classes: HashMap::new(),
         registration: true,
         deregistration: true,
         priority_cell: false,
@@ -69,6 +82,10 @@ pub fn default_cell_info(freq_info: FreqInfo) -> CfgCellInfo {
         ms_txpwr_max_cell: 4,
         local_ssi_ranges: SortedDisjointSsiRanges::from_vec_ssirange(vec![]),
         timezone: None,
+        d_release_repeat: 3,
+        subscriber_policy: SubscriberPolicy::AllowAll,
+        neighbor_cells: Vec::new(),
+        energy_economy: None,
     }
 }
 
@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+use tetra_core::Sap;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_entities::{MessageQueue, TetraEntityTrait};
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+type ReplyTrigger = Box<dyn Fn(&SapMsg) -> bool + Send>;
+
+/// A mock TETRA entity for testing individual components in isolation
+/// Like a Sink, it collects all received SapMsg messages. Additionally, it can be pre-loaded
+/// with canned replies, each sent back once when a received message matches its trigger.
+pub struct MockEntity {
+    component: TetraEntity,
+    received: Vec<SapMsg>,
+    replies: Vec<(ReplyTrigger, SapMsg)>,
+    /// Messages sent from the shutdown hook
+    shutdown_msgs: Vec<SapMsg>,
+    /// Number of messages received when the shutdown hook ran, None before shutdown
+    received_at_shutdown: Option<usize>,
+    /// Time spent in rx_prim for every received message, to simulate a slow entity
+    rx_delay: Duration,
+}
+
+impl MockEntity {
+    pub fn new(component: TetraEntity) -> Self {
+        Self {
+            component,
+            received: vec![],
+            replies: vec![],
+            shutdown_msgs: vec![],
+            received_at_shutdown: None,
+            rx_delay: Duration::ZERO,
+        }
+    }
+
+    /// Make rx_prim sleep for `delay` on every received message
+    pub fn set_rx_delay(&mut self, delay: Duration) {
+        self.rx_delay = delay;
+    }
+
+    /// Queue a message to be sent when the entity is shut down
+    pub fn add_shutdown_msg(&mut self, message: SapMsg) {
+        self.shutdown_msgs.push(message);
+    }
+
+    pub fn received_at_shutdown(&self) -> Option<usize> {
+        self.received_at_shutdown
+    }
+
+    /// Queue a canned reply, sent back the first time a received message satisfies `trigger`
+    pub fn add_reply(&mut self, trigger: impl Fn(&SapMsg) -> bool + Send + 'static, reply: SapMsg) {
+        self.replies.push((Box::new(trigger), reply));
+    }
+
+    pub fn received(&self) -> &[SapMsg] {
+        &self.received
+    }
+
+    pub fn take_received(&mut self) -> Vec<SapMsg> {
+        std::mem::take(&mut self.received)
+    }
+
+    /// Asserts that a message satisfying `matcher` was received on the given SAP, and returns the first one
+    pub fn assert_received(&self, sap: Sap, matcher: impl Fn(&SapMsgInner) -> bool) -> &SapMsg {
+        self.received.iter().find(|m| m.sap == sap && matcher(&m.msg)).unwrap_or_else(|| {
+            let got: Vec<_> = self.received.iter().map(|m| format!("{} on {:?}", m.msg, m.sap)).collect();
+            panic!(
+                "{:?} did not receive the expected message on {:?}, got: {:?}",
+                self.component, sap, got
+            )
+        })
+    }
+
+    /// Asserts that no message satisfying `matcher` was received on the given SAP
+    pub fn assert_not_received(&self, sap: Sap, matcher: impl Fn(&SapMsgInner) -> bool) {
+        if let Some(m) = self.received.iter().find(|m| m.sap == sap && matcher(&m.msg)) {
+            panic!("{:?} unexpectedly received {} on {:?}", self.component, m.msg, sap);
+        }
+    }
+}
+
+impl TetraEntityTrait for MockEntity {
+    fn entity(&self) -> TetraEntity {
+        self.component
+    }
+
+    fn rx_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        tracing::debug!("rx_prim: {:?}", message);
+        std::thread::sleep(self.rx_delay);
+
+        if let Some(index) = self.replies.iter().position(|(trigger, _)| trigger(&message)) {
+            let (_, reply) = self.replies.remove(index);
+            let _ = queue.push_back(reply);
+        }
+        self.received.push(message);
+    }
+
+    fn shutdown(&mut self, queue: &mut MessageQueue) {
+        self.received_at_shutdown = Some(self.received.len());
+        for message in self.shutdown_msgs.drain(..) {
+            let _ = queue.push_back(message);
+        }
+    }
+}
@@ -2,6 +2,8 @@
 
 pub mod component_test;
 pub mod default_stack;
+pub mod loopback;
+pub mod mock_entity;
 pub mod sink;
 
 pub use component_test::ComponentTest;
@@ -0,0 +1,276 @@
+mod common;
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde_json::Value;
+use tetra_config::bluestation::{CfgBrewDto, StackMode, apply_brew_patch};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Direction, Sap, SsiType, TetraAddress, debug};
+use tetra_entities::net_admin::{AdminServer, make_admin_link};
+use tetra_pdus::cmce::enums::party_type_identifier::PartyTypeIdentifier;
+use tetra_pdus::cmce::fields::basic_service_information::BasicServiceInformation;
+use tetra_pdus::cmce::pdus::u_setup::USetup;
+use tetra_saps::control::admin::{ReleaseCallReq, UTILIZATION_DL};
+use tetra_saps::control::brew::{BrewSubscriberAction, MmSubscriberUpdate};
+use tetra_saps::control::enums::circuit_mode_type::CircuitModeType;
+use tetra_saps::control::enums::communication_type::CommunicationType;
+use tetra_saps::lcmc::LcmcMleUnitdataInd;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+use crate::common::ComponentTest;
+
+const TEST_GSSI: u32 = 91;
+const TEST_ISSI: u32 = 1000001;
+
+fn subscriber_update(action: BrewSubscriberAction, groups: Vec<u32>) -> SapMsg {
+    SapMsg::new(
+        Sap::Control,
+        TetraEntity::Mm,
+        TetraEntity::Cmce,
+        SapMsgInner::MmSubscriberUpdate(MmSubscriberUpdate {
+            issi: TEST_ISSI,
+            groups,
+            action,
+        }),
+    )
+}
+
+/// Group U-SETUP from TEST_ISSI to TEST_GSSI
+fn build_group_u_setup() -> SapMsg {
+    let u_setup = USetup {
+        area_selection: 0,
+        hook_method_selection: false,
+        simplex_duplex_selection: false,
+        basic_service_information: BasicServiceInformation {
+            circuit_mode_type: CircuitModeType::TchS,
+            encryption_flag: false,
+            communication_type: CommunicationType::P2Mp,
+            slots_per_frame: None,
+            speech_service: Some(0),
+        },
+        request_to_transmit_send_data: false,
+        call_priority: 3,
+        clir_control: 0,
+        called_party_type_identifier: PartyTypeIdentifier::Ssi,
+        called_party_ssi: Some(TEST_GSSI as u64),
+        called_party_short_number_address: None,
+        called_party_extension: None,
+        external_subscriber_number: None,
+        facility: None,
+        dm_ms_address: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(80);
+    u_setup.to_bitbuf(&mut sdu).expect("Failed to serialize USetup");
+    sdu.seek(0);
+
+    SapMsg::new(
+        Sap::LcmcSap,
+        TetraEntity::Mle,
+        TetraEntity::Cmce,
+        SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
+            sdu,
+            handle: 1,
+            endpoint_id: 1,
+            link_id: 1,
+            received_tetra_address: TetraAddress::new(TEST_ISSI, SsiType::Issi),
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+        }),
+    )
+}
+
+/// Send one command line and read the response line
+fn command(conn: &mut BufReader<TcpStream>, line: &str) -> Value {
+    writeln!(conn.get_mut(), "{}", line).unwrap();
+    let mut response = String::new();
+    conn.read_line(&mut response).unwrap();
+    serde_json::from_str(&response).unwrap_or_else(|e| panic!("invalid JSON response {:?}: {}", response, e))
+}
+
+#[test]
+fn test_admin_commands() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, None);
+    test.populate_entities(
+        vec![TetraEntity::Llc, TetraEntity::Mm, TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac],
+    );
+
+    // Registered and affiliated subscriber with an ongoing group call
+    {
+        let mut state = test.config.state_write();
+        state.subscribers.register(TEST_ISSI);
+        state.subscribers.affiliate(TEST_ISSI, TEST_GSSI);
+    }
+    test.submit_message(subscriber_update(BrewSubscriberAction::Register, vec![]));
+    test.submit_message(subscriber_update(BrewSubscriberAction::Affiliate, vec![TEST_GSSI]));
+    test.run_stack(Some(1));
+    test.submit_message(build_group_u_setup());
+    test.run_stack(Some(4));
+
+    // Run the stack in the background with the admin server attached
+    let (client, link) = make_admin_link();
+    let config_path = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/../../example_config/config.toml"));
+    let mut server = AdminServer::bind(0, client, Some(config_path)).unwrap();
+    let port = server.local_port();
+    std::thread::spawn(move || server.run());
+
+    let mut router = test.router;
+    router.set_admin_link(link);
+    let running = Arc::new(AtomicBool::new(true));
+    let running_clone = running.clone();
+    let stack = std::thread::spawn(move || router.run_stack(None, Some(running_clone)));
+
+    let mut conn = BufReader::new(TcpStream::connect(("127.0.0.1", port)).unwrap());
+
+    let stats = command(&mut conn, r#"{ "cmd": "stats" }"#);
+    assert_eq!(stats["ok"], true, "{}", stats);
+    assert!(stats["llc"]["bl_data_tx"].is_u64());
+
+    let registered = command(&mut conn, r#"{ "cmd": "registered_ms" }"#);
+    assert_eq!(registered["ok"], true, "{}", registered);
+    assert_eq!(registered["registered_ms"][0]["issi"], TEST_ISSI);
+    assert_eq!(registered["registered_ms"][0]["groups"][0], TEST_GSSI);
+
+    let calls = command(&mut conn, r#"{ "cmd": "active_calls" }"#);
+    assert_eq!(calls["ok"], true, "{}", calls);
+    let call = &calls["active_calls"][0];
+    assert_eq!(call["type"], "group");
+    assert_eq!(call["origin"], "local");
+    assert_eq!(call["source_ssi"], TEST_ISSI);
+    assert_eq!(call["dest_ssi"], TEST_GSSI);
+    assert_eq!(call["priority"], 3);
+    let call_id = call["call_id"].as_u64().unwrap();
+
+    let released = command(&mut conn, &format!(r#"{{ "cmd": "release_call", "call_id": {} }}"#, call_id));
+    assert_eq!(released["ok"], true, "{}", released);
+    let calls = command(&mut conn, r#"{ "cmd": "active_calls" }"#);
+    assert_eq!(calls["active_calls"].as_array().unwrap().len(), 0);
+    let released = command(&mut conn, &format!(r#"{{ "cmd": "release_call", "call_id": {} }}"#, call_id));
+    assert_eq!(released["ok"], false);
+
+    let reloaded = command(&mut conn, r#"{ "cmd": "reload_config" }"#);
+    assert_eq!(reloaded["ok"], true, "{}", reloaded);
+
+    let invalid = command(&mut conn, r#"{ "cmd": "reboot" }"#);
+    assert_eq!(invalid["ok"], false);
+    assert!(invalid["error"].is_string());
+
+    running.store(false, Ordering::SeqCst);
+    stack.join().unwrap();
+}
+
+#[test]
+fn test_stack_state_snapshot() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, None);
+    test.populate_entities(
+        vec![
+            TetraEntity::Mle,
+            TetraEntity::Llc,
+            TetraEntity::Umac,
+            TetraEntity::Mm,
+            TetraEntity::Cmce,
+        ],
+        vec![TetraEntity::Lmac],
+    );
+
+    let snapshot = test.router.get_stack_state();
+    assert!(snapshot.registered_ms.is_empty() && snapshot.active_calls.is_empty() && snapshot.active_circuits.is_empty());
+
+    {
+        let mut state = test.config.state_write();
+        state.subscribers.register(TEST_ISSI);
+        state.subscribers.affiliate(TEST_ISSI, TEST_GSSI);
+    }
+    test.submit_message(subscriber_update(BrewSubscriberAction::Register, vec![]));
+    test.submit_message(subscriber_update(BrewSubscriberAction::Affiliate, vec![TEST_GSSI]));
+    test.run_stack(Some(1));
+    test.submit_message(build_group_u_setup());
+    test.run_stack(Some(4));
+
+    let snapshot = test.router.get_stack_state();
+    assert_eq!(snapshot.registered_ms.len(), 1);
+    assert_eq!(snapshot.registered_ms[0].issi, TEST_ISSI);
+    assert_eq!(snapshot.registered_ms[0].groups, vec![TEST_GSSI]);
+
+    assert_eq!(snapshot.active_calls.len(), 1);
+    let call = &snapshot.active_calls[0];
+    assert!(call.group);
+    assert_eq!(call.dest_ssi, TEST_GSSI);
+    assert_eq!(call.source_ssi, TEST_ISSI);
+    assert!((2..=4).contains(&call.ts), "call on unexpected timeslot {}", call.ts);
+
+    // The call's circuit is open in both directions on the same timeslot
+    let directions: Vec<Direction> = snapshot
+        .active_circuits
+        .iter()
+        .filter(|circuit| circuit.ts == call.ts)
+        .map(|circuit| circuit.direction)
+        .collect();
+    assert_eq!(directions, vec![Direction::Dl, Direction::Ul]);
+
+    // Once the circuit carries traffic, the timeslot shows up as used
+    test.run_stack(Some(8));
+    let utilization = test.router.get_stack_state().frame_utilization;
+    assert_ne!(utilization.last().unwrap().bitmap[call.ts as usize - 1] & UTILIZATION_DL, 0);
+
+    // Releasing the call clears it from the snapshot
+    test.submit_message(SapMsg::new(
+        Sap::Control,
+        TetraEntity::Admin,
+        TetraEntity::Cmce,
+        SapMsgInner::ReleaseCallReq(ReleaseCallReq { call_id: call.call_id }),
+    ));
+    test.run_stack(Some(1));
+    assert!(test.router.get_stack_state().active_calls.is_empty());
+
+    // The circuit stays open until its repeated D-RELEASE was stolen from the traffic channel
+    let d_release_repeat = test.config.config().cell.d_release_repeat as usize;
+    test.run_stack(Some((d_release_repeat + 1) * 4));
+    let snapshot = test.router.get_stack_state();
+    assert!(snapshot.active_calls.is_empty());
+    assert!(snapshot.active_circuits.is_empty());
+}
+
+/// Registered subscribers in the snapshot carry the alias configured for them in [brew.aliases]
+#[test]
+fn test_stack_state_aliases() {
+    debug::setup_logging_verbose();
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.brew = Some(
+        apply_brew_patch(CfgBrewDto {
+            aliases: HashMap::from([
+                (TEST_ISSI.to_string(), "Unit 1".to_string()),
+                ((TEST_ISSI + 1).to_string(), "Dispatch".to_string()),
+            ]),
+            ..Default::default()
+        })
+        .unwrap(),
+    );
+    let mut test = ComponentTest::from_config(config, None);
+    test.populate_entities(vec![TetraEntity::Mm], vec![]);
+    {
+        let mut state = test.config.state_write();
+        for issi in TEST_ISSI..TEST_ISSI + 3 {
+            state.subscribers.register(issi);
+        }
+    }
+
+    let snapshot = test.router.get_stack_state();
+    let aliases: Vec<(u32, Option<&str>)> = snapshot.registered_ms.iter().map(|ms| (ms.issi, ms.alias.as_deref())).collect();
+    assert_eq!(
+        aliases,
+        vec![
+            (TEST_ISSI, Some("Unit 1")),
+            (TEST_ISSI + 1, Some("Dispatch")),
+            (TEST_ISSI + 2, None)
+        ]
+    );
+}
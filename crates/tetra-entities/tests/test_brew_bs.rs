@@ -0,0 +1,468 @@
+mod common;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{Receiver, Sender, unbounded};
+use tetra_config::bluestation::{CfgBrew, DEFAULT_BREW_PRIORITY_MAP, StackMode};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{Sap, TdmaTime, debug};
+use tetra_entities::net_brew::entity::BrewEntity;
+use tetra_entities::net_brew::protocol::{
+    BREW_SUBSCRIBER_DEREGISTER, BREW_SUBSCRIBER_REGISTER, BrewCallPayload, BrewMessage, build_group_tx, build_voice_frame,
+    parse_brew_message,
+};
+use tetra_entities::network::transports::{NetworkAddress, NetworkError, NetworkMessage, NetworkTransport, TransportMetrics};
+use tetra_saps::control::brew::{BrewSubscriberAction, MmSubscriberUpdate};
+use tetra_saps::control::call_control::CallControl;
+use tetra_saps::control::transport_metrics::QueryTransportMetrics;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tracing::field::{Field, Visit};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::{Layer, Registry};
+use uuid::Uuid;
+
+use crate::common::ComponentTest;
+
+const MONITORED_GSSI: u32 = 91;
+const NORMAL_GSSI: u32 = 92;
+
+/// Transport backed by channels to the test, which plays the Brew server
+struct ChannelTransport {
+    inbound: Receiver<Vec<u8>>,
+    outbound: Sender<Vec<u8>>,
+    connected: bool,
+    bytes_sent: u64,
+}
+
+impl NetworkTransport for ChannelTransport {
+    fn connect(&mut self) -> Result<(), NetworkError> {
+        self.connected = true;
+        Ok(())
+    }
+
+    fn send_reliable(&mut self, payload: &[u8]) -> Result<(), NetworkError> {
+        self.bytes_sent += payload.len() as u64;
+        let _ = self.outbound.send(payload.to_vec());
+        Ok(())
+    }
+
+    fn send_unreliable(&mut self, _payload: &[u8]) -> Result<(), NetworkError> {
+        Ok(())
+    }
+
+    fn receive_reliable(&mut self) -> Vec<NetworkMessage> {
+        // Avoid spinning the worker thread
+        std::thread::sleep(Duration::from_millis(1));
+        self.inbound
+            .try_iter()
+            .map(|payload| NetworkMessage {
+                source: NetworkAddress::Custom {
+                    scheme: "test".into(),
+                    address: "brew".into(),
+                },
+                payload,
+                timestamp: Instant::now(),
+            })
+            .collect()
+    }
+
+    fn receive_unreliable(&mut self) -> Vec<NetworkMessage> {
+        vec![]
+    }
+
+    fn wait_for_response_reliable(&mut self) -> Result<NetworkMessage, NetworkError> {
+        Err(NetworkError::Timeout)
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    fn disconnect(&mut self) {
+        self.connected = false;
+    }
+
+    fn metrics(&self) -> TransportMetrics {
+        TransportMetrics {
+            bytes_sent: self.bytes_sent,
+            ..Default::default()
+        }
+    }
+}
+
+fn get_brew_config() -> CfgBrew {
+    CfgBrew {
+        host: "test.local".into(),
+        port: 3000,
+        tls: false,
+        username: None,
+        password: None,
+        reconnect_delay: Duration::from_secs(1),
+        jitter_buffer_frames: 4,
+        jitter_initial_latency_frames: 0,
+        recording_dir: None,
+        feature_sds_enabled: true,
+        whitelisted_ssis: None,
+        monitoring_only: vec![],
+        alias_map: HashMap::new(),
+        priority_map: DEFAULT_BREW_PRIORITY_MAP,
+    }
+}
+
+/// Set up a stack with a Brew entity behind a ChannelTransport and sinks for CMCE and UMAC.
+/// Returns the test, the sender used to inject messages from the Brew server and the
+/// receiver for messages sent to the Brew server.
+fn get_brew_test(brew_config: CfgBrew) -> (ComponentTest, Sender<Vec<u8>>, Receiver<Vec<u8>>) {
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.brew = Some(brew_config);
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+
+    let (inbound_sender, inbound) = unbounded();
+    let (outbound, outbound_receiver) = unbounded();
+    let transport = ChannelTransport {
+        inbound,
+        outbound,
+        connected: false,
+        bytes_sent: 0,
+    };
+    test.register_entity(BrewEntity::new(test.get_shared_config(), transport));
+    test.populate_entities(vec![], vec![TetraEntity::Cmce, TetraEntity::Umac]);
+    (test, inbound_sender, outbound_receiver)
+}
+
+/// Run the stack until the predicate holds for the collected sink output, or panic after a timeout
+fn run_until(test: &mut ComponentTest, msgs: &mut Vec<SapMsg>, pred: impl Fn(&[SapMsg]) -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !pred(msgs) {
+        assert!(Instant::now() < deadline, "Timed out waiting for expected stack output");
+        test.run_stack(Some(1));
+        msgs.extend(test.dump_sinks());
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+/// Captured (message, issi, alias) of tracing events that carry an alias field
+type AliasEvents = Arc<Mutex<Vec<(String, u64, String)>>>;
+
+/// Tracing layer that records events carrying an alias field
+struct AliasCapture(AliasEvents);
+
+#[derive(Default)]
+struct AliasVisitor {
+    message: String,
+    issi: u64,
+    alias: Option<String>,
+}
+
+impl Visit for AliasVisitor {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "issi" {
+            self.issi = value;
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "alias" {
+            self.alias = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        }
+    }
+}
+
+impl<S: tracing::Subscriber> Layer<S> for AliasCapture {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = AliasVisitor::default();
+        event.record(&mut visitor);
+        if let Some(alias) = visitor.alias {
+            self.0.lock().unwrap().push((visitor.message, visitor.issi, alias));
+        }
+    }
+}
+
+fn floor_granted(call_id: u16, source_issi: u32, ts: u8, priority: u8) -> SapMsg {
+    SapMsg::new(
+        Sap::Control,
+        TetraEntity::Cmce,
+        TetraEntity::Brew,
+        SapMsgInner::CmceCallControl(CallControl::FloorGranted {
+            call_id,
+            source_issi,
+            dest_gssi: NORMAL_GSSI,
+            ts,
+            priority,
+        }),
+    )
+}
+
+fn network_call_starts(msgs: &[SapMsg]) -> Vec<u32> {
+    msgs.iter()
+        .filter_map(|msg| match &msg.msg {
+            SapMsgInner::CmceCallControl(CallControl::NetworkCallStart { dest_gssi, .. }) => Some(*dest_gssi),
+            _ => None,
+        })
+        .collect()
+}
+
+fn dl_voice_timeslots(msgs: &[SapMsg]) -> Vec<u8> {
+    msgs.iter()
+        .filter_map(|msg| match &msg.msg {
+            SapMsgInner::TmdCircuitDataReq(prim) if msg.dest == TetraEntity::Umac => Some(prim.ts),
+            _ => None,
+        })
+        .collect()
+}
+
+fn network_call_ready(brew_uuid: Uuid, call_id: u16, ts: u8) -> SapMsg {
+    SapMsg::new(
+        Sap::Control,
+        TetraEntity::Cmce,
+        TetraEntity::Brew,
+        SapMsgInner::CmceCallControl(CallControl::NetworkCallReady {
+            brew_uuid,
+            call_id,
+            ts,
+            usage: call_id as u8 + 4,
+        }),
+    )
+}
+
+/// Calls from Brew to a monitoring-only group are neither set up nor transmitted,
+/// while a call to a normal group is set up and its voice is played out on the DL.
+#[test]
+fn test_monitoring_only_group_gets_no_dl_audio() {
+    debug::setup_logging_verbose();
+    let mut brew_config = get_brew_config();
+    brew_config.monitoring_only = vec![MONITORED_GSSI];
+    let (mut test, brew_server, _) = get_brew_test(brew_config);
+    let monitored_uuid = Uuid::new_v4();
+    let normal_uuid = Uuid::new_v4();
+
+    // Monitored call first: once the normal call is seen, the monitored GROUP_TX was processed as well
+    brew_server
+        .send(build_group_tx(&monitored_uuid, 2000001, MONITORED_GSSI, 0, 0))
+        .unwrap();
+    brew_server.send(build_group_tx(&normal_uuid, 2000002, NORMAL_GSSI, 0, 0)).unwrap();
+    let mut msgs = vec![];
+    run_until(&mut test, &mut msgs, |msgs| network_call_starts(msgs).contains(&NORMAL_GSSI));
+    assert_eq!(network_call_starts(&msgs), vec![NORMAL_GSSI]);
+
+    // Even if CMCE reported resources for the monitored call, its audio must not reach UMAC
+    test.submit_message(network_call_ready(monitored_uuid, 1, 3));
+    test.submit_message(network_call_ready(normal_uuid, 2, 2));
+    test.run_stack(Some(1));
+    test.dump_sinks();
+
+    for _ in 0..12 {
+        brew_server.send(build_voice_frame(&monitored_uuid, 274, &[0u8; 36])).unwrap();
+        brew_server.send(build_voice_frame(&normal_uuid, 274, &[0u8; 36])).unwrap();
+    }
+    let mut msgs = vec![];
+    run_until(&mut test, &mut msgs, |msgs| dl_voice_timeslots(msgs).len() >= 4);
+    test.run_stack(Some(72));
+    msgs.extend(test.dump_sinks());
+
+    let timeslots = dl_voice_timeslots(&msgs);
+    assert!(timeslots.iter().all(|&ts| ts == 2), "DL audio for monitored group: {:?}", timeslots);
+}
+
+/// Call log events for floor grants, floor releases and call ends carry the configured alias of the ISSI
+#[test]
+fn test_call_log_aliases() {
+    let mut brew_config = get_brew_config();
+    brew_config.alias_map = HashMap::from([
+        (2041234, "Unit 1".to_string()),
+        (2041235, "Dispatch".to_string()),
+        (2041236, "Unit 2".to_string()),
+    ]);
+    let (mut test, _brew_server, _) = get_brew_test(brew_config);
+
+    let events = AliasEvents::default();
+    let subscriber = Registry::default().with(AliasCapture(events.clone()));
+    tracing::subscriber::with_default(subscriber, || {
+        test.submit_message(floor_granted(1, 2041234, 2, 0));
+        test.submit_message(floor_granted(2, 2041235, 3, 0));
+        test.submit_message(floor_granted(3, 2041236, 4, 0));
+        test.deliver_all_messages();
+
+        // Talker change to an ISSI without alias, then the end of that call
+        test.submit_message(floor_granted(3, 2049999, 4, 0));
+        test.submit_message(SapMsg::new(
+            Sap::Control,
+            TetraEntity::Cmce,
+            TetraEntity::Brew,
+            SapMsgInner::CmceCallControl(CallControl::CallEnded { call_id: 1, ts: 2 }),
+        ));
+        test.deliver_all_messages();
+    });
+
+    let events = events.lock().unwrap().clone();
+    let granted: Vec<(u64, &str)> = events
+        .iter()
+        .filter(|(msg, _, _)| msg.contains("floor granted"))
+        .map(|(_, issi, alias)| (*issi, alias.as_str()))
+        .collect();
+    assert_eq!(
+        granted,
+        vec![(2041234, "Unit 1"), (2041235, "Dispatch"), (2041236, "Unit 2"), (2049999, "")]
+    );
+    let ended: Vec<(u64, &str)> = events
+        .iter()
+        .filter(|(msg, _, _)| msg.contains("call ended"))
+        .map(|(_, issi, alias)| (*issi, alias.as_str()))
+        .collect();
+    assert_eq!(ended, vec![(2041234, "Unit 1")]);
+}
+
+/// Priorities are mapped through the configured table: TETRA to Brew for local calls forwarded
+/// to Brew, and Brew back to TETRA for calls coming from Brew
+#[test]
+fn test_priority_map() {
+    debug::setup_logging_verbose();
+    let mut brew_config = get_brew_config();
+    brew_config.priority_map = [0, 10, 20, 30, 40, 50, 60, 70, 80, 90, 100, 110, 120, 130, 140, 200];
+    let (mut test, brew_server, brew_sent) = get_brew_test(brew_config);
+
+    // Wait for the worker to connect, local calls are only forwarded while connected
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !test.config.state_read().network_connected {
+        assert!(Instant::now() < deadline, "Brew worker did not connect");
+        test.run_stack(Some(1));
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    // Local emergency call: GROUP_TX to Brew carries the mapped priority
+    test.submit_message(floor_granted(1, 2041234, 2, 15));
+    test.deliver_all_messages();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let group_tx = loop {
+        let payload = brew_sent.recv_deadline(deadline).expect("No GROUP_TX sent to Brew");
+        if let Ok(BrewMessage::CallControl(cc)) = parse_brew_message(&payload)
+            && let BrewCallPayload::GroupTransmission(gt) = cc.payload
+        {
+            break gt;
+        }
+    };
+    assert_eq!(group_tx.source, 2041234);
+    assert_eq!(group_tx.priority, 200);
+
+    // Call from Brew with priority 75 maps to TETRA priority 7
+    brew_server
+        .send(build_group_tx(&Uuid::new_v4(), 2000001, NORMAL_GSSI, 75, 0))
+        .unwrap();
+    let mut msgs = vec![];
+    run_until(&mut test, &mut msgs, |msgs| !network_call_starts(msgs).is_empty());
+    let priorities: Vec<u8> = msgs
+        .iter()
+        .filter_map(|msg| match &msg.msg {
+            SapMsgInner::CmceCallControl(CallControl::NetworkCallStart { priority, .. }) => Some(*priority),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(priorities, vec![7]);
+}
+
+/// QueryTransportMetrics is answered by the Brew entity with the counters of its transport
+#[test]
+fn test_transport_metrics_query() {
+    debug::setup_logging_verbose();
+    let (mut test, _brew_server, brew_sent) = get_brew_test(get_brew_config());
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !test.config.state_read().network_connected {
+        assert!(Instant::now() < deadline, "Brew worker did not connect");
+        test.run_stack(Some(1));
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    // Generate some traffic towards Brew
+    test.submit_message(floor_granted(1, 2041234, 2, 0));
+    test.deliver_all_messages();
+    let first = brew_sent
+        .recv_deadline(Instant::now() + Duration::from_secs(5))
+        .expect("Nothing sent to Brew");
+
+    test.submit_message(SapMsg::new(
+        Sap::Control,
+        TetraEntity::Cmce,
+        TetraEntity::Brew,
+        SapMsgInner::QueryTransportMetrics(QueryTransportMetrics),
+    ));
+    let mut msgs = vec![];
+    run_until(&mut test, &mut msgs, |msgs| {
+        msgs.iter().any(|msg| matches!(msg.msg, SapMsgInner::TransportMetricsReply(_)))
+    });
+    let metrics = msgs
+        .iter()
+        .find_map(|msg| match &msg.msg {
+            SapMsgInner::TransportMetricsReply(metrics) => Some(metrics.clone()),
+            _ => None,
+        })
+        .unwrap();
+
+    // The worker answers commands in order, so everything sent before the reply is counted
+    let sent: u64 = std::iter::once(first).chain(brew_sent.try_iter()).map(|p| p.len() as u64).sum();
+    assert_eq!(metrics.bytes_sent, sent);
+    assert_eq!(metrics.send_errors, 0);
+}
+
+/// On shutdown, network calls are ended towards CMCE and the worker deregisters its subscribers before closing
+#[test]
+fn test_shutdown() {
+    debug::setup_logging_verbose();
+    let (mut test, brew_server, brew_sent) = get_brew_test(get_brew_config());
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !test.config.state_read().network_connected {
+        assert!(Instant::now() < deadline, "Brew worker did not connect");
+        test.run_stack(Some(1));
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    test.submit_message(SapMsg::new(
+        Sap::Control,
+        TetraEntity::Mm,
+        TetraEntity::Brew,
+        SapMsgInner::MmSubscriberUpdate(MmSubscriberUpdate {
+            issi: 2041234,
+            groups: vec![],
+            action: BrewSubscriberAction::Register,
+        }),
+    ));
+    test.deliver_all_messages();
+    let subscriber_msg = |msg_type: u8| {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let payload = brew_sent
+                .recv_deadline(deadline)
+                .expect("Expected subscriber message not sent to Brew");
+            if let Ok(BrewMessage::Subscriber(sub)) = parse_brew_message(&payload)
+                && sub.msg_type == msg_type
+            {
+                break sub.number;
+            }
+        }
+    };
+    assert_eq!(subscriber_msg(BREW_SUBSCRIBER_REGISTER), 2041234);
+
+    let uuid = Uuid::new_v4();
+    brew_server.send(build_group_tx(&uuid, 2000001, NORMAL_GSSI, 0, 0)).unwrap();
+    let mut msgs = vec![];
+    run_until(&mut test, &mut msgs, |msgs| !network_call_starts(msgs).is_empty());
+
+    test.router.shutdown();
+    let msgs = test.dump_sinks();
+    assert!(msgs.iter().any(|msg| matches!(
+        msg.msg,
+        SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid }) if brew_uuid == uuid
+    )));
+    assert!(!test.config.state_read().network_connected);
+    assert_eq!(subscriber_msg(BREW_SUBSCRIBER_DEREGISTER), 2041234);
+}
@@ -1,54 +1,98 @@
 mod common;
 
+use std::sync::{Arc, Mutex};
+
 use tetra_config::bluestation::StackMode;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, TxState, debug};
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, TimeslotAllocator, TimeslotOwner, TxState, debug};
+use tetra_pdus::cmce::enums::cmce_pdu_type_dl::CmcePduTypeDl;
+use tetra_pdus::cmce::enums::disconnect_cause::DisconnectCause;
 use tetra_pdus::cmce::enums::party_type_identifier::PartyTypeIdentifier;
+use tetra_pdus::cmce::enums::transmission_grant::TransmissionGrant;
 use tetra_pdus::cmce::fields::basic_service_information::BasicServiceInformation;
+use tetra_pdus::cmce::pdus::d_alert::DAlert;
+use tetra_pdus::cmce::pdus::d_connect::DConnect;
+use tetra_pdus::cmce::pdus::d_release::DRelease;
+use tetra_pdus::cmce::pdus::d_setup::DSetup;
+use tetra_pdus::cmce::pdus::u_alert::UAlert;
+use tetra_pdus::cmce::pdus::u_connect::UConnect;
+use tetra_pdus::cmce::pdus::u_disconnect::UDisconnect;
+use tetra_pdus::cmce::pdus::u_release::URelease;
 use tetra_pdus::cmce::pdus::u_setup::USetup;
 use tetra_saps::control::brew::{BrewSubscriberAction, MmSubscriberUpdate};
+use tetra_saps::control::call_control::CallControl;
 use tetra_saps::control::enums::circuit_mode_type::CircuitModeType;
 use tetra_saps::control::enums::communication_type::CommunicationType;
 use tetra_saps::lcmc::LcmcMleUnitdataInd;
 use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tracing_subscriber::fmt::format::FmtSpan;
+
+use tetra_entities::cmce::subentities::cc_bs::{T309_TIMESLOTS, T310_TIMESLOTS};
 
 use crate::common::ComponentTest;
 
 const TEST_GSSI: u32 = 91;
 const TEST_ISSI: u32 = 1000001;
+const TEST_CALLED_ISSI: u32 = 1000002;
+
+/// Hold time of timeslots restored from the state file, ~10 seconds
+const RESTORED_TIMESLOT_HOLD_TIMESLOTS: usize = 10 * 18 * 4;
 
 /// Helper: register a subscriber on a GSSI so CMCE accepts calls for that group.
 fn register_subscriber(test: &mut ComponentTest, issi: u32, gssi: u32) {
-    let register = SapMsg {
-        sap: Sap::Control,
-        src: TetraEntity::Mm,
-        dest: TetraEntity::Cmce,
-        msg: SapMsgInner::MmSubscriberUpdate(MmSubscriberUpdate {
+    let register = SapMsg::new(
+        Sap::Control,
+        TetraEntity::Mm,
+        TetraEntity::Cmce,
+        SapMsgInner::MmSubscriberUpdate(MmSubscriberUpdate {
             issi,
             groups: vec![],
             action: BrewSubscriberAction::Register,
         }),
-    };
+    );
     test.submit_message(register);
     test.run_stack(Some(1));
 
-    let affiliate = SapMsg {
-        sap: Sap::Control,
-        src: TetraEntity::Mm,
-        dest: TetraEntity::Cmce,
-        msg: SapMsgInner::MmSubscriberUpdate(MmSubscriberUpdate {
+    let affiliate = SapMsg::new(
+        Sap::Control,
+        TetraEntity::Mm,
+        TetraEntity::Cmce,
+        SapMsgInner::MmSubscriberUpdate(MmSubscriberUpdate {
             issi,
             groups: vec![gssi],
             action: BrewSubscriberAction::Affiliate,
         }),
-    };
+    );
     test.submit_message(affiliate);
     test.run_stack(Some(1));
     test.dump_sinks();
 }
 
-/// Helper: build a U-SETUP SAP message for a group call.
-fn build_u_setup_msg(calling_issi: u32, dest_gssi: u32) -> SapMsg {
+/// Helper: wrap an uplink CMCE PDU into an LCMC indication from the given ISSI.
+fn build_ul_msg(issi: u32, sdu: BitBuffer) -> SapMsg {
+    SapMsg::new(
+        Sap::LcmcSap,
+        TetraEntity::Mle,
+        TetraEntity::Cmce,
+        SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
+            sdu,
+            handle: 1,
+            endpoint_id: 1,
+            link_id: 1,
+            received_tetra_address: TetraAddress::new(issi, SsiType::Issi),
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+        }),
+    )
+}
+
+/// Helper: build a U-SETUP SAP message for a group (P2Mp) or individual (P2p) call.
+fn build_u_setup_msg(calling_issi: u32, called_ssi: u32, communication_type: CommunicationType) -> SapMsg {
+    build_u_setup_msg_with_priority(calling_issi, called_ssi, communication_type, 0)
+}
+
+/// Helper: build a U-SETUP SAP message with the given call priority, 15 being an emergency call.
+fn build_u_setup_msg_with_priority(calling_issi: u32, called_ssi: u32, communication_type: CommunicationType, call_priority: u8) -> SapMsg {
     let u_setup = USetup {
         area_selection: 0,
         hook_method_selection: false,
@@ -56,15 +100,15 @@ fn build_u_setup_msg(calling_issi: u32, dest_gssi: u32) -> SapMsg {
         basic_service_information: BasicServiceInformation {
             circuit_mode_type: CircuitModeType::TchS,
             encryption_flag: false,
-            communication_type: CommunicationType::P2Mp,
+            communication_type,
             slots_per_frame: None,
             speech_service: Some(0),
         },
         request_to_transmit_send_data: false,
-        call_priority: 0,
+        call_priority,
         clir_control: 0,
         called_party_type_identifier: PartyTypeIdentifier::Ssi,
-        called_party_ssi: Some(dest_gssi as u64),
+        called_party_ssi: Some(called_ssi as u64),
         called_party_short_number_address: None,
         called_party_extension: None,
         external_subscriber_number: None,
@@ -76,21 +120,125 @@ fn build_u_setup_msg(calling_issi: u32, dest_gssi: u32) -> SapMsg {
     let mut sdu = BitBuffer::new_autoexpand(80);
     u_setup.to_bitbuf(&mut sdu).expect("Failed to serialize USetup");
     sdu.seek(0);
+    build_ul_msg(calling_issi, sdu)
+}
 
-    SapMsg {
-        sap: Sap::LcmcSap,
-        src: TetraEntity::Mle,
-        dest: TetraEntity::Cmce,
-        msg: SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
-            sdu,
-            handle: 1,
-            endpoint_id: 1,
-            link_id: 1,
-            received_tetra_address: TetraAddress::new(calling_issi, SsiType::Issi),
-            chan_change_resp_req: false,
-            chan_change_handle: None,
-        }),
-    }
+/// Helper: build a U-ALERT SAP message from the called MS.
+fn build_u_alert_msg(called_issi: u32, call_id: u16) -> SapMsg {
+    let u_alert = UAlert {
+        call_identifier: call_id,
+        reserved: true,
+        simplex_duplex_selection: false,
+        basic_service_information: None,
+        facility: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    u_alert.to_bitbuf(&mut sdu).expect("Failed to serialize UAlert");
+    sdu.seek(0);
+    build_ul_msg(called_issi, sdu)
+}
+
+/// Helper: build a U-CONNECT SAP message from the called MS.
+fn build_u_connect_msg(called_issi: u32, call_id: u16) -> SapMsg {
+    let u_connect = UConnect {
+        call_identifier: call_id,
+        hook_method_selection: false,
+        simplex_duplex_selection: false,
+        basic_service_information: None,
+        facility: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    u_connect.to_bitbuf(&mut sdu).expect("Failed to serialize UConnect");
+    sdu.seek(0);
+    build_ul_msg(called_issi, sdu)
+}
+
+/// Helper: build a U-DISCONNECT SAP message.
+fn build_u_disconnect_msg(issi: u32, call_id: u16) -> SapMsg {
+    let u_disconnect = UDisconnect {
+        call_identifier: call_id,
+        disconnect_cause: DisconnectCause::UserRequestedDisconnection,
+        facility: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    u_disconnect.to_bitbuf(&mut sdu).expect("Failed to serialize UDisconnect");
+    sdu.seek(0);
+    build_ul_msg(issi, sdu)
+}
+
+/// Helper: build a U-RELEASE SAP message.
+fn build_u_release_msg(issi: u32, call_id: u16) -> SapMsg {
+    let u_release = URelease {
+        call_identifier: call_id,
+        disconnect_cause: DisconnectCause::UserRequestedDisconnection,
+        facility: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    u_release.to_bitbuf(&mut sdu).expect("Failed to serialize URelease");
+    sdu.seek(0);
+    build_ul_msg(issi, sdu)
+}
+
+/// Collect (destination SSI, SDU) of all downlink CMCE PDUs of the given type sent to MLE.
+fn find_dl_pdus(msgs: &[SapMsg], pdu_type: CmcePduTypeDl) -> Vec<(u32, BitBuffer)> {
+    msgs.iter()
+        .filter_map(|msg| match &msg.msg {
+            SapMsgInner::LcmcMleUnitdataReq(prim) if msg.dest == TetraEntity::Mle => {
+                let sdu = BitBuffer::from_bitbuffer(&prim.sdu);
+                (sdu.peek_bits(5) == Some(pdu_type.into_raw())).then_some((prim.main_address.ssi, sdu))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Helper: set up an individual call from TEST_ISSI to TEST_CALLED_ISSI, returns the call identifier
+/// taken from the D-SETUP sent to the called MS.
+fn setup_individual_call(test: &mut ComponentTest) -> u16 {
+    test.config.state_write().subscribers.register(TEST_ISSI);
+    test.config.state_write().subscribers.register(TEST_CALLED_ISSI);
+
+    test.submit_message(build_u_setup_msg(TEST_ISSI, TEST_CALLED_ISSI, CommunicationType::P2p));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+
+    assert_eq!(find_dl_pdus(&msgs, CmcePduTypeDl::DCallProceeding).len(), 1);
+    assert_eq!(find_dl_pdus(&msgs, CmcePduTypeDl::DCallProceeding)[0].0, TEST_ISSI);
+    let mut setups = find_dl_pdus(&msgs, CmcePduTypeDl::DSetup);
+    assert_eq!(setups.len(), 1, "Expected a single D-SETUP to the called MS");
+    assert_eq!(setups[0].0, TEST_CALLED_ISSI);
+    let d_setup = DSetup::from_bitbuf(&mut setups[0].1).expect("Failed parsing DSetup");
+    assert_eq!(d_setup.calling_party_address_ssi, Some(TEST_ISSI));
+    d_setup.call_identifier
+}
+
+/// Helper: set up an individual call and let the called MS answer it, returns the call identifier.
+fn connect_individual_call(test: &mut ComponentTest) -> u16 {
+    let call_id = setup_individual_call(test);
+    test.submit_message(build_u_connect_msg(TEST_CALLED_ISSI, call_id));
+    test.run_stack(Some(1));
+    assert_eq!(find_dl_pdus(&test.dump_sinks(), CmcePduTypeDl::DConnect).len(), 1);
+    call_id
+}
+
+/// Destination SSIs of all D-RELEASE PDUs in the sink output, sorted.
+fn d_release_dests(msgs: &[SapMsg]) -> Vec<u32> {
+    let mut dests: Vec<u32> = find_dl_pdus(msgs, CmcePduTypeDl::DRelease).iter().map(|(ssi, _)| *ssi).collect();
+    dests.sort();
+    dests
+}
+
+fn get_individual_call_test() -> ComponentTest {
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    let components = vec![TetraEntity::Cmce];
+    let sinks = vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew];
+    test.populate_entities(components, sinks);
+    test
 }
 
 /// Extract tx_reporters from D-SETUP messages in the sink output.
@@ -140,7 +288,7 @@ fn test_dsetup_late_entry_throttle() {
     register_subscriber(&mut test, TEST_ISSI, TEST_GSSI);
 
     // Send U-SETUP to start a group call
-    let u_setup_msg = build_u_setup_msg(TEST_ISSI, TEST_GSSI);
+    let u_setup_msg = build_u_setup_msg(TEST_ISSI, TEST_GSSI, CommunicationType::P2Mp);
     test.submit_message(u_setup_msg);
     test.run_stack(Some(1));
 
@@ -193,3 +341,624 @@ fn test_dsetup_late_entry_throttle() {
         "Each re-sent D-SETUP should carry a fresh tx_reporter"
     );
 }
+
+/// Individual call to an ISSI that is not registered: the calling MS gets D-RELEASE right away and no D-SETUP is sent
+#[test]
+fn test_individual_call_called_party_not_registered() {
+    debug::setup_logging_verbose();
+    let mut test = get_individual_call_test();
+    test.config.state_write().subscribers.register(TEST_ISSI);
+
+    test.submit_message(build_u_setup_msg(TEST_ISSI, TEST_CALLED_ISSI, CommunicationType::P2p));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+
+    assert!(find_dl_pdus(&msgs, CmcePduTypeDl::DCallProceeding).is_empty());
+    assert!(find_dl_pdus(&msgs, CmcePduTypeDl::DSetup).is_empty());
+    let mut releases = find_dl_pdus(&msgs, CmcePduTypeDl::DRelease);
+    assert_eq!(releases.len(), 1, "Expected D-RELEASE to the calling MS");
+    assert_eq!(releases[0].0, TEST_ISSI);
+    let d_release = DRelease::from_bitbuf(&mut releases[0].1).expect("Failed parsing DRelease");
+    assert_eq!(d_release.call_identifier, 0);
+    assert_eq!(d_release.disconnect_cause, DisconnectCause::CalledPartyNotReachable);
+}
+
+/// Group call to the reserved all-ones GSSI: the calling MS gets D-RELEASE right away and no call is set up
+#[test]
+fn test_group_call_reserved_called_party() {
+    debug::setup_logging_verbose();
+    let mut test = get_individual_call_test();
+    register_subscriber(&mut test, TEST_ISSI, TEST_GSSI);
+
+    test.submit_message(build_u_setup_msg(TEST_ISSI, 0xFFFFFF, CommunicationType::P2Mp));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+
+    assert!(find_dl_pdus(&msgs, CmcePduTypeDl::DSetup).is_empty());
+    let mut releases = find_dl_pdus(&msgs, CmcePduTypeDl::DRelease);
+    assert_eq!(releases.len(), 1, "Expected D-RELEASE to the calling MS");
+    assert_eq!(releases[0].0, TEST_ISSI);
+    let d_release = DRelease::from_bitbuf(&mut releases[0].1).expect("Failed parsing DRelease");
+    assert_eq!(d_release.call_identifier, 0);
+    assert_eq!(d_release.disconnect_cause, DisconnectCause::UnknownTetraIdentity);
+    assert!(test.router.get_stack_state().active_calls.is_empty());
+}
+
+/// Called MS alerts its user: the calling MS must receive D-ALERT for the same call
+#[test]
+fn test_individual_call_alert() {
+    debug::setup_logging_verbose();
+    let mut test = get_individual_call_test();
+    let call_id = setup_individual_call(&mut test);
+
+    test.submit_message(build_u_alert_msg(TEST_CALLED_ISSI, call_id));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+
+    let mut alerts = find_dl_pdus(&msgs, CmcePduTypeDl::DAlert);
+    assert_eq!(alerts.len(), 1, "Expected D-ALERT to the calling MS");
+    assert_eq!(alerts[0].0, TEST_ISSI);
+    let d_alert = DAlert::from_bitbuf(&mut alerts[0].1).expect("Failed parsing DAlert");
+    assert_eq!(d_alert.call_identifier, call_id);
+
+    // A repeated U-ALERT does not cause another D-ALERT
+    test.submit_message(build_u_alert_msg(TEST_CALLED_ISSI, call_id));
+    test.run_stack(Some(1));
+    assert!(find_dl_pdus(&test.dump_sinks(), CmcePduTypeDl::DAlert).is_empty());
+}
+
+/// Called MS never answers: T310 expires and both parties get D-RELEASE
+#[test]
+fn test_individual_call_no_answer() {
+    debug::setup_logging_verbose();
+    let mut test = get_individual_call_test();
+    let call_id = setup_individual_call(&mut test);
+
+    test.submit_message(build_u_alert_msg(TEST_CALLED_ISSI, call_id));
+    test.run_stack(Some(1));
+    test.dump_sinks();
+
+    // Nothing happens while T310 is still running
+    test.run_stack(Some(T310_TIMESLOTS as usize - 8));
+    assert!(find_dl_pdus(&test.dump_sinks(), CmcePduTypeDl::DRelease).is_empty());
+
+    test.run_stack(Some(16));
+    let msgs = test.dump_sinks();
+    let releases = find_dl_pdus(&msgs, CmcePduTypeDl::DRelease);
+    assert_eq!(releases.len(), 2, "Expected D-RELEASE to both parties");
+    let mut dests: Vec<u32> = releases.iter().map(|(ssi, _)| *ssi).collect();
+    dests.sort();
+    assert_eq!(dests, vec![TEST_ISSI, TEST_CALLED_ISSI]);
+    for (_, mut sdu) in releases {
+        let d_release = DRelease::from_bitbuf(&mut sdu).expect("Failed parsing DRelease");
+        assert_eq!(d_release.call_identifier, call_id);
+        assert_eq!(d_release.disconnect_cause, DisconnectCause::ExpiryOfTimer);
+    }
+
+    // The call is gone, a late U-CONNECT is ignored
+    test.submit_message(build_u_connect_msg(TEST_CALLED_ISSI, call_id));
+    test.run_stack(Some(1));
+    assert!(find_dl_pdus(&test.dump_sinks(), CmcePduTypeDl::DConnect).is_empty());
+}
+
+/// Called MS answers: the circuit is opened, both parties get the channel allocation and T310 is stopped
+#[test]
+fn test_individual_call_connect() {
+    debug::setup_logging_verbose();
+    let mut test = get_individual_call_test();
+    let call_id = setup_individual_call(&mut test);
+
+    test.submit_message(build_u_alert_msg(TEST_CALLED_ISSI, call_id));
+    test.run_stack(Some(1));
+    test.dump_sinks();
+
+    test.submit_message(build_u_connect_msg(TEST_CALLED_ISSI, call_id));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+
+    let connects = find_dl_pdus(&msgs, CmcePduTypeDl::DConnect);
+    assert_eq!(connects.len(), 1, "Expected D-CONNECT to the calling MS");
+    assert_eq!(connects[0].0, TEST_ISSI);
+    let acks = find_dl_pdus(&msgs, CmcePduTypeDl::DConnectAcknowledge);
+    assert_eq!(acks.len(), 1, "Expected D-CONNECT ACKNOWLEDGE to the called MS");
+    assert_eq!(acks[0].0, TEST_CALLED_ISSI);
+
+    // Both carry the traffic channel allocation
+    let with_chan_alloc = msgs
+        .iter()
+        .filter(|msg| matches!(&msg.msg, SapMsgInner::LcmcMleUnitdataReq(prim) if prim.chan_alloc.is_some()))
+        .count();
+    assert_eq!(with_chan_alloc, 2);
+    assert!(
+        msgs.iter()
+            .any(|msg| msg.dest == TetraEntity::Umac && matches!(&msg.msg, SapMsgInner::CmceCallControl(CallControl::Open(_))))
+    );
+
+    // T310 no longer runs, so the call is not released when it would have expired
+    test.run_stack(Some(T310_TIMESLOTS as usize + 8));
+    assert!(find_dl_pdus(&test.dump_sinks(), CmcePduTypeDl::DRelease).is_empty());
+}
+
+/// Caller hangs up: called MS gets D-DISCONNECT, answers with U-RELEASE, caller gets D-RELEASE
+#[test]
+fn test_individual_call_disconnect_handshake() {
+    debug::setup_logging_verbose();
+    let mut test = get_individual_call_test();
+    let call_id = connect_individual_call(&mut test);
+
+    test.submit_message(build_u_disconnect_msg(TEST_ISSI, call_id));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let disconnects = find_dl_pdus(&msgs, CmcePduTypeDl::DDisconnect);
+    assert_eq!(disconnects.len(), 1, "Expected D-DISCONNECT to the other party only");
+    assert_eq!(disconnects[0].0, TEST_CALLED_ISSI);
+    assert!(d_release_dests(&msgs).is_empty(), "No D-RELEASE before the other party released");
+
+    test.submit_message(build_u_release_msg(TEST_CALLED_ISSI, call_id));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(d_release_dests(&msgs), vec![TEST_ISSI]);
+    assert!(
+        msgs.iter()
+            .any(|msg| msg.dest == TetraEntity::Umac && matches!(&msg.msg, SapMsgInner::CmceCallControl(CallControl::Close(_, _))))
+    );
+
+    // The call is gone, T309 does not fire afterwards
+    test.run_stack(Some(T309_TIMESLOTS as usize + 8));
+    assert!(d_release_dests(&test.dump_sinks()).is_empty());
+}
+
+/// Called MS never answers D-DISCONNECT: T309 expires and both parties are force-released
+#[test]
+fn test_individual_call_disconnect_t309_timeout() {
+    debug::setup_logging_verbose();
+    let mut test = get_individual_call_test();
+    let call_id = connect_individual_call(&mut test);
+
+    test.submit_message(build_u_disconnect_msg(TEST_ISSI, call_id));
+    test.run_stack(Some(1));
+    test.dump_sinks();
+
+    test.run_stack(Some(T309_TIMESLOTS as usize - 8));
+    assert!(d_release_dests(&test.dump_sinks()).is_empty());
+
+    test.run_stack(Some(16));
+    assert_eq!(d_release_dests(&test.dump_sinks()), vec![TEST_ISSI, TEST_CALLED_ISSI]);
+}
+
+/// Called MS sends U-DISCONNECT itself while T309 runs: both parties are released
+#[test]
+fn test_individual_call_disconnect_collision() {
+    debug::setup_logging_verbose();
+    let mut test = get_individual_call_test();
+    let call_id = connect_individual_call(&mut test);
+
+    test.submit_message(build_u_disconnect_msg(TEST_ISSI, call_id));
+    test.run_stack(Some(1));
+    test.dump_sinks();
+
+    test.submit_message(build_u_disconnect_msg(TEST_CALLED_ISSI, call_id));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert!(find_dl_pdus(&msgs, CmcePduTypeDl::DDisconnect).is_empty());
+    assert_eq!(d_release_dests(&msgs), vec![TEST_ISSI, TEST_CALLED_ISSI]);
+}
+
+/// Emergency U-SETUP while all traffic timeslots are busy: the lowest-priority call is pre-empted
+/// and the emergency call is set up on the freed timeslot with the floor granted to the caller.
+#[test]
+fn test_emergency_call_preempts_lowest_priority_call() {
+    debug::setup_logging_verbose();
+    let mut test = get_individual_call_test();
+
+    // One group per call, each with the calling ISSI as listener
+    let calls = [(1000011, 101, 3), (1000012, 102, 1), (1000013, 103, 5), (1000014, 104, 15)];
+    for (issi, gssi, _) in calls {
+        register_subscriber(&mut test, issi, gssi);
+    }
+
+    // Fill all traffic timeslots with normal calls
+    for (issi, gssi, priority) in &calls[..3] {
+        test.submit_message(build_u_setup_msg_with_priority(*issi, *gssi, CommunicationType::P2Mp, *priority));
+        test.run_stack(Some(1));
+        let msgs = test.dump_sinks();
+        assert!(find_dl_pdus(&msgs, CmcePduTypeDl::DSetup).iter().any(|(ssi, _)| ssi == gssi));
+    }
+
+    // A normal call finds no free timeslot
+    let (issi, gssi, _) = calls[3];
+    test.submit_message(build_u_setup_msg(issi, gssi, CommunicationType::P2Mp));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert!(find_dl_pdus(&msgs, CmcePduTypeDl::DSetup).iter().all(|(ssi, _)| *ssi != gssi));
+    assert!(find_dl_pdus(&msgs, CmcePduTypeDl::DRelease).is_empty());
+
+    // The emergency call pre-empts the priority 1 call
+    test.submit_message(build_u_setup_msg_with_priority(issi, gssi, CommunicationType::P2Mp, 15));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+
+    let mut releases = find_dl_pdus(&msgs, CmcePduTypeDl::DRelease);
+    assert_eq!(releases.len(), 1, "Expected exactly one call to be pre-empted");
+    assert_eq!(releases[0].0, 102);
+    let d_release = DRelease::from_bitbuf(&mut releases[0].1).expect("Failed parsing DRelease");
+    assert_eq!(d_release.disconnect_cause, DisconnectCause::PreEmptiveUseOfResource);
+
+    let mut setups: Vec<_> = find_dl_pdus(&msgs, CmcePduTypeDl::DSetup)
+        .into_iter()
+        .filter(|(ssi, _)| *ssi == gssi)
+        .collect();
+    assert_eq!(setups.len(), 1, "Expected D-SETUP for the emergency call");
+    let d_setup = DSetup::from_bitbuf(&mut setups[0].1).expect("Failed parsing DSetup");
+    assert_eq!(d_setup.call_priority, 15);
+
+    let mut connects = find_dl_pdus(&msgs, CmcePduTypeDl::DConnect);
+    assert_eq!(connects.len(), 1);
+    assert_eq!(connects[0].0, issi);
+    let d_connect = DConnect::from_bitbuf(&mut connects[0].1).expect("Failed parsing DConnect");
+    assert_eq!(d_connect.transmission_grant, TransmissionGrant::Granted);
+}
+
+/// The calling MS clears its group call: the D-RELEASE to the group is stolen from the traffic channel with the
+/// configured repeat count, and UMAC only closes the circuit once it was sent
+#[test]
+fn test_group_call_release_repeated() {
+    debug::setup_logging_verbose();
+    let mut test = get_individual_call_test();
+    register_subscriber(&mut test, TEST_ISSI, TEST_GSSI);
+
+    test.submit_message(build_u_setup_msg(TEST_ISSI, TEST_GSSI, CommunicationType::P2Mp));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let mut setups = find_dl_pdus(&msgs, CmcePduTypeDl::DSetup);
+    assert_eq!(setups.len(), 1);
+    let call_id = DSetup::from_bitbuf(&mut setups[0].1)
+        .expect("Failed parsing DSetup")
+        .call_identifier;
+
+    test.submit_message(build_u_disconnect_msg(TEST_ISSI, call_id));
+    test.run_stack(Some(1));
+    let mut msgs = test.dump_sinks();
+    assert_eq!(d_release_dests(&msgs), vec![TEST_GSSI]);
+    let release = msgs
+        .iter_mut()
+        .find_map(|msg| match &mut msg.msg {
+            SapMsgInner::LcmcMleUnitdataReq(prim) if prim.main_address.ssi == TEST_GSSI => Some(prim),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(release.redundant_transmission, test.config.config().cell.d_release_repeat);
+    assert_eq!(release.redundant_transmission, 3);
+    assert!(release.stealing_permission, "D-RELEASE must reach the MSs on the traffic channel");
+    let reporter = release.tx_reporter.take().expect("D-RELEASE without tx reporter");
+    assert!(
+        !msgs
+            .iter()
+            .any(|msg| matches!(msg.msg, SapMsgInner::CmceCallControl(CallControl::Close(..)))),
+        "Circuit closed before the D-RELEASE was sent"
+    );
+
+    // Still open while the D-RELEASE is queued
+    test.run_stack(Some(4));
+    let msgs = test.dump_sinks();
+    assert!(
+        !msgs
+            .iter()
+            .any(|msg| matches!(msg.msg, SapMsgInner::CmceCallControl(CallControl::Close(..))))
+    );
+
+    reporter.mark_transmitted();
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let umac_controls: Vec<_> = msgs
+        .iter()
+        .filter(|msg| msg.dest == TetraEntity::Umac)
+        .filter_map(|msg| match &msg.msg {
+            SapMsgInner::CmceCallControl(control) => Some(control),
+            _ => None,
+        })
+        .collect();
+    assert!(matches!(umac_controls[..], [CallControl::Close(..), CallControl::CallEnded { .. }]));
+}
+
+/// With teardown_on_empty, a group call stays up until every MS in it sent U-RELEASE, then is released right away
+#[test]
+fn test_group_call_teardown_on_empty() {
+    debug::setup_logging_verbose();
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.cmce.teardown_on_empty = true;
+    let mut test = ComponentTest::from_config(config, Some(TdmaTime { h: 0, m: 1, f: 1, t: 1 }));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+    register_subscriber(&mut test, TEST_ISSI, TEST_GSSI);
+    register_subscriber(&mut test, TEST_CALLED_ISSI, TEST_GSSI);
+
+    test.submit_message(build_u_setup_msg(TEST_ISSI, TEST_GSSI, CommunicationType::P2Mp));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let mut setups = find_dl_pdus(&msgs, CmcePduTypeDl::DSetup);
+    assert_eq!(setups.len(), 1);
+    let call_id = DSetup::from_bitbuf(&mut setups[0].1)
+        .expect("Failed parsing DSetup")
+        .call_identifier;
+
+    test.submit_message(build_u_connect_msg(TEST_CALLED_ISSI, call_id));
+    test.run_stack(Some(1));
+    test.dump_sinks();
+
+    // The first MS leaving keeps the call up for the other one
+    test.submit_message(build_u_release_msg(TEST_ISSI, call_id));
+    test.run_stack(Some(1));
+    assert!(d_release_dests(&test.dump_sinks()).is_empty());
+    assert_eq!(test.router.get_stack_state().active_calls.len(), 1);
+
+    // The last one leaving releases it without waiting for hangtime
+    test.submit_message(build_u_release_msg(TEST_CALLED_ISSI, call_id));
+    test.run_stack(Some(1));
+    assert_eq!(d_release_dests(&test.dump_sinks()), vec![TEST_GSSI]);
+    assert!(test.router.get_stack_state().active_calls.is_empty());
+}
+
+/// A group member sending U-SETUP for a group with an ongoing call has UMAC send a SYNC on the call's traffic timeslot
+#[test]
+fn test_group_call_late_entry() {
+    debug::setup_logging_verbose();
+    let mut test = get_individual_call_test();
+    register_subscriber(&mut test, TEST_ISSI, TEST_GSSI);
+    register_subscriber(&mut test, TEST_CALLED_ISSI, TEST_GSSI);
+
+    let is_late_entry =
+        |msg: &SapMsg| msg.dest == TetraEntity::Umac && matches!(msg.msg, SapMsgInner::CmceCallControl(CallControl::LateEntry { .. }));
+
+    test.submit_message(build_u_setup_msg(TEST_ISSI, TEST_GSSI, CommunicationType::P2Mp));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert!(!msgs.iter().any(is_late_entry));
+    let ts = msgs
+        .iter()
+        .find_map(|msg| match &msg.msg {
+            SapMsgInner::CmceCallControl(CallControl::Open(circuit)) => Some(circuit.ts),
+            _ => None,
+        })
+        .expect("circuit opened");
+
+    test.submit_message(build_u_setup_msg(TEST_CALLED_ISSI, TEST_GSSI, CommunicationType::P2Mp));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let late_entry = msgs.iter().find(|msg| is_late_entry(msg)).expect("LateEntry sent to UMAC");
+    let SapMsgInner::CmceCallControl(CallControl::LateEntry { ts: late_ts, .. }) = late_entry.msg else {
+        unreachable!()
+    };
+    assert_eq!(late_ts, ts);
+}
+
+/// A circuit Open that does not fit in the UMAC queue is not lost, but resent by CMCE on the next tick
+#[test]
+fn test_circuit_open_held_back_on_full_queue() {
+    debug::setup_logging_verbose();
+    let mut test = get_individual_call_test();
+    register_subscriber(&mut test, TEST_ISSI, TEST_GSSI);
+    let is_open = |msg: &SapMsg| matches!(msg.msg, SapMsgInner::CmceCallControl(CallControl::Open(_)));
+
+    // Fill the UMAC queue, so the Open sent while handling the U-SETUP is rejected
+    test.router.set_max_queue_depth(1);
+    test.submit_message(build_u_setup_msg(TEST_ISSI, TEST_GSSI, CommunicationType::P2Mp));
+    test.submit_message(SapMsg::new(
+        Sap::Control,
+        TetraEntity::Cmce,
+        TetraEntity::Umac,
+        SapMsgInner::CmceCallControl(CallControl::CallEnded { call_id: 0, ts: 1 }),
+    ));
+    test.router.deliver_message();
+    test.router.set_max_queue_depth(usize::MAX);
+    test.deliver_all_messages();
+    assert_eq!(test.router.get_stats()[&TetraEntity::Umac].queue_overflows, 1);
+    assert!(!test.dump_sinks().iter().any(is_open));
+
+    test.run_stack(Some(1));
+    assert_eq!(test.dump_sinks().iter().filter(|msg| is_open(msg)).count(), 1);
+    test.run_stack(Some(1));
+    assert!(!test.dump_sinks().iter().any(is_open));
+}
+
+/// On shutdown, the CMCE releases its active calls and closes their circuits
+#[test]
+fn test_shutdown_releases_calls() {
+    debug::setup_logging_verbose();
+    let mut test = get_individual_call_test();
+    register_subscriber(&mut test, TEST_ISSI, TEST_GSSI);
+
+    test.submit_message(build_u_setup_msg(TEST_ISSI, TEST_GSSI, CommunicationType::P2Mp));
+    test.run_stack(Some(1));
+    assert_eq!(find_dl_pdus(&test.dump_sinks(), CmcePduTypeDl::DSetup).len(), 1);
+
+    test.router.shutdown();
+    let msgs = test.dump_sinks();
+    assert_eq!(d_release_dests(&msgs), vec![TEST_GSSI]);
+    assert!(
+        msgs.iter()
+            .any(|msg| matches!(msg.msg, SapMsgInner::CmceCallControl(CallControl::Close(..)))),
+        "circuit not closed towards UMAC"
+    );
+    assert!(test.router.get_stack_state().active_calls.is_empty());
+}
+
+/// Replays the recording of an individual call into a fresh CMCE and checks that it sends the same messages
+#[test]
+fn test_record_and_replay() {
+    debug::setup_logging_verbose();
+    let dir = std::env::temp_dir();
+    let recorded = dir.join(format!("bluestation_record_{}.jsonl", std::process::id()));
+    let replayed = dir.join(format!("bluestation_replay_{}.jsonl", std::process::id()));
+
+    let mut test = get_individual_call_test();
+    test.config.state_write().subscribers.register(TEST_ISSI);
+    test.config.state_write().subscribers.register(TEST_CALLED_ISSI);
+    test.router.enable_recording(recorded.clone()).unwrap();
+
+    test.submit_message(build_u_setup_msg(TEST_ISSI, TEST_CALLED_ISSI, CommunicationType::P2p));
+    test.deliver_all_messages();
+    let mut setups = find_dl_pdus(&test.dump_sinks(), CmcePduTypeDl::DSetup);
+    let call_id = DSetup::from_bitbuf(&mut setups[0].1)
+        .expect("Failed parsing DSetup")
+        .call_identifier;
+    for msg in [
+        build_u_alert_msg(TEST_CALLED_ISSI, call_id),
+        build_u_connect_msg(TEST_CALLED_ISSI, call_id),
+        build_u_disconnect_msg(TEST_ISSI, call_id),
+        build_u_release_msg(TEST_CALLED_ISSI, call_id),
+    ] {
+        test.submit_message(msg);
+        test.deliver_all_messages();
+    }
+    test.router.stop_recording();
+
+    // Replay without MLE, so the uplink PDUs it delivered are taken from the recording
+    let mut replay = ComponentTest::new(StackMode::Bs, Some(TdmaTime { h: 0, m: 1, f: 1, t: 1 }));
+    replay.populate_entities(vec![TetraEntity::Cmce], vec![TetraEntity::Umac, TetraEntity::Brew]);
+    replay.config.state_write().subscribers.register(TEST_ISSI);
+    replay.config.state_write().subscribers.register(TEST_CALLED_ISSI);
+    replay.router.enable_recording(replayed.clone()).unwrap();
+    assert_eq!(replay.router.replay_from_file(recorded.clone()).unwrap(), 5);
+    replay.router.stop_recording();
+
+    let recorded_lines = std::fs::read_to_string(&recorded).unwrap();
+    let replayed_lines = std::fs::read_to_string(&replayed).unwrap();
+    std::fs::remove_file(&recorded).unwrap();
+    std::fs::remove_file(&replayed).unwrap();
+    // The 5 uplink PDUs and the 10 messages the CMCE sent in response
+    assert_eq!(recorded_lines.lines().count(), 15);
+    assert_eq!(recorded_lines, replayed_lines);
+}
+
+fn get_state_file_test(state_file: &std::path::Path) -> ComponentTest {
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.state_file = Some(state_file.to_path_buf());
+    let mut test = ComponentTest::from_config(config, Some(TdmaTime { h: 0, m: 1, f: 1, t: 1 }));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+    test
+}
+
+/// Timeslots in use are persisted to the state file, and after a restart held reserved until MSs left the old calls
+#[test]
+fn test_timeslot_alloc_restored_from_state_file() {
+    debug::setup_logging_verbose();
+    let state_file = std::env::temp_dir().join(format!("bluestation_state_{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&state_file);
+
+    let mut test = get_state_file_test(&state_file);
+    register_subscriber(&mut test, TEST_ISSI, TEST_GSSI);
+    test.submit_message(build_u_setup_msg(TEST_ISSI, TEST_GSSI, CommunicationType::P2Mp));
+    test.run_stack(Some(1));
+    let allocated = test.config.state_read().timeslot_alloc.owned_by(TimeslotOwner::Cmce);
+    assert_eq!(allocated.len(), 1);
+    let persisted = TimeslotAllocator::from_json(&std::fs::read_to_string(&state_file).unwrap()).unwrap();
+    assert_eq!(persisted.owned_by(TimeslotOwner::Cmce), allocated);
+
+    // Restart: the timeslot stays reserved for a while, then is released and the state file updated
+    let mut restarted = get_state_file_test(&state_file);
+    assert_eq!(
+        restarted.config.state_read().timeslot_alloc.owned_by(TimeslotOwner::Cmce),
+        allocated
+    );
+    restarted.run_stack(Some(8));
+    assert!(!restarted.config.state_read().timeslot_alloc.is_free(allocated[0]));
+    restarted.run_stack(Some(RESTORED_TIMESLOT_HOLD_TIMESLOTS));
+    assert!(restarted.config.state_read().timeslot_alloc.is_free(allocated[0]));
+    let persisted = TimeslotAllocator::from_json(&std::fs::read_to_string(&state_file).unwrap()).unwrap();
+    assert_eq!(persisted, TimeslotAllocator::default());
+
+    std::fs::remove_file(&state_file).unwrap();
+}
+
+/// A corrupted state file is ignored, the stack starts with all timeslots free
+#[test]
+fn test_timeslot_alloc_corrupted_state_file() {
+    debug::setup_logging_verbose();
+    let state_file = std::env::temp_dir().join(format!("bluestation_state_corrupted_{}.json", std::process::id()));
+    std::fs::write(&state_file, "{\"owners\":[\"Cmce\",").unwrap();
+
+    let test = get_state_file_test(&state_file);
+    assert_eq!(test.config.state_read().timeslot_alloc, TimeslotAllocator::default());
+
+    std::fs::remove_file(&state_file).unwrap();
+}
+
+/// Writer collecting formatted tracing output in a shared buffer
+#[derive(Clone)]
+struct TraceBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl std::io::Write for TraceBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A group call is traced in a "call" span that opens on U-SETUP and closes once the call is released,
+/// and the messages sent for the call carry its call_id down the stack
+#[test]
+fn test_group_call_span() {
+    let mut test = get_individual_call_test();
+    register_subscriber(&mut test, TEST_ISSI, TEST_GSSI);
+
+    let buf = TraceBuffer(Arc::new(Mutex::new(Vec::new())));
+    let writer = buf.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE)
+        .finish();
+
+    let (call_id, msgs) = tracing::subscriber::with_default(subscriber, || {
+        test.submit_message(build_u_setup_msg(TEST_ISSI, TEST_GSSI, CommunicationType::P2Mp));
+        test.run_stack(Some(1));
+        let mut msgs = test.dump_sinks();
+        let mut setups = find_dl_pdus(&msgs, CmcePduTypeDl::DSetup);
+        assert_eq!(setups.len(), 1);
+        let call_id = DSetup::from_bitbuf(&mut setups[0].1)
+            .expect("Failed parsing DSetup")
+            .call_identifier;
+
+        test.submit_message(build_u_disconnect_msg(TEST_ISSI, call_id));
+        test.run_stack(Some(1));
+        msgs.extend(test.dump_sinks());
+
+        // The circuit is closed once UMAC reports the D-RELEASE as sent
+        for msg in &msgs {
+            if let SapMsgInner::LcmcMleUnitdataReq(prim) = &msg.msg
+                && let Some(reporter) = &prim.tx_reporter
+            {
+                reporter.mark_transmitted();
+            }
+        }
+        test.run_stack(Some(1));
+        msgs.extend(test.dump_sinks());
+        (call_id, msgs)
+    });
+
+    let output = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+    let span_name = format!("call{{call_id={} gssi={}}}", call_id, TEST_GSSI);
+    let span_lines: Vec<&str> = output.lines().filter(|line| line.contains(&span_name)).collect();
+    assert!(span_lines.iter().any(|line| line.ends_with(": new")), "{}", output);
+    assert!(span_lines.iter().any(|line| line.contains(": close")), "{}", output);
+    assert!(
+        span_lines.iter().any(|line| line.contains("rx_u_setup: call from ISSI")),
+        "{}",
+        output
+    );
+    assert!(span_lines.iter().any(|line| line.contains("DRelease")), "{}", output);
+
+    // D-SETUP, D-CONNECT, D-RELEASE and the circuit open/close to UMAC are all tagged with the call
+    let tagged = |dest: TetraEntity| msgs.iter().filter(|msg| msg.dest == dest && msg.call_id == Some(call_id)).count();
+    assert!(tagged(TetraEntity::Mle) >= 3, "{}", tagged(TetraEntity::Mle));
+    assert!(tagged(TetraEntity::Umac) >= 2, "{}", tagged(TetraEntity::Umac));
+}
@@ -3,8 +3,16 @@ mod common;
 use common::ComponentTest;
 use tetra_config::bluestation::StackMode;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, TxReporter, TxState, debug, hyperframes, multiframes};
+use tetra_entities::llc::components::fcs;
+use tetra_pdus::llc::consts::timers::T251_SENDER_RETRY_TIMER;
+use tetra_pdus::llc::enums::al_setup_report::AlSetupReport;
+use tetra_pdus::llc::pdus::al_setup::AlSetup;
+use tetra_pdus::llc::pdus::bl_adata::BlAdata;
+use tetra_pdus::llc::pdus::bl_data::BlData;
+use tetra_saps::control::llc_stats::QueryLlcStats;
 use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tetra_saps::tla::{TlaTlConnectReq, TlaTlConnectResp, TlaTlDataReqBl};
 use tetra_saps::tma::TmaUnitdataInd;
 
 #[test]
@@ -30,12 +38,12 @@ fn test_udata_with_broken_mm_payload() {
         chan_change_handle: None,
         chan_info: None,
     };
-    let test_sapmsg = SapMsg {
-        sap: Sap::TmaSap,
-        src: TetraEntity::Umac,
-        dest: TetraEntity::Llc,
-        msg: SapMsgInner::TmaUnitdataInd(test_prim),
-    };
+    let test_sapmsg = SapMsg::new(
+        Sap::TmaSap,
+        TetraEntity::Umac,
+        TetraEntity::Llc,
+        SapMsgInner::TmaUnitdataInd(test_prim),
+    );
 
     // Setup testing stack
     let mut test = ComponentTest::new(StackMode::Bs, Some(dltime_vec));
@@ -52,3 +60,432 @@ fn test_udata_with_broken_mm_payload() {
     assert_eq!(sink_msgs.len(), 1);
     tracing::warn!("Validation of result not implemented");
 }
+
+const ADV_LINK_ISSI: u32 = 2040001;
+
+fn adv_link_addr() -> TetraAddress {
+    TetraAddress {
+        ssi: ADV_LINK_ISSI,
+        ssi_type: SsiType::Issi,
+    }
+}
+
+fn build_al_setup_ind(setup_report: AlSetupReport) -> SapMsg {
+    let pdu = AlSetup {
+        advanced_link_service: 0,
+        advanced_link_number: 0,
+        max_tlsdu_len: 7,
+        window_size_acked: 3,
+        max_tlsdu_retransmissions: 3,
+        max_segment_retransmissions: 3,
+        window_size_unacked: 3,
+        num_repetitions_unacked: 3,
+        setup_report,
+        num_timeslots: 0,
+    };
+    let mut buf = BitBuffer::new_autoexpand(32);
+    pdu.to_bitbuf(&mut buf);
+    buf.seek(0);
+    SapMsg::new(
+        Sap::TmaSap,
+        TetraEntity::Umac,
+        TetraEntity::Llc,
+        SapMsgInner::TmaUnitdataInd(TmaUnitdataInd {
+            pdu: Some(buf),
+            main_address: adv_link_addr(),
+            scrambling_code: 864282631,
+            endpoint_id: 0,
+            new_endpoint_id: None,
+            css_endpoint_id: None,
+            air_interface_encryption: 0,
+            chan_change_response_req: false,
+            chan_change_handle: None,
+            chan_info: None,
+        }),
+    )
+}
+
+/// Extracts the AL-SETUP set-up report from a TMA-UNITDATA request towards the MAC
+fn get_al_setup_report(msg: &SapMsg) -> AlSetupReport {
+    let SapMsgInner::TmaUnitdataReq(prim) = &msg.msg else {
+        panic!("expected TmaUnitdataReq, got {:?}", msg.msg);
+    };
+    let mut pdu = BitBuffer::from_bitbuffer(&prim.pdu);
+    AlSetup::from_bitbuf(&mut pdu).expect("Failed parsing AL-SETUP").setup_report
+}
+
+fn get_adv_link_test() -> ComponentTest {
+    let mut test = ComponentTest::new(StackMode::Bs, Some(TdmaTime::default().add_timeslots(2)));
+    test.populate_entities(vec![TetraEntity::Llc], vec![TetraEntity::Umac, TetraEntity::Mle]);
+    test
+}
+
+#[test]
+fn test_adv_link_local_setup() {
+    debug::setup_logging_verbose();
+    let mut test = get_adv_link_test();
+
+    // TL-CONNECT request from the service user results in AL-SETUP towards the MS
+    test.submit_message(SapMsg::new(
+        Sap::TlaSap,
+        TetraEntity::Mle,
+        TetraEntity::Llc,
+        SapMsgInner::TlaTlConnectReq(TlaTlConnectReq {
+            main_address: adv_link_addr(),
+            scrambling_code: 0,
+            link_id: 0,
+            endpoint_id: 0,
+            pdu_prio: 0,
+            stealing_permission: false,
+            subscriber_class: 0,
+            qos: 0,
+            al_service: 0,
+            air_interface_encryption: 0,
+            req_handle: 0,
+            setup_report: 0,
+        }),
+    ));
+    test.run_stack(Some(1));
+    let sink_msgs = test.dump_sinks();
+    assert_eq!(sink_msgs.len(), 1);
+    assert_eq!(sink_msgs[0].dest, TetraEntity::Umac);
+    assert_eq!(get_al_setup_report(&sink_msgs[0]), AlSetupReport::ServiceDefinition);
+
+    // MS accepts, service user receives TL-CONNECT confirm
+    test.submit_message(build_al_setup_ind(AlSetupReport::ServiceAccepted));
+    test.run_stack(Some(1));
+    let sink_msgs = test.dump_sinks();
+    assert_eq!(sink_msgs.len(), 1);
+    let SapMsgInner::TlaTlConnectConf(prim) = &sink_msgs[0].msg else {
+        panic!("expected TlaTlConnectConf, got {:?}", sink_msgs[0].msg);
+    };
+    assert_eq!(prim.main_address.ssi, ADV_LINK_ISSI);
+    assert!(test.config.state_read().link_ids.is_active(prim.link_id));
+}
+
+#[test]
+fn test_adv_link_peer_setup() {
+    debug::setup_logging_verbose();
+    let mut test = get_adv_link_test();
+
+    // AL-SETUP from the MS results in TL-CONNECT indication to the service user
+    test.submit_message(build_al_setup_ind(AlSetupReport::ServiceDefinition));
+    test.run_stack(Some(1));
+    let sink_msgs = test.dump_sinks();
+    assert_eq!(sink_msgs.len(), 1);
+    let SapMsgInner::TlaTlConnectInd(prim) = &sink_msgs[0].msg else {
+        panic!("expected TlaTlConnectInd, got {:?}", sink_msgs[0].msg);
+    };
+    assert_eq!(prim.main_address.ssi, ADV_LINK_ISSI);
+
+    // Service user accepts, AL-SETUP with ServiceAccepted goes back to the MS
+    test.submit_message(SapMsg::new(
+        Sap::TlaSap,
+        TetraEntity::Mle,
+        TetraEntity::Llc,
+        SapMsgInner::TlaTlConnectResp(TlaTlConnectResp {
+            main_address: adv_link_addr(),
+            scrambling_code: 0,
+            link_id: 0,
+            endpoint_id: 0,
+            pdu_prio: 0,
+            stealing_permission: false,
+            subscriber_class: 0,
+            qos: 0,
+            al_service: 0,
+            air_interface_encryption: 0,
+            req_handle: 0,
+            setup_report: 0,
+        }),
+    ));
+    test.run_stack(Some(1));
+    let sink_msgs = test.dump_sinks();
+    assert_eq!(sink_msgs.len(), 1);
+    assert_eq!(get_al_setup_report(&sink_msgs[0]), AlSetupReport::ServiceAccepted);
+}
+
+/// Link ids of advanced links without activity are recovered, and handed out again to new links
+#[test]
+fn test_adv_link_id_gc() {
+    debug::setup_logging_verbose();
+    let mut test = get_adv_link_test();
+
+    test.submit_message(build_al_setup_ind(AlSetupReport::ServiceDefinition));
+    test.run_stack(Some(1));
+    let sink_msgs = test.dump_sinks();
+    let SapMsgInner::TlaTlConnectInd(prim) = &sink_msgs[0].msg else {
+        panic!("expected TlaTlConnectInd, got {:?}", sink_msgs[0].msg);
+    };
+    let link_id = prim.link_id;
+    assert!(test.config.state_read().link_ids.is_active(link_id));
+
+    // The service user never responds, the link id is collected after a hyperframe without activity
+    test.run_stack(Some(hyperframes!(1) + multiframes!(2)));
+    test.dump_sinks();
+    assert!(!test.config.state_read().link_ids.is_active(link_id));
+    assert_eq!(test.config.state_read().link_ids.num_active(), 0);
+
+    // A new set-up gets a fresh link, reusing the released id
+    test.submit_message(build_al_setup_ind(AlSetupReport::ServiceDefinition));
+    test.run_stack(Some(1));
+    let sink_msgs = test.dump_sinks();
+    let SapMsgInner::TlaTlConnectInd(prim) = &sink_msgs[0].msg else {
+        panic!("expected TlaTlConnectInd, got {:?}", sink_msgs[0].msg);
+    };
+    assert_eq!(prim.link_id, link_id);
+}
+
+/// Captured BL-DATA with FCS: 5 bit header, 224 bit TL-SDU, 32 bit FCS
+const BL_DATA_FCS_VEC: &str = "010100100111101011010111110000100110000110001011000011000000000000000011000100000001001100110011000000110010001011000011001000110000001100100011000100110001001100010011000100110101001100100011000000110010001100000011000000110001011001111010000010101011000110101";
+
+fn build_bl_data_ind(bitstr: &str) -> SapMsg {
+    SapMsg::new(
+        Sap::TmaSap,
+        TetraEntity::Umac,
+        TetraEntity::Llc,
+        SapMsgInner::TmaUnitdataInd(TmaUnitdataInd {
+            pdu: Some(BitBuffer::from_bitstr(bitstr)),
+            main_address: adv_link_addr(),
+            scrambling_code: 864282631,
+            endpoint_id: 0,
+            new_endpoint_id: None,
+            css_endpoint_id: None,
+            air_interface_encryption: 0,
+            chan_change_response_req: false,
+            chan_change_handle: None,
+            chan_info: None,
+        }),
+    )
+}
+
+#[test]
+fn test_fcs_stripped_on_receive() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, Some(TdmaTime::default().add_timeslots(2)));
+    test.populate_entities(vec![TetraEntity::Llc], vec![TetraEntity::Umac, TetraEntity::Mle]);
+
+    test.submit_message(build_bl_data_ind(BL_DATA_FCS_VEC));
+    test.run_stack(Some(1));
+    let sink_msgs = test.dump_sinks();
+
+    // TL-SDU without FCS goes to MLE, BL-ACK goes to MAC
+    let ind = sink_msgs
+        .iter()
+        .find_map(|m| match &m.msg {
+            SapMsgInner::TlaTlDataIndBl(prim) => Some(prim),
+            _ => None,
+        })
+        .expect("no TL-DATA indication");
+    assert!(ind.fcs_flag);
+    assert_eq!(ind.tl_sdu.as_ref().unwrap().to_bitstr(), &BL_DATA_FCS_VEC[5..5 + 224]);
+    assert!(sink_msgs.iter().any(|m| m.dest == TetraEntity::Umac));
+}
+
+#[test]
+fn test_fcs_error_discards_pdu() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, Some(TdmaTime::default().add_timeslots(2)));
+    test.populate_entities(vec![TetraEntity::Llc], vec![TetraEntity::Umac, TetraEntity::Mle]);
+
+    // Flip a bit in the TL-SDU. PDU is neither delivered nor acknowledged
+    let mut corrupted = BL_DATA_FCS_VEC.to_string();
+    corrupted.replace_range(20..21, if &BL_DATA_FCS_VEC[20..21] == "0" { "1" } else { "0" });
+    test.submit_message(build_bl_data_ind(&corrupted));
+    test.run_stack(Some(1));
+    assert!(test.dump_sinks().is_empty());
+}
+
+#[test]
+fn test_fcs_appended_on_transmit() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, Some(TdmaTime::default().add_timeslots(2)));
+    test.populate_entities(vec![TetraEntity::Llc], vec![TetraEntity::Umac, TetraEntity::Mle]);
+
+    let sdu = &BL_DATA_FCS_VEC[5..5 + 224];
+    test.submit_message(SapMsg::new(
+        Sap::TlaSap,
+        TetraEntity::Mle,
+        TetraEntity::Llc,
+        SapMsgInner::TlaTlDataReqBl(TlaTlDataReqBl {
+            main_address: adv_link_addr(),
+            link_id: 0,
+            endpoint_id: 0,
+            tl_sdu: BitBuffer::from_bitstr(sdu),
+            stealing_permission: false,
+            subscriber_class: 0,
+            fcs_flag: true,
+            air_interface_encryption: None,
+            stealing_repeats_flag: None,
+            data_class_info: None,
+            req_handle: 0,
+            graceful_degradation: None,
+            chan_alloc: None,
+            tx_reporter: None,
+        }),
+    ));
+    test.run_stack(Some(1));
+    let sink_msgs = test.dump_sinks();
+    assert_eq!(sink_msgs.len(), 1);
+    let SapMsgInner::TmaUnitdataReq(prim) = &sink_msgs[0].msg else {
+        panic!("expected TmaUnitdataReq, got {:?}", sink_msgs[0].msg);
+    };
+
+    let mut pdu = BitBuffer::from_bitbuffer(&prim.pdu);
+    let hdr = BlData::from_bitbuf(&mut pdu).expect("Failed parsing BL-DATA");
+    assert!(hdr.has_fcs);
+    assert_eq!(pdu.get_len_remaining(), 224 + 32);
+    assert!(fcs::check_fcs(&pdu));
+}
+
+#[test]
+fn test_stats_with_injected_errors() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, Some(TdmaTime::default().add_timeslots(2)));
+    test.populate_entities(vec![TetraEntity::Llc], vec![TetraEntity::Umac, TetraEntity::Mle]);
+
+    // Send one BL-DATA to each of 100 MSs
+    let sdu = &BL_DATA_FCS_VEC[5..5 + 224];
+    let mut reporters = vec![];
+    for i in 0..100 {
+        let tx_reporter = TxReporter::new();
+        reporters.push(tx_reporter.clone());
+        test.submit_message(SapMsg::new(
+            Sap::TlaSap,
+            TetraEntity::Mle,
+            TetraEntity::Llc,
+            SapMsgInner::TlaTlDataReqBl(TlaTlDataReqBl {
+                main_address: TetraAddress {
+                    ssi: ADV_LINK_ISSI + i,
+                    ssi_type: SsiType::Issi,
+                },
+                link_id: 0,
+                endpoint_id: 0,
+                tl_sdu: BitBuffer::from_bitstr(sdu),
+                stealing_permission: false,
+                subscriber_class: 0,
+                fcs_flag: true,
+                air_interface_encryption: None,
+                stealing_repeats_flag: None,
+                data_class_info: None,
+                req_handle: 0,
+                graceful_degradation: None,
+                chan_alloc: None,
+                tx_reporter: Some(tx_reporter),
+            }),
+        ));
+    }
+    test.run_stack(Some(1));
+    assert_eq!(test.dump_sinks().len(), 100);
+
+    // MAC transmits all of them
+    for tx_reporter in reporters.iter() {
+        tx_reporter.mark_transmitted();
+    }
+    test.run_stack(Some(1));
+
+    // Each MS answers with a BL-ADATA with FCS, acknowledging our BL-DATA. Every tenth one is corrupted on the air.
+    for i in 0..100 {
+        let mut pdu = BitBuffer::new_autoexpand(261);
+        BlAdata {
+            has_fcs: true,
+            nr: 0,
+            ns: 0,
+        }
+        .to_bitbuf(&mut pdu);
+        let sdu_start = pdu.get_pos();
+        pdu.copy_bits(&mut BitBuffer::from_bitstr(sdu), 224);
+        fcs::append_fcs(&mut pdu, sdu_start);
+        let mut bits = pdu.to_bitstr();
+        if i % 10 == 0 {
+            let flipped = if &bits[20..21] == "0" { "1" } else { "0" };
+            bits.replace_range(20..21, flipped);
+        }
+
+        let mut msg = build_bl_data_ind(&bits);
+        let SapMsgInner::TmaUnitdataInd(prim) = &mut msg.msg else {
+            unreachable!()
+        };
+        prim.main_address.ssi = ADV_LINK_ISSI + i;
+        test.submit_message(msg);
+    }
+
+    // Let T.251 expire, so the unacknowledged messages are retransmitted
+    test.run_stack(Some(T251_SENDER_RETRY_TIMER as usize + 1));
+    test.dump_sinks();
+    for (i, tx_reporter) in reporters.iter().enumerate() {
+        assert_eq!(tx_reporter.is_acknowledged(), i % 10 != 0);
+    }
+
+    // Query the stats
+    test.submit_message(SapMsg::new(
+        Sap::Control,
+        TetraEntity::Mle,
+        TetraEntity::Llc,
+        SapMsgInner::QueryLlcStats(QueryLlcStats),
+    ));
+    test.run_stack(Some(1));
+    let sink_msgs = test.dump_sinks();
+    let stats = sink_msgs
+        .iter()
+        .find_map(|m| match &m.msg {
+            SapMsgInner::LlcStatsReply(stats) => Some(stats),
+            _ => None,
+        })
+        .expect("no LlcStatsReply");
+    tracing::info!("LLC stats: {}", stats);
+    assert_eq!(stats.bl_data_tx, 100);
+    assert_eq!(stats.bl_data_rx, 90);
+    assert_eq!(stats.fcs_errors, 10);
+    assert_eq!(stats.retransmissions, 10);
+    assert_eq!(stats.window_stalls, 0);
+}
+
+#[test]
+fn test_retransmissions_exhausted_with_configured_t251_n252() {
+    debug::setup_logging_verbose();
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.llc.t251_ms = 100; // 7 timeslots
+    config.llc.n252 = 2;
+    let t251 = config.llc.t251_timeslots() as usize;
+    let mut test = ComponentTest::from_config(config, Some(TdmaTime::default().add_timeslots(2)));
+    test.populate_entities(vec![TetraEntity::Llc], vec![TetraEntity::Umac, TetraEntity::Mle]);
+
+    let tx_reporter = TxReporter::new();
+    test.submit_message(SapMsg::new(
+        Sap::TlaSap,
+        TetraEntity::Mle,
+        TetraEntity::Llc,
+        SapMsgInner::TlaTlDataReqBl(TlaTlDataReqBl {
+            main_address: adv_link_addr(),
+            link_id: 0,
+            endpoint_id: 0,
+            tl_sdu: BitBuffer::from_bitstr("0010110100111"),
+            stealing_permission: false,
+            subscriber_class: 0,
+            fcs_flag: false,
+            air_interface_encryption: None,
+            stealing_repeats_flag: None,
+            data_class_info: None,
+            req_handle: 0,
+            graceful_degradation: None,
+            chan_alloc: None,
+            tx_reporter: Some(tx_reporter.clone()),
+        }),
+    ));
+
+    // The MS never acknowledges. Count how often the PDU is handed to the MAC, with the MAC transmitting it each time.
+    let mut transmissions = 0;
+    for _ in 0..(t251 + 1) * 4 {
+        test.run_stack(Some(1));
+        let sink_msgs = test.dump_sinks();
+        if !sink_msgs.is_empty() {
+            assert_eq!(sink_msgs.len(), 1);
+            transmissions += 1;
+            tx_reporter.mark_transmitted();
+        }
+    }
+
+    // Initial transmission plus N.252 retransmissions, after which the TL-SDU is reported lost
+    assert_eq!(transmissions, 3);
+    assert_eq!(tx_reporter.get_state(), TxState::Lost);
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{TdmaTime, debug};
+use tetra_saps::sapmsg::SapMsgInner;
+
+use crate::common::loopback::LoopbackTest;
+
+#[test]
+/// Run a BS stack and an MS UMAC back-to-back through the TMV-SAP loopback for one full multiframe.
+/// The MS must receive the BS SYSINFO broadcast and pass it up to the MLE.
+fn test_loopback_bs_broadcast_reaches_ms() {
+    debug::setup_logging_verbose();
+    let bs_components = vec![
+        TetraEntity::Umac,
+        TetraEntity::Llc,
+        TetraEntity::Mle,
+        TetraEntity::Mm,
+        TetraEntity::Cmce,
+    ];
+    let ms_components = vec![TetraEntity::Umac];
+    let ms_sinks = vec![TetraEntity::Llc, TetraEntity::Mle];
+    let mut test = LoopbackTest::new(Some(TdmaTime::default()), bs_components, vec![], ms_components, ms_sinks);
+
+    let num_ticks = 18 * 4;
+    let msgs = test.run_ticks(num_ticks);
+
+    // Every tick, the BS put exactly one slot on air, in order
+    assert_eq!(test.dl_slots.len(), num_ticks);
+    for pair in test.dl_slots.windows(2) {
+        assert_eq!(pair[0].add_timeslots(1), pair[1]);
+    }
+
+    // Idle slots carry SYSINFO in their second half, so the MS MLE receives one per such slot
+    let sysinfo_count = msgs.iter().filter(|m| matches!(m.msg, SapMsgInner::TlmbSysinfoInd(_))).count();
+    assert!(sysinfo_count > 0, "MS did not receive any SYSINFO");
+    assert_eq!(sysinfo_count, msgs.len(), "unexpected messages at MS: {:?}", msgs);
+}
+
+#[test]
+/// Two loopback runs from the same starting point must produce identical downlink schedules
+fn test_loopback_deterministic() {
+    debug::setup_logging_verbose();
+    let run = || {
+        let mut test = LoopbackTest::new(
+            None,
+            vec![TetraEntity::Umac, TetraEntity::Llc, TetraEntity::Mle],
+            vec![],
+            vec![TetraEntity::Umac],
+            vec![TetraEntity::Mle],
+        );
+        let msgs = test.run_ticks(36);
+        (test.dl_slots, msgs.len())
+    };
+    assert_eq!(run(), run());
+}
@@ -0,0 +1,231 @@
+mod common;
+
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+use tetra_config::bluestation::StackMode;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{Sap, debug};
+use tetra_saps::control::call_control::CallControl;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+use tetra_entities::messagerouter::{EntityStats, LatencyHistogram, MessagePrio, MessageQueue, QueueFull};
+
+use crate::common::ComponentTest;
+use crate::common::mock_entity::MockEntity;
+
+/// Control message from CMCE to `dest`, used as a generic payload to route
+fn call_ended(dest: TetraEntity) -> SapMsg {
+    SapMsg::new(
+        Sap::Control,
+        TetraEntity::Cmce,
+        dest,
+        SapMsgInner::CmceCallControl(CallControl::CallEnded { call_id: 1, ts: 2 }),
+    )
+}
+
+#[test]
+/// Entities are shut down in reverse registration order, and messages sent from a shutdown hook
+/// are delivered before the entities below are shut down
+fn test_shutdown_order() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, None);
+    test.register_entity(MockEntity::new(TetraEntity::Llc));
+    test.register_entity(MockEntity::new(TetraEntity::Mle));
+    let mut cmce = MockEntity::new(TetraEntity::Cmce);
+    cmce.add_shutdown_msg(SapMsg::new(
+        Sap::LcmcSap,
+        TetraEntity::Cmce,
+        TetraEntity::Llc,
+        SapMsgInner::CmceCallControl(CallControl::CallEnded { call_id: 1, ts: 2 }),
+    ));
+    test.register_entity(cmce);
+
+    test.router.shutdown();
+    assert_eq!(test.get_mock(TetraEntity::Cmce).unwrap().received_at_shutdown(), Some(0));
+    assert_eq!(test.get_mock(TetraEntity::Mle).unwrap().received_at_shutdown(), Some(0));
+    let llc = test.get_mock(TetraEntity::Llc).unwrap();
+    assert_eq!(llc.received_at_shutdown(), Some(1));
+    llc.assert_received(Sap::LcmcSap, |m| matches!(m, SapMsgInner::CmceCallControl(_)));
+}
+
+#[test]
+/// Messages from shutdown hooks are still delivered when the drain timeout has already passed, and all entities are dropped
+fn test_graceful_shutdown() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, None);
+    test.register_entity(MockEntity::new(TetraEntity::Llc));
+    let mut cmce = MockEntity::new(TetraEntity::Cmce);
+    cmce.add_shutdown_msg(call_ended(TetraEntity::Llc));
+    test.register_entity(cmce);
+
+    test.router.graceful_shutdown(Duration::ZERO);
+    assert_eq!(test.router.get_stats()[&TetraEntity::Llc].messages_received, 1);
+    assert!(test.get_mock(TetraEntity::Llc).is_none());
+    assert!(test.get_mock(TetraEntity::Cmce).is_none());
+}
+
+#[test]
+/// The router measures the time from SapMsg creation to delivery
+fn test_router_latency_stats() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, None);
+    test.register_entity(MockEntity::new(TetraEntity::Llc));
+    let stats = test.router.stats();
+    assert_eq!(stats.avg_latency_us.load(Ordering::Relaxed), 0);
+
+    let msg = SapMsg::new(
+        Sap::LcmcSap,
+        TetraEntity::Cmce,
+        TetraEntity::Llc,
+        SapMsgInner::CmceCallControl(CallControl::CallEnded { call_id: 1, ts: 2 }),
+    );
+    std::thread::sleep(Duration::from_millis(1));
+    test.submit_message(msg);
+    test.deliver_all_messages();
+
+    assert_eq!(stats.delivered.load(Ordering::Relaxed), 1);
+    assert!(stats.avg_latency_us.load(Ordering::Relaxed) >= 1000);
+    test.get_mock(TetraEntity::Llc)
+        .unwrap()
+        .assert_received(Sap::LcmcSap, |m| matches!(m, SapMsgInner::CmceCallControl(_)));
+}
+
+#[test]
+fn test_latency_histogram_buckets() {
+    let histogram = LatencyHistogram::default();
+    for us in [0, 10, 11, 100, 500, 1000, 1001, 10_000, 10_001, 1_000_000] {
+        histogram.record(Duration::from_micros(us));
+    }
+    assert_eq!(histogram.counts(), [2, 1, 1, 1, 1, 1, 1, 2]);
+}
+
+#[test]
+fn test_router_processing_time_histogram() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, None);
+    let mut llc = MockEntity::new(TetraEntity::Llc);
+    llc.set_rx_delay(Duration::from_millis(20));
+    let mut mle = MockEntity::new(TetraEntity::Mle);
+    mle.set_rx_delay(Duration::from_millis(2));
+    test.register_entity(llc);
+    test.register_entity(mle);
+    let stats = test.router.stats();
+    for _ in 0..3 {
+        test.submit_message(call_ended(TetraEntity::Llc));
+    }
+    for _ in 0..2 {
+        test.submit_message(call_ended(TetraEntity::Mle));
+    }
+    test.deliver_all_messages();
+
+    // 20 ms is above the last finite threshold of 10 ms
+    assert_eq!(stats.processing_time(TetraEntity::Llc), Some([0, 0, 0, 0, 0, 0, 0, 3]));
+    // 2 ms lands in the 5 ms bucket, or a later one if the sleep overshoots
+    let mle_counts = stats.processing_time(TetraEntity::Mle).unwrap();
+    assert_eq!(mle_counts[..5], [0; 5]);
+    assert_eq!(mle_counts.iter().sum::<u64>(), 2);
+    assert_eq!(stats.processing_time(TetraEntity::Mm), None);
+
+    let json = stats.to_json();
+    assert_eq!(json["delivered"], 5);
+    assert_eq!(json["processing_time_us"]["Llc"]["buckets"][7], 3);
+    assert_eq!(json["processing_time_us"]["Llc"]["thresholds_us"][0], 10);
+}
+
+#[test]
+fn test_router_entity_stats() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, None);
+    test.register_entity(MockEntity::new(TetraEntity::Llc));
+    test.register_entity(MockEntity::new(TetraEntity::Mle));
+    assert!(test.router.get_stats().is_empty());
+
+    for _ in 0..3 {
+        test.submit_message(call_ended(TetraEntity::Llc));
+    }
+    for _ in 0..2 {
+        test.submit_message(call_ended(TetraEntity::Mle));
+    }
+    let before = Instant::now();
+    test.deliver_all_messages();
+    let stats = test.router.get_stats();
+
+    // The first LLC message leaves four queued behind it, the first MLE message one
+    let llc = &stats[&TetraEntity::Llc];
+    assert_eq!((llc.messages_received, llc.messages_sent, llc.peak_queue_depth), (3, 0, 4));
+    assert!(llc.last_active.unwrap() >= before);
+    let mle = &stats[&TetraEntity::Mle];
+    assert_eq!((mle.messages_received, mle.peak_queue_depth), (2, 1));
+    assert!(mle.last_active.unwrap() >= llc.last_active.unwrap());
+
+    // The unregistered sender is counted, but never active
+    assert_eq!(
+        stats[&TetraEntity::Cmce],
+        EntityStats {
+            messages_sent: 5,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_message_queue_max_depth() {
+    let mut queue = MessageQueue::new();
+    queue.set_max_depth(2);
+    assert_eq!(queue.push_back(call_ended(TetraEntity::Llc)), Ok(()));
+    assert_eq!(queue.push_back(call_ended(TetraEntity::Llc)), Ok(()));
+    assert_eq!(queue.push_back(call_ended(TetraEntity::Llc)), Err(QueueFull));
+    // The limit applies per destination
+    assert_eq!(queue.push_back(call_ended(TetraEntity::Mle)), Ok(()));
+
+    // Immediate messages bypass the limit and go first
+    assert_eq!(queue.push_prio(call_ended(TetraEntity::Umac), MessagePrio::Immediate), Ok(()));
+    queue.set_max_depth(0);
+    assert_eq!(queue.push_prio(call_ended(TetraEntity::Llc), MessagePrio::Immediate), Ok(()));
+    assert_eq!(queue.push_prio(call_ended(TetraEntity::Llc), MessagePrio::Normal), Err(QueueFull));
+    let dests: Vec<_> = std::iter::from_fn(|| queue.pop_front()).map(|m| *m.get_dest()).collect();
+    assert_eq!(
+        dests,
+        [
+            TetraEntity::Llc,
+            TetraEntity::Umac,
+            TetraEntity::Llc,
+            TetraEntity::Llc,
+            TetraEntity::Mle
+        ]
+    );
+
+    // Popping frees up room again
+    queue.set_max_depth(1);
+    assert_eq!(queue.push_back(call_ended(TetraEntity::Llc)), Ok(()));
+}
+
+#[test]
+fn test_router_queue_overflows() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, None);
+    test.register_entity(MockEntity::new(TetraEntity::Llc));
+    test.register_entity(MockEntity::new(TetraEntity::Mle));
+    test.router.set_max_queue_depth(2);
+
+    let results: Vec<_> = (0..5).map(|_| test.router.submit_message(call_ended(TetraEntity::Llc))).collect();
+    assert_eq!(results, [Ok(()), Ok(()), Err(QueueFull), Err(QueueFull), Err(QueueFull)]);
+    assert_eq!(test.router.submit_message(call_ended(TetraEntity::Mle)), Ok(()));
+    test.deliver_all_messages();
+
+    let stats = test.router.get_stats();
+    assert_eq!(stats[&TetraEntity::Llc].messages_received, 2);
+    assert_eq!(stats[&TetraEntity::Llc].queue_overflows, 3);
+    assert_eq!(stats[&TetraEntity::Mle].messages_received, 1);
+    assert_eq!(stats[&TetraEntity::Mle].queue_overflows, 0);
+    assert_eq!(stats[&TetraEntity::Cmce].messages_sent, 3);
+
+    // Once delivered, the queue accepts messages again and the counter keeps its value
+    for _ in 0..3 {
+        let _ = test.router.submit_message(call_ended(TetraEntity::Llc));
+    }
+    test.deliver_all_messages();
+    let llc = &test.router.get_stats()[&TetraEntity::Llc];
+    assert_eq!((llc.messages_received, llc.queue_overflows), (4, 4));
+}
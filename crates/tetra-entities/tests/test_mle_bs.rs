@@ -0,0 +1,289 @@
+mod common;
+
+use tetra_config::bluestation::{EnergyEconomyConfig, StackMode};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, ChannelClassInfo, Layer2Service, Sap, TdmaTime, TetraAddress, TransferResult, debug};
+use tetra_pdus::llc::pdus::bl_ack::BlAck;
+use tetra_saps::lcmc::LcmcMleUnitdataReq;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tetra_saps::tla::{TlaTlDataIndBl, TlaTlReportInd};
+use tetra_saps::tma::TmaUnitdataInd;
+
+use crate::common::ComponentTest;
+
+fn cmce_unitdata_req(handle: u32) -> SapMsg {
+    SapMsg::new(
+        Sap::LcmcSap,
+        TetraEntity::Cmce,
+        TetraEntity::Mle,
+        SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
+            sdu: BitBuffer::from_bitstr("0101"),
+            handle,
+            endpoint_id: 0,
+            link_id: 0,
+            layer2service: Layer2Service::Acknowledged,
+            pdu_prio: 0,
+            layer2_qos: 0,
+            stealing_permission: false,
+            stealing_repeats_flag: false,
+            main_address: TetraAddress::issi(1000),
+            chan_alloc: None,
+            redundant_transmission: 1,
+            tx_reporter: None,
+        }),
+    )
+}
+
+fn llc_report(handle: u32) -> SapMsg {
+    SapMsg::new(
+        Sap::TlaSap,
+        TetraEntity::Llc,
+        TetraEntity::Mle,
+        SapMsgInner::TlaTlReportInd(TlaTlReportInd {
+            req_handle: Some(handle as i32),
+            report: TransferResult::Successful,
+            chan_change_resp_req: None,
+            chan_change_handle: None,
+            chan_info: None,
+            endpoint_id: None,
+        }),
+    )
+}
+
+#[test]
+fn test_report_handle_correlation() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, None);
+    test.populate_entities(vec![TetraEntity::Mle], vec![TetraEntity::Llc, TetraEntity::Cmce]);
+
+    // MLE passes a handle of its own down to LLC
+    test.submit_message(cmce_unitdata_req(7));
+    test.deliver_all_messages();
+    let msgs = test.dump_sinks();
+    assert_eq!(msgs.len(), 1);
+    let SapMsgInner::TlaTlDataReqBl(req) = &msgs[0].msg else {
+        panic!("expected TL-DATA request, got {:?}", msgs[0].msg);
+    };
+    let llc_handle = req.req_handle as u32;
+    assert_ne!(llc_handle, 0);
+
+    // The report for it reaches CMCE exactly once, with the CMCE handle
+    test.submit_message(llc_report(llc_handle));
+    test.deliver_all_messages();
+    let msgs = test.dump_sinks();
+    assert_eq!(msgs.len(), 1);
+    assert_eq!(msgs[0].dest, TetraEntity::Cmce);
+    let SapMsgInner::LcmcMleReportInd(report) = &msgs[0].msg else {
+        panic!("expected LCMC report, got {:?}", msgs[0].msg);
+    };
+    assert_eq!(report.handle, 7);
+    assert_eq!(report.transfer_result, TransferResult::Successful);
+
+    test.submit_message(llc_report(llc_handle));
+    test.deliver_all_messages();
+    assert!(test.dump_sinks().is_empty(), "completed handle must not be reported again");
+}
+
+#[test]
+fn test_report_unknown_handle_dropped() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, None);
+    test.populate_entities(vec![TetraEntity::Mle], vec![TetraEntity::Llc, TetraEntity::Cmce]);
+
+    test.submit_message(llc_report(9));
+    test.deliver_all_messages();
+    assert!(test.dump_sinks().is_empty());
+}
+
+#[test]
+fn test_unacknowledged_request_not_tracked() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, None);
+    test.populate_entities(vec![TetraEntity::Mle], vec![TetraEntity::Llc, TetraEntity::Cmce]);
+
+    let mut msg = cmce_unitdata_req(7);
+    let SapMsgInner::LcmcMleUnitdataReq(prim) = &mut msg.msg else {
+        unreachable!()
+    };
+    prim.layer2service = Layer2Service::Unacknowledged;
+    test.submit_message(msg);
+    test.deliver_all_messages();
+    let msgs = test.dump_sinks();
+    assert_eq!(msgs.len(), 1);
+    let SapMsgInner::TlaTlUnitdataReqBl(req) = &msgs[0].msg else {
+        panic!("expected TL-UNITDATA request, got {:?}", msgs[0].msg);
+    };
+    assert_eq!(req.req_handle, 0, "no report expected for unacknowledged service");
+}
+
+/// BL-ACK from the MS, as received from the MAC
+fn bl_ack_ind(issi: u32, nr: u8) -> SapMsg {
+    let mut pdu = BitBuffer::new_autoexpand(8);
+    BlAck { has_fcs: false, nr }.to_bitbuf(&mut pdu);
+    pdu.seek(0);
+    SapMsg::new(
+        Sap::TmaSap,
+        TetraEntity::Umac,
+        TetraEntity::Llc,
+        SapMsgInner::TmaUnitdataInd(TmaUnitdataInd {
+            pdu: Some(pdu),
+            main_address: TetraAddress::issi(issi),
+            scrambling_code: 864282631,
+            endpoint_id: 0,
+            new_endpoint_id: None,
+            css_endpoint_id: None,
+            air_interface_encryption: 0,
+            chan_change_response_req: false,
+            chan_change_handle: None,
+            chan_info: None,
+        }),
+    )
+}
+
+/// Runs the stack one timeslot, letting the MAC transmit whatever LLC handed it. Returns the messages for CMCE.
+fn run_and_transmit(test: &mut ComponentTest) -> Vec<SapMsg> {
+    test.run_stack(Some(1));
+    let mut to_cmce = Vec::new();
+    for msg in test.dump_sinks() {
+        match msg.msg {
+            SapMsgInner::TmaUnitdataReq(ref prim) => {
+                if let Some(tx_reporter) = &prim.tx_reporter {
+                    tx_reporter.mark_transmitted();
+                }
+            }
+            _ if msg.dest == TetraEntity::Cmce => to_cmce.push(msg),
+            _ => {}
+        }
+    }
+    to_cmce
+}
+
+#[test]
+fn test_llc_reports_acknowledged_delivery() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, Some(TdmaTime::default().add_timeslots(2)));
+    test.populate_entities(vec![TetraEntity::Mle, TetraEntity::Llc], vec![TetraEntity::Umac, TetraEntity::Cmce]);
+
+    test.submit_message(cmce_unitdata_req(7));
+    assert!(run_and_transmit(&mut test).is_empty());
+    assert!(run_and_transmit(&mut test).is_empty(), "no report before the MS acknowledges");
+
+    // The MS acknowledges N(S)=0, LLC reports success and MLE maps it back to the CMCE handle
+    test.submit_message(bl_ack_ind(1000, 0));
+    let msgs = run_and_transmit(&mut test);
+    assert_eq!(msgs.len(), 1);
+    let SapMsgInner::LcmcMleReportInd(report) = &msgs[0].msg else {
+        panic!("expected LCMC report, got {:?}", msgs[0].msg);
+    };
+    assert_eq!(report.handle, 7);
+    assert_eq!(report.transfer_result, TransferResult::Successful);
+}
+
+#[test]
+fn test_llc_reports_failed_delivery() {
+    debug::setup_logging_verbose();
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.llc.t251_ms = 100; // 7 timeslots
+    config.llc.n252 = 2;
+    let t251 = config.llc.t251_timeslots() as usize;
+    let mut test = ComponentTest::from_config(config, Some(TdmaTime::default().add_timeslots(2)));
+    test.populate_entities(vec![TetraEntity::Mle, TetraEntity::Llc], vec![TetraEntity::Umac, TetraEntity::Cmce]);
+
+    // The MS never acknowledges, so LLC gives up after N.252 retransmissions
+    test.submit_message(cmce_unitdata_req(7));
+    let mut reports = Vec::new();
+    for _ in 0..(t251 + 1) * 4 {
+        reports.extend(run_and_transmit(&mut test));
+    }
+    assert_eq!(reports.len(), 1);
+    let SapMsgInner::LcmcMleReportInd(report) = &reports[0].msg else {
+        panic!("expected LCMC report, got {:?}", reports[0].msg);
+    };
+    assert_eq!(report.handle, 7);
+    assert_eq!(report.transfer_result, TransferResult::Failed);
+}
+
+#[test]
+fn test_energy_economy_configured_in_mac() {
+    debug::setup_logging_verbose();
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.cell.energy_economy = Some(EnergyEconomyConfig {
+        group: 2,
+        startpoint: 7,
+        dual_watch_group: Some(4),
+    });
+    let mut test = ComponentTest::from_config(config, None);
+    test.populate_entities(vec![TetraEntity::Mle], vec![TetraEntity::Umac]);
+
+    // TL-CONFIGURE is sent once, directly to the MAC
+    test.run_stack(Some(4));
+    let msgs = test.dump_sinks();
+    assert_eq!(msgs.len(), 1);
+    assert_eq!(msgs[0].sap, Sap::TlmcSap);
+    let SapMsgInner::TlmcConfigureReq(req) = &msgs[0].msg else {
+        panic!("expected TL-CONFIGURE request, got {:?}", msgs[0].msg);
+    };
+    assert_eq!(req.energy_economy_group, Some(2));
+    assert_eq!(req.energy_economy_startpoint, Some(7));
+    assert_eq!(req.dual_watch_energy_economy_group, Some(4));
+}
+
+#[test]
+fn test_energy_economy_disabled_not_configured() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, None);
+    test.populate_entities(vec![TetraEntity::Mle], vec![TetraEntity::Umac]);
+    test.run_stack(Some(4));
+    assert!(test.dump_sinks().is_empty());
+}
+
+fn llc_data_ind(issi: u32, tl_sdu: &str) -> SapMsg {
+    SapMsg::new(
+        Sap::TlaSap,
+        TetraEntity::Llc,
+        TetraEntity::Mle,
+        SapMsgInner::TlaTlDataIndBl(TlaTlDataIndBl {
+            main_address: TetraAddress::issi(issi),
+            link_id: 0,
+            endpoint_id: 0,
+            new_endpoint_id: None,
+            css_endpoint_id: None,
+            tl_sdu: Some(BitBuffer::from_bitstr(tl_sdu)),
+            scrambling_code: 0,
+            fcs_flag: false,
+            air_interface_encryption: 0,
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+            chan_info: None,
+            req_handle: 0,
+        }),
+    )
+}
+
+#[test]
+fn test_channel_class_advice_stored() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, None);
+    test.populate_entities(vec![TetraEntity::Mle], vec![TetraEntity::Llc, TetraEntity::Mm]);
+    test.get_shared_config().state_write().subscribers.register(1000);
+
+    // MLE protocol discriminator 101, U-CHANNEL CLASS ADVICE with classes 2 and 5 and data priority 6
+    let advice = "10101101001001010111100";
+    test.submit_message(llc_data_ind(1000, advice));
+    // Advice from an MS that isn't registered is ignored
+    test.submit_message(llc_data_ind(1001, advice));
+    test.deliver_all_messages();
+    assert!(test.dump_sinks().is_empty());
+
+    let config = test.get_shared_config();
+    let state = config.state_read();
+    let subscribers: Vec<_> = state.subscribers.subscribers().collect();
+    assert_eq!(subscribers.len(), 1);
+    assert_eq!(
+        subscribers[0].channel_class,
+        Some(ChannelClassInfo {
+            identifiers: vec![2, 5],
+            data_priority: Some(6),
+        })
+    );
+}
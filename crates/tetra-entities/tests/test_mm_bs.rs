@@ -1,9 +1,13 @@
 mod common;
 
-use tetra_config::bluestation::StackMode;
+use tetra_config::bluestation::{StackMode, SubscriberPolicy};
 use tetra_core::tetra_entities::TetraEntity;
 use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_pdus::mm::enums::location_update_type::LocationUpdateType;
+use tetra_pdus::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use tetra_pdus::mm::enums::reject_cause::RejectCause;
 use tetra_pdus::mm::pdus::d_mm_status::DMmStatus;
+use tetra_pdus::mm::pdus::u_location_update_demand::ULocationUpdateDemand;
 use tetra_saps::lmm::LmmMleUnitdataInd;
 use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
 
@@ -24,12 +28,12 @@ fn test_u_mm_status_energy_saving() {
             ssi: 2040814,
         },
     };
-    let test_sapmsg1 = SapMsg {
-        sap: Sap::LmmSap,
-        src: TetraEntity::Mle,
-        dest: TetraEntity::Mm,
-        msg: SapMsgInner::LmmMleUnitdataInd(test_prim1),
-    };
+    let test_sapmsg1 = SapMsg::new(
+        Sap::LmmSap,
+        TetraEntity::Mle,
+        TetraEntity::Mm,
+        SapMsgInner::LmmMleUnitdataInd(test_prim1),
+    );
 
     // Setup testing stack
     let mut test = ComponentTest::new(StackMode::Bs, Some(dltime_vec1));
@@ -57,3 +61,139 @@ fn test_u_mm_status_energy_saving() {
     );
     assert!(resp_pdu.energy_saving_information.is_some());
 }
+
+fn get_policy_test(policy: SubscriberPolicy) -> ComponentTest {
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.cell.subscriber_policy = policy;
+    let mut test = ComponentTest::from_config(config, None);
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle, TetraEntity::Cmce]);
+    test
+}
+
+/// Send an ITSI attach from the given ISSI. Returns the MM PDU type of the response and the reject cause, if rejected.
+fn itsi_attach(test: &mut ComponentTest, issi: u32) -> (MmPduTypeDl, Option<u8>) {
+    let pdu = ULocationUpdateDemand {
+        location_update_type: LocationUpdateType::ItsiAttach,
+        request_to_append_la: false,
+        cipher_control: false,
+        ciphering_parameters: None,
+        class_of_ms: None,
+        energy_saving_mode: None,
+        la_information: None,
+        ssi: None,
+        address_extension: None,
+        group_identity_location_demand: None,
+        group_report_response: None,
+        authentication_uplink: None,
+        extended_capabilities: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(16);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(SapMsg::new(
+        Sap::LmmSap,
+        TetraEntity::Mle,
+        TetraEntity::Mm,
+        SapMsgInner::LmmMleUnitdataInd(LmmMleUnitdataInd {
+            sdu,
+            handle: 0,
+            received_address: TetraAddress::issi(issi),
+        }),
+    ));
+    test.run_stack(Some(1));
+
+    let msgs = test.dump_sinks();
+    let responses: Vec<_> = msgs
+        .iter()
+        .filter_map(|msg| match &msg.msg {
+            SapMsgInner::LmmMleUnitdataReq(prim) => Some(BitBuffer::from_bitbuffer(&prim.sdu)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(responses.len(), 1, "expected a single response to ISSI {}", issi);
+    let mut sdu = responses.into_iter().next().unwrap();
+    let pdu_type = MmPduTypeDl::try_from(sdu.peek_bits(4).unwrap()).unwrap();
+    // The reject cause follows the 4-bit PDU type and the 3-bit location update type
+    let reject_cause = (pdu_type == MmPduTypeDl::DLocationUpdateReject).then(|| {
+        sdu.seek(7);
+        sdu.read_field(5, "reject_cause").unwrap() as u8
+    });
+    (pdu_type, reject_cause)
+}
+
+fn is_registered(test: &ComponentTest, issi: u32) -> bool {
+    test.config.state_read().subscribers.is_registered(issi)
+}
+
+#[test]
+fn test_subscriber_policy_allow_all() {
+    debug::setup_logging_verbose();
+    let mut test = get_policy_test(SubscriberPolicy::AllowAll);
+    for issi in [1, 2041234, 0xFFFFFF] {
+        assert_eq!(itsi_attach(&mut test, issi), (MmPduTypeDl::DLocationUpdateAccept, None));
+        assert!(is_registered(&test, issi));
+    }
+}
+
+#[test]
+fn test_subscriber_policy_whitelist() {
+    debug::setup_logging_verbose();
+    let mut test = get_policy_test(SubscriberPolicy::Whitelist {
+        issis: vec![2041234, 2041235],
+    });
+
+    for issi in [2041234, 2041235] {
+        assert_eq!(itsi_attach(&mut test, issi), (MmPduTypeDl::DLocationUpdateAccept, None));
+        assert!(is_registered(&test, issi));
+    }
+
+    // Direct neighbours of listed ISSIs are not provisioned
+    for issi in [2041233, 2041236] {
+        assert_eq!(
+            itsi_attach(&mut test, issi),
+            (MmPduTypeDl::DLocationUpdateReject, Some(RejectCause::ItsiAtsiUnknown as u8))
+        );
+        assert!(!is_registered(&test, issi));
+    }
+}
+
+#[test]
+fn test_subscriber_policy_blacklist() {
+    debug::setup_logging_verbose();
+    let mut test = get_policy_test(SubscriberPolicy::Blacklist { issis: vec![2041234] });
+
+    assert_eq!(
+        itsi_attach(&mut test, 2041234),
+        (MmPduTypeDl::DLocationUpdateReject, Some(RejectCause::IllegalMs as u8))
+    );
+    assert!(!is_registered(&test, 2041234));
+
+    for issi in [2041233, 2041235] {
+        assert_eq!(itsi_attach(&mut test, issi), (MmPduTypeDl::DLocationUpdateAccept, None));
+        assert!(is_registered(&test, issi));
+    }
+}
+
+#[test]
+fn test_subscriber_class_filtering() {
+    debug::setup_logging_verbose();
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.cell.subscriber_class = 0x0003;
+    config.cell.ms_subscriber_classes = [(2041234, 0x0002), (2041235, 0x000C)].into_iter().collect();
+    let mut test = ComponentTest::from_config(config, None);
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle, TetraEntity::Cmce]);
+
+    // Class 2 is allowed, and an MS without an assigned class is not filtered
+    for issi in [2041234, 2041236] {
+        assert_eq!(itsi_attach(&mut test, issi), (MmPduTypeDl::DLocationUpdateAccept, None));
+        assert!(is_registered(&test, issi));
+    }
+
+    // Classes 3 and 4 are both barred in this cell
+    assert_eq!(
+        itsi_attach(&mut test, 2041235),
+        (MmPduTypeDl::DLocationUpdateReject, Some(RejectCause::LaNotAllowed as u8))
+    );
+    assert!(!is_registered(&test, 2041235));
+}
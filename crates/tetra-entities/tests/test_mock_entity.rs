@@ -0,0 +1,58 @@
+mod common;
+
+use tetra_config::bluestation::StackMode;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, PhyBlockNum, Sap, TdmaTime, debug};
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tetra_saps::tmv::{TmvUnitdataInd, enums::logical_chans::LogicalChannel};
+
+use crate::common::ComponentTest;
+use crate::common::mock_entity::MockEntity;
+
+#[test]
+/// UMAC in isolation, with a MockEntity as LLC peer. A MAC-ACCESS on SCH/HU must be passed up as TMA-UNITDATA indication.
+fn test_umac_bs_mac_access_to_mock_llc() {
+    debug::setup_logging_verbose();
+    // MAC-ACCESS: type 0, fill_bits 1, encrypted 0, addr_type 00, ssi 30128, optional_field_flag 1, length_ind 11 octets
+    // followed by a 48-bit SDU, fill bits 1000 and unused 0000 to the end of the subslot
+    let mac_access = concat!(
+        "010",
+        "00",
+        "000000000111010110110000",
+        "1",
+        "0",
+        "01011",
+        "101100110111001101001100011010111000000000000111",
+        "1000",
+        "0000"
+    );
+    let dltime = TdmaTime::default().add_timeslots(2);
+
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Umac], vec![TetraEntity::Lmac]);
+    test.register_entity(MockEntity::new(TetraEntity::Llc));
+
+    test.submit_message(SapMsg::new(
+        Sap::TmvSap,
+        TetraEntity::Lmac,
+        TetraEntity::Umac,
+        SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+            pdu: BitBuffer::from_bitstr(mac_access),
+            block_num: PhyBlockNum::Block1,
+            logical_channel: LogicalChannel::SchHu,
+            crc_pass: true,
+            scrambling_code: 864282631,
+        }),
+    ));
+    test.deliver_all_messages();
+
+    let llc = test.get_mock(TetraEntity::Llc).unwrap();
+    let msg = llc.assert_received(Sap::TmaSap, |m| matches!(m, SapMsgInner::TmaUnitdataInd(_)));
+    let SapMsgInner::TmaUnitdataInd(prim) = &msg.msg else { panic!() };
+    assert_eq!(prim.main_address.ssi, 30128);
+    assert_eq!(
+        prim.pdu.as_ref().unwrap().to_bitstr(),
+        "101100110111001101001100011010111000000000000111"
+    );
+    llc.assert_not_received(Sap::TmaSap, |m| matches!(m, SapMsgInner::TmaReportInd(_)));
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use tetra_config::bluestation::StackMode;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, BurstType, Sap, TrainingSequence, debug};
+use tetra_entities::phy::phy_bs::PhyBs;
+use tetra_pdus::phy::traits::rxtx_dev::{RxSlotBits, RxTxDev, RxTxDevError, TxSlotBits};
+use tetra_saps::control::admin::PhyRxStats;
+use tetra_saps::tp::TpUnitdataReqSlot;
+use tetra_saps::{SapMsg, SapMsgInner};
+
+use crate::common::ComponentTest;
+
+/// RX/TX device that receives nothing and records whether it was shut down
+struct MockDev {
+    stopped: Arc<AtomicBool>,
+}
+
+impl RxTxDev for MockDev {
+    fn rxtx_timeslot(&mut self, _tx_slot: &[TxSlotBits]) -> Result<Vec<Option<RxSlotBits<'_>>>, RxTxDevError> {
+        assert!(!self.stopped.load(Ordering::SeqCst), "slot exchanged after shutdown");
+        Ok(vec![])
+    }
+
+    fn shutdown(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn test_shutdown_stops_device() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, None);
+    let stopped = Arc::new(AtomicBool::new(false));
+    let dev = MockDev { stopped: stopped.clone() };
+    test.register_entity(PhyBs::new(test.get_shared_config(), dev));
+    test.populate_entities(vec![], vec![TetraEntity::Lmac]);
+
+    assert!(!stopped.load(Ordering::SeqCst));
+    test.router.shutdown();
+    assert!(stopped.load(Ordering::SeqCst));
+}
+
+/// RX/TX device that demodulates one slot without a burst per timeslot and loses samples on every read
+struct OverrunDev {
+    overruns: Arc<AtomicU64>,
+}
+
+impl RxTxDev for OverrunDev {
+    fn rxtx_timeslot(&mut self, _tx_slot: &[TxSlotBits]) -> Result<Vec<Option<RxSlotBits<'_>>>, RxTxDevError> {
+        self.overruns.fetch_add(1, Ordering::SeqCst);
+        Ok(vec![Some(RxSlotBits::default())])
+    }
+
+    fn rx_stats(&self) -> PhyRxStats {
+        PhyRxStats {
+            buffer_overruns: self.overruns.load(Ordering::SeqCst),
+            ..Default::default()
+        }
+    }
+}
+
+fn build_tx_slot() -> SapMsg {
+    SapMsg::new(
+        Sap::TpSap,
+        TetraEntity::Lmac,
+        TetraEntity::Phy,
+        SapMsgInner::TpUnitdataReq(TpUnitdataReqSlot {
+            train_type: TrainingSequence::NormalTrainSeq1,
+            burst_type: BurstType::NDB,
+            bbk: Some(BitBuffer::from_bitstr(&"0".repeat(30))),
+            blk1: Some(BitBuffer::from_bitstr(&"0".repeat(432))),
+            blk2: None,
+        }),
+    )
+}
+
+#[test]
+fn test_rx_stats_buffer_overruns() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, None);
+    let overruns = Arc::new(AtomicU64::new(0));
+    let dev = OverrunDev {
+        overruns: overruns.clone(),
+    };
+    test.register_entity(PhyBs::new(test.get_shared_config(), dev));
+    test.populate_entities(vec![], vec![TetraEntity::Lmac]);
+
+    assert_eq!(test.router.get_stack_state().phy_rx_stats, Some(PhyRxStats::default()));
+
+    for _ in 0..3 {
+        test.submit_message(build_tx_slot());
+        test.deliver_all_messages();
+    }
+
+    let stats = test.router.get_stack_state().phy_rx_stats.expect("PHY stats in snapshot");
+    assert_eq!(stats.buffer_overruns, 3);
+    assert_eq!(stats.bursts_attempted, 3);
+    assert_eq!(stats.bursts_decoded, 0);
+    assert_eq!(stats.correlation_failures, 3);
+    assert_eq!(stats.timing_errors, 0);
+    assert!(test.dump_sinks().is_empty(), "no burst must reach the LMAC");
+}
@@ -1,8 +1,9 @@
 mod common;
 
+use std::collections::HashMap;
 use std::time::Duration;
 
-use tetra_config::bluestation::{CfgBrew, StackMode};
+use tetra_config::bluestation::{CfgBrew, DEFAULT_BREW_PRIORITY_MAP, StackMode};
 use tetra_core::tetra_entities::TetraEntity;
 use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug};
 use tetra_pdus::cmce::enums::party_type_identifier::PartyTypeIdentifier;
@@ -43,11 +44,11 @@ fn build_u_sds_data_msg(source_issi: u32, dest_ssi: u32, payload: u16) -> SapMsg
     u_sds.to_bitbuf(&mut sdu).expect("Failed to serialize U-SDS-DATA");
     sdu.seek(0);
 
-    SapMsg {
-        sap: Sap::LcmcSap,
-        src: TetraEntity::Mle,
-        dest: TetraEntity::Cmce,
-        msg: SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
+    SapMsg::new(
+        Sap::LcmcSap,
+        TetraEntity::Mle,
+        TetraEntity::Cmce,
+        SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
             sdu,
             handle: 1,
             endpoint_id: 1,
@@ -56,7 +57,7 @@ fn build_u_sds_data_msg(source_issi: u32, dest_ssi: u32, payload: u16) -> SapMsg
             chan_change_resp_req: false,
             chan_change_handle: None,
         }),
-    }
+    )
 }
 
 /// Count D-SDS-DATA messages (LcmcMleUnitdataReq to Mle) in sink output
@@ -120,9 +121,14 @@ fn test_sds_brew_forward() {
         username: None,
         password: None,
         reconnect_delay: Duration::from_secs(1),
+        jitter_buffer_frames: 4,
         jitter_initial_latency_frames: 0,
+        recording_dir: None,
         feature_sds_enabled: true,
         whitelisted_ssis: None,
+        monitoring_only: vec![],
+        alias_map: HashMap::new(),
+        priority_map: DEFAULT_BREW_PRIORITY_MAP,
     });
     let mut test = ComponentTest::from_config(config, Some(dltime));
 
@@ -158,16 +164,16 @@ fn test_sds_from_brew_to_local() {
     register_subscriber(&mut test, 2000001);
 
     // Submit CmceSdsData from Brew on Control SAP
-    let msg = SapMsg {
-        sap: Sap::Control,
-        src: TetraEntity::Brew,
-        dest: TetraEntity::Cmce,
-        msg: SapMsgInner::CmceSdsData(CmceSdsData {
+    let msg = SapMsg::new(
+        Sap::Control,
+        TetraEntity::Brew,
+        TetraEntity::Cmce,
+        SapMsgInner::CmceSdsData(CmceSdsData {
             source_issi: 3000001,
             dest_issi: 2000001,
             user_defined_data: SdsUserData::Type1(0xCAFE),
         }),
-    };
+    );
     test.submit_message(msg);
     test.run_stack(Some(1));
 
@@ -188,16 +194,16 @@ fn test_sds_from_brew_unregistered() {
     test.populate_entities(components, sinks);
 
     // Do NOT register dest ISSI
-    let msg = SapMsg {
-        sap: Sap::Control,
-        src: TetraEntity::Brew,
-        dest: TetraEntity::Cmce,
-        msg: SapMsgInner::CmceSdsData(CmceSdsData {
+    let msg = SapMsg::new(
+        Sap::Control,
+        TetraEntity::Brew,
+        TetraEntity::Cmce,
+        SapMsgInner::CmceSdsData(CmceSdsData {
             source_issi: 3000001,
             dest_issi: 9999999,
             user_defined_data: SdsUserData::Type1(0xDEAD),
         }),
-    };
+    );
     test.submit_message(msg);
     test.run_stack(Some(1));
 
@@ -276,11 +282,11 @@ fn test_u_status_forwarded_as_d_status() {
     u_status.to_bitbuf(&mut sdu).expect("Failed to serialize U-STATUS");
     sdu.seek(0);
 
-    let msg = SapMsg {
-        sap: Sap::LcmcSap,
-        src: TetraEntity::Mle,
-        dest: TetraEntity::Cmce,
-        msg: SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
+    let msg = SapMsg::new(
+        Sap::LcmcSap,
+        TetraEntity::Mle,
+        TetraEntity::Cmce,
+        SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
             sdu,
             handle: 1,
             endpoint_id: 1,
@@ -289,7 +295,7 @@ fn test_u_status_forwarded_as_d_status() {
             chan_change_resp_req: false,
             chan_change_handle: None,
         }),
-    };
+    );
     test.submit_message(msg);
     test.run_stack(Some(1));
 
@@ -322,9 +328,14 @@ fn test_u_status_brew_forward() {
         username: None,
         password: None,
         reconnect_delay: Duration::from_secs(1),
+        jitter_buffer_frames: 4,
         jitter_initial_latency_frames: 0,
+        recording_dir: None,
         feature_sds_enabled: true,
         whitelisted_ssis: None,
+        monitoring_only: vec![],
+        alias_map: HashMap::new(),
+        priority_map: DEFAULT_BREW_PRIORITY_MAP,
     });
     let mut test = ComponentTest::from_config(config, Some(dltime));
 
@@ -350,11 +361,11 @@ fn test_u_status_brew_forward() {
     u_status.to_bitbuf(&mut sdu).expect("Failed to serialize U-STATUS");
     sdu.seek(0);
 
-    let msg = SapMsg {
-        sap: Sap::LcmcSap,
-        src: TetraEntity::Mle,
-        dest: TetraEntity::Cmce,
-        msg: SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
+    let msg = SapMsg::new(
+        Sap::LcmcSap,
+        TetraEntity::Mle,
+        TetraEntity::Cmce,
+        SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
             sdu,
             handle: 1,
             endpoint_id: 1,
@@ -363,7 +374,7 @@ fn test_u_status_brew_forward() {
             chan_change_resp_req: false,
             chan_change_handle: None,
         }),
-    };
+    );
     test.submit_message(msg);
     test.run_stack(Some(1));
 
@@ -417,11 +428,11 @@ fn test_u_status_unregistered_dest_dropped() {
     u_status.to_bitbuf(&mut sdu).expect("Failed to serialize U-STATUS");
     sdu.seek(0);
 
-    let msg = SapMsg {
-        sap: Sap::LcmcSap,
-        src: TetraEntity::Mle,
-        dest: TetraEntity::Cmce,
-        msg: SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
+    let msg = SapMsg::new(
+        Sap::LcmcSap,
+        TetraEntity::Mle,
+        TetraEntity::Cmce,
+        SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
             sdu,
             handle: 1,
             endpoint_id: 1,
@@ -430,7 +441,7 @@ fn test_u_status_unregistered_dest_dropped() {
             chan_change_resp_req: false,
             chan_change_handle: None,
         }),
-    };
+    );
     test.submit_message(msg);
     test.run_stack(Some(1));
 
@@ -1,11 +1,32 @@
 mod common;
 
-use tetra_config::bluestation::StackMode;
+use std::sync::{Arc, Mutex};
+
+use tetra_config::bluestation::{NeighborCell, StackMode};
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, Layer2Service, PhyBlockNum, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_core::{
+    BitBuffer, BurstType, Direction, Layer2Service, MAIN_CARRIER_ID, PhyBlockNum, PhysicalChannel, Sap, SsiType, TdmaTime, TetraAddress,
+    debug,
+};
+use tetra_pdus::llc::pdus::bl_udata::BlUdata;
+use tetra_pdus::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
+use tetra_pdus::mle::enums::mle_protocol_discriminator::MleProtocolDiscriminator;
+use tetra_pdus::mle::pdus::d_nwrk_broadcast::DNwrkBroadcast;
+use tetra_pdus::mle::pdus::d_nwrk_broadcast_remove::DNwrkBroadcastRemove;
+use tetra_pdus::umac::pdus::mac_resource::MacResource;
+use tetra_saps::control::call_control::{CallControl, Circuit};
+use tetra_saps::control::enums::circuit_mode_type::CircuitModeType;
 use tetra_saps::lmm::LmmMleUnitdataReq;
 use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tetra_saps::tlmc::TlmcConfigureReq;
 use tetra_saps::tmv::{TmvUnitdataInd, enums::logical_chans::LogicalChannel};
+use tracing::Level;
+use tracing::field::{Field, Visit};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::{Layer, Registry};
+
+use tetra_entities::umac::subcomp::cell_load::{CELL_LOAD_HIGH, CELL_LOAD_LOW, CELL_LOAD_MEDIUM};
+use tetra_entities::umac::umac_bs::UmacBs;
 
 use crate::common::ComponentTest;
 
@@ -25,12 +46,12 @@ fn test_in_fragmented_sch_hu_and_sch_f() {
         crc_pass: true,
         scrambling_code: 864282631,
     };
-    let test_sapmsg1 = SapMsg {
-        sap: Sap::TmvSap,
-        src: TetraEntity::Lmac,
-        dest: TetraEntity::Umac,
-        msg: SapMsgInner::TmvUnitdataInd(test_prim1),
-    };
+    let test_sapmsg1 = SapMsg::new(
+        Sap::TmvSap,
+        TetraEntity::Lmac,
+        TetraEntity::Umac,
+        SapMsgInner::TmvUnitdataInd(test_prim1),
+    );
     let test_prim2 = TmvUnitdataInd {
         pdu: BitBuffer::from_bitstr(test_vec2),
         block_num: PhyBlockNum::Both,
@@ -38,12 +59,12 @@ fn test_in_fragmented_sch_hu_and_sch_f() {
         crc_pass: true,
         scrambling_code: 864282631,
     };
-    let test_sapmsg2 = SapMsg {
-        sap: Sap::TmvSap,
-        src: TetraEntity::Lmac,
-        dest: TetraEntity::Umac,
-        msg: SapMsgInner::TmvUnitdataInd(test_prim2),
-    };
+    let test_sapmsg2 = SapMsg::new(
+        Sap::TmvSap,
+        TetraEntity::Lmac,
+        TetraEntity::Umac,
+        SapMsgInner::TmvUnitdataInd(test_prim2),
+    );
 
     // Setup testing stack
     let mut test = ComponentTest::new(StackMode::Bs, Some(dltime_vec1));
@@ -83,12 +104,12 @@ fn test_in_fragmented_sch_hu_and_sch_hu() {
         crc_pass: true,
         scrambling_code: 864282631,
     };
-    let test_sapmsg1 = SapMsg {
-        sap: Sap::TmvSap,
-        src: TetraEntity::Lmac,
-        dest: TetraEntity::Umac,
-        msg: SapMsgInner::TmvUnitdataInd(test_prim1),
-    };
+    let test_sapmsg1 = SapMsg::new(
+        Sap::TmvSap,
+        TetraEntity::Lmac,
+        TetraEntity::Umac,
+        SapMsgInner::TmvUnitdataInd(test_prim1),
+    );
     let test_prim2 = TmvUnitdataInd {
         pdu: BitBuffer::from_bitstr(test_vec2),
         block_num: PhyBlockNum::Block1,
@@ -96,12 +117,12 @@ fn test_in_fragmented_sch_hu_and_sch_hu() {
         crc_pass: true,
         scrambling_code: 864282631,
     };
-    let test_sapmsg2 = SapMsg {
-        sap: Sap::TmvSap,
-        src: TetraEntity::Lmac,
-        dest: TetraEntity::Umac,
-        msg: SapMsgInner::TmvUnitdataInd(test_prim2),
-    };
+    let test_sapmsg2 = SapMsg::new(
+        Sap::TmvSap,
+        TetraEntity::Lmac,
+        TetraEntity::Umac,
+        SapMsgInner::TmvUnitdataInd(test_prim2),
+    );
 
     // Setup testing stack
     let mut test = ComponentTest::new(StackMode::Bs, Some(dltime_vec1));
@@ -147,12 +168,12 @@ fn test_out_fragmented_resource() {
         is_null_pdu: false,
         tx_reporter: None,
     };
-    let test_sapmsg = SapMsg {
-        sap: Sap::LmmSap,
-        src: TetraEntity::Mm,
-        dest: TetraEntity::Mle,
-        msg: SapMsgInner::LmmMleUnitdataReq(test_prim),
-    };
+    let test_sapmsg = SapMsg::new(
+        Sap::LmmSap,
+        TetraEntity::Mm,
+        TetraEntity::Mle,
+        SapMsgInner::LmmMleUnitdataReq(test_prim),
+    );
 
     // Setup testing stack
     let mut test = ComponentTest::new(StackMode::Bs, Some(dltime_vec));
@@ -166,3 +187,313 @@ fn test_out_fragmented_resource() {
 
     tracing::info!("Validation of result not implemented");
 }
+
+fn call_control_msg(prim: CallControl) -> SapMsg {
+    SapMsg::new(
+        Sap::Control,
+        TetraEntity::Cmce,
+        TetraEntity::Umac,
+        SapMsgInner::CmceCallControl(prim),
+    )
+}
+
+#[test]
+fn test_circuit_endpoint_registry() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Umac], vec![TetraEntity::Lmac]);
+
+    // Open a duplex circuit on ts 2, its endpoint id is the timeslot number
+    test.submit_message(call_control_msg(CallControl::Open(Circuit {
+        direction: Direction::Both,
+        ts: 2,
+        usage: 4,
+        circuit_mode: CircuitModeType::TchS,
+        speech_service: Some(0),
+        etee_encrypted: false,
+    })));
+    test.run_stack(Some(1));
+    {
+        let state = test.config.state_read();
+        let info = state.endpoints.resolve(2).expect("endpoint registered");
+        assert_eq!(info.ts, 2);
+        assert_eq!(info.direction, Direction::Both);
+        assert_eq!(info.owner, TetraEntity::Cmce);
+        assert_eq!(state.endpoints.reverse_lookup(2), vec![2]);
+        assert!(state.endpoints.reverse_lookup(3).is_empty());
+    }
+
+    // Closing the downlink leaves the uplink endpoint in place
+    test.submit_message(call_control_msg(CallControl::Close(Direction::Dl, 2)));
+    test.run_stack(Some(1));
+    assert_eq!(test.config.state_read().endpoints.resolve(2).unwrap().direction, Direction::Ul);
+
+    test.submit_message(call_control_msg(CallControl::Close(Direction::Ul, 2)));
+    test.run_stack(Some(1));
+    assert!(test.config.state_read().endpoints.resolve(2).is_none());
+}
+
+#[test]
+fn test_phy_chan_carrier_id() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Umac], vec![TetraEntity::Lmac]);
+
+    test.submit_message(call_control_msg(CallControl::Open(Circuit {
+        direction: Direction::Both,
+        ts: 2,
+        usage: 4,
+        circuit_mode: CircuitModeType::TchS,
+        speech_service: Some(0),
+        etee_encrypted: false,
+    })));
+    test.run_stack(Some(8));
+
+    // Every slot handed to the LMAC is on the main carrier, traffic channels on their own timeslot
+    let mut num_slots = 0;
+    for msg in test.dump_sinks() {
+        let SapMsgInner::TmvUnitdataReq(slot) = msg.msg else {
+            continue;
+        };
+        num_slots += 1;
+        let expected = if slot.ts.t == 2 {
+            PhysicalChannel::Tp {
+                ts: 2,
+                carrier_id: MAIN_CARRIER_ID,
+            }
+        } else {
+            PhysicalChannel::Cp {
+                carrier_id: MAIN_CARRIER_ID,
+            }
+        };
+        assert_eq!(slot.ul_phy_chan, expected, "ts {}", slot.ts);
+    }
+    assert!(num_slots >= 8);
+}
+
+#[test]
+fn test_cell_load_broadcast() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Umac], vec![TetraEntity::Lmac]);
+
+    let cell_load = |test: &mut ComponentTest| {
+        let umac = test.router.get_entity(TetraEntity::Umac).unwrap();
+        umac.as_any_mut().downcast_mut::<UmacBs>().unwrap().channel_scheduler.cell_load()
+    };
+    test.run_stack(Some(1));
+    assert_eq!(cell_load(&mut test), CELL_LOAD_LOW);
+
+    // Traffic on 3 of the 4 timeslots
+    for ts in 2..=4 {
+        test.submit_message(call_control_msg(CallControl::Open(Circuit {
+            direction: Direction::Both,
+            ts,
+            usage: 2 + ts,
+            circuit_mode: CircuitModeType::TchS,
+            speech_service: Some(0),
+            etee_encrypted: false,
+        })));
+    }
+    test.run_stack(Some(2));
+    assert!(matches!(cell_load(&mut test), CELL_LOAD_MEDIUM | CELL_LOAD_HIGH));
+
+    for ts in 2..=4 {
+        test.submit_message(call_control_msg(CallControl::Close(Direction::Both, ts)));
+    }
+    test.run_stack(Some(2));
+    assert_eq!(cell_load(&mut test), CELL_LOAD_LOW);
+}
+
+/// Tracing layer that records the message of every ERROR event
+struct ErrorCapture(Arc<Mutex<Vec<String>>>);
+
+#[derive(Default)]
+struct MessageVisitor(String);
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.0 = format!("{:?}", value);
+        }
+    }
+}
+
+impl<S: tracing::Subscriber> Layer<S> for ErrorCapture {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        if *event.metadata().level() == Level::ERROR {
+            let mut visitor = MessageVisitor::default();
+            event.record(&mut visitor);
+            self.0.lock().unwrap().push(visitor.0);
+        }
+    }
+}
+
+#[test]
+fn test_invalid_block_num_dropped() {
+    assert!(UmacBs::block_num_valid_for_channel(LogicalChannel::SchF, PhyBlockNum::Both));
+    assert!(!UmacBs::block_num_valid_for_channel(LogicalChannel::SchF, PhyBlockNum::Block1));
+    assert!(UmacBs::block_num_valid_for_channel(LogicalChannel::Stch, PhyBlockNum::Block2));
+    assert!(!UmacBs::block_num_valid_for_channel(LogicalChannel::SchHu, PhyBlockNum::Both));
+    assert!(!UmacBs::block_num_valid_for_channel(LogicalChannel::Aach, PhyBlockNum::Both));
+
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Umac], vec![TetraEntity::Llc]);
+
+    // Valid MAC-DATA on SCH/F, but handed up by the LMAC as a half slot
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let subscriber = Registry::default().with(ErrorCapture(errors.clone()));
+    tracing::subscriber::with_default(subscriber, || {
+        test.submit_message(SapMsg::new(
+            Sap::TmvSap,
+            TetraEntity::Lmac,
+            TetraEntity::Umac,
+            SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+                pdu: BitBuffer::from_bitstr(&"0".repeat(268)),
+                block_num: PhyBlockNum::Block1,
+                logical_channel: LogicalChannel::SchF,
+                crc_pass: true,
+                scrambling_code: 864282631,
+            }),
+        ));
+        test.deliver_all_messages();
+    });
+
+    let errors = errors.lock().unwrap();
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(errors[0].contains("SchF") && errors[0].contains("Block1"), "{}", errors[0]);
+    assert!(test.dump_sinks().is_empty());
+}
+
+/// Number of synchronization bursts handed to the PHY
+fn count_sync_bursts(msgs: Vec<SapMsg>) -> usize {
+    msgs.into_iter()
+        .filter(|msg| matches!(&msg.msg, SapMsgInner::TpUnitdataReq(slot) if slot.burst_type == BurstType::SDB))
+        .count()
+}
+
+#[test]
+fn test_late_entry_sync_on_traffic_channel() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Umac, TetraEntity::Lmac], vec![TetraEntity::Phy]);
+
+    test.submit_message(call_control_msg(CallControl::Open(Circuit {
+        direction: Direction::Both,
+        ts: 2,
+        usage: 4,
+        circuit_mode: CircuitModeType::TchS,
+        speech_service: Some(0),
+        etee_encrypted: false,
+    })));
+    test.run_stack(Some(4));
+    let baseline = count_sync_bursts(test.dump_sinks());
+
+    // A late entering MS gets exactly one SYNC on the traffic timeslot, in place of a traffic burst
+    test.submit_message(call_control_msg(CallControl::LateEntry { call_id: 1, ts: 2 }));
+    test.run_stack(Some(4));
+    assert_eq!(count_sync_bursts(test.dump_sinks()), baseline + 1);
+    test.run_stack(Some(4));
+    assert_eq!(count_sync_bursts(test.dump_sinks()), baseline);
+
+    // No SYNC for a timeslot without a downlink circuit
+    test.submit_message(call_control_msg(CallControl::LateEntry { call_id: 2, ts: 3 }));
+    test.run_stack(Some(4));
+    assert_eq!(count_sync_bursts(test.dump_sinks()), baseline);
+}
+
+#[test]
+fn test_tlmc_configure_energy_economy() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, None);
+    test.populate_entities(vec![TetraEntity::Umac], vec![TetraEntity::Mle]);
+    test.submit_message(SapMsg::new(
+        Sap::TlmcSap,
+        TetraEntity::Mle,
+        TetraEntity::Umac,
+        SapMsgInner::TlmcConfigureReq(TlmcConfigureReq {
+            energy_economy_group: Some(3),
+            energy_economy_startpoint: Some(11),
+            dual_watch_energy_economy_group: None,
+            ..Default::default()
+        }),
+    ));
+    test.deliver_all_messages();
+
+    let msgs = test.dump_sinks();
+    assert_eq!(msgs.len(), 1);
+    assert_eq!(msgs[0].dest, TetraEntity::Mle);
+    let SapMsgInner::TlmcConfigureConf(conf) = &msgs[0].msg else {
+        panic!("expected TL-CONFIGURE confirm, got {:?}", msgs[0].msg);
+    };
+    assert_eq!(conf.energy_economy_group, Some(3));
+    assert_eq!(conf.energy_economy_startpoint, Some(11));
+    assert_eq!(conf.dual_watch_energy_economy_group, None);
+}
+
+/// MLE PDU carried in a neighbour cell block on frame 18, read up to and including the PDU type
+fn read_frame_18_mle_pdu(mut block: BitBuffer) -> (MlePduTypeDl, BitBuffer) {
+    let resource = MacResource::from_bitbuf(&mut block).unwrap();
+    assert_eq!(resource.addr.unwrap().ssi, 0xFFFFFF);
+    BlUdata::from_bitbuf(&mut block).unwrap();
+    assert_eq!(block.read_field(3, "pd").unwrap(), MleProtocolDiscriminator::Mle.into_raw());
+    let pdu_type = MlePduTypeDl::try_from(block.peek_bits(3).unwrap()).unwrap();
+    (pdu_type, block)
+}
+
+#[test]
+fn test_neighbor_cell_removed_on_config_reload() {
+    debug::setup_logging_verbose();
+    let neighbor = |carrier| NeighborCell {
+        carrier,
+        mcc: None,
+        mnc: None,
+        location_area: None,
+    };
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.cell.neighbor_cells = vec![neighbor(1522), neighbor(1530)];
+
+    // Start past the multiframe of the regular neighbour broadcast, which is then a hyperframe away
+    let dltime = TdmaTime::default().add_timeslots(2 * 18 * 4 + 2);
+    let mut test = ComponentTest::from_config(config.clone(), Some(dltime));
+    test.populate_entities(vec![TetraEntity::Umac], vec![TetraEntity::Lmac]);
+
+    // Drop the second neighbour and reload
+    config.cell.neighbor_cells = vec![neighbor(1522)];
+    let reloaded = test.get_shared_config().with_config(config.clone()).unwrap();
+    test.router.get_entity(TetraEntity::Umac).unwrap().set_config(reloaded);
+
+    test.run_stack(Some(3 * 18 * 4));
+    let frame_18_blocks: Vec<_> = test
+        .dump_sinks()
+        .into_iter()
+        .filter_map(|msg| match msg.msg {
+            SapMsgInner::TmvUnitdataReq(slot) if slot.ts.is_frame18() => slot.blk1,
+            _ => None,
+        })
+        .filter(|blk| blk.logical_channel == LogicalChannel::SchF)
+        .collect();
+    assert_eq!(frame_18_blocks.len(), 2);
+
+    // The removal comes first, on the first frame 18
+    let (pdu_type, mut block) = read_frame_18_mle_pdu(frame_18_blocks[0].mac_block.clone());
+    assert_eq!(pdu_type, MlePduTypeDl::ExtPdu);
+    let removal = DNwrkBroadcastRemove::from_bitbuf(&mut block).unwrap();
+    assert_eq!(removal, DNwrkBroadcastRemove { cell_identifier_ca: 2 });
+
+    // Followed by the new broadcast on the next one, without waiting for the next hyperframe
+    let (pdu_type, mut block) = read_frame_18_mle_pdu(frame_18_blocks[1].mac_block.clone());
+    assert_eq!(pdu_type, MlePduTypeDl::DNwrkBroadcast);
+    let broadcast = DNwrkBroadcast::from_bitbuf(&mut block).unwrap();
+    let carriers: Vec<_> = broadcast
+        .neighbour_cell_information_for_ca
+        .iter()
+        .map(|c| c.main_carrier_number)
+        .collect();
+    assert_eq!(carriers, vec![1522]);
+}
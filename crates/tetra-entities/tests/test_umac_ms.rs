@@ -17,11 +17,11 @@ fn test_umac_ms() {
     let sinks: Vec<TetraEntity> = vec![];
     test.populate_entities(components, sinks);
 
-    let m = SapMsg {
-        sap: Sap::TmvSap,
-        src: TetraEntity::Lmac,
-        dest: TetraEntity::Umac,
-        msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+    let m = SapMsg::new(
+        Sap::TmvSap,
+        TetraEntity::Lmac,
+        TetraEntity::Umac,
+        SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
             pdu: BitBuffer::from_bitstr(
                 "0010001000110001011010110000101010001010000100000000110000010000100000000000000000000000000000000000000000000000000000000000",
             ),
@@ -30,7 +30,7 @@ fn test_umac_ms() {
             crc_pass: true,
             scrambling_code: 0,
         }),
-    };
+    );
 
     // Submit and process message
     test.submit_message(m);
@@ -55,11 +55,11 @@ fn test_umac_frag() {
 
     // NDB 56/18/1/000 type1: 0000000111111001011010110000101001100011000000110100111101011010111110000100110000110000100100011000000000001100010101000000
     // NDB 57/01/1/000 type1: 0111000100110000000000010011001000110000001101000010110000110001010000000000110000010000100000000000000000000000000000000000
-    let m = SapMsg {
-        sap: Sap::TmvSap,
-        src: TetraEntity::Lmac,
-        dest: TetraEntity::Umac,
-        msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+    let m = SapMsg::new(
+        Sap::TmvSap,
+        TetraEntity::Lmac,
+        TetraEntity::Umac,
+        SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
             pdu: BitBuffer::from_bitstr(
                 "0000000111111001011010110000101001100011000000110100111101011010111110000100110000110000100100011000000000001100010101000000",
             ),
@@ -68,15 +68,15 @@ fn test_umac_frag() {
             crc_pass: true,
             scrambling_code: 0,
         }),
-    };
+    );
     test.submit_message(m);
     test.deliver_all_messages();
 
-    let m = SapMsg {
-        sap: Sap::TmvSap,
-        src: TetraEntity::Lmac,
-        dest: TetraEntity::Umac,
-        msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+    let m = SapMsg::new(
+        Sap::TmvSap,
+        TetraEntity::Lmac,
+        TetraEntity::Umac,
+        SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
             pdu: BitBuffer::from_bitstr(
                 "0111000100110000000000010011001000110000001101000010110000110001010000000000110000010000100000000000000000000000000000000000",
             ),
@@ -85,7 +85,7 @@ fn test_umac_frag() {
             crc_pass: true,
             scrambling_code: 0,
         }),
-    };
+    );
 
     test.submit_message(m);
     test.deliver_all_messages();
@@ -109,11 +109,11 @@ fn test_sysinfo() {
     test.populate_entities(components, sinks);
 
     // Sysinfo test
-    let m = SapMsg {
-        sap: Sap::TmvSap,
-        src: TetraEntity::Lmac,
-        dest: TetraEntity::Umac,
-        msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+    let m = SapMsg::new(
+        Sap::TmvSap,
+        TetraEntity::Lmac,
+        TetraEntity::Umac,
+        SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
             // mac_block: BitBuffer::from_bitstr("1000001100101010010000000000110001101001011100000000001110001111100000100000000000010111100001100000111111000000110101100111"),
             pdu: BitBuffer::from_bitstr(
                 "1000010000111111010001000000100001101001111100000000000000011101000011100000000000000000000000101111111111100101110101110111",
@@ -123,7 +123,7 @@ fn test_sysinfo() {
             crc_pass: true,
             scrambling_code: 0,
         }),
-    };
+    );
     test.submit_message(m);
     test.deliver_all_messages();
     let msgs = test.dump_sinks();
@@ -145,11 +145,11 @@ fn test_sync() {
 
     // SB1 09/11/4/000 type1: 000100000111010110010010000000001101001000000100010101110011
     // TMB-SAP SYNC CC 000001(0x01) TN 11(4) FN 01011(11) MN 001001( 9) MCC 0110100100(420) MNC 00001000101011(555)
-    let m = SapMsg {
-        sap: Sap::TmvSap,
-        src: TetraEntity::Lmac,
-        dest: TetraEntity::Umac,
-        msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+    let m = SapMsg::new(
+        Sap::TmvSap,
+        TetraEntity::Lmac,
+        TetraEntity::Umac,
+        SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
             pdu: BitBuffer::from_bitstr("000100000111010110010010000000001101001000000100010101110011"),
             // pdu: BitBuffer::from_bitstr("000100000111100100111110000000000110011000000000000101111001"),
             block_num: PhyBlockNum::Block1,
@@ -157,7 +157,7 @@ fn test_sync() {
             crc_pass: true,
             scrambling_code: 0,
         }),
-    };
+    );
     test.submit_message(m);
     test.deliver_all_messages();
     let msgs = test.dump_sinks();
@@ -176,11 +176,11 @@ fn test_resource() {
     let sinks = vec![];
     test.populate_entities(components, sinks);
 
-    let m = SapMsg {
-        sap: Sap::TmvSap,
-        src: TetraEntity::Lmac,
-        dest: TetraEntity::Umac,
-        msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+    let m = SapMsg::new(
+        Sap::TmvSap,
+        TetraEntity::Lmac,
+        TetraEntity::Umac,
+        SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
             pdu: BitBuffer::from_bitstr(
                 "0010000010001110000000000000000001100101110110001000100110001001010001101100100100011110001110010011000000000001001100111110000000001000000000000001000010000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
             ),
@@ -189,7 +189,7 @@ fn test_resource() {
             crc_pass: true,
             scrambling_code: 0,
         }),
-    };
+    );
     test.submit_message(m);
     test.deliver_all_messages();
     let msgs = test.dump_sinks();
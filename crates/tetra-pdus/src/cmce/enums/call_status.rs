@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// 14.8.13 Call status
 /// Bits: 3
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -11,7 +13,7 @@ pub enum CallStatus {
 }
 
 impl std::convert::TryFrom<u64> for CallStatus {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(CallStatus::Callproceeding),
@@ -19,7 +21,10 @@ impl std::convert::TryFrom<u64> for CallStatus {
             2 => Ok(CallStatus::Requestedsubscriberpaged),
             3 => Ok(CallStatus::Callcontinue),
             4 => Ok(CallStatus::Hangtimeexpired),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "call_status",
+                value: x,
+            }),
         }
     }
 }
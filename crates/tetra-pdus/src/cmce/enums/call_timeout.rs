@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 14.8.16 Call time-out
 /// Bits: 4
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -22,7 +24,7 @@ pub enum CallTimeout {
 }
 
 impl std::convert::TryFrom<u64> for CallTimeout {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(CallTimeout::Infinite),
@@ -41,7 +43,10 @@ impl std::convert::TryFrom<u64> for CallTimeout {
             13 => Ok(CallTimeout::T20m),
             14 => Ok(CallTimeout::T30m),
             15 => Ok(CallTimeout::Reserved),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "call_timeout",
+                value: x,
+            }),
         }
     }
 }
@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 14.8.17 Call time-out, set-up phase
 /// Bits: 3
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -14,7 +16,7 @@ pub enum CallTimeoutSetupPhase {
 }
 
 impl std::convert::TryFrom<u64> for CallTimeoutSetupPhase {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(CallTimeoutSetupPhase::Predefined),
@@ -25,7 +27,10 @@ impl std::convert::TryFrom<u64> for CallTimeoutSetupPhase {
             5 => Ok(CallTimeoutSetupPhase::T20s),
             6 => Ok(CallTimeoutSetupPhase::T30s),
             7 => Ok(CallTimeoutSetupPhase::T60s),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "call_timeout_setup_phase",
+                value: x,
+            }),
         }
     }
 }
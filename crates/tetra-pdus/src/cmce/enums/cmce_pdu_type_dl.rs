@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 14.8.28 PDU type
 /// Bits: 5
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -24,7 +26,7 @@ pub enum CmcePduTypeDl {
 }
 
 impl std::convert::TryFrom<u64> for CmcePduTypeDl {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(CmcePduTypeDl::DAlert),
@@ -45,7 +47,10 @@ impl std::convert::TryFrom<u64> for CmcePduTypeDl {
             15 => Ok(CmcePduTypeDl::DSdsData),
             16 => Ok(CmcePduTypeDl::DFacility),
             31 => Ok(CmcePduTypeDl::CmceFunctionNotSupported),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "cmce_pdu_type_dl",
+                value: x,
+            }),
         }
     }
 }
@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 14.8.28 PDU type
 /// Bits: 5
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -19,7 +21,7 @@ pub enum CmcePduTypeUl {
 }
 
 impl std::convert::TryFrom<u64> for CmcePduTypeUl {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(raw: u64) -> Result<Self, Self::Error> {
         let x = raw as u8;
         match x {
@@ -36,7 +38,10 @@ impl std::convert::TryFrom<u64> for CmcePduTypeUl {
             15 => Ok(CmcePduTypeUl::USdsData),
             16 => Ok(CmcePduTypeUl::UFacility),
             31 => Ok(CmcePduTypeUl::CmceFunctionNotSupported),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "cmce_pdu_type_ul",
+                value: raw,
+            }),
         }
     }
 }
@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// 14.8.18 Disconnect cause
 /// Bits: 3
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -30,7 +32,7 @@ pub enum DisconnectCause {
 }
 
 impl std::convert::TryFrom<u64> for DisconnectCause {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(DisconnectCause::CauseNotDefinedOrUnknown),
@@ -57,7 +59,10 @@ impl std::convert::TryFrom<u64> for DisconnectCause {
             21 => Ok(DisconnectCause::ConcurrentSetUpNotSupported),
             22 => Ok(DisconnectCause::CalledPartyIsUnderTheSameDmGateOfTheCallingParty),
             23 => Ok(DisconnectCause::NonCallOwnerRequestedDisconnection),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "disconnect_cause",
+                value: x,
+            }),
         }
     }
 }
@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 14.8.5 / 14.8.9 — Called/Calling Party Type Identifier (CPTI).
 /// Indicates the type of address which follows in the PDU (Table 14.39).
 /// Bits: 2
@@ -15,14 +17,17 @@ pub enum PartyTypeIdentifier {
 }
 
 impl std::convert::TryFrom<u64> for PartyTypeIdentifier {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(PartyTypeIdentifier::Sna),
             1 => Ok(PartyTypeIdentifier::Ssi),
             2 => Ok(PartyTypeIdentifier::Tsi),
             3 => Ok(PartyTypeIdentifier::Reserved),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "party_type_identifier",
+                value: x,
+            }),
         }
     }
 }
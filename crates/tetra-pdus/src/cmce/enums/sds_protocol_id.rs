@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 29.4.3.9 SDS Protocol identifier. Values undefined here may be user definition or reserved
 /// Bits: 8
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -30,7 +32,7 @@ pub enum SdsProtocolId {
 }
 
 impl std::convert::TryFrom<u64> for SdsProtocolId {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             1 => Ok(SdsProtocolId::Otak),
@@ -57,7 +59,10 @@ impl std::convert::TryFrom<u64> for SdsProtocolId {
             138 => Ok(SdsProtocolId::MessageWithUserDataHeader),
             140 => Ok(SdsProtocolId::ConcatenatedSdsMessageSdsTl),
             141 => Ok(SdsProtocolId::AgnssServiceSdsTl),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "sds_protocol_id",
+                value: x,
+            }),
         }
     }
 }
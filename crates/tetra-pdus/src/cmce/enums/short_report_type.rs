@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 29.4.3.11 Short report type
 /// The Short report type information element shall indicate the reason for report as defined in table 29.23.
 /// Bits: 2
@@ -11,14 +13,17 @@ pub enum ShortReportType {
 }
 
 impl std::convert::TryFrom<u64> for ShortReportType {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(ShortReportType::ProtOrEncodingNotSupported),
             1 => Ok(ShortReportType::DestMemFull),
             2 => Ok(ShortReportType::MessageReceived),
             3 => Ok(ShortReportType::MessageConsumed),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "short_report_type",
+                value: x,
+            }),
         }
     }
 }
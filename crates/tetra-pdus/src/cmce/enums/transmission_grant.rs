@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 14.8.42 Transmission grant
 /// Bits: 2
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -10,14 +12,17 @@ pub enum TransmissionGrant {
 }
 
 impl std::convert::TryFrom<u64> for TransmissionGrant {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(TransmissionGrant::Granted),
             1 => Ok(TransmissionGrant::NotGranted),
             2 => Ok(TransmissionGrant::RequestQueued),
             3 => Ok(TransmissionGrant::GrantedToOtherUser),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "transmission_grant",
+                value: x,
+            }),
         }
     }
 }
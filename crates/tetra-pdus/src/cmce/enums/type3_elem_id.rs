@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 14.8.48 Type 3 element identifier
 ///
 /// Bits: 4
@@ -14,7 +16,7 @@ pub enum CmceType3ElemId {
 }
 
 impl std::convert::TryFrom<u64> for CmceType3ElemId {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             1 => Ok(CmceType3ElemId::Dtmf),
@@ -24,7 +26,10 @@ impl std::convert::TryFrom<u64> for CmceType3ElemId {
             5 => Ok(CmceType3ElemId::TempAddr),
             6 => Ok(CmceType3ElemId::DmMsAddr),
             15 => Ok(CmceType3ElemId::Proprietary),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "type3_elem_id",
+                value: x,
+            }),
         }
     }
 }
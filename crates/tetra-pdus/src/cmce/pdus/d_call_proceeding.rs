@@ -62,7 +62,7 @@ impl DCallProceeding {
         let val = typed::parse_type2_generic(obit, buffer, 3, "call_status")?;
         let call_status = match val {
             None => None,
-            Some(val) => Some(CallStatus::try_from(val).map_err(|_| PduParseErr::InvalidValue {
+            Some(val) => Some(CallStatus::try_from(val).map_err(|_| PduParseErr::UnsupportedValue {
                 field: "call_status",
                 value: val,
             })?),
@@ -36,7 +36,7 @@ impl DRelease {
 
         // Type1
         let val = buffer.read_field(5, "disconnect_cause")?;
-        let disconnect_cause = DisconnectCause::try_from(val).map_err(|_| PduParseErr::InvalidValue {
+        let disconnect_cause = DisconnectCause::try_from(val).map_err(|_| PduParseErr::UnsupportedValue {
             field: "disconnect_cause",
             value: val,
         })?;
@@ -36,7 +36,7 @@ impl DSdsData {
 
         // Type1
         let cpti_raw = buffer.read_field(2, "calling_party_type_identifier")?;
-        let calling_party_type_identifier = PartyTypeIdentifier::try_from(cpti_raw).map_err(|_| PduParseErr::InvalidValue {
+        let calling_party_type_identifier = PartyTypeIdentifier::try_from(cpti_raw).map_err(|_| PduParseErr::UnsupportedValue {
             field: "calling_party_type_identifier",
             value: cpti_raw,
         })?;
@@ -64,10 +64,13 @@ impl DSdsData {
                 let len_bits = buffer.read_field(11, "length_indicator")? as u16;
                 let num_bytes = (len_bits + 7) / 8;
                 let mut data = vec![0u8; num_bytes as usize];
+                let bit_offset = buffer.get_pos();
                 buffer
                     .read_bits_into_slice(len_bits as usize, &mut data)
                     .ok_or(PduParseErr::BufferEnded {
                         field: Some("user_defined_data_4"),
+                        bit_offset,
+                        bit_length: len_bits as usize,
                     })?;
                 SdsUserData::Type4(len_bits, data)
             }
@@ -124,14 +127,7 @@ impl DSdsData {
             SdsUserData::Type3(value) => buffer.write_bits(*value, 64),
             SdsUserData::Type4(len_bits, data) => {
                 buffer.write_bits(*len_bits as u64, 11);
-                let full_bytes = (*len_bits as usize) / 8;
-                let remaining_bits = len_bits % 8;
-                for i in 0..full_bytes {
-                    buffer.write_bits(data[i] as u64, 8);
-                }
-                if remaining_bits > 0 {
-                    buffer.write_bits((data[full_bytes] >> (8 - remaining_bits)) as u64, remaining_bits as usize);
-                }
+                buffer.copy_from_slice(data, *len_bits as usize);
             }
         }
 
@@ -36,7 +36,7 @@ impl DStatus {
 
         // Type1
         let cpti_raw = buffer.read_field(2, "calling_party_type_identifier")?;
-        let calling_party_type_identifier = PartyTypeIdentifier::try_from(cpti_raw).map_err(|_| PduParseErr::InvalidValue {
+        let calling_party_type_identifier = PartyTypeIdentifier::try_from(cpti_raw).map_err(|_| PduParseErr::UnsupportedValue {
             field: "calling_party_type_identifier",
             value: cpti_raw,
         })?;
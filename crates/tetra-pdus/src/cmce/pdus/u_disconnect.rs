@@ -33,7 +33,7 @@ impl UDisconnect {
         let call_identifier = buffer.read_field(14, "call_identifier")? as u16;
         // Type1
         let val = buffer.read_field(5, "disconnect_cause")?;
-        let disconnect_cause = DisconnectCause::try_from(val).map_err(|_| PduParseErr::InvalidValue {
+        let disconnect_cause = DisconnectCause::try_from(val).map_err(|_| PduParseErr::UnsupportedValue {
             field: "disconnect_cause",
             value: val,
         })?;
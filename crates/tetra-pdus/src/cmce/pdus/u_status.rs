@@ -43,7 +43,7 @@ impl UStatus {
         let area_selection = buffer.read_field(4, "area_selection")? as u8;
         // Type1
         let cpti_raw = buffer.read_field(2, "called_party_type_identifier")?;
-        let called_party_type_identifier = PartyTypeIdentifier::try_from(cpti_raw).map_err(|_| PduParseErr::InvalidValue {
+        let called_party_type_identifier = PartyTypeIdentifier::try_from(cpti_raw).map_err(|_| PduParseErr::UnsupportedValue {
             field: "called_party_type_identifier",
             value: cpti_raw,
         })?;
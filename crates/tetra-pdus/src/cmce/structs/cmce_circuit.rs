@@ -74,3 +74,148 @@ pub struct CmceCircuit {
 //         }
 //     }
 // }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitBuildError {
+    /// A field without a default was not set
+    MissingField(&'static str),
+    /// Circuits only exist on timeslots 1-4
+    InvalidTimeslot(u8),
+    /// Usage numbers 0-3 are reserved, and the field is 6 bits
+    InvalidUsage(u8),
+}
+
+/// Builds a CmceCircuit. Direction, communication type, circuit mode and usage have to be set, the remaining
+/// fields default to a simplex circuit without speech service or end-to-end encryption.
+#[derive(Debug, Clone)]
+pub struct CmceCircuitBuilder {
+    call_id: CallId,
+    ts: u8,
+    ts_created: TdmaTime,
+    direction: Option<Direction>,
+    comm_type: Option<CommunicationType>,
+    circuit_mode: Option<CircuitModeType>,
+    usage: Option<u8>,
+    simplex_duplex: bool,
+    speech_service: Option<u8>,
+    etee_encrypted: bool,
+}
+
+impl CmceCircuitBuilder {
+    pub fn new(call_id: CallId, ts: u8) -> Self {
+        Self {
+            call_id,
+            ts,
+            ts_created: TdmaTime::default(),
+            direction: None,
+            comm_type: None,
+            circuit_mode: None,
+            usage: None,
+            simplex_duplex: false,
+            speech_service: None,
+            etee_encrypted: false,
+        }
+    }
+
+    pub fn ts_created(mut self, ts_created: TdmaTime) -> Self {
+        self.ts_created = ts_created;
+        self
+    }
+
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    pub fn comm_type(mut self, comm_type: CommunicationType) -> Self {
+        self.comm_type = Some(comm_type);
+        self
+    }
+
+    pub fn circuit_mode(mut self, circuit_mode: CircuitModeType) -> Self {
+        self.circuit_mode = Some(circuit_mode);
+        self
+    }
+
+    pub fn usage(mut self, usage: u8) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
+    pub fn simplex_duplex(mut self, simplex_duplex: bool) -> Self {
+        self.simplex_duplex = simplex_duplex;
+        self
+    }
+
+    pub fn speech_service(mut self, speech_service: u8) -> Self {
+        self.speech_service = Some(speech_service);
+        self
+    }
+
+    pub fn etee_encrypted(mut self, etee_encrypted: bool) -> Self {
+        self.etee_encrypted = etee_encrypted;
+        self
+    }
+
+    pub fn build(self) -> Result<CmceCircuit, CircuitBuildError> {
+        if !(1..=4).contains(&self.ts) {
+            return Err(CircuitBuildError::InvalidTimeslot(self.ts));
+        }
+        let usage = self.usage.ok_or(CircuitBuildError::MissingField("usage"))?;
+        if !(4..=63).contains(&usage) {
+            return Err(CircuitBuildError::InvalidUsage(usage));
+        }
+        Ok(CmceCircuit {
+            ts_created: self.ts_created,
+            direction: self.direction.ok_or(CircuitBuildError::MissingField("direction"))?,
+            ts: self.ts,
+            call_id: self.call_id,
+            usage,
+            circuit_mode: self.circuit_mode.ok_or(CircuitBuildError::MissingField("circuit_mode"))?,
+            comm_type: self.comm_type.ok_or(CircuitBuildError::MissingField("comm_type"))?,
+            simplex_duplex: self.simplex_duplex,
+            speech_service: self.speech_service,
+            etee_encrypted: self.etee_encrypted,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn speech_circuit(call_id: CallId, ts: u8) -> CmceCircuitBuilder {
+        CmceCircuitBuilder::new(call_id, ts)
+            .direction(Direction::Both)
+            .comm_type(CommunicationType::P2Mp)
+            .circuit_mode(CircuitModeType::TchS)
+            .usage(4)
+    }
+
+    #[test]
+    fn test_build_valid_circuit() {
+        let circuit = speech_circuit(5, 2).speech_service(0).build().unwrap();
+        assert_eq!(circuit.ts, 2);
+        assert_eq!(circuit.call_id, 5);
+        assert_eq!(circuit.usage, 4);
+        assert_eq!(circuit.direction, Direction::Both);
+        assert_eq!(circuit.comm_type, CommunicationType::P2Mp);
+        assert_eq!(circuit.speech_service, Some(0));
+        assert!(!circuit.simplex_duplex);
+        assert!(!circuit.etee_encrypted);
+    }
+
+    #[test]
+    fn test_build_rejects_invalid_circuit() {
+        assert_eq!(speech_circuit(5, 0).build().unwrap_err(), CircuitBuildError::InvalidTimeslot(0));
+        assert_eq!(
+            speech_circuit(5, 2).usage(3).build().unwrap_err(),
+            CircuitBuildError::InvalidUsage(3)
+        );
+        let no_mode = CmceCircuitBuilder::new(5, 2)
+            .direction(Direction::Dl)
+            .comm_type(CommunicationType::P2Mp)
+            .usage(4);
+        assert_eq!(no_mode.build().unwrap_err(), CircuitBuildError::MissingField("circuit_mode"));
+    }
+}
@@ -0,0 +1,60 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
+/// Clause 21.3.3.4 Set-up report
+/// Bits: 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum AlSetupReport {
+    /// Initial AL-SETUP from the initiating entity, defining the advanced link parameters
+    ServiceDefinition = 0,
+    /// AL-SETUP proposing changed parameters for an existing advanced link
+    ServiceChange = 1,
+    /// AL-SETUP reply from the responding entity, accepting the (possibly modified) parameters
+    ServiceAccepted = 2,
+    Reserved = 3,
+}
+
+impl std::convert::TryFrom<u64> for AlSetupReport {
+    type Error = PduParseErr;
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(AlSetupReport::ServiceDefinition),
+            1 => Ok(AlSetupReport::ServiceChange),
+            2 => Ok(AlSetupReport::ServiceAccepted),
+            3 => Ok(AlSetupReport::Reserved),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "al_setup_report",
+                value: x,
+            }),
+        }
+    }
+}
+
+impl AlSetupReport {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            AlSetupReport::ServiceDefinition => 0,
+            AlSetupReport::ServiceChange => 1,
+            AlSetupReport::ServiceAccepted => 2,
+            AlSetupReport::Reserved => 3,
+        }
+    }
+}
+
+impl From<AlSetupReport> for u64 {
+    fn from(e: AlSetupReport) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for AlSetupReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AlSetupReport::ServiceDefinition => write!(f, "ServiceDefinition"),
+            AlSetupReport::ServiceChange => write!(f, "ServiceChange"),
+            AlSetupReport::ServiceAccepted => write!(f, "ServiceAccepted"),
+            AlSetupReport::Reserved => write!(f, "Reserved"),
+        }
+    }
+}
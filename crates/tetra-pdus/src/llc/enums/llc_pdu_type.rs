@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 21.2.1 LLC PDU types
 /// Bits: 4
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -22,7 +24,7 @@ pub enum LlcPduType {
 }
 
 impl std::convert::TryFrom<u64> for LlcPduType {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(LlcPduType::BlAdata),
@@ -41,7 +43,10 @@ impl std::convert::TryFrom<u64> for LlcPduType {
             13 => Ok(LlcPduType::SuppLlcPdu),
             14 => Ok(LlcPduType::L2SigPdu),
             15 => Ok(LlcPduType::AlDisc),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "llc_pdu_type",
+                value: x,
+            }),
         }
     }
 }
@@ -1 +1,2 @@
+pub mod al_setup_report;
 pub mod llc_pdu_type;
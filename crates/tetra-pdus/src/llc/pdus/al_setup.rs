@@ -0,0 +1,135 @@
+use core::fmt;
+
+use tetra_core::BitBuffer;
+use tetra_core::pdu_parse_error::*;
+use tetra_core::{expect_value, let_field};
+
+use crate::llc::enums::al_setup_report::AlSetupReport;
+use crate::llc::enums::llc_pdu_type::LlcPduType;
+
+/// Clause 21.2.3.1 AL-SETUP
+/// Used by both peers during advanced link establishment. The initiator sends it with set-up report
+/// ServiceDefinition, the responder answers with an AL-SETUP carrying ServiceAccepted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlSetup {
+    // 1, 0 = acknowledged and unacknowledged service, 1 = unacknowledged service only
+    pub advanced_link_service: u8,
+    // 1
+    pub advanced_link_number: u8,
+    // 3, N.271 maximum length of TL-SDU
+    pub max_tlsdu_len: u8,
+    // 2, N.272 window size for acknowledged service
+    pub window_size_acked: u8,
+    // 3, N.273 maximum number of TL-SDU retransmissions
+    pub max_tlsdu_retransmissions: u8,
+    // 4, N.274 maximum number of segment retransmissions
+    pub max_segment_retransmissions: u8,
+    // 2, N.281 window size for unacknowledged service
+    pub window_size_unacked: u8,
+    // 3, N.282 number of repetitions for unacknowledged service
+    pub num_repetitions_unacked: u8,
+    // 2
+    pub setup_report: AlSetupReport,
+    // 2, N.264 number of timeslots used per TDMA frame, minus one
+    pub num_timeslots: u8,
+}
+
+impl AlSetup {
+    pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let_field!(buf, llc_pdu_type, 4);
+        expect_value!(llc_pdu_type, LlcPduType::AlSetup.into_raw())?;
+
+        let_field!(buf, advanced_link_service, 1);
+        let_field!(buf, advanced_link_number, 1);
+        let_field!(buf, max_tlsdu_len, 3);
+        let_field!(buf, window_size_acked, 2);
+        let_field!(buf, max_tlsdu_retransmissions, 3);
+        let_field!(buf, max_segment_retransmissions, 4);
+        let_field!(buf, window_size_unacked, 2);
+        let_field!(buf, num_repetitions_unacked, 3);
+        let_field!(buf, setup_report, 2);
+        let_field!(buf, num_timeslots, 2);
+
+        Ok(AlSetup {
+            advanced_link_service: advanced_link_service as u8,
+            advanced_link_number: advanced_link_number as u8,
+            max_tlsdu_len: max_tlsdu_len as u8,
+            window_size_acked: window_size_acked as u8,
+            max_tlsdu_retransmissions: max_tlsdu_retransmissions as u8,
+            max_segment_retransmissions: max_segment_retransmissions as u8,
+            window_size_unacked: window_size_unacked as u8,
+            num_repetitions_unacked: num_repetitions_unacked as u8,
+            setup_report: AlSetupReport::try_from(setup_report).unwrap(), // Never fails, 2-bit field
+            num_timeslots: num_timeslots as u8,
+        })
+    }
+
+    pub fn to_bitbuf(&self, buf: &mut BitBuffer) {
+        buf.write_bits(LlcPduType::AlSetup.into_raw(), 4);
+        buf.write_bits(self.advanced_link_service as u64, 1);
+        buf.write_bits(self.advanced_link_number as u64, 1);
+        buf.write_bits(self.max_tlsdu_len as u64, 3);
+        buf.write_bits(self.window_size_acked as u64, 2);
+        buf.write_bits(self.max_tlsdu_retransmissions as u64, 3);
+        buf.write_bits(self.max_segment_retransmissions as u64, 4);
+        buf.write_bits(self.window_size_unacked as u64, 2);
+        buf.write_bits(self.num_repetitions_unacked as u64, 3);
+        buf.write_bits(self.setup_report.into_raw(), 2);
+        buf.write_bits(self.num_timeslots as u64, 2);
+    }
+}
+
+impl fmt::Display for AlSetup {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "al_setup {{ service: {}, link: {}, max_tlsdu_len: {}, window_acked: {}, tlsdu_retx: {}, segment_retx: {}, window_unacked: {}, repetitions: {}, report: {}, timeslots: {} }}",
+            self.advanced_link_service,
+            self.advanced_link_number,
+            self.max_tlsdu_len,
+            self.window_size_acked,
+            self.max_tlsdu_retransmissions,
+            self.max_segment_retransmissions,
+            self.window_size_unacked,
+            self.num_repetitions_unacked,
+            self.setup_report,
+            self.num_timeslots,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_al_setup_roundtrip() {
+        let pdu = AlSetup {
+            advanced_link_service: 0,
+            advanced_link_number: 1,
+            max_tlsdu_len: 7,
+            window_size_acked: 3,
+            max_tlsdu_retransmissions: 3,
+            max_segment_retransmissions: 3,
+            window_size_unacked: 3,
+            num_repetitions_unacked: 3,
+            setup_report: AlSetupReport::ServiceAccepted,
+            num_timeslots: 0,
+        };
+        let mut buf = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf);
+        assert_eq!(buf.to_bitstr(), "100001111110110011110111000");
+
+        buf.seek(0);
+        let parsed = AlSetup::from_bitbuf(&mut buf).expect("Failed parsing");
+        assert_eq!(buf.get_len_remaining(), 0);
+        assert_eq!(parsed, pdu);
+    }
+
+    #[test]
+    fn test_al_setup_rejects_other_pdu_type() {
+        // BL-ACK header followed by padding
+        let mut buf = BitBuffer::from_bitstr("00110000000000000000000000");
+        assert!(AlSetup::from_bitbuf(&mut buf).is_err());
+    }
+}
@@ -1,8 +1,8 @@
+pub mod al_setup;
 pub mod bl_ack;
 pub mod bl_adata;
 pub mod bl_data;
 pub mod bl_udata;
-// mod al_setup;
 // mod al_data; // and possibly AL-DATA-AR/AL-FINAL/AL-FINAL-AR
 // mod al_udata; // and AL-UFINAL
 // mod al_ack // and AL-RNR
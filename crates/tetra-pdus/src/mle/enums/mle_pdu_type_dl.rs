@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 18.5.20 MLE PDU types
 /// Bits: 3
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -14,7 +16,7 @@ pub enum MlePduTypeDl {
 }
 
 impl std::convert::TryFrom<u64> for MlePduTypeDl {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(MlePduTypeDl::DNewCell),
@@ -25,7 +27,10 @@ impl std::convert::TryFrom<u64> for MlePduTypeDl {
             5 => Ok(MlePduTypeDl::DRestoreFail),
             6 => Ok(MlePduTypeDl::DChannelResponse),
             7 => Ok(MlePduTypeDl::ExtPdu),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "mle_pdu_type_dl",
+                value: x,
+            }),
         }
     }
 }
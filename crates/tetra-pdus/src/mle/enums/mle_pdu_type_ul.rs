@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 18.5.20 MLE PDU types
 /// Bits: 3
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -13,7 +15,7 @@ pub enum MlePduTypeUl {
 }
 
 impl std::convert::TryFrom<u64> for MlePduTypeUl {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(MlePduTypeUl::UPrepare),
@@ -23,7 +25,10 @@ impl std::convert::TryFrom<u64> for MlePduTypeUl {
             4 => Ok(MlePduTypeUl::URestore),
             6 => Ok(MlePduTypeUl::UChannelRequest),
             7 => Ok(MlePduTypeUl::ExtPdu),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "mle_pdu_type_ul",
+                value: x,
+            }),
         }
     }
 }
@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 18.5.21 Protocol discriminator
 /// Bits: 3
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -14,7 +16,7 @@ pub enum MleProtocolDiscriminator {
 }
 
 impl std::convert::TryFrom<u64> for MleProtocolDiscriminator {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             // 0 => Ok(MleProtocolDiscriminator::RESERVED),
@@ -25,7 +27,10 @@ impl std::convert::TryFrom<u64> for MleProtocolDiscriminator {
             5 => Ok(MleProtocolDiscriminator::Mle),
             6 => Ok(MleProtocolDiscriminator::TetraManagementEntity),
             // 7 => Ok(MleProtocolDiscriminator::ReservedForTesting),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "mle_protocol_discriminator",
+                value: x,
+            }),
         }
     }
 }
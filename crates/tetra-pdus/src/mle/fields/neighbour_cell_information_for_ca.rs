@@ -0,0 +1,145 @@
+use core::fmt;
+
+use tetra_core::typed_pdu_fields::{delimiters, typed};
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::mle::fields::bs_service_details::BsServiceDetails;
+
+/// Clause 18.5.17 Neighbour cell information for CA
+/// Describes one CA neighbour cell in D-NWRK-BROADCAST. Carried without a preceding P-bit.
+#[derive(Debug, Clone)]
+pub struct NeighbourCellInformationForCa {
+    // 5
+    pub cell_identifier_ca: u8,
+    // 2
+    pub cell_reselection_types_supported: u8,
+    // 1
+    pub neighbour_cell_synchronized: bool,
+    // 2
+    pub cell_load_ca: u8,
+    // 12
+    pub main_carrier_number: u16,
+    /// 10 opt, frequency band (4), offset (2), duplex spacing (3) and reverse operation (1)
+    pub main_carrier_number_extension: Option<u16>,
+    // 10 opt
+    pub mcc: Option<u16>,
+    // 14 opt
+    pub mnc: Option<u16>,
+    // 14 opt
+    pub location_area: Option<u16>,
+    // 3 opt
+    pub maximum_ms_transmit_power: Option<u8>,
+    // 4 opt
+    pub minimum_rx_access_level: Option<u8>,
+    // 16 opt
+    pub subscriber_class: Option<u16>,
+    // 12 opt
+    pub bs_service_details: Option<BsServiceDetails>,
+    // 5 opt
+    pub timeshare_cell_information_or_security_parameters: Option<u8>,
+    // 6 opt
+    pub tdma_frame_offset: Option<u8>,
+}
+
+impl NeighbourCellInformationForCa {
+    pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let cell_identifier_ca = buf.read_field(5, "cell_identifier_ca")? as u8;
+        let cell_reselection_types_supported = buf.read_field(2, "cell_reselection_types_supported")? as u8;
+        let neighbour_cell_synchronized = buf.read_field(1, "neighbour_cell_synchronized")? != 0;
+        let cell_load_ca = buf.read_field(2, "cell_load_ca")? as u8;
+        let main_carrier_number = buf.read_field(12, "main_carrier_number")? as u16;
+
+        let obit = delimiters::read_obit(buf)?;
+        let main_carrier_number_extension = typed::parse_type2_generic(obit, buf, 10, "main_carrier_number_extension")?.map(|v| v as u16);
+        let mcc = typed::parse_type2_generic(obit, buf, 10, "mcc")?.map(|v| v as u16);
+        let mnc = typed::parse_type2_generic(obit, buf, 14, "mnc")?.map(|v| v as u16);
+        let location_area = typed::parse_type2_generic(obit, buf, 14, "location_area")?.map(|v| v as u16);
+        let maximum_ms_transmit_power = typed::parse_type2_generic(obit, buf, 3, "maximum_ms_transmit_power")?.map(|v| v as u8);
+        let minimum_rx_access_level = typed::parse_type2_generic(obit, buf, 4, "minimum_rx_access_level")?.map(|v| v as u8);
+        let subscriber_class = typed::parse_type2_generic(obit, buf, 16, "subscriber_class")?.map(|v| v as u16);
+        let bs_service_details = typed::parse_type2_struct(obit, buf, BsServiceDetails::from_bitbuf)?;
+        let timeshare_cell_information_or_security_parameters =
+            typed::parse_type2_generic(obit, buf, 5, "timeshare_cell_information_or_security_parameters")?.map(|v| v as u8);
+        let tdma_frame_offset = typed::parse_type2_generic(obit, buf, 6, "tdma_frame_offset")?.map(|v| v as u8);
+
+        Ok(NeighbourCellInformationForCa {
+            cell_identifier_ca,
+            cell_reselection_types_supported,
+            neighbour_cell_synchronized,
+            cell_load_ca,
+            main_carrier_number,
+            main_carrier_number_extension,
+            mcc,
+            mnc,
+            location_area,
+            maximum_ms_transmit_power,
+            minimum_rx_access_level,
+            subscriber_class,
+            bs_service_details,
+            timeshare_cell_information_or_security_parameters,
+            tdma_frame_offset,
+        })
+    }
+
+    pub fn to_bitbuf(&self, buf: &mut BitBuffer) -> Result<(), PduParseErr> {
+        if self.cell_identifier_ca >= 1 << 5 {
+            return Err(PduParseErr::InvalidValue {
+                field: "cell_identifier_ca",
+                value: self.cell_identifier_ca as u64,
+            });
+        }
+        if self.main_carrier_number >= 1 << 12 {
+            return Err(PduParseErr::InvalidValue {
+                field: "main_carrier_number",
+                value: self.main_carrier_number as u64,
+            });
+        }
+        buf.write_bits(self.cell_identifier_ca as u64, 5);
+        buf.write_bits(self.cell_reselection_types_supported as u64, 2);
+        buf.write_bits(self.neighbour_cell_synchronized as u64, 1);
+        buf.write_bits(self.cell_load_ca as u64, 2);
+        buf.write_bits(self.main_carrier_number as u64, 12);
+
+        let obit = self.main_carrier_number_extension.is_some()
+            || self.mcc.is_some()
+            || self.mnc.is_some()
+            || self.location_area.is_some()
+            || self.maximum_ms_transmit_power.is_some()
+            || self.minimum_rx_access_level.is_some()
+            || self.subscriber_class.is_some()
+            || self.bs_service_details.is_some()
+            || self.timeshare_cell_information_or_security_parameters.is_some()
+            || self.tdma_frame_offset.is_some();
+        delimiters::write_obit(buf, obit as u8);
+        typed::write_type2_generic(obit, buf, self.main_carrier_number_extension.map(|v| v as u64), 10);
+        typed::write_type2_generic(obit, buf, self.mcc.map(|v| v as u64), 10);
+        typed::write_type2_generic(obit, buf, self.mnc.map(|v| v as u64), 14);
+        typed::write_type2_generic(obit, buf, self.location_area.map(|v| v as u64), 14);
+        typed::write_type2_generic(obit, buf, self.maximum_ms_transmit_power.map(|v| v as u64), 3);
+        typed::write_type2_generic(obit, buf, self.minimum_rx_access_level.map(|v| v as u64), 4);
+        typed::write_type2_generic(obit, buf, self.subscriber_class.map(|v| v as u64), 16);
+        typed::write_type2_struct(obit, buf, &self.bs_service_details, |details, buf| {
+            details.to_bitbuf(buf);
+            Ok(())
+        })?;
+        typed::write_type2_generic(
+            obit,
+            buf,
+            self.timeshare_cell_information_or_security_parameters.map(|v| v as u64),
+            5,
+        );
+        typed::write_type2_generic(obit, buf, self.tdma_frame_offset.map(|v| v as u64), 6);
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for NeighbourCellInformationForCa {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "NeighbourCellInformationForCa {{ cell_identifier_ca: {} main_carrier_number: {} mcc: {:?} mnc: {:?} location_area: {:?} }}",
+            self.cell_identifier_ca, self.main_carrier_number, self.mcc, self.mnc, self.location_area,
+        )
+    }
+}
@@ -25,7 +25,6 @@ pub struct DChannelResponse {
     pub reserved2: Option<u64>,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
 impl DChannelResponse {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
@@ -47,8 +46,8 @@ impl DChannelResponse {
         // Type2
         let reserved2 = typed::parse_type2_generic(obit, buffer, 8, "reserved2")?;
 
-        // Read trailing obit (if not previously encountered)
-        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
+        // Read trailing mbit (if not previously encountered)
+        obit = if obit { delimiters::read_mbit(buffer)? } else { obit };
         if obit {
             return Err(PduParseErr::InvalidTrailingMbitValue);
         }
@@ -105,3 +104,53 @@ impl fmt::Display for DChannelResponse {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_d_channel_response_accept() {
+        debug::setup_logging_verbose();
+        // pdu_type 110, response type 1 (accepted), reason 010, retry delay 0000, obit 0
+        let test_vec = "110101000000";
+        let mut buf_in = BitBuffer::from_bitstr(test_vec);
+        let pdu = DChannelResponse::from_bitbuf(&mut buf_in).expect("Failed parsing");
+
+        tracing::info!("Parsed: {}", pdu);
+        assert!(buf_in.get_len_remaining() == 0, "Buffer not fully consumed");
+        assert!(pdu.channel_response_type);
+        assert_eq!(pdu.reason_for_the_channel_request, 2);
+        assert_eq!(pdu.channel_request_retry_delay, 0);
+
+        let mut buf_out = BitBuffer::new_autoexpand(16);
+        pdu.to_bitbuf(&mut buf_out).unwrap();
+        assert_eq!(buf_out.to_bitstr(), test_vec);
+    }
+
+    #[test]
+    fn test_d_channel_response_reject() {
+        debug::setup_logging_verbose();
+        let pdu = DChannelResponse {
+            channel_response_type: false,
+            reason_for_the_channel_request: 1,
+            channel_request_retry_delay: 9,
+            reserved1: None,
+            reserved2: None,
+        };
+        let mut buf = BitBuffer::new_autoexpand(16);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        tracing::info!("Serialized: {}", buf.dump_bin());
+        // pdu_type 110, response type 0 (rejected), reason 001, retry delay 1001, obit 0
+        assert_eq!(buf.to_bitstr(), "110000110010");
+
+        buf.seek(0);
+        let parsed = DChannelResponse::from_bitbuf(&mut buf).expect("Failed parsing");
+        assert!(!parsed.channel_response_type);
+        assert_eq!(parsed.reason_for_the_channel_request, 1);
+        assert_eq!(parsed.channel_request_retry_delay, 9);
+        assert!(parsed.reserved1.is_none() && parsed.reserved2.is_none());
+    }
+}
@@ -15,12 +15,10 @@ use crate::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
 pub struct DNewCell {
     /// Type1, 2 bits, Channel command valid
     pub channel_command_valid: u8,
-    /// Conditional SDU
-    pub sdu: Option<u64>,
+    /// Conditional See note, MM PDU taking up the remainder of the PDU
+    pub sdu: Option<BitBuffer>,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
-#[allow(unused_variables)]
 impl DNewCell {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
@@ -29,25 +27,25 @@ impl DNewCell {
 
         // Type1
         let channel_command_valid = buffer.read_field(2, "channel_command_valid")? as u8;
-        // Exceptional case: obit required for SDU field.
-        // SDU takes rest of slot, but still ends with 0-bit (closing obit)
 
-        // obit designates presence of any further type2, type3 or type4 fields
+        // obit designates presence of the SDU
         let obit = delimiters::read_obit(buffer)?;
 
-        let sdu = if buffer.get_len_remaining() > 0 {
-            Some(buffer.read_field(buffer.get_len_remaining() - 1, "sdu")?)
+        // Conditional
+        // Exceptional case: SDU takes the rest of the PDU, but is still followed by the closing m-bit
+        let sdu = if obit && buffer.get_len_remaining() > 1 {
+            let sdu_len = buffer.get_len_remaining() - 1;
+            let mut sdu = BitBuffer::new(sdu_len);
+            sdu.copy_bits(buffer, sdu_len);
+            sdu.seek(0);
+            Some(sdu)
         } else {
             None
         };
-        unimplemented!(); // read closing obit
-
-        // obit designates presence of any further type2, type3 or type4 fields
-        let mut obit = delimiters::read_obit(buffer)?;
 
-        // Read trailing obit (if not previously encountered)
-        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
-        if obit {
+        // Read trailing mbit (if not previously encountered)
+        let mbit = if obit { delimiters::read_mbit(buffer)? } else { false };
+        if mbit {
             return Err(PduParseErr::InvalidTrailingMbitValue);
         }
 
@@ -59,16 +57,30 @@ impl DNewCell {
 
     /// Serialize this PDU into the given BitBuffer.
     pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        if self.channel_command_valid >= 1 << 2 {
+            return Err(PduParseErr::InvalidValue {
+                field: "channel_command_valid",
+                value: self.channel_command_valid as u64,
+            });
+        }
+
         // PDU Type
         buffer.write_bits(MlePduTypeDl::DNewCell.into_raw(), 3);
         // Type1
         buffer.write_bits(self.channel_command_valid as u64, 2);
-        // TODO FIXME: sdu handling
+
+        // Check if the SDU is present and place o-bit
+        let obit = self.sdu.is_some();
+        delimiters::write_obit(buffer, obit as u8);
+        let Some(sdu) = &self.sdu else {
+            return Ok(());
+        };
+
         // Conditional
-        if let Some(ref value) = self.sdu {
-            unimplemented!();
-            buffer.write_bits(*value, 999);
-        }
+        let mut sdu = BitBuffer::from_bitbuffer(sdu);
+        let sdu_len = sdu.get_len();
+        buffer.copy_bits(&mut sdu, sdu_len);
+
         // Write terminating m-bit
         delimiters::write_mbit(buffer, 0);
         Ok(())
@@ -79,8 +91,63 @@ impl fmt::Display for DNewCell {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "DNewCell {{ channel_command_valid: {:?} sdu: {:?} }}",
-            self.channel_command_valid, self.sdu,
+            "DNewCell {{ channel_command_valid: {} sdu: {:?} }}",
+            self.channel_command_valid,
+            self.sdu.as_ref().map(|sdu| sdu.dump_bin_unformatted()),
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_d_new_cell_without_sdu() {
+        debug::setup_logging_verbose();
+        // pdu_type 000, channel command valid 10, obit 0
+        let test_vec = "000100";
+        let mut buf_in = BitBuffer::from_bitstr(test_vec);
+        let pdu = DNewCell::from_bitbuf(&mut buf_in).expect("Failed parsing");
+
+        tracing::info!("Parsed: {}", pdu);
+        assert!(buf_in.get_len_remaining() == 0, "Buffer not fully consumed");
+        assert_eq!(pdu.channel_command_valid, 2);
+        assert!(pdu.sdu.is_none());
+
+        let mut buf_out = BitBuffer::new_autoexpand(8);
+        pdu.to_bitbuf(&mut buf_out).unwrap();
+        assert_eq!(buf_out.to_bitstr(), test_vec);
+    }
+
+    #[test]
+    fn test_d_new_cell_roundtrip_with_sdu() {
+        debug::setup_logging_verbose();
+        let pdu = DNewCell {
+            channel_command_valid: 1,
+            sdu: Some(BitBuffer::from_bitstr("0101100111")),
+        };
+        let mut buf = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        // pdu_type 000, channel command valid 01, obit 1, sdu, mbit 0
+        assert_eq!(buf.to_bitstr(), "00001101011001110");
+
+        buf.seek(0);
+        let parsed = DNewCell::from_bitbuf(&mut buf).expect("Failed parsing");
+        assert!(buf.get_len_remaining() == 0, "Buffer not fully consumed");
+        assert_eq!(parsed.channel_command_valid, 1);
+        assert_eq!(parsed.sdu.unwrap().to_bitstr(), "0101100111");
+    }
+
+    #[test]
+    fn test_d_new_cell_rejects_invalid_channel_command() {
+        let pdu = DNewCell {
+            channel_command_valid: 4,
+            sdu: None,
+        };
+        let mut buf = BitBuffer::new_autoexpand(8);
+        assert!(pdu.to_bitbuf(&mut buf).is_err());
+    }
+}
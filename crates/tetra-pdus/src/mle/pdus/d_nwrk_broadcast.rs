@@ -4,6 +4,7 @@ use tetra_core::typed_pdu_fields::*;
 use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
 
 use crate::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
+use crate::mle::fields::neighbour_cell_information_for_ca::NeighbourCellInformationForCa;
 
 /// Representation of the D-NWRK-BROADCAST PDU (Clause 18.4.1.4.1).
 /// Upon receipt from the SwMI, the message shall inform the MS-MLE about parameters for the CA serving cell and parameters for one or more CA neighbour cells.
@@ -13,7 +14,7 @@ use crate::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
 // note 1: This element shall not be used by a DA MS.
 // note 2: If present, the element shall indicate how many “Neighbour cell information for CA” elements follow. If not present, no neighbour cell information shall follow.
 // note 3: The element definition is contained in clause 18.5 which gives the type and length for each sub-element which is included in this element. The element shall be present as many times as indicated by the “number of CA neighbour cells” element. There shall be no P-bit preceding each “neighbour cell information for CA” element which is carried by this PDU.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct DNwrkBroadcast {
     /// Type1, 16 bits, See note 1,
     pub cell_re_select_parameters: u16,
@@ -23,12 +24,14 @@ pub struct DNwrkBroadcast {
     pub tetra_network_time: Option<u64>,
     /// Type2, 3 bits, See note 2,
     pub number_of_ca_neighbour_cells: Option<u64>,
-    /// Conditional See note 3, condition: number_of_ca_neighbour_cells > Some(0)
-    pub neighbour_cell_information_for_ca: Option<u64>,
+    /// Conditional See note 3, one element per CA neighbour cell
+    pub neighbour_cell_information_for_ca: Vec<NeighbourCellInformationForCa>,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
 impl DNwrkBroadcast {
+    /// Maximum number of CA neighbour cells, limited by the 3-bit count
+    pub const MAX_CA_NEIGHBOUR_CELLS: usize = 7;
+
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
         let pdu_type = buffer.read_field(3, "pdu_type")?;
@@ -48,12 +51,10 @@ impl DNwrkBroadcast {
         let number_of_ca_neighbour_cells = typed::parse_type2_generic(obit, buffer, 3, "number_of_ca_neighbour_cells")?;
 
         // Conditional
-        let neighbour_cell_information_for_ca = if obit && number_of_ca_neighbour_cells > Some(0) {
-            unimplemented!();
-            Some(buffer.read_field(999, "neighbour_cell_information_for_ca")?)
-        } else {
-            None
-        };
+        let mut neighbour_cell_information_for_ca = Vec::new();
+        for _ in 0..number_of_ca_neighbour_cells.unwrap_or(0) {
+            neighbour_cell_information_for_ca.push(NeighbourCellInformationForCa::from_bitbuf(buffer)?);
+        }
 
         // MLE PDUs do not use M-bits (Annex E.2.1) — no trailing delimiter to read
 
@@ -89,9 +90,14 @@ impl DNwrkBroadcast {
         typed::write_type2_generic(obit, buffer, self.number_of_ca_neighbour_cells, 3);
 
         // Conditional
-        if let Some(ref _value) = self.neighbour_cell_information_for_ca {
-            unimplemented!();
-            buffer.write_bits(*_value, 999);
+        if self.neighbour_cell_information_for_ca.len() as u64 != self.number_of_ca_neighbour_cells.unwrap_or(0) {
+            return Err(PduParseErr::InvalidValue {
+                field: "number_of_ca_neighbour_cells",
+                value: self.number_of_ca_neighbour_cells.unwrap_or(0),
+            });
+        }
+        for cell in &self.neighbour_cell_information_for_ca {
+            cell.to_bitbuf(buffer)?;
         }
         // MLE PDUs do not use M-bits (Annex E.2.1) — PDU ends after last Type 2 element
         Ok(())
@@ -111,3 +117,91 @@ impl fmt::Display for DNwrkBroadcast {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    fn neighbour(cell_identifier_ca: u8, main_carrier_number: u16) -> NeighbourCellInformationForCa {
+        NeighbourCellInformationForCa {
+            cell_identifier_ca,
+            cell_reselection_types_supported: 0,
+            neighbour_cell_synchronized: false,
+            cell_load_ca: 0,
+            main_carrier_number,
+            main_carrier_number_extension: None,
+            mcc: None,
+            mnc: None,
+            location_area: None,
+            maximum_ms_transmit_power: None,
+            minimum_rx_access_level: None,
+            subscriber_class: None,
+            bs_service_details: None,
+            timeshare_cell_information_or_security_parameters: None,
+            tdma_frame_offset: None,
+        }
+    }
+
+    #[test]
+    fn test_d_nwrk_broadcast_neighbour_cells() {
+        debug::setup_logging_verbose();
+        let mut second = neighbour(2, 1522);
+        second.mcc = Some(204);
+        second.mnc = Some(1337);
+        let pdu = DNwrkBroadcast {
+            cell_re_select_parameters: 0,
+            cell_load_ca: 1,
+            tetra_network_time: None,
+            number_of_ca_neighbour_cells: Some(2),
+            neighbour_cell_information_for_ca: vec![neighbour(1, 1521), second],
+        };
+
+        let mut buf = BitBuffer::new_autoexpand(128);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        // Header: pdu_type 010, 16 bits cell reselect, cell load 01, obit, no time, 2 cells
+        // Cell 1: id 00001, types 00, sync 0, load 00, carrier 1521, obit 0
+        // Cell 2: id 00010, types 00, sync 0, load 00, carrier 1522, obit 1, mcc and mnc present, rest absent
+        let expected = "010".to_string()
+            + "0000000000000000"
+            + "01"
+            + "1"
+            + "0"
+            + "1010"
+            + "00001000000101111100010"
+            + "00010000000101111100101"
+            + "0"
+            + "10011001100"
+            + "100010100111001"
+            + "0000000";
+        assert_eq!(buf.to_bitstr(), expected);
+
+        buf.seek(0);
+        let parsed = DNwrkBroadcast::from_bitbuf(&mut buf).expect("Failed parsing");
+        tracing::info!("Parsed: {}", parsed);
+        assert_eq!(buf.get_len_remaining(), 0);
+        assert_eq!(parsed.number_of_ca_neighbour_cells, Some(2));
+        let cells = &parsed.neighbour_cell_information_for_ca;
+        assert_eq!(cells.len(), 2);
+        assert_eq!(
+            (cells[0].cell_identifier_ca, cells[0].main_carrier_number, cells[0].mcc),
+            (1, 1521, None)
+        );
+        assert_eq!((cells[1].cell_identifier_ca, cells[1].main_carrier_number), (2, 1522));
+        assert_eq!((cells[1].mcc, cells[1].mnc), (Some(204), Some(1337)));
+    }
+
+    #[test]
+    fn test_d_nwrk_broadcast_count_mismatch() {
+        let pdu = DNwrkBroadcast {
+            cell_re_select_parameters: 0,
+            cell_load_ca: 0,
+            tetra_network_time: None,
+            number_of_ca_neighbour_cells: Some(2),
+            neighbour_cell_information_for_ca: vec![neighbour(1, 1521)],
+        };
+        let mut buf = BitBuffer::new_autoexpand(64);
+        assert!(pdu.to_bitbuf(&mut buf).is_err());
+    }
+}
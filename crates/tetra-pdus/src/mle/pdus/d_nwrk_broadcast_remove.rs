@@ -9,6 +9,9 @@ use crate::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
 /// Upon receipt from the SwMI, the message shall inform the MS-MLE about broadcast neighbour cell and channel information received on the present cell that is to be removed from the MS's memory.
 /// Response expected: -
 /// Response to: -
+///
+/// Only a single CA cell for removal is supported, identified by the cell identifier CA it was broadcast with.
+/// DA cell removal data is never sent, and is rejected when parsing.
 
 // note 1: If present, the element shall indicate how many "removal data for CA cell" elements follow. If not present, no "removal data for CA cell" elements shall follow.
 // note 2: The element definition is contained in clause 18.5 which gives the type and length for each sub-element which is included in this element. The element shall be present as many times as indicated by the "Number of CA cells for removal" element. There shall be no P-bit preceding each "removal data for CA cell" element which is carried by this PDU.
@@ -16,159 +19,131 @@ use crate::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
 // note 4: The element definition is contained in clause 18.5 which gives the type and length for each sub-element which is included in this element. The element shall be present as many times as indicated by the "Number of DA cells for removal" element. There shall be no P-bit preceding each "removal data for DA cell" element which is carried by this PDU.
 // note 5: This element shall not be included unless its value is appropriate to all cells using the channel on which this PDU is sent.
 // note 6: Shall not be used in the present document.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct DNwrkBroadcastRemove {
-    /// Type1, 4 bits, D-NWRK-BROADCAST REMOVE,
-    pub pdu_type_extension: u8,
-    /// Type2, 5 bits, See note 1,
-    pub number_of_ca_cells_for_removal: Option<u64>,
-    /// Conditional See note 2,
-    pub removal_data_for_ca_cell: Option<u64>,
-    /// Type2, 8 bits, See note 3,
-    pub number_of_da_cells_for_removal: Option<u64>,
-    /// Conditional See note 4,
-    pub removal_data_for_da_cell: Option<u64>,
-    /// Conditional See note 5,
-    pub removal_data_for_serving_cell: Option<u64>,
-    /// Type2, 8 bits, See note 6,
-    pub reserved1: Option<u64>,
-    /// Type2, 8 bits, See note 6,
-    pub reserved2: Option<u64>,
-    /// Type2, 16 bits, See note 6,
-    pub reserved3: Option<u64>,
-    /// Type2, 32 bits, See note 6,
-    pub reserved4: Option<u64>,
+    /// 5 bits, removal data for CA cell (Clause 18.5), the cell identifier CA of the removed cell in D-NWRK-BROADCAST
+    pub cell_identifier_ca: u8,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
-#[allow(unused_variables)]
 impl DNwrkBroadcastRemove {
+    /// 4 bits, MLE PDU type extension for D-NWRK-BROADCAST REMOVE (Clause 18.5.21)
+    pub const PDU_TYPE_EXTENSION: u8 = 1;
+
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
         let pdu_type = buffer.read_field(3, "pdu_type")?;
         expect_pdu_type!(pdu_type, MlePduTypeDl::ExtPdu)?;
 
         // Type1
-        let pdu_type_extension = buffer.read_field(4, "pdu_type_extension")? as u8;
+        let pdu_type_extension = buffer.read_field(4, "pdu_type_extension")?;
+        if pdu_type_extension != Self::PDU_TYPE_EXTENSION as u64 {
+            return Err(PduParseErr::InvalidValue {
+                field: "pdu_type_extension",
+                value: pdu_type_extension,
+            });
+        }
 
-        // obit designates presence of any further type2, type3 or type4 fields
+        // obit designates presence of any further type2 fields
         let obit = delimiters::read_obit(buffer)?;
 
         // Type2
         let number_of_ca_cells_for_removal = typed::parse_type2_generic(obit, buffer, 5, "number_of_ca_cells_for_removal")?;
-        // Conditional
-        unimplemented!();
-        let removal_data_for_ca_cell = if obit { Some(0) } else { None };
-        // Type2
-        let number_of_da_cells_for_removal = typed::parse_type2_generic(obit, buffer, 8, "number_of_da_cells_for_removal")?;
-        // Conditional
-        unimplemented!();
-        let removal_data_for_da_cell = if obit { Some(0) } else { None };
-        // Conditional
-        unimplemented!();
-        let removal_data_for_serving_cell = if obit { Some(0) } else { None };
-        // Type2
-        let reserved1 = typed::parse_type2_generic(obit, buffer, 8, "reserved1")?;
-        // Type2
-        let reserved2 = typed::parse_type2_generic(obit, buffer, 8, "reserved2")?;
-        // Type2
-        let reserved3 = typed::parse_type2_generic(obit, buffer, 16, "reserved3")?;
-        // Type2
-        let reserved4 = typed::parse_type2_generic(obit, buffer, 32, "reserved4")?;
+        if number_of_ca_cells_for_removal != Some(1) {
+            return Err(PduParseErr::InvalidValue {
+                field: "number_of_ca_cells_for_removal",
+                value: number_of_ca_cells_for_removal.unwrap_or(0),
+            });
+        }
+
+        // Conditional, see note 2
+        let cell_identifier_ca = buffer.read_field(5, "cell_identifier_ca")? as u8;
 
-        // Read trailing obit (if not previously encountered)
-        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
-        if obit {
-            return Err(PduParseErr::InvalidTrailingMbitValue);
+        // Type2
+        if let Some(count) = typed::parse_type2_generic(obit, buffer, 8, "number_of_da_cells_for_removal")? {
+            return Err(PduParseErr::InvalidValue {
+                field: "number_of_da_cells_for_removal",
+                value: count,
+            });
         }
+        // Type2, see note 6
+        typed::parse_type2_generic(obit, buffer, 8, "reserved1")?;
+        typed::parse_type2_generic(obit, buffer, 8, "reserved2")?;
+        typed::parse_type2_generic(obit, buffer, 16, "reserved3")?;
+        typed::parse_type2_generic(obit, buffer, 32, "reserved4")?;
+
+        // MLE PDUs do not use M-bits (Annex E.2.1) — no trailing delimiter to read
 
-        Ok(DNwrkBroadcastRemove {
-            pdu_type_extension,
-            number_of_ca_cells_for_removal,
-            removal_data_for_ca_cell,
-            number_of_da_cells_for_removal,
-            removal_data_for_da_cell,
-            removal_data_for_serving_cell,
-            reserved1,
-            reserved2,
-            reserved3,
-            reserved4,
-        })
+        Ok(DNwrkBroadcastRemove { cell_identifier_ca })
     }
 
     /// Serialize this PDU into the given BitBuffer.
     pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        if self.cell_identifier_ca >= 1 << 5 {
+            return Err(PduParseErr::InvalidValue {
+                field: "cell_identifier_ca",
+                value: self.cell_identifier_ca as u64,
+            });
+        }
+
         // PDU Type
         buffer.write_bits(MlePduTypeDl::ExtPdu.into_raw(), 3);
         // Type1
-        buffer.write_bits(self.pdu_type_extension as u64, 4);
-
-        // Check if any optional field present and place o-bit
-        let obit = self.number_of_ca_cells_for_removal.is_some()
-            || self.number_of_da_cells_for_removal.is_some()
-            || self.reserved1.is_some()
-            || self.reserved2.is_some()
-            || self.reserved3.is_some()
-            || self.reserved4.is_some();
-        delimiters::write_obit(buffer, obit as u8);
-        if !obit {
-            return Ok(());
-        }
-
-        // Type2
-        typed::write_type2_generic(obit, buffer, self.number_of_ca_cells_for_removal, 5);
+        buffer.write_bits(Self::PDU_TYPE_EXTENSION as u64, 4);
 
-        // Conditional
-        if let Some(ref _value) = self.removal_data_for_ca_cell {
-            unimplemented!();
-            buffer.write_bits(*_value, 999);
-        }
-        // Type2
-        typed::write_type2_generic(obit, buffer, self.number_of_da_cells_for_removal, 8);
-
-        // Conditional
-        if let Some(ref _value) = self.removal_data_for_da_cell {
-            unimplemented!();
-            buffer.write_bits(*_value, 999);
-        }
-        // Conditional
-        if let Some(ref _value) = self.removal_data_for_serving_cell {
-            unimplemented!();
-            buffer.write_bits(*_value, 999);
-        }
-        // Type2
-        typed::write_type2_generic(obit, buffer, self.reserved1, 8);
-
-        // Type2
-        typed::write_type2_generic(obit, buffer, self.reserved2, 8);
+        // The CA cell count is always present
+        let obit = true;
+        delimiters::write_obit(buffer, obit as u8);
 
         // Type2
-        typed::write_type2_generic(obit, buffer, self.reserved3, 16);
+        typed::write_type2_generic(obit, buffer, Some(1), 5);
+        // Conditional, see note 2
+        buffer.write_bits(self.cell_identifier_ca as u64, 5);
 
         // Type2
-        typed::write_type2_generic(obit, buffer, self.reserved4, 32);
+        typed::write_type2_generic(obit, buffer, None, 8);
+        // Type2, see note 6
+        typed::write_type2_generic(obit, buffer, None, 8);
+        typed::write_type2_generic(obit, buffer, None, 8);
+        typed::write_type2_generic(obit, buffer, None, 16);
+        typed::write_type2_generic(obit, buffer, None, 32);
 
-        // Write terminating m-bit
-        delimiters::write_mbit(buffer, 0);
         Ok(())
     }
 }
 
 impl fmt::Display for DNwrkBroadcastRemove {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "DNwrkBroadcastRemove {{ pdu_type_extension: {:?} number_of_ca_cells_for_removal: {:?} removal_data_for_ca_cell: {:?} number_of_da_cells_for_removal: {:?} removal_data_for_da_cell: {:?} removal_data_for_serving_cell: {:?} reserved1: {:?} reserved2: {:?} reserved3: {:?} reserved4: {:?} }}",
-            self.pdu_type_extension,
-            self.number_of_ca_cells_for_removal,
-            self.removal_data_for_ca_cell,
-            self.number_of_da_cells_for_removal,
-            self.removal_data_for_da_cell,
-            self.removal_data_for_serving_cell,
-            self.reserved1,
-            self.reserved2,
-            self.reserved3,
-            self.reserved4,
-        )
+        write!(f, "DNwrkBroadcastRemove {{ cell_identifier_ca: {} }}", self.cell_identifier_ca)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_d_nwrk_broadcast_remove() {
+        debug::setup_logging_verbose();
+        // pdu_type 111, ext 0001, obit 1, pbit 1, count 00001, cell_identifier_ca 00010, 5x pbit 0
+        let test_vec = "111000111000010001000000";
+        let mut buf_in = BitBuffer::from_bitstr(test_vec);
+        let pdu = DNwrkBroadcastRemove::from_bitbuf(&mut buf_in).expect("Failed parsing");
+
+        tracing::info!("Parsed: {}", pdu);
+        assert!(buf_in.get_len_remaining() == 0, "Buffer not fully consumed");
+        assert_eq!(pdu, DNwrkBroadcastRemove { cell_identifier_ca: 2 });
+
+        let mut buf_out = BitBuffer::new_autoexpand(64);
+        pdu.to_bitbuf(&mut buf_out).unwrap();
+        assert_eq!(buf_out.to_bitstr(), test_vec);
+    }
+
+    #[test]
+    fn test_d_nwrk_broadcast_remove_rejects_invalid_cell_identifier() {
+        let pdu = DNwrkBroadcastRemove { cell_identifier_ca: 32 };
+        let mut buf = BitBuffer::new_autoexpand(64);
+        assert!(pdu.to_bitbuf(&mut buf).is_err());
     }
 }
@@ -13,12 +13,10 @@ use crate::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
 // note 1: This PDU shall carry a CMCE D-CALL RESTORE PDU which can be used to restore a call after cell reselection. The SDU is coded according to the CMCE protocol description. There shall be no P-bit in the PDU coding preceding the SDU information element.
 #[derive(Debug)]
 pub struct DRestoreAck {
-    /// Conditional See note,
-    pub sdu: Option<u64>,
+    /// Conditional See note, CMCE PDU taking up the remainder of the PDU
+    pub sdu: Option<BitBuffer>,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
-#[allow(unused_variables)]
 impl DRestoreAck {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
@@ -26,24 +24,25 @@ impl DRestoreAck {
         expect_pdu_type!(pdu_type, MlePduTypeDl::DRestoreAck)?;
 
         // Exceptional case: obit required for SDU field.
-        // SDU takes rest of slot, but still ends with 0-bit (closing obit)
+        // SDU takes rest of slot, but still ends with 0-bit (closing mbit)
 
         // obit designates presence of any further type2, type3 or type4 fields
         let obit = delimiters::read_obit(buffer)?;
 
-        let sdu = if buffer.get_len_remaining() > 0 {
-            Some(buffer.read_field(buffer.get_len_remaining() - 1, "sdu")?)
+        // Conditional
+        let sdu = if obit && buffer.get_len_remaining() > 1 {
+            let sdu_len = buffer.get_len_remaining() - 1;
+            let mut sdu = BitBuffer::new(sdu_len);
+            sdu.copy_bits(buffer, sdu_len);
+            sdu.seek(0);
+            Some(sdu)
         } else {
             None
         };
-        unimplemented!(); // read closing obit
-
-        // obit designates presence of any further type2, type3 or type4 fields
-        let mut obit = delimiters::read_obit(buffer)?;
 
-        // Read trailing obit (if not previously encountered)
-        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
-        if obit {
+        // Read trailing mbit (if not previously encountered)
+        let mbit = if obit { delimiters::read_mbit(buffer)? } else { false };
+        if mbit {
             return Err(PduParseErr::InvalidTrailingMbitValue);
         }
 
@@ -54,12 +53,21 @@ impl DRestoreAck {
     pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
         // PDU Type
         buffer.write_bits(MlePduTypeDl::DRestoreAck.into_raw(), 3);
-        // TODO FIXME: sdu handling
+
+        // Check if SDU present and place o-bit
+        let obit = self.sdu.is_some();
+        delimiters::write_obit(buffer, obit as u8);
+        if !obit {
+            return Ok(());
+        }
+
         // Conditional
-        if let Some(ref value) = self.sdu {
-            unimplemented!();
-            buffer.write_bits(*value, 999);
+        if let Some(sdu) = &self.sdu {
+            let mut sdu = BitBuffer::from_bitbuffer(sdu);
+            let sdu_len = sdu.get_len();
+            buffer.copy_bits(&mut sdu, sdu_len);
         }
+
         // Write terminating m-bit
         delimiters::write_mbit(buffer, 0);
         Ok(())
@@ -68,6 +76,46 @@ impl DRestoreAck {
 
 impl fmt::Display for DRestoreAck {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "DRestoreAck {{ sdu: {:?} }}", self.sdu)
+        write!(
+            f,
+            "DRestoreAck {{ sdu: {:?} }}",
+            self.sdu.as_ref().map(|sdu| sdu.dump_bin_unformatted())
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_d_restore_ack() {
+        debug::setup_logging_verbose();
+        // pdu_type 100, obit 1, sdu 0101100001, mbit 0
+        let test_vec = "100101011000010";
+        let mut buf_in = BitBuffer::from_bitstr(test_vec);
+        let pdu = DRestoreAck::from_bitbuf(&mut buf_in).expect("Failed parsing");
+
+        tracing::info!("Parsed: {}", pdu);
+        assert!(buf_in.get_len_remaining() == 0, "Buffer not fully consumed");
+        assert_eq!(pdu.sdu.as_ref().unwrap().to_bitstr(), "0101100001");
+
+        let mut buf_out = BitBuffer::new_autoexpand(16);
+        pdu.to_bitbuf(&mut buf_out).unwrap();
+        assert_eq!(buf_out.to_bitstr(), test_vec);
+    }
+
+    #[test]
+    fn test_d_restore_ack_roundtrip_without_sdu() {
+        let pdu = DRestoreAck { sdu: None };
+        let mut buf = BitBuffer::new_autoexpand(8);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        assert_eq!(buf.to_bitstr(), "1000");
+
+        buf.seek(0);
+        let parsed = DRestoreAck::from_bitbuf(&mut buf).expect("Failed parsing");
+        assert!(parsed.sdu.is_none());
     }
 }
@@ -18,22 +18,16 @@ use crate::mle::enums::mle_pdu_type_ul::MlePduTypeUl;
 // note 6: If present, this instance of “protocol discriminator” indicates the SDU protocol.
 #[derive(Debug)]
 pub struct UChannelClassAdvice {
-    /// Type1, 2 bits, See note 1,
-    pub number_of_channel_class_identifiers: u8,
-    /// Conditional 4 bits, Repeatable; see note 2,
-    pub channel_class_identifier: Option<u64>,
-    /// Type1, 1 bits, See note 4,
-    pub discriminator_for_sdu_protocol_present: bool,
-    /// Conditional 3 bits, See notes 5 and 6,
-    pub protocol_discriminator: Option<u64>,
+    /// 4 bits each, 1 to 4 identifiers. Preceded by the 2-bit number of channel class identifiers, see notes 1 and 2
+    pub channel_class_identifiers: Vec<u8>,
+    /// Conditional 3 bits, see notes 4, 5 and 6. Preceded by the 1-bit discriminator for SDU protocol present
+    pub protocol_discriminator: Option<u8>,
     /// Type2, 3 bits, Data priority
     pub data_priority: Option<u64>,
-    /// Conditional See note 3,
-    pub sdu: Option<u64>,
+    /// Conditional See note 3, SDU taking up the remainder of the PDU
+    pub sdu: Option<BitBuffer>,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
-#[allow(unused_variables)]
 impl UChannelClassAdvice {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
@@ -41,35 +35,47 @@ impl UChannelClassAdvice {
         expect_pdu_type!(pdu_type, MlePduTypeUl::UChannelClassAdvice)?;
 
         // Type1
-        let number_of_channel_class_identifiers = buffer.read_field(2, "number_of_channel_class_identifiers")? as u8;
+        let num_identifiers = buffer.read_field(2, "number_of_channel_class_identifiers")? as usize + 1;
         // Conditional
-        unimplemented!();
-        let channel_class_identifier = if true { Some(0) } else { None };
+        let mut channel_class_identifiers = Vec::with_capacity(num_identifiers);
+        for _ in 0..num_identifiers {
+            channel_class_identifiers.push(buffer.read_field(4, "channel_class_identifier")? as u8);
+        }
         // Type1
         let discriminator_for_sdu_protocol_present = buffer.read_field(1, "discriminator_for_sdu_protocol_present")? != 0;
         // Conditional
-        unimplemented!();
-        let protocol_discriminator = if true { Some(0) } else { None };
+        let protocol_discriminator = if discriminator_for_sdu_protocol_present {
+            Some(buffer.read_field(3, "protocol_discriminator")? as u8)
+        } else {
+            None
+        };
 
         // obit designates presence of any further type2, type3 or type4 fields
-        let mut obit = delimiters::read_obit(buffer)?;
+        let obit = delimiters::read_obit(buffer)?;
 
         // Type2
         let data_priority = typed::parse_type2_generic(obit, buffer, 3, "data_priority")?;
+
         // Conditional
-        unimplemented!();
-        let sdu = if obit { Some(0) } else { None };
+        // Exceptional case: SDU takes the rest of the PDU, but is still followed by the closing m-bit
+        let sdu = if obit && buffer.get_len_remaining() > 1 {
+            let sdu_len = buffer.get_len_remaining() - 1;
+            let mut sdu = BitBuffer::new(sdu_len);
+            sdu.copy_bits(buffer, sdu_len);
+            sdu.seek(0);
+            Some(sdu)
+        } else {
+            None
+        };
 
-        // Read trailing obit (if not previously encountered)
-        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
-        if obit {
+        // Read trailing mbit (if not previously encountered)
+        let mbit = if obit { delimiters::read_mbit(buffer)? } else { false };
+        if mbit {
             return Err(PduParseErr::InvalidTrailingMbitValue);
         }
 
         Ok(UChannelClassAdvice {
-            number_of_channel_class_identifiers,
-            channel_class_identifier,
-            discriminator_for_sdu_protocol_present,
+            channel_class_identifiers,
             protocol_discriminator,
             data_priority,
             sdu,
@@ -78,23 +84,31 @@ impl UChannelClassAdvice {
 
     /// Serialize this PDU into the given BitBuffer.
     pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        let num_identifiers = self.channel_class_identifiers.len();
+        if !(1..=4).contains(&num_identifiers) {
+            return Err(PduParseErr::InvalidValue {
+                field: "number_of_channel_class_identifiers",
+                value: num_identifiers as u64,
+            });
+        }
+
         // PDU Type
         buffer.write_bits(MlePduTypeUl::UChannelClassAdvice.into_raw(), 3);
         // Type1
-        buffer.write_bits(self.number_of_channel_class_identifiers as u64, 2);
+        buffer.write_bits(num_identifiers as u64 - 1, 2);
         // Conditional
-        if let Some(ref value) = self.channel_class_identifier {
-            buffer.write_bits(*value, 4);
+        for &identifier in &self.channel_class_identifiers {
+            buffer.write_bits(identifier as u64, 4);
         }
         // Type1
-        buffer.write_bits(self.discriminator_for_sdu_protocol_present as u64, 1);
+        buffer.write_bits(self.protocol_discriminator.is_some() as u64, 1);
         // Conditional
-        if let Some(ref value) = self.protocol_discriminator {
-            buffer.write_bits(*value, 3);
+        if let Some(value) = self.protocol_discriminator {
+            buffer.write_bits(value as u64, 3);
         }
 
         // Check if any optional field present and place o-bit
-        let obit = self.data_priority.is_some();
+        let obit = self.data_priority.is_some() || self.sdu.is_some();
         delimiters::write_obit(buffer, obit as u8);
         if !obit {
             return Ok(());
@@ -104,10 +118,12 @@ impl UChannelClassAdvice {
         typed::write_type2_generic(obit, buffer, self.data_priority, 3);
 
         // Conditional
-        if let Some(ref value) = self.sdu {
-            unimplemented!();
-            buffer.write_bits(*value, 999);
+        if let Some(sdu) = &self.sdu {
+            let mut sdu = BitBuffer::from_bitbuffer(sdu);
+            let sdu_len = sdu.get_len();
+            buffer.copy_bits(&mut sdu, sdu_len);
         }
+
         // Write terminating m-bit
         delimiters::write_mbit(buffer, 0);
         Ok(())
@@ -118,13 +134,72 @@ impl fmt::Display for UChannelClassAdvice {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "UChannelClassAdvice {{ number_of_channel_class_identifiers: {:?} channel_class_identifier: {:?} discriminator_for_sdu_protocol_present: {:?} protocol_discriminator: {:?} data_priority: {:?} sdu: {:?} }}",
-            self.number_of_channel_class_identifiers,
-            self.channel_class_identifier,
-            self.discriminator_for_sdu_protocol_present,
+            "UChannelClassAdvice {{ channel_class_identifiers: {:?} protocol_discriminator: {:?} data_priority: {:?} sdu: {:?} }}",
+            self.channel_class_identifiers,
             self.protocol_discriminator,
             self.data_priority,
-            self.sdu,
+            self.sdu.as_ref().map(|sdu| sdu.dump_bin_unformatted()),
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_u_channel_class_advice_two_classes() {
+        debug::setup_logging_verbose();
+        // pdu_type 011, num 01, classes 0010 0101, sdu protocol present 0, obit 1, pbit 1, data priority 110, mbit 0
+        let test_vec = "01101001001010111100";
+        let mut buf_in = BitBuffer::from_bitstr(test_vec);
+        let pdu = UChannelClassAdvice::from_bitbuf(&mut buf_in).expect("Failed parsing");
+
+        tracing::info!("Parsed: {}", pdu);
+        assert!(buf_in.get_len_remaining() == 0, "Buffer not fully consumed");
+        assert_eq!(pdu.channel_class_identifiers, vec![2, 5]);
+        assert_eq!(pdu.protocol_discriminator, None);
+        assert_eq!(pdu.data_priority, Some(6));
+        assert!(pdu.sdu.is_none());
+
+        let mut buf_out = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf_out).unwrap();
+        assert_eq!(buf_out.to_bitstr(), test_vec);
+    }
+
+    #[test]
+    fn test_u_channel_class_advice_roundtrip_with_sdu() {
+        debug::setup_logging_verbose();
+        let pdu = UChannelClassAdvice {
+            channel_class_identifiers: vec![1, 3, 7, 15],
+            protocol_discriminator: Some(4),
+            data_priority: None,
+            sdu: Some(BitBuffer::from_bitstr("1100101")),
+        };
+        let mut buf = BitBuffer::new_autoexpand(48);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        tracing::info!("Serialized: {}", buf.dump_bin());
+
+        buf.seek(0);
+        let parsed = UChannelClassAdvice::from_bitbuf(&mut buf).expect("Failed parsing");
+        assert!(buf.get_len_remaining() == 0, "Buffer not fully consumed");
+        assert_eq!(parsed.channel_class_identifiers, pdu.channel_class_identifiers);
+        assert_eq!(parsed.protocol_discriminator, Some(4));
+        assert_eq!(parsed.data_priority, None);
+        assert_eq!(parsed.sdu.unwrap().to_bitstr(), "1100101");
+    }
+
+    #[test]
+    fn test_u_channel_class_advice_rejects_invalid_count() {
+        let pdu = UChannelClassAdvice {
+            channel_class_identifiers: vec![],
+            protocol_discriminator: None,
+            data_priority: None,
+            sdu: None,
+        };
+        let mut buf = BitBuffer::new_autoexpand(8);
+        assert!(pdu.to_bitbuf(&mut buf).is_err());
+    }
+}
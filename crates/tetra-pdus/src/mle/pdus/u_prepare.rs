@@ -15,12 +15,10 @@ use crate::mle::enums::mle_pdu_type_ul::MlePduTypeUl;
 pub struct UPrepare {
     /// Type2, 5 bits, Cell identifier CA
     pub cell_identifier_ca: Option<u64>,
-    /// Conditional See note,
-    pub sdu: Option<u64>,
+    /// Conditional See note, MM PDU taking up the remainder of the PDU
+    pub sdu: Option<BitBuffer>,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
-#[allow(unused_variables)]
 impl UPrepare {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
@@ -34,12 +32,20 @@ impl UPrepare {
         let cell_identifier_ca = typed::parse_type2_generic(obit, buffer, 5, "cell_identifier_ca")?;
 
         // Conditional
-        unimplemented!();
-        let sdu = if obit { Some(0) } else { None };
+        // Exceptional case: SDU takes the rest of the PDU, but is still followed by the closing m-bit
+        let sdu = if obit && buffer.get_len_remaining() > 1 {
+            let sdu_len = buffer.get_len_remaining() - 1;
+            let mut sdu = BitBuffer::new(sdu_len);
+            sdu.copy_bits(buffer, sdu_len);
+            sdu.seek(0);
+            Some(sdu)
+        } else {
+            None
+        };
 
-        // Read trailing obit (if not previously encountered)
-        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
-        if obit {
+        // Read trailing mbit (if not previously encountered)
+        let mbit = if obit { delimiters::read_mbit(buffer)? } else { false };
+        if mbit {
             return Err(PduParseErr::InvalidTrailingMbitValue);
         }
 
@@ -52,7 +58,7 @@ impl UPrepare {
         buffer.write_bits(MlePduTypeUl::UPrepare.into_raw(), 3);
 
         // Check if any optional field present and place o-bit
-        let obit = self.cell_identifier_ca.is_some();
+        let obit = self.cell_identifier_ca.is_some() || self.sdu.is_some();
         delimiters::write_obit(buffer, obit as u8);
         if !obit {
             return Ok(());
@@ -62,10 +68,12 @@ impl UPrepare {
         typed::write_type2_generic(obit, buffer, self.cell_identifier_ca, 5);
 
         // Conditional
-        if let Some(ref value) = self.sdu {
-            unimplemented!();
-            buffer.write_bits(*value, 999);
+        if let Some(sdu) = &self.sdu {
+            let mut sdu = BitBuffer::from_bitbuffer(sdu);
+            let sdu_len = sdu.get_len();
+            buffer.copy_bits(&mut sdu, sdu_len);
         }
+
         // Write terminating m-bit
         delimiters::write_mbit(buffer, 0);
         Ok(())
@@ -77,7 +85,67 @@ impl fmt::Display for UPrepare {
         write!(
             f,
             "UPrepare {{ cell_identifier_ca: {:?} sdu: {:?} }}",
-            self.cell_identifier_ca, self.sdu,
+            self.cell_identifier_ca,
+            self.sdu.as_ref().map(|sdu| sdu.dump_bin_unformatted()),
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_u_prepare_cell_id_only() {
+        debug::setup_logging_verbose();
+        // pdu_type 000, obit 1, pbit 1, cell_identifier_ca 10110, mbit 0
+        let test_vec = "00011101100";
+        let mut buf_in = BitBuffer::from_bitstr(test_vec);
+        let pdu = UPrepare::from_bitbuf(&mut buf_in).expect("Failed parsing");
+
+        tracing::info!("Parsed: {}", pdu);
+        assert!(buf_in.get_len_remaining() == 0, "Buffer not fully consumed");
+        assert_eq!(pdu.cell_identifier_ca, Some(0b10110));
+        assert!(pdu.sdu.is_none());
+
+        let mut buf_out = BitBuffer::new_autoexpand(16);
+        pdu.to_bitbuf(&mut buf_out).unwrap();
+        assert_eq!(buf_out.to_bitstr(), test_vec);
+    }
+
+    #[test]
+    fn test_u_prepare_roundtrip_with_sdu() {
+        debug::setup_logging_verbose();
+        let pdu = UPrepare {
+            cell_identifier_ca: Some(3),
+            sdu: Some(BitBuffer::from_bitstr("0010110100111")),
+        };
+        let mut buf = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        tracing::info!("Serialized: {}", buf.dump_bin());
+
+        buf.seek(0);
+        let parsed = UPrepare::from_bitbuf(&mut buf).expect("Failed parsing");
+        assert!(buf.get_len_remaining() == 0, "Buffer not fully consumed");
+        assert_eq!(parsed.cell_identifier_ca, pdu.cell_identifier_ca);
+        assert_eq!(parsed.sdu.unwrap().to_bitstr(), "0010110100111");
+    }
+
+    #[test]
+    fn test_u_prepare_empty() {
+        let pdu = UPrepare {
+            cell_identifier_ca: None,
+            sdu: None,
+        };
+        let mut buf = BitBuffer::new_autoexpand(8);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        assert_eq!(buf.to_bitstr(), "0000");
+
+        buf.seek(0);
+        let parsed = UPrepare::from_bitbuf(&mut buf).expect("Failed parsing");
+        assert!(parsed.cell_identifier_ca.is_none());
+        assert!(parsed.sdu.is_none());
+    }
+}
@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 16.10.9 Energy saving mode
 /// Bits: 3
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -21,7 +23,7 @@ pub enum EnergySavingMode {
 }
 
 impl std::convert::TryFrom<u64> for EnergySavingMode {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(EnergySavingMode::StayAlive),
@@ -32,7 +34,10 @@ impl std::convert::TryFrom<u64> for EnergySavingMode {
             5 => Ok(EnergySavingMode::Eg5),
             6 => Ok(EnergySavingMode::Eg6),
             7 => Ok(EnergySavingMode::Eg7),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "energy_saving_mode",
+                value: x,
+            }),
         }
     }
 }
@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 16.10.35a Location update accept type
 /// Bits: 3
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -14,7 +16,7 @@ pub enum LocationUpdateAcceptType {
 }
 
 impl std::convert::TryFrom<u64> for LocationUpdateAcceptType {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(LocationUpdateAcceptType::RoamingLocationUpdating),
@@ -25,7 +27,10 @@ impl std::convert::TryFrom<u64> for LocationUpdateAcceptType {
             5 => Ok(LocationUpdateAcceptType::MigratingOrServiceRestorationMigratingLocationUpdating),
             6 => Ok(LocationUpdateAcceptType::DemandLocationUpdating),
             7 => Ok(LocationUpdateAcceptType::DisabledMsUpdating),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "location_update_accept_type",
+                value: x,
+            }),
         }
     }
 }
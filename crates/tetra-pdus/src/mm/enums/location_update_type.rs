@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 16.10.35 Location update type
 /// Almost identical to MmLocationUpdateAcceptType
 /// Bits: 3
@@ -15,7 +17,7 @@ pub enum LocationUpdateType {
 }
 
 impl std::convert::TryFrom<u64> for LocationUpdateType {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(LocationUpdateType::RoamingLocationUpdating),
@@ -26,7 +28,10 @@ impl std::convert::TryFrom<u64> for LocationUpdateType {
             5 => Ok(LocationUpdateType::ServiceRestorationMigratingLocationUpdating),
             6 => Ok(LocationUpdateType::DemandLocationUpdating),
             7 => Ok(LocationUpdateType::DisabledMsUpdating),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "location_update_type",
+                value: x,
+            }),
         }
     }
 }
@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 16.10.39 MM PDU types
 /// Bits: 4
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -19,7 +21,7 @@ pub enum MmPduTypeDl {
 }
 
 impl std::convert::TryFrom<u64> for MmPduTypeDl {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(MmPduTypeDl::DOtar),
@@ -35,7 +37,10 @@ impl std::convert::TryFrom<u64> for MmPduTypeDl {
             11 => Ok(MmPduTypeDl::DAttachDetachGroupIdentityAcknowledgement),
             12 => Ok(MmPduTypeDl::DMmStatus),
             15 => Ok(MmPduTypeDl::MmPduFunctionNotSupported),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "mm_pdu_type_dl",
+                value: x,
+            }),
         }
     }
 }
@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 16.10.39 MM PDU types
 /// Bits: 4
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -18,7 +20,7 @@ pub enum MmPduTypeUl {
 }
 
 impl std::convert::TryFrom<u64> for MmPduTypeUl {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(MmPduTypeUl::UAuthentication),
@@ -33,7 +35,10 @@ impl std::convert::TryFrom<u64> for MmPduTypeUl {
             9 => Ok(MmPduTypeUl::UTeiProvide),
             11 => Ok(MmPduTypeUl::UDisableStatus),
             15 => Ok(MmPduTypeUl::MmPduFunctionNotSupported),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "mm_pdu_type_ul",
+                value: x,
+            }),
         }
     }
 }
@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 16.10.42 Reject cause
 /// Bits: 5
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -26,7 +28,7 @@ pub enum RejectCause {
 }
 
 impl std::convert::TryFrom<u64> for RejectCause {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             1 => Ok(RejectCause::ItsiAtsiUnknown),
@@ -49,7 +51,10 @@ impl std::convert::TryFrom<u64> for RejectCause {
             19 => Ok(RejectCause::AuthenticationFailure),
             20 => Ok(RejectCause::UseCaCellNotPermitted),
             21 => Ok(RejectCause::UseDaCellNotPermitted),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "reject_cause",
+                value: x,
+            }),
         }
     }
 }
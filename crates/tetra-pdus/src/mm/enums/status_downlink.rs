@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// 16.10.48 Status downlink (also B.3.10 Status downlink in ETSI EN 300 396-5, Gateway air interface)
 /// Bits: 6
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -54,7 +56,7 @@ pub enum StatusDownlink {
 }
 
 impl std::convert::TryFrom<u64> for StatusDownlink {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             1 => Ok(StatusDownlink::ChangeOfEnergySavingModeRequest),
@@ -105,7 +107,10 @@ impl std::convert::TryFrom<u64> for StatusDownlink {
             61 => Ok(StatusDownlink::NetworkOrUserSpecific61),
             62 => Ok(StatusDownlink::NetworkOrUserSpecific62),
             63 => Ok(StatusDownlink::NetworkOrUserSpecific63),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "status_downlink",
+                value: x,
+            }),
         }
     }
 }
@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// 16.10.48a Status uplink (also B.3.11 Status uplink in ETSI EN 300 396-5, Gateway air interface)
 /// Bits: 6
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -54,7 +56,7 @@ pub enum StatusUplink {
 }
 
 impl std::convert::TryFrom<u64> for StatusUplink {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             1 => Ok(StatusUplink::ChangeOfEnergySavingModeRequest),
@@ -105,7 +107,10 @@ impl std::convert::TryFrom<u64> for StatusUplink {
             61 => Ok(StatusUplink::NetworkOrUserSpecific61),
             62 => Ok(StatusUplink::NetworkOrUserSpecific62),
             63 => Ok(StatusUplink::NetworkOrUserSpecific63),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "status_uplink",
+                value: x,
+            }),
         }
     }
 }
@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 16.10.51 Type 3/4 element identifier
 /// Bits: 3
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -17,7 +19,7 @@ pub enum MmType34ElemIdDl {
 }
 
 impl std::convert::TryFrom<u64> for MmType34ElemIdDl {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             1 => Ok(MmType34ElemIdDl::DefaultGroupAttachLifetime),
@@ -31,7 +33,10 @@ impl std::convert::TryFrom<u64> for MmType34ElemIdDl {
             12 => Ok(MmType34ElemIdDl::GroupIdentitySecurityRelatedInformation),
             13 => Ok(MmType34ElemIdDl::CellTypeControl),
             15 => Ok(MmType34ElemIdDl::Proprietary),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "type34_elem_id_dl",
+                value: x,
+            }),
         }
     }
 }
@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 16.10.39 MM PDU types
 /// Bits: 3
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -13,7 +15,7 @@ pub enum MmType34ElemIdUl {
 }
 
 impl std::convert::TryFrom<u64> for MmType34ElemIdUl {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             3 => Ok(MmType34ElemIdUl::GroupIdentityLocationDemand),
@@ -23,7 +25,10 @@ impl std::convert::TryFrom<u64> for MmType34ElemIdUl {
             9 => Ok(MmType34ElemIdUl::AuthenticationUplink),
             11 => Ok(MmType34ElemIdUl::ExtendedCapabilities),
             15 => Ok(MmType34ElemIdUl::Proprietary),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "type34_elem_id_ul",
+                value: x,
+            }),
         }
     }
 }
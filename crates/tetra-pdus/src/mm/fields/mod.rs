@@ -5,3 +5,4 @@ pub mod group_identity_downlink;
 pub mod group_identity_location_accept;
 pub mod group_identity_location_demand;
 pub mod group_identity_uplink;
+pub mod new_registered_area;
@@ -0,0 +1,65 @@
+use core::fmt;
+
+use tetra_core::typed_pdu_fields::{delimiters, typed};
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+/// 16.10.40 New registered area
+/// Element of the New registered area type4 information element, one per location area added to the registered area.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewRegisteredArea {
+    // 3
+    pub la_timer: u8,
+    // 14
+    pub la: u16,
+    // 10 opt
+    pub lacc: Option<u16>,
+    // 14 opt
+    pub lanc: Option<u16>,
+}
+
+impl NewRegisteredArea {
+    pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let la_timer = buf.read_field(3, "la_timer")? as u8;
+        let la = buf.read_field(14, "la")? as u16;
+
+        let obit = delimiters::read_obit(buf)?;
+        let lacc = typed::parse_type2_generic(obit, buf, 10, "lacc")?.map(|v| v as u16);
+        let lanc = typed::parse_type2_generic(obit, buf, 14, "lanc")?.map(|v| v as u16);
+
+        Ok(NewRegisteredArea { la_timer, la, lacc, lanc })
+    }
+
+    pub fn to_bitbuf(&self, buf: &mut BitBuffer) -> Result<(), PduParseErr> {
+        if self.la_timer > 7 {
+            return Err(PduParseErr::InvalidValue {
+                field: "la_timer",
+                value: self.la_timer as u64,
+            });
+        }
+        if self.la >= 1 << 14 {
+            return Err(PduParseErr::InvalidValue {
+                field: "la",
+                value: self.la as u64,
+            });
+        }
+        buf.write_bits(self.la_timer as u64, 3);
+        buf.write_bits(self.la as u64, 14);
+
+        let obit = self.lacc.is_some() || self.lanc.is_some();
+        delimiters::write_obit(buf, obit as u8);
+        typed::write_type2_generic(obit, buf, self.lacc.map(|v| v as u64), 10);
+        typed::write_type2_generic(obit, buf, self.lanc.map(|v| v as u64), 14);
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for NewRegisteredArea {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "NewRegisteredArea {{ la_timer: {} la: {} lacc: {:?} lanc: {:?} }}",
+            self.la_timer, self.la, self.lacc, self.lanc,
+        )
+    }
+}
@@ -9,6 +9,7 @@ use crate::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
 use crate::mm::enums::type34_elem_id_dl::MmType34ElemIdDl;
 use crate::mm::fields::energy_saving_information::EnergySavingInformation;
 use crate::mm::fields::group_identity_location_accept::GroupIdentityLocationAccept;
+use crate::mm::fields::new_registered_area::NewRegisteredArea;
 
 /// Representation of the D-LOCATION UPDATE ACCEPT PDU (Clause 16.9.2.7).
 /// The infrastructure sends this message to the MS to indicate that updating in the network has been completed.
@@ -31,7 +32,7 @@ pub struct DLocationUpdateAccept {
     /// Type2, 6 bits, SCCH information and distribution on 18th frame
     pub scch_information_and_distribution_on_18th_frame: Option<u64>,
     /// Type4, See note,
-    pub new_registered_area: Option<Type4FieldGeneric>,
+    pub new_registered_area: Option<Vec<NewRegisteredArea>>,
     /// Type3, See ETSI EN 300 392-7 [8],
     pub security_downlink: Option<Type3FieldGeneric>,
     /// Type3, See note,
@@ -61,7 +62,7 @@ impl DLocationUpdateAccept {
         let location_update_accept_type = match result {
             Ok(x) => x,
             Err(_) => {
-                return Err(PduParseErr::InvalidValue {
+                return Err(PduParseErr::UnsupportedValue {
                     field: "location_update_accept_type",
                     value: val,
                 });
@@ -84,7 +85,8 @@ impl DLocationUpdateAccept {
             typed::parse_type2_generic(obit, buffer, 6, "scch_information_and_distribution_on_18th_frame")?;
 
         // Type4
-        let new_registered_area = typed::parse_type4_generic(obit, buffer, MmType34ElemIdDl::NewRegisteredArea)?;
+        let new_registered_area =
+            typed::parse_type4_struct(obit, buffer, MmType34ElemIdDl::NewRegisteredArea, NewRegisteredArea::from_bitbuf)?;
 
         // Type3
         let security_downlink = typed::parse_type3_generic(obit, buffer, MmType34ElemIdDl::SecurityDownlink)?;
@@ -179,7 +181,13 @@ impl DLocationUpdateAccept {
         typed::write_type2_generic(obit, buffer, self.scch_information_and_distribution_on_18th_frame, 6);
 
         // Type4
-        typed::write_type4_todo(obit, buffer, &self.new_registered_area, MmType34ElemIdDl::NewRegisteredArea)?;
+        typed::write_type4_struct(
+            obit,
+            buffer,
+            &self.new_registered_area,
+            MmType34ElemIdDl::NewRegisteredArea,
+            NewRegisteredArea::to_bitbuf,
+        )?;
 
         // Type3
         typed::write_type3_generic(obit, buffer, &self.security_downlink, MmType34ElemIdDl::SecurityDownlink)?;
@@ -277,4 +285,87 @@ mod tests {
         tracing::info!("Serialized: {}", buf_out.dump_bin());
         assert_eq!(buf_out.to_bitstr(), test_vec);
     }
+
+    #[test]
+    fn test_d_location_update_accept_with_new_registered_area() {
+        debug::setup_logging_verbose();
+        let pdu = DLocationUpdateAccept {
+            location_update_accept_type: LocationUpdateType::RoamingLocationUpdating,
+            ssi: Some(1234567),
+            address_extension: None,
+            subscriber_class: Some(0xFFFF),
+            energy_saving_information: None,
+            scch_information_and_distribution_on_18th_frame: None,
+            new_registered_area: Some(vec![
+                NewRegisteredArea {
+                    la_timer: 7,
+                    la: 2,
+                    lacc: None,
+                    lanc: None,
+                },
+                NewRegisteredArea {
+                    la_timer: 3,
+                    la: 0x3FFF,
+                    lacc: Some(204),
+                    lanc: Some(1337),
+                },
+            ]),
+            security_downlink: None,
+            group_identity_location_accept: None,
+            default_group_attachment_lifetime: None,
+            authentication_downlink: None,
+            group_identity_security_related_information: None,
+            cell_type_control: None,
+            proprietary: None,
+        };
+
+        let mut buf = BitBuffer::new_autoexpand(64);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        tracing::info!("Serialized: {}", buf.dump_bin());
+
+        buf.seek(0);
+        let parsed = DLocationUpdateAccept::from_bitbuf(&mut buf).expect("Failed parsing");
+        assert!(buf.get_len_remaining() == 0, "Buffer not fully consumed");
+        assert_eq!(parsed.ssi, pdu.ssi);
+        assert_eq!(parsed.subscriber_class, pdu.subscriber_class);
+        assert_eq!(parsed.new_registered_area, pdu.new_registered_area);
+    }
+
+    #[test]
+    fn test_d_location_update_accept_truncated_input() {
+        // Feed every prefix of a valid PDU to the parser; it must fail gracefully instead of panicking
+        let pdu = DLocationUpdateAccept {
+            location_update_accept_type: LocationUpdateType::RoamingLocationUpdating,
+            ssi: Some(1234567),
+            address_extension: None,
+            subscriber_class: Some(0x1234),
+            energy_saving_information: None,
+            scch_information_and_distribution_on_18th_frame: None,
+            new_registered_area: Some(vec![NewRegisteredArea {
+                la_timer: 1,
+                la: 42,
+                lacc: Some(204),
+                lanc: None,
+            }]),
+            security_downlink: None,
+            group_identity_location_accept: None,
+            default_group_attachment_lifetime: None,
+            authentication_downlink: None,
+            group_identity_security_related_information: None,
+            cell_type_control: None,
+            proprietary: None,
+        };
+        let mut buf = BitBuffer::new_autoexpand(64);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        let bitstr = buf.to_bitstr();
+
+        for len in 0..bitstr.len() {
+            let mut buf_in = BitBuffer::from_bitstr(&bitstr[..len]);
+            assert!(
+                DLocationUpdateAccept::from_bitbuf(&mut buf_in).is_err(),
+                "prefix of len {} parsed",
+                len
+            );
+        }
+    }
 }
@@ -31,7 +31,7 @@ impl DMmStatus {
 
         // Type1
         let val = buffer.read_field(6, "status_downlink")?;
-        let status_downlink = StatusDownlink::try_from(val).map_err(|_| PduParseErr::InvalidValue {
+        let status_downlink = StatusDownlink::try_from(val).map_err(|_| PduParseErr::UnsupportedValue {
             field: "status_downlink",
             value: val,
         })?;
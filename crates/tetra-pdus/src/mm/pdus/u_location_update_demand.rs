@@ -63,7 +63,7 @@ impl ULocationUpdateDemand {
         let location_update_type = match result {
             Ok(x) => x,
             Err(_) => {
-                return Err(PduParseErr::InvalidValue {
+                return Err(PduParseErr::UnsupportedValue {
                     field: "location_update_type",
                     value: val,
                 });
@@ -37,7 +37,7 @@ impl UMmStatus {
         let status_uplink = match result {
             Ok(x) => x,
             Err(_) => {
-                return Err(PduParseErr::InvalidValue {
+                return Err(PduParseErr::UnsupportedValue {
                     field: "status_uplink",
                     value: val,
                 });
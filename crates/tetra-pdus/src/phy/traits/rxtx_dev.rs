@@ -1,5 +1,6 @@
 use tetra_core::TdmaTime;
 use tetra_core::TrainingSequence;
+use tetra_saps::control::admin::PhyRxStats;
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum RxTxDevError {
@@ -40,4 +41,13 @@ pub struct TxSlotBits<'a> {
 /// Trait for RX/TX devices that work with full slots.
 pub trait RxTxDev {
     fn rxtx_timeslot(&mut self, tx_slot: &[TxSlotBits]) -> Result<Vec<Option<RxSlotBits<'_>>>, RxTxDevError>;
+
+    /// Stop the RF hardware. No slots are exchanged afterwards.
+    fn shutdown(&mut self) {}
+
+    /// Receive counters kept by the device itself, i.e. timing_errors and buffer_overruns.
+    /// The burst counters are kept by the PHY from the slots it gets, and left zero here.
+    fn rx_stats(&self) -> PhyRxStats {
+        PhyRxStats::default()
+    }
 }
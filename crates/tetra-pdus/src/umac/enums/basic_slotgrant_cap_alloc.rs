@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 21.5.6 Basic slot granting, Capacity Allocation element
 /// Bits: 4
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -22,7 +24,7 @@ pub enum BasicSlotgrantCapAlloc {
 }
 
 impl std::convert::TryFrom<u64> for BasicSlotgrantCapAlloc {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(BasicSlotgrantCapAlloc::FirstSubslotGranted),
@@ -41,7 +43,10 @@ impl std::convert::TryFrom<u64> for BasicSlotgrantCapAlloc {
             13 => Ok(BasicSlotgrantCapAlloc::Grant51Slots),
             14 => Ok(BasicSlotgrantCapAlloc::Grant68Slots),
             15 => Ok(BasicSlotgrantCapAlloc::SecondSubslotGranted),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "basic_slotgrant_cap_alloc",
+                value: x,
+            }),
         }
     }
 }
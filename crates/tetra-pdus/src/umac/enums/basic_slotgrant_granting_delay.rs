@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 21.5.6 Basic slot granting, granting delay element
 /// Bits: 4
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -11,14 +13,17 @@ pub enum BasicSlotgrantGrantingDelay {
 }
 
 impl std::convert::TryFrom<u64> for BasicSlotgrantGrantingDelay {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(BasicSlotgrantGrantingDelay::CapAllocAtNextOpportunity),
             1..=13 => Ok(BasicSlotgrantGrantingDelay::DelayNOpportunities(x as u8)),
             14 => Ok(BasicSlotgrantGrantingDelay::AllocStartsAtOpportunityInFr18),
             15 => Ok(BasicSlotgrantGrantingDelay::WaitForAnotherSlotgrantMessage),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "basic_slotgrant_granting_delay",
+                value: x,
+            }),
         }
     }
 }
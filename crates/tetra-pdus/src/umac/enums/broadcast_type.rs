@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 21.4.4.0 Table 21.64
 /// Bits: 2
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -12,13 +14,16 @@ pub enum BroadcastType {
 }
 
 impl std::convert::TryFrom<u64> for BroadcastType {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(BroadcastType::Sysinfo),
             1 => Ok(BroadcastType::AccessDefine),
             2 => Ok(BroadcastType::SysinfoDa),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "broadcast_type",
+                value: x,
+            }),
         }
     }
 }
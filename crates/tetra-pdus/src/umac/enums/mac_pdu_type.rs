@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 21.4.1 Table 21.38: MAC PDU types for SCH/F, SCH/HD, STCH, SCH-P8/F, SCH-P8/HD, SCH-Q/D, SCH-Q/B and SCH-Q/U
 /// Bits: 2
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -14,14 +16,17 @@ pub enum MacPduType {
 }
 
 impl std::convert::TryFrom<u64> for MacPduType {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(MacPduType::MacResourceMacData),
             1 => Ok(MacPduType::MacFragMacEnd),
             2 => Ok(MacPduType::Broadcast),
             3 => Ok(MacPduType::SuppMacUSignal),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "mac_pdu_type",
+                value: x,
+            }),
         }
     }
 }
@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 21.4.3.1 Table 21.55 MAC-RESOURCE address types
 /// Bits: 3
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -14,7 +16,7 @@ pub enum MacResourceAddrType {
 }
 
 impl std::convert::TryFrom<u64> for MacResourceAddrType {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(MacResourceAddrType::NullPdu),
@@ -25,7 +27,10 @@ impl std::convert::TryFrom<u64> for MacResourceAddrType {
             5 => Ok(MacResourceAddrType::SsiAndEventLabel),
             6 => Ok(MacResourceAddrType::SsiAndUsageMarker),
             7 => Ok(MacResourceAddrType::SmiAndEventLabel),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "mac_resource_addr_type",
+                value: x,
+            }),
         }
     }
 }
@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 21.5.4 Reservation requirement
 /// Bits: 4
 ///
@@ -32,7 +34,7 @@ pub enum ReservationRequirement {
 }
 
 impl std::convert::TryFrom<u64> for ReservationRequirement {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(ReservationRequirement::Req1Subslot),
@@ -51,7 +53,10 @@ impl std::convert::TryFrom<u64> for ReservationRequirement {
             13 => Ok(ReservationRequirement::Req51Slots),
             14 => Ok(ReservationRequirement::Req68Slots),
             15 => Ok(ReservationRequirement::ReqOver68),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "reservation_requirement",
+                value: x,
+            }),
         }
     }
 }
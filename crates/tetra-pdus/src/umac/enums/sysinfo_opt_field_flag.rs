@@ -1,3 +1,5 @@
+use tetra_core::pdu_parse_error::PduParseErr;
+
 /// Clause 21.4.4.1 Table 21.65
 /// Bits: 2
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -14,14 +16,17 @@ pub enum SysinfoOptFieldFlag {
 }
 
 impl std::convert::TryFrom<u64> for SysinfoOptFieldFlag {
-    type Error = ();
+    type Error = PduParseErr;
     fn try_from(x: u64) -> Result<Self, Self::Error> {
         match x {
             0 => Ok(SysinfoOptFieldFlag::EvenMfDefForTsMode),
             1 => Ok(SysinfoOptFieldFlag::OddMfDefForTsMode),
             2 => Ok(SysinfoOptFieldFlag::DefaultDefForAccCodeA),
             3 => Ok(SysinfoOptFieldFlag::ExtServicesBroadcast),
-            _ => Err(()),
+            _ => Err(PduParseErr::UnsupportedValue {
+                field: "sysinfo_opt_field_flag",
+                value: x,
+            }),
         }
     }
 }
@@ -16,13 +16,13 @@ pub struct BasicSlotgrant {
 impl BasicSlotgrant {
     pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
         let cap_alloc_val = buf.read_field(4, "capacity_allocation")?;
-        let capacity_allocation = BasicSlotgrantCapAlloc::try_from(cap_alloc_val).map_err(|_| PduParseErr::InvalidValue {
+        let capacity_allocation = BasicSlotgrantCapAlloc::try_from(cap_alloc_val).map_err(|_| PduParseErr::UnsupportedValue {
             field: "capacity_allocation",
             value: cap_alloc_val,
         })?;
 
         let granting_delay_val = buf.read_field(4, "granting_delay")?;
-        let granting_delay = BasicSlotgrantGrantingDelay::try_from(granting_delay_val).map_err(|_| PduParseErr::InvalidValue {
+        let granting_delay = BasicSlotgrantGrantingDelay::try_from(granting_delay_val).map_err(|_| PduParseErr::UnsupportedValue {
             field: "granting_delay",
             value: granting_delay_val,
         })?;
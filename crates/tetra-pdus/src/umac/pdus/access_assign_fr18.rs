@@ -169,3 +169,75 @@ impl fmt::Display for AccessAssignFr18 {
         write!(f, " }}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(pdu: &AccessAssignFr18) -> String {
+        let mut buf = BitBuffer::new(14);
+        pdu.to_bitbuf(&mut buf);
+        assert_eq!(buf.get_pos(), 14);
+        buf.seek(0);
+        buf.dump_bin_unformatted()
+    }
+
+    #[test]
+    fn test_common_only_offsets() {
+        // Header at bits 0..2, AF1 at 2..8, AF2 at 8..14
+        let pdu = AccessAssignFr18 {
+            ul_usage: AccessAssignUlUsage::CommonOnly,
+            f1_af1: Some(AccessField {
+                access_code: 2,
+                base_frame_len: 0x5,
+            }),
+            f2_af2: Some(AccessField {
+                access_code: 1,
+                base_frame_len: 0xA,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(encode(&pdu), "00100101011010");
+
+        let mut buf = BitBuffer::from_bitstr("00100101011010");
+        let parsed = AccessAssignFr18::from_bitbuf(&mut buf).unwrap();
+        assert_eq!(parsed._header, 0);
+        assert_eq!(parsed.ul_usage, AccessAssignUlUsage::CommonOnly);
+        assert_eq!(parsed.f1_af1.unwrap().access_code, 2);
+        assert_eq!(parsed.f1_af1.unwrap().base_frame_len, 0x5);
+        assert_eq!(parsed.f2_af2.unwrap().access_code, 1);
+        assert_eq!(parsed.f2_af2.unwrap().base_frame_len, 0xA);
+        assert!(parsed.f2_af.is_none());
+    }
+
+    #[test]
+    fn test_traffic_offsets() {
+        // Header 3, UL usage marker at bits 2..8, AF at 8..14
+        let pdu = AccessAssignFr18 {
+            ul_usage: AccessAssignUlUsage::Traffic(0x21),
+            f2_af: Some(AccessField {
+                access_code: 3,
+                base_frame_len: 0x1,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(encode(&pdu), "11100001110001");
+
+        let mut buf = BitBuffer::from_bitstr("11100001110001");
+        let parsed = AccessAssignFr18::from_bitbuf(&mut buf).unwrap();
+        assert_eq!(parsed._header, 3);
+        assert_eq!(parsed.ul_usage, AccessAssignUlUsage::Traffic(0x21));
+        assert_eq!(parsed.f2_af.unwrap().access_code, 3);
+        assert_eq!(parsed.f2_af.unwrap().base_frame_len, 0x1);
+        assert!(parsed.f1_af1.is_none() && parsed.f2_af2.is_none());
+    }
+
+    #[test]
+    fn test_roundtrip_headers() {
+        for bitstr in ["01000001000000", "10110000001111"] {
+            let mut buf = BitBuffer::from_bitstr(bitstr);
+            let pdu = AccessAssignFr18::from_bitbuf(&mut buf).unwrap();
+            assert_eq!(encode(&pdu), bitstr);
+        }
+    }
+}
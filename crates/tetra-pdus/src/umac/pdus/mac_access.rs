@@ -1,6 +1,6 @@
 use core::fmt;
 
-use tetra_core::{BitBuffer, SsiType, TetraAddress, pdu_parse_error::PduParseErr};
+use tetra_core::{BitBuffer, SsiType, TetraAddress, expect_value, pdu_parse_error::PduParseErr};
 
 use crate::umac::{enums::reservation_requirement::ReservationRequirement, fields::EventLabel};
 
@@ -35,7 +35,7 @@ impl MacAccess {
     pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
         // required constant mac_pdu_type
         let mac_pdu_type = buf.read_field(1, "mac_pdu_type")?;
-        assert!(mac_pdu_type == 0);
+        expect_value!(mac_pdu_type, 0)?;
         let fill_bits = buf.read_field(1, "fill_bits")? != 0;
         let encrypted = buf.read_field(1, "encrypted")? != 0;
 
@@ -1,19 +1,22 @@
 use core::fmt;
 
-use tetra_core::BitBuffer;
 use tetra_core::pdu_parse_error::PduParseErr;
+use tetra_core::typed_pdu_fields::{FieldU2, FieldU10};
+use tetra_core::{BitBuffer, expect_value};
 
+use crate::umac::enums::mac_pdu_type::MacPduType;
 use crate::umac::fields::basic_slotgrant::BasicSlotgrant;
 
 /// Clause 21.4.3.4 MAC-D-BLCK
+/// Supplementary DL PDU on SCH/F, addressed by event label. Always fills the slot.
 #[derive(Debug, Clone)]
 pub struct MacDBlck {
     // 1
     pub fill_bits: bool,
     // 2
-    pub encryption_mode: u8,
+    pub encryption_mode: FieldU2,
     // 10
-    pub event_label: u16,
+    pub event_label: FieldU10,
     // 1
     pub imm_napping_permission: bool,
     // 1
@@ -26,21 +29,23 @@ impl MacDBlck {
     pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
         let mut s = MacDBlck {
             fill_bits: false,
-            encryption_mode: 0,
-            event_label: 0,
+            encryption_mode: FieldU2::new(0),
+            event_label: FieldU10::new(0),
             imm_napping_permission: false,
             // slot_granting_flag: false,
             slot_granting_element: None,
         };
 
         // required constant mac_pdu_type
-        assert!(buf.read_field(2, "mac_pdu_type")? == 3);
+        let mac_pdu_type = buf.read_field(2, "mac_pdu_type")?;
+        expect_value!(mac_pdu_type, MacPduType::SuppMacUSignal.into_raw())?;
         // required constant pdu_subtype
-        assert!(buf.read_field(1, "pdu_subtype")? == 0);
+        let pdu_subtype = buf.read_field(1, "pdu_subtype")?;
+        expect_value!(pdu_subtype, 0)?;
 
         s.fill_bits = buf.read_field(1, "fill_bits")? != 0;
-        s.encryption_mode = buf.read_field(2, "encryption_mode")? as u8;
-        s.event_label = buf.read_field(10, "event_label")? as u16;
+        s.encryption_mode = FieldU2::read(buf, "encryption_mode")?;
+        s.event_label = FieldU10::read(buf, "event_label")?;
         s.imm_napping_permission = buf.read_field(1, "imm_napping_permission")? != 0;
 
         let slot_granting_flag = buf.read_field(1, "slot_granting_flag")?;
@@ -58,8 +63,8 @@ impl MacDBlck {
         buf.write_bits(0, 1);
 
         buf.write_bits(self.fill_bits as u8 as u64, 1);
-        buf.write_bits(self.encryption_mode as u64, 2);
-        buf.write_bits(self.event_label as u64, 10);
+        self.encryption_mode.write(buf);
+        self.event_label.write(buf);
         buf.write_bits(self.imm_napping_permission as u8 as u64, 1);
 
         if let Some(v) = &self.slot_granting_element {
@@ -83,3 +88,64 @@ impl fmt::Display for MacDBlck {
         write!(f, " }}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::umac::enums::{
+        basic_slotgrant_cap_alloc::BasicSlotgrantCapAlloc, basic_slotgrant_granting_delay::BasicSlotgrantGrantingDelay,
+    };
+
+    #[test]
+    fn test_with_slotgrant() {
+        // type 11, subtype 0, fill_bits 1, encryption_mode 00, event_label 42, imm_napping 1,
+        // slot granting 1: first subslot, at next opportunity
+        let bitstr = "110100000010101011".to_string() + "00000000";
+        let mut buf = BitBuffer::from_bitstr(&bitstr);
+        let pdu = MacDBlck::from_bitbuf(&mut buf).unwrap();
+        assert!(pdu.fill_bits);
+        assert_eq!(pdu.encryption_mode.get(), 0);
+        assert_eq!(pdu.event_label.get(), 42);
+        assert!(pdu.imm_napping_permission);
+        let grant = pdu.slot_granting_element.as_ref().unwrap();
+        assert_eq!(grant.capacity_allocation, BasicSlotgrantCapAlloc::FirstSubslotGranted);
+        assert_eq!(grant.granting_delay, BasicSlotgrantGrantingDelay::CapAllocAtNextOpportunity);
+
+        let mut out = BitBuffer::new_autoexpand(26);
+        pdu.to_bitbuf(&mut out);
+        assert_eq!(out.to_bitstr(), bitstr);
+    }
+
+    #[test]
+    fn test_without_slotgrant() {
+        // type 11, subtype 0, fill_bits 0, encryption_mode 10, event_label 1023, imm_napping 0, no slot granting
+        let pdu = MacDBlck {
+            fill_bits: false,
+            encryption_mode: FieldU2::new(2),
+            event_label: FieldU10::new(1023),
+            imm_napping_permission: false,
+            slot_granting_element: None,
+        };
+        let mut buf = BitBuffer::new_autoexpand(18);
+        pdu.to_bitbuf(&mut buf);
+        assert_eq!(buf.to_bitstr(), "110010111111111100");
+
+        buf.seek(0);
+        let parsed = MacDBlck::from_bitbuf(&mut buf).unwrap();
+        assert_eq!(parsed.encryption_mode.get(), 2);
+        assert_eq!(parsed.event_label.get(), 1023);
+        assert!(parsed.slot_granting_element.is_none());
+    }
+
+    #[test]
+    fn test_reserved_subtype() {
+        let mut buf = BitBuffer::from_bitstr("111000000000000000");
+        assert_eq!(
+            MacDBlck::from_bitbuf(&mut buf).unwrap_err(),
+            PduParseErr::InvalidValue {
+                field: "pdu_subtype",
+                value: 1,
+            }
+        );
+    }
+}
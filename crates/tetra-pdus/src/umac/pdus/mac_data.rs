@@ -1,5 +1,6 @@
 use core::fmt;
 
+use tetra_core::expect_value;
 use tetra_core::pdu_parse_error::PduParseErr;
 use tetra_core::{BitBuffer, SsiType, TetraAddress};
 
@@ -30,7 +31,8 @@ pub struct MacData {
 impl MacData {
     pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
         // required constant mac_pdu_type
-        assert!(buf.read_field(2, "mac_pdu_type")? == 0);
+        let mac_pdu_type = buf.read_field(2, "mac_pdu_type")?;
+        expect_value!(mac_pdu_type, 0)?;
         let fill_bits = buf.read_field(1, "fill_bits")? != 0;
         let encrypted = buf.read_field(1, "encrypted")? != 0;
         let addr_type = buf.read_field(2, "addr_type")? as u8;
@@ -1,7 +1,7 @@
 use core::fmt;
 
-use tetra_core::BitBuffer;
 use tetra_core::pdu_parse_error::PduParseErr;
+use tetra_core::{BitBuffer, expect_value};
 
 use crate::umac::fields::basic_slotgrant::BasicSlotgrant;
 use crate::umac::fields::channel_allocation::ChanAllocElement;
@@ -36,9 +36,11 @@ impl MacEndDl {
         };
 
         // required constant mac_pdu_type
-        assert!(buf.read_field(2, "mac_pdu_type")? == 1);
+        let mac_pdu_type = buf.read_field(2, "mac_pdu_type")?;
+        expect_value!(mac_pdu_type, 1)?;
         // required constant pdu_subtype
-        assert!(buf.read_field(1, "pdu_subtype")? == 1);
+        let pdu_subtype = buf.read_field(1, "pdu_subtype")?;
+        expect_value!(pdu_subtype, 1)?;
         s.fill_bits = buf.read_field(1, "fill_bits")? != 0;
         s.pos_of_grant = buf.read_field(1, "pos_of_grant")? as u8;
         s.length_ind = buf.read_field(6, "length_ind")? as u8;
@@ -59,7 +61,7 @@ impl MacEndDl {
 
     pub fn compute_hdr_len(has_slotgrant: bool, has_chanalloc: bool) -> usize {
         assert!(!has_chanalloc, "unimplemented");
-        2 + 1 + 1 + 1 + 6 + 1 + (if has_slotgrant { 6 } else { 0 }) + 1
+        2 + 1 + 1 + 1 + 6 + 1 + (if has_slotgrant { 8 } else { 0 }) + 1
     }
 
     pub fn to_bitbuf(&self, buf: &mut BitBuffer) {
@@ -102,3 +104,78 @@ impl fmt::Display for MacEndDl {
         write!(f, "}}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+    use crate::umac::enums::{
+        basic_slotgrant_cap_alloc::BasicSlotgrantCapAlloc, basic_slotgrant_granting_delay::BasicSlotgrantGrantingDelay,
+    };
+
+    #[test]
+    fn test_mac_end_dl() {
+        debug::setup_logging_verbose();
+        // type 01, subtype 1, fill bits 1, pos of grant 0, length 001010, no slot grant, no chan alloc
+        let mut buffer = BitBuffer::from_bitstr("0111000101000");
+        let pdu = MacEndDl::from_bitbuf(&mut buffer).unwrap();
+        tracing::info!("Parsed: {}", pdu);
+
+        assert!(buffer.get_len_remaining() == 0);
+        assert!(pdu.fill_bits);
+        assert_eq!(pdu.length_ind, 10);
+        assert!(pdu.slot_granting_element.is_none());
+        assert_eq!(MacEndDl::compute_hdr_len(false, false), buffer.get_len());
+
+        let mut new = BitBuffer::new_autoexpand(buffer.get_len());
+        pdu.to_bitbuf(&mut new);
+        assert_eq!(new.to_bitstr(), buffer.to_bitstr());
+    }
+
+    #[test]
+    fn test_mac_end_dl_with_slotgrant() {
+        debug::setup_logging_verbose();
+        // type 01, subtype 1, fill bits 0, pos of grant 1, length 100010, slot grant 0001 0000, no chan alloc
+        let mut buffer = BitBuffer::from_bitstr("011011000101000100000");
+        let pdu = MacEndDl::from_bitbuf(&mut buffer).unwrap();
+        tracing::info!("Parsed: {}", pdu);
+
+        assert!(buffer.get_len_remaining() == 0);
+        assert!(!pdu.fill_bits);
+        assert_eq!(pdu.pos_of_grant, 1);
+        assert_eq!(pdu.length_ind, 34);
+        let grant = pdu.slot_granting_element.as_ref().unwrap();
+        assert_eq!(grant.capacity_allocation, BasicSlotgrantCapAlloc::Grant1Slot);
+        assert_eq!(grant.granting_delay, BasicSlotgrantGrantingDelay::CapAllocAtNextOpportunity);
+        assert_eq!(MacEndDl::compute_hdr_len(true, false), buffer.get_len());
+
+        let mut new = BitBuffer::new_autoexpand(buffer.get_len());
+        pdu.to_bitbuf(&mut new);
+        assert_eq!(new.to_bitstr(), buffer.to_bitstr());
+    }
+
+    #[test]
+    fn test_mac_end_dl_malformed() {
+        // Header cut short within the length indication
+        let mut buffer = BitBuffer::from_bitstr("01110001");
+        assert_eq!(
+            MacEndDl::from_bitbuf(&mut buffer).unwrap_err(),
+            PduParseErr::BufferEnded {
+                field: Some("length_ind"),
+                bit_offset: 5,
+                bit_length: 6
+            }
+        );
+
+        // A MAC-FRAG is not a MAC-END
+        let mut buffer = BitBuffer::from_bitstr("0101000101000");
+        assert_eq!(
+            MacEndDl::from_bitbuf(&mut buffer).unwrap_err(),
+            PduParseErr::InvalidValue {
+                field: "pdu_subtype",
+                value: 0
+            }
+        );
+    }
+}
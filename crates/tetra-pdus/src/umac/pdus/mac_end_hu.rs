@@ -1,7 +1,7 @@
 use core::fmt;
 
-use tetra_core::BitBuffer;
 use tetra_core::pdu_parse_error::PduParseErr;
+use tetra_core::{BitBuffer, expect_value};
 
 use crate::umac::enums::reservation_requirement::ReservationRequirement;
 
@@ -22,7 +22,7 @@ impl MacEndHu {
     pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
         // required constant mac_pdu_type
         let mac_pdu_type = buf.read_field(1, "mac_pdu_type")?;
-        assert!(mac_pdu_type == 1);
+        expect_value!(mac_pdu_type, 1)?;
         let fill_bits = buf.read_field(1, "fill_bits")? != 0;
 
         let length_ind_or_cap_req = buf.read_field(1, "length_ind_or_cap_req")?;
@@ -31,7 +31,7 @@ impl MacEndHu {
             (Some(len), None)
         } else {
             let val = buf.read_field(4, "reservation_req")?;
-            let res_req = ReservationRequirement::try_from(val).map_err(|_| PduParseErr::InvalidValue {
+            let res_req = ReservationRequirement::try_from(val).map_err(|_| PduParseErr::UnsupportedValue {
                 field: "reservation_req",
                 value: val,
             })?;
@@ -21,10 +21,10 @@ impl MacEndUl {
     pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
         // required constant mac_pdu_type
         let mac_pdu_type = buf.read_field(2, "mac_pdu_type")?;
-        assert!(mac_pdu_type == 1);
+        expect_value!(mac_pdu_type, 1)?;
         // required constant pdu_subtype
         let pdu_subtype = buf.read_field(1, "pdu_subtype")?;
-        assert!(pdu_subtype == 1);
+        expect_value!(pdu_subtype, 1)?;
         let fill_bits = buf.read_field(1, "fill_bits")? != 0;
         let length_ind_cap_req = buf.read_field(6, "length_ind_cap_req")?;
         let (length_ind, reservation_req) = if length_ind_cap_req == 0 {
@@ -39,7 +39,7 @@ impl MacEndUl {
         } else {
             // 0x110000 or higher, cap req
             let val = length_ind_cap_req & 0b001111;
-            let res_req = ReservationRequirement::try_from(val).map_err(|_| PduParseErr::InvalidValue {
+            let res_req = ReservationRequirement::try_from(val).map_err(|_| PduParseErr::UnsupportedValue {
                 field: "reservation_req",
                 value: val,
             })?;
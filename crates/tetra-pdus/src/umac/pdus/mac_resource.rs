@@ -1,7 +1,9 @@
 use core::fmt;
 use std::panic;
 
-use tetra_core::{BitBuffer, SsiType, TetraAddress, pdu_parse_error::PduParseErr};
+use tetra_core::typed_pdu_fields::{FieldU2, FieldU6};
+use tetra_core::{BitBuffer, SsiType, TetraAddress, expect_value, pdu_parse_error::PduParseErr};
+use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
 
 use crate::umac::{
     enums::mac_resource_addr_type::MacResourceAddrType,
@@ -21,13 +23,13 @@ pub struct MacResource {
     pub pos_of_grant: u8,
     /// 2 bits. upper bit = encryption enabled, lower bit = cck parity
     /// Carries no meaning if Null PDU
-    pub encryption_mode: u8,
+    pub encryption_mode: FieldU2,
     /// 1 bit. If true, random access acknowledged
     /// Carries no meaning if Null PDU
     pub random_access_flag: bool,
     /// 6 bits, 0b111111 = FRAG START, 0b111110 = 2ND SLOT STOLEN
     /// May be left as 0 and updated through MacResource::update_len_and_fill_ind
-    pub length_ind: u8,
+    pub length_ind: FieldU6,
 
     /// 3 bits.
     /// If not present, this is a null PDU
@@ -57,9 +59,9 @@ impl MacResource {
         MacResource {
             fill_bits: false,
             pos_of_grant: 0,
-            encryption_mode: 0,
+            encryption_mode: FieldU2::new(0),
             random_access_flag: false,
-            length_ind: 2,
+            length_ind: FieldU6::new(2),
             addr: None,
             event_label: None,
             usage_marker: None,
@@ -69,13 +71,28 @@ impl MacResource {
         }
     }
 
+    /// Null PDU filling a whole block on the given downlink signalling channel, together with the
+    /// number of fill bits that must follow it. As observed in real-world captures (and clause 21.4.7.2),
+    /// length_ind stays at the 16-bit PDU length and fill_bits is false, yet the remainder of the block is
+    /// still padded with a 1 followed by zeroes.
+    pub fn null_pdu_for_channel(lchan: LogicalChannel) -> (Self, usize) {
+        let capacity = match lchan {
+            LogicalChannel::SchF => 268,
+            LogicalChannel::SchHd | LogicalChannel::Stch => 124,
+            _ => panic!("MAC-RESOURCE Null PDU not allowed on {:?}", lchan),
+        };
+        let pdu = Self::null_pdu();
+        let num_fill_bits = capacity - pdu.compute_header_len();
+        (pdu, num_fill_bits)
+    }
+
     pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
         let mut s = MacResource {
             fill_bits: false,
             pos_of_grant: 0,
-            encryption_mode: 0,
+            encryption_mode: FieldU2::new(0),
             random_access_flag: false,
-            length_ind: 0,
+            length_ind: FieldU6::new(0),
             addr: None,
             event_label: None,
             usage_marker: None,
@@ -85,16 +102,17 @@ impl MacResource {
         };
 
         // required constant mac_pdu_type
-        assert!(buf.read_field(2, "mac_pdu_type")? == 0);
+        let mac_pdu_type = buf.read_field(2, "mac_pdu_type")?;
+        expect_value!(mac_pdu_type, 0)?;
         s.fill_bits = buf.read_field(1, "fill_bits")? != 0;
         s.pos_of_grant = buf.read_field(1, "pos_of_grant")? as u8;
-        s.encryption_mode = buf.read_field(2, "encryption_mode")? as u8;
+        s.encryption_mode = FieldU2::read(buf, "encryption_mode")?;
         s.random_access_flag = buf.read_field(1, "random_access_flag")? != 0;
-        s.length_ind = buf.read_field(6, "length_ind")? as u8;
+        s.length_ind = FieldU6::read(buf, "length_ind")?;
 
         // Parse address type and fields
         let bits = buf.read_field(3, "addr_type")?;
-        let addr_type = MacResourceAddrType::try_from(bits).expect("invalid address type");
+        let addr_type = MacResourceAddrType::try_from(bits)?;
 
         match addr_type {
             MacResourceAddrType::NullPdu => {
@@ -102,7 +120,7 @@ impl MacResource {
                 // While this deviates from the truly received message, it may prevent a bug or two
                 s.fill_bits = false;
                 s.pos_of_grant = 0;
-                s.encryption_mode = 0;
+                s.encryption_mode = FieldU2::new(0);
                 s.random_access_flag = false;
             }
 
@@ -157,7 +175,7 @@ impl MacResource {
         }
 
         if addr_type == MacResourceAddrType::NullPdu {
-            s.encryption_mode = 0;
+            s.encryption_mode = FieldU2::new(0);
             return Ok(s);
         }
 
@@ -186,9 +204,9 @@ impl MacResource {
         buf.write_bits(0, 2);
         buf.write_bits(self.fill_bits as u8 as u64, 1);
         buf.write_bits(self.pos_of_grant as u64, 1);
-        buf.write_bits(self.encryption_mode as u64, 2);
+        self.encryption_mode.write(buf);
         buf.write_bits(self.random_access_flag as u8 as u64, 1);
-        buf.write_bits(self.length_ind as u64, 6);
+        self.length_ind.write(buf);
 
         // Derive SSI type from provided info
         let addr_type;
@@ -326,7 +344,7 @@ impl MacResource {
         let total_len_bytes = (total_len + 7) / 8;
         let num_fill_bits = (8 - (total_len % 8)) % 8;
 
-        self.length_ind = total_len_bytes as u8;
+        self.length_ind = FieldU6::new(total_len_bytes as u8);
         self.fill_bits = num_fill_bits != 0;
         num_fill_bits
     }
@@ -388,4 +406,41 @@ mod tests {
         pdu.to_bitbuf(&mut new);
         assert_eq!(new.to_bitstr(), buffer.to_bitstr());
     }
+
+    #[test]
+    fn test_mac_resource_truncated() {
+        // mac_pdu_type, fill_bits, pos_of_grant, encryption_mode, random_access_flag, then only 3 bits of length_ind
+        let mut buf = BitBuffer::from_bitstr("0000000101");
+        let err = MacResource::from_bitbuf(&mut buf).unwrap_err();
+        assert_eq!(
+            err,
+            PduParseErr::BufferEnded {
+                field: Some("length_ind"),
+                bit_offset: 7,
+                bit_length: 6
+            }
+        );
+        assert_eq!(err.to_string(), "Failed at field 'length_ind' offset=7 len=6: buffer ended");
+    }
+
+    #[test]
+    fn test_null_pdu_for_channel() {
+        for (lchan, capacity) in [(LogicalChannel::SchF, 268), (LogicalChannel::SchHd, 124)] {
+            let (pdu, num_fill_bits) = MacResource::null_pdu_for_channel(lchan);
+            assert!(pdu.is_null_pdu());
+            assert_eq!(pdu.length_ind, 2);
+            assert!(!pdu.fill_bits);
+
+            let mut buf = BitBuffer::new_autoexpand(capacity);
+            pdu.to_bitbuf(&mut buf);
+            buf.write_bit(1);
+            buf.write_zeroes(num_fill_bits - 1);
+            assert_eq!(buf.get_len(), capacity, "{:?}", lchan);
+
+            buf.seek(0);
+            let parsed = MacResource::from_bitbuf(&mut buf).unwrap();
+            assert!(parsed.is_null_pdu());
+            assert_eq!(buf.read_field(1, "fill_bit_start").unwrap(), 1);
+        }
+    }
 }
@@ -23,7 +23,8 @@ pub struct MacSync {
     pub ts_reserved_frames: u8,
     // 1
     pub u_plane_dtx: bool,
-    // 1
+    // 1, permission for the MS to use the frame 18 extension. This is a flag only;
+    // the SYNC PDU has a fixed size and carries no extension data (hyperframe is sent in MAC-SYSINFO)
     pub frame_18_ext: bool,
     // 1
     // pub reserved: bool,
@@ -86,3 +87,37 @@ impl fmt::Display for MacSync {
         write!(f, " }}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mac_sync_roundtrip() {
+        for frame_18_ext in [false, true] {
+            let pdu = MacSync {
+                system_code: 5,
+                colour_code: 33,
+                time: TdmaTime { t: 3, f: 18, m: 60, h: 0 },
+                sharing_mode: 1,
+                ts_reserved_frames: 2,
+                u_plane_dtx: true,
+                frame_18_ext,
+            };
+            let mut buf = BitBuffer::new_autoexpand(32);
+            pdu.to_bitbuf(&mut buf);
+            // SYNC PDU length does not depend on the frame 18 extension flag
+            assert_eq!(buf.get_len(), 31);
+
+            buf.seek(0);
+            let parsed = MacSync::from_bitbuf(&mut buf).expect("Failed parsing");
+            assert_eq!(parsed.system_code, pdu.system_code);
+            assert_eq!(parsed.colour_code, pdu.colour_code);
+            assert_eq!(parsed.time, pdu.time);
+            assert_eq!(parsed.sharing_mode, pdu.sharing_mode);
+            assert_eq!(parsed.ts_reserved_frames, pdu.ts_reserved_frames);
+            assert_eq!(parsed.u_plane_dtx, pdu.u_plane_dtx);
+            assert_eq!(parsed.frame_18_ext, frame_18_ext);
+        }
+    }
+}
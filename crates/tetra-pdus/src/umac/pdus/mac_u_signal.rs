@@ -1,9 +1,13 @@
 use core::fmt;
 
-use tetra_core::BitBuffer;
 use tetra_core::pdu_parse_error::PduParseErr;
+use tetra_core::{BitBuffer, expect_value};
+
+use crate::umac::enums::mac_pdu_type::MacPduType;
 
 /// Clause 21.4.5 MAC-U-SIGNAL
+/// U-plane signalling on STCH, same format on UL and DL. The TM-SDU follows the header and fills the half slot.
+/// C-plane signalling stolen from a DL traffic channel, e.g. D-TX GRANTED, uses MAC-RESOURCE instead.
 #[derive(Debug, Clone)]
 pub struct MacUSignal {
     // 1
@@ -14,7 +18,7 @@ impl MacUSignal {
     pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
         // required constant mac_pdu_type
         let mac_pdu_type = buf.read_field(2, "mac_pdu_type")?;
-        assert!(mac_pdu_type == 3);
+        expect_value!(mac_pdu_type, MacPduType::SuppMacUSignal.into_raw())?;
         let second_half_stolen = buf.read_field(1, "second_half_stolen")? != 0;
 
         Ok(MacUSignal { second_half_stolen })
@@ -32,3 +36,37 @@ impl fmt::Display for MacUSignal {
         write!(f, "mac_u_signal {{\n  second_half_stolen: {}\n}}\n", self.second_half_stolen,)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        for second_half_stolen in [false, true] {
+            let mut buf = BitBuffer::new(124);
+            MacUSignal { second_half_stolen }.to_bitbuf(&mut buf);
+            buf.write_bits(0x1_2345_6789, 36);
+            buf.seek(0);
+            assert_eq!(buf.peek_bits(3), Some(if second_half_stolen { 0b111 } else { 0b110 }));
+
+            let pdu = MacUSignal::from_bitbuf(&mut buf).unwrap();
+            assert_eq!(pdu.second_half_stolen, second_half_stolen);
+            // TM-SDU starts right after the 3-bit header
+            assert_eq!(buf.get_pos(), 3);
+            assert_eq!(buf.read_field(36, "sdu").unwrap(), 0x1_2345_6789);
+        }
+    }
+
+    #[test]
+    fn test_wrong_pdu_type() {
+        let mut buf = BitBuffer::from_bitstr("001");
+        assert_eq!(
+            MacUSignal::from_bitbuf(&mut buf).unwrap_err(),
+            PduParseErr::InvalidValue {
+                field: "mac_pdu_type",
+                value: 0,
+            }
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! Conformance runner for PDU encoders.
+//!
+//! Reads reference vectors from `vectors.json`. Each vector names a PDU type, a direction and the
+//! expected encoding as hex, MSB first, with `len_bits` giving the exact length. Every vector is
+//! decoded with `from_bitbuf`, encoded again with `to_bitbuf` and compared bit by bit against the
+//! reference. Mismatches are reported with the offending bit positions.
+
+use serde::Deserialize;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+use tetra_pdus::cmce::pdus::{d_connect::DConnect, d_setup::DSetup, d_tx_granted::DTxGranted, u_setup::USetup};
+use tetra_pdus::mle::pdus::{d_mle_sync::DMleSync, d_mle_sysinfo::DMleSysinfo};
+use tetra_pdus::umac::pdus::{access_assign::AccessAssign, mac_resource::MacResource, mac_sync::MacSync, mac_sysinfo::MacSysinfo};
+
+const VECTORS: &str = include_str!("vectors.json");
+
+#[derive(Debug, Deserialize)]
+struct Vector {
+    pdu_type: String,
+    direction: String,
+    len_bits: usize,
+    expected_hex: String,
+    #[serde(default)]
+    description: String,
+}
+
+/// Decode the PDU from `input` and encode it again into a fresh buffer
+fn reencode(pdu_type: &str, direction: &str, input: &mut BitBuffer) -> Result<BitBuffer, PduParseErr> {
+    let mut out = BitBuffer::new_autoexpand(input.get_len());
+    match (pdu_type, direction) {
+        ("MacSync", "dl") => MacSync::from_bitbuf(input)?.to_bitbuf(&mut out),
+        ("MacSysinfo", "dl") => MacSysinfo::from_bitbuf(input)?.to_bitbuf(&mut out),
+        ("MacResource", "dl") => MacResource::from_bitbuf(input)?.to_bitbuf(&mut out),
+        ("AccessAssign", "dl") => AccessAssign::from_bitbuf(input)?.to_bitbuf(&mut out),
+        ("DMleSync", "dl") => DMleSync::from_bitbuf(input)?.to_bitbuf(&mut out),
+        ("DMleSysinfo", "dl") => DMleSysinfo::from_bitbuf(input)?.to_bitbuf(&mut out),
+        ("DSetup", "dl") => DSetup::from_bitbuf(input)?.to_bitbuf(&mut out)?,
+        ("DConnect", "dl") => DConnect::from_bitbuf(input)?.to_bitbuf(&mut out)?,
+        ("DTxGranted", "dl") => DTxGranted::from_bitbuf(input)?.to_bitbuf(&mut out)?,
+        ("USetup", "ul") => USetup::from_bitbuf(input)?.to_bitbuf(&mut out)?,
+        _ => return Err(PduParseErr::NotImplemented { field: Some("pdu_type") }),
+    }
+    Ok(out)
+}
+
+/// Describe the differences between two bitstrings, listing mismatching bit positions
+fn bit_diff(expected: &str, actual: &str) -> String {
+    let mismatches: Vec<usize> = expected
+        .bytes()
+        .zip(actual.bytes())
+        .enumerate()
+        .filter(|(_, (e, a))| e != a)
+        .map(|(i, _)| i)
+        .collect();
+    let markers: String = (0..expected.len().max(actual.len()))
+        .map(|i| {
+            if mismatches.contains(&i) || i >= expected.len().min(actual.len()) {
+                '^'
+            } else {
+                ' '
+            }
+        })
+        .collect();
+    format!(
+        "expected {} bits, got {} bits, mismatching bits at {:?}\n  expected: {}\n  actual:   {}\n            {}",
+        expected.len(),
+        actual.len(),
+        mismatches,
+        expected,
+        actual,
+        markers
+    )
+}
+
+fn run_vector(v: &Vector) -> Result<(), String> {
+    let mut input = BitBuffer::from_hex_str(&v.expected_hex).map_err(|e| format!("invalid hex: {:?}", e))?;
+    if v.len_bits > input.get_len() || input.get_len() - v.len_bits >= 4 {
+        return Err(format!("len_bits {} does not match hex length {}", v.len_bits, input.get_len()));
+    }
+    input.set_raw_end(input.get_raw_start() + v.len_bits);
+    let expected = input.to_bitstr();
+
+    let out = reencode(&v.pdu_type, &v.direction, &mut input).map_err(|e| format!("decode/encode failed: {:?}", e))?;
+    if input.get_len_remaining() != 0 {
+        return Err(format!("decoder left {} bits unconsumed", input.get_len_remaining()));
+    }
+    let actual = out.to_bitstr();
+    if actual != expected {
+        return Err(bit_diff(&expected, &actual));
+    }
+    Ok(())
+}
+
+#[test]
+fn conformance_vectors() {
+    let vectors: Vec<Vector> = serde_json::from_str(VECTORS).expect("vectors.json is malformed");
+    assert!(!vectors.is_empty());
+
+    let failures: Vec<String> = vectors
+        .iter()
+        .enumerate()
+        .filter_map(|(i, v)| {
+            run_vector(v)
+                .err()
+                .map(|e| format!("#{} {} ({}) {}: {}", i, v.pdu_type, v.direction, v.description, e))
+        })
+        .collect();
+
+    assert!(
+        failures.is_empty(),
+        "{} of {} vectors failed:\n{}",
+        failures.len(),
+        vectors.len(),
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn bit_diff_marks_positions() {
+    let diff = bit_diff("10110", "10011");
+    assert!(diff.contains("[2, 4]"), "{}", diff);
+}
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use tetra_core::{ChannelClassInfo, Direction};
+
+use crate::control::enums::circuit_mode_type::CircuitModeType;
+
+/// Request for the subscribers registered at the MM. The MM replies to the sender with a RegisteredMsReply.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryRegisteredMs;
+
+/// A registered subscriber and the groups it is attached to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegisteredMs {
+    pub issi: u32,
+    pub groups: Vec<u32>,
+    /// Name configured for the ISSI in [brew.aliases], if any
+    pub alias: Option<String>,
+    /// Channel classes advised by the MS, if it sent a U-CHANNEL CLASS ADVICE
+    pub channel_class: Option<ChannelClassInfo>,
+}
+
+/// Registered subscribers, sorted by ISSI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisteredMsReply {
+    pub subscribers: Vec<RegisteredMs>,
+}
+
+/// Request for the calls active at the CMCE. The CMCE replies to the sender with an ActiveCallsReply.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryActiveCalls;
+
+/// Snapshot of an active group or individual call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActiveCallInfo {
+    pub call_id: u16,
+    /// True for a group call, false for an individual call
+    pub group: bool,
+    /// True if the call came in from the network (Brew)
+    pub network: bool,
+    /// Current speaker for a group call, calling party for an individual call
+    pub source_ssi: u32,
+    /// GSSI for a group call, called party for an individual call
+    pub dest_ssi: u32,
+    pub ts: u8,
+    pub priority: u8,
+}
+
+/// Active calls, sorted by call identifier
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveCallsReply {
+    pub calls: Vec<ActiveCallInfo>,
+}
+
+/// Request to release a call. The CMCE replies to the sender with a ReleaseCallConf.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseCallReq {
+    pub call_id: u16,
+}
+
+/// Outcome of a ReleaseCallReq. `released` is false if no call with the identifier exists.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseCallConf {
+    pub call_id: u16,
+    pub released: bool,
+}
+
+/// Request for an entity's part of the stack state. MM, CMCE, UMAC and PHY reply to the sender with a
+/// StackStateSnapshot that holds only their own part filled in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryStackState;
+
+/// An open circuit at the MAC
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CircuitInfo {
+    pub ts: u8,
+    /// Dl or Ul
+    pub direction: Direction,
+    pub usage: u8,
+    pub circuit_mode: CircuitModeType,
+}
+
+/// Bit in a MultiframeUtilization bitmap entry, set if the downlink carried traffic or signalling
+pub const UTILIZATION_DL: u8 = 0b01;
+/// Bit in a MultiframeUtilization bitmap entry, set if the uplink was reserved for traffic or signalling
+pub const UTILIZATION_UL: u8 = 0b10;
+
+/// Timeslot usage during one multiframe
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultiframeUtilization {
+    /// Multiframes since the start of hyperframe 0
+    pub multiframe: u64,
+    /// One entry per timeslot, holding UTILIZATION_DL and UTILIZATION_UL if used in any frame of the multiframe
+    pub bitmap: [u8; 4],
+}
+
+/// Uplink grant state of a timeslot at the MAC
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UlGrantInfo {
+    pub ts: u8,
+    /// SSIs with a slot grant queued for transmission on the downlink, in queue order
+    pub pending_grant_ssis: Vec<u32>,
+    /// Uplink subslots reserved for granted MSs over the scheduling window
+    pub reserved_ul_slots: usize,
+}
+
+/// Receive path counters of the PHY, counted since startup
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhyRxStats {
+    /// Slots the demodulator searched for a burst
+    pub bursts_attempted: u64,
+    /// Slots in which a training sequence was found
+    pub bursts_decoded: u64,
+    /// Slots skipped by the demodulator because its slot timing slipped
+    pub timing_errors: u64,
+    /// Slots searched without finding any training sequence
+    pub correlation_failures: u64,
+    /// Reads from the device that lost samples
+    pub buffer_overruns: u64,
+}
+
+/// Point-in-time view of the stack state. Each list is sorted by the entity that fills it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StackStateSnapshot {
+    /// Registered subscribers, filled by the MM
+    pub registered_ms: Vec<RegisteredMs>,
+    /// Active calls, filled by the CMCE
+    pub active_calls: Vec<ActiveCallInfo>,
+    /// Open circuits, filled by the UMAC
+    pub active_circuits: Vec<CircuitInfo>,
+    /// Timeslot usage over the last hyperframe, oldest first, filled by the UMAC
+    pub frame_utilization: Vec<MultiframeUtilization>,
+    /// Uplink grants per timeslot, filled by the UMAC
+    pub ul_grants: Vec<UlGrantInfo>,
+    /// Receive statistics, filled by the PHY
+    pub phy_rx_stats: Option<PhyRxStats>,
+}
+
+impl StackStateSnapshot {
+    /// Add the parts filled in by another entity
+    pub fn merge(&mut self, other: StackStateSnapshot) {
+        self.registered_ms.extend(other.registered_ms);
+        self.active_calls.extend(other.active_calls);
+        self.active_circuits.extend(other.active_circuits);
+        self.frame_utilization.extend(other.frame_utilization);
+        self.ul_grants.extend(other.ul_grants);
+        if other.phy_rx_stats.is_some() {
+            self.phy_rx_stats = other.phy_rx_stats;
+        }
+    }
+}
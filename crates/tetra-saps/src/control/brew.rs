@@ -1,4 +1,6 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BrewSubscriberAction {
     Register,
     Deregister,
@@ -6,7 +8,7 @@ pub enum BrewSubscriberAction {
     Deaffiliate,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MmSubscriberUpdate {
     pub issi: u32,
     pub groups: Vec<u32>,
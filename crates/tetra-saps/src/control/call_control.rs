@@ -1,8 +1,9 @@
+use serde::{Deserialize, Serialize};
 use tetra_core::Direction;
 
 use crate::control::enums::circuit_mode_type::CircuitModeType;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Circuit {
     /// Direction
     pub direction: Direction,
@@ -27,7 +28,7 @@ pub struct Circuit {
     pub etee_encrypted: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum CallControl {
     /// Signals to set up a circuit
     /// Created by CMCE, sent to Umac
@@ -45,6 +46,8 @@ pub enum CallControl {
         source_issi: u32,
         dest_gssi: u32,
         ts: u8,
+        /// Call priority, 15 for emergency calls
+        priority: u8,
     },
     /// Floor released: speaker stopped transmitting (entering hangtime).
     /// Sent to UMAC to enter hangtime signalling mode and to Brew to stop forwarding audio.
@@ -73,6 +76,10 @@ pub enum CallControl {
     NetworkCallEnd {
         brew_uuid: uuid::Uuid, // Identifies the call to end
     },
+    /// A group member sent U-SETUP for a group with an ongoing call on this timeslot.
+    /// Sent by CMCE to UMAC, which has the LMAC send a synchronization burst on the traffic
+    /// channel, as the late entering MS may not be synchronized to it yet.
+    LateEntry { call_id: u16, ts: u8 },
     /// UL inactivity detected on a traffic timeslot — no voice frames received
     /// for the timeout period. Sent by UMAC to CMCE.
     UlInactivityTimeout { ts: u8 },
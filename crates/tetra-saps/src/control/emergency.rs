@@ -0,0 +1,11 @@
+/// An MS initiated an emergency call (call priority 15).
+/// Sent by CMCE to Brew so the emergency can be flagged towards the network.
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CmceEmergencyCall {
+    /// Calling party
+    pub issi: u32,
+    /// Called group
+    pub gssi: u32,
+}
@@ -1,6 +1,8 @@
 /// 14.8.17a Circuit mode type
 /// Bits: 3
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum CircuitModeType {
     /// Tch/S
@@ -0,0 +1,35 @@
+use core::fmt;
+use serde::{Deserialize, Serialize};
+
+/// Request for a snapshot of the LLC counters. The LLC replies to the sender with an LlcStatsReply.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryLlcStats;
+
+/// Snapshot of the LLC counters, counted since stack start
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LlcStats {
+    /// BL-DATA / BL-ADATA PDUs handed to the MAC for the first time
+    pub bl_data_tx: u64,
+    /// BL-DATA / BL-ADATA PDUs received and passed up, after FCS check
+    pub bl_data_rx: u64,
+    /// BL-UDATA PDUs handed to the MAC
+    pub bl_udata_tx: u64,
+    /// BL-UDATA PDUs received and passed up, after FCS check
+    pub bl_udata_rx: u64,
+    /// BL-DATA / BL-ADATA retransmissions after T.251 expiry or MAC discard
+    pub retransmissions: u64,
+    /// Received PDUs discarded due to a failed FCS check
+    pub fcs_errors: u64,
+    /// Outbound BL-DATA PDUs that had to wait for a previous PDU on the same link to be acknowledged
+    pub window_stalls: u64,
+}
+
+impl fmt::Display for LlcStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "bl_data tx {} rx {}, bl_udata tx {} rx {}, retransmissions {}, fcs_errors {}, window_stalls {}",
+            self.bl_data_tx, self.bl_data_rx, self.bl_udata_tx, self.bl_udata_rx, self.retransmissions, self.fcs_errors, self.window_stalls
+        )
+    }
+}
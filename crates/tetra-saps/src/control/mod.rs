@@ -1,4 +1,8 @@
+pub mod admin;
 pub mod brew;
 pub mod call_control;
+pub mod emergency;
 pub mod enums;
+pub mod llc_stats;
 pub mod sds;
+pub mod transport_metrics;
@@ -1,7 +1,8 @@
 use crate::control::enums::sds_user_data::SdsUserData;
+use serde::{Deserialize, Serialize};
 
 /// SDS data routing between CMCE SDS subentity and Brew entity
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CmceSdsData {
     /// Source ISSI (calling party)
     pub source_issi: u32,
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// Request for the counters of an entity's network transport. The entity replies to the sender with a TransportMetricsReply.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryTransportMetrics;
+
+/// Snapshot of the counters of a network transport, counted since the transport was created
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransportMetrics {
+    /// Payload bytes handed to the network, excluding transport framing
+    pub bytes_sent: u64,
+    /// Payload bytes received from the network, excluding transport framing
+    pub bytes_received: u64,
+    pub send_errors: u64,
+    pub receive_errors: u64,
+    /// Successful connects after the first one
+    pub reconnections: u64,
+    /// Time of the last successful connect, None if never connected. Not recorded, as Instant is process-local
+    #[serde(skip)]
+    pub last_connected: Option<Instant>,
+}
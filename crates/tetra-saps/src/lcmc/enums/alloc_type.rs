@@ -1,6 +1,8 @@
 /// 14.8.17a Circuit mode type
 /// Bits: 2
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum ChanAllocType {
     Replace = 0,
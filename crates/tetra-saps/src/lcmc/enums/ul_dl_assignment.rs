@@ -1,6 +1,8 @@
 /// 21.5.2 Channel allocation
 /// Bits: 2
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum UlDlAssignment {
     Augmented = 0,
@@ -1,8 +1,9 @@
+use serde::{Deserialize, Serialize};
 use tetra_core::Todo;
 
 use crate::lcmc::enums::{alloc_type::ChanAllocType, ul_dl_assignment::UlDlAssignment};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CmceChanAllocReq {
     /// Set for new allocation, None for QuitAndGo
     pub usage: Option<u8>,
@@ -1,10 +1,12 @@
 // Clause 17.3.2 Service primitives for the LMM-SAP
 #![allow(unused)]
-use tetra_core::{BitBuffer, Layer2Service, MleHandle, TetraAddress, Todo, TxReporter};
+use core::fmt;
+use serde::{Deserialize, Serialize};
+use tetra_core::{BitBuffer, Layer2Service, MleHandle, TetraAddress, Todo, TransferResult, TxReporter};
 
 /// This shall be used as a request to initiate the selection of a cell for communications. The
 /// request shall always be made after power on and may be made at any time thereafter.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleActivateReq {
     pub mcc_list: Vec<u16>,
     pub mnc_list: Vec<u16>,
@@ -12,57 +14,57 @@ pub struct LmmMleActivateReq {
     pub cell_type_prefs: Option<Todo>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleActivateInd {
     pub cell_availability: Todo,
 }
 
 /// This shall be used as a confirmation to the MM entity that a cell has been selected with the
 /// required characteristics.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleActivateConf {
     pub registration_required: bool,
     pub la: u16,
     pub cell_type: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleActivityReq {
     pub sleep_mode: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleBusyReq {}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleCancelReq {
     pub handle: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleCloseReq {}
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleConfigureReq {
     pub periodic_reporting_timer: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleConfigureInd {
     pub periodic_reporting_timer: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleDeactivateReq {}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleDisableReq {
     pub permitted_services_in_temp_disabled_mode: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleEnableReq {}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleIdentitiesReq {
     pub issi: Todo,
     pub assi: Todo,
@@ -70,10 +72,10 @@ pub struct LmmMleIdentitiesReq {
     pub detached_gssis: Vec<Todo>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleIdleReq {}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleInfoReq {
     pub subscriber_class: Todo,
     pub scch_config: Todo,
@@ -82,13 +84,13 @@ pub struct LmmMleInfoReq {
     pub dual_watch_config: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleInfoInd {
     pub broadcast_params: Todo,
     pub subscriber_class_match: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleLinkReq {
     pub mcc: Todo,
     pub mnc: Todo,
@@ -96,7 +98,7 @@ pub struct LmmMleLinkReq {
     pub cell_type_prefs: Option<Todo>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleLinkInd {
     pub mcc: Todo,
     pub mnc: Todo,
@@ -106,10 +108,10 @@ pub struct LmmMleLinkInd {
     pub cell_type: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleOpen {}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMlePrepareReq {
     pub sdu: Todo,
     pub handle: Todo,
@@ -119,19 +121,19 @@ pub struct LmmMlePrepareReq {
     pub stealing_repeats_flag: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMlePrepareConfirm {
     pub sdu: Todo,
     pub handle: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleReportInd {
     pub handle: MleHandle,
-    pub transfer_result: Todo,
+    pub transfer_result: TransferResult,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleUnitdataReq {
     pub sdu: BitBuffer,
     pub handle: MleHandle,
@@ -143,10 +145,11 @@ pub struct LmmMleUnitdataReq {
     pub stealing_repeats_flag: bool,
     pub encryption_flag: bool,
     pub is_null_pdu: bool, // Prio should be lowest and may not steal
+    #[serde(skip)]
     pub tx_reporter: Option<TxReporter>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleUnitdataInd {
     pub sdu: BitBuffer,
     pub handle: MleHandle,
@@ -154,7 +157,7 @@ pub struct LmmMleUnitdataInd {
     // pub received_address_type: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LmmMleUpdateReq {
     pub mcc: Todo,
     pub mnc: Todo,
@@ -162,3 +165,54 @@ pub struct LmmMleUpdateReq {
     pub cell_type_prefs: Option<Todo>,
     pub registration_result: Todo,
 }
+
+impl fmt::Display for LmmMleUnitdataReq {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "LMM-UNITDATA req {} handle {} sdu {} bits",
+            self.address,
+            self.handle,
+            self.sdu.get_len()
+        )?;
+        if self.is_null_pdu {
+            write!(f, " null_pdu")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for LmmMleUnitdataInd {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "LMM-UNITDATA ind {} handle {} sdu {} bits",
+            self.received_address,
+            self.handle,
+            self.sdu.get_len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::SsiType;
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let req = LmmMleUnitdataReq {
+            sdu: BitBuffer::new(32),
+            handle: 4,
+            address: TetraAddress::new(1000002, SsiType::Issi),
+            layer2service: Layer2Service::Unacknowledged,
+            stealing_permission: false,
+            stealing_repeats_flag: false,
+            encryption_flag: false,
+            is_null_pdu: false,
+            tx_reporter: None,
+        };
+        assert_eq!(req.to_string(), "LMM-UNITDATA req ISSI:1000002 handle 4 sdu 32 bits");
+    }
+}
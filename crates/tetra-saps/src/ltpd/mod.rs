@@ -1,28 +1,29 @@
 // Clause 17.3.5 Service state diagram for the LTPD-SAP (MLE-SNDCP)
 
 #![allow(unused)]
+use serde::{Deserialize, Serialize};
 use tetra_core::{BitBuffer, EndpointId, Layer2Service, LinkId, TetraAddress, Todo};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleActivityReq {
     pub sleep_mode: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleBreakInd {}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleBusyInd {}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleCancelReq {
     pub handle: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleCloseInd {}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleConfigureReq {
     pub chan_change_accepted: bool,
     pub chan_change_handle: Todo,
@@ -38,7 +39,7 @@ pub struct LtpdMleConfigureReq {
     pub sndcp_status: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleConfigureInd {
     pub endpoint_id: EndpointId,
     pub chan_change_responce_required: bool,
@@ -47,7 +48,7 @@ pub struct LtpdMleConfigureInd {
     pub conflicting_endpoint_id: EndpointId,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleConnectReq {
     pub address: Todo,
     pub endpoint_id: EndpointId,
@@ -59,7 +60,7 @@ pub struct LtpdMleConnectReq {
     pub setup_report: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleConnectInd {
     pub address: Todo,
     pub endpoint_id: EndpointId,
@@ -72,7 +73,7 @@ pub struct LtpdMleConnectInd {
     pub setup_report: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleConnectResp {
     pub address: Todo,
     pub endpoint_id: EndpointId,
@@ -84,7 +85,7 @@ pub struct LtpdMleConnectResp {
     pub setup_report: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleConnectConfirm {
     pub address: Todo,
     pub endpoint_id: EndpointId,
@@ -96,12 +97,12 @@ pub struct LtpdMleConnectConfirm {
     pub setup_report: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleDisableInd {
     pub permitted_services_in_temp_disabled_mode: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleDisconnectReq {
     pub endpoint_id: EndpointId,
     pub link_id: LinkId,
@@ -110,7 +111,7 @@ pub struct LtpdMleDisconnectReq {
     pub report: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleDisconnectInd {
     pub endpoint_id: EndpointId,
     pub new_endpoint_id: EndpointId,
@@ -121,10 +122,10 @@ pub struct LtpdMleDisconnectInd {
     pub report: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleEnableInd {}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleInfoInd {
     pub broadcast_params: Todo,
     pub subscriber_class_match: Todo,
@@ -132,23 +133,23 @@ pub struct LtpdMleInfoInd {
     pub permitted_cell_info: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleIdleInd {}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleOpenInd {
     pub mcc: Todo, // Current network
     pub mnc: Todo, // Current network
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleReceiveInd {
     pub endpoint_id: EndpointId,
     pub received_tetra_address: Todo, // ITSI/GSSI
     pub received_address_type: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleReconnectReq {
     pub endpoint_id: EndpointId,
     pub link_id: LinkId,
@@ -158,7 +159,7 @@ pub struct LtpdMleReconnectReq {
     pub stealing_permission: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleReconnectConfirm {
     pub endpoint_id: EndpointId,
     pub new_endpoint_id: EndpointId,
@@ -168,7 +169,7 @@ pub struct LtpdMleReconnectConfirm {
     pub reconnection_result: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleReconnectInd {
     pub endpoint_id: EndpointId,
     pub new_endpoint_id: EndpointId,
@@ -178,24 +179,24 @@ pub struct LtpdMleReconnectInd {
     pub reconnection_result: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleReleaseReq {
     pub link_id: LinkId,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleReportInd {
     pub handle: Todo,
     pub transfer_result: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleResumeInd {
     pub mcc: Todo, // Current network
     pub mnc: Todo, // Current network
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleUnitdataReq {
     pub sdu: Todo,
     pub handle: Todo,
@@ -216,7 +217,7 @@ pub struct LtpdMleUnitdataReq {
     pub fcs_flag: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LtpdMleUnitdataInd {
     pub sdu: BitBuffer,
     pub endpoint_id: EndpointId,
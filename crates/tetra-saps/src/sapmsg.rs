@@ -1,11 +1,20 @@
 use core::fmt::Display;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
 
 use tetra_core::Sap;
 use tetra_core::tetra_entities::TetraEntity;
 
+use crate::control::admin::{
+    ActiveCallsReply, QueryActiveCalls, QueryRegisteredMs, QueryStackState, RegisteredMsReply, ReleaseCallConf, ReleaseCallReq,
+    StackStateSnapshot,
+};
 use crate::control::brew::MmSubscriberUpdate;
 use crate::control::call_control::CallControl;
+use crate::control::emergency::CmceEmergencyCall;
+use crate::control::llc_stats::{LlcStats, QueryLlcStats};
 use crate::control::sds::CmceSdsData;
+use crate::control::transport_metrics::{QueryTransportMetrics, TransportMetrics};
 use crate::tmd::TmdCircuitDataInd;
 use crate::tmd::TmdCircuitDataReq;
 use crate::tnmm::TnmmTestDemand;
@@ -23,7 +32,7 @@ use super::tp::*;
 
 /// Exhaustive list of SapMsgType structs for use in the SapMsg struct
 /// See Clause 19.2.1 for an overview of all lower-layer SAPs
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SapMsgInner {
     // TODO FIXME and all that stuff
     // PhyControlUpdateNetinfo(PhyControlUpdateNetinfo),
@@ -50,6 +59,7 @@ pub enum SapMsgInner {
 
     // TMC-SAP
     TlmcConfigureReq(TlmcConfigureReq),
+    TlmcConfigureConf(TlmcConfigureConf),
 
     // TMD-SAP (Uplane traffic and signalling)
     TmdCircuitDataReq(TmdCircuitDataReq),
@@ -60,6 +70,10 @@ pub enum SapMsgInner {
     // TlmbSysinfoInd(TlmbSysinfoInd),
 
     // TLA-SAP
+    TlaTlConnectReq(TlaTlConnectReq),
+    TlaTlConnectInd(TlaTlConnectInd),
+    TlaTlConnectResp(TlaTlConnectResp),
+    TlaTlConnectConf(TlaTlConnectConf),
     TlaTlDataIndBl(TlaTlDataIndBl),
     TlaTlDataReqBl(TlaTlDataReqBl),
     TlaTlReportInd(TlaTlReportInd),
@@ -69,20 +83,43 @@ pub enum SapMsgInner {
     // LMM-SAP (MLE-MM)
     LmmMleUnitdataInd(LmmMleUnitdataInd),
     LmmMleUnitdataReq(LmmMleUnitdataReq),
+    LmmMleReportInd(LmmMleReportInd),
 
     // LCMC-SAP (MLE-CMCE)
     LcmcMleUnitdataInd(LcmcMleUnitdataInd),
     LcmcMleUnitdataReq(LcmcMleUnitdataReq),
+    LcmcMleReportInd(LcmcMleReportInd),
 
     // CMCE -> UMAC control
     CmceCallControl(CallControl),
 
+    // CMCE -> Brew emergency call notification
+    CmceEmergencyCall(CmceEmergencyCall),
+
     // MM -> Brew/CMCE subscriber update
     MmSubscriberUpdate(MmSubscriberUpdate),
 
     // CMCE SDS <-> Brew SDS routing
     CmceSdsData(CmceSdsData),
 
+    // LLC statistics query and reply
+    QueryLlcStats(QueryLlcStats),
+    LlcStatsReply(LlcStats),
+
+    // Admin interface queries and replies
+    QueryRegisteredMs(QueryRegisteredMs),
+    RegisteredMsReply(RegisteredMsReply),
+    QueryActiveCalls(QueryActiveCalls),
+    ActiveCallsReply(ActiveCallsReply),
+    ReleaseCallReq(ReleaseCallReq),
+    ReleaseCallConf(ReleaseCallConf),
+    QueryStackState(QueryStackState),
+    StackStateSnapshot(StackStateSnapshot),
+
+    // Network transport counters query and reply
+    QueryTransportMetrics(QueryTransportMetrics),
+    TransportMetricsReply(TransportMetrics),
+
     // LTPD-SAP (MLE-LTPD)
     LtpdMleUnitdataInd(LtpdMleUnitdataInd),
 
@@ -95,7 +132,8 @@ impl Display for SapMsgInner {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             // TP-SAP
-            // SapMsgInner::TpUnitdataInd(_) => write!(f, "TpUnitdataInd"),
+            SapMsgInner::TpUnitdataInd(_) => write!(f, "TpUnitdataInd"),
+            SapMsgInner::TpUnitdataReq(_) => write!(f, "TpUnitdataReq"),
 
             // TMV-SAP
             SapMsgInner::TmvUnitdataReq(_) => write!(f, "TmvUnitdataReq"),
@@ -106,33 +144,115 @@ impl Display for SapMsgInner {
             // TMA-SAP
             SapMsgInner::TmaUnitdataInd(_) => write!(f, "TmaUnitdataInd"),
             SapMsgInner::TmaUnitdataReq(_) => write!(f, "TmaUnitdataReq"),
+            SapMsgInner::TmaReportInd(_) => write!(f, "TmaReportInd"),
 
             // TMB-SAP
             SapMsgInner::TlmbSyncInd(_) => write!(f, "TmbSyncInd"),
             SapMsgInner::TlmbSysinfoInd(_) => write!(f, "TmbSysinfoInd"),
 
-            // Control/Brew
+            // TMC-SAP
+            SapMsgInner::TlmcConfigureReq(_) => write!(f, "TlmcConfigureReq"),
+            SapMsgInner::TlmcConfigureConf(_) => write!(f, "TlmcConfigureConf"),
+
+            // TMD-SAP (Uplane traffic and signalling)
+            SapMsgInner::TmdCircuitDataReq(_) => write!(f, "TmdCircuitDataReq"),
+            SapMsgInner::TmdCircuitDataInd(_) => write!(f, "TmdCircuitDataInd"),
+
+            // TLA-SAP
+            SapMsgInner::TlaTlConnectReq(_) => write!(f, "TlaTlConnectReq"),
+            SapMsgInner::TlaTlConnectInd(_) => write!(f, "TlaTlConnectInd"),
+            SapMsgInner::TlaTlConnectResp(_) => write!(f, "TlaTlConnectResp"),
+            SapMsgInner::TlaTlConnectConf(_) => write!(f, "TlaTlConnectConf"),
+            SapMsgInner::TlaTlDataIndBl(_) => write!(f, "TlaTlDataIndBl"),
+            SapMsgInner::TlaTlDataReqBl(_) => write!(f, "TlaTlDataReqBl"),
+            SapMsgInner::TlaTlReportInd(_) => write!(f, "TlaTlReportInd"),
+            SapMsgInner::TlaTlUnitdataIndBl(_) => write!(f, "TlaTlUnitdataIndBl"),
+            SapMsgInner::TlaTlUnitdataReqBl(_) => write!(f, "TlaTlUnitdataReqBl"),
+
+            // LMM-SAP (MLE-MM)
+            SapMsgInner::LmmMleUnitdataInd(_) => write!(f, "LmmMleUnitdataInd"),
+            SapMsgInner::LmmMleUnitdataReq(_) => write!(f, "LmmMleUnitdataReq"),
+            SapMsgInner::LmmMleReportInd(_) => write!(f, "LmmMleReportInd"),
+
+            // LCMC-SAP (MLE-CMCE)
+            SapMsgInner::LcmcMleUnitdataInd(_) => write!(f, "LcmcMleUnitdataInd"),
+            SapMsgInner::LcmcMleUnitdataReq(_) => write!(f, "LcmcMleUnitdataReq"),
+            SapMsgInner::LcmcMleReportInd(_) => write!(f, "LcmcMleReportInd"),
+
+            // CMCE -> UMAC control
+            SapMsgInner::CmceCallControl(_) => write!(f, "CmceCallControl"),
+
+            // CMCE -> Brew emergency call notification
+            SapMsgInner::CmceEmergencyCall(_) => write!(f, "CmceEmergencyCall"),
+
+            // MM -> Brew/CMCE subscriber update
             SapMsgInner::MmSubscriberUpdate(_) => write!(f, "MmSubscriberUpdate"),
 
-            // TLB-SAP
-            // SapMsgInner::TlbTlSyncInd(_) => write!(f, "TlbTlSyncInd"),
-            // SapMsgInner::TlbTlSysinfoInd(_) => write!(f, "TlbTlSysinfoInd"),
-            _ => panic!("Unknown SapMsgInner type"),
+            // CMCE SDS <-> Brew SDS routing
+            SapMsgInner::CmceSdsData(_) => write!(f, "CmceSdsData"),
+
+            // LLC statistics query and reply
+            SapMsgInner::QueryLlcStats(_) => write!(f, "QueryLlcStats"),
+            SapMsgInner::LlcStatsReply(_) => write!(f, "LlcStatsReply"),
+
+            // Admin interface queries and replies
+            SapMsgInner::QueryRegisteredMs(_) => write!(f, "QueryRegisteredMs"),
+            SapMsgInner::RegisteredMsReply(_) => write!(f, "RegisteredMsReply"),
+            SapMsgInner::QueryActiveCalls(_) => write!(f, "QueryActiveCalls"),
+            SapMsgInner::ActiveCallsReply(_) => write!(f, "ActiveCallsReply"),
+            SapMsgInner::ReleaseCallReq(_) => write!(f, "ReleaseCallReq"),
+            SapMsgInner::ReleaseCallConf(_) => write!(f, "ReleaseCallConf"),
+            SapMsgInner::QueryStackState(_) => write!(f, "QueryStackState"),
+            SapMsgInner::StackStateSnapshot(_) => write!(f, "StackStateSnapshot"),
+
+            // Network transport counters query and reply
+            SapMsgInner::QueryTransportMetrics(_) => write!(f, "QueryTransportMetrics"),
+            SapMsgInner::TransportMetricsReply(_) => write!(f, "TransportMetricsReply"),
+
+            // LTPD-SAP (MLE-LTPD)
+            SapMsgInner::LtpdMleUnitdataInd(_) => write!(f, "LtpdMleUnitdataInd"),
+
+            // TNMM-SAP (MM-User)
+            SapMsgInner::TnmmTestDemand(_) => write!(f, "TnmmTestDemand"),
+            SapMsgInner::TnmmTestResponse(_) => write!(f, "TnmmTestResponse"),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SapMsg {
     pub sap: Sap,
     pub src: TetraEntity,
     pub dest: TetraEntity,
+    /// Wall-clock creation time, used to measure routing latency. Not recorded, deserialized messages get the
+    /// time they were read.
+    #[serde(skip, default = "Instant::now")]
+    pub created_at: Instant,
+    /// Call the message belongs to, if any. Set by CMCE on call signalling and carried along by the lower
+    /// layers, so their events can be correlated with the call span.
+    #[serde(default)]
+    pub call_id: Option<u16>,
     pub msg: SapMsgInner,
 }
 
 impl SapMsg {
+    /// Create a message stamped with the current time and not tagged with a call, see `with_call_id`
     pub fn new(sap: Sap, src: TetraEntity, dest: TetraEntity, msg: SapMsgInner) -> Self {
-        Self { sap, src, dest, msg }
+        Self {
+            sap,
+            src,
+            dest,
+            created_at: Instant::now(),
+            call_id: None,
+            msg,
+        }
+    }
+
+    /// Tag this message as belonging to the given call. Also takes an `Option`, so an id can be carried over
+    /// from the message being handled.
+    pub fn with_call_id(mut self, call_id: impl Into<Option<u16>>) -> Self {
+        self.call_id = call_id.into();
+        self
     }
 
     pub fn get_source(&self) -> &TetraEntity {
@@ -151,3 +271,14 @@ impl SapMsg {
     //     &self.subprim
     // }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(SapMsgInner::QueryLlcStats(QueryLlcStats).to_string(), "QueryLlcStats");
+        assert_eq!(SapMsgInner::QueryStackState(QueryStackState).to_string(), "QueryStackState");
+    }
+}
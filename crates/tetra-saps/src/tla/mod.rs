@@ -1,98 +1,111 @@
 #![allow(unused)]
-use tetra_core::{BitBuffer, EndpointId, LinkId, TetraAddress, Todo, TxReporter};
+use core::fmt;
+use serde::{Deserialize, Serialize};
+use tetra_core::{BitBuffer, EndpointId, LinkId, TetraAddress, Todo, TransferResult, TxReporter};
 
 use crate::lcmc::fields::chan_alloc_req::CmceChanAllocReq;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlCancelReq {
     pub handle: Todo,
 }
 
-/// advanced link
-#[derive(Debug, Clone)]
-pub struct TlConnectReq {
-    // address_type: Todo,
-    main_address: Todo,
-    scrambling_code: Todo,
-    link_id: LinkId,
-    endpoint_id: EndpointId,
-    pdu_prio: Todo,
-    stealing_permission: bool,
-    subscriber_class: Todo,
-    qos: Todo,
-    al_service: Todo,
-    air_interface_encryption: Todo,
-    req_handle: Todo,
-    setup_report: Todo,
+/// Clause 20.3.5.1.2
+/// TL-CONNECT request: this primitive shall be used by the layer 2 service user to initiate the set-up of an
+/// advanced link, or to reset an existing one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlaTlConnectReq {
+    // pub address_type: Todo,
+    pub main_address: TetraAddress,
+    pub scrambling_code: Todo,
+    pub link_id: LinkId,
+    pub endpoint_id: EndpointId,
+    pub pdu_prio: Todo,
+    pub stealing_permission: bool,
+    pub subscriber_class: Todo,
+    pub qos: Todo,
+    pub al_service: Todo,
+    pub air_interface_encryption: Todo,
+    pub req_handle: Todo,
+    pub setup_report: Todo,
 }
-/// advanced link
-#[derive(Debug, Clone)]
-pub struct TlConnectInd {
-    // address_type: Todo,
-    main_address: Todo,
-    scrambling_code: Todo,
-    link_id: LinkId,
-    endpoint_id: EndpointId,
-    new_endpoint_id: Option<Todo>,
-    css_endpoint_id: Option<Todo>,
-    qos: Todo,
-    al_service: Todo,
-    air_interface_encryption: Todo,
-    chan_change_resp_req: bool,
-    chan_change_handle: Option<Todo>,
-    chan_info: Option<Todo>,
-    req_handle: Todo,
-    setup_report: Todo,
+
+/// Clause 20.3.5.1.2
+/// TL-CONNECT indication: this primitive shall be used by the layer 2 to inform the layer 2 service user that the
+/// peer entity requested the set-up or reset of an advanced link.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlaTlConnectInd {
+    // pub address_type: Todo,
+    pub main_address: TetraAddress,
+    pub scrambling_code: Todo,
+    pub link_id: LinkId,
+    pub endpoint_id: EndpointId,
+    pub new_endpoint_id: Option<Todo>,
+    pub css_endpoint_id: Option<Todo>,
+    pub qos: Todo,
+    pub al_service: Todo,
+    pub air_interface_encryption: Todo,
+    pub chan_change_resp_req: bool,
+    pub chan_change_handle: Option<Todo>,
+    pub chan_info: Option<Todo>,
+    pub req_handle: Todo,
+    pub setup_report: Todo,
 }
-/// advanced link
-#[derive(Debug, Clone)]
-pub struct TlConnectResp {
-    // address_type: Todo,
-    main_address: Todo,
-    scrambling_code: Todo,
-    link_id: LinkId,
-    endpoint_id: EndpointId,
-    pdu_prio: Todo,
-    stealing_permission: bool,
-    subscriber_class: Todo,
-    qos: Todo,
-    al_service: Todo,
-    air_interface_encryption: Todo,
-    req_handle: Todo,
-    setup_report: Todo,
+
+/// Clause 20.3.5.1.2
+/// TL-CONNECT response: this primitive shall be used by the layer 2 service user to accept the advanced link
+/// set-up requested by the peer entity.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlaTlConnectResp {
+    // pub address_type: Todo,
+    pub main_address: TetraAddress,
+    pub scrambling_code: Todo,
+    pub link_id: LinkId,
+    pub endpoint_id: EndpointId,
+    pub pdu_prio: Todo,
+    pub stealing_permission: bool,
+    pub subscriber_class: Todo,
+    pub qos: Todo,
+    pub al_service: Todo,
+    pub air_interface_encryption: Todo,
+    pub req_handle: Todo,
+    pub setup_report: Todo,
 }
-/// advanced link
-#[derive(Debug, Clone)]
-pub struct TlConnectConf {
-    // address_type: Todo,
-    main_address: Todo,
-    scrambling_code: Todo,
-    link_id: LinkId,
-    endpoint_id: EndpointId,
-    new_endpoint_id: Option<Todo>,
-    css_endpoint_id: Option<Todo>,
-    qos: Todo,
-    al_service: Todo,
-    air_interface_encryption: Todo,
-    chan_change_resp_req: bool,
-    chan_change_handle: Option<Todo>,
-    chan_info: Option<Todo>,
-    req_handle: Todo,
-    setup_report: Todo,
+
+/// Clause 20.3.5.1.2
+/// TL-CONNECT confirm: this primitive shall be used by the layer 2 to inform the layer 2 service user that the
+/// peer entity accepted the requested advanced link set-up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlaTlConnectConf {
+    // pub address_type: Todo,
+    pub main_address: TetraAddress,
+    pub scrambling_code: Todo,
+    pub link_id: LinkId,
+    pub endpoint_id: EndpointId,
+    pub new_endpoint_id: Option<Todo>,
+    pub css_endpoint_id: Option<Todo>,
+    pub qos: Todo,
+    pub al_service: Todo,
+    pub air_interface_encryption: Todo,
+    pub chan_change_resp_req: bool,
+    pub chan_change_handle: Option<Todo>,
+    pub chan_info: Option<Todo>,
+    pub req_handle: Todo,
+    pub setup_report: Todo,
 }
 
 /// advanced link only
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlDataReqAl;
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlDataIndAl;
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlDataConfAl;
 
 /// Clause 20.3.5.1.4
 /// TL-DATA request: this primitive shall be used by the layer 2 service user to request transmission of a TL-SDU. The
 // TL-SDU will be acknowledged by the peer entity.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlaTlDataReqBl {
     // pub address_type: Todo,
     pub main_address: TetraAddress,
@@ -115,13 +128,14 @@ pub struct TlaTlDataReqBl {
     pub chan_alloc: Option<CmceChanAllocReq>,
 
     /// Optional TxReporter that may be included to track transmission and optionally, acknowledgement
+    #[serde(skip)]
     pub tx_reporter: Option<TxReporter>,
 }
 
 /// Clause 20.3.5.1.4
 /// TL-DATA indication: this primitive shall be used by the layer 2 to deliver the received TL-SDU to the layer 2 service
 // user.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlaTlDataIndBl {
     // pub address_type: Todo,
     pub main_address: TetraAddress,
@@ -143,7 +157,7 @@ pub struct TlaTlDataIndBl {
 /// TL-DATA response: this primitive shall be used by the layer 2 service user to respond to the previous TL-DATA
 // indication primitive. The TL-DATA response primitive may contain a TL-SDU. That TL-SDU will be sent without an
 // explicit acknowledgement from the peer entity.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlDataRespBl {
     // pub address_type: Todo,
     pub main_address: TetraAddress,
@@ -165,7 +179,7 @@ pub struct TlDataRespBl {
 // TL-DATA confirm: this primitive shall be used by the layer 2 to inform the layer 2 service user that it has completed
 // successfully the transmission of the requested TL-SDU. Depending on the availability of the response primitive at the
 // peer entity before transmission of the acknowledgement, the confirm primitive may or may not carry a TL-SDU.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlDataConfBl {
     // pub address_type: Todo,
     pub main_address: TetraAddress,
@@ -185,27 +199,27 @@ pub struct TlDataConfBl {
 }
 
 /// Advanced link only
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlDisconnectReq;
 /// Advanced link only
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlDisconnectInd;
 /// Advanced link only
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlDisconnectConf;
 
 /// advanced link, BS only
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlReceiveInd;
 
 // advanced link
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlReleaseReq {
     // pub address_type: Todo,
     pub main_address: TetraAddress,
     pub link_id: LinkId,
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlReleaseInd {
     // pub address_type: Todo,
     pub main_address: TetraAddress,
@@ -214,10 +228,10 @@ pub struct TlReleaseInd {
 }
 
 /// advanced link
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlReconnectReq;
 /// advanced link
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlReconnectResp;
 
 // pub enum TlaReport {
@@ -226,10 +240,10 @@ pub struct TlReconnectResp;
 
 // }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlaTlReportInd {
     pub req_handle: Option<Todo>,
-    pub report: Todo,
+    pub report: TransferResult,
     pub chan_change_resp_req: Option<bool>,
     pub chan_change_handle: Option<Todo>,
     pub chan_info: Option<Todo>,
@@ -239,7 +253,7 @@ pub struct TlaTlReportInd {
 /// Clause 20.3.5.1.9
 /// TL-UNITDATA request: this primitive shall be used in the unacknowledged data transfer service by the layer 2
 /// service user to request layer 2 to transmit a TL-SDU.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlaTlUnitdataReqBl {
     // pub address_type: Todo,
     pub main_address: TetraAddress,
@@ -265,13 +279,14 @@ pub struct TlaTlUnitdataReqBl {
     pub chan_alloc: Option<CmceChanAllocReq>,
 
     /// Optional TxReporter that may be included to track transmission and optionally, acknowledgement
+    #[serde(skip)]
     pub tx_reporter: Option<TxReporter>,
 }
 
 /// Clause 20.3.5.1.9
 /// TL-UNITDATA indication: this primitive shall be used in the unacknowledged data transfer service to deliver
 /// the received TL-SDU to the layer 2 service user.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlaTlUnitdataIndBl {
     // pub address_type: Todo,
     pub main_address: TetraAddress,
@@ -292,7 +307,7 @@ pub struct TlaTlUnitdataIndBl {
 /// Clause 20.3.5.1.9, optional
 /// TL-UNITDATA confirm: this primitive may be used in the unacknowledged data transfer service to indicate
 /// completion of sending of the requested TL-SDU.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlUnitdataConfBl {
     // pub address_type: Todo,
     pub main_address: TetraAddress,
@@ -303,11 +318,128 @@ pub struct TlUnitdataConfBl {
 }
 
 /// Advanced link
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlUnitdataReqAl;
 /// Advanced link
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlUnitdataIndAl;
 /// Advanced link, optional?
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlUnitdataConfAl;
+
+impl fmt::Display for TlaTlDataReqBl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "TL-DATA req {} link {} ep {} sdu {} bits",
+            self.main_address,
+            self.link_id,
+            self.endpoint_id,
+            self.tl_sdu.get_len()
+        )?;
+        if self.fcs_flag {
+            write!(f, " fcs")?;
+        }
+        if self.chan_alloc.is_some() {
+            write!(f, " chan_alloc")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TlaTlDataIndBl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TL-DATA ind {} link {} ep {}", self.main_address, self.link_id, self.endpoint_id)?;
+        fmt_opt_sdu(f, "sdu", &self.tl_sdu)
+    }
+}
+
+impl fmt::Display for TlaTlUnitdataReqBl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "TL-UNITDATA req {} link {} ep {} sdu {} bits",
+            self.main_address,
+            self.link_id,
+            self.endpoint_id,
+            self.tl_sdu.get_len()
+        )?;
+        if self.fcs_flag {
+            write!(f, " fcs")?;
+        }
+        if self.n_tlsdu_repeats > 0 {
+            write!(f, " repeats {}", self.n_tlsdu_repeats)?;
+        }
+        if self.chan_alloc.is_some() {
+            write!(f, " chan_alloc")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TlaTlUnitdataIndBl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "TL-UNITDATA ind {} link {} ep {}",
+            self.main_address, self.link_id, self.endpoint_id
+        )?;
+        fmt_opt_sdu(f, "sdu", &self.tl_sdu)
+    }
+}
+
+fn fmt_opt_sdu(f: &mut fmt::Formatter, name: &str, sdu: &Option<BitBuffer>) -> fmt::Result {
+    match sdu {
+        Some(sdu) => write!(f, " {} {} bits", name, sdu.get_len()),
+        None => write!(f, " no {}", name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::SsiType;
+
+    use super::*;
+
+    fn addr() -> TetraAddress {
+        TetraAddress::new(1000001, SsiType::Issi)
+    }
+
+    #[test]
+    fn test_display() {
+        let req = TlaTlDataReqBl {
+            main_address: addr(),
+            link_id: 3,
+            endpoint_id: 1,
+            tl_sdu: BitBuffer::new(40),
+            stealing_permission: false,
+            subscriber_class: 0,
+            fcs_flag: true,
+            air_interface_encryption: None,
+            stealing_repeats_flag: None,
+            data_class_info: None,
+            req_handle: 0,
+            graceful_degradation: None,
+            chan_alloc: None,
+            tx_reporter: None,
+        };
+        assert_eq!(req.to_string(), "TL-DATA req ISSI:1000001 link 3 ep 1 sdu 40 bits fcs");
+
+        let ind = TlaTlUnitdataIndBl {
+            main_address: addr(),
+            link_id: 0,
+            endpoint_id: 2,
+            new_endpoint_id: None,
+            css_endpoint_id: None,
+            tl_sdu: None,
+            scrambling_code: 0,
+            fcs_flag: false,
+            air_interface_encryption: 0,
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+            chan_info: None,
+            report: None,
+        };
+        assert_eq!(ind.to_string(), "TL-UNITDATA ind ISSI:1000001 link 0 ep 2 no sdu");
+    }
+}
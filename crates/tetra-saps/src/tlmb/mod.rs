@@ -1,8 +1,9 @@
+use serde::{Deserialize, Serialize};
 use tetra_core::{BitBuffer, EndpointId, Todo};
 
 /// BS only
 /// TL-SAP and TMB-SAP merged into TLMB-SAP
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlmbSyncReq {
     pub endpoint_id: EndpointId,
     pub tl_sdu: BitBuffer,
@@ -11,7 +12,7 @@ pub struct TlmbSyncReq {
 
 /// MS only
 /// TL-SAP and TMB-SAP merged into TLMB-SAP
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlmbSyncInd {
     pub endpoint_id: EndpointId,
     pub tl_sdu: BitBuffer,
@@ -19,7 +20,7 @@ pub struct TlmbSyncInd {
 
 /// BS only
 /// TL-SAP and TMB-SAP merged into TLMB-SAP
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlmbSysinfoReq {
     pub endpoint_id: EndpointId,
     pub tl_sdu: BitBuffer,
@@ -29,7 +30,7 @@ pub struct TlmbSysinfoReq {
 
 /// MS only
 /// TL-SAP and TMB-SAP merged into TLMB-SAP
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlmbSysinfoInd {
     pub endpoint_id: EndpointId,
     pub tl_sdu: BitBuffer,
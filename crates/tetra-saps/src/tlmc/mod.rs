@@ -1,26 +1,27 @@
+use serde::{Deserialize, Serialize};
 use tetra_core::{EndpointId, Todo};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlmcAssessmentInd;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlmcAssessmentListReq;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlmcCellReadInd;
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlmcCellReadConf;
 
 /// Clause 20.4.3 and 20.3.5.4.1c
 /// TMC-CONFIGURE indication: MAY BE USED BY LLC. this primitive shall be used to indicate loss of lower layer
 /// resources. It may be used to indicate regain of lower layer resources.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlmcConfigureInd {
     pub endpoint_id: EndpointId,
     pub lower_layer_resource_availability: Todo,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// Poorly documented, but used in TL-CONFIGURE. Signals which addresses are valid,
 /// including full MCC/MNC.
 pub struct TlmcValidAddress {
@@ -32,14 +33,16 @@ pub struct TlmcValidAddress {
 /// TL-CONFIGURE request, confirm: this primitive shall be used to set up and configure the layer 2 according to the
 /// chosen cell parameters and the current state of the MS. It may also be used to provide the LLC and MAC with
 /// retransmission strategy in graceful service degradation mode. The parameters shall be as defined in table 20.36.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TlmcConfigureReq {
     pub threshold_values: Option<Todo>,
     pub distribution_on_18th_frame: Option<Todo>,
     pub scch_information: Option<Todo>,
-    pub energy_economy_group: Option<Todo>,
-    pub energy_economy_startpoint: Option<Todo>,
-    pub dual_watch_energy_economy_group: Option<Todo>,
+    /// Energy economy group EG1-EG7, Table 16.29
+    pub energy_economy_group: Option<u8>,
+    /// TDMA frame number (1-18) of the first awake frame
+    pub energy_economy_startpoint: Option<u8>,
+    pub dual_watch_energy_economy_group: Option<u8>,
     pub dual_watch_startpoint: Option<Todo>,
     pub mle_activity_indicator: Option<Todo>,
     pub channel_change_accepted: Option<Todo>,
@@ -64,7 +67,7 @@ pub struct TlmcConfigureReq {
 /// information that may be needed in the napping procedure. It may also be used for the LLC to provide the MAC with
 /// information that the MAC may use to make choices about link adaptation. It may also be used to provide the MAC with
 /// retransmission strategy in graceful service degradation mode. The parameters shall be as defined in table 20.57.
-// #[derive(Debug, Clone)]
+// #[derive(Debug, Clone, Serialize, Deserialize)]
 // pub struct TmcTlConfigureReq {
 //     pub channel_change_handle: Option<Todo>,
 //     pub channel_change_accepted: Option<bool>,
@@ -79,14 +82,16 @@ pub struct TlmcConfigureReq {
 /// TL-CONFIGURE request, confirm: this primitive shall be used to set up and configure the layer 2 according to the
 /// chosen cell parameters and the current state of the MS. It may also be used to provide the LLC and MAC with
 /// retransmission strategy in graceful service degradation mode. The parameters shall be as defined in table 20.36.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TlmcConfigureConf {
     pub threshold_values: Option<Todo>,
     pub distribution_on_18th_frame: Option<Todo>,
     pub scch_information: Option<Todo>,
-    pub energy_economy_group: Option<Todo>,
-    pub energy_economy_startpoint: Option<Todo>,
-    pub dual_watch_energy_economy_group: Option<Todo>,
+    /// Energy economy group EG1-EG7, Table 16.29
+    pub energy_economy_group: Option<u8>,
+    /// TDMA frame number (1-18) of the first awake frame
+    pub energy_economy_startpoint: Option<u8>,
+    pub dual_watch_energy_economy_group: Option<u8>,
     pub dual_watch_startpoint: Option<Todo>,
     pub operating_mode: Option<Todo>,
     pub call_release: Option<Todo>,
@@ -100,33 +105,33 @@ pub struct TlmcConfigureConf {
     pub endpoint_id: Option<Todo>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlmcMeasurementInd;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlmcMonitorInd;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlmcMonitorListReq;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlmcReportInd;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlmcScanReq;
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlmcScanConf;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlmcScanReportInd;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlmcSelectReq;
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlmcSelectInd;
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlmcSelectResp;
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TlmcSelectConf;
 
 // Clause 20.4.3
@@ -1,3 +1,5 @@
+use core::fmt;
+use serde::{Deserialize, Serialize};
 use tetra_core::{BitBuffer, EndpointId, TetraAddress, Todo, TxReporter};
 
 use crate::lcmc::fields::chan_alloc_req::CmceChanAllocReq;
@@ -5,7 +7,7 @@ use crate::lcmc::fields::chan_alloc_req::CmceChanAllocReq;
 /// Clause 20.4.1.1.1
 /// TMA-CANCEL request: this primitive shall be used to cancel a TMA-UNITDATA
 /// request primitive that was submitted by the LLC.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TmaCancelReq {
     pub req_handle: Todo,
 }
@@ -14,13 +16,13 @@ pub struct TmaCancelReq {
 /// TMA-RELEASE indication: this primitive may be used when the MAC leaves a
 /// channel in order to indicate that the connection on that channel is lost
 /// (e.g. to indicate local disconnection of any advanced links on that channel).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TmaReleaseInd {
     pub endpoint_id: EndpointId,
 }
 
 /// Clause 22.3.3.1.1 gives some hints on reports in the MS context
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TmaReport {
     /// Confirm handle to the request
     ConfirmHandle,
@@ -39,7 +41,7 @@ pub enum TmaReport {
 /// TMA-REPORT indication: this primitive shall be used by the MAC to report
 /// on the progress or failure of a request procedure. The result of the
 /// transfer shall be passed as a report parameter.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TmaReportInd {
     pub req_handle: Todo,
     pub report: TmaReport,
@@ -48,7 +50,7 @@ pub struct TmaReportInd {
 /// Clause 20.4.1.1.4
 /// TMA-UNITDATA request: this primitive shall be used to request the MAC to
 /// transmit a TM-SDU.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TmaUnitdataReq {
     pub req_handle: Todo,
     pub pdu: BitBuffer,
@@ -65,7 +67,10 @@ pub struct TmaUnitdataReq {
     // Custom fields for BS stack:
     /// Optional Channel Allocation Request that may be included by CMCE
     pub chan_alloc: Option<CmceChanAllocReq>,
+    #[serde(skip)]
     pub tx_reporter: Option<TxReporter>,
+    /// Number of transmissions in consecutive frames, if capacity allows. 0 and 1 both mean once.
+    pub repeat_count: u8,
 }
 
 /// Clause 20.4.1.1.4
@@ -73,7 +78,7 @@ pub struct TmaUnitdataReq {
 /// a received TM-SDU. This primitive may also be used with no TM-SDU if the
 /// MAC needs to inform the higher layers of a channel allocation received
 /// without an associated TM-SDU.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TmaUnitdataInd {
     pub pdu: Option<BitBuffer>,
     pub main_address: TetraAddress,
@@ -86,3 +91,72 @@ pub struct TmaUnitdataInd {
     pub chan_change_handle: Option<Todo>,
     pub chan_info: Option<Todo>,
 }
+
+impl fmt::Display for TmaUnitdataReq {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "TMA-UNITDATA req {} ep {} pdu {} bits",
+            self.main_address,
+            self.endpoint_id,
+            self.pdu.get_len()
+        )?;
+        if self.repeat_count > 1 {
+            write!(f, " repeat {}", self.repeat_count)?;
+        }
+        if self.chan_alloc.is_some() {
+            write!(f, " chan_alloc")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TmaUnitdataInd {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TMA-UNITDATA ind {} ep {}", self.main_address, self.endpoint_id)?;
+        match &self.pdu {
+            Some(pdu) => write!(f, " pdu {} bits", pdu.get_len()),
+            None => write!(f, " no pdu"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::SsiType;
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let req = TmaUnitdataReq {
+            req_handle: 0,
+            pdu: BitBuffer::new(72),
+            main_address: TetraAddress::new(91, SsiType::Gssi),
+            endpoint_id: 0,
+            stealing_permission: false,
+            subscriber_class: 0,
+            air_interface_encryption: None,
+            stealing_repeats_flag: None,
+            data_category: None,
+            chan_alloc: None,
+            tx_reporter: None,
+            repeat_count: 3,
+        };
+        assert_eq!(req.to_string(), "TMA-UNITDATA req GSSI:91 ep 0 pdu 72 bits repeat 3");
+
+        let ind = TmaUnitdataInd {
+            pdu: Some(BitBuffer::new(24)),
+            main_address: TetraAddress::new(1000001, SsiType::Ssi),
+            scrambling_code: 0,
+            endpoint_id: 1,
+            new_endpoint_id: None,
+            css_endpoint_id: None,
+            air_interface_encryption: 0,
+            chan_change_response_req: false,
+            chan_change_handle: None,
+            chan_info: None,
+        };
+        assert_eq!(ind.to_string(), "TMA-UNITDATA ind SSI:1000001 ep 1 pdu 24 bits");
+    }
+}
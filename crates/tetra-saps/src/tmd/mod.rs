@@ -1,5 +1,7 @@
 /// Pass TMD circuit data to UMAC for TX scheduling
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TmdCircuitDataReq {
     // call_id: CallId,
     pub ts: u8,
@@ -7,9 +9,11 @@ pub struct TmdCircuitDataReq {
 }
 
 /// Rx'ed traffic
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TmdCircuitDataInd {
     // call_id: CallId,
     pub ts: u8,
     pub data: Vec<u8>,
+    /// False if the frame failed CRC (bad frame indication), the data is still passed for concealment
+    pub crc_pass: bool,
 }
@@ -1,5 +1,7 @@
 /// Logical channels as defined in the standard
-#[derive(Debug, Clone, Copy, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum LogicalChannel {
     /// Access Assignment CHannel
     Aach,
@@ -1,18 +1,20 @@
 pub mod enums;
 
+use core::fmt;
+use serde::{Deserialize, Serialize};
 use tetra_core::{BitBuffer, PhyBlockNum, PhysicalChannel, TdmaTime, Todo};
 
 use crate::tmv::enums::logical_chans::LogicalChannel;
 
 // The TMV-UNITDATA request primitive shall be used to request the lower MAC to transmit a MAC block
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TmvUnitdataReq {
     pub mac_block: BitBuffer,
     pub logical_channel: LogicalChannel,
     pub scrambling_code: u32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TmvUnitdataReqSlot {
     /// Timeslot at which this block is to be transmitted
     pub ts: TdmaTime,
@@ -32,8 +34,48 @@ pub struct TmvUnitdataReqSlot {
     pub bbk: Option<TmvUnitdataReq>,
 }
 
+/// Reasons a TmvUnitdataReqSlot can't be handed to the LMAC
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SlotValidationError {
+    /// blk2 is set while blk1 is not
+    Blk2WithoutBlk1,
+    /// blk1 occupies the full slot, yet blk2 is set
+    Blk2AfterFullSlot(LogicalChannel),
+    /// Timeslot 1 carries the MCCH, which always needs a BBK
+    MissingBbkOnMcch,
+}
+
+impl fmt::Display for SlotValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SlotValidationError::Blk2WithoutBlk1 => write!(f, "blk2 set without blk1"),
+            SlotValidationError::Blk2AfterFullSlot(lchan) => write!(f, "blk2 set after full-slot {:?} blk1", lchan),
+            SlotValidationError::MissingBbkOnMcch => write!(f, "no BBK on MCCH slot"),
+        }
+    }
+}
+
+impl std::error::Error for SlotValidationError {}
+
+impl TmvUnitdataReqSlot {
+    /// Checks the combination of blocks in this slot can be transmitted by the LMAC
+    pub fn validate(&self) -> Result<(), SlotValidationError> {
+        match (&self.blk1, &self.blk2) {
+            (None, Some(_)) => return Err(SlotValidationError::Blk2WithoutBlk1),
+            (Some(blk1), Some(_)) if blk1.logical_channel == LogicalChannel::SchF || blk1.logical_channel.is_traffic() => {
+                return Err(SlotValidationError::Blk2AfterFullSlot(blk1.logical_channel));
+            }
+            _ => {}
+        }
+        if self.ts.t == 1 && self.bbk.is_none() {
+            return Err(SlotValidationError::MissingBbkOnMcch);
+        }
+        Ok(())
+    }
+}
+
 /// The TMV-UNITDATA indication primitive shall be used by the lower MAC to deliver a received MAC block;
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TmvUnitdataInd {
     pub pdu: BitBuffer,
 
@@ -52,7 +94,7 @@ pub struct TmvUnitdataInd {
 /// The TMV-CONFIGURE primitive shall be used to provide the lower MAC with information about the configuration
 /// of the channel or about the format of a received slot.
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TmvConfigureReq {
     // pub channel_info: Option<Todo>,
     /// Received from umac upon change of network information
@@ -66,9 +108,132 @@ pub struct TmvConfigureReq {
     // pub monitoring_pattern_info: Option<Todo>,
     /// NOTE time not usually passed down but convenient for detecting fr18 etc.
     pub time: Option<TdmaTime>,
+    /// Used by Umac to have Lmac send a synchronization burst on a traffic timeslot, for late entry.
+    /// Contains the timeslot and the BSCH block (MAC-SYNC and D-MLE-SYNC), the time is filled in by Lmac.
+    pub traffic_bsch: Option<(u8, BitBuffer)>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TmvConfigureConf {
     pub channel_info: Todo,
 }
+
+impl fmt::Display for TmvUnitdataReq {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TMV-UNITDATA req {:?} {} bits", self.logical_channel, self.mac_block.get_len())
+    }
+}
+
+impl fmt::Display for TmvUnitdataReqSlot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TMV-UNITDATA req ts {} ul {}", self.ts, self.ul_phy_chan)?;
+        for (name, blk) in [("blk1", &self.blk1), ("blk2", &self.blk2)] {
+            if let Some(blk) = blk {
+                write!(f, " {} {:?} {} bits", name, blk.logical_channel, blk.mac_block.get_len())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TmvUnitdataInd {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "TMV-UNITDATA ind {:?} {:?} {} bits crc {}",
+            self.logical_channel,
+            self.block_num,
+            self.pdu.get_len(),
+            if self.crc_pass { "pass" } else { "fail" }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let blk = |logical_channel, len| TmvUnitdataReq {
+            mac_block: BitBuffer::new(len),
+            logical_channel,
+            scrambling_code: 0,
+        };
+        assert_eq!(blk(LogicalChannel::SchF, 268).to_string(), "TMV-UNITDATA req SchF 268 bits");
+
+        let slot = TmvUnitdataReqSlot {
+            ts: TdmaTime { h: 1, m: 2, f: 3, t: 4 },
+            ul_phy_chan: PhysicalChannel::cp(),
+            blk1: Some(blk(LogicalChannel::SchHd, 124)),
+            blk2: Some(blk(LogicalChannel::Bnch, 124)),
+            bbk: Some(blk(LogicalChannel::Aach, 14)),
+        };
+        assert_eq!(
+            slot.to_string(),
+            "TMV-UNITDATA req ts     1/02/03/4 ul Cp c0 blk1 SchHd 124 bits blk2 Bnch 124 bits"
+        );
+
+        let ind = TmvUnitdataInd {
+            pdu: BitBuffer::new(92),
+            block_num: PhyBlockNum::Block1,
+            logical_channel: LogicalChannel::SchHu,
+            crc_pass: false,
+            scrambling_code: 0,
+        };
+        assert_eq!(ind.to_string(), "TMV-UNITDATA ind SchHu Block1 92 bits crc fail");
+    }
+
+    #[test]
+    fn test_validate() {
+        let blk = |logical_channel, len| {
+            Some(TmvUnitdataReq {
+                mac_block: BitBuffer::new(len),
+                logical_channel,
+                scrambling_code: 0,
+            })
+        };
+        let slot = |t, blk1, blk2, bbk| TmvUnitdataReqSlot {
+            ts: TdmaTime { h: 0, m: 1, f: 1, t },
+            ul_phy_chan: PhysicalChannel::cp(),
+            blk1,
+            blk2,
+            bbk,
+        };
+        let aach = || blk(LogicalChannel::Aach, 14);
+
+        // Valid combinations
+        assert_eq!(
+            slot(1, blk(LogicalChannel::Bsch, 60), blk(LogicalChannel::Bnch, 124), aach()).validate(),
+            Ok(())
+        );
+        assert_eq!(slot(1, blk(LogicalChannel::SchF, 268), None, aach()).validate(), Ok(()));
+        assert_eq!(
+            slot(2, blk(LogicalChannel::Stch, 124), blk(LogicalChannel::TchS, 137), None).validate(),
+            Ok(())
+        );
+        assert_eq!(slot(3, blk(LogicalChannel::TchS, 274), None, None).validate(), Ok(()));
+
+        // blk2 without blk1
+        assert_eq!(
+            slot(1, None, blk(LogicalChannel::Bnch, 124), aach()).validate(),
+            Err(SlotValidationError::Blk2WithoutBlk1)
+        );
+
+        // blk2 after a full-slot blk1
+        assert_eq!(
+            slot(1, blk(LogicalChannel::SchF, 268), blk(LogicalChannel::Bnch, 124), aach()).validate(),
+            Err(SlotValidationError::Blk2AfterFullSlot(LogicalChannel::SchF))
+        );
+        assert_eq!(
+            slot(2, blk(LogicalChannel::TchS, 274), blk(LogicalChannel::SchHd, 124), None).validate(),
+            Err(SlotValidationError::Blk2AfterFullSlot(LogicalChannel::TchS))
+        );
+
+        // MCCH without BBK
+        assert_eq!(
+            slot(1, blk(LogicalChannel::SchHd, 124), blk(LogicalChannel::Bnch, 124), None).validate(),
+            Err(SlotValidationError::MissingBbkOnMcch)
+        );
+    }
+}
@@ -1,9 +1,11 @@
 // Placeholder for testing
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TnmmTestDemand {
     pub issi: u32,
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TnmmTestResponse {
     pub issi: u32,
     pub data: u32,
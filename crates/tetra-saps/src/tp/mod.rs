@@ -1,6 +1,7 @@
+use serde::{Deserialize, Serialize};
 use tetra_core::{BitBuffer, BurstType, PhyBlockNum, PhyBlockType, TrainingSequence};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TpUnitdataInd {
     pub train_type: TrainingSequence,
     pub burst_type: BurstType,
@@ -10,7 +11,7 @@ pub struct TpUnitdataInd {
     pub block: BitBuffer,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TpUnitdataReqSlot {
     pub train_type: TrainingSequence,
     pub burst_type: BurstType,
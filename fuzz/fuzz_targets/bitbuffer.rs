@@ -0,0 +1,19 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tetra_core::BitBuffer;
+use tetra_fuzz::bitbuffer_from_fuzz_input;
+
+fuzz_target!(|data: &[u8]| {
+    // Construction from arbitrary bytes, then exercise the accessors that walk the whole window
+    let mut buf = bitbuffer_from_fuzz_input(data);
+    let len = buf.get_len();
+    let _ = buf.to_bitstr();
+    let _ = buf.dump_hex();
+    let _ = buf.peek_bits_startoffset(len.saturating_sub(64), len.min(64));
+
+    let mut copy = BitBuffer::new_autoexpand(len);
+    copy.copy_bits(&mut buf, len);
+    let payload = data.get(1..).unwrap_or_default();
+    assert_eq!(copy.to_bitstr(), BitBuffer::from_bytes(payload).to_bitstr()[..len]);
+});
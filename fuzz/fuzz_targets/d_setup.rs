@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tetra_fuzz::bitbuffer_from_fuzz_input;
+use tetra_pdus::cmce::pdus::d_setup::DSetup;
+
+fuzz_target!(|data: &[u8]| {
+    let mut buf = bitbuffer_from_fuzz_input(data);
+    let _ = DSetup::from_bitbuf(&mut buf);
+});
@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tetra_fuzz::bitbuffer_from_fuzz_input;
+use tetra_pdus::umac::pdus::mac_access::MacAccess;
+
+fuzz_target!(|data: &[u8]| {
+    let mut buf = bitbuffer_from_fuzz_input(data);
+    let _ = MacAccess::from_bitbuf(&mut buf);
+});
@@ -0,0 +1,14 @@
+use tetra_core::BitBuffer;
+
+/// Build a BitBuffer from raw fuzz input. The low three bits of the first byte select how many
+/// bits to drop from the end of the remaining payload, so parsers also see lengths that are not
+/// a multiple of 8.
+pub fn bitbuffer_from_fuzz_input(data: &[u8]) -> BitBuffer {
+    let Some((&first, payload)) = data.split_first() else {
+        return BitBuffer::new(0);
+    };
+    let mut buf = BitBuffer::from_bytes(payload);
+    let trim = ((first & 7) as usize).min(buf.get_len());
+    buf.set_raw_end(buf.get_raw_end() - trim);
+    buf
+}